@@ -0,0 +1,130 @@
+use super::Condition;
+use std::collections::{HashMap, HashSet};
+
+/// Expand any alias in the given command line, returning the expanded command line.
+///
+/// An alias is only recognized where a subcommand could appear: as the first argument, following
+/// any global options and filter conditions.  If the expansion itself begins with an alias, that
+/// is expanded in turn, but an alias that would expand to itself, directly or indirectly, is an
+/// error.
+pub(crate) fn expand_aliases(
+    argv: &[&str],
+    aliases: &HashMap<String, Vec<String>>,
+) -> Result<Vec<String>, crate::Error> {
+    let mut argv: Vec<String> = argv.iter().map(|s| s.to_string()).collect();
+    if aliases.is_empty() {
+        return Ok(argv);
+    }
+
+    // find the position of the subcommand, skipping the command name, global options, and
+    // filter conditions
+    let mut i = 1;
+    while i < argv.len() {
+        let arg = argv[i].as_str();
+        if aliases.contains_key(arg) {
+            break;
+        } else if arg.starts_with("--color=")
+            || [
+                "--yes",
+                "-y",
+                "--dry-run",
+                "-n",
+                "--no-pager",
+                "--verbose",
+                "--quiet",
+                "-q",
+            ]
+            .contains(&arg)
+            || (arg.starts_with("-v") && arg[1..].chars().all(|c| c == 'v'))
+        {
+            i += 1;
+        } else if arg == "--format" || arg == "--profile" || arg == "--date-format" {
+            i += 2;
+        } else if Condition::parse_str(arg).is_ok() {
+            i += 1;
+        } else {
+            return Ok(argv);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    while let Some(expansion) = argv.get(i).and_then(|arg| aliases.get(arg)) {
+        if !seen.insert(argv[i].clone()) {
+            return Err(crate::Error::for_arguments(format!(
+                "alias `{}` is recursive",
+                argv[i]
+            )));
+        }
+        argv.splice(i..=i, expansion.iter().cloned());
+    }
+
+    Ok(argv)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn aliases() -> HashMap<String, Vec<String>> {
+        let mut aliases = HashMap::new();
+        aliases.insert(s!("in"), vec![s!("add"), s!("+inbox")]);
+        aliases.insert(s!("rm"), vec![s!("delete")]);
+        aliases.insert(s!("inn"), vec![s!("in"), s!("priority:H")]);
+        aliases.insert(s!("loop"), vec![s!("loop2")]);
+        aliases.insert(s!("loop2"), vec![s!("loop"), s!("x")]);
+        aliases
+    }
+
+    fn expand(argv: &[&str]) -> Result<Vec<String>, crate::Error> {
+        expand_aliases(argv, &aliases())
+    }
+
+    #[test]
+    fn test_no_alias() {
+        assert_eq!(
+            expand(&["ta", "add", "in", "a", "bit"]).unwrap(),
+            vec!["ta", "add", "in", "a", "bit"]
+        );
+    }
+
+    #[test]
+    fn test_first_arg() {
+        assert_eq!(
+            expand(&["ta", "in", "buy", "milk"]).unwrap(),
+            vec!["ta", "add", "+inbox", "buy", "milk"]
+        );
+    }
+
+    #[test]
+    fn test_after_filter_and_options() {
+        assert_eq!(
+            expand(&["ta", "--format", "json", "3", "+work", "rm"]).unwrap(),
+            vec!["ta", "--format", "json", "3", "+work", "delete"]
+        );
+    }
+
+    #[test]
+    fn test_after_profile() {
+        assert_eq!(
+            expand(&["ta", "--profile", "rm", "3", "rm"]).unwrap(),
+            vec!["ta", "--profile", "rm", "3", "delete"]
+        );
+    }
+
+    #[test]
+    fn test_nested() {
+        assert_eq!(
+            expand(&["ta", "inn", "call"]).unwrap(),
+            vec!["ta", "add", "+inbox", "priority:H", "call"]
+        );
+    }
+
+    #[test]
+    fn test_recursive() {
+        assert_eq!(
+            expand(&["ta", "loop"]).unwrap_err().to_string(),
+            "Command-Line Syntax Error: alias `loop` is recursive"
+        );
+    }
+}
@@ -1,8 +1,9 @@
-use super::{any, id_list, timestamp, TaskId};
+use super::{any, duration, id_list, positive_integer, timestamp, TaskId};
 use crate::argparse::NOW;
 use nom::bytes::complete::tag as nomtag;
 use nom::{branch::*, character::complete::*, combinator::*, sequence::*, IResult};
 use taskchampion::chrono::prelude::*;
+use taskchampion::chrono::Duration;
 use taskchampion::Status;
 
 /// Recognizes up to the colon of the common `<prefix>:...` syntax
@@ -31,23 +32,161 @@ pub(crate) fn status_colon(input: &str) -> IResult<&str, Status> {
     map_res(colon_prefix("status"), to_status)(input)
 }
 
+/// Recognizes `<prefix>:` to None and `<prefix>:<ts>` to `Some(ts)`
+fn optional_timestamp_colon(
+    prefix: &'static str,
+) -> impl Fn(&str) -> IResult<&str, Option<DateTime<Utc>>> {
+    fn to_some(input: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, ()> {
+        Ok(Some(input))
+    }
+    fn to_none(_: &str) -> Result<Option<DateTime<Utc>>, ()> {
+        Ok(None)
+    }
+    move |input: &str| {
+        preceded(
+            pair(nomtag(prefix), char(':')),
+            alt((
+                map_res(timestamp(*NOW, Local), to_some),
+                map_res(nomtag(""), to_none),
+            )),
+        )(input)
+    }
+}
+
 /// Recognizes `wait:` to None and `wait:<ts>` to `Some(ts)`
 pub(crate) fn wait_colon(input: &str) -> IResult<&str, Option<DateTime<Utc>>> {
-    fn to_wait(input: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, ()> {
+    optional_timestamp_colon("wait")(input)
+}
+
+/// Recognizes `due:` to None and `due:<ts>` to `Some(ts)`
+pub(crate) fn due_colon(input: &str) -> IResult<&str, Option<DateTime<Utc>>> {
+    optional_timestamp_colon("due")(input)
+}
+
+/// Recognizes `scheduled:` to None and `scheduled:<ts>` to `Some(ts)`
+pub(crate) fn scheduled_colon(input: &str) -> IResult<&str, Option<DateTime<Utc>>> {
+    optional_timestamp_colon("scheduled")(input)
+}
+
+/// Recognizes `until:` to None and `until:<ts>` to `Some(ts)`
+pub(crate) fn until_colon(input: &str) -> IResult<&str, Option<DateTime<Utc>>> {
+    optional_timestamp_colon("until")(input)
+}
+
+/// Recognizes `recur:` to None and `recur:<duration>` to `Some(duration)`; the duration must be
+/// positive.
+pub(crate) fn recur_colon(input: &str) -> IResult<&str, Option<Duration>> {
+    fn to_some(input: Duration) -> Result<Option<Duration>, ()> {
         Ok(Some(input))
     }
-    fn to_none(_: &str) -> Result<Option<DateTime<Utc>>, ()> {
+    fn to_none(_: &str) -> Result<Option<Duration>, ()> {
         Ok(None)
     }
     preceded(
-        nomtag("wait:"),
+        pair(nomtag("recur"), char(':')),
         alt((
-            map_res(timestamp(*NOW, Local), to_wait),
+            map_res(
+                verify(duration, |d: &Duration| *d > Duration::zero()),
+                to_some,
+            ),
             map_res(nomtag(""), to_none),
         )),
     )(input)
 }
 
+/// Recognizes `limit:<n>` to `Some(n)` and `limit:page` to None; the limit must be positive.
+pub(crate) fn limit_colon(input: &str) -> IResult<&str, Option<usize>> {
+    fn to_limit(input: &str) -> Result<Option<usize>, ()> {
+        if input == "page" {
+            Ok(None)
+        } else {
+            match positive_integer(input) {
+                Ok((_, n)) => Ok(Some(n)),
+                Err(_) => Err(()),
+            }
+        }
+    }
+    map_res(colon_prefix("limit"), to_limit)(input)
+}
+
+/// Recognizes `<prefix>.before:<ts>` to `(true, ts)` and `<prefix>.after:<ts>` to `(false, ts)`
+pub(crate) fn timestamp_cmp_colon(
+    prefix: &'static str,
+) -> impl Fn(&str) -> IResult<&str, (bool, DateTime<Utc>)> {
+    fn to_before(input: DateTime<Utc>) -> Result<(bool, DateTime<Utc>), ()> {
+        Ok((true, input))
+    }
+    fn to_after(input: DateTime<Utc>) -> Result<(bool, DateTime<Utc>), ()> {
+        Ok((false, input))
+    }
+    move |input: &str| {
+        preceded(
+            pair(nomtag(prefix), char('.')),
+            alt((
+                map_res(
+                    preceded(nomtag("before:"), timestamp(*NOW, Local)),
+                    to_before,
+                ),
+                map_res(preceded(nomtag("after:"), timestamp(*NOW, Local)), to_after),
+            )),
+        )(input)
+    }
+}
+
+/// Recognizes `project:` to None and `project:<project>` to `Some(project)`
+pub(crate) fn project_colon(input: &str) -> IResult<&str, Option<&str>> {
+    fn to_project(input: &str) -> Result<Option<&str>, ()> {
+        if input.is_empty() {
+            Ok(None)
+        } else if input.chars().any(char::is_whitespace) {
+            Err(())
+        } else {
+            Ok(Some(input))
+        }
+    }
+    map_res(colon_prefix("project"), to_project)(input)
+}
+
+/// Recognizes `annotation:` to None and `annotation:<text>` to `Some(text)`
+pub(crate) fn annotation_colon(input: &str) -> IResult<&str, Option<&str>> {
+    fn to_annotation(input: &str) -> Result<Option<&str>, ()> {
+        Ok(if input.is_empty() { None } else { Some(input) })
+    }
+    map_res(colon_prefix("annotation"), to_annotation)(input)
+}
+
+/// Recognizes `priority:{H,M,L}` to the given priority, and `priority:` to an empty string
+pub(crate) fn priority_colon(input: &str) -> IResult<&str, &str> {
+    fn to_priority(input: &str) -> Result<&str, ()> {
+        match input {
+            "H" | "M" | "L" | "" => Ok(input),
+            _ => Err(()),
+        }
+    }
+    map_res(colon_prefix("priority"), to_priority)(input)
+}
+
+/// Recognizes `priority.above:{M,L}` and `priority.below:{H,M}` to `(true, priority)` and
+/// `(false, priority)`, respectively.
+pub(crate) fn priority_cmp_colon(input: &str) -> IResult<&str, (bool, &str)> {
+    fn to_above(input: &str) -> Result<(bool, &str), ()> {
+        match input {
+            "H" | "M" | "L" => Ok((true, input)),
+            _ => Err(()),
+        }
+    }
+    fn to_below(input: &str) -> Result<(bool, &str), ()> {
+        match input {
+            "H" | "M" | "L" => Ok((false, input)),
+            _ => Err(()),
+        }
+    }
+    alt((
+        map_res(colon_prefix("priority.above"), to_above),
+        map_res(colon_prefix("priority.below"), to_below),
+    ))(input)
+}
+
 /// Recognizes `depends:<task>` to `(true, <task>)` and `depends:-<task>` to `(false, <task>)`.
 pub(crate) fn depends_colon(input: &str) -> IResult<&str, (bool, Vec<TaskId>)> {
     fn to_bool(maybe_minus: Option<char>) -> Result<bool, ()> {
@@ -59,11 +198,24 @@ pub(crate) fn depends_colon(input: &str) -> IResult<&str, (bool, Vec<TaskId>)> {
     )(input)
 }
 
+/// Recognizes `link:<url>` to `(true, <url>)` and `link:-<url>` to `(false, <url>)`.
+pub(crate) fn link_colon(input: &str) -> IResult<&str, (bool, &str)> {
+    fn to_bool(maybe_minus: Option<char>) -> Result<bool, ()> {
+        Ok(maybe_minus.is_none()) // None -> true, Some -> false
+    }
+    preceded(
+        nomtag("link:"),
+        pair(
+            map_res(opt(char('-')), to_bool),
+            verify(any, |s: &str| !s.is_empty()),
+        ),
+    )(input)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use pretty_assertions::assert_eq;
-    use taskchampion::chrono::Duration;
 
     #[test]
     fn test_colon_prefix() {
@@ -92,7 +244,133 @@ mod test {
         let one_day = *NOW + Duration::days(1);
         assert_eq!(wait_colon("wait:1d").unwrap(), ("", Some(one_day)));
 
+        // timestamps must make up the remainder of the argument
+        assert!(all_consuming(wait_colon)("wait:1d2").is_err());
+    }
+
+    #[test]
+    fn test_due() {
+        assert_eq!(due_colon("due:").unwrap(), ("", None));
+
         let one_day = *NOW + Duration::days(1);
-        assert_eq!(wait_colon("wait:1d2").unwrap(), ("2", Some(one_day)));
+        assert_eq!(due_colon("due:1d").unwrap(), ("", Some(one_day)));
+        assert!(due_colon("wait:1d").is_err());
+    }
+
+    #[test]
+    fn test_link() {
+        assert_eq!(
+            link_colon("link:https://example.com").unwrap(),
+            ("", (true, "https://example.com"))
+        );
+        assert_eq!(
+            link_colon("link:-https://example.com").unwrap(),
+            ("", (false, "https://example.com"))
+        );
+        assert!(link_colon("link:").is_err());
+        assert!(link_colon("link:-").is_err());
+        assert!(link_colon("link").is_err());
+    }
+
+    #[test]
+    fn test_priority() {
+        assert_eq!(priority_colon("priority:H").unwrap(), ("", "H"));
+        assert_eq!(priority_colon("priority:L").unwrap(), ("", "L"));
+        assert_eq!(priority_colon("priority:").unwrap(), ("", ""));
+        assert!(priority_colon("priority:X").is_err());
+        assert!(priority_colon("priority:h").is_err());
+        assert!(priority_colon("priority").is_err());
+    }
+
+    #[test]
+    fn test_priority_cmp() {
+        assert_eq!(
+            priority_cmp_colon("priority.above:M").unwrap(),
+            ("", (true, "M"))
+        );
+        assert_eq!(
+            priority_cmp_colon("priority.below:H").unwrap(),
+            ("", (false, "H"))
+        );
+        assert!(priority_cmp_colon("priority.above:").is_err());
+        assert!(priority_cmp_colon("priority.below:X").is_err());
+        assert!(priority_cmp_colon("priority:H").is_err());
+    }
+
+    #[test]
+    fn test_project() {
+        assert_eq!(project_colon("project:").unwrap(), ("", None));
+        assert_eq!(project_colon("project:work").unwrap(), ("", Some("work")));
+        assert_eq!(
+            project_colon("project:work.client").unwrap(),
+            ("", Some("work.client"))
+        );
+        assert!(project_colon("project:two words").is_err());
+        assert!(project_colon("project").is_err());
+    }
+
+    #[test]
+    fn test_annotation() {
+        assert_eq!(annotation_colon("annotation:").unwrap(), ("", None));
+        assert_eq!(
+            annotation_colon("annotation:JIRA-123").unwrap(),
+            ("", Some("JIRA-123"))
+        );
+        assert_eq!(
+            annotation_colon("annotation:called back").unwrap(),
+            ("", Some("called back"))
+        );
+        assert!(annotation_colon("annotations:x").is_err());
+    }
+
+    #[test]
+    fn test_scheduled_until() {
+        let one_day = *NOW + Duration::days(1);
+        assert_eq!(scheduled_colon("scheduled:").unwrap(), ("", None));
+        assert_eq!(
+            scheduled_colon("scheduled:1d").unwrap(),
+            ("", Some(one_day))
+        );
+        assert_eq!(until_colon("until:").unwrap(), ("", None));
+        assert_eq!(until_colon("until:1d").unwrap(), ("", Some(one_day)));
+    }
+
+    #[test]
+    fn test_timestamp_cmp() {
+        let one_day = *NOW + Duration::days(1);
+        assert_eq!(
+            timestamp_cmp_colon("until")("until.before:1d").unwrap(),
+            ("", (true, one_day))
+        );
+        assert_eq!(
+            timestamp_cmp_colon("until")("until.after:1d").unwrap(),
+            ("", (false, one_day))
+        );
+        assert!(timestamp_cmp_colon("until")("until.after:").is_err());
+        assert!(timestamp_cmp_colon("until")("until:1d").is_err());
+        assert!(timestamp_cmp_colon("until")("scheduled.before:1d").is_err());
+    }
+
+    #[test]
+    fn test_recur() {
+        assert_eq!(recur_colon("recur:").unwrap(), ("", None));
+        assert_eq!(
+            recur_colon("recur:1w").unwrap(),
+            ("", Some(Duration::days(7)))
+        );
+        // a non-positive or invalid duration is not consumed
+        assert_eq!(recur_colon("recur:0d").unwrap(), ("0d", None));
+        assert_eq!(recur_colon("recur:soon").unwrap(), ("soon", None));
+        assert!(recur_colon("recurs:1w").is_err());
+    }
+
+    #[test]
+    fn test_limit() {
+        assert_eq!(limit_colon("limit:10").unwrap(), ("", Some(10)));
+        assert_eq!(limit_colon("limit:page").unwrap(), ("", None));
+        assert!(limit_colon("limit:0").is_err());
+        assert!(limit_colon("limit:").is_err());
+        assert!(limit_colon("limit:ten").is_err());
+        assert!(limit_colon("limits:10").is_err());
     }
 }
@@ -1,5 +1,7 @@
 use nom::bytes::complete::tag as nomtag;
-use nom::{character::complete::*, combinator::*, sequence::*, IResult};
+use nom::{character::complete::*, combinator::*, error::*, sequence::*, Err, IResult};
+use regex::{Regex, RegexBuilder};
+use std::str::FromStr;
 
 /// Recognizes any argument
 pub(crate) fn any(input: &str) -> IResult<&str, &str> {
@@ -11,6 +13,26 @@ pub(crate) fn report_name(input: &str) -> IResult<&str, &str> {
     all_consuming(recognize(pair(alpha1, alphanumeric0)))(input)
 }
 
+/// Recognizes a positive integer
+pub(crate) fn positive_integer(input: &str) -> IResult<&str, usize> {
+    verify(
+        map_res(all_consuming(digit1), usize::from_str),
+        |n: &usize| *n > 0,
+    )(input)
+}
+
+/// Recognizes a `/pattern/`, as a case-insensitive regular expression
+pub(crate) fn pattern(input: &str) -> IResult<&str, Regex> {
+    let inner = input
+        .strip_prefix('/')
+        .and_then(|s| s.strip_suffix('/'))
+        .filter(|s| !s.is_empty());
+    match inner.map(|s| RegexBuilder::new(s).case_insensitive(true).build()) {
+        Some(Ok(re)) => Ok((&input[input.len()..], re)),
+        _ => Err(Err::Error(Error::new(input, ErrorKind::Verify))),
+    }
+}
+
 /// Recognizes a literal string
 pub(crate) fn literal(literal: &'static str) -> impl Fn(&str) -> IResult<&str, &str> {
     move |input: &str| all_consuming(nomtag(literal))(input)
@@ -31,6 +53,28 @@ mod test {
         assert!(arg_matching(plus_tag)(argv!["foo", "bar"]).is_err());
     }
 
+    #[test]
+    fn test_positive_integer() {
+        assert_eq!(positive_integer("3").unwrap().1, 3);
+        assert_eq!(positive_integer("120").unwrap().1, 120);
+        assert!(positive_integer("0").is_err());
+        assert!(positive_integer("-1").is_err());
+        assert!(positive_integer("3x").is_err());
+    }
+
+    #[test]
+    fn test_pattern() {
+        let re = pattern("/invoice/").unwrap().1;
+        assert_eq!(re.as_str(), "invoice");
+        assert!(re.is_match("Send INVOICE to client"));
+        assert!(pattern("/inv.*ce/").unwrap().1.is_match("invoiced"));
+        assert!(pattern("invoice").is_err());
+        assert!(pattern("/invoice").is_err());
+        assert!(pattern("//").is_err());
+        assert!(pattern("/").is_err());
+        assert!(pattern("/in(voice/").is_err());
+    }
+
     #[test]
     fn test_literal() {
         assert_eq!(literal("list")("list").unwrap().1, "list");
@@ -8,9 +8,12 @@ mod tags;
 mod time;
 
 pub(crate) use arg_matching::arg_matching;
-pub(crate) use colon::{depends_colon, status_colon, wait_colon};
+pub(crate) use colon::{
+    annotation_colon, depends_colon, due_colon, limit_colon, link_colon, priority_cmp_colon,
+    priority_colon, project_colon, recur_colon, scheduled_colon, status_colon, timestamp_cmp_colon,
+    until_colon, wait_colon,
+};
 pub(crate) use idlist::{id_list, TaskId};
-pub(crate) use misc::{any, literal, report_name};
-pub(crate) use tags::{minus_tag, plus_tag};
-#[allow(unused_imports)]
+pub(crate) use misc::{any, literal, pattern, positive_integer, report_name};
+pub(crate) use tags::{minus_tag, plus_tag, tag};
 pub(crate) use time::{duration, timestamp};
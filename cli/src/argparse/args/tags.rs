@@ -12,6 +12,11 @@ pub(crate) fn minus_tag(input: &str) -> IResult<&str, Tag> {
     preceded(char('-'), map_res(rest, Tag::try_from))(input)
 }
 
+/// Recognizes a tag, optionally prefixed with `+`, and returns the tag value
+pub(crate) fn tag(input: &str) -> IResult<&str, Tag> {
+    preceded(opt(char('+')), map_res(rest, Tag::try_from))(input)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -32,4 +37,12 @@ mod test {
         assert!(minus_tag("+abc123").is_err());
         assert!(minus_tag("-1abc").is_err());
     }
+
+    #[test]
+    fn test_tag() {
+        assert_eq!(tag("abc").unwrap().1, tag!("abc"));
+        assert_eq!(tag("+abc").unwrap().1, tag!("abc"));
+        assert!(tag("-abc").is_err());
+        assert!(tag("1abc").is_err());
+    }
 }
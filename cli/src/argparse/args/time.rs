@@ -45,6 +45,8 @@ lazy_static! {
         DurationCase("s", Duration::seconds(1), false),
         DurationCase("weeks", Duration::days(7), false),
         DurationCase("week", Duration::days(7), true),
+        DurationCase("wks", Duration::days(7), false),
+        DurationCase("wk", Duration::days(7), true),
         DurationCase("w", Duration::days(7), false),
         DurationCase("years", Duration::days(365), false),
         DurationCase("year", Duration::days(365), true),
@@ -115,20 +117,34 @@ fn iso8601_dur(input: &str) -> IResult<&str, Duration> {
     }
 }
 
-/// Recognizes durations
+/// Recognizes a single duration with a numeric prefix, such as `3days` or `1.5 h`
+fn prefixed_duration(input: &str) -> IResult<&str, Duration> {
+    map_res(
+        tuple((
+            decimal_prefix_multiplier,
+            multispace0,
+            duration_suffix(true),
+        )),
+        |input: (f64, &str, Duration)| -> Result<Duration, ()> {
+            // `as i64` is saturating, so for large offsets this will
+            // just pick an imprecise very-futuristic date
+            let secs = (input.0 * input.2.num_seconds() as f64) as i64;
+            Ok(Duration::seconds(secs))
+        },
+    )(input)
+}
+
+/// Recognizes durations, including combinations such as `1d12h` or `1 week 2 days`, which are
+/// summed.
 pub(crate) fn duration(input: &str) -> IResult<&str, Duration> {
     alt((
         map_res(
-            tuple((
-                decimal_prefix_multiplier,
-                multispace0,
-                duration_suffix(true),
-            )),
-            |input: (f64, &str, Duration)| -> Result<Duration, ()> {
-                // `as i64` is saturating, so for large offsets this will
-                // just pick an imprecise very-futuristic date
-                let secs = (input.0 * input.2.num_seconds() as f64) as i64;
-                Ok(Duration::seconds(secs))
+            pair(
+                prefixed_duration,
+                many0(preceded(multispace0, prefixed_duration)),
+            ),
+            |input: (Duration, Vec<Duration>)| -> Result<Duration, ()> {
+                Ok(input.1.iter().fold(input.0, |acc, d| acc + *d))
             },
         ),
         duration_suffix(false),
@@ -147,19 +163,69 @@ fn rfc3339_timestamp(input: &str) -> IResult<&str, DateTime<Utc>> {
     }
 }
 
+/// Recognizes the name of a day of the week, such as `friday` or `fri`
+fn weekday(input: &str) -> IResult<&str, Weekday> {
+    map_opt(alpha1, |name: &str| {
+        Some(match name {
+            "monday" | "mon" => Weekday::Mon,
+            "tuesday" | "tue" => Weekday::Tue,
+            "wednesday" | "wed" => Weekday::Wed,
+            "thursday" | "thu" => Weekday::Thu,
+            "friday" | "fri" => Weekday::Fri,
+            "saturday" | "sat" => Weekday::Sat,
+            "sunday" | "sun" => Weekday::Sun,
+            _ => return None,
+        })
+    })(input)
+}
+
+/// Recognizes a day of the week, optionally preceded by `next`, as the local midnight at the
+/// beginning of the next such day after today.
+fn next_weekday<Tz: TimeZone>(
+    now: DateTime<Utc>,
+    local: Tz,
+) -> impl Fn(&str) -> IResult<&str, DateTime<Utc>> {
+    move |input: &str| {
+        let (rem, day) = preceded(opt(pair(tag("next"), multispace1)), weekday)(input)?;
+        let local_today = now.with_timezone(&local).date();
+        let today_index = local_today.weekday().num_days_from_monday() as i64;
+        let mut days = day.num_days_from_monday() as i64 - today_index;
+        if days <= 0 {
+            days += 7;
+        }
+        Ok((
+            rem,
+            (local_today + Duration::days(days))
+                .and_hms(0, 0, 0)
+                .with_timezone(&Utc),
+        ))
+    }
+}
+
 fn named_date<Tz: TimeZone>(
     now: DateTime<Utc>,
     local: Tz,
 ) -> impl Fn(&str) -> IResult<&str, DateTime<Utc>> {
     move |input: &str| {
+        let (remaining, name) = alpha1(input)?;
         let local_today = now.with_timezone(&local).date();
-        let remaining = &input[input.len()..];
         let day_index = local_today.weekday().num_days_from_monday();
-        match input {
+        let (year, month) = (local_today.year(), local_today.month());
+        let next_month = if month == 12 {
+            local.ymd(year + 1, 1, 1)
+        } else {
+            local.ymd(year, month + 1, 1)
+        };
+        let quarter_month = (month - 1) / 3 * 3 + 1;
+        let next_quarter = if quarter_month == 10 {
+            local.ymd(year + 1, 1, 1)
+        } else {
+            local.ymd(year, quarter_month + 3, 1)
+        };
+        match name {
             "yesterday" => Ok((remaining, local_today - Duration::days(1))),
             "today" => Ok((remaining, local_today)),
             "tomorrow" => Ok((remaining, local_today + Duration::days(1))),
-            // TODO: lots more!
             "eod" => Ok((remaining, local_today + Duration::days(1))),
             "sod" => Ok((remaining, local_today)),
             "eow" => Ok((
@@ -178,6 +244,12 @@ fn named_date<Tz: TimeZone>(
                 remaining,
                 local_today + Duration::days((7 - day_index).into()),
             )),
+            "som" => Ok((remaining, local.ymd(year, month, 1))),
+            "eom" => Ok((remaining, next_month)),
+            "soq" => Ok((remaining, local.ymd(year, quarter_month, 1))),
+            "eoq" => Ok((remaining, next_quarter)),
+            "soy" => Ok((remaining, local.ymd(year, 1, 1))),
+            "eoy" => Ok((remaining, local.ymd(year + 1, 1, 1))),
             _ => Err(Err::Error(Error::new(input, ErrorKind::Tag))),
         }
         .map(|(rem, dt)| (rem, dt.and_hms(0, 0, 0).with_timezone(&Utc)))
@@ -215,7 +287,23 @@ fn yyyy_mm_dd<Tz: TimeZone>(local: Tz) -> impl Fn(&str) -> IResult<&str, DateTim
     }
 }
 
-/// Recognizes timestamps
+/// Recognizes a timestamp that can be offset by a duration, such as `monday` in `monday+1wk`
+fn base_timestamp<Tz: TimeZone + Copy>(
+    now: DateTime<Utc>,
+    local: Tz,
+) -> impl Fn(&str) -> IResult<&str, DateTime<Utc>> {
+    move |input: &str| {
+        alt((
+            yyyy_mm_dd(local),
+            value(now, tag("now")),
+            next_weekday(now, local),
+            named_date(now, local),
+        ))(input)
+    }
+}
+
+/// Recognizes timestamps.  Each form must consume the entire input, since a prefix of one form
+/// may look like another form; for example, `monday` begins with the duration `mo`.
 pub(crate) fn timestamp<Tz: TimeZone + Copy>(
     now: DateTime<Utc>,
     local: Tz,
@@ -223,14 +311,32 @@ pub(crate) fn timestamp<Tz: TimeZone + Copy>(
     move |input: &str| {
         alt((
             // relative time
-            map_res(
+            all_consuming(map_res(
                 duration,
                 |duration: Duration| -> Result<DateTime<Utc>, ()> { Ok(now + duration) },
-            ),
-            rfc3339_timestamp,
-            yyyy_mm_dd(local),
-            value(now, tag("now")),
-            named_date(now, local),
+            )),
+            // relative time, such as `in 3 days`
+            all_consuming(map_res(
+                preceded(pair(tag("in"), multispace1), duration),
+                |duration: Duration| -> Result<DateTime<Utc>, ()> { Ok(now + duration) },
+            )),
+            all_consuming(rfc3339_timestamp),
+            // offset from another timestamp, such as `monday+1wk` or `eom-1d`
+            all_consuming(map_res(
+                tuple((
+                    base_timestamp(now, local),
+                    alt((char('+'), char('-'))),
+                    duration,
+                )),
+                |input: (DateTime<Utc>, char, Duration)| -> Result<DateTime<Utc>, ()> {
+                    Ok(if input.1 == '+' {
+                        input.0 + input.2
+                    } else {
+                        input.0 - input.2
+                    })
+                },
+            )),
+            all_consuming(base_timestamp(now, local)),
         ))(input)
     }
 }
@@ -301,6 +407,9 @@ mod test {
     #[case::rel_days_10(dt(2021, 5, 29, 1, 30, 0), "10d", dt(2021, 6, 8, 1, 30, 0))]
     #[case::rfc3339_datetime(*NOW, "2019-10-12T07:20:50.12Z", dt(2019, 10, 12, 7, 20, 50))]
     #[case::now(*NOW, "now", *NOW)]
+    #[case::in_days(dt(2021, 5, 29, 1, 30, 0), "in 3 days", dt(2021, 6, 1, 1, 30, 0))]
+    #[case::in_hours(dt(2021, 5, 29, 1, 30, 0), "in 2h", dt(2021, 5, 29, 3, 30, 0))]
+    #[case::now_plus(dt(2021, 5, 29, 1, 30, 0), "now+1wk", dt(2021, 6, 5, 1, 30, 0))]
     /// Cases where the `local` parameter is ignored
     fn test_nonlocal_timestamp(
         #[case] now: DateTime<Utc>,
@@ -327,6 +436,24 @@ mod test {
     #[case::start_of_work_week(ld(2021, 8, 25), "soww", ld(2021, 8, 30))]
     #[case::end_of_today(ld(2021, 8, 25), "eod", ld(2021, 8, 26))]
     #[case::start_of_today(ld(2021, 8, 25), "sod", ld(2021, 8, 25))]
+    #[case::start_of_month(ld(2021, 8, 25), "som", ld(2021, 8, 1))]
+    #[case::end_of_month(ld(2021, 8, 25), "eom", ld(2021, 9, 1))]
+    #[case::end_of_december(ld(2021, 12, 25), "eom", ld(2022, 1, 1))]
+    #[case::start_of_year(ld(2021, 8, 25), "soy", ld(2021, 1, 1))]
+    #[case::end_of_year(ld(2021, 8, 25), "eoy", ld(2022, 1, 1))]
+    #[case::start_of_quarter(ld(2021, 8, 25), "soq", ld(2021, 7, 1))]
+    #[case::end_of_quarter(ld(2021, 8, 25), "eoq", ld(2021, 10, 1))]
+    #[case::end_of_last_quarter(ld(2021, 11, 25), "eoq", ld(2022, 1, 1))]
+    // 2021-08-25 is a Wednesday
+    #[case::friday(ld(2021, 8, 25), "friday", ld(2021, 8, 27))]
+    #[case::fri(ld(2021, 8, 25), "fri", ld(2021, 8, 27))]
+    #[case::next_friday(ld(2021, 8, 25), "next friday", ld(2021, 8, 27))]
+    #[case::monday(ld(2021, 8, 25), "monday", ld(2021, 8, 30))]
+    #[case::wednesday(ld(2021, 8, 25), "wednesday", ld(2021, 9, 1))]
+    #[case::monday_plus_week(ld(2021, 8, 25), "monday+1wk", ld(2021, 9, 6))]
+    #[case::eom_minus_day(ld(2021, 8, 25), "eom-1d", ld(2021, 8, 31))]
+    #[case::tomorrow_plus_hours(ld(2021, 8, 25), "tomorrow+9h", ldt(2021, 8, 26, 9, 0, 0))]
+    #[case::date_plus_days(ld(2021, 8, 25), "2021-09-01+2d", ld(2021, 9, 3))]
     fn test_local_timestamp(
         #[case] now: Box<dyn Fn(FixedOffset) -> DateTime<Utc>>,
         #[values(*IST, *UTC_FO, *HST)] tz: FixedOffset,
@@ -346,6 +473,9 @@ mod test {
     #[rstest]
     #[case::rfc3339_datetime_bad_month(*NOW, "2019-10-99T07:20:50.12Z")]
     #[case::yyyy_mm_dd_bad_month(*NOW, "2019-10-99")]
+    #[case::next_non_weekday(*NOW, "next eom")]
+    #[case::in_without_space(*NOW, "in3days")]
+    #[case::bad_offset(*NOW, "monday+fortnight")]
     fn test_timestamp_err(#[case] now: DateTime<Utc>, #[case] input: &'static str) {
         let res = complete_timestamp(now, Utc)(input);
         assert!(
@@ -419,6 +549,9 @@ mod test {
     #[case("2 w", 14 * DAY)]
     #[case("10w", 70 * DAY)]
     #[case("1.5w", 10 * DAY + 12 * H)]
+    #[case("2wks", 14 * DAY)]
+    #[case("1wk", 7 * DAY)]
+    #[case("wk", 7 * DAY)]
     #[case("0months", 0)]
     #[case("2 months", 60 * DAY)]
     #[case("10months", 300 * DAY)]
@@ -448,12 +581,25 @@ mod test {
         assert_eq!(res.num_seconds(), seconds, "parsing {}", input);
     }
 
+    #[rstest]
+    #[case("1d12h", DAY + 12 * H)]
+    #[case("1h30min", H + 30 * M)]
+    #[case("2w3d", 17 * DAY)]
+    #[case("1 week 2 days", 9 * DAY)]
+    #[case("1.5h15min", H + 45 * M)]
+    fn test_duration_combined(#[case] input: &'static str, #[case] seconds: i64) {
+        let (_, res) = complete_duration(input).expect(input);
+        assert_eq!(res.num_seconds(), seconds, "parsing {}", input);
+    }
+
     #[rstest]
     #[case("years")]
     #[case("minutes")]
     #[case("eons")]
     #[case("P1S")] // missing T
     #[case("p1y")] // lower-case
+    #[case("1d12")] // missing unit
+    #[case("day12h")] // combination without prefix
     fn test_duration_errors(#[case] input: &'static str) {
         let res = complete_duration(input);
         assert!(
@@ -485,6 +631,8 @@ mod test {
     #[case("PT40000000S", 40000000)]
     #[case("PT3600S", H)]
     #[case("PT60M", H)]
+    #[case("P3DT4H", 3 * DAY + 4 * H)]
+    #[case("PT1.5H", H + 30 * M)]
     fn test_duration_8601(#[case] input: &'static str, #[case] seconds: i64) {
         let (_, res) = complete_duration(input).expect(input);
         assert_eq!(res.num_seconds(), seconds, "parsing {}", input);
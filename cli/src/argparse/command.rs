@@ -1,6 +1,115 @@
 use super::args::*;
+use super::suggest::explain_unrecognized;
 use super::{ArgList, Subcommand};
-use nom::{combinator::*, sequence::*, Err, IResult};
+use nom::bytes::complete::tag as nomtag;
+use nom::{branch::*, combinator::*, multi::fold_many0, sequence::*, Err, IResult};
+
+/// When to use color in the command's output, as given by the `--color` option
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum ColorMode {
+    /// Use color if the output is a terminal and `NO_COLOR` is not set
+    Auto,
+    /// Always use color
+    Always,
+    /// Never use color
+    Never,
+}
+
+/// Recognizes `--color=always`, `--color=never`, or `--color=auto`
+fn color_mode(input: &str) -> IResult<&str, ColorMode> {
+    fn to_color_mode(input: &str) -> Result<ColorMode, ()> {
+        Ok(match input {
+            "always" => ColorMode::Always,
+            "never" => ColorMode::Never,
+            _ => ColorMode::Auto,
+        })
+    }
+    map_res(
+        all_consuming(preceded(
+            nomtag("--color="),
+            alt((nomtag("always"), nomtag("never"), nomtag("auto"))),
+        )),
+        to_color_mode,
+    )(input)
+}
+
+/// The format of the command's output, as given by the `--format` option
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum OutputFormat {
+    /// Human-readable tables
+    Table,
+    /// JSON, for consumption by other programs
+    Json,
+    /// Tab-separated values, for consumption by other programs
+    Tsv,
+    /// Comma-separated values, for spreadsheets
+    Csv,
+    /// A Markdown table, for documents
+    Markdown,
+}
+
+/// Recognizes `table`, `json`, `tsv`, `csv`, or `markdown`
+fn output_format(input: &str) -> IResult<&str, OutputFormat> {
+    fn to_output_format(input: &str) -> Result<OutputFormat, ()> {
+        Ok(match input {
+            "json" => OutputFormat::Json,
+            "tsv" => OutputFormat::Tsv,
+            "csv" => OutputFormat::Csv,
+            "markdown" => OutputFormat::Markdown,
+            _ => OutputFormat::Table,
+        })
+    }
+    map_res(
+        all_consuming(alt((
+            nomtag("table"),
+            nomtag("json"),
+            nomtag("tsv"),
+            nomtag("csv"),
+            nomtag("markdown"),
+        ))),
+        to_output_format,
+    )(input)
+}
+
+/// An option that applies to all subcommands, appearing before the subcommand
+enum GlobalOption {
+    Color(ColorMode),
+    Format(OutputFormat),
+    Yes,
+    DryRun,
+    NoPager,
+    Profile(String),
+    DateFormat(String),
+    Verbosity(i8),
+}
+
+/// The accumulated values of the global options
+#[derive(Clone)]
+struct GlobalOptions {
+    color: ColorMode,
+    format: OutputFormat,
+    yes: bool,
+    dry_run: bool,
+    no_pager: bool,
+    profile: Option<String>,
+    date_format: Option<String>,
+    verbosity: i8,
+}
+
+impl Default for GlobalOptions {
+    fn default() -> Self {
+        GlobalOptions {
+            color: ColorMode::Auto,
+            format: OutputFormat::Table,
+            yes: false,
+            dry_run: false,
+            no_pager: false,
+            profile: None,
+            date_format: None,
+            verbosity: 0,
+        }
+    }
+}
 
 /// A command is the overall command that the CLI should execute.
 ///
@@ -9,12 +118,28 @@ use nom::{combinator::*, sequence::*, Err, IResult};
 #[derive(Debug, PartialEq)]
 pub(crate) struct Command {
     pub(crate) command_name: String,
+    pub(crate) color: ColorMode,
+    pub(crate) format: OutputFormat,
+    /// Skip any confirmation prompts, as given by the `--yes` option
+    pub(crate) yes: bool,
+    /// Show what the command would change without changing anything, as given by the
+    /// `--dry-run` option
+    pub(crate) dry_run: bool,
+    /// Do not page long output, as given by the `--no-pager` option
+    pub(crate) no_pager: bool,
+    /// The profile selecting the replica to use, as given by the `--profile` option
+    pub(crate) profile: Option<String>,
+    /// The format in which to display dates, as given by the `--date-format` option
+    pub(crate) date_format: Option<String>,
+    /// The number of `-v` options, less the number of `-q` options, adjusting the level of log
+    /// messages
+    pub(crate) verbosity: i8,
     pub(crate) subcommand: Subcommand,
 }
 
 impl Command {
     pub(super) fn parse(input: ArgList) -> IResult<ArgList, Command> {
-        fn to_command(input: (&str, Subcommand)) -> Result<Command, ()> {
+        fn to_command(input: (&str, GlobalOptions, Subcommand)) -> Result<Command, ()> {
             // Clean up command name, so `./target/bin/ta` to `ta` etc
             let command_name: String = std::path::PathBuf::from(&input.0)
                 .file_name()
@@ -24,33 +149,143 @@ impl Command {
 
             let command = Command {
                 command_name,
-                subcommand: input.1,
+                color: input.1.color,
+                format: input.1.format,
+                yes: input.1.yes,
+                dry_run: input.1.dry_run,
+                no_pager: input.1.no_pager,
+                profile: input.1.profile,
+                date_format: input.1.date_format,
+                verbosity: input.1.verbosity,
+                subcommand: input.2,
             };
             Ok(command)
         }
         map_res(
-            all_consuming(tuple((arg_matching(any), Subcommand::parse))),
+            all_consuming(tuple((
+                arg_matching(any),
+                Self::parse_global_options,
+                Subcommand::parse,
+            ))),
             to_command,
         )(input)
     }
 
+    /// Parse any `--color`, `--format`, `--yes`, `--dry-run`, `--no-pager`, `--profile`,
+    /// `--date-format`, `--verbose`, and `--quiet` options, in any order
+    fn parse_global_options(input: ArgList) -> IResult<ArgList, GlobalOptions> {
+        fn to_color(input: ColorMode) -> Result<GlobalOption, ()> {
+            Ok(GlobalOption::Color(input))
+        }
+        fn to_format(input: OutputFormat) -> Result<GlobalOption, ()> {
+            Ok(GlobalOption::Format(input))
+        }
+        fn to_yes(_: &str) -> Result<GlobalOption, ()> {
+            Ok(GlobalOption::Yes)
+        }
+        fn to_dry_run(_: &str) -> Result<GlobalOption, ()> {
+            Ok(GlobalOption::DryRun)
+        }
+        fn to_no_pager(_: &str) -> Result<GlobalOption, ()> {
+            Ok(GlobalOption::NoPager)
+        }
+        fn to_profile(input: &str) -> Result<GlobalOption, ()> {
+            Ok(GlobalOption::Profile(input.to_owned()))
+        }
+        fn to_date_format(input: &str) -> Result<GlobalOption, ()> {
+            Ok(GlobalOption::DateFormat(input.to_owned()))
+        }
+        /// Recognizes `--verbose`, `--quiet`, `-q`, or `-v`, which can be repeated as in `-vv`
+        fn to_verbosity(input: &str) -> Result<GlobalOption, ()> {
+            match input {
+                "--verbose" => Ok(GlobalOption::Verbosity(1)),
+                "--quiet" | "-q" => Ok(GlobalOption::Verbosity(-1)),
+                _ if input.starts_with("-v")
+                    && input.len() < 8
+                    && input[1..].chars().all(|c| c == 'v') =>
+                {
+                    Ok(GlobalOption::Verbosity(input.len() as i8 - 1))
+                }
+                _ => Err(()),
+            }
+        }
+        fold_many0(
+            alt((
+                map_res(arg_matching(color_mode), to_color),
+                map_res(
+                    preceded(
+                        arg_matching(literal("--format")),
+                        arg_matching(output_format),
+                    ),
+                    to_format,
+                ),
+                map_res(
+                    alt((arg_matching(literal("--yes")), arg_matching(literal("-y")))),
+                    to_yes,
+                ),
+                map_res(
+                    alt((
+                        arg_matching(literal("--dry-run")),
+                        arg_matching(literal("-n")),
+                    )),
+                    to_dry_run,
+                ),
+                map_res(arg_matching(literal("--no-pager")), to_no_pager),
+                map_res(
+                    preceded(arg_matching(literal("--profile")), arg_matching(any)),
+                    to_profile,
+                ),
+                map_res(
+                    preceded(arg_matching(literal("--date-format")), arg_matching(any)),
+                    to_date_format,
+                ),
+                map_res(arg_matching(any), to_verbosity),
+            )),
+            GlobalOptions::default(),
+            |acc, option| match option {
+                GlobalOption::Color(color) => GlobalOptions { color, ..acc },
+                GlobalOption::Format(format) => GlobalOptions { format, ..acc },
+                GlobalOption::Yes => GlobalOptions { yes: true, ..acc },
+                GlobalOption::DryRun => GlobalOptions {
+                    dry_run: true,
+                    ..acc
+                },
+                GlobalOption::NoPager => GlobalOptions {
+                    no_pager: true,
+                    ..acc
+                },
+                GlobalOption::Profile(profile) => GlobalOptions {
+                    profile: Some(profile),
+                    ..acc
+                },
+                GlobalOption::DateFormat(date_format) => GlobalOptions {
+                    date_format: Some(date_format),
+                    ..acc
+                },
+                GlobalOption::Verbosity(v) => GlobalOptions {
+                    verbosity: acc.verbosity.saturating_add(v),
+                    ..acc
+                },
+            },
+        )(input)
+    }
+
     /// Parse a command from the given list of strings.
     pub fn from_argv(argv: &[&str]) -> Result<Command, crate::Error> {
+        // explain the failure to parse the given remaining arguments
+        let unrecognized = |remaining: ArgList| {
+            let failed = argv.len() - remaining.len();
+            crate::Error::for_arguments(format!(
+                "command line not recognized: {}",
+                explain_unrecognized(&argv[1.min(failed)..failed], remaining.first().copied())
+            ))
+        };
         match Command::parse(argv) {
             Ok((&[], cmd)) => Ok(cmd),
-            Ok((trailing, _)) => Err(crate::Error::for_arguments(format!(
-                "command line has trailing arguments: {:?}",
-                trailing
-            ))),
+            Ok((trailing, _)) => Err(unrecognized(trailing)),
             Err(Err::Incomplete(_)) => unreachable!(),
-            Err(Err::Error(e)) => Err(crate::Error::for_arguments(format!(
-                "command line not recognized: {:?}",
-                e
-            ))),
-            Err(Err::Failure(e)) => Err(crate::Error::for_arguments(format!(
-                "command line not recognized: {:?}",
-                e
-            ))),
+            Err(Err::Error(e)) => Err(unrecognized(e.input)),
+            Err(Err::Failure(e)) => Err(unrecognized(e.input)),
         }
     }
 }
@@ -68,6 +303,14 @@ mod test {
             Command::from_argv(argv!["ta", "version"]).unwrap(),
             Command {
                 subcommand: Subcommand::Version,
+                color: ColorMode::Auto,
+                format: OutputFormat::Table,
+                yes: false,
+                dry_run: false,
+                no_pager: false,
+                profile: None,
+                date_format: None,
+                verbosity: 0,
                 command_name: s!("ta"),
             }
         );
@@ -79,8 +322,145 @@ mod test {
             Command::from_argv(argv!["/tmp/ta", "version"]).unwrap(),
             Command {
                 subcommand: Subcommand::Version,
+                color: ColorMode::Auto,
+                format: OutputFormat::Table,
+                yes: false,
+                dry_run: false,
+                no_pager: false,
+                profile: None,
+                date_format: None,
+                verbosity: 0,
+                command_name: s!("ta"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_color() {
+        assert_eq!(
+            Command::from_argv(argv!["ta", "--color=never", "version"]).unwrap(),
+            Command {
+                subcommand: Subcommand::Version,
+                color: ColorMode::Never,
+                format: OutputFormat::Table,
+                yes: false,
+                dry_run: false,
+                no_pager: false,
+                profile: None,
+                date_format: None,
+                verbosity: 0,
                 command_name: s!("ta"),
             }
         );
+        assert_eq!(
+            Command::from_argv(argv!["ta", "--color=always", "version"])
+                .unwrap()
+                .color,
+            ColorMode::Always
+        );
+        assert!(Command::from_argv(argv!["ta", "--color=sometimes", "version"]).is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        let command =
+            Command::from_argv(argv!["ta", "--format", "json", "--color=never", "list"]).unwrap();
+        assert_eq!(command.format, OutputFormat::Json);
+        assert_eq!(command.color, ColorMode::Never);
+        assert_eq!(
+            Command::from_argv(argv!["ta", "--format", "tsv", "list"])
+                .unwrap()
+                .format,
+            OutputFormat::Tsv
+        );
+        assert_eq!(
+            Command::from_argv(argv!["ta", "--format", "csv", "list"])
+                .unwrap()
+                .format,
+            OutputFormat::Csv
+        );
+        assert_eq!(
+            Command::from_argv(argv!["ta", "--format", "markdown", "list"])
+                .unwrap()
+                .format,
+            OutputFormat::Markdown
+        );
+        assert!(Command::from_argv(argv!["ta", "--format", "xml", "list"]).is_err());
+    }
+
+    #[test]
+    fn test_yes() {
+        assert!(
+            Command::from_argv(argv!["ta", "--yes", "1", "delete"])
+                .unwrap()
+                .yes
+        );
+        assert!(
+            Command::from_argv(argv!["ta", "-y", "1", "delete"])
+                .unwrap()
+                .yes
+        );
+        assert!(!Command::from_argv(argv!["ta", "1", "delete"]).unwrap().yes);
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let command =
+            Command::from_argv(argv!["ta", "--dry-run", "--yes", "+work", "done"]).unwrap();
+        assert!(command.dry_run);
+        assert!(command.yes);
+        assert!(
+            Command::from_argv(argv!["ta", "-n", "add", "x"])
+                .unwrap()
+                .dry_run
+        );
+        assert!(!Command::from_argv(argv!["ta", "add", "x"]).unwrap().dry_run);
+    }
+
+    #[test]
+    fn test_no_pager() {
+        assert!(
+            Command::from_argv(argv!["ta", "--no-pager", "list"])
+                .unwrap()
+                .no_pager
+        );
+        assert!(!Command::from_argv(argv!["ta", "list"]).unwrap().no_pager);
+    }
+
+    #[test]
+    fn test_verbosity() {
+        let verbosity = |argv: &[&str]| Command::from_argv(argv).unwrap().verbosity;
+        assert_eq!(verbosity(argv!["ta", "list"]), 0);
+        assert_eq!(verbosity(argv!["ta", "-v", "sync"]), 1);
+        assert_eq!(verbosity(argv!["ta", "-vvv", "sync"]), 3);
+        assert_eq!(verbosity(argv!["ta", "--verbose", "-v", "sync"]), 2);
+        assert_eq!(verbosity(argv!["ta", "-q", "sync"]), -1);
+        assert_eq!(verbosity(argv!["ta", "--quiet", "-v", "sync"]), 0);
+        // `-vx` is a filter excluding tasks tagged `vx`
+        assert_eq!(verbosity(argv!["ta", "-vx", "list"]), 0);
+    }
+
+    #[test]
+    fn test_date_format() {
+        let command = Command::from_argv(argv!["ta", "--date-format", "relative", "list"]).unwrap();
+        assert_eq!(command.date_format, Some(s!("relative")));
+        assert_eq!(
+            Command::from_argv(argv!["ta", "list"]).unwrap().date_format,
+            None
+        );
+        assert!(Command::from_argv(argv!["ta", "--date-format"]).is_err());
+    }
+
+    #[test]
+    fn test_profile() {
+        let command =
+            Command::from_argv(argv!["ta", "--profile", "work", "--yes", "1", "done"]).unwrap();
+        assert_eq!(command.profile, Some(s!("work")));
+        assert!(command.yes);
+        assert_eq!(
+            Command::from_argv(argv!["ta", "list"]).unwrap().profile,
+            None
+        );
+        assert!(Command::from_argv(argv!["ta", "--profile"]).is_err());
     }
 }
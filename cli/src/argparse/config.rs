@@ -8,8 +8,14 @@ use nom::{branch::alt, combinator::*, sequence::*, IResult};
 pub(crate) enum ConfigOperation {
     /// Set a configuration value
     Set(String, String),
+    /// Get a configuration value
+    Get(String),
+    /// List all configuration values
+    List,
     /// Show configuration path
     Path,
+    /// Check the configuration for problems
+    Check,
 }
 
 impl ConfigOperation {
@@ -17,9 +23,18 @@ impl ConfigOperation {
         fn set_to_op(input: (&str, &str, &str)) -> Result<ConfigOperation, ()> {
             Ok(ConfigOperation::Set(input.1.to_owned(), input.2.to_owned()))
         }
+        fn get_to_op(input: (&str, &str)) -> Result<ConfigOperation, ()> {
+            Ok(ConfigOperation::Get(input.1.to_owned()))
+        }
+        fn list_to_op(_: &str) -> Result<ConfigOperation, ()> {
+            Ok(ConfigOperation::List)
+        }
         fn path_to_op(_: &str) -> Result<ConfigOperation, ()> {
             Ok(ConfigOperation::Path)
         }
+        fn check_to_op(_: &str) -> Result<ConfigOperation, ()> {
+            Ok(ConfigOperation::Check)
+        }
         alt((
             map_res(
                 tuple((
@@ -29,7 +44,13 @@ impl ConfigOperation {
                 )),
                 set_to_op,
             ),
+            map_res(
+                pair(arg_matching(literal("get")), arg_matching(any)),
+                get_to_op,
+            ),
+            map_res(arg_matching(literal("list")), list_to_op),
             map_res(arg_matching(literal("path")), path_to_op),
+            map_res(arg_matching(literal("check")), check_to_op),
         ))(input)
     }
 
@@ -38,7 +59,38 @@ impl ConfigOperation {
             name: "config set",
             syntax: "config set <key> <value>",
             summary: "Set a configuration value",
-            description: "Update Taskchampion configuration file to set key = value",
+            description: "
+                Update Taskchampion configuration file to set key = value.  The value is checked
+                before the file is updated.",
+            examples: &["config set pager \"less -R\""],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "config get",
+            syntax: "config get <key>",
+            summary: "Get a configuration value",
+            description: "
+                Show the current value of the given configuration key, including default values.
+                Nothing is shown if the key has no value.",
+            examples: &["config get data_dir"],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "config list",
+            syntax: "config list",
+            summary: "List configuration values",
+            description: "
+                Show the keys that can be set with `config set`, and the current value of each.",
+            examples: &[],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "config check",
+            syntax: "config check",
+            summary: "Check the configuration",
+            description: "
+                Check the configuration file for problems that are otherwise only found when the
+                affected setting is used, such as a malformed sync server URL or a default report
+                that does not exist.  Unknown keys are reported, with the line on which they
+                appear, whenever the configuration file is read.",
+            examples: &[],
         });
     }
 }
@@ -0,0 +1,79 @@
+use super::args::{any, arg_matching, literal};
+use super::ArgList;
+use crate::usage;
+use nom::{branch::alt, combinator::*, multi::many1, sequence::*, IResult};
+
+#[derive(Debug, PartialEq)]
+/// A context operation
+pub(crate) enum ContextOperation {
+    /// Define a context with the given name and filter expression
+    Define(String, String),
+    /// Make the named context active
+    Set(String),
+    /// Deactivate the active context
+    None,
+    /// Show the defined contexts
+    Show,
+}
+
+impl ContextOperation {
+    pub(super) fn parse(input: ArgList) -> IResult<ArgList, ContextOperation> {
+        fn define_to_op(input: (&str, &str, Vec<&str>)) -> Result<ContextOperation, ()> {
+            Ok(ContextOperation::Define(
+                input.1.to_owned(),
+                input.2.join(" "),
+            ))
+        }
+        fn none_to_op(_: &str) -> Result<ContextOperation, ()> {
+            Ok(ContextOperation::None)
+        }
+        fn set_to_op(input: &str) -> Result<ContextOperation, ()> {
+            Ok(ContextOperation::Set(input.to_owned()))
+        }
+        fn show_to_op(_: ()) -> Result<ContextOperation, ()> {
+            Ok(ContextOperation::Show)
+        }
+        alt((
+            map_res(
+                tuple((
+                    arg_matching(literal("define")),
+                    arg_matching(any),
+                    many1(arg_matching(any)),
+                )),
+                define_to_op,
+            ),
+            map_res(arg_matching(literal("none")), none_to_op),
+            map_res(arg_matching(any), set_to_op),
+            map_res(not(arg_matching(any)), show_to_op),
+        ))(input)
+    }
+
+    pub(super) fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "context",
+            syntax: "context [<name>]",
+            summary: "Show or set the active context",
+            description: "
+                With a name, make the named context active.  The filter of the active context is
+                combined with the filter of every report, and the name of the active context is
+                shown above the report.  Without a name, show the defined contexts.",
+            examples: &["context", "context work"],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "context define",
+            syntax: "context define <name> <filter>",
+            summary: "Define a context",
+            description: "
+                Define a context with the given name, as a filter expression such as
+                'project:acme or +work'.  This updates the configuration file.",
+            examples: &["context define work project:work or +meeting"],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "context none",
+            syntax: "context none",
+            summary: "Deactivate the active context",
+            description: "Deactivate the active context, so that reports show all matching tasks.",
+            examples: &[],
+        });
+    }
+}
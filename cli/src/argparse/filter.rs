@@ -1,13 +1,19 @@
-use super::args::{arg_matching, id_list, literal, minus_tag, plus_tag, status_colon, TaskId};
+use super::args::{
+    annotation_colon, arg_matching, due_colon, id_list, literal, minus_tag, pattern, plus_tag,
+    priority_cmp_colon, project_colon, status_colon, timestamp_cmp_colon, TaskId,
+};
 use super::ArgList;
 use crate::usage;
 use anyhow::bail;
 use nom::{
     branch::alt,
     combinator::*,
-    multi::{fold_many0, fold_many1},
+    multi::many0,
+    sequence::{delimited, pair, preceded},
     IResult,
 };
+use regex::Regex;
+use taskchampion::chrono::prelude::*;
 use taskchampion::{Status, Tag};
 
 /// A filter represents a selection of a particular set of tasks.
@@ -22,6 +28,28 @@ pub(crate) struct Filter {
     pub(crate) conditions: Vec<Condition>,
 }
 
+/// A timestamp-valued task property that can be compared in a filter
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum DateProperty {
+    Due,
+    Wait,
+    Scheduled,
+    Until,
+    Entry,
+    Modified,
+    End,
+}
+
+/// A case-insensitive regular expression to search for in a task's description and annotations
+#[derive(Debug, Clone)]
+pub(crate) struct Pattern(pub(crate) Regex);
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
 /// A condition which tasks must match to be accepted by the filter.
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) enum Condition {
@@ -36,6 +64,41 @@ pub(crate) enum Condition {
 
     /// Task has one of the given IDs
     IdList(Vec<TaskId>),
+
+    /// Task is due on the same (local) day as the given time, or with `None`, has no due time
+    Due(Option<DateTime<Utc>>),
+
+    /// Task has a priority higher than the given priority
+    PriorityAbove(String),
+
+    /// Task has a priority lower than the given priority; tasks with no priority are lower
+    /// than all priorities
+    PriorityBelow(String),
+
+    /// Task is in the given project or one of its sub-projects, or with `None`, has no project
+    Project(Option<String>),
+
+    /// Task has the given property, and it is before the given time
+    Before(DateProperty, DateTime<Utc>),
+
+    /// Task has the given property, and it is after the given time
+    After(DateProperty, DateTime<Utc>),
+
+    /// Task description or one of its annotations matches the given pattern
+    Matches(Pattern),
+
+    /// One of the task's annotations contains the given text, ignoring case, or with `None`,
+    /// the task has no annotations
+    Annotation(Option<String>),
+
+    /// Task does not match the given condition
+    Not(Box<Condition>),
+
+    /// Task matches all of the given conditions
+    And(Vec<Condition>),
+
+    /// Task matches at least one of the given conditions
+    Or(Vec<Condition>),
 }
 
 impl Condition {
@@ -45,13 +108,19 @@ impl Condition {
             Self::parse_plus_tag,
             Self::parse_minus_tag,
             Self::parse_status,
+            Self::parse_due,
+            Self::parse_priority_cmp,
+            Self::parse_project,
+            Self::parse_annotation,
+            Self::parse_date_cmp,
+            Self::parse_pattern,
         ))(input)
     }
 
-    /// Parse a single condition string
+    /// Parse a single condition string, which may be a negated or parenthesized expression
     pub(crate) fn parse_str(input: &str) -> anyhow::Result<Condition> {
         let input = &[input];
-        Ok(match Condition::parse(input) {
+        Ok(match Condition::parse_factor(input) {
             Ok((&[], cond)) => cond,
             Ok(_) => unreachable!(), // input only has one element
             Err(nom::Err::Incomplete(_)) => unreachable!(),
@@ -85,7 +154,203 @@ impl Condition {
         fn to_condition(input: Status) -> Result<Condition, ()> {
             Ok(Condition::Status(input))
         }
-        map_res(arg_matching(status_colon), to_condition)(input)
+        // TaskWarrior treats waiting as a status, but in TaskChampion it is a pending task with
+        // the synthetic WAITING tag
+        fn to_waiting(_: &str) -> Result<Condition, ()> {
+            Ok(Condition::And(vec![
+                Condition::Status(Status::Pending),
+                Condition::HasTag("WAITING".parse().map_err(|_| ())?),
+            ]))
+        }
+        alt((
+            map_res(arg_matching(status_colon), to_condition),
+            map_res(arg_matching(literal("status:waiting")), to_waiting),
+        ))(input)
+    }
+
+    fn parse_due(input: ArgList) -> IResult<ArgList, Condition> {
+        fn to_condition(input: Option<DateTime<Utc>>) -> Result<Condition, ()> {
+            Ok(Condition::Due(input))
+        }
+        map_res(arg_matching(due_colon), to_condition)(input)
+    }
+
+    fn parse_priority_cmp(input: ArgList) -> IResult<ArgList, Condition> {
+        fn to_condition(input: (bool, &str)) -> Result<Condition, ()> {
+            Ok(if input.0 {
+                Condition::PriorityAbove(input.1.to_owned())
+            } else {
+                Condition::PriorityBelow(input.1.to_owned())
+            })
+        }
+        map_res(arg_matching(priority_cmp_colon), to_condition)(input)
+    }
+
+    fn parse_project(input: ArgList) -> IResult<ArgList, Condition> {
+        fn to_condition(input: Option<&str>) -> Result<Condition, ()> {
+            Ok(Condition::Project(input.map(|p| p.to_owned())))
+        }
+        map_res(arg_matching(project_colon), to_condition)(input)
+    }
+
+    fn parse_annotation(input: ArgList) -> IResult<ArgList, Condition> {
+        fn to_condition(input: Option<&str>) -> Result<Condition, ()> {
+            Ok(Condition::Annotation(input.map(|a| a.to_owned())))
+        }
+        map_res(arg_matching(annotation_colon), to_condition)(input)
+    }
+
+    fn parse_date_cmp(input: ArgList) -> IResult<ArgList, Condition> {
+        fn to_condition(
+            prop: DateProperty,
+        ) -> impl Fn((bool, DateTime<Utc>)) -> Result<Condition, ()> {
+            move |(before, ts)| {
+                Ok(if before {
+                    Condition::Before(prop, ts)
+                } else {
+                    Condition::After(prop, ts)
+                })
+            }
+        }
+        alt((
+            map_res(
+                arg_matching(timestamp_cmp_colon("due")),
+                to_condition(DateProperty::Due),
+            ),
+            map_res(
+                arg_matching(timestamp_cmp_colon("wait")),
+                to_condition(DateProperty::Wait),
+            ),
+            map_res(
+                arg_matching(timestamp_cmp_colon("scheduled")),
+                to_condition(DateProperty::Scheduled),
+            ),
+            map_res(
+                arg_matching(timestamp_cmp_colon("until")),
+                to_condition(DateProperty::Until),
+            ),
+            map_res(
+                arg_matching(timestamp_cmp_colon("entry")),
+                to_condition(DateProperty::Entry),
+            ),
+            map_res(
+                arg_matching(timestamp_cmp_colon("modified")),
+                to_condition(DateProperty::Modified),
+            ),
+            map_res(
+                arg_matching(timestamp_cmp_colon("end")),
+                to_condition(DateProperty::End),
+            ),
+        ))(input)
+    }
+
+    fn parse_pattern(input: ArgList) -> IResult<ArgList, Condition> {
+        fn to_condition(input: Regex) -> Result<Condition, ()> {
+            Ok(Condition::Matches(Pattern(input)))
+        }
+        map_res(arg_matching(pattern), to_condition)(input)
+    }
+
+    // Boolean expressions of conditions.  In order of increasing precedence, these are `or`,
+    // `and` (which may be implicit, as in `+foo +bar`), `not`, and parentheses.  Parentheses can
+    // be given as separate arguments, or an entire parenthesized expression can be given as a
+    // single argument, such as `'(+foo or +bar)'`.
+
+    /// Parse a conjunction of conditions, returned as a vector of conditions
+    fn parse_and(input: ArgList) -> IResult<ArgList, Vec<Condition>> {
+        fn to_conditions(input: (Condition, Vec<Condition>)) -> Result<Vec<Condition>, ()> {
+            let (first, mut rest) = input;
+            rest.insert(0, first);
+            Ok(rest)
+        }
+        map_res(
+            pair(
+                Self::parse_factor,
+                many0(preceded(
+                    opt(arg_matching(literal("and"))),
+                    Self::parse_factor,
+                )),
+            ),
+            to_conditions,
+        )(input)
+    }
+
+    /// Parse a disjunction of conjunctions of conditions
+    fn parse_or(input: ArgList) -> IResult<ArgList, Vec<Vec<Condition>>> {
+        fn to_alternatives(
+            input: (Vec<Condition>, Vec<Vec<Condition>>),
+        ) -> Result<Vec<Vec<Condition>>, ()> {
+            let (first, mut rest) = input;
+            rest.insert(0, first);
+            Ok(rest)
+        }
+        map_res(
+            pair(
+                Self::parse_and,
+                many0(preceded(arg_matching(literal("or")), Self::parse_and)),
+            ),
+            to_alternatives,
+        )(input)
+    }
+
+    /// Parse a disjunction into a single condition
+    fn parse_expr(input: ArgList) -> IResult<ArgList, Condition> {
+        fn to_condition(input: Vec<Vec<Condition>>) -> Result<Condition, ()> {
+            Ok(Condition::from_alternatives(input))
+        }
+        map_res(Self::parse_or, to_condition)(input)
+    }
+
+    /// Parse a negated condition, a parenthesized expression, or a single condition
+    fn parse_factor(input: ArgList) -> IResult<ArgList, Condition> {
+        fn to_not(input: Condition) -> Result<Condition, ()> {
+            Ok(Condition::Not(Box::new(input)))
+        }
+        alt((
+            map_res(
+                preceded(arg_matching(literal("not")), Self::parse_factor),
+                to_not,
+            ),
+            delimited(
+                arg_matching(literal("(")),
+                Self::parse_expr,
+                arg_matching(literal(")")),
+            ),
+            arg_matching(Self::parenthesized_arg),
+            Self::parse,
+        ))(input)
+    }
+
+    /// Recognize a single argument containing an entire parenthesized expression
+    fn parenthesized_arg(input: &str) -> IResult<&str, Condition> {
+        let err = || nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Verify));
+        if !(input.starts_with('(') && input.ends_with(')')) {
+            return Err(err());
+        }
+
+        // split the argument into words, with each parenthesis as a separate word
+        let spaced = input.replace('(', " ( ").replace(')', " ) ");
+        let words: Vec<&str> = spaced.split_whitespace().collect();
+        let cond = all_consuming(Self::parse_expr)(&words)
+            .map(|(_, cond)| cond)
+            .map_err(|_| err())?;
+        Ok((&input[input.len()..], cond))
+    }
+
+    /// Convert a disjunction of conjunctions into a single condition
+    fn from_alternatives(mut alternatives: Vec<Vec<Condition>>) -> Condition {
+        fn conjunction(mut conds: Vec<Condition>) -> Condition {
+            if conds.len() == 1 {
+                conds.remove(0)
+            } else {
+                Condition::And(conds)
+            }
+        }
+        if alternatives.len() == 1 {
+            conjunction(alternatives.remove(0))
+        } else {
+            Condition::Or(alternatives.drain(..).map(conjunction).collect())
+        }
     }
 }
 
@@ -93,28 +358,40 @@ impl Filter {
     /// Parse a filter that can include an empty set of args (meaning
     /// all tasks)
     pub(super) fn parse0(input: ArgList) -> IResult<ArgList, Filter> {
-        fold_many0(
-            Condition::parse,
-            Filter {
-                ..Default::default()
-            },
-            |acc, arg| acc.with_arg(arg),
-        )(input)
+        fn to_filter(input: Option<Vec<Vec<Condition>>>) -> Result<Filter, ()> {
+            Ok(match input {
+                Some(alternatives) => Filter::from_alternatives(alternatives),
+                None => Filter {
+                    ..Default::default()
+                },
+            })
+        }
+        map_res(opt(Condition::parse_or), to_filter)(input)
     }
 
     /// Parse a filter that must have at least one arg, which can be `all`
     /// to mean all tasks
     pub(super) fn parse1(input: ArgList) -> IResult<ArgList, Filter> {
-        alt((
-            Filter::parse_all,
-            fold_many1(
-                Condition::parse,
-                Filter {
-                    ..Default::default()
-                },
-                |acc, arg| acc.with_arg(arg),
-            ),
-        ))(input)
+        fn to_filter(input: Vec<Vec<Condition>>) -> Result<Filter, ()> {
+            Ok(Filter::from_alternatives(input))
+        }
+        alt((Filter::parse_all, map_res(Condition::parse_or, to_filter)))(input)
+    }
+
+    /// Build a filter from the alternatives parsed from the command line.  If there is only one
+    /// alternative, its conditions are AND'd together at the top level of the filter.
+    fn from_alternatives(mut alternatives: Vec<Vec<Condition>>) -> Filter {
+        let filter = Filter {
+            ..Default::default()
+        };
+        if alternatives.len() == 1 {
+            alternatives
+                .remove(0)
+                .drain(..)
+                .fold(filter, |acc, cond| acc.with_arg(cond))
+        } else {
+            filter.with_arg(Condition::from_alternatives(alternatives))
+        }
     }
 
     fn parse_all(input: ArgList) -> IResult<ArgList, Filter> {
@@ -183,10 +460,70 @@ impl Filter {
                 Select tasks that do not have the given tag.",
         });
         u.filters.push(usage::Filter {
-            syntax: "status:pending, status:completed, status:deleted",
+            syntax: "status:pending, status:completed, status:deleted, status:waiting",
             summary: "Task status",
             description: "
-                Select tasks with the given status.",
+                Select tasks with the given status.  `status:waiting` selects pending tasks that
+                are waiting, equivalent to `status:pending +WAITING`.",
+        });
+        u.filters.push(usage::Filter {
+            syntax: "due:<timestamp>",
+            summary: "Due date",
+            description: "
+                Select tasks due on the same day as the given timestamp, e.g., `due:today`.  With
+                `due:`, select tasks that have no due date.",
+        });
+        u.filters.push(usage::Filter {
+            syntax: "priority.above:{M,L}, priority.below:{H,M}",
+            summary: "Priority",
+            description: "
+                Select tasks with a priority higher or lower than the given priority.  Tasks
+                without a priority are considered lower than `L`.",
+        });
+        u.filters.push(usage::Filter {
+            syntax: "project:<project>",
+            summary: "Project",
+            description: "
+                Select tasks in the given project, including its sub-projects.  For example,
+                `project:work` matches tasks in projects `work` and `work.client`, but not
+                `workshop`.  With `project:`, select tasks that have no project.",
+        });
+        u.filters.push(usage::Filter {
+            syntax: "annotation:<text>",
+            summary: "Annotation text",
+            description: "
+                Select tasks with an annotation containing the given text, ignoring case.  For
+                example, `annotation:JIRA-123` selects tasks with a note mentioning that ticket.
+                Quote text containing spaces, as in `'annotation:called back'`.  With
+                `annotation:`, select tasks that have no annotations.",
+        });
+        u.filters.push(usage::Filter {
+            syntax: "<property>.before:<timestamp>, <property>.after:<timestamp>",
+            summary: "Time range",
+            description: "
+                Select tasks with the given time property before or after the given timestamp.
+                The property can be `due`, `wait`, `scheduled`, `until`, `entry`, `modified`, or
+                `end`.  Tasks without the property are not selected.  For example,
+                `due.before:eom` selects tasks due before the end of the month, and
+                `end.after:2024-01-01` selects tasks completed or deleted since the start of 2024.",
+        });
+        u.filters.push(usage::Filter {
+            syntax: "/<pattern>/",
+            summary: "Description search",
+            description: "
+                Select tasks whose description or annotations match the given pattern,
+                ignoring case.  The pattern can be a simple word, or a regular expression such
+                as `/^call/`.",
+        });
+        u.filters.push(usage::Filter {
+            syntax: "<filter> or <filter>, <filter> and <filter>, not <filter>, ( <filter> )",
+            summary: "Boolean combinations",
+            description: "
+                Combine filters.  Filters given one after another must all match, as if joined by
+                `and`.  `not` has the highest precedence, followed by `and` and then `or`, so
+                parentheses are needed to group alternatives, as in
+                `ta '(project:home or +errand)' -waiting list`.  Each parenthesis may be a separate
+                argument, or an entire parenthesized expression may be quoted as one argument.",
         });
         u.filters.push(usage::Filter {
             syntax: "all",
@@ -328,6 +665,211 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_due() {
+        let (input, filter) = Filter::parse0(argv!["due:", "due:2022-01-01T00:00:00Z"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            filter,
+            Filter {
+                conditions: vec![
+                    Condition::Due(None),
+                    Condition::Due(Some(Utc.ymd(2022, 1, 1).and_hms(0, 0, 0))),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_priority_cmp() {
+        let (input, filter) =
+            Filter::parse0(argv!["priority.above:L", "priority.below:H"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            filter,
+            Filter {
+                conditions: vec![
+                    Condition::PriorityAbove(s!("L")),
+                    Condition::PriorityBelow(s!("H")),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_project() {
+        let (input, filter) = Filter::parse0(argv!["project:work", "project:"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            filter,
+            Filter {
+                conditions: vec![
+                    Condition::Project(Some(s!("work"))),
+                    Condition::Project(None),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_annotation() {
+        let (input, filter) = Filter::parse0(argv!["annotation:JIRA-123", "annotation:"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            filter,
+            Filter {
+                conditions: vec![
+                    Condition::Annotation(Some(s!("JIRA-123"))),
+                    Condition::Annotation(None),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_pattern() {
+        let (input, filter) = Filter::parse0(argv!["/invoice/", "+work"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(filter.conditions.len(), 2);
+        match &filter.conditions[0] {
+            Condition::Matches(Pattern(re)) => assert_eq!(re.as_str(), "invoice"),
+            cond => panic!("unexpected condition {:?}", cond),
+        }
+    }
+
+    #[test]
+    fn test_or() {
+        let (input, filter) =
+            Filter::parse0(argv!["+foo", "+bar", "or", "+baz", "and", "list"]).unwrap();
+        // `and` is not followed by a condition, so it is not consumed
+        assert_eq!(input, argv!["and", "list"]);
+        assert_eq!(
+            filter,
+            Filter {
+                conditions: vec![Condition::Or(vec![
+                    Condition::And(vec![
+                        Condition::HasTag(tag!("foo")),
+                        Condition::HasTag(tag!("bar"))
+                    ]),
+                    Condition::HasTag(tag!("baz")),
+                ])],
+            }
+        );
+    }
+
+    #[test]
+    fn test_explicit_and() {
+        let (input, filter) = Filter::parse0(argv!["+foo", "and", "+bar"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            filter,
+            Filter {
+                conditions: vec![
+                    Condition::HasTag(tag!("foo")),
+                    Condition::HasTag(tag!("bar"))
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_not_and_parens() {
+        let (input, filter) =
+            Filter::parse0(argv!["not", "(", "+foo", "or", "+bar", ")", "+baz"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            filter,
+            Filter {
+                conditions: vec![
+                    Condition::Not(Box::new(Condition::Or(vec![
+                        Condition::HasTag(tag!("foo")),
+                        Condition::HasTag(tag!("bar"))
+                    ]))),
+                    Condition::HasTag(tag!("baz")),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parenthesized_arg() {
+        let (input, filter) =
+            Filter::parse0(argv!["(project:home or +errand)", "-waiting", "list"]).unwrap();
+        assert_eq!(input, argv!["list"]);
+        assert_eq!(
+            filter,
+            Filter {
+                conditions: vec![
+                    Condition::Or(vec![
+                        Condition::Project(Some(s!("home"))),
+                        Condition::HasTag(tag!("errand"))
+                    ]),
+                    Condition::NoTag(tag!("waiting")),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_nested_parenthesized_arg() {
+        let (input, filter) = Filter::parse0(argv!["((+a or +b) not +c)"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            filter,
+            Filter {
+                conditions: vec![Condition::And(vec![
+                    Condition::Or(vec![
+                        Condition::HasTag(tag!("a")),
+                        Condition::HasTag(tag!("b"))
+                    ]),
+                    Condition::Not(Box::new(Condition::HasTag(tag!("c")))),
+                ])],
+            }
+        );
+    }
+
+    #[test]
+    fn test_unbalanced_parens() {
+        let (input, filter) = Filter::parse0(argv!["(", "+foo", "list"]).unwrap();
+        assert_eq!(input, argv!["(", "+foo", "list"]);
+        assert_eq!(filter, Filter::default());
+
+        let (input, _) = Filter::parse0(argv!["(+foo or", "list"]).unwrap();
+        assert_eq!(input, argv!["(+foo or", "list"]);
+    }
+
+    #[test]
+    fn test_date_cmp() {
+        let (input, filter) = Filter::parse0(argv![
+            "scheduled.before:2022-01-01T00:00:00Z",
+            "until.after:2022-02-01T00:00:00Z",
+            "due.before:2022-03-01T00:00:00Z",
+            "wait.after:2022-04-01T00:00:00Z",
+            "entry.after:2022-05-01T00:00:00Z",
+            "modified.before:2022-06-01T00:00:00Z",
+            "end.after:2022-07-01T00:00:00Z"
+        ])
+        .unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            filter,
+            Filter {
+                conditions: vec![
+                    Condition::Before(
+                        DateProperty::Scheduled,
+                        Utc.ymd(2022, 1, 1).and_hms(0, 0, 0)
+                    ),
+                    Condition::After(DateProperty::Until, Utc.ymd(2022, 2, 1).and_hms(0, 0, 0)),
+                    Condition::Before(DateProperty::Due, Utc.ymd(2022, 3, 1).and_hms(0, 0, 0)),
+                    Condition::After(DateProperty::Wait, Utc.ymd(2022, 4, 1).and_hms(0, 0, 0)),
+                    Condition::After(DateProperty::Entry, Utc.ymd(2022, 5, 1).and_hms(0, 0, 0)),
+                    Condition::Before(DateProperty::Modified, Utc.ymd(2022, 6, 1).and_hms(0, 0, 0)),
+                    Condition::After(DateProperty::End, Utc.ymd(2022, 7, 1).and_hms(0, 0, 0)),
+                ],
+            }
+        );
+    }
+
     #[test]
     fn test_status() {
         let (input, filter) = Filter::parse0(argv!["status:completed", "status:pending"]).unwrap();
@@ -343,6 +885,24 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_status_waiting() {
+        let (input, filter) = Filter::parse0(argv!["status:waiting", "+OVERDUE"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            filter,
+            Filter {
+                conditions: vec![
+                    Condition::And(vec![
+                        Condition::Status(Status::Pending),
+                        Condition::HasTag(tag!("WAITING")),
+                    ]),
+                    Condition::HasTag(tag!("OVERDUE")),
+                ],
+            }
+        );
+    }
+
     #[test]
     fn intersect_idlist_idlist() {
         let left = Filter::parse0(argv!["1,2", "+yes"]).unwrap().1;
@@ -16,23 +16,33 @@ All of the structs produced by this module are fully-owned, data-only structs.
 That is, they contain no references, and have no methods to aid in their execution -- that is the `invocation` module's job.
 
 */
+mod alias;
 mod args;
 mod command;
 mod config;
+mod context;
 mod filter;
 mod modification;
 mod subcommand;
+mod suggest;
 
+pub(crate) use alias::expand_aliases;
 pub(crate) use args::TaskId;
-pub(crate) use command::Command;
+pub(crate) use command::{ColorMode, Command, OutputFormat};
 pub(crate) use config::ConfigOperation;
-pub(crate) use filter::{Condition, Filter};
+pub(crate) use context::ContextOperation;
+pub(crate) use filter::{Condition, DateProperty, Filter, Pattern};
 pub(crate) use modification::{DescriptionMod, Modification};
-pub(crate) use subcommand::Subcommand;
+pub(crate) use subcommand::{
+    ExportFormat, ImportFormat, ReportLimit, ReportOptions, Shell, Subcommand,
+};
+pub(crate) use suggest::{did_you_mean, subcommand_names};
 
 use crate::usage::Usage;
 use lazy_static::lazy_static;
+use nom::combinator::all_consuming;
 use taskchampion::chrono::prelude::*;
+use taskchampion::chrono::Duration;
 
 lazy_static! {
     // A static value of NOW to make tests easier
@@ -41,6 +51,21 @@ lazy_static! {
 
 type ArgList<'a> = &'a [&'a str];
 
+/// Parse a duration in any of the formats accepted on the command line, such as `1w` or `P7D`
+pub(crate) fn parse_duration(input: &str) -> Option<Duration> {
+    all_consuming(args::duration)(input)
+        .ok()
+        .map(|(_, dur)| dur)
+}
+
+/// Parse a timestamp in any of the formats accepted on the command line, such as `2021-03-04`,
+/// an RFC3339 timestamp, or a duration relative to now
+pub(crate) fn parse_timestamp(input: &str) -> Option<DateTime<Utc>> {
+    all_consuming(args::timestamp(*NOW, Local))(input)
+        .ok()
+        .map(|(_, ts)| ts)
+}
+
 pub(crate) fn get_usage(usage: &mut Usage) {
     Subcommand::get_usage(usage);
     Filter::get_usage(usage);
@@ -1,9 +1,14 @@
-use super::args::{any, arg_matching, depends_colon, minus_tag, plus_tag, wait_colon, TaskId};
+use super::args::{
+    any, arg_matching, depends_colon, due_colon, link_colon, minus_tag, plus_tag, priority_colon,
+    project_colon, recur_colon, scheduled_colon, until_colon, wait_colon, TaskId,
+};
 use super::ArgList;
 use crate::usage;
+use anyhow::bail;
 use nom::{branch::alt, combinator::*, multi::fold_many0, IResult};
 use std::collections::HashSet;
 use taskchampion::chrono::prelude::*;
+use taskchampion::chrono::Duration;
 use taskchampion::{Status, Tag};
 
 #[derive(Debug, PartialEq, Clone)]
@@ -40,6 +45,24 @@ pub(crate) struct Modification {
     /// Set (or, with `Some(None)`, clear) the wait timestamp
     pub(crate) wait: Option<Option<DateTime<Utc>>>,
 
+    /// Set (or, with `Some(None)`, clear) the due timestamp
+    pub(crate) due: Option<Option<DateTime<Utc>>>,
+
+    /// Set (or, with `Some(None)`, clear) the scheduled timestamp
+    pub(crate) scheduled: Option<Option<DateTime<Utc>>>,
+
+    /// Set (or, with `Some(None)`, clear) the until timestamp
+    pub(crate) until: Option<Option<DateTime<Utc>>>,
+
+    /// Set (or, with `Some(None)`, clear) the recurrence interval
+    pub(crate) recur: Option<Option<Duration>>,
+
+    /// Set (or, with `Some(None)`, clear) the project
+    pub(crate) project: Option<Option<String>>,
+
+    /// Set the priority (`H`, `M`, `L`, or empty to clear it)
+    pub(crate) priority: Option<String>,
+
     /// Set the "active" state, that is, start (true) or stop (false) the task.
     pub(crate) active: Option<bool>,
 
@@ -57,6 +80,15 @@ pub(crate) struct Modification {
 
     /// Add annotation
     pub(crate) annotate: Option<String>,
+
+    /// Remove annotations containing this text
+    pub(crate) denotate: Option<String>,
+
+    /// Add links, by URL
+    pub(crate) add_links: HashSet<String>,
+
+    /// Remove links, by URL
+    pub(crate) remove_links: HashSet<String>,
 }
 
 /// A single argument that is part of a modification, used internally to this module
@@ -65,8 +97,16 @@ enum ModArg<'a> {
     PlusTag(Tag),
     MinusTag(Tag),
     Wait(Option<DateTime<Utc>>),
+    Due(Option<DateTime<Utc>>),
+    Scheduled(Option<DateTime<Utc>>),
+    Until(Option<DateTime<Utc>>),
+    Recur(Option<Duration>),
+    Priority(&'a str),
+    Project(Option<&'a str>),
     AddDependencies(Vec<TaskId>),
     RemoveDependencies(Vec<TaskId>),
+    AddLink(&'a str),
+    RemoveLink(&'a str),
 }
 
 impl Modification {
@@ -90,6 +130,24 @@ impl Modification {
                 ModArg::Wait(wait) => {
                     acc.wait = Some(wait);
                 }
+                ModArg::Due(due) => {
+                    acc.due = Some(due);
+                }
+                ModArg::Scheduled(scheduled) => {
+                    acc.scheduled = Some(scheduled);
+                }
+                ModArg::Until(until) => {
+                    acc.until = Some(until);
+                }
+                ModArg::Recur(recur) => {
+                    acc.recur = Some(recur);
+                }
+                ModArg::Priority(priority) => {
+                    acc.priority = Some(priority.to_string());
+                }
+                ModArg::Project(project) => {
+                    acc.project = Some(project.map(|p| p.to_string()));
+                }
                 ModArg::AddDependencies(task_ids) => {
                     for tid in task_ids {
                         acc.add_dependencies.insert(tid);
@@ -100,6 +158,12 @@ impl Modification {
                         acc.remove_dependencies.insert(tid);
                     }
                 }
+                ModArg::AddLink(url) => {
+                    acc.add_links.insert(url.to_string());
+                }
+                ModArg::RemoveLink(url) => {
+                    acc.remove_links.insert(url.to_string());
+                }
             }
             acc
         }
@@ -108,7 +172,14 @@ impl Modification {
                 Self::plus_tag,
                 Self::minus_tag,
                 Self::wait,
+                Self::due,
+                Self::scheduled,
+                Self::until,
+                Self::recur,
+                Self::priority,
+                Self::project,
                 Self::dependencies,
+                Self::link,
                 // this must come last
                 Self::description,
             )),
@@ -119,6 +190,18 @@ impl Modification {
         )(input)
     }
 
+    /// Parse a modification from a complete list of arguments, such as the words of a line of
+    /// input
+    pub(crate) fn parse_args(input: ArgList) -> anyhow::Result<Modification> {
+        Ok(match Modification::parse(input) {
+            Ok((&[], modification)) => modification,
+            Ok((rest, _)) => bail!("unrecognized arguments: {}", rest.join(" ")),
+            Err(nom::Err::Incomplete(_)) => unreachable!(),
+            Err(nom::Err::Error(e)) => bail!("invalid modification: {:?}", e),
+            Err(nom::Err::Failure(e)) => bail!("invalid modification: {:?}", e),
+        })
+    }
+
     fn description(input: ArgList) -> IResult<ArgList, ModArg> {
         fn to_modarg(input: &str) -> Result<ModArg, ()> {
             Ok(ModArg::Description(input))
@@ -147,6 +230,48 @@ impl Modification {
         map_res(arg_matching(wait_colon), to_modarg)(input)
     }
 
+    fn due(input: ArgList) -> IResult<ArgList, ModArg> {
+        fn to_modarg(input: Option<DateTime<Utc>>) -> Result<ModArg<'static>, ()> {
+            Ok(ModArg::Due(input))
+        }
+        map_res(arg_matching(due_colon), to_modarg)(input)
+    }
+
+    fn scheduled(input: ArgList) -> IResult<ArgList, ModArg> {
+        fn to_modarg(input: Option<DateTime<Utc>>) -> Result<ModArg<'static>, ()> {
+            Ok(ModArg::Scheduled(input))
+        }
+        map_res(arg_matching(scheduled_colon), to_modarg)(input)
+    }
+
+    fn until(input: ArgList) -> IResult<ArgList, ModArg> {
+        fn to_modarg(input: Option<DateTime<Utc>>) -> Result<ModArg<'static>, ()> {
+            Ok(ModArg::Until(input))
+        }
+        map_res(arg_matching(until_colon), to_modarg)(input)
+    }
+
+    fn recur(input: ArgList) -> IResult<ArgList, ModArg> {
+        fn to_modarg(input: Option<Duration>) -> Result<ModArg<'static>, ()> {
+            Ok(ModArg::Recur(input))
+        }
+        map_res(arg_matching(recur_colon), to_modarg)(input)
+    }
+
+    fn priority(input: ArgList) -> IResult<ArgList, ModArg> {
+        fn to_modarg(input: &str) -> Result<ModArg<'_>, ()> {
+            Ok(ModArg::Priority(input))
+        }
+        map_res(arg_matching(priority_colon), to_modarg)(input)
+    }
+
+    fn project(input: ArgList) -> IResult<ArgList, ModArg> {
+        fn to_modarg(input: Option<&str>) -> Result<ModArg<'_>, ()> {
+            Ok(ModArg::Project(input))
+        }
+        map_res(arg_matching(project_colon), to_modarg)(input)
+    }
+
     fn dependencies(input: ArgList) -> IResult<ArgList, ModArg> {
         fn to_modarg(input: (bool, Vec<TaskId>)) -> Result<ModArg<'static>, ()> {
             Ok(if input.0 {
@@ -158,12 +283,24 @@ impl Modification {
         map_res(arg_matching(depends_colon), to_modarg)(input)
     }
 
+    fn link(input: ArgList) -> IResult<ArgList, ModArg> {
+        fn to_modarg(input: (bool, &str)) -> Result<ModArg<'_>, ()> {
+            Ok(if input.0 {
+                ModArg::AddLink(input.1)
+            } else {
+                ModArg::RemoveLink(input.1)
+            })
+        }
+        map_res(arg_matching(link_colon), to_modarg)(input)
+    }
+
     pub(super) fn get_usage(u: &mut usage::Usage) {
         u.modifications.push(usage::Modification {
             syntax: "DESCRIPTION",
             summary: "Set description/annotation",
             description: "
-                Set the task description (or the task annotation for `ta annotate`).  Multiple
+                Set the task description (or the task annotation for `ta annotate`, or the
+                text of the annotations to remove for `ta denotate`).  Multiple
                 arguments are combined into a single space-separated description.  To avoid
                 surprises from shell quoting, prefer to use a single quoted argument, for example
                 `ta 19 modify \"return library books\"`",
@@ -191,6 +328,50 @@ impl Modification {
                 reports, e.g., `wait:3day` to wait for three days.  With `wait:`, the time is
                 un-set.  See the documentation for the timestamp syntax.",
         });
+        u.modifications.push(usage::Modification {
+            syntax: "due:<timestamp>",
+            summary: "Set or unset the task's due time",
+            description: "
+                Set the time by which the task should be completed, e.g., `due:2022-03-01`.  With
+                `due:`, the time is un-set.  See the documentation for the timestamp syntax.",
+        });
+        u.modifications.push(usage::Modification {
+            syntax: "scheduled:<timestamp>",
+            summary: "Set or unset the task's scheduled time",
+            description: "
+                Set the time before which work on the task should not begin.  With `scheduled:`,
+                the time is un-set.  See the documentation for the timestamp syntax.",
+        });
+        u.modifications.push(usage::Modification {
+            syntax: "recur:<duration>",
+            summary: "Set or unset the task's recurrence",
+            description: "
+                Make this a recurring task, repeating at the given interval, such as `recur:1w`.
+                A recurring task must have a due date.  With `recur:`, the task no longer
+                recurs.",
+        });
+        u.modifications.push(usage::Modification {
+            syntax: "until:<timestamp>",
+            summary: "Set or unset the task's until time",
+            description: "
+                Set the time after which the task is no longer relevant.  With `until:`, the time
+                is un-set.  See the documentation for the timestamp syntax.",
+        });
+        u.modifications.push(usage::Modification {
+            syntax: "priority:{H,M,L}",
+            summary: "Set or unset the task's priority",
+            description: "
+                Set the task's priority to high (`H`), medium (`M`), or low (`L`).  With
+                `priority:`, the priority is un-set.",
+        });
+        u.modifications.push(usage::Modification {
+            syntax: "project:<project>",
+            summary: "Set or unset the task's project",
+            description: "
+                Set the project to which the task belongs.  Projects are hierarchical, with levels
+                separated by `.`, such as `project:work.client`.  With `project:`, the project is
+                un-set.",
+        });
         u.modifications.push(usage::Modification {
             syntax: "depends:<task-list>",
             summary: "Add task dependencies",
@@ -204,6 +385,18 @@ impl Modification {
             description: "
                 Remove the dependency of this task on the given tasks.",
         });
+        u.modifications.push(usage::Modification {
+            syntax: "link:<url>",
+            summary: "Add a link",
+            description: "
+                Add a link to the given URL, such as a ticket or document related to the task.",
+        });
+        u.modifications.push(usage::Modification {
+            syntax: "link:-<url>",
+            summary: "Remove a link",
+            description: "
+                Remove the link to the given URL.",
+        });
     }
 }
 
@@ -265,6 +458,152 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_set_due() {
+        let (input, modification) = Modification::parse(argv!["due:2d"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                due: Some(Some(*NOW + Duration::days(2))),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_unset_due() {
+        let (input, modification) = Modification::parse(argv!["due:"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                due: Some(None),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_set_scheduled_until() {
+        let (input, modification) = Modification::parse(argv!["scheduled:2d", "until:3d"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                scheduled: Some(Some(*NOW + Duration::days(2))),
+                until: Some(Some(*NOW + Duration::days(3))),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_unset_scheduled_until() {
+        let (input, modification) = Modification::parse(argv!["scheduled:", "until:"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                scheduled: Some(None),
+                until: Some(None),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let modification = Modification::parse_args(&["buy", "milk", "+shopping"]).unwrap();
+        assert_eq!(
+            modification,
+            Modification {
+                description: DescriptionMod::Set(s!("buy milk")),
+                add_tags: set![tag!("shopping")],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_set_recur() {
+        let (input, modification) = Modification::parse(argv!["recur:2w", "due:1d"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                recur: Some(Some(Duration::days(14))),
+                due: Some(Some(*NOW + Duration::days(1))),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_unset_recur() {
+        let (input, modification) = Modification::parse(argv!["recur:"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                recur: Some(None),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_set_priority() {
+        let (input, modification) = Modification::parse(argv!["priority:H"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                priority: Some(s!("H")),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_unset_priority() {
+        let (input, modification) = Modification::parse(argv!["priority:"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                priority: Some(s!("")),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_set_project() {
+        let (input, modification) = Modification::parse(argv!["project:work.client"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                project: Some(Some(s!("work.client"))),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_unset_project() {
+        let (input, modification) = Modification::parse(argv!["project:"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                project: Some(None),
+                ..Default::default()
+            }
+        );
+    }
+
     #[test]
     fn test_add_deps() {
         let (input, modification) = Modification::parse(argv!["depends:13,e72b73d1-9e88"]).unwrap();
@@ -298,6 +637,34 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_add_link() {
+        let (input, modification) =
+            Modification::parse(argv!["link:https://example.com/1"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                add_links: set![s!("https://example.com/1")],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_remove_link() {
+        let (input, modification) =
+            Modification::parse(argv!["link:-https://example.com/1"]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(
+            modification,
+            Modification {
+                remove_links: set![s!("https://example.com/1")],
+                ..Default::default()
+            }
+        );
+    }
+
     #[test]
     fn test_unset_wait() {
         let (input, modification) = Modification::parse(argv!["wait:"]).unwrap();
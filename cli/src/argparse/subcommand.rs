@@ -1,8 +1,9 @@
 use super::args::*;
-use super::{ArgList, ConfigOperation, DescriptionMod, Filter, Modification};
+use super::{ArgList, ConfigOperation, ContextOperation, DescriptionMod, Filter, Modification};
 use crate::usage;
-use nom::{branch::alt, combinator::*, sequence::*, IResult};
-use taskchampion::Status;
+use nom::{branch::alt, combinator::*, multi::*, sequence::*, IResult};
+use taskchampion::chrono::Duration;
+use taskchampion::{Status, Tag};
 
 // IMPLEMENTATION NOTE:
 //
@@ -13,6 +14,71 @@ use taskchampion::Status;
 // This organization helps to gather the parsing and usage information into
 // comprehensible chunks of code, to ensure that everything is documented.
 
+/// Command-line overrides of a report's definition, from the `--columns` and `--sort` options
+/// and the `limit:` argument
+#[derive(Debug, PartialEq, Default, Clone)]
+pub(crate) struct ReportOptions {
+    /// Columns to display instead of those in the report
+    pub(crate) columns: Option<Vec<String>>,
+
+    /// Sort order to use instead of that in the report
+    pub(crate) sort: Option<Vec<String>>,
+
+    /// Maximum number of tasks to display instead of that in the report
+    pub(crate) limit: Option<ReportLimit>,
+}
+
+impl ReportOptions {
+    /// Combine these options with options given later on the command line, which take
+    /// precedence.
+    fn merge(self, later: ReportOptions) -> ReportOptions {
+        ReportOptions {
+            columns: later.columns.or(self.columns),
+            sort: later.sort.or(self.sort),
+            limit: later.limit.or(self.limit),
+        }
+    }
+}
+
+/// A limit on the number of tasks shown in a report
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum ReportLimit {
+    /// Show at most this many tasks
+    Count(usize),
+    /// Show as many tasks as fit in the terminal
+    Page,
+}
+
+/// A shell for which completions can be generated
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// The format of an export, as given by the export subcommand's `--format` option
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum ExportFormat {
+    /// TaskWarrior's JSON export format
+    Json,
+    /// iCalendar (RFC 5545), with a VTODO for each task
+    Ical,
+    /// todo.txt, with one task per line
+    TodoTxt,
+    /// Emacs org-mode, with a heading for each task
+    Org,
+}
+
+/// The format of an import, as given by the import subcommand's `--format` option
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum ImportFormat {
+    /// Detect TaskWarrior JSON, or otherwise treat each line as the arguments to `ta add`
+    Auto,
+    /// todo.txt, with one task per line
+    TodoTxt,
+}
+
 /// A subcommand is the specific operation that the CLI should execute.
 #[derive(Debug, PartialEq)]
 pub(crate) enum Subcommand {
@@ -23,6 +89,8 @@ pub(crate) enum Subcommand {
     Help {
         /// Give the summary help (fitting on a few lines)
         summary: bool,
+        /// The subcommand or report for which to give detailed help, if any
+        topic: Option<String>,
     },
 
     /// Manipulate configuration
@@ -30,9 +98,16 @@ pub(crate) enum Subcommand {
         config_operation: ConfigOperation,
     },
 
+    /// Define, show, or set the active context
+    Context {
+        context_operation: ContextOperation,
+    },
+
     /// Add a new task
     Add {
         modification: Modification,
+        /// Read task descriptions from stdin, one per line
+        stdin: bool,
     },
 
     /// Modify existing tasks
@@ -41,13 +116,29 @@ pub(crate) enum Subcommand {
         modification: Modification,
     },
 
+    /// Duplicate existing tasks, modifying the copies
+    Duplicate {
+        filter: Filter,
+        modification: Modification,
+    },
+
+    /// Interactively pick a task, optionally running a command on it
+    Pick {
+        filter: Filter,
+        /// The arguments of the command to run on the picked task, if any
+        command: Vec<String>,
+    },
+
     /// Lists (reports)
     Report {
-        /// The name of the report to show
-        report_name: String,
+        /// The name of the report to show, or None for the default report
+        report_name: Option<String>,
 
         /// Additional filter terms beyond those in the report
         filter: Filter,
+
+        /// Command-line overrides of the report's columns and sort order
+        options: ReportOptions,
     },
 
     /// Per-task information (typically one task)
@@ -56,30 +147,137 @@ pub(crate) enum Subcommand {
         debug: bool,
     },
 
+    /// Edit a task in an external editor
+    Edit {
+        filter: Filter,
+    },
+
+    /// Export tasks as JSON or iCalendar
+    Export {
+        filter: Filter,
+        format: ExportFormat,
+    },
+
+    /// Count of matching tasks
+    Count {
+        filter: Filter,
+    },
+
+    /// Working-set ids or UUIDs of matching tasks
+    Ids {
+        filter: Filter,
+        /// Show UUIDs instead of working-set ids
+        uuids: bool,
+    },
+
+    /// Summary statistics
+    Stats {
+        filter: Filter,
+    },
+
+    /// Chart of pending and completed tasks over time
+    Burndown {
+        filter: Filter,
+    },
+
+    /// Calendar of due dates
+    Calendar {
+        filter: Filter,
+    },
+
+    /// Tasks due or scheduled soon
+    Reminders {
+        /// The window in which to look for reminders, instead of the configured window
+        within: Option<Duration>,
+        /// Run the reminder command for each reminder
+        notify: bool,
+    },
+
+    /// Show diagnostic information
+    Show,
+
+    /// Briefly describe the active task, for use in a shell prompt
+    Prompt,
+
     /// Basic operations without args
     Gc,
-    Sync,
+    Projects,
+    Tags,
+
+    /// Rename a tag on all tasks
+    RenameTag {
+        old: Tag,
+        new: Tag,
+    },
+
+    /// Rename a project, and its sub-projects, on all tasks
+    RenameProject {
+        old: String,
+        new: String,
+    },
+
+    Sync {
+        /// Interactively configure synchronization before synchronizing
+        init: bool,
+    },
+    Import {
+        /// The file to import from, or None for stdin
+        path: Option<String>,
+        format: ImportFormat,
+    },
     ImportTW,
     ImportTDB2 {
         path: String,
     },
-    Undo,
+    Undo {
+        /// The number of undo points to revert
+        count: usize,
+    },
+
+    /// Generate a shell completion script
+    Completions {
+        shell: Shell,
+    },
 }
 
 impl Subcommand {
     pub(super) fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        // nom's `alt` accepts a limited number of alternatives, so these are grouped
         all_consuming(alt((
-            Version::parse,
-            Help::parse,
-            Config::parse,
-            Add::parse,
-            Modify::parse,
-            Info::parse,
-            Gc::parse,
-            Sync::parse,
-            ImportTW::parse,
-            ImportTDB2::parse,
-            Undo::parse,
+            alt((
+                Version::parse,
+                Help::parse,
+                Config::parse,
+                Context::parse,
+                Add::parse,
+                Modify::parse,
+                Duplicate::parse,
+                Info::parse,
+                Edit::parse,
+                Export::parse,
+                Count::parse,
+                Ids::parse,
+                Stats::parse,
+                Show::parse,
+                Prompt::parse,
+            )),
+            alt((
+                Burndown::parse,
+                Calendar::parse,
+                Reminders::parse,
+                Gc::parse,
+                Projects::parse,
+                Tags::parse,
+                RenameTag::parse,
+                RenameProject::parse,
+                Sync::parse,
+                Import::parse,
+                ImportTW::parse,
+                ImportTDB2::parse,
+                Undo::parse,
+                Completions::parse,
+                Pick::parse,
+            )),
             // This must come last since it accepts arbitrary report names
             Report::parse,
         )))(input)
@@ -89,14 +287,33 @@ impl Subcommand {
         Version::get_usage(u);
         Help::get_usage(u);
         Config::get_usage(u);
+        Context::get_usage(u);
         Add::get_usage(u);
         Modify::get_usage(u);
+        Duplicate::get_usage(u);
         Info::get_usage(u);
+        Edit::get_usage(u);
+        Export::get_usage(u);
+        Count::get_usage(u);
+        Ids::get_usage(u);
+        Stats::get_usage(u);
+        Show::get_usage(u);
+        Prompt::get_usage(u);
+        Burndown::get_usage(u);
+        Calendar::get_usage(u);
+        Reminders::get_usage(u);
         Gc::get_usage(u);
+        Projects::get_usage(u);
+        Tags::get_usage(u);
+        RenameTag::get_usage(u);
+        RenameProject::get_usage(u);
         Sync::get_usage(u);
+        Import::get_usage(u);
         ImportTW::get_usage(u);
         ImportTDB2::get_usage(u);
         Undo::get_usage(u);
+        Completions::get_usage(u);
+        Pick::get_usage(u);
         Report::get_usage(u);
     }
 }
@@ -123,6 +340,7 @@ impl Version {
             syntax: "version",
             summary: "Show the TaskChampion version",
             description: "Show the version of the TaskChampion binary",
+            examples: &[],
         });
     }
 }
@@ -131,22 +349,54 @@ struct Help;
 
 impl Help {
     fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        /// Recognizes the name of the subcommand or report for which to give help
+        fn topic(input: &str) -> IResult<&str, &str> {
+            verify(any, |s: &str| !s.is_empty() && !s.starts_with('-'))(input)
+        }
         fn to_subcommand(input: &str) -> Result<Subcommand, ()> {
             Ok(Subcommand::Help {
                 summary: input == "-h",
+                topic: None,
             })
         }
-        map_res(
-            alt((
-                arg_matching(literal("help")),
-                arg_matching(literal("--help")),
-                arg_matching(literal("-h")),
-            )),
-            to_subcommand,
-        )(input)
+        fn to_topic_subcommand(input: &str) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Help {
+                summary: false,
+                topic: Some(input.to_owned()),
+            })
+        }
+        let help_flag = || alt((arg_matching(literal("--help")), arg_matching(literal("-h"))));
+        alt((
+            // `help <topic>`
+            map_res(
+                preceded(arg_matching(literal("help")), arg_matching(topic)),
+                to_topic_subcommand,
+            ),
+            // `<topic> --help`
+            map_res(
+                terminated(arg_matching(topic), help_flag()),
+                to_topic_subcommand,
+            ),
+            map_res(
+                alt((arg_matching(literal("help")), help_flag())),
+                to_subcommand,
+            ),
+        ))(input)
     }
 
-    fn get_usage(_u: &mut usage::Usage) {}
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "help",
+            syntax: "help [<subcommand>]",
+            summary: "Show help",
+            description: "
+                Show help for all subcommands or, given the name of a subcommand or report,
+                detailed help for that subcommand, including the filters and modifications it
+                accepts.  `ta <subcommand> --help` is equivalent to `ta help <subcommand>`, and
+                `ta -h` shows a brief summary of all subcommands.",
+            examples: &["help", "help modify", "done --help"],
+        });
+    }
 }
 
 struct Config;
@@ -169,17 +419,46 @@ impl Config {
     }
 }
 
+struct Context;
+
+impl Context {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (&str, ContextOperation)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Context {
+                context_operation: input.1,
+            })
+        }
+        map_res(
+            pair(arg_matching(literal("context")), ContextOperation::parse),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        ContextOperation::get_usage(u);
+    }
+}
+
 struct Add;
 
 impl Add {
     fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
-        fn to_subcommand(input: (&str, Modification)) -> Result<Subcommand, ()> {
+        fn to_subcommand(input: (&str, Option<&str>, Modification)) -> Result<Subcommand, ()> {
+            let mut modification = input.2;
+            if input.0 == "log" {
+                modification.status = Some(Status::Completed);
+            }
             Ok(Subcommand::Add {
-                modification: input.1,
+                modification,
+                stdin: input.1.is_some(),
             })
         }
         map_res(
-            pair(arg_matching(literal("add")), Modification::parse),
+            tuple((
+                alt((arg_matching(literal("add")), arg_matching(literal("log")))),
+                opt(arg_matching(literal("--stdin"))),
+                Modification::parse,
+            )),
             to_subcommand,
         )(input)
     }
@@ -192,6 +471,31 @@ impl Add {
             description: "
                 Add a new, pending task to the list of tasks.  The modification must include a
                 description.",
+            examples: &[
+                "add buy milk +shop due:tomorrow",
+                "add fix the bike project:home priority:H",
+            ],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "add --stdin",
+            syntax: "add --stdin [modification]",
+            summary: "Add new tasks from stdin",
+            description: "
+                Add a new, pending task for each non-empty line of stdin, using the line as the
+                task's description.  The modification is applied to each new task, and must not
+                include a description.  All lines are read before any tasks are added, and the
+                tasks are added as a single change that can be reverted with `ta undo`.",
+            examples: &["add --stdin +inbox < ideas.txt"],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "log",
+            syntax: "log [modification]",
+            summary: "Record an already-completed task",
+            description: "
+                Add a new task that is already completed, with its end time set to now.  This is
+                useful for recording work that was done without being tracked as a task.  The
+                modification must include a description.",
+            examples: &["log mowed the lawn project:home"],
         });
     }
 }
@@ -226,6 +530,13 @@ impl Modify {
                         modification.annotate = Some(s);
                     }
                 }
+                "denotate" => {
+                    // what would be parsed as a description is, here, used as the pattern
+                    if let DescriptionMod::Set(s) = modification.description {
+                        modification.description = DescriptionMod::None;
+                        modification.denotate = Some(s);
+                    }
+                }
                 _ => {}
             }
 
@@ -246,6 +557,7 @@ impl Modify {
                     arg_matching(literal("done")),
                     arg_matching(literal("delete")),
                     arg_matching(literal("annotate")),
+                    arg_matching(literal("denotate")),
                 )),
                 Modification::parse,
             )),
@@ -260,6 +572,7 @@ impl Modify {
             summary: "Modify tasks",
             description: "
                 Modify all tasks matching the required filter.",
+            examples: &["3 modify due:friday", "+inbox modify -inbox project:work"],
         });
         u.subcommands.push(usage::Subcommand {
             name: "prepend",
@@ -268,6 +581,7 @@ impl Modify {
             description: "
                 Modify all tasks matching the required filter by inserting the given description before each
                 task's description.",
+            examples: &["3 prepend urgently:"],
         });
         u.subcommands.push(usage::Subcommand {
             name: "append",
@@ -276,13 +590,15 @@ impl Modify {
             description: "
                 Modify all tasks matching the required filter by adding the given description to the end
                 of each task's description.",
+            examples: &["3 append before the meeting"],
         });
         u.subcommands.push(usage::Subcommand {
             name: "start",
             syntax: "<filter> start [modification]",
             summary: "Start tasks",
             description: "
-                Start all tasks matching the required filter, additionally applying any given modifications."
+                Start all tasks matching the required filter, additionally applying any given modifications.",
+            examples: &["3 start"],
         });
         u.subcommands.push(usage::Subcommand {
             name: "stop",
@@ -290,6 +606,7 @@ impl Modify {
             summary: "Stop tasks",
             description: "
                 Stop all tasks matching the required filter, additionally applying any given modifications.",
+            examples: &["3 stop"],
         });
         u.subcommands.push(usage::Subcommand {
             name: "done",
@@ -298,6 +615,7 @@ impl Modify {
             description: "
                 Mark all tasks matching the required filter as completed, additionally applying any given
                 modifications.",
+            examples: &["3 done", "project:home +garden done"],
         });
         u.subcommands.push(usage::Subcommand {
             name: "delete",
@@ -307,6 +625,7 @@ impl Modify {
                 Mark all tasks matching the required filter as deleted, additionally applying any given
                 modifications.  Deleted tasks remain until they are expired in a 'ta gc' operation at
                 least six months after their last modification.",
+            examples: &["3 delete"],
         });
         u.subcommands.push(usage::Subcommand {
             name: "annotate",
@@ -314,6 +633,89 @@ impl Modify {
             summary: "Annotate a task",
             description: "
                 Add an annotation to all tasks matching the required filter.",
+            examples: &["3 annotate called the plumber, waiting for a quote"],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "denotate",
+            syntax: "<filter> denotate [modification]",
+            summary: "Remove annotations from a task",
+            description: "
+                Remove all annotations containing the given text from all tasks matching the
+                required filter.",
+            examples: &["3 denotate plumber"],
+        });
+    }
+}
+
+struct Duplicate;
+
+impl Duplicate {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (Filter, &str, Modification)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Duplicate {
+                filter: input.0,
+                modification: input.2,
+            })
+        }
+        map_res(
+            tuple((
+                Filter::parse1,
+                arg_matching(literal("duplicate")),
+                Modification::parse,
+            )),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "duplicate",
+            syntax: "<filter> duplicate [modification]",
+            summary: "Duplicate tasks",
+            description: "
+                Create a copy of each task matching the required filter, then apply the
+                modification to the copy.  The copy is a new pending task, with the same
+                description, tags, annotations, and other properties as the original.",
+            examples: &["3 duplicate due:nextweek"],
+        });
+    }
+}
+
+struct Pick;
+
+impl Pick {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (Filter, &str, Vec<&str>)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Pick {
+                filter: input.0,
+                command: input.2.iter().map(|s| s.to_string()).collect(),
+            })
+        }
+        map_res(
+            tuple((
+                Filter::parse0,
+                arg_matching(literal("pick")),
+                many0(arg_matching(any)),
+            )),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "pick",
+            syntax: "[filter] pick [command]",
+            summary: "Pick a task interactively",
+            description: "
+                Show the tasks matching the filter, or all pending tasks if no filter is given,
+                and select one by typing part of its description, project, or tags.  Use the
+                arrow keys to move between matching tasks, Enter to pick a task, and Escape to
+                cancel.
+
+                With no command, the UUID of the picked task is printed.  Otherwise, the command
+                is run on the picked task, so `ta +inbox pick done` marks the picked task as
+                completed, and `ta pick modify +next` adds a tag to it.",
+            examples: &["+work pick", "pick done"],
         });
     }
 }
@@ -322,39 +724,164 @@ struct Report;
 
 impl Report {
     fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
-        fn to_subcommand(filter: Filter, report_name: &str) -> Result<Subcommand, ()> {
+        fn to_subcommand(
+            filter: Filter,
+            report_name: Option<&str>,
+            options: ReportOptions,
+        ) -> Result<Subcommand, ()> {
             Ok(Subcommand::Report {
                 filter,
-                report_name: report_name.to_owned(),
+                report_name: report_name.map(|n| n.to_owned()),
+                options,
             })
         }
-        // allow the filter expression before or after the report name
+        // allow the filter expression before or after the report name, with options before or
+        // after the filter
         alt((
-            map_res(pair(arg_matching(report_name), Filter::parse0), |input| {
-                to_subcommand(input.1, input.0)
-            }),
-            map_res(pair(Filter::parse0, arg_matching(report_name)), |input| {
-                to_subcommand(input.0, input.1)
-            }),
-            // default to a "next" report
-            map_res(Filter::parse0, |input| to_subcommand(input, "next")),
+            map_res(
+                tuple((
+                    arg_matching(report_name),
+                    Self::parse_options,
+                    Filter::parse0,
+                    Self::parse_options,
+                )),
+                |input| to_subcommand(input.2, Some(input.0), input.1.merge(input.3)),
+            ),
+            map_res(
+                tuple((
+                    Self::parse_options,
+                    Filter::parse0,
+                    arg_matching(report_name),
+                    Self::parse_options,
+                )),
+                |input| to_subcommand(input.1, Some(input.2), input.0.merge(input.3)),
+            ),
+            // default to the `default_report` setting
+            map_res(
+                tuple((Self::parse_options, Filter::parse0, Self::parse_options)),
+                |input| to_subcommand(input.1, None, input.0.merge(input.2)),
+            ),
         ))(input)
     }
 
+    /// Parse any `--columns` and `--sort` options, each taking a comma-separated list, and any
+    /// `limit:` arguments
+    fn parse_options(input: ArgList) -> IResult<ArgList, ReportOptions> {
+        fn to_list(input: &str) -> Result<Vec<String>, ()> {
+            Ok(input.split(',').map(|s| s.to_owned()).collect())
+        }
+        fn to_columns(input: (&str, Vec<String>)) -> Result<ReportOptions, ()> {
+            Ok(ReportOptions {
+                columns: Some(input.1),
+                ..Default::default()
+            })
+        }
+        fn to_sort(input: (&str, Vec<String>)) -> Result<ReportOptions, ()> {
+            Ok(ReportOptions {
+                sort: Some(input.1),
+                ..Default::default()
+            })
+        }
+        fn to_limit(input: Option<usize>) -> Result<ReportOptions, ()> {
+            Ok(ReportOptions {
+                limit: Some(match input {
+                    Some(n) => ReportLimit::Count(n),
+                    None => ReportLimit::Page,
+                }),
+                ..Default::default()
+            })
+        }
+        fold_many0(
+            alt((
+                map_res(
+                    pair(
+                        arg_matching(literal("--columns")),
+                        map_res(arg_matching(any), to_list),
+                    ),
+                    to_columns,
+                ),
+                map_res(
+                    pair(
+                        arg_matching(literal("--sort")),
+                        map_res(arg_matching(any), to_list),
+                    ),
+                    to_sort,
+                ),
+                map_res(arg_matching(limit_colon), to_limit),
+            )),
+            ReportOptions::default(),
+            ReportOptions::merge,
+        )(input)
+    }
+
     fn get_usage(u: &mut usage::Usage) {
         u.subcommands.push(usage::Subcommand {
             name: "report",
-            syntax: "[filter] [report-name] *or* [report-name] [filter]",
+            syntax: "[filter] [report-name] *or* [report-name] [filter], then [--columns <columns>] [--sort <sort>] [limit:<n>]",
             summary: "Show a report",
             description: "
-                Show the named report, including only tasks matching the filter",
+                Show the named report, including only tasks matching the filter.  With no report
+                name, the report given by the `default_report` setting is shown, which is `next`
+                unless configured otherwise.
+
+                The `--columns` option takes a comma-separated list of properties to display
+                instead of the report's configured columns, such as `id,description,urgency`.
+                The `--sort` option similarly replaces the report's sort order, with each
+                property optionally followed by `+` for ascending or `-` for descending order,
+                such as `urgency-,due+`.
+
+                The `limit:<n>` argument shows only the first n tasks, after sorting, instead of
+                the report's configured limit, so `next limit:5` shows the five most urgent
+                tasks.  With `limit:page`, only as many tasks as fit in the terminal are shown.",
+            examples: &["list", "project:home next --sort due+", "+work list --columns id,description limit:5"],
         });
         u.subcommands.push(usage::Subcommand {
             name: "next",
             syntax: "[filter]",
             summary: "Show the 'next' report",
             description: "
-                Show the report named 'next', including only tasks matching the filter",
+                Show the report named 'next', including only tasks matching the filter.  This
+                shows the pending tasks that are not waiting or blocked, most urgent first, as
+                many as fit in the terminal.",
+            examples: &["next", "+work next"],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "active",
+            syntax: "[filter]",
+            summary: "Show the 'active' report",
+            description: "
+                Show the report named 'active', listing started tasks with the time since each
+                was started, including only tasks matching the filter",
+            examples: &["active"],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "waiting",
+            syntax: "[filter]",
+            summary: "Show the 'waiting' report",
+            description: "
+                Show the report named 'waiting', listing tasks that are waiting, those whose wait
+                date comes soonest first, including only tasks matching the filter.  Once its wait
+                date has passed, a task no longer appears in this report, and appears in the
+                'next' report instead.",
+            examples: &["waiting", "+work waiting"],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "blocked",
+            syntax: "[filter]",
+            summary: "Show the 'blocked' report",
+            description: "
+                Show the report named 'blocked', listing pending tasks that depend on other pending
+                tasks, including only tasks matching the filter",
+            examples: &["blocked"],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "blocking",
+            syntax: "[filter]",
+            summary: "Show the 'blocking' report",
+            description: "
+                Show the report named 'blocking', listing pending tasks on which other pending
+                tasks depend, including only tasks matching the filter",
+            examples: &["blocking"],
         });
     }
 }
@@ -387,562 +914,1799 @@ impl Info {
             name: "info",
             syntax: "[filter] info",
             summary: "Show tasks",
-            description: " Show information about all tasks matching the fiter.",
+            description: "
+                Show information about all tasks matching the fiter, including their
+                annotations, dependencies, a breakdown of their urgency, and whether they have
+                changes that have not yet been synchronized.",
+            examples: &["3 info", "3 info --format json"],
         });
         u.subcommands.push(usage::Subcommand {
             name: "debug",
             syntax: "[filter] debug",
             summary: "Show task debug details",
             description: " Show all key/value properties of the tasks matching the fiter.",
+            examples: &["3 debug"],
         });
     }
 }
 
-struct Gc;
+struct Edit;
 
-impl Gc {
+impl Edit {
     fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
-        fn to_subcommand(_: &str) -> Result<Subcommand, ()> {
-            Ok(Subcommand::Gc)
+        fn to_subcommand(input: (Filter, &str)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Edit { filter: input.0 })
         }
-        map_res(arg_matching(literal("gc")), to_subcommand)(input)
+        map_res(
+            pair(Filter::parse1, arg_matching(literal("edit"))),
+            to_subcommand,
+        )(input)
     }
 
     fn get_usage(u: &mut usage::Usage) {
         u.subcommands.push(usage::Subcommand {
-            name: "gc",
-            syntax: "gc",
-            summary: "Perform 'garbage collection'",
+            name: "edit",
+            syntax: "<filter> edit",
+            summary: "Edit tasks in a text editor",
             description: "
-                Perform 'garbage collection'.  This refreshes the list of pending tasks
-                and their short id's.",
+                Open each task matching the required filter in a text editor, given by the
+                `VISUAL` or `EDITOR` environment variables.  The task's description, project,
+                priority, tags, dates, annotations, and UDAs can be edited, and any changes are
+                applied to the task when the editor exits.",
+            examples: &["3 edit"],
         });
     }
 }
 
-struct Sync;
+struct Export;
 
-impl Sync {
+impl Export {
     fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
-        fn to_subcommand(_: &str) -> Result<Subcommand, ()> {
-            Ok(Subcommand::Sync)
+        fn to_subcommand(input: (Filter, &str, Option<&str>)) -> Result<Subcommand, ()> {
+            let format = match input.2 {
+                Some("ical") => ExportFormat::Ical,
+                Some("todotxt") => ExportFormat::TodoTxt,
+                Some("org") => ExportFormat::Org,
+                _ => ExportFormat::Json,
+            };
+            Ok(Subcommand::Export {
+                filter: input.0,
+                format,
+            })
         }
-        map_res(arg_matching(literal("sync")), to_subcommand)(input)
+        map_res(
+            tuple((
+                Filter::parse0,
+                arg_matching(literal("export")),
+                opt(preceded(
+                    arg_matching(literal("--format")),
+                    alt((
+                        arg_matching(literal("json")),
+                        arg_matching(literal("ical")),
+                        arg_matching(literal("todotxt")),
+                        arg_matching(literal("org")),
+                    )),
+                )),
+            )),
+            to_subcommand,
+        )(input)
     }
 
     fn get_usage(u: &mut usage::Usage) {
         u.subcommands.push(usage::Subcommand {
-            name: "sync",
-            syntax: "sync",
-            summary: "Synchronize this replica",
+            name: "export",
+            syntax: "[filter] export [--format <json|ical|todotxt|org>]",
+            summary: "Export tasks as JSON, iCalendar, todo.txt, or org-mode",
             description: "
-                Synchronize this replica locally or against a remote server, as configured.
-
-                Synchronization is a critical part of maintaining the task database, and should
-                be done regularly, even if only locally.  It is typically run in a crontask.",
-        })
+                Write all tasks matching the filter to stdout in TaskWarrior's JSON export format.
+                With no filter, all tasks are exported, including completed and deleted tasks.
+
+                With `--format ical`, the tasks are written as an iCalendar file instead, with a
+                VTODO for each task giving its due date, priority, status, and tags, so that the
+                tasks can be displayed in calendar applications.
+
+                With `--format todotxt`, the tasks are written in the todo.txt format, one per
+                line, with their priority, project, tags (as contexts), and due date.
+
+                With `--format org`, the tasks are written as Emacs org-mode headings, with a TODO,
+                DONE, or CANCELED keyword from the task's status, SCHEDULED and DEADLINE from its
+                scheduled and due dates, its tags, and a properties drawer giving its UUID and
+                project.  Dates are given in UTC.",
+            examples: &[
+                "project:home export > home.json",
+                "status:pending export --format ical > tasks.ics",
+                "status:pending export --format todotxt > todo.txt",
+                "status:pending export --format org > tasks.org",
+            ],
+        });
     }
 }
 
-struct ImportTW;
+struct Count;
 
-impl ImportTW {
+impl Count {
     fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
-        fn to_subcommand(_: &str) -> Result<Subcommand, ()> {
-            Ok(Subcommand::ImportTW)
+        fn to_subcommand(input: (Filter, &str)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Count { filter: input.0 })
         }
-        map_res(arg_matching(literal("import-tw")), to_subcommand)(input)
+        map_res(
+            pair(Filter::parse0, arg_matching(literal("count"))),
+            to_subcommand,
+        )(input)
     }
 
     fn get_usage(u: &mut usage::Usage) {
         u.subcommands.push(usage::Subcommand {
-            name: "import-tw",
-            syntax: "import-tw",
-            summary: "Import tasks from TaskWarrior export",
+            name: "count",
+            syntax: "[filter] count",
+            summary: "Count tasks",
             description: "
-                Import tasks into this replica.
-
-                The tasks must be provided in the TaskWarrior JSON format on stdin.  If tasks
-                in the import already exist, they are 'merged'.
-
-                Because TaskChampion lacks the information about the types of UDAs that is stored
-                in the TaskWarrior configuration, UDA values are imported as simple strings, in the
-                format they appear in the JSON export.  This may cause undesirable results.
-                ",
-        })
+                Show the number of tasks matching the filter.  With no filter, all tasks are
+                counted, including completed and deleted tasks.",
+            examples: &["+work count"],
+        });
     }
 }
 
-struct ImportTDB2;
+struct Ids;
 
-impl ImportTDB2 {
+impl Ids {
     fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
-        fn to_subcommand(input: (&str, &str)) -> Result<Subcommand, ()> {
-            Ok(Subcommand::ImportTDB2 {
-                path: input.1.into(),
-            })
+        fn to_subcommand(input: (Filter, &str)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Ids {
+                filter: input.0,
+                uuids: input.1 == "uuids",
+            })
         }
         map_res(
-            pair(arg_matching(literal("import-tdb2")), arg_matching(any)),
+            pair(
+                Filter::parse0,
+                alt((arg_matching(literal("ids")), arg_matching(literal("uuids")))),
+            ),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "ids",
+            syntax: "[filter] ids",
+            summary: "Show task ids",
+            description: "
+                Show the working-set ids of the tasks matching the filter, one per line, for use
+                in scripts.  Tasks that are not in the working set, such as completed tasks, are
+                omitted.",
+            examples: &["+inbox ids"],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "uuids",
+            syntax: "[filter] uuids",
+            summary: "Show task UUIDs",
+            description: "
+                Show the UUIDs of the tasks matching the filter, one per line, for use in
+                scripts.",
+            examples: &["+inbox uuids"],
+        });
+    }
+}
+
+struct Stats;
+
+impl Stats {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (Filter, &str)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Stats { filter: input.0 })
+        }
+        map_res(
+            pair(Filter::parse0, arg_matching(literal("stats"))),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "stats",
+            syntax: "[filter] stats",
+            summary: "Show task statistics",
+            description: "
+                Show statistics about the tasks matching the filter: the number of tasks with
+                each status, the average age of pending tasks, and the number of tasks added and
+                completed in each of the last few weeks.",
+            examples: &["stats", "project:work stats"],
+        });
+    }
+}
+
+struct Show;
+
+impl Show {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(_: &str) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Show)
+        }
+        map_res(
+            alt((
+                arg_matching(literal("show")),
+                arg_matching(literal("diagnostics")),
+            )),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "show",
+            syntax: "show",
+            summary: "Show diagnostic information",
+            description: "
+                Show the version, the base directories for configuration and data (following
+                the XDG base directory specification where the platform does), whether the
+                configuration file was moved from the location used by earlier versions, the
+                configuration file and data directory in use, the synchronization server, counts
+                of tasks, the number of operations not yet synchronized, and the time of the last
+                synchronization, followed by each
+                configuration value and whether it comes from the configuration file.  This
+                information is useful when reporting a bug.
+
+                This subcommand is also available as `diagnostics`.",
+            examples: &[],
+        });
+    }
+}
+
+struct Prompt;
+
+impl Prompt {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(_: &str) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Prompt)
+        }
+        map_res(arg_matching(literal("_prompt")), to_subcommand)(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "_prompt",
+            syntax: "_prompt",
+            summary: "Describe the active task, for a shell prompt",
+            description: "
+                Print a short description of the most recently started task and the time since
+                it was started, such as `working on: write report (25min)`, suitable for
+                embedding in a shell prompt or a tmux status line.  If more than one task is
+                active, the number of others is included.  Nothing is printed if no task is
+                active.",
+            examples: &[],
+        });
+    }
+}
+
+struct Burndown;
+
+impl Burndown {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (Filter, &str)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Burndown { filter: input.0 })
+        }
+        map_res(
+            pair(Filter::parse0, arg_matching(literal("burndown"))),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "burndown",
+            syntax: "[filter] burndown",
+            summary: "Show a burndown chart",
+            description: "
+                Show a chart of the number of pending and completed tasks matching the filter at
+                the end of each of the last few weeks, based on the times the tasks were entered
+                and completed.",
+            examples: &["project:work burndown"],
+        });
+    }
+}
+
+struct Calendar;
+
+impl Calendar {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (Filter, &str)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Calendar { filter: input.0 })
+        }
+        map_res(
+            pair(Filter::parse0, arg_matching(literal("calendar"))),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "calendar",
+            syntax: "[filter] calendar",
+            summary: "Show a calendar of due dates",
+            description: "
+                Show a calendar of this month and next, with the number of pending tasks matching
+                the filter that are due on each day.  Today is highlighted, as are days with
+                overdue tasks.",
+            examples: &["calendar", "+work calendar"],
+        });
+    }
+}
+
+struct Reminders;
+
+impl Reminders {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (&str, Option<Duration>, Option<&str>)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Reminders {
+                within: input.1,
+                notify: input.2.is_some(),
+            })
+        }
+        map_res(
+            tuple((
+                arg_matching(literal("reminders")),
+                opt(preceded(
+                    arg_matching(literal("--within")),
+                    arg_matching(duration),
+                )),
+                opt(arg_matching(literal("--notify"))),
+            )),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "reminders",
+            syntax: "reminders [--within <duration>] [--notify]",
+            summary: "Show tasks due or scheduled soon",
+            description: "
+                Show the pending tasks that are due or scheduled between now and the end of the
+                `reminder_window` setting (one hour by default), or of the `--within` option.  Use
+                the global `--format` option for machine-readable output.
+
+                With `--notify`, the `reminder_command` setting (`notify-send` by default) is also
+                run for each reminder, with a title and the task's description as arguments, and
+                the environment variables TASK_ID, TASK_UUID, REMINDER_KIND (`due` or
+                `scheduled`), and REMINDER_TIME set.  Running this from cron or a systemd timer
+                as often as the window is long gives each reminder once.",
+            examples: &[
+                "reminders",
+                "reminders --within 1d",
+                "reminders --within 15min --notify",
+            ],
+        });
+    }
+}
+
+struct Gc;
+
+impl Gc {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(_: &str) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Gc)
+        }
+        map_res(arg_matching(literal("gc")), to_subcommand)(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "gc",
+            syntax: "gc",
+            summary: "Perform 'garbage collection'",
+            description: "
+                Perform 'garbage collection'.  This refreshes the list of pending tasks
+                and their short id's, expires old deleted tasks, deletes pending tasks whose
+                `until` time has passed, and removes dependencies on tasks that no longer
+                exist.",
+            examples: &[],
+        });
+    }
+}
+
+struct Projects;
+
+impl Projects {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(_: &str) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Projects)
+        }
+        map_res(arg_matching(literal("projects")), to_subcommand)(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "projects",
+            syntax: "projects",
+            summary: "Show a summary of projects",
+            description: "
+                Show all projects, arranged in a hierarchy, with the number of pending and
+                completed tasks in each and the percentage of tasks complete.  The counts for
+                a project include those of its sub-projects, such as `home.garden` for `home`.",
+            examples: &[],
+        });
+    }
+}
+
+struct Tags;
+
+impl Tags {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(_: &str) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Tags)
+        }
+        map_res(arg_matching(literal("tags")), to_subcommand)(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "tags",
+            syntax: "tags",
+            summary: "Show a summary of tags",
+            description: "
+                Show all tags in use by pending tasks, with the number of pending tasks having
+                each tag.  Synthetic tags, such as `BLOCKED`, are shown separately.",
+            examples: &[],
+        });
+    }
+}
+
+struct RenameTag;
+
+impl RenameTag {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (&str, &str, Tag, Tag)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::RenameTag {
+                old: input.2,
+                new: input.3,
+            })
+        }
+        map_res(
+            tuple((
+                arg_matching(literal("tag")),
+                arg_matching(literal("rename")),
+                arg_matching(tag),
+                arg_matching(tag),
+            )),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "tag rename",
+            syntax: "tag rename <old> <new>",
+            summary: "Rename a tag",
+            description: "
+                Replace a tag with another on every task having it, including completed and
+                deleted tasks.  The changes can be reverted with a single `undo`.",
+            examples: &["tag rename hom home"],
+        });
+    }
+}
+
+struct RenameProject;
+
+impl RenameProject {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (&str, &str, &str, &str)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::RenameProject {
+                old: input.2.to_owned(),
+                new: input.3.to_owned(),
+            })
+        }
+        map_res(
+            tuple((
+                arg_matching(literal("project")),
+                arg_matching(literal("rename")),
+                arg_matching(any),
+                arg_matching(any),
+            )),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "project rename",
+            syntax: "project rename <old> <new>",
+            summary: "Rename a project",
+            description: "
+                Move every task in a project to a new project, including completed and deleted
+                tasks.  Sub-projects are renamed as well, so renaming `home` to `house` moves
+                tasks in `home.garden` to `house.garden`.  The changes can be reverted with a
+                single `undo`.",
+            examples: &["project rename home house"],
+        });
+    }
+}
+
+struct Sync;
+
+impl Sync {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (&str, Option<&str>)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Sync {
+                init: input.1.is_some(),
+            })
+        }
+        map_res(
+            tuple((
+                arg_matching(literal("sync")),
+                opt(arg_matching(literal("--init"))),
+            )),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "sync",
+            syntax: "sync",
+            summary: "Synchronize this replica",
+            description: "
+                Synchronize this replica locally or against a remote server, as configured.
+
+                Synchronization is a critical part of maintaining the task database, and should
+                be done regularly, even if only locally.  It is typically run in a crontask.",
+            examples: &[],
+        });
+        u.subcommands.push(usage::Subcommand {
+            name: "sync --init",
+            syntax: "sync --init",
+            summary: "Set up synchronization",
+            description: "
+                Interactively configure synchronization, then synchronize for the first time.
+                This asks whether to synchronize with a local directory or a TaskChampion sync
+                server and, for a server, for its URL, the client key, and the encryption secret,
+                generating the latter two if they are not given.  The server is contacted to
+                verify the configuration before it is written to the configuration file.
+
+                To synchronize an additional replica with an existing server, give the same
+                client key and encryption secret as the first replica.",
+            examples: &["sync --init"],
+        })
+    }
+}
+
+struct Import;
+
+impl Import {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (&str, Option<&str>, Option<&str>)) -> Result<Subcommand, ()> {
+            let format = match input.1 {
+                Some("todotxt") => ImportFormat::TodoTxt,
+                _ => ImportFormat::Auto,
+            };
+            Ok(Subcommand::Import {
+                path: input.2.map(|p| p.to_owned()),
+                format,
+            })
+        }
+        map_res(
+            tuple((
+                arg_matching(literal("import")),
+                opt(preceded(
+                    arg_matching(literal("--format")),
+                    alt((
+                        arg_matching(literal("auto")),
+                        arg_matching(literal("todotxt")),
+                    )),
+                )),
+                opt(arg_matching(any)),
+            )),
             to_subcommand,
         )(input)
     }
 
-    fn get_usage(u: &mut usage::Usage) {
-        u.subcommands.push(usage::Subcommand {
-            name: "import-tdb2",
-            syntax: "import-tdb2 <directory>",
-            summary: "Import tasks from the TaskWarrior data directory",
-            description: "
-                Import tasks into this replica from a TaskWarrior data directory.  If tasks in the
-                import already exist, they are 'merged'.  This mode of import supports UDAs better
-                than the `import` subcommand, but requires access to the \"raw\" TaskWarrior data.
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "import",
+            syntax: "import [--format <auto|todotxt>] [file]",
+            summary: "Import tasks",
+            description: "
+                Import tasks from the given file, or from stdin if no file is given.
+
+                The input may be in the TaskWarrior JSON format, such as that produced by `ta
+                export`, either as a JSON array or with one JSON object per line.  Tasks are
+                created or updated based on their UUID.
+
+                Otherwise, each non-empty line of the input that does not begin with `#` is added
+                as a new task, interpreted in the same way as the arguments to `ta add`, such as
+                `buy milk +shopping due:tomorrow`.
+
+                With `--format todotxt`, each non-empty line of the input is added as a new task in
+                the todo.txt format, such as `(A) call the plumber +home @phone due:2022-01-05`.
+
+                A summary of the tasks added, updated, and skipped is shown on completion.",
+            examples: &[
+                "import tasks.json",
+                "import < tasks.json",
+                "import --format todotxt todo.txt",
+            ],
+        })
+    }
+}
+
+struct ImportTW;
+
+impl ImportTW {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(_: &str) -> Result<Subcommand, ()> {
+            Ok(Subcommand::ImportTW)
+        }
+        map_res(arg_matching(literal("import-tw")), to_subcommand)(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "import-tw",
+            syntax: "import-tw",
+            summary: "Import tasks from TaskWarrior export",
+            description: "
+                Import tasks into this replica.
+
+                The tasks must be provided in the TaskWarrior JSON format on stdin.  If tasks
+                in the import already exist, they are 'merged'.
+
+                Because TaskChampion lacks the information about the types of UDAs that is stored
+                in the TaskWarrior configuration, UDA values are imported as simple strings, in the
+                format they appear in the JSON export.  This may cause undesirable results.
+                ",
+            examples: &["import-tw < taskwarrior-export.json"],
+        })
+    }
+}
+
+struct ImportTDB2;
+
+impl ImportTDB2 {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (&str, &str)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::ImportTDB2 {
+                path: input.1.into(),
+            })
+        }
+        map_res(
+            pair(arg_matching(literal("import-tdb2")), arg_matching(any)),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "import-tdb2",
+            syntax: "import-tdb2 <directory>",
+            summary: "Import tasks from the TaskWarrior data directory",
+            description: "
+                Import tasks into this replica from a TaskWarrior data directory.  If tasks in the
+                import already exist, they are 'merged'.  This mode of import supports UDAs better
+                than the `import` subcommand, but requires access to the \"raw\" TaskWarrior data.
+
+                This command supports task directories written by TaskWarrior-2.6.1 or later.
+                ",
+            examples: &["import-tdb2 ~/.task"],
+        })
+    }
+}
+
+struct Undo;
+
+impl Undo {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (&str, Option<usize>)) -> Result<Subcommand, ()> {
+            Ok(Subcommand::Undo {
+                count: input.1.unwrap_or(1),
+            })
+        }
+        map_res(
+            pair(
+                arg_matching(literal("undo")),
+                opt(preceded(
+                    arg_matching(literal("--count")),
+                    arg_matching(positive_integer),
+                )),
+            ),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "undo",
+            syntax: "undo [--count N]",
+            summary: "Undo the latest change made on this replica",
+            description: "
+                Undo the latest change made on this replica, or with `--count`, the latest N
+                changes.  The changes to be reverted are shown, and must be confirmed before they
+                are undone.
+
+                Changes cannot be undone once they have been synchronized.",
+            examples: &["undo", "undo --count 3"],
+        })
+    }
+}
+
+struct Completions;
+
+impl Completions {
+    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
+        fn to_subcommand(input: (&str, &str)) -> Result<Subcommand, ()> {
+            let shell = match input.1 {
+                "bash" => Shell::Bash,
+                "zsh" => Shell::Zsh,
+                _ => Shell::Fish,
+            };
+            Ok(Subcommand::Completions { shell })
+        }
+        map_res(
+            pair(
+                arg_matching(literal("completions")),
+                alt((
+                    arg_matching(literal("bash")),
+                    arg_matching(literal("zsh")),
+                    arg_matching(literal("fish")),
+                )),
+            ),
+            to_subcommand,
+        )(input)
+    }
+
+    fn get_usage(u: &mut usage::Usage) {
+        u.subcommands.push(usage::Subcommand {
+            name: "completions",
+            syntax: "completions <bash|zsh|fish>",
+            summary: "Generate a shell completion script",
+            description: "
+                Write a completion script for the given shell to stdout.  The script completes
+                subcommands, report names, configuration keys, and the tags and projects in use
+                when it was generated, so it should be regenerated occasionally.  For example, with
+                bash, add `source <(ta completions bash)` to `~/.bashrc`.",
+            examples: &["completions bash > ~/.local/share/bash-completion/completions/ta"],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::argparse::Condition;
+    use pretty_assertions::assert_eq;
+
+    const EMPTY: Vec<&str> = vec![];
+
+    #[test]
+    fn test_version() {
+        assert_eq!(
+            Subcommand::parse(argv!["version"]).unwrap(),
+            (&EMPTY[..], Subcommand::Version)
+        );
+    }
+
+    #[test]
+    fn test_dd_version() {
+        assert_eq!(
+            Subcommand::parse(argv!["--version"]).unwrap(),
+            (&EMPTY[..], Subcommand::Version)
+        );
+    }
+
+    #[test]
+    fn test_d_h() {
+        assert_eq!(
+            Subcommand::parse(argv!["-h"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Help {
+                    summary: true,
+                    topic: None
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_help() {
+        assert_eq!(
+            Subcommand::parse(argv!["help"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Help {
+                    summary: false,
+                    topic: None
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_dd_help() {
+        assert_eq!(
+            Subcommand::parse(argv!["--help"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Help {
+                    summary: false,
+                    topic: None
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_help_topic() {
+        let help_modify = || Subcommand::Help {
+            summary: false,
+            topic: Some(s!("modify")),
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["help", "modify"]).unwrap(),
+            (&EMPTY[..], help_modify())
+        );
+        assert_eq!(
+            Subcommand::parse(argv!["modify", "--help"]).unwrap(),
+            (&EMPTY[..], help_modify())
+        );
+        assert_eq!(
+            Subcommand::parse(argv!["modify", "-h"]).unwrap(),
+            (&EMPTY[..], help_modify())
+        );
+    }
+
+    #[test]
+    fn test_config_set() {
+        assert_eq!(
+            Subcommand::parse(argv!["config", "set", "x", "y"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Config {
+                    config_operation: ConfigOperation::Set("x".to_owned(), "y".to_owned())
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_config_get_list() {
+        assert_eq!(
+            Subcommand::parse(argv!["config", "get", "x"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Config {
+                    config_operation: ConfigOperation::Get("x".to_owned())
+                }
+            )
+        );
+        assert_eq!(
+            Subcommand::parse(argv!["config", "list"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Config {
+                    config_operation: ConfigOperation::List
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_config_check() {
+        assert_eq!(
+            Subcommand::parse(argv!["config", "check"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Config {
+                    config_operation: ConfigOperation::Check
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_add_description() {
+        let subcommand = Subcommand::Add {
+            modification: Modification {
+                description: DescriptionMod::Set(s!("foo")),
+                ..Default::default()
+            },
+            stdin: false,
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["add", "foo"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_log() {
+        let subcommand = Subcommand::Add {
+            modification: Modification {
+                description: DescriptionMod::Set(s!("foo")),
+                status: Some(Status::Completed),
+                add_tags: set![tag!("work")],
+                ..Default::default()
+            },
+            stdin: false,
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["log", "foo", "+work"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_add_stdin() {
+        let subcommand = Subcommand::Add {
+            modification: Modification {
+                add_tags: set![tag!("inbox")],
+                ..Default::default()
+            },
+            stdin: true,
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["add", "--stdin", "+inbox"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_add_description_multi() {
+        let subcommand = Subcommand::Add {
+            modification: Modification {
+                description: DescriptionMod::Set(s!("foo bar")),
+                ..Default::default()
+            },
+            stdin: false,
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["add", "foo", "bar"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_modify_description_multi() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                description: DescriptionMod::Set(s!("foo bar")),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "modify", "foo", "bar"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_append() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                description: DescriptionMod::Append(s!("foo bar")),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "append", "foo", "bar"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_prepend() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                description: DescriptionMod::Prepend(s!("foo bar")),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "prepend", "foo", "bar"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_done() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                status: Some(Status::Completed),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "done"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_done_modify() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                description: DescriptionMod::Set(s!("now-finished")),
+                status: Some(Status::Completed),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "done", "now-finished"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_start() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                active: Some(true),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "start"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_start_modify() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                active: Some(true),
+                description: DescriptionMod::Set(s!("mod")),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "start", "mod"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_stop() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                active: Some(false),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "stop"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_stop_modify() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                description: DescriptionMod::Set(s!("mod")),
+                active: Some(false),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "stop", "mod"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_delete() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                status: Some(Status::Deleted),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "delete"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_annotate() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                annotate: Some("sent invoice".into()),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "annotate", "sent", "invoice"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
 
-                This command supports task directories written by TaskWarrior-2.6.1 or later.
-                ",
-        })
+    #[test]
+    fn test_denotate() {
+        let subcommand = Subcommand::Modify {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
+            },
+            modification: Modification {
+                denotate: Some("invoice".into()),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["123", "denotate", "invoice"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
     }
-}
 
-struct Undo;
+    #[test]
+    fn test_report() {
+        let subcommand = Subcommand::Report {
+            filter: Default::default(),
+            report_name: Some("myreport".to_owned()),
+            options: Default::default(),
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["myreport"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
 
-impl Undo {
-    fn parse(input: ArgList) -> IResult<ArgList, Subcommand> {
-        fn to_subcommand(_: &str) -> Result<Subcommand, ()> {
-            Ok(Subcommand::Undo)
-        }
-        map_res(arg_matching(literal("undo")), to_subcommand)(input)
+    #[test]
+    fn test_report_filter_before() {
+        let subcommand = Subcommand::Report {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![
+                    TaskId::WorkingSetId(12),
+                    TaskId::WorkingSetId(13),
+                ])],
+            },
+            report_name: Some("foo".to_owned()),
+            options: Default::default(),
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["12,13", "foo"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
     }
 
-    fn get_usage(u: &mut usage::Usage) {
-        u.subcommands.push(usage::Subcommand {
-            name: "undo",
-            syntax: "undo",
-            summary: "Undo the latest change made on this replica",
-            description: "
-                Undo the latest change made on this replica.
+    #[test]
+    fn test_report_filter_after() {
+        let subcommand = Subcommand::Report {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![
+                    TaskId::WorkingSetId(12),
+                    TaskId::WorkingSetId(13),
+                ])],
+            },
+            report_name: Some("foo".to_owned()),
+            options: Default::default(),
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["foo", "12,13"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
 
-                Changes cannot be undone once they have been synchronized.",
-        })
+    #[test]
+    fn test_report_filter_next() {
+        let subcommand = Subcommand::Report {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![
+                    TaskId::WorkingSetId(12),
+                    TaskId::WorkingSetId(13),
+                ])],
+            },
+            report_name: None,
+            options: Default::default(),
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["12,13"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::argparse::Condition;
-    use pretty_assertions::assert_eq;
+    #[test]
+    fn test_report_next() {
+        let subcommand = Subcommand::Report {
+            filter: Filter {
+                ..Default::default()
+            },
+            report_name: None,
+            options: Default::default(),
+        };
+        assert_eq!(
+            Subcommand::parse(argv![]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
 
-    const EMPTY: Vec<&str> = vec![];
+    #[test]
+    fn test_report_options() {
+        let subcommand = Subcommand::Report {
+            filter: Filter {
+                conditions: vec![Condition::HasTag(tag!("work"))],
+            },
+            report_name: Some("list".to_owned()),
+            options: ReportOptions {
+                columns: Some(vec![s!("id"), s!("description"), s!("urgency")]),
+                sort: Some(vec![s!("urgency-"), s!("due+")]),
+                limit: None,
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv![
+                "list",
+                "+work",
+                "--sort",
+                "urgency-,due+",
+                "--columns",
+                "id,description,urgency"
+            ])
+            .unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
 
     #[test]
-    fn test_version() {
+    fn test_report_options_next() {
+        let subcommand = Subcommand::Report {
+            filter: Default::default(),
+            report_name: None,
+            options: ReportOptions {
+                columns: None,
+                sort: Some(vec![s!("due")]),
+                limit: None,
+            },
+        };
         assert_eq!(
-            Subcommand::parse(argv!["version"]).unwrap(),
-            (&EMPTY[..], Subcommand::Version)
+            Subcommand::parse(argv!["--sort", "due"]).unwrap(),
+            (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_dd_version() {
+    fn test_limit_after_report() {
+        let subcommand = Subcommand::Report {
+            filter: Default::default(),
+            report_name: Some("next".to_owned()),
+            options: ReportOptions {
+                limit: Some(ReportLimit::Count(5)),
+                ..Default::default()
+            },
+        };
         assert_eq!(
-            Subcommand::parse(argv!["--version"]).unwrap(),
-            (&EMPTY[..], Subcommand::Version)
+            Subcommand::parse(argv!["next", "limit:5"]).unwrap(),
+            (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_d_h() {
+    fn test_limit_before_filter() {
+        let subcommand = Subcommand::Report {
+            filter: Filter {
+                conditions: vec![Condition::HasTag(tag!("work"))],
+            },
+            report_name: Some("list".to_owned()),
+            options: ReportOptions {
+                columns: None,
+                sort: Some(vec![s!("due")]),
+                limit: Some(ReportLimit::Page),
+            },
+        };
         assert_eq!(
-            Subcommand::parse(argv!["-h"]).unwrap(),
-            (&EMPTY[..], Subcommand::Help { summary: true })
+            Subcommand::parse(argv!["limit:page", "+work", "list", "--sort", "due"]).unwrap(),
+            (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_help() {
+    fn test_limit_default_report() {
+        let subcommand = Subcommand::Report {
+            filter: Filter {
+                conditions: vec![Condition::HasTag(tag!("work"))],
+            },
+            report_name: None,
+            options: ReportOptions {
+                limit: Some(ReportLimit::Count(3)),
+                ..Default::default()
+            },
+        };
         assert_eq!(
-            Subcommand::parse(argv!["help"]).unwrap(),
-            (&EMPTY[..], Subcommand::Help { summary: false })
+            Subcommand::parse(argv!["+work", "limit:3"]).unwrap(),
+            (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_dd_help() {
+    fn test_info_filter() {
+        let subcommand = Subcommand::Info {
+            debug: false,
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![
+                    TaskId::WorkingSetId(12),
+                    TaskId::WorkingSetId(13),
+                ])],
+            },
+        };
         assert_eq!(
-            Subcommand::parse(argv!["--help"]).unwrap(),
-            (&EMPTY[..], Subcommand::Help { summary: false })
+            Subcommand::parse(argv!["12,13", "info"]).unwrap(),
+            (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_config_set() {
+    fn test_debug_filter() {
+        let subcommand = Subcommand::Info {
+            debug: true,
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(12)])],
+            },
+        };
         assert_eq!(
-            Subcommand::parse(argv!["config", "set", "x", "y"]).unwrap(),
-            (
-                &EMPTY[..],
-                Subcommand::Config {
-                    config_operation: ConfigOperation::Set("x".to_owned(), "y".to_owned())
-                }
-            )
+            Subcommand::parse(argv!["12", "debug"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_edit() {
+        let subcommand = Subcommand::Edit {
+            filter: Filter {
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(12)])],
+            },
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["12", "edit"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let subcommand = Subcommand::Export {
+            filter: Default::default(),
+            format: ExportFormat::Json,
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["export"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_export_filter() {
+        let subcommand = Subcommand::Export {
+            filter: Filter {
+                conditions: vec![Condition::HasTag(tag!("work"))],
+            },
+            format: ExportFormat::Json,
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["+work", "export"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
+
+    #[test]
+    fn test_export_format() {
+        let subcommand = Subcommand::Export {
+            filter: Default::default(),
+            format: ExportFormat::Ical,
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["export", "--format", "ical"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+        let subcommand = Subcommand::Export {
+            filter: Default::default(),
+            format: ExportFormat::Json,
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["export", "--format", "json"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+        let subcommand = Subcommand::Export {
+            filter: Default::default(),
+            format: ExportFormat::TodoTxt,
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["export", "--format", "todotxt"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+        let subcommand = Subcommand::Export {
+            filter: Default::default(),
+            format: ExportFormat::Org,
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["export", "--format", "org"]).unwrap(),
+            (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_add_description() {
-        let subcommand = Subcommand::Add {
-            modification: Modification {
-                description: DescriptionMod::Set(s!("foo")),
-                ..Default::default()
+    fn test_stats() {
+        let subcommand = Subcommand::Stats {
+            filter: Filter {
+                conditions: vec![Condition::HasTag(tag!("work"))],
             },
         };
         assert_eq!(
-            Subcommand::parse(argv!["add", "foo"]).unwrap(),
+            Subcommand::parse(argv!["+work", "stats"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_add_description_multi() {
-        let subcommand = Subcommand::Add {
-            modification: Modification {
-                description: DescriptionMod::Set(s!("foo bar")),
-                ..Default::default()
-            },
+    fn test_burndown() {
+        let subcommand = Subcommand::Burndown {
+            filter: Default::default(),
         };
         assert_eq!(
-            Subcommand::parse(argv!["add", "foo", "bar"]).unwrap(),
+            Subcommand::parse(argv!["burndown"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_modify_description_multi() {
-        let subcommand = Subcommand::Modify {
+    fn test_calendar() {
+        let subcommand = Subcommand::Calendar {
             filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
-            },
-            modification: Modification {
-                description: DescriptionMod::Set(s!("foo bar")),
-                ..Default::default()
+                conditions: vec![Condition::Project(Some(s!("work")))],
             },
         };
         assert_eq!(
-            Subcommand::parse(argv!["123", "modify", "foo", "bar"]).unwrap(),
+            Subcommand::parse(argv!["project:work", "calendar"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_append() {
-        let subcommand = Subcommand::Modify {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
-            },
-            modification: Modification {
-                description: DescriptionMod::Append(s!("foo bar")),
-                ..Default::default()
-            },
+    fn test_reminders() {
+        let subcommand = Subcommand::Reminders {
+            within: None,
+            notify: false,
         };
         assert_eq!(
-            Subcommand::parse(argv!["123", "append", "foo", "bar"]).unwrap(),
+            Subcommand::parse(argv!["reminders"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_prepend() {
-        let subcommand = Subcommand::Modify {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
-            },
-            modification: Modification {
-                description: DescriptionMod::Prepend(s!("foo bar")),
-                ..Default::default()
-            },
+    fn test_reminders_options() {
+        let subcommand = Subcommand::Reminders {
+            within: Some(Duration::minutes(15)),
+            notify: true,
         };
         assert_eq!(
-            Subcommand::parse(argv!["123", "prepend", "foo", "bar"]).unwrap(),
+            Subcommand::parse(argv!["reminders", "--within", "15min", "--notify"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
+        assert!(Subcommand::parse(argv!["reminders", "--within", "soon"]).is_err());
     }
 
     #[test]
-    fn test_done() {
-        let subcommand = Subcommand::Modify {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
-            },
-            modification: Modification {
-                status: Some(Status::Completed),
-                ..Default::default()
-            },
+    fn test_import() {
+        let subcommand = Subcommand::Import {
+            path: None,
+            format: ImportFormat::Auto,
         };
         assert_eq!(
-            Subcommand::parse(argv!["123", "done"]).unwrap(),
+            Subcommand::parse(argv!["import"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_done_modify() {
-        let subcommand = Subcommand::Modify {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
-            },
-            modification: Modification {
-                description: DescriptionMod::Set(s!("now-finished")),
-                status: Some(Status::Completed),
-                ..Default::default()
-            },
+    fn test_import_path() {
+        let subcommand = Subcommand::Import {
+            path: Some(s!("tasks.json")),
+            format: ImportFormat::Auto,
         };
         assert_eq!(
-            Subcommand::parse(argv!["123", "done", "now-finished"]).unwrap(),
+            Subcommand::parse(argv!["import", "tasks.json"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_start() {
-        let subcommand = Subcommand::Modify {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
-            },
-            modification: Modification {
-                active: Some(true),
-                ..Default::default()
-            },
+    fn test_import_format() {
+        let subcommand = Subcommand::Import {
+            path: Some(s!("todo.txt")),
+            format: ImportFormat::TodoTxt,
         };
         assert_eq!(
-            Subcommand::parse(argv!["123", "start"]).unwrap(),
+            Subcommand::parse(argv!["import", "--format", "todotxt", "todo.txt"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_start_modify() {
-        let subcommand = Subcommand::Modify {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
-            },
-            modification: Modification {
-                active: Some(true),
-                description: DescriptionMod::Set(s!("mod")),
-                ..Default::default()
-            },
-        };
+    fn test_gc() {
+        let subcommand = Subcommand::Gc;
         assert_eq!(
-            Subcommand::parse(argv!["123", "start", "mod"]).unwrap(),
+            Subcommand::parse(argv!["gc"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_stop() {
-        let subcommand = Subcommand::Modify {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
-            },
-            modification: Modification {
-                active: Some(false),
-                ..Default::default()
-            },
-        };
+    fn test_gc_extra_args() {
+        assert!(Subcommand::parse(argv!["gc", "foo"]).is_err());
+    }
+
+    #[test]
+    fn test_projects() {
+        let subcommand = Subcommand::Projects;
         assert_eq!(
-            Subcommand::parse(argv!["123", "stop"]).unwrap(),
+            Subcommand::parse(argv!["projects"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_stop_modify() {
-        let subcommand = Subcommand::Modify {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
-            },
-            modification: Modification {
-                description: DescriptionMod::Set(s!("mod")),
-                active: Some(false),
-                ..Default::default()
-            },
-        };
+    fn test_tags() {
+        let subcommand = Subcommand::Tags;
         assert_eq!(
-            Subcommand::parse(argv!["123", "stop", "mod"]).unwrap(),
+            Subcommand::parse(argv!["tags"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_delete() {
-        let subcommand = Subcommand::Modify {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
-            },
-            modification: Modification {
-                status: Some(Status::Deleted),
-                ..Default::default()
-            },
+    fn test_rename_tag() {
+        let subcommand = Subcommand::RenameTag {
+            old: tag!("hom"),
+            new: tag!("home"),
         };
         assert_eq!(
-            Subcommand::parse(argv!["123", "delete"]).unwrap(),
+            Subcommand::parse(argv!["tag", "rename", "+hom", "home"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
+        assert!(Subcommand::parse(argv!["tag", "rename", "hom"]).is_err());
+        assert!(Subcommand::parse(argv!["tag", "rename", "hom", "BLOCKED"]).is_ok());
     }
 
     #[test]
-    fn test_annotate() {
-        let subcommand = Subcommand::Modify {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(123)])],
-            },
-            modification: Modification {
-                annotate: Some("sent invoice".into()),
-                ..Default::default()
-            },
+    fn test_rename_project() {
+        let subcommand = Subcommand::RenameProject {
+            old: s!("home"),
+            new: s!("house.main"),
         };
         assert_eq!(
-            Subcommand::parse(argv!["123", "annotate", "sent", "invoice"]).unwrap(),
+            Subcommand::parse(argv!["project", "rename", "home", "house.main"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_report() {
-        let subcommand = Subcommand::Report {
-            filter: Default::default(),
-            report_name: "myreport".to_owned(),
-        };
+    fn test_sync() {
+        let subcommand = Subcommand::Sync { init: false };
         assert_eq!(
-            Subcommand::parse(argv!["myreport"]).unwrap(),
+            Subcommand::parse(argv!["sync"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_report_filter_before() {
-        let subcommand = Subcommand::Report {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![
-                    TaskId::WorkingSetId(12),
-                    TaskId::WorkingSetId(13),
-                ])],
-            },
-            report_name: "foo".to_owned(),
-        };
+    fn test_sync_init() {
+        let subcommand = Subcommand::Sync { init: true };
         assert_eq!(
-            Subcommand::parse(argv!["12,13", "foo"]).unwrap(),
+            Subcommand::parse(argv!["sync", "--init"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_report_filter_after() {
-        let subcommand = Subcommand::Report {
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![
-                    TaskId::WorkingSetId(12),
-                    TaskId::WorkingSetId(13),
-                ])],
-            },
-            report_name: "foo".to_owned(),
-        };
+    fn test_completions() {
+        let subcommand = Subcommand::Completions { shell: Shell::Zsh };
         assert_eq!(
-            Subcommand::parse(argv!["foo", "12,13"]).unwrap(),
+            Subcommand::parse(argv!["completions", "zsh"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
+        assert!(Subcommand::parse(argv!["completions", "tcsh"]).is_err());
     }
 
     #[test]
-    fn test_report_filter_next() {
-        let subcommand = Subcommand::Report {
+    fn test_duplicate() {
+        let subcommand = Subcommand::Duplicate {
             filter: Filter {
-                conditions: vec![Condition::IdList(vec![
-                    TaskId::WorkingSetId(12),
-                    TaskId::WorkingSetId(13),
-                ])],
+                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(3)])],
+            },
+            modification: Modification {
+                add_tags: set![tag!("copy")],
+                ..Default::default()
             },
-            report_name: "next".to_owned(),
         };
         assert_eq!(
-            Subcommand::parse(argv!["12,13"]).unwrap(),
+            Subcommand::parse(argv!["3", "duplicate", "+copy"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_report_next() {
-        let subcommand = Subcommand::Report {
+    fn test_pick() {
+        let subcommand = Subcommand::Pick {
             filter: Filter {
-                ..Default::default()
+                conditions: vec![Condition::HasTag(tag!("inbox"))],
             },
-            report_name: "next".to_owned(),
+            command: vec![s!("modify"), s!("+next")],
         };
         assert_eq!(
-            Subcommand::parse(argv![]).unwrap(),
+            Subcommand::parse(argv!["+inbox", "pick", "modify", "+next"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_info_filter() {
-        let subcommand = Subcommand::Info {
-            debug: false,
-            filter: Filter {
-                conditions: vec![Condition::IdList(vec![
-                    TaskId::WorkingSetId(12),
-                    TaskId::WorkingSetId(13),
-                ])],
-            },
+    fn test_pick_no_command() {
+        let subcommand = Subcommand::Pick {
+            filter: Default::default(),
+            command: vec![],
         };
         assert_eq!(
-            Subcommand::parse(argv!["12,13", "info"]).unwrap(),
+            Subcommand::parse(argv!["pick"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_debug_filter() {
-        let subcommand = Subcommand::Info {
-            debug: true,
+    fn test_prompt() {
+        assert_eq!(
+            Subcommand::parse(argv!["_prompt"]).unwrap(),
+            (&EMPTY[..], Subcommand::Prompt)
+        );
+    }
+
+    #[test]
+    fn test_show() {
+        assert_eq!(
+            Subcommand::parse(argv!["show"]).unwrap(),
+            (&EMPTY[..], Subcommand::Show)
+        );
+        assert_eq!(
+            Subcommand::parse(argv!["diagnostics"]).unwrap(),
+            (&EMPTY[..], Subcommand::Show)
+        );
+    }
+
+    #[test]
+    fn test_count() {
+        let subcommand = Subcommand::Count {
             filter: Filter {
-                conditions: vec![Condition::IdList(vec![TaskId::WorkingSetId(12)])],
+                conditions: vec![Condition::HasTag(tag!("work"))],
             },
         };
         assert_eq!(
-            Subcommand::parse(argv!["12", "debug"]).unwrap(),
+            Subcommand::parse(argv!["+work", "count"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
-    fn test_gc() {
-        let subcommand = Subcommand::Gc;
+    fn test_ids() {
         assert_eq!(
-            Subcommand::parse(argv!["gc"]).unwrap(),
-            (&EMPTY[..], subcommand)
+            Subcommand::parse(argv!["ids"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Ids {
+                    filter: Filter::default(),
+                    uuids: false,
+                }
+            )
+        );
+        assert_eq!(
+            Subcommand::parse(argv!["+work", "uuids"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Ids {
+                    filter: Filter {
+                        conditions: vec![Condition::HasTag(tag!("work"))],
+                    },
+                    uuids: true,
+                }
+            )
         );
     }
 
     #[test]
-    fn test_gc_extra_args() {
-        assert!(Subcommand::parse(argv!["gc", "foo"]).is_err());
+    fn test_context() {
+        assert_eq!(
+            Subcommand::parse(argv!["context"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Context {
+                    context_operation: ContextOperation::Show
+                }
+            )
+        );
+        assert_eq!(
+            Subcommand::parse(argv!["context", "work"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Context {
+                    context_operation: ContextOperation::Set(s!("work"))
+                }
+            )
+        );
+        assert_eq!(
+            Subcommand::parse(argv!["context", "none"]).unwrap(),
+            (
+                &EMPTY[..],
+                Subcommand::Context {
+                    context_operation: ContextOperation::None
+                }
+            )
+        );
     }
 
     #[test]
-    fn test_sync() {
-        let subcommand = Subcommand::Sync;
+    fn test_context_define() {
+        let subcommand = Subcommand::Context {
+            context_operation: ContextOperation::Define(s!("work"), s!("project:acme or +work")),
+        };
         assert_eq!(
-            Subcommand::parse(argv!["sync"]).unwrap(),
+            Subcommand::parse(argv!["context", "define", "work", "project:acme or +work"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+        let subcommand = Subcommand::Context {
+            context_operation: ContextOperation::Define(s!("work"), s!("+work -home")),
+        };
+        assert_eq!(
+            Subcommand::parse(argv!["context", "define", "work", "+work", "-home"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
 
     #[test]
     fn test_undo() {
-        let subcommand = Subcommand::Undo;
+        let subcommand = Subcommand::Undo { count: 1 };
         assert_eq!(
             Subcommand::parse(argv!["undo"]).unwrap(),
             (&EMPTY[..], subcommand)
         );
     }
+
+    #[test]
+    fn test_undo_count() {
+        let subcommand = Subcommand::Undo { count: 3 };
+        assert_eq!(
+            Subcommand::parse(argv!["undo", "--count", "3"]).unwrap(),
+            (&EMPTY[..], subcommand)
+        );
+    }
 }
@@ -0,0 +1,211 @@
+//! Suggestions for mistyped subcommands, report names, and attributes, for use in error messages.
+
+use crate::usage::Usage;
+
+/// The attributes that can appear before a colon in a filter or modification, such as
+/// `project:home` or `due.before:eow`
+const ATTRIBUTES: &[&str] = &[
+    "annotation",
+    "depends",
+    "due",
+    "end",
+    "entry",
+    "limit",
+    "link",
+    "modified",
+    "priority",
+    "project",
+    "recur",
+    "scheduled",
+    "status",
+    "until",
+    "wait",
+];
+
+/// Calculate the number of single-character insertions, deletions, substitutions, or
+/// transpositions of adjacent characters needed to change `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // d[i][j] is the distance between the first i characters of a and the first j of b
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Find the candidate closest to `word`, if any is close enough that `word` is likely a typo for
+/// it.  Of equally-close candidates, the first is returned.
+pub(crate) fn did_you_mean<'a, I>(word: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let max_distance = 1 + word.chars().count() / 4;
+    candidates
+        .into_iter()
+        .filter(|c| *c != word)
+        .map(|c| (edit_distance(word, c), c))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| c)
+}
+
+/// Get the names of all subcommands, such as `modify` or `config`
+pub(crate) fn subcommand_names() -> Vec<&'static str> {
+    let mut names = vec!["help"];
+    for subcommand in Usage::new().subcommands {
+        if let Some(name) = subcommand.name.split_whitespace().next() {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// Split an argument of the form `<attribute>:<value>` or `<attribute>.<modifier>:<value>`,
+/// returning the attribute.
+fn attribute(arg: &str) -> Option<&str> {
+    let (prefix, _) = arg.split_at(arg.find(':')?);
+    let attribute = prefix.split('.').next().unwrap_or(prefix);
+    if !attribute.is_empty() && attribute.chars().all(|c| c.is_ascii_alphabetic()) {
+        Some(attribute)
+    } else {
+        None
+    }
+}
+
+/// Explain why a command line could not be parsed, given the arguments up to and including the
+/// one at which parsing failed (without the command name).  Where a mistyped attribute or
+/// subcommand can be identified, this includes a suggestion for what was meant.
+pub(super) fn explain_unrecognized(args: &[&str], failed_at: Option<&str>) -> String {
+    let subcommands = subcommand_names();
+
+    for arg in args.iter().chain(failed_at.iter()) {
+        if let Some(attr) = attribute(arg) {
+            if !ATTRIBUTES.contains(&attr) {
+                return match did_you_mean(attr, ATTRIBUTES.iter().copied()) {
+                    Some(suggestion) => format!(
+                        "unknown attribute `{}`, did you mean `{}`?",
+                        attr, suggestion
+                    ),
+                    None => format!(
+                        "unknown attribute `{}`; valid attributes are {}",
+                        attr,
+                        ATTRIBUTES.join(", ")
+                    ),
+                };
+            }
+        } else if !arg.starts_with('-')
+            && arg.chars().all(|c| c.is_ascii_alphabetic() || c == '-')
+            && !subcommands.contains(arg)
+        {
+            if let Some(suggestion) = did_you_mean(arg, subcommands.iter().copied()) {
+                return format!(
+                    "unknown subcommand `{}`, did you mean `{}`?",
+                    arg, suggestion
+                );
+            }
+        }
+    }
+
+    match failed_at {
+        Some(arg) => match attribute(arg) {
+            Some(attr) => format!("invalid value for `{}` in `{}`", attr, arg),
+            None => format!("unexpected argument `{}`", arg),
+        },
+        None => "incomplete command line".into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("list", "list"), 0);
+        assert_eq!(edit_distance("lsit", "list"), 1);
+        assert_eq!(edit_distance("modfy", "modify"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let candidates = vec!["list", "next", "blocked"];
+        assert_eq!(did_you_mean("lsit", candidates.clone()), Some("list"));
+        assert_eq!(did_you_mean("nxet", candidates.clone()), Some("next"));
+        assert_eq!(did_you_mean("blokd", candidates.clone()), Some("blocked"));
+        assert_eq!(did_you_mean("frobnicate", candidates.clone()), None);
+        assert_eq!(did_you_mean("list", candidates), None);
+    }
+
+    #[test]
+    fn test_subcommand_names() {
+        let names = subcommand_names();
+        assert!(names.contains(&"help"));
+        assert!(names.contains(&"modify"));
+        assert!(names.contains(&"config"));
+        assert!(!names.contains(&"config set"));
+    }
+
+    #[test]
+    fn test_explain_unknown_attribute() {
+        assert_eq!(
+            explain_unrecognized(&[], Some("porject:home")),
+            "unknown attribute `porject`, did you mean `project`?"
+        );
+        assert!(explain_unrecognized(&[], Some("xyzzy:home"))
+            .starts_with("unknown attribute `xyzzy`; valid attributes are annotation, depends,"));
+        assert_eq!(
+            explain_unrecognized(&["+work"], Some("shceduled.before:eow")),
+            "unknown attribute `shceduled`, did you mean `scheduled`?"
+        );
+    }
+
+    #[test]
+    fn test_explain_unknown_subcommand() {
+        assert_eq!(
+            explain_unrecognized(&["1", "modfy"], Some("foo")),
+            "unknown subcommand `modfy`, did you mean `modify`?"
+        );
+        assert_eq!(
+            explain_unrecognized(&[], Some("confg")),
+            "unknown subcommand `confg`, did you mean `config`?"
+        );
+    }
+
+    #[test]
+    fn test_explain_other() {
+        assert_eq!(
+            explain_unrecognized(&["+work"], Some("due:someday")),
+            "invalid value for `due` in `due:someday`"
+        );
+        assert_eq!(
+            explain_unrecognized(&["list"], Some("--frob")),
+            "unexpected argument `--frob`"
+        );
+        assert_eq!(
+            explain_unrecognized(&["config"], None),
+            "incomplete command line"
+        );
+    }
+}
@@ -4,7 +4,10 @@ pub fn main() {
     match taskchampion_cli::main() {
         Ok(_) => exit(0),
         Err(e) => {
-            eprintln!("{:?}", e);
+            // the absence of matching tasks is reported only with the exit status
+            if !e.is_no_matches() {
+                eprintln!("{:?}", e);
+            }
             exit(e.exit_status());
         }
     }
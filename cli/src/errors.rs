@@ -6,6 +6,11 @@ pub enum Error {
     #[error("Command-Line Syntax Error: {0}")]
     Arguments(String),
 
+    /// No tasks matched the filter of a subcommand that lists or counts tasks.  This is not
+    /// really an error, but is reported with a distinct exit status for use in scripts.
+    #[error("No matching tasks")]
+    NoMatches,
+
     #[error(transparent)]
     TaskChampion(#[from] TcError),
 
@@ -19,11 +24,17 @@ impl Error {
         Error::Arguments(msg.to_string())
     }
 
+    /// Determine whether this error indicates only that no tasks matched
+    pub fn is_no_matches(&self) -> bool {
+        matches!(self, Error::NoMatches)
+    }
+
     /// Determine the exit status for this error, as documented.
     pub fn exit_status(&self) -> i32 {
         match *self {
+            Error::NoMatches => 1,
             Error::Arguments(_) => 3,
-            _ => 1,
+            _ => 2,
         }
     }
 }
@@ -46,15 +57,18 @@ mod test {
         let mut err: Error;
 
         err = anyhow!("uhoh").into();
+        assert_eq!(err.exit_status(), 2);
+
+        err = Error::NoMatches;
         assert_eq!(err.exit_status(), 1);
 
         err = Error::Arguments("uhoh".to_string());
         assert_eq!(err.exit_status(), 3);
 
         err = std::io::Error::last_os_error().into();
-        assert_eq!(err.exit_status(), 1);
+        assert_eq!(err.exit_status(), 2);
 
         err = TcError::Database("uhoh".to_string()).into();
-        assert_eq!(err.exit_status(), 1);
+        assert_eq!(err.exit_status(), 2);
     }
 }
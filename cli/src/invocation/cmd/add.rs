@@ -1,24 +1,168 @@
 use crate::argparse::DescriptionMod;
+use crate::invocation::util::{confirm, summarize_task};
 use crate::invocation::{apply_modification, ResolvedModification};
-use taskchampion::{Replica, Status};
+use crate::settings::{DuplicateCheck, Settings};
+use std::io::Read;
+use taskchampion::{Replica, Status, StorageConfig};
 use termcolor::WriteColor;
 
+/// The similarity (see [`Replica::similar_tasks`]) at which an existing task is considered a
+/// possible duplicate of a new task
+const DUPLICATE_THRESHOLD: f64 = 0.8;
+
+/// The number of similar tasks to list when checking for duplicates
+const MAX_LISTED_TASKS: usize = 5;
+
 pub(in crate::invocation) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    settings: &Settings,
+    modification: ResolvedModification,
+    stdin: bool,
+    yes: bool,
+    dry_run: bool,
+) -> Result<(), crate::Error> {
+    let options = AddOptions {
+        duplicate_check: settings.duplicate_check,
+        // with --yes or without confirmations, similar tasks are only listed
+        prompt: !yes && settings.confirmation && !dry_run,
+        dry_run,
+    };
+    if stdin {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input)?;
+        return add_lines(w, replica, modification, &input, &options);
+    }
+    add_tasks(w, replica, modification, vec![None], &options)
+}
+
+/// Options controlling how tasks are added
+struct AddOptions {
+    duplicate_check: DuplicateCheck,
+    /// Whether the duplicate check may prompt for confirmation
+    prompt: bool,
+    dry_run: bool,
+}
+
+/// Add a task for each non-empty line of the input, using the line as its description
+fn add_lines<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    modification: ResolvedModification,
+    input: &str,
+    options: &AddOptions,
+) -> Result<(), crate::Error> {
+    if modification.0.description != DescriptionMod::None {
+        return Err(crate::Error::for_arguments(
+            "a description cannot be given with --stdin",
+        ));
+    }
+    let descriptions = input
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| Some(l.to_owned()))
+        .collect();
+    add_tasks(w, replica, modification, descriptions, options)
+}
+
+/// Check for pending tasks with descriptions similar to that of a new task, as configured by the
+/// `duplicate_check` setting, listing any that are found.  Returns false if the new task should
+/// not be added.
+fn check_duplicates<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    description: &str,
+    options: &AddOptions,
+) -> Result<bool, crate::Error> {
+    if options.duplicate_check == DuplicateCheck::Off {
+        return Ok(true);
+    }
+    let similar = replica.similar_tasks(description, DUPLICATE_THRESHOLD)?;
+    if similar.is_empty() {
+        return Ok(true);
+    }
+
+    writeln!(w, "Similar pending tasks exist:")?;
+    for task in similar.iter().take(MAX_LISTED_TASKS) {
+        writeln!(w, "  {}", summarize_task(replica, task)?)?;
+    }
+    if similar.len() > MAX_LISTED_TASKS {
+        writeln!(w, "  ..and {} more", similar.len() - MAX_LISTED_TASKS)?;
+    }
+
+    if options.duplicate_check == DuplicateCheck::Warn || !options.prompt {
+        return Ok(true);
+    }
+    if confirm(format!("Add task `{}` anyway?", description))? {
+        return Ok(true);
+    }
+    writeln!(w, "not adding task `{}`", description)?;
+    Ok(false)
+}
+
+/// Add a task for each description, applying the modification to each.  A description of None
+/// uses the description in the modification.
+fn add_tasks<W: WriteColor>(
     w: &mut W,
     replica: &mut Replica,
     mut modification: ResolvedModification,
+    descriptions: Vec<Option<String>>,
+    options: &AddOptions,
 ) -> Result<(), crate::Error> {
+    // for a dry run, add the tasks to a scratch replica instead
+    let mut scratch = if options.dry_run {
+        Some(Replica::new(StorageConfig::InMemory.into_storage()?))
+    } else {
+        None
+    };
+
     // extract the description from the modification to handle it specially
-    let description = match modification.0.description {
+    let default_description = match modification.0.description {
         DescriptionMod::Set(ref s) => s.clone(),
         _ => "(no description)".to_owned(),
     };
     modification.0.description = DescriptionMod::None;
 
-    let task = replica.new_task(Status::Pending, description).unwrap();
-    let mut task = task.into_mut(replica);
-    apply_modification(&mut task, &modification)?;
-    writeln!(w, "added task {}", task.get_uuid())?;
+    // only new pending tasks can duplicate existing pending tasks
+    let pending = matches!(modification.0.status, None | Some(Status::Pending));
+
+    for description in descriptions {
+        let description = description.unwrap_or_else(|| default_description.clone());
+        if pending && !check_duplicates(w, replica, &description, options)? {
+            continue;
+        }
+
+        let target = match scratch {
+            Some(ref mut scratch) => scratch,
+            None => &mut *replica,
+        };
+        let task = target.new_task(Status::Pending, description).unwrap();
+        let mut task = task.into_mut(target);
+        apply_modification(&mut task, &modification)?;
+
+        if options.dry_run {
+            writeln!(w, "would add task")?;
+            let task = task.into_immut();
+            let mut properties: Vec<_> = task
+                .get_taskmap()
+                .iter()
+                .filter(|(k, _)| *k != "modified")
+                .collect();
+            properties.sort();
+            for (k, v) in properties {
+                writeln!(w, "  {}: {:?}", k, v)?;
+            }
+        } else if task.get_status() == Status::Completed {
+            writeln!(w, "logged task {}", task.get_uuid())?;
+        } else {
+            writeln!(w, "added task {}", task.get_uuid())?;
+        }
+    }
+
+    if options.dry_run {
+        writeln!(w, "Dry run; no changes were made.")?;
+    }
     Ok(())
 }
 
@@ -29,6 +173,14 @@ mod test {
     use crate::invocation::test::*;
     use pretty_assertions::assert_eq;
 
+    fn options() -> AddOptions {
+        AddOptions {
+            duplicate_check: DuplicateCheck::Off,
+            prompt: false,
+            dry_run: false,
+        }
+    }
+
     #[test]
     fn test_add() {
         let mut w = test_writer();
@@ -37,7 +189,16 @@ mod test {
             description: DescriptionMod::Set(s!("my description")),
             ..Default::default()
         });
-        execute(&mut w, &mut replica, modification).unwrap();
+        execute(
+            &mut w,
+            &mut replica,
+            &Settings::default(),
+            modification,
+            false,
+            false,
+            false,
+        )
+        .unwrap();
 
         // check that the task appeared..
         let working_set = replica.working_set().unwrap();
@@ -60,7 +221,16 @@ mod test {
             add_tags: vec![tag!("tag1")].drain(..).collect(),
             ..Default::default()
         });
-        execute(&mut w, &mut replica, modification).unwrap();
+        execute(
+            &mut w,
+            &mut replica,
+            &Settings::default(),
+            modification,
+            false,
+            false,
+            false,
+        )
+        .unwrap();
 
         // check that the task appeared..
         let working_set = replica.working_set().unwrap();
@@ -74,4 +244,155 @@ mod test {
 
         assert_eq!(w.into_string(), format!("added task {}\n", task.get_uuid()));
     }
+
+    #[test]
+    fn test_add_dry_run() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let modification = ResolvedModification(Modification {
+            description: DescriptionMod::Set(s!("my description")),
+            add_tags: vec![tag!("tag1")].drain(..).collect(),
+            ..Default::default()
+        });
+        execute(
+            &mut w,
+            &mut replica,
+            &Settings::default(),
+            modification,
+            false,
+            false,
+            true,
+        )
+        .unwrap();
+
+        // no task was added..
+        assert_eq!(replica.all_task_uuids().unwrap(), vec![]);
+
+        // ..but its properties are shown
+        let output = w.into_string();
+        assert!(output.starts_with("would add task\n  description: \"my description\"\n"));
+        assert!(output.contains("  tag_tag1: \"\"\n"));
+    }
+
+    #[test]
+    fn test_log() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let modification = ResolvedModification(Modification {
+            description: DescriptionMod::Set(s!("did a thing")),
+            status: Some(Status::Completed),
+            ..Default::default()
+        });
+        execute(
+            &mut w,
+            &mut replica,
+            &Settings::default(),
+            modification,
+            false,
+            false,
+            false,
+        )
+        .unwrap();
+
+        let uuid = replica.all_task_uuids().unwrap()[0];
+        let task = replica.get_task(uuid).unwrap().unwrap();
+        assert_eq!(task.get_description(), "did a thing");
+        assert_eq!(task.get_status(), Status::Completed);
+        assert!(task.get_end().is_some());
+
+        assert_eq!(w.into_string(), format!("logged task {}\n", uuid));
+    }
+
+    #[test]
+    fn test_add_lines() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let modification = ResolvedModification(Modification {
+            add_tags: vec![tag!("inbox")].drain(..).collect(),
+            ..Default::default()
+        });
+        let input = "buy milk\n\n  call mom  \n";
+        add_lines(&mut w, &mut replica, modification, input, &options()).unwrap();
+
+        let working_set = replica.working_set().unwrap();
+        let mut descriptions = vec![];
+        for i in 1..=2 {
+            let task = replica
+                .get_task(working_set.by_index(i).unwrap())
+                .unwrap()
+                .unwrap();
+            assert!(task.has_tag(&tag!("inbox")));
+            descriptions.push(task.get_description().to_owned());
+        }
+        assert_eq!(descriptions, vec![s!("buy milk"), s!("call mom")]);
+        assert_eq!(w.into_string().lines().count(), 2);
+    }
+
+    #[test]
+    fn test_add_lines_with_description() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let modification = ResolvedModification(Modification {
+            description: DescriptionMod::Set(s!("oops")),
+            ..Default::default()
+        });
+        assert!(add_lines(&mut w, &mut replica, modification, "buy milk\n", &options()).is_err());
+        assert_eq!(replica.all_task_uuids().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_add_duplicate_warn() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("Buy milk")).unwrap();
+        let options = AddOptions {
+            duplicate_check: DuplicateCheck::Warn,
+            ..options()
+        };
+        let modification = ResolvedModification(Modification::default());
+        add_lines(
+            &mut w,
+            &mut replica,
+            modification,
+            "buy milk!\ncall mom\n",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(replica.all_task_uuids().unwrap().len(), 3);
+        let output = w.into_string();
+        assert!(output.starts_with("Similar pending tasks exist:\n  1 - Buy milk\nadded task "));
+        // "call mom" is not similar to any existing task
+        assert_eq!(output.matches("Similar").count(), 1);
+    }
+
+    #[test]
+    fn test_add_duplicate_off() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("Buy milk")).unwrap();
+        let modification = ResolvedModification(Modification::default());
+        add_lines(&mut w, &mut replica, modification, "buy milk\n", &options()).unwrap();
+
+        assert_eq!(replica.all_task_uuids().unwrap().len(), 2);
+        assert!(w.into_string().starts_with("added task "));
+    }
+
+    #[test]
+    fn test_log_not_checked() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("Buy milk")).unwrap();
+        let options = AddOptions {
+            duplicate_check: DuplicateCheck::Prompt,
+            prompt: true,
+            ..options()
+        };
+        let modification = ResolvedModification(Modification {
+            status: Some(Status::Completed),
+            ..Default::default()
+        });
+        add_lines(&mut w, &mut replica, modification, "buy milk\n", &options).unwrap();
+        assert!(w.into_string().starts_with("logged task "));
+    }
 }
@@ -0,0 +1,161 @@
+use crate::argparse::Filter;
+use crate::invocation::filtered_tasks;
+use taskchampion::chrono::{DateTime, Duration, Local, Utc};
+use taskchampion::{Replica, Status, Task};
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// The number of weeks of history to chart
+const WEEKS: i64 = 8;
+
+/// The maximum width of a bar in the chart
+const WIDTH: usize = 50;
+
+/// The state of the tasks at a point in time
+#[derive(Debug, PartialEq)]
+struct Point {
+    at: DateTime<Utc>,
+    pending: usize,
+    completed: usize,
+}
+
+/// Calculate the number of pending and completed tasks at the end of each of the last few weeks,
+/// based on the entry and end times of the tasks.
+fn burndown(tasks: &[Task], now: DateTime<Utc>) -> Vec<Point> {
+    (0..=WEEKS)
+        .rev()
+        .map(|i| {
+            let at = now - Duration::weeks(i);
+            let mut point = Point {
+                at,
+                pending: 0,
+                completed: 0,
+            };
+            for task in tasks {
+                match task.get_entry() {
+                    Some(entry) if entry <= at => {}
+                    _ => continue,
+                }
+                match task.get_end() {
+                    Some(end) if end <= at => {
+                        if task.get_status() == Status::Completed {
+                            point.completed += 1;
+                        }
+                    }
+                    // a task that has not yet ended (at this time) is pending
+                    _ => point.pending += 1,
+                }
+            }
+            point
+        })
+        .collect()
+}
+
+/// Scale the number of pending and completed tasks to bar lengths, given the largest total
+fn bar_lengths(point: &Point, max: usize) -> (usize, usize) {
+    if max <= WIDTH {
+        return (point.pending, point.completed);
+    }
+    let scale = |n: usize| (n * WIDTH + max / 2) / max;
+    (scale(point.pending), scale(point.completed))
+}
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+) -> Result<(), crate::Error> {
+    let tasks: Vec<Task> = filtered_tasks(replica, &filter)?.collect();
+    let points = burndown(&tasks, Utc::now());
+    let max = points
+        .iter()
+        .map(|p| p.pending + p.completed)
+        .max()
+        .unwrap_or(0);
+
+    for point in &points {
+        let (pending, completed) = bar_lengths(point, max);
+        write!(w, "{} ", point.at.with_timezone(&Local).format("%Y-%m-%d"))?;
+        w.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+        write!(w, "{}", "#".repeat(pending))?;
+        w.set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
+        write!(w, "{}", "+".repeat(completed))?;
+        w.reset()?;
+        writeln!(w, " {}/{}", point.pending, point.completed)?;
+    }
+    writeln!(w, "# pending, + completed")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_burndown() {
+        let mut replica = test_replica();
+        let now = Utc::now();
+
+        let t = replica.new_task(Status::Pending, s!("old")).unwrap();
+        let mut t = t.into_mut(&mut replica);
+        t.set_entry(Some(now - Duration::days(10))).unwrap();
+        t.done().unwrap();
+
+        let t = replica.new_task(Status::Pending, s!("older")).unwrap();
+        let mut t = t.into_mut(&mut replica);
+        t.set_entry(Some(now - Duration::days(20))).unwrap();
+
+        // deleted tasks count as pending until they are deleted
+        let t = replica.new_task(Status::Pending, s!("deleted")).unwrap();
+        let mut t = t.into_mut(&mut replica);
+        t.set_entry(Some(now - Duration::days(3))).unwrap();
+        t.delete().unwrap();
+
+        let tasks: Vec<_> = replica
+            .all_tasks()
+            .unwrap()
+            .into_iter()
+            .map(|(_, t)| t)
+            .collect();
+        let points: Vec<_> = burndown(&tasks, now)
+            .iter()
+            .map(|p| (p.pending, p.completed))
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                (0, 0),
+                (0, 0),
+                (0, 0),
+                (0, 0),
+                (0, 0),
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (1, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bar_lengths() {
+        let point = Point {
+            at: Utc::now(),
+            pending: 30,
+            completed: 70,
+        };
+        // scaled to fit in the chart
+        assert_eq!(bar_lengths(&point, 100), (15, 35));
+        assert_eq!(bar_lengths(&point, 200), (8, 18));
+
+        // small numbers are not scaled
+        let point = Point {
+            at: Utc::now(),
+            pending: 3,
+            completed: 7,
+        };
+        assert_eq!(bar_lengths(&point, 10), (3, 7));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::argparse::Filter;
+use crate::invocation::filtered_tasks;
+use std::collections::HashMap;
+use taskchampion::chrono::{Datelike, Local, NaiveDate};
+use taskchampion::{Replica, Status};
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// The width of each day in the calendar
+const DAY_WIDTH: usize = 6;
+
+/// Get the first day of the month following the given date
+fn next_month(date: NaiveDate) -> NaiveDate {
+    if date.month() == 12 {
+        NaiveDate::from_ymd(date.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd(date.year(), date.month() + 1, 1)
+    }
+}
+
+/// Write a calendar for the month containing `first`, with weeks beginning on Monday.  Each day
+/// shows the number of tasks due that day, if any.
+fn write_month<W: WriteColor>(
+    w: &mut W,
+    first: NaiveDate,
+    due: &HashMap<NaiveDate, usize>,
+    today: NaiveDate,
+) -> anyhow::Result<()> {
+    let title = first.format("%B %Y").to_string();
+    w.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(w, "{:^width$}", title, width = DAY_WIDTH * 7)?;
+    for day in &["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"] {
+        write!(w, "{:<width$}", day, width = DAY_WIDTH)?;
+    }
+    w.reset()?;
+    writeln!(w)?;
+
+    let offset = first.weekday().num_days_from_monday() as usize;
+    write!(w, "{}", " ".repeat(offset * DAY_WIDTH))?;
+
+    let mut date = first;
+    let mut column = offset;
+    while date.month() == first.month() {
+        let count = due.get(&date).copied().unwrap_or(0);
+        let cell = if count > 0 {
+            format!("{}({})", date.day(), count)
+        } else {
+            format!("{}", date.day())
+        };
+
+        let mut color = ColorSpec::new();
+        if count > 0 && date < today {
+            color.set_fg(Some(Color::Red));
+        } else if count > 0 {
+            color.set_fg(Some(Color::Yellow));
+        }
+        if date == today {
+            color.set_bold(true).set_underline(true);
+        }
+        w.set_color(&color)?;
+        write!(w, "{}", cell)?;
+        w.reset()?;
+
+        column += 1;
+        if column == 7 {
+            writeln!(w)?;
+            column = 0;
+        } else {
+            write!(w, "{}", " ".repeat(DAY_WIDTH.saturating_sub(cell.len())))?;
+        }
+        date = date.succ();
+    }
+    if column != 0 {
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+) -> Result<(), crate::Error> {
+    let mut due: HashMap<NaiveDate, usize> = HashMap::new();
+    for task in filtered_tasks(replica, &filter)? {
+        if task.get_status() != Status::Pending {
+            continue;
+        }
+        if let Some(ts) = task.get_due() {
+            *due.entry(ts.with_timezone(&Local).naive_local().date())
+                .or_insert(0) += 1;
+        }
+    }
+
+    let today = Local::now().naive_local().date();
+    let this_month = today.with_day(1).unwrap();
+    write_month(w, this_month, &due, today)?;
+    writeln!(w)?;
+    write_month(w, next_month(this_month), &due, today)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_next_month() {
+        assert_eq!(
+            next_month(NaiveDate::from_ymd(2021, 12, 1)),
+            NaiveDate::from_ymd(2022, 1, 1)
+        );
+        assert_eq!(
+            next_month(NaiveDate::from_ymd(2021, 3, 1)),
+            NaiveDate::from_ymd(2021, 4, 1)
+        );
+    }
+
+    #[test]
+    fn test_write_month() {
+        let mut w = test_writer();
+        let mut due = HashMap::new();
+        due.insert(NaiveDate::from_ymd(2021, 11, 3), 2);
+        due.insert(NaiveDate::from_ymd(2021, 11, 18), 1);
+        write_month(
+            &mut w,
+            NaiveDate::from_ymd(2021, 11, 1),
+            &due,
+            NaiveDate::from_ymd(2021, 11, 10),
+        )
+        .unwrap();
+        assert_eq!(
+            w.into_string(),
+            concat!(
+                "              November 2021               \n",
+                "Mo    Tu    We    Th    Fr    Sa    Su    \n",
+                "1     2     3(2)  4     5     6     7\n",
+                "8     9     10    11    12    13    14\n",
+                "15    16    17    18(1) 19    20    21\n",
+                "22    23    24    25    26    27    28\n",
+                "29    30    \n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_calendar() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        execute(&mut w, &mut replica, Filter::default()).unwrap();
+        let output = w.into_string();
+        let today = Local::now().naive_local().date();
+        assert!(output.contains(&today.format("%B %Y").to_string()));
+    }
+}
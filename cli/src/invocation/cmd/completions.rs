@@ -0,0 +1,292 @@
+use super::tags::tag_counts;
+use crate::argparse::Shell;
+use crate::settings::Settings;
+use crate::usage::Usage;
+use std::collections::BTreeSet;
+use taskchampion::Replica;
+use termcolor::WriteColor;
+
+/// The words to offer as completions
+#[derive(Debug, Default, PartialEq)]
+struct Completions {
+    /// Subcommands and report names, with a short description of each
+    commands: Vec<(String, String)>,
+    /// Tags, each prefixed with `+`
+    tags: Vec<String>,
+    /// Projects, each prefixed with `project:`
+    projects: Vec<String>,
+    /// Configuration keys that can be used with `config set`
+    config_keys: Vec<String>,
+}
+
+/// Determine whether a word can be included in a completion script without quoting
+fn is_plain_word(word: &str) -> bool {
+    !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_alphanumeric() || "+-_.:/@".contains(c))
+}
+
+/// Gather the completions from the usage information, settings, and replica
+fn gather(replica: &mut Replica, settings: &Settings) -> anyhow::Result<Completions> {
+    let mut commands = Vec::new();
+    let mut seen = BTreeSet::new();
+
+    // subcommands, using only the first word of multi-word subcommands like `config set`
+    for subcommand in Usage::new().subcommands {
+        let name = subcommand.name.split(' ').next().unwrap_or_default();
+        // `report` describes the report subcommands generally, and is not itself a subcommand;
+        // helpers like `_prompt` are meant for scripts rather than for typing
+        if name == "report" || name.starts_with('_') || !seen.insert(name.to_owned()) {
+            continue;
+        }
+        commands.push((name.to_owned(), subcommand.summary.to_owned()));
+    }
+
+    let mut report_names: Vec<_> = settings.reports.keys().collect();
+    report_names.sort();
+    for name in report_names {
+        if seen.insert(name.clone()) {
+            commands.push((name.clone(), format!("Show the '{}' report", name)));
+        }
+    }
+
+    let (user, synthetic) = tag_counts(replica)?;
+    let tags = user
+        .keys()
+        .chain(synthetic.keys())
+        .map(|t| format!("+{}", t))
+        .collect();
+
+    let mut projects: Vec<_> = replica
+        .projects()?
+        .keys()
+        .filter(|p| is_plain_word(p))
+        .map(|p| format!("project:{}", p))
+        .collect();
+    projects.sort();
+
+    let config_keys = Settings::SETTABLE_KEYS
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
+
+    Ok(Completions {
+        commands,
+        tags,
+        projects,
+        config_keys,
+    })
+}
+
+fn write_bash<W: WriteColor>(
+    w: &mut W,
+    command_name: &str,
+    completions: &Completions,
+) -> anyhow::Result<()> {
+    let commands: Vec<_> = completions
+        .commands
+        .iter()
+        .map(|(c, _)| c.as_str())
+        .collect();
+    let function = format!("_{}", command_name.replace('-', "_"));
+    // treat `project:foo` as a single word
+    writeln!(w, "COMP_WORDBREAKS=${{COMP_WORDBREAKS//:}}")?;
+    writeln!(w, "{}() {{", function)?;
+    writeln!(w, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"")?;
+    writeln!(w, "    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"")?;
+    writeln!(w, "    local words")?;
+    writeln!(w, "    case \"$cur\" in")?;
+    writeln!(w, "        +*) words=\"{}\" ;;", completions.tags.join(" "))?;
+    writeln!(
+        w,
+        "        project:*) words=\"{}\" ;;",
+        completions.projects.join(" ")
+    )?;
+    writeln!(w, "        *)")?;
+    writeln!(
+        w,
+        "            if [ \"$prev\" = set ] && [ \"${{COMP_WORDS[1]}}\" = config ]; then"
+    )?;
+    writeln!(
+        w,
+        "                words=\"{}\"",
+        completions.config_keys.join(" ")
+    )?;
+    writeln!(w, "            else")?;
+    writeln!(w, "                words=\"{}\"", commands.join(" "))?;
+    writeln!(w, "            fi ;;")?;
+    writeln!(w, "    esac")?;
+    writeln!(w, "    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))")?;
+    writeln!(w, "}}")?;
+    writeln!(w, "complete -F {} {}", function, command_name)?;
+    Ok(())
+}
+
+fn write_zsh<W: WriteColor>(
+    w: &mut W,
+    command_name: &str,
+    completions: &Completions,
+) -> anyhow::Result<()> {
+    let function = format!("_{}", command_name.replace('-', "_"));
+    writeln!(w, "#compdef {}", command_name)?;
+    writeln!(w, "{}() {{", function)?;
+    writeln!(w, "    local -a commands")?;
+    writeln!(w, "    commands=(")?;
+    for (name, summary) in &completions.commands {
+        writeln!(w, "        '{}:{}'", name, summary.replace('\'', "'\\''"))?;
+    }
+    writeln!(w, "    )")?;
+    writeln!(w, "    case \"$words[CURRENT]\" in")?;
+    writeln!(
+        w,
+        "        +*) compadd -- {} ;;",
+        completions.tags.join(" ")
+    )?;
+    writeln!(
+        w,
+        "        project:*) compadd -- {} ;;",
+        completions.projects.join(" ")
+    )?;
+    writeln!(w, "        *)")?;
+    writeln!(
+        w,
+        "            if [[ $words[CURRENT-1] == set && $words[2] == config ]]; then"
+    )?;
+    writeln!(
+        w,
+        "                compadd -- {}",
+        completions.config_keys.join(" ")
+    )?;
+    writeln!(w, "            else")?;
+    writeln!(w, "                _describe 'command' commands")?;
+    writeln!(w, "            fi ;;")?;
+    writeln!(w, "    esac")?;
+    writeln!(w, "}}")?;
+    writeln!(w, "compdef {} {}", function, command_name)?;
+    Ok(())
+}
+
+fn write_fish<W: WriteColor>(
+    w: &mut W,
+    command_name: &str,
+    completions: &Completions,
+) -> anyhow::Result<()> {
+    writeln!(w, "complete -c {} -f", command_name)?;
+    for (name, summary) in &completions.commands {
+        writeln!(
+            w,
+            "complete -c {} -n 'not __fish_seen_subcommand_from config' -a {} -d '{}'",
+            command_name,
+            name,
+            summary.replace('\'', "\\'")
+        )?;
+    }
+    if !completions.tags.is_empty() {
+        writeln!(
+            w,
+            "complete -c {} -a '{}'",
+            command_name,
+            completions.tags.join(" ")
+        )?;
+    }
+    if !completions.projects.is_empty() {
+        writeln!(
+            w,
+            "complete -c {} -a '{}'",
+            command_name,
+            completions.projects.join(" ")
+        )?;
+    }
+    writeln!(
+        w,
+        "complete -c {} -n '__fish_seen_subcommand_from set' -a '{}'",
+        command_name,
+        completions.config_keys.join(" ")
+    )?;
+    Ok(())
+}
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    settings: &Settings,
+    command_name: &str,
+    shell: Shell,
+) -> Result<(), crate::Error> {
+    let completions = gather(replica, settings)?;
+    match shell {
+        Shell::Bash => write_bash(w, command_name, &completions)?,
+        Shell::Zsh => write_zsh(w, command_name, &completions)?,
+        Shell::Fish => write_fish(w, command_name, &completions)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+    use taskchampion::Status;
+
+    fn test_data(replica: &mut Replica) {
+        let mut t = replica
+            .new_task(Status::Pending, s!("a task"))
+            .unwrap()
+            .into_mut(replica);
+        t.add_tag(&tag!("home")).unwrap();
+        t.set_project(Some(s!("house.garden"))).unwrap();
+        let mut t = replica
+            .new_task(Status::Pending, s!("another"))
+            .unwrap()
+            .into_mut(replica);
+        t.set_project(Some(s!("not plain"))).unwrap();
+    }
+
+    #[test]
+    fn test_gather() {
+        let mut replica = test_replica();
+        test_data(&mut replica);
+        let settings = Settings::default();
+
+        let completions = gather(&mut replica, &settings).unwrap();
+        let commands: Vec<_> = completions
+            .commands
+            .iter()
+            .map(|(c, _)| c.as_str())
+            .collect();
+        assert!(commands.contains(&"add"));
+        assert!(commands.contains(&"config"));
+        assert!(commands.contains(&"list"));
+        assert!(!commands.contains(&"report"));
+        // each command appears once, although `next` is both a subcommand and a report
+        assert_eq!(commands.iter().filter(|c| **c == "next").count(), 1);
+
+        assert!(completions.tags.contains(&s!("+home")));
+        assert!(completions.tags.contains(&s!("+PENDING")));
+        assert_eq!(completions.projects, vec![s!("project:house.garden")]);
+        assert!(completions.config_keys.contains(&s!("data_dir")));
+    }
+
+    #[test]
+    fn test_scripts() {
+        let mut replica = test_replica();
+        test_data(&mut replica);
+        let settings = Settings::default();
+
+        for &(shell, expected) in &[
+            (Shell::Bash, "complete -F _ta ta"),
+            (Shell::Zsh, "compdef _ta ta"),
+            (Shell::Fish, "complete -c ta -f"),
+        ] {
+            let mut w = test_writer();
+            execute(&mut w, &mut replica, &settings, "ta", shell).unwrap();
+            let script = w.into_string();
+            assert!(script.contains(expected));
+            assert!(script.contains("+home"));
+            assert!(script.contains("project:house.garden"));
+            assert!(script.contains("modification_count_prompt"));
+        }
+    }
+}
@@ -2,6 +2,16 @@ use crate::argparse::ConfigOperation;
 use crate::settings::Settings;
 use termcolor::{ColorSpec, WriteColor};
 
+/// Describe the value of a configuration key, for display following the key
+pub(super) fn describe_value(key: &str, value: Option<String>) -> String {
+    match value {
+        // avoid displaying the secret where it might be seen by others
+        Some(_) if key == "encryption_secret" => " = (hidden)".to_owned(),
+        Some(value) => format!(" = {}", value),
+        None => " (not set)".to_owned(),
+    }
+}
+
 pub(crate) fn execute<W: WriteColor>(
     w: &mut W,
     config_operation: ConfigOperation,
@@ -19,6 +29,19 @@ pub(crate) fn execute<W: WriteColor>(
             writeln!(w, "{:?}.", filename)?;
             w.set_color(ColorSpec::new().set_bold(false))?;
         }
+        ConfigOperation::Get(key) => {
+            if let Some(value) = settings.get(&key)? {
+                writeln!(w, "{}", value)?;
+            }
+        }
+        ConfigOperation::List => {
+            for key in Settings::SETTABLE_KEYS {
+                w.set_color(ColorSpec::new().set_bold(true))?;
+                write!(w, "{}", key)?;
+                w.set_color(ColorSpec::new().set_bold(false))?;
+                writeln!(w, "{}", describe_value(key, settings.get(key)?))?;
+            }
+        }
         ConfigOperation::Path => {
             if let Some(ref filename) = settings.filename {
                 writeln!(w, "{}", filename.to_string_lossy())?;
@@ -26,6 +49,10 @@ pub(crate) fn execute<W: WriteColor>(
                 return Err(anyhow::anyhow!("No configuration filename found").into());
             }
         }
+        ConfigOperation::Check => {
+            settings.validate()?;
+            writeln!(w, "No problems found in the configuration.")?;
+        }
     }
     Ok(())
 }
@@ -66,4 +93,102 @@ mod test {
             "# store data everywhere\ndata_dir = \"/somewhere\"\n"
         );
     }
+
+    #[test]
+    fn test_config_get() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(cfg_file.clone(), "data_dir = \"/nowhere\"\n").unwrap();
+        let settings = Settings::load_from_file(cfg_file, true).unwrap();
+
+        let mut w = test_writer();
+        execute(&mut w, ConfigOperation::Get(s!("data_dir")), &settings).unwrap();
+        assert_eq!(w.into_string(), "/nowhere\n");
+
+        let mut w = test_writer();
+        execute(&mut w, ConfigOperation::Get(s!("server_origin")), &settings).unwrap();
+        assert_eq!(w.into_string(), "");
+    }
+
+    #[test]
+    fn test_config_list() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(
+            cfg_file.clone(),
+            "data_dir = \"/nowhere\"\nencryption_secret = \"shh\"\n",
+        )
+        .unwrap();
+        let settings = Settings::load_from_file(cfg_file, true).unwrap();
+
+        let mut w = test_writer();
+        execute(&mut w, ConfigOperation::List, &settings).unwrap();
+        let output = w.into_string();
+        assert!(output.contains("data_dir = /nowhere\n"));
+        assert!(output.contains("encryption_secret = (hidden)\n"));
+        assert!(output.contains("server_origin (not set)\n"));
+        assert!(!output.contains("shh"));
+    }
+
+    #[test]
+    fn test_config_set_keeps_comments() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        let original = "# sync settings\n\
+                        server_origin = \"https://example.com\" # the team's server\n\
+                        \n\
+                        [reports.mine]\n\
+                        # only my tasks\n\
+                        filter = [\"+mine\"]\n\
+                        columns = [{ label = \"Description\", property = \"description\" }]\n";
+        fs::write(cfg_file.clone(), original).unwrap();
+        let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
+
+        let mut w = test_writer();
+        execute(
+            &mut w,
+            ConfigOperation::Set(s!("server_origin"), s!("https://example.org")),
+            &settings,
+        )
+        .unwrap();
+
+        let updated_toml = fs::read_to_string(cfg_file).unwrap();
+        assert_eq!(updated_toml, original.replace("example.com", "example.org"));
+    }
+
+    #[test]
+    fn test_config_check() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(
+            cfg_file.clone(),
+            "server_origin = \"https://example.com\"\n",
+        )
+        .unwrap();
+        let settings = Settings::load_from_file(cfg_file, true).unwrap();
+
+        let mut w = test_writer();
+        execute(&mut w, ConfigOperation::Check, &settings).unwrap();
+        assert_eq!(w.into_string(), "No problems found in the configuration.\n");
+    }
+
+    #[test]
+    fn test_config_check_problems() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(
+            cfg_file.clone(),
+            "server_origin = \"example.com\"\ndefault_report = \"nosuch\"\n",
+        )
+        .unwrap();
+        let settings = Settings::load_from_file(cfg_file, true).unwrap();
+
+        let mut w = test_writer();
+        let err = execute(&mut w, ConfigOperation::Check, &settings).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "server_origin must be an http:// or https:// URL\n\
+             default_report: report `nosuch` is not defined"
+        );
+    }
 }
@@ -0,0 +1,93 @@
+use crate::argparse::ContextOperation;
+use crate::settings::Settings;
+use termcolor::{ColorSpec, WriteColor};
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    context_operation: ContextOperation,
+    settings: &Settings,
+) -> Result<(), crate::Error> {
+    match context_operation {
+        ContextOperation::Define(name, expr) => {
+            let filename = settings.define_context(&name, &expr)?;
+            write!(w, "Defined context ")?;
+            w.set_color(ColorSpec::new().set_bold(true))?;
+            write!(w, "{}", &name)?;
+            w.set_color(ColorSpec::new().set_bold(false))?;
+            write!(w, " in ")?;
+            w.set_color(ColorSpec::new().set_bold(true))?;
+            writeln!(w, "{:?}.", filename)?;
+            w.set_color(ColorSpec::new().set_bold(false))?;
+        }
+        ContextOperation::Set(name) => {
+            settings.set_context(Some(&name))?;
+            write!(w, "Context ")?;
+            w.set_color(ColorSpec::new().set_bold(true))?;
+            write!(w, "{}", &name)?;
+            w.set_color(ColorSpec::new().set_bold(false))?;
+            writeln!(w, " is now active.")?;
+        }
+        ContextOperation::None => {
+            settings.set_context(None)?;
+            writeln!(w, "No context is active.")?;
+        }
+        ContextOperation::Show => {
+            if settings.contexts.is_empty() {
+                writeln!(w, "No contexts are defined.")?;
+            }
+            let mut contexts: Vec<_> = settings.contexts.iter().collect();
+            contexts.sort();
+            for (name, expr) in contexts {
+                if settings.context.as_ref() == Some(name) {
+                    w.set_color(ColorSpec::new().set_bold(true))?;
+                    writeln!(w, "{}: {} (active)", name, expr)?;
+                    w.set_color(ColorSpec::new().set_bold(false))?;
+                } else {
+                    writeln!(w, "{}: {}", name, expr)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_context_define_and_set() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(cfg_file.clone(), "").unwrap();
+
+        let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
+        let mut w = test_writer();
+        execute(
+            &mut w,
+            ContextOperation::Define(s!("work"), s!("project:acme or +work")),
+            &settings,
+        )
+        .unwrap();
+        assert!(w.into_string().starts_with("Defined context work in "));
+
+        let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
+        let mut w = test_writer();
+        execute(&mut w, ContextOperation::Set(s!("work")), &settings).unwrap();
+        assert_eq!(w.into_string(), "Context work is now active.\n");
+
+        let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
+        let mut w = test_writer();
+        execute(&mut w, ContextOperation::Show, &settings).unwrap();
+        assert_eq!(w.into_string(), "work: project:acme or +work (active)\n");
+
+        let mut w = test_writer();
+        execute(&mut w, ContextOperation::None, &settings).unwrap();
+        let settings = Settings::load_from_file(cfg_file, true).unwrap();
+        assert_eq!(settings.context, None);
+    }
+}
@@ -0,0 +1,46 @@
+use crate::argparse::Filter;
+use crate::invocation::filtered_tasks;
+use taskchampion::Replica;
+use termcolor::WriteColor;
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+) -> Result<(), crate::Error> {
+    let count = filtered_tasks(replica, &filter)?.count();
+    writeln!(w, "{}", count)?;
+    if count == 0 {
+        return Err(crate::Error::NoMatches);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+    use taskchampion::Status;
+
+    #[test]
+    fn test_count() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("one")).unwrap();
+        replica.new_task(Status::Pending, s!("two")).unwrap();
+
+        execute(&mut w, &mut replica, Filter::default()).unwrap();
+        assert_eq!(w.into_string(), "2\n");
+    }
+
+    #[test]
+    fn test_count_no_matches() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        let err = execute(&mut w, &mut replica, Filter::default()).unwrap_err();
+        assert!(err.is_no_matches());
+        assert_eq!(w.into_string(), "0\n");
+    }
+}
@@ -0,0 +1,60 @@
+use crate::argparse::Filter;
+use crate::invocation::util::summarize_task;
+use crate::invocation::{apply_modification, filtered_tasks, ResolvedModification};
+use taskchampion::Replica;
+use termcolor::WriteColor;
+
+pub(in crate::invocation) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+    modification: ResolvedModification,
+) -> Result<(), crate::Error> {
+    let tasks: Vec<_> = filtered_tasks(replica, &filter)?.collect();
+
+    for task in tasks {
+        let copy = replica.duplicate_task(task.get_uuid())?;
+        let mut copy = copy.into_mut(replica);
+        apply_modification(&mut copy, &modification)?;
+        let copy = copy.into_immut();
+
+        let original = summarize_task(replica, &task)?;
+        let copy = summarize_task(replica, &copy)?;
+        writeln!(w, "duplicated task {} as {}", original, copy)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::argparse::{DescriptionMod, Modification};
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+    use taskchampion::Status;
+
+    #[test]
+    fn test_duplicate() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("call mom")).unwrap();
+
+        let modification = ResolvedModification(Modification {
+            description: DescriptionMod::Append(s!("again")),
+            ..Default::default()
+        });
+        execute(&mut w, &mut replica, Filter::default(), modification).unwrap();
+
+        let working_set = replica.working_set().unwrap();
+        let copy = replica
+            .get_task(working_set.by_index(2).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(copy.get_description(), "call mom again");
+        assert_eq!(
+            w.into_string(),
+            "duplicated task 1 - call mom as 2 - call mom again\n"
+        );
+    }
+}
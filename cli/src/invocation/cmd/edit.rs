@@ -0,0 +1,445 @@
+use crate::argparse::{parse_timestamp, Filter};
+use crate::invocation::filtered_tasks;
+use crate::invocation::util::summarize_task;
+use anyhow::{anyhow, bail};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::io::Write;
+use std::process::Command;
+use taskchampion::chrono::{DateTime, Duration, SecondsFormat, Utc};
+use taskchampion::{Annotation, Replica, Tag, Task, TaskMut};
+use termcolor::WriteColor;
+
+const HEADER: &str = "\
+# Edit the task below, then save the file and exit the editor to apply the changes.
+#
+# Each line has the form `Key: value`; clear a value to remove it.  Dates may be given in any
+# format accepted on the command line, such as `2021-03-04` or `3d`.  Tags are separated by
+# spaces.  Each annotation is on a separate line, and begins with its timestamp; new annotations
+# may omit the timestamp.  UDAs are given as `UDA <key>: value`.
+#
+# Lines beginning with `#` are ignored.
+";
+
+/// The editable properties of a task
+#[derive(Debug, PartialEq)]
+struct Edited {
+    description: String,
+    project: Option<String>,
+    priority: String,
+    tags: HashSet<Tag>,
+    due: Option<DateTime<Utc>>,
+    wait: Option<DateTime<Utc>>,
+    scheduled: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    annotations: Vec<(Option<DateTime<Utc>>, String)>,
+    udas: HashMap<String, String>,
+}
+
+fn format_timestamp(ts: Option<DateTime<Utc>>) -> String {
+    ts.map(|ts| ts.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_default()
+}
+
+/// Serialize the editable properties of a task into text
+fn serialize(task: &Task) -> String {
+    let mut text = String::from(HEADER);
+    let mut line = |key: &str, value: &str| {
+        text.push_str(&format!("{}: {}\n", key, value));
+    };
+
+    line("Description", task.get_description());
+    line("Project", task.get_project().unwrap_or(""));
+    line("Priority", task.get_priority());
+
+    let mut tags: Vec<_> = task
+        .get_tags()
+        .filter(|t| t.is_user())
+        .map(|t| t.to_string())
+        .collect();
+    tags.sort();
+    line("Tags", &tags.join(" "));
+
+    line("Due", &format_timestamp(task.get_due()));
+    line("Wait", &format_timestamp(task.get_wait()));
+    line("Scheduled", &format_timestamp(task.get_scheduled()));
+    line("Until", &format_timestamp(task.get_until()));
+
+    let mut annotations: Vec<_> = task.get_annotations().collect();
+    annotations.sort();
+    for ann in annotations {
+        line(
+            "Annotation",
+            &format!("{} {}", format_timestamp(Some(ann.entry)), ann.description),
+        );
+    }
+
+    let mut udas: Vec<_> = task.get_legacy_udas().collect();
+    udas.sort_unstable();
+    for (key, value) in udas {
+        line(&format!("UDA {}", key), value);
+    }
+
+    text
+}
+
+fn parse_optional_timestamp(value: &str) -> anyhow::Result<Option<DateTime<Utc>>> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    parse_timestamp(value)
+        .map(Some)
+        .ok_or_else(|| anyhow!("invalid timestamp `{}`", value))
+}
+
+/// Parse the text produced by [`serialize`], as edited by the user
+fn parse(text: &str) -> anyhow::Result<Edited> {
+    let mut edited = Edited {
+        description: String::new(),
+        project: None,
+        priority: String::new(),
+        tags: HashSet::new(),
+        due: None,
+        wait: None,
+        scheduled: None,
+        until: None,
+        annotations: vec![],
+        udas: HashMap::new(),
+    };
+
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let err = |e: anyhow::Error| anyhow!("line {}: {}", i + 1, e);
+        let colon = line
+            .find(':')
+            .ok_or_else(|| err(anyhow!("expected `Key: value`")))?;
+        let (key, value) = (&line[..colon], line[colon + 1..].trim());
+
+        match key.trim() {
+            "Description" => edited.description = value.to_owned(),
+            "Project" if value.is_empty() => edited.project = None,
+            "Project" => edited.project = Some(value.to_owned()),
+            "Priority" => edited.priority = value.to_owned(),
+            "Tags" => {
+                for tag in value.split_whitespace() {
+                    edited
+                        .tags
+                        .insert(Tag::try_from(tag).map_err(|e| err(e.into()))?);
+                }
+            }
+            "Due" => edited.due = parse_optional_timestamp(value).map_err(err)?,
+            "Wait" => edited.wait = parse_optional_timestamp(value).map_err(err)?,
+            "Scheduled" => edited.scheduled = parse_optional_timestamp(value).map_err(err)?,
+            "Until" => edited.until = parse_optional_timestamp(value).map_err(err)?,
+            "Annotation" => {
+                if value.is_empty() {
+                    continue;
+                }
+                // the annotation begins with its timestamp, unless it is new
+                let (entry, description) = match value.find(' ') {
+                    Some(space) => match DateTime::parse_from_rfc3339(&value[..space]) {
+                        Ok(entry) => (Some(entry.with_timezone(&Utc)), value[space..].trim()),
+                        Err(_) => (None, value),
+                    },
+                    None => (None, value),
+                };
+                edited.annotations.push((entry, description.to_owned()));
+            }
+            key => match key.strip_prefix("UDA ") {
+                Some(uda) => {
+                    if !value.is_empty() {
+                        edited.udas.insert(uda.trim().to_owned(), value.to_owned());
+                    }
+                }
+                None => return Err(err(anyhow!("unknown key `{}`", key))),
+            },
+        }
+    }
+
+    if edited.description.is_empty() {
+        bail!("the description must not be empty");
+    }
+
+    Ok(edited)
+}
+
+/// Apply the edited properties to the task, changing only those which differ
+fn apply(task: &mut TaskMut, edited: Edited) -> anyhow::Result<()> {
+    if task.get_description() != edited.description {
+        task.set_description(edited.description)?;
+    }
+    if task.get_project() != edited.project.as_deref() {
+        task.set_project(edited.project)?;
+    }
+    if task.get_priority() != edited.priority {
+        task.set_priority(edited.priority)?;
+    }
+
+    let tags: HashSet<Tag> = task.get_tags().filter(|t| t.is_user()).collect();
+    for tag in tags.difference(&edited.tags) {
+        task.remove_tag(tag)?;
+    }
+    for tag in edited.tags.difference(&tags) {
+        task.add_tag(tag)?;
+    }
+
+    if task.get_due() != edited.due {
+        task.set_due(edited.due)?;
+    }
+    if task.get_wait() != edited.wait {
+        task.set_wait(edited.wait)?;
+    }
+    if task.get_scheduled() != edited.scheduled {
+        task.set_scheduled(edited.scheduled)?;
+    }
+    if task.get_until() != edited.until {
+        task.set_until(edited.until)?;
+    }
+
+    // annotations are identified by their entry time; new annotations get entry times one second
+    // apart, so that they do not collide
+    let existing: Vec<Annotation> = task.get_annotations().collect();
+    let mut new_entry = Utc::now();
+    let mut annotations = vec![];
+    for (entry, description) in edited.annotations {
+        let entry = entry.unwrap_or_else(|| {
+            new_entry = new_entry + Duration::seconds(1);
+            new_entry
+        });
+        annotations.push(Annotation { entry, description });
+    }
+    for ann in existing.iter() {
+        if !annotations.iter().any(|a| a.entry == ann.entry) {
+            task.remove_annotation(ann.entry)?;
+        }
+    }
+    for ann in annotations {
+        if !existing.contains(&ann) {
+            task.add_annotation(ann)?;
+        }
+    }
+
+    let udas: HashMap<String, String> = task
+        .get_legacy_udas()
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .collect();
+    for key in udas.keys() {
+        if !edited.udas.contains_key(key) {
+            task.remove_legacy_uda(key.clone())?;
+        }
+    }
+    for (key, value) in edited.udas {
+        if udas.get(&key) != Some(&value) {
+            task.set_legacy_uda(key, value)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the user's editor on the given file
+fn run_editor(path: &std::path::Path) -> anyhow::Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_owned());
+    // the editor may include arguments, such as `code --wait`
+    let mut words = editor.split_whitespace();
+    let program = words.next().ok_or_else(|| anyhow!("EDITOR is empty"))?;
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .map_err(|e| anyhow!("Could not run editor `{}`: {}", editor, e))?;
+    if !status.success() {
+        bail!("Editor `{}` exited with {}", editor, status);
+    }
+    Ok(())
+}
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+) -> Result<(), crate::Error> {
+    for task in filtered_tasks(replica, &filter)? {
+        let text = serialize(&task);
+        // the file is created securely with a unique name, and removed when dropped
+        let mut file = tempfile::Builder::new()
+            .prefix("ta-edit-")
+            .suffix(".txt")
+            .tempfile()?;
+        file.write_all(text.as_bytes())?;
+        file.flush()?;
+        run_editor(file.path())?;
+        let edited_text = std::fs::read_to_string(file.path())?;
+
+        let summary = summarize_task(replica, &task)?;
+        if edited_text == text {
+            writeln!(w, "no changes to task {}", summary)?;
+            continue;
+        }
+
+        // on error, keep the file so that the user's edits are not lost
+        let edited = match parse(&edited_text) {
+            Ok(edited) => edited,
+            Err(e) => {
+                let (_, path) = file.keep().map_err(|e| e.error)?;
+                return Err(anyhow!("{} (edits saved in {})", e, path.display()).into());
+            }
+        };
+
+        let mut task = task.into_mut(replica);
+        apply(&mut task, edited)?;
+        writeln!(w, "modified task {}", summary)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+    use taskchampion::chrono::TimeZone;
+    use taskchampion::Status;
+
+    fn test_task(replica: &mut Replica) -> Task {
+        let mut task = replica
+            .new_task(Status::Pending, s!("fix the roof"))
+            .unwrap()
+            .into_mut(replica);
+        task.set_project(Some(s!("home"))).unwrap();
+        task.add_tag(&tag!("house")).unwrap();
+        task.add_tag(&tag!("urgent")).unwrap();
+        task.set_due(Some(Utc.ymd(2021, 3, 4).and_hms(12, 0, 0)))
+            .unwrap();
+        task.add_annotation(Annotation {
+            entry: Utc.ymd(2021, 3, 1).and_hms(8, 30, 0),
+            description: s!("called roofer"),
+        })
+        .unwrap();
+        task.set_legacy_uda(s!("estimate"), s!("3h")).unwrap();
+        task.into_immut()
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut replica = test_replica();
+        let task = test_task(&mut replica);
+        let text = serialize(&task);
+        let body: Vec<_> = text.lines().filter(|l| !l.starts_with('#')).collect();
+        assert_eq!(
+            body,
+            vec![
+                "Description: fix the roof",
+                "Project: home",
+                "Priority: ",
+                "Tags: house urgent",
+                "Due: 2021-03-04T12:00:00Z",
+                "Wait: ",
+                "Scheduled: ",
+                "Until: ",
+                "Annotation: 2021-03-01T08:30:00Z called roofer",
+                "UDA estimate: 3h",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let mut replica = test_replica();
+        let task = test_task(&mut replica);
+        let edited = parse(&serialize(&task)).unwrap();
+        assert_eq!(edited.description, "fix the roof");
+        assert_eq!(edited.project, Some(s!("home")));
+        assert_eq!(edited.tags, set![tag!("house"), tag!("urgent")]);
+        assert_eq!(edited.due, Some(Utc.ymd(2021, 3, 4).and_hms(12, 0, 0)));
+        assert_eq!(
+            edited.annotations,
+            vec![(
+                Some(Utc.ymd(2021, 3, 1).and_hms(8, 30, 0)),
+                s!("called roofer")
+            )]
+        );
+        assert_eq!(edited.udas.get("estimate"), Some(&s!("3h")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("Description: x\nColor: blue\n").is_err());
+        assert!(parse("Description: x\nDue: someday\n").is_err());
+        assert!(parse("Description: x\nno colon\n").is_err());
+        assert!(parse("Project: home\n").is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut replica = test_replica();
+        let task = test_task(&mut replica);
+        let text = serialize(&task)
+            .replace(
+                "Description: fix the roof",
+                "Description: fix the whole roof",
+            )
+            .replace("Project: home", "Project: ")
+            .replace("Tags: house urgent", "Tags: house diy")
+            .replace("Due: 2021-03-04T12:00:00Z", "Due: 2021-03-05T12:00:00Z")
+            .replace(
+                "Annotation: 2021-03-01T08:30:00Z called roofer",
+                "Annotation: bought shingles",
+            )
+            .replace("UDA estimate: 3h", "UDA estimate: 5h");
+        let edited = parse(&text).unwrap();
+
+        let mut task = task.into_mut(&mut replica);
+        apply(&mut task, edited).unwrap();
+        let task = task.into_immut();
+
+        assert_eq!(task.get_description(), "fix the whole roof");
+        assert_eq!(task.get_project(), None);
+        let tags: HashSet<_> = task.get_tags().filter(|t| t.is_user()).collect();
+        assert_eq!(tags, set![tag!("house"), tag!("diy")]);
+        assert_eq!(task.get_due(), Some(Utc.ymd(2021, 3, 5).and_hms(12, 0, 0)));
+        let anns: Vec<_> = task.get_annotations().map(|a| a.description).collect();
+        assert_eq!(anns, vec![s!("bought shingles")]);
+        assert_eq!(task.get_legacy_uda("estimate"), Some("5h"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_parse_error_keeps_edits() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let editor = tmp_dir.path().join("editor.sh");
+        std::fs::write(&editor, "#!/bin/sh\necho bogus > \"$1\"\n").unwrap();
+        std::fs::set_permissions(&editor, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::env::set_var("VISUAL", &editor);
+
+        let mut replica = test_replica();
+        test_task(&mut replica);
+
+        // each failed edit is saved to its own file
+        let mut paths = vec![];
+        for _ in 0..2 {
+            let mut w = test_writer();
+            let err = execute(&mut w, &mut replica, Filter::default()).unwrap_err();
+            let msg = err.to_string();
+            let path = msg
+                .split("(edits saved in ")
+                .nth(1)
+                .and_then(|p| p.strip_suffix(')'))
+                .unwrap()
+                .to_owned();
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), "bogus\n");
+            paths.push(path);
+        }
+        std::env::remove_var("VISUAL");
+        assert_ne!(paths[0], paths[1]);
+        for path in paths {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
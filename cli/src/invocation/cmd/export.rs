@@ -0,0 +1,490 @@
+use crate::argparse::{ExportFormat, Filter};
+use crate::invocation::filtered_tasks;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use taskchampion::chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use taskchampion::todotxt::TodoTxt;
+use taskchampion::{Replica, Status, Tag, Task, WorkingSet};
+use termcolor::WriteColor;
+
+/// Properties containing timestamps, which TaskWarrior represents as formatted dates
+const TIMESTAMP_PROPERTIES: &[&str] = &[
+    "entry",
+    "modified",
+    "start",
+    "end",
+    "wait",
+    "due",
+    "scheduled",
+    "until",
+];
+
+/// Format a DateTime in the TaskWarrior format, such as `20211231T175614Z`
+fn tw_datetime(dt: DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Format a Duration as an ISO-8601 duration, which TaskWarrior accepts for `recur`
+fn tw_duration(dur: Duration) -> String {
+    let secs = dur.num_seconds();
+    if secs % 86400 == 0 {
+        format!("P{}D", secs / 86400)
+    } else {
+        format!("PT{}S", secs)
+    }
+}
+
+/// Convert a task into a JSON object in the TaskWarrior export format
+pub(super) fn task_to_json(task: &Task, working_set: &WorkingSet) -> Value {
+    let uuid = task.get_uuid();
+    let mut obj = Map::new();
+
+    // TaskWarrior uses id 0 for tasks not in the working set
+    obj.insert("id".into(), json!(working_set.by_uuid(uuid).unwrap_or(0)));
+    obj.insert("uuid".into(), json!(uuid.to_string()));
+
+    for (k, v) in task.get_taskmap().iter() {
+        if k.starts_with("tag_") || k.starts_with("annotation_") || k.starts_with("dep_") {
+            // handled below
+            continue;
+        }
+        let value = if TIMESTAMP_PROPERTIES.contains(&k.as_str()) {
+            match v
+                .parse()
+                .ok()
+                .and_then(|ts| Utc.timestamp_opt(ts, 0).single())
+            {
+                Some(ts) => tw_datetime(ts),
+                // pass invalid or out-of-range timestamps through unchanged
+                None => v.clone(),
+            }
+        } else if k == "recur" {
+            match task.get_recur() {
+                Some(recur) => tw_duration(recur),
+                None => v.clone(),
+            }
+        } else {
+            v.clone()
+        };
+        obj.insert(k.clone(), json!(value));
+    }
+
+    let mut tags: Vec<_> = task
+        .get_tags()
+        .filter(|t| t.is_user())
+        .map(|t| t.to_string())
+        .collect();
+    if !tags.is_empty() {
+        tags.sort();
+        obj.insert("tags".into(), json!(tags));
+    }
+
+    let mut annotations: Vec<_> = task.get_annotations().collect();
+    if !annotations.is_empty() {
+        annotations.sort();
+        let annotations: Vec<_> = annotations
+            .drain(..)
+            .map(|ann| json!({"entry": tw_datetime(ann.entry), "description": ann.description}))
+            .collect();
+        obj.insert("annotations".into(), json!(annotations));
+    }
+
+    let mut depends: Vec<_> = task.get_dependencies().map(|u| u.to_string()).collect();
+    if !depends.is_empty() {
+        depends.sort();
+        obj.insert("depends".into(), json!(depends));
+    }
+
+    Value::Object(obj)
+}
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+    format: ExportFormat,
+) -> Result<(), crate::Error> {
+    match format {
+        ExportFormat::Json => export_json(w, replica, filter),
+        ExportFormat::Ical => export_ical(w, replica, filter),
+        ExportFormat::TodoTxt => export_todotxt(w, replica, filter),
+        ExportFormat::Org => export_org(w, replica, filter),
+    }
+}
+
+fn export_json<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+) -> Result<(), crate::Error> {
+    let working_set = replica.working_set()?;
+
+    // Like TaskWarrior, write one task per line, so that the output is easy to process with
+    // line-oriented tools as well as JSON parsers.
+    writeln!(w, "[")?;
+    let mut first = true;
+    for task in filtered_tasks(replica, &filter)? {
+        if !first {
+            writeln!(w, ",")?;
+        }
+        write!(w, "{}", task_to_json(&task, &working_set))?;
+        first = false;
+    }
+    if !first {
+        writeln!(w)?;
+    }
+    writeln!(w, "]")?;
+
+    Ok(())
+}
+
+fn export_ical<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+) -> Result<(), crate::Error> {
+    let uuids: HashSet<_> = filtered_tasks(replica, &filter)?
+        .map(|t| t.get_uuid())
+        .collect();
+    replica.export_ical(w, |t| uuids.contains(&t.get_uuid()))?;
+    Ok(())
+}
+
+fn export_todotxt<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+) -> Result<(), crate::Error> {
+    for task in filtered_tasks(replica, &filter)? {
+        writeln!(w, "{}", TodoTxt::from_task(&task))?;
+    }
+    Ok(())
+}
+
+/// Format a DateTime as an org-mode timestamp, such as `<2021-12-31 Fri 17:56>`, or
+/// `[2021-12-31 Fri 17:56]` if inactive.  The time is omitted at midnight.
+fn org_timestamp(dt: DateTime<Utc>, active: bool) -> String {
+    let (open, close) = if active { ('<', '>') } else { ('[', ']') };
+    let format = if dt.time() == NaiveTime::from_hms(0, 0, 0) {
+        "%Y-%m-%d %a"
+    } else {
+        "%Y-%m-%d %a %H:%M"
+    };
+    format!("{}{}{}", open, dt.format(format), close)
+}
+
+/// Convert a tag into an org-mode tag, which may only contain letters, numbers, `_`, `@`, `#`,
+/// and `%`
+fn org_tag(tag: &Tag) -> String {
+    tag.as_ref()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "_@#%".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Write a task as an org-mode heading
+fn write_org_heading<W: WriteColor>(w: &mut W, task: &Task) -> Result<(), crate::Error> {
+    let keyword = match task.get_status() {
+        Status::Completed => "DONE",
+        Status::Deleted => "CANCELED",
+        _ => "TODO",
+    };
+    write!(w, "* {}", keyword)?;
+    let priority = match task.get_priority() {
+        "H" => Some('A'),
+        "M" => Some('B'),
+        "L" => Some('C'),
+        _ => None,
+    };
+    if let Some(priority) = priority {
+        write!(w, " [#{}]", priority)?;
+    }
+    // a heading is a single line
+    let description: Vec<_> = task.get_description().split_whitespace().collect();
+    write!(w, " {}", description.join(" "))?;
+    let mut tags: Vec<_> = task
+        .get_tags()
+        .filter(|t| t.is_user())
+        .map(|t| org_tag(&t))
+        .collect();
+    if !tags.is_empty() {
+        tags.sort();
+        write!(w, " :{}:", tags.join(":"))?;
+    }
+    writeln!(w)?;
+
+    let mut planning = vec![];
+    if task.get_status() != Status::Pending {
+        if let Some(end) = task.get_end() {
+            planning.push(format!("CLOSED: {}", org_timestamp(end, false)));
+        }
+    }
+    if let Some(scheduled) = task.get_scheduled() {
+        planning.push(format!("SCHEDULED: {}", org_timestamp(scheduled, true)));
+    }
+    if let Some(due) = task.get_due() {
+        planning.push(format!("DEADLINE: {}", org_timestamp(due, true)));
+    }
+    if !planning.is_empty() {
+        writeln!(w, "  {}", planning.join(" "))?;
+    }
+
+    writeln!(w, "  :PROPERTIES:")?;
+    writeln!(w, "  :UUID:     {}", task.get_uuid())?;
+    if let Some(project) = task.get_project() {
+        writeln!(w, "  :CATEGORY: {}", project)?;
+    }
+    if let Some(entry) = task.get_entry() {
+        writeln!(w, "  :CREATED:  {}", org_timestamp(entry, false))?;
+    }
+    writeln!(w, "  :END:")?;
+
+    let mut annotations: Vec<_> = task.get_annotations().collect();
+    annotations.sort();
+    for ann in annotations {
+        writeln!(
+            w,
+            "  - {} {}",
+            org_timestamp(ann.entry, false),
+            ann.description
+        )?;
+    }
+    Ok(())
+}
+
+fn export_org<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+) -> Result<(), crate::Error> {
+    // CANCELED is not one of org-mode's default keywords
+    writeln!(w, "#+TODO: TODO | DONE CANCELED")?;
+    for task in filtered_tasks(replica, &filter)? {
+        write_org_heading(w, &task)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::argparse::Condition;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+    use taskchampion::Annotation;
+
+    #[test]
+    fn test_export() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        let dep = replica.new_task(Status::Pending, s!("dep")).unwrap();
+        let mut t = replica
+            .new_task(Status::Pending, s!("my task"))
+            .unwrap()
+            .into_mut(&mut replica);
+        t.add_tag(&tag!("work")).unwrap();
+        t.add_annotation(Annotation {
+            entry: Utc.ymd(2021, 12, 31).and_hms(17, 56, 14),
+            description: s!("note"),
+        })
+        .unwrap();
+        t.add_dependency(dep.get_uuid()).unwrap();
+        t.set_due(Some(Utc.ymd(2022, 1, 2).and_hms(3, 4, 5)))
+            .unwrap();
+        t.set_recur(Some(Duration::days(7))).unwrap();
+        let uuid = t.get_uuid();
+
+        execute(&mut w, &mut replica, Filter::default(), ExportFormat::Json).unwrap();
+        let output = w.into_string();
+        let tasks: Vec<Value> = serde_json::from_str(&output).unwrap();
+        assert_eq!(tasks.len(), 2);
+        // one line for each task, plus the brackets
+        assert_eq!(output.lines().count(), 4);
+
+        let task = tasks
+            .iter()
+            .find(|t| t["uuid"] == json!(uuid.to_string()))
+            .unwrap();
+        assert_eq!(task["id"], json!(2));
+        assert_eq!(task["description"], json!("my task"));
+        assert_eq!(task["status"], json!("pending"));
+        assert_eq!(task["due"], json!("20220102T030405Z"));
+        assert_eq!(task["recur"], json!("P7D"));
+        assert_eq!(task["tags"], json!(["work"]));
+        assert_eq!(
+            task["annotations"],
+            json!([{"entry": "20211231T175614Z", "description": "note"}])
+        );
+        assert_eq!(task["depends"], json!([dep.get_uuid().to_string()]));
+        assert!(task.get("tag_work").is_none());
+    }
+
+    #[test]
+    fn test_export_empty() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        execute(&mut w, &mut replica, Filter::default(), ExportFormat::Json).unwrap();
+        assert_eq!(w.into_string(), "[\n]\n");
+    }
+
+    #[test]
+    fn test_export_invalid_timestamp() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        let uuid = replica
+            .new_task(Status::Pending, s!("my task"))
+            .unwrap()
+            .get_uuid();
+        replica
+            .update_task(uuid, "scheduled", Some("99999999999999999"))
+            .unwrap();
+
+        execute(&mut w, &mut replica, Filter::default(), ExportFormat::Json).unwrap();
+        let tasks: Vec<Value> = serde_json::from_str(&w.into_string()).unwrap();
+        assert_eq!(tasks[0]["scheduled"], json!("99999999999999999"));
+    }
+
+    #[test]
+    fn test_export_ical() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        let mut t = replica
+            .new_task(Status::Pending, s!("my task"))
+            .unwrap()
+            .into_mut(&mut replica);
+        t.add_tag(&tag!("work")).unwrap();
+        t.set_due(Some(Utc.ymd(2022, 1, 2).and_hms(3, 4, 5)))
+            .unwrap();
+        let uuid = t.get_uuid();
+        replica.new_task(Status::Pending, s!("other")).unwrap();
+
+        let filter = Filter {
+            conditions: vec![Condition::HasTag(tag!("work"))],
+        };
+        execute(&mut w, &mut replica, filter, ExportFormat::Ical).unwrap();
+        let output = w.into_string();
+        assert!(output.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert_eq!(output.matches("BEGIN:VTODO").count(), 1);
+        assert!(output.contains(&format!("\r\nUID:{}\r\n", uuid)));
+        assert!(output.contains("\r\nSUMMARY:my task\r\n"));
+        assert!(output.contains("\r\nDUE:20220102T030405Z\r\n"));
+        assert!(output.contains("\r\nSTATUS:NEEDS-ACTION\r\n"));
+        assert!(output.contains("\r\nCATEGORIES:work\r\n"));
+    }
+
+    #[test]
+    fn test_export_todotxt() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        let mut t = replica
+            .new_task(Status::Pending, s!("my task"))
+            .unwrap()
+            .into_mut(&mut replica);
+        t.set_entry(Some(Utc.ymd(2022, 1, 1).and_hms(3, 4, 5)))
+            .unwrap();
+        t.set_priority(s!("M")).unwrap();
+        t.set_project(Some(s!("home"))).unwrap();
+        t.add_tag(&tag!("work")).unwrap();
+        t.set_due(Some(Utc.ymd(2022, 1, 2).and_hms(3, 4, 5)))
+            .unwrap();
+
+        execute(
+            &mut w,
+            &mut replica,
+            Filter::default(),
+            ExportFormat::TodoTxt,
+        )
+        .unwrap();
+        assert_eq!(
+            w.into_string(),
+            "(B) 2022-01-01 my task +home @work due:2022-01-02\n"
+        );
+    }
+
+    #[test]
+    fn test_export_org() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        let mut t = replica
+            .new_task(Status::Pending, s!("my\ntask"))
+            .unwrap()
+            .into_mut(&mut replica);
+        t.set_entry(Some(Utc.ymd(2022, 1, 1).and_hms(3, 4, 5)))
+            .unwrap();
+        t.set_priority(s!("H")).unwrap();
+        t.set_project(Some(s!("home"))).unwrap();
+        t.add_tag(&tag!("work")).unwrap();
+        t.add_tag(&tag!("a.b")).unwrap();
+        t.set_scheduled(Some(Utc.ymd(2022, 1, 2).and_hms(0, 0, 0)))
+            .unwrap();
+        t.set_due(Some(Utc.ymd(2022, 1, 3).and_hms(17, 30, 0)))
+            .unwrap();
+        t.add_annotation(Annotation {
+            entry: Utc.ymd(2022, 1, 1).and_hms(4, 0, 0),
+            description: s!("note"),
+        })
+        .unwrap();
+        let uuid = t.get_uuid();
+
+        execute(&mut w, &mut replica, Filter::default(), ExportFormat::Org).unwrap();
+        assert_eq!(
+            w.into_string(),
+            format!(
+                "#+TODO: TODO | DONE CANCELED
+* TODO [#A] my task :a_b:work:
+  SCHEDULED: <2022-01-02 Sun> DEADLINE: <2022-01-03 Mon 17:30>
+  :PROPERTIES:
+  :UUID:     {}
+  :CATEGORY: home
+  :CREATED:  [2022-01-01 Sat 03:04]
+  :END:
+  - [2022-01-01 Sat 04:00] note
+",
+                uuid
+            )
+        );
+    }
+
+    #[test]
+    fn test_export_org_completed() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        let mut t = replica
+            .new_task(Status::Pending, s!("done"))
+            .unwrap()
+            .into_mut(&mut replica);
+        t.set_entry(None).unwrap();
+        t.done().unwrap();
+        t.set_end(Some(Utc.ymd(2022, 1, 4).and_hms(0, 0, 0)))
+            .unwrap();
+        let uuid = t.get_uuid();
+
+        execute(&mut w, &mut replica, Filter::default(), ExportFormat::Org).unwrap();
+        assert_eq!(
+            w.into_string(),
+            format!(
+                "#+TODO: TODO | DONE CANCELED
+* DONE done
+  CLOSED: [2022-01-04 Tue]
+  :PROPERTIES:
+  :UUID:     {}
+  :END:
+",
+                uuid
+            )
+        );
+    }
+}
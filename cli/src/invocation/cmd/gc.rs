@@ -6,6 +6,14 @@ pub(crate) fn execute<W: WriteColor>(w: &mut W, replica: &mut Replica) -> Result
     replica.rebuild_working_set(true)?;
     log::debug!("expiring old tasks");
     replica.expire_tasks()?;
+    log::debug!("removing dangling references");
+    let report = replica.maintenance()?;
+    for (uuid, dep) in &report.dangling_dependencies {
+        writeln!(w, "removed dependency of {} on missing task {}", uuid, dep)?;
+    }
+    for uuid in &report.expired_tasks {
+        writeln!(w, "deleted task {} as its until time has passed", uuid)?;
+    }
     writeln!(w, "garbage collected.")?;
     Ok(())
 }
@@ -15,6 +23,8 @@ mod test {
     use super::*;
     use crate::invocation::test::*;
     use pretty_assertions::assert_eq;
+    use taskchampion::chrono::{Duration, Utc};
+    use taskchampion::{Status, Uuid};
 
     #[test]
     fn test_gc() {
@@ -23,4 +33,51 @@ mod test {
         execute(&mut w, &mut replica).unwrap();
         assert_eq!(&w.into_string(), "garbage collected.\n")
     }
+
+    #[test]
+    fn test_gc_dangling_dependency() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let missing = Uuid::new_v4();
+        let mut t = replica
+            .new_task(Status::Pending, s!("task"))
+            .unwrap()
+            .into_mut(&mut replica);
+        t.add_dependency(missing).unwrap();
+        let uuid = t.get_uuid();
+
+        execute(&mut w, &mut replica).unwrap();
+        assert_eq!(
+            w.into_string(),
+            format!(
+                "removed dependency of {} on missing task {}\ngarbage collected.\n",
+                uuid, missing
+            )
+        );
+        let t = replica.get_task(uuid).unwrap().unwrap();
+        assert_eq!(t.get_dependencies().count(), 0);
+    }
+
+    #[test]
+    fn test_gc_until() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let mut t = replica
+            .new_task(Status::Pending, s!("task"))
+            .unwrap()
+            .into_mut(&mut replica);
+        t.set_until(Some(Utc::now() - Duration::hours(1))).unwrap();
+        let uuid = t.get_uuid();
+
+        execute(&mut w, &mut replica).unwrap();
+        assert_eq!(
+            w.into_string(),
+            format!(
+                "deleted task {} as its until time has passed\ngarbage collected.\n",
+                uuid
+            )
+        );
+        let t = replica.get_task(uuid).unwrap().unwrap();
+        assert_eq!(t.get_status(), Status::Deleted);
+    }
 }
@@ -1,16 +1,89 @@
+use crate::argparse::{did_you_mean, subcommand_names};
+use crate::settings::Settings;
 use crate::usage::Usage;
 use termcolor::WriteColor;
 
 pub(crate) fn execute<W: WriteColor>(
     w: &mut W,
+    settings: &Settings,
     command_name: String,
     summary: bool,
+    topic: Option<String>,
 ) -> Result<(), crate::Error> {
     let usage = Usage::new();
-    usage.write_help(w, command_name.as_ref(), summary)?;
+
+    if let Some(topic) = topic {
+        return write_topic_help(w, &usage, settings, &command_name, &topic);
+    }
+
+    usage.write_help(&mut *w, command_name.as_ref(), summary)?;
+
+    if !settings.aliases.is_empty() {
+        writeln!(w, "\nAliases:")?;
+        let mut aliases: Vec<_> = settings.aliases.iter().collect();
+        aliases.sort();
+        for (name, expansion) in aliases {
+            writeln!(w, "  {} {} = {}", command_name, name, expansion.join(" "))?;
+        }
+    }
     Ok(())
 }
 
+/// Write detailed help for a single subcommand, report, or alias.
+fn write_topic_help<W: WriteColor>(
+    w: &mut W,
+    usage: &Usage,
+    settings: &Settings,
+    command_name: &str,
+    topic: &str,
+) -> Result<(), crate::Error> {
+    if let Some(expansion) = settings.aliases.get(topic) {
+        writeln!(
+            w,
+            "`{} {}` is an alias for `{} {}`\n",
+            command_name,
+            topic,
+            command_name,
+            expansion.join(" ")
+        )?;
+        // show help for the subcommand the alias expands to, if it names one
+        return match expansion.iter().find(|arg| usage.has_subcommand(arg)) {
+            Some(name) => {
+                usage.write_subcommand_help(&mut *w, command_name, name)?;
+                Ok(())
+            }
+            None => Ok(()),
+        };
+    }
+
+    if usage.write_subcommand_help(&mut *w, command_name, topic)? {
+        return Ok(());
+    }
+
+    // any other report is described by the general report usage
+    if settings.reports.contains_key(topic) {
+        usage.write_subcommand_help(&mut *w, command_name, "report")?;
+        return Ok(());
+    }
+
+    let mut reports: Vec<&str> = settings.reports.keys().map(|k| k.as_ref()).collect();
+    reports.sort_unstable();
+    let subcommands = subcommand_names();
+    let candidates = subcommands.iter().chain(reports.iter()).copied();
+    Err(crate::Error::for_arguments(
+        match did_you_mean(topic, candidates) {
+            Some(suggestion) => format!(
+                "no help for unknown subcommand `{}`, did you mean `{}`?",
+                topic, suggestion
+            ),
+            None => format!(
+                "no help for unknown subcommand `{}`; see `{} help` for all subcommands",
+                topic, command_name
+            ),
+        },
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -19,12 +92,105 @@ mod test {
     #[test]
     fn test_summary() {
         let mut w = test_writer();
-        execute(&mut w, s!("ta"), true).unwrap();
+        execute(&mut w, &Settings::default(), s!("ta"), true, None).unwrap();
     }
 
     #[test]
     fn test_long() {
         let mut w = test_writer();
-        execute(&mut w, s!("ta"), false).unwrap();
+        execute(&mut w, &Settings::default(), s!("ta"), false, None).unwrap();
+    }
+
+    #[test]
+    fn test_aliases() {
+        let mut w = test_writer();
+        let mut settings = Settings::default();
+        settings
+            .aliases
+            .insert(s!("in"), vec![s!("add"), s!("+inbox")]);
+        execute(&mut w, &settings, s!("ta"), true, None).unwrap();
+        assert!(w
+            .into_string()
+            .ends_with("Aliases:\n  ta in = add +inbox\n"));
+    }
+
+    #[test]
+    fn test_topic() {
+        let mut w = test_writer();
+        execute(
+            &mut w,
+            &Settings::default(),
+            s!("ta"),
+            false,
+            Some(s!("modify")),
+        )
+        .unwrap();
+        let s = w.into_string();
+        assert!(s.starts_with("USAGE:\n  ta <filter> modify [modification]\n"));
+        assert!(s.contains("ta 3 modify due:friday"));
+        assert!(s.contains("Modifications:"));
+        // only the named subcommand is described
+        assert!(!s.contains("ta <filter> prepend"));
+    }
+
+    #[test]
+    fn test_topic_group() {
+        let mut w = test_writer();
+        execute(
+            &mut w,
+            &Settings::default(),
+            s!("ta"),
+            false,
+            Some(s!("config")),
+        )
+        .unwrap();
+        let s = w.into_string();
+        assert!(s.contains("ta config set <key> <value>"));
+        assert!(s.contains("ta config list"));
+        assert!(!s.contains("Filter Expressions:"));
+    }
+
+    #[test]
+    fn test_topic_report() {
+        let mut w = test_writer();
+        execute(
+            &mut w,
+            &Settings::default(),
+            s!("ta"),
+            false,
+            Some(s!("list")),
+        )
+        .unwrap();
+        assert!(w.into_string().contains("ta [filter] [report-name]"));
+    }
+
+    #[test]
+    fn test_topic_alias() {
+        let mut w = test_writer();
+        let mut settings = Settings::default();
+        settings
+            .aliases
+            .insert(s!("in"), vec![s!("add"), s!("+inbox")]);
+        execute(&mut w, &settings, s!("ta"), false, Some(s!("in"))).unwrap();
+        let s = w.into_string();
+        assert!(s.starts_with("`ta in` is an alias for `ta add +inbox`\n"));
+        assert!(s.contains("ta add [modification]"));
+    }
+
+    #[test]
+    fn test_topic_unknown() {
+        let mut w = test_writer();
+        let err = execute(
+            &mut w,
+            &Settings::default(),
+            s!("ta"),
+            false,
+            Some(s!("modfy")),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Command-Line Syntax Error: no help for unknown subcommand `modfy`, did you mean `modify`?"
+        );
     }
 }
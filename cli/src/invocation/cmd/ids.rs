@@ -0,0 +1,85 @@
+use crate::argparse::Filter;
+use crate::invocation::filtered_tasks;
+use taskchampion::Replica;
+use termcolor::WriteColor;
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+    uuids: bool,
+) -> Result<(), crate::Error> {
+    let working_set = replica.working_set()?;
+
+    // order by working-set id, with tasks not in the working set last
+    let mut tasks: Vec<_> = filtered_tasks(replica, &filter)?
+        .map(|t| {
+            let uuid = t.get_uuid();
+            (working_set.by_uuid(uuid).unwrap_or(usize::MAX), uuid)
+        })
+        .collect();
+    tasks.sort();
+
+    let mut written = 0;
+    for (id, uuid) in tasks {
+        if uuids {
+            writeln!(w, "{}", uuid)?;
+        } else if id != usize::MAX {
+            writeln!(w, "{}", id)?;
+        } else {
+            continue;
+        }
+        written += 1;
+    }
+
+    if written == 0 {
+        return Err(crate::Error::NoMatches);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+    use taskchampion::Status;
+
+    #[test]
+    fn test_ids() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("one")).unwrap();
+        replica.new_task(Status::Pending, s!("two")).unwrap();
+        replica.new_task(Status::Completed, s!("done")).unwrap();
+
+        execute(&mut w, &mut replica, Filter::default(), false).unwrap();
+        assert_eq!(w.into_string(), "1\n2\n");
+    }
+
+    #[test]
+    fn test_uuids() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let t1 = replica.new_task(Status::Pending, s!("one")).unwrap();
+        let t2 = replica.new_task(Status::Completed, s!("done")).unwrap();
+
+        execute(&mut w, &mut replica, Filter::default(), true).unwrap();
+        assert_eq!(
+            w.into_string(),
+            format!("{}\n{}\n", t1.get_uuid(), t2.get_uuid())
+        );
+    }
+
+    #[test]
+    fn test_ids_no_matches() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        // a completed task is not in the working set, so has no id
+        replica.new_task(Status::Completed, s!("done")).unwrap();
+
+        let err = execute(&mut w, &mut replica, Filter::default(), false).unwrap_err();
+        assert!(err.is_no_matches());
+        assert_eq!(w.into_string(), "");
+    }
+}
@@ -0,0 +1,375 @@
+use super::import_tw::{import_task_json, task_json_uuid};
+use crate::argparse::{DescriptionMod, ImportFormat, Modification};
+use crate::invocation::{apply_modification, resolve_modification};
+use anyhow::{anyhow, bail};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use taskchampion::todotxt::TodoTxt;
+use taskchampion::{Replica, Status, StorageConfig, TaskMut};
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// Counts of the results of an import
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    added: usize,
+    updated: usize,
+    skipped: usize,
+}
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    path: Option<String>,
+    format: ImportFormat,
+) -> Result<(), crate::Error> {
+    let input = match path {
+        Some(path) => {
+            std::fs::read_to_string(&path).map_err(|e| anyhow!("Could not read {}: {}", path, e))?
+        }
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+
+    let summary = import_str(w, replica, &input, format)?;
+
+    w.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(
+        w,
+        "{} added, {} updated, {} skipped.",
+        summary.added, summary.updated, summary.skipped
+    )?;
+    w.reset()?;
+
+    Ok(())
+}
+
+/// Import tasks from the given input in the given format, detecting the format for
+/// `ImportFormat::Auto`
+fn import_str<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    input: &str,
+    format: ImportFormat,
+) -> anyhow::Result<Summary> {
+    let mut summary = Summary::default();
+    let trimmed = input.trim_start();
+
+    if format == ImportFormat::TodoTxt {
+        for (i, line) in input.lines().enumerate() {
+            if let Some(todo) = TodoTxt::parse(line) {
+                let result = import_todotxt(replica, todo).map(|_| Imported::Added);
+                record(w, &mut summary, "line", i + 1, result)?;
+            }
+        }
+    } else if trimmed.starts_with('[') {
+        // a TaskWarrior JSON export
+        let tasks: Vec<HashMap<String, Value>> =
+            serde_json::from_str(input).map_err(|e| anyhow!("Invalid JSON: {}", e))?;
+        for (i, task_json) in tasks.into_iter().enumerate() {
+            let result = import_json(replica, task_json);
+            record(w, &mut summary, "task", i + 1, result)?;
+        }
+    } else if trimmed.starts_with('{') {
+        // one TaskWarrior JSON object per line
+        for (i, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let result = serde_json::from_str(line)
+                .map_err(|e| anyhow!("Invalid JSON: {}", e))
+                .and_then(|task_json| import_json(replica, task_json));
+            record(w, &mut summary, "line", i + 1, result)?;
+        }
+    } else {
+        // one task per line, in the format of the arguments to `ta add`
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let result = import_line(replica, line).map(|_| Imported::Added);
+            record(w, &mut summary, "line", i + 1, result)?;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// The result of importing a single task
+enum Imported {
+    Added,
+    Updated,
+    Unchanged,
+}
+
+/// Update the summary with the result of importing a single task, and describe any errors
+fn record<W: WriteColor>(
+    w: &mut W,
+    summary: &mut Summary,
+    kind: &str,
+    index: usize,
+    result: anyhow::Result<Imported>,
+) -> anyhow::Result<()> {
+    match result {
+        Ok(Imported::Added) => summary.added += 1,
+        Ok(Imported::Updated) => summary.updated += 1,
+        Ok(Imported::Unchanged) => summary.skipped += 1,
+        Err(e) => {
+            summary.skipped += 1;
+            w.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+            writeln!(w, "Skipping {} {}: {}", kind, index, e)?;
+            w.reset()?;
+        }
+    }
+    Ok(())
+}
+
+/// Import a task in the TaskWarrior JSON format
+fn import_json(
+    replica: &mut Replica,
+    task_json: HashMap<String, Value>,
+) -> anyhow::Result<Imported> {
+    let uuid = task_json_uuid(&task_json)?;
+    let before = replica.get_task(uuid)?.map(|t| t.get_taskmap().clone());
+
+    import_task_json(replica, task_json)?;
+
+    let after = replica.get_task(uuid)?.map(|t| t.get_taskmap().clone());
+    Ok(match before {
+        None => Imported::Added,
+        Some(before) if Some(&before) == after.as_ref() => Imported::Unchanged,
+        Some(_) => Imported::Updated,
+    })
+}
+
+/// Add a new task described by a line in the todo.txt format
+fn import_todotxt(replica: &mut Replica, todo: TodoTxt) -> anyhow::Result<()> {
+    if todo.description.is_empty() {
+        bail!("no description");
+    }
+    new_task_with(replica, todo.description.clone(), |task| {
+        Ok(todo.apply(task)?)
+    })
+}
+
+/// Add a new task described by a line in the format of the arguments to `ta add`
+fn import_line(replica: &mut Replica, line: &str) -> anyhow::Result<()> {
+    let args: Vec<&str> = line.split_whitespace().collect();
+    let mut modification = Modification::parse_args(&args)?;
+
+    let description = match modification.description {
+        DescriptionMod::Set(ref s) => s.clone(),
+        _ => bail!("no description"),
+    };
+    modification.description = DescriptionMod::None;
+    let modification = resolve_modification(modification, replica)?;
+
+    new_task_with(replica, description, |task| {
+        apply_modification(task, &modification)
+    })
+}
+
+/// Add a new pending task, calling `modify` to set its properties.  The modification is first
+/// tried on a scratch task in an in-memory replica, so that a modification that fails does not
+/// leave a partially-built task behind.
+fn new_task_with<F>(replica: &mut Replica, description: String, modify: F) -> anyhow::Result<()>
+where
+    F: Fn(&mut TaskMut) -> anyhow::Result<()>,
+{
+    let mut scratch = Replica::new(StorageConfig::InMemory.into_storage()?);
+    let task = scratch.new_task(Status::Pending, description.clone())?;
+    modify(&mut task.into_mut(&mut scratch))?;
+
+    let task = replica.new_task(Status::Pending, description)?;
+    modify(&mut task.into_mut(replica))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+    use taskchampion::Uuid;
+
+    const UUID: &str = "fa01e916-1587-4c7d-a646-f7be62be8ee7";
+
+    #[test]
+    fn test_import_json_array() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        let input = format!(
+            r#"[
+            {{"uuid": "{}", "description": "repair window", "status": "pending",
+              "entry": "20211117T022410Z", "tags": ["house"]}},
+            {{"description": "no uuid"}}
+            ]"#,
+            UUID
+        );
+        let summary = import_str(&mut w, &mut replica, &input, ImportFormat::Auto).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                added: 1,
+                updated: 0,
+                skipped: 1
+            }
+        );
+        assert_eq!(w.into_string(), "Skipping task 2: task has no uuid\n");
+
+        let task = replica
+            .get_task(Uuid::parse_str(UUID).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.get_description(), "repair window");
+        assert!(task.has_tag(&tag!("house")));
+
+        // importing the same input again changes nothing
+        let mut w = test_writer();
+        let summary = import_str(&mut w, &mut replica, &input, ImportFormat::Auto).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                added: 0,
+                updated: 0,
+                skipped: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_import_json_lines() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let uuid = Uuid::parse_str(UUID).unwrap();
+        replica.import_task_with_uuid(uuid).unwrap();
+
+        let input = format!(
+            "{{\"uuid\": \"{}\", \"description\": \"repair window\"}}\n\nnot json\n",
+            UUID
+        );
+        let summary = import_str(&mut w, &mut replica, &input, ImportFormat::Auto).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                added: 0,
+                updated: 1,
+                skipped: 1
+            }
+        );
+        assert!(w.into_string().starts_with("Skipping line 3: Invalid JSON"));
+
+        let task = replica.get_task(uuid).unwrap().unwrap();
+        assert_eq!(task.get_description(), "repair window");
+    }
+
+    #[test]
+    fn test_import_lines() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        let input = "# shopping\nbuy milk +shopping priority:H\n\n  walk dog\n+notag\n";
+        let summary = import_str(&mut w, &mut replica, input, ImportFormat::Auto).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                added: 2,
+                updated: 0,
+                skipped: 1
+            }
+        );
+        assert_eq!(w.into_string(), "Skipping line 5: no description\n");
+
+        let mut tasks: Vec<_> = replica
+            .all_tasks()
+            .unwrap()
+            .into_iter()
+            .map(|(_, t)| t)
+            .collect();
+        tasks.sort_by(|a, b| a.get_description().cmp(b.get_description()));
+        assert_eq!(tasks[0].get_description(), "buy milk");
+        assert!(tasks[0].has_tag(&tag!("shopping")));
+        assert_eq!(tasks[0].get_priority(), "H");
+        assert_eq!(tasks[1].get_description(), "walk dog");
+    }
+
+    #[test]
+    fn test_import_lines_failed_modification() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        // a recurring task needs a due date, and synthetic tags cannot be added
+        let input = "water plants recur:1w\nwalk dog +PENDING\nbuy milk\n";
+        let summary = import_str(&mut w, &mut replica, input, ImportFormat::Auto).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                added: 1,
+                updated: 0,
+                skipped: 2
+            }
+        );
+        let output = w.into_string();
+        assert!(output.contains("Skipping line 1: A recurring task must have a due date"));
+        assert!(output.contains("Skipping line 2: "));
+
+        // nothing is left behind by the skipped lines
+        let tasks = replica.all_tasks().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks.values().next().unwrap().get_description(), "buy milk");
+    }
+
+    #[test]
+    fn test_import_todotxt() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+
+        let input =
+            "(A) call plumber +home @phone due:2022-01-05\n\nx 2022-01-03 mow lawn\n+home\n";
+        let summary = import_str(&mut w, &mut replica, input, ImportFormat::TodoTxt).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                added: 2,
+                updated: 0,
+                skipped: 1
+            }
+        );
+        assert_eq!(w.into_string(), "Skipping line 4: no description\n");
+
+        let mut tasks: Vec<_> = replica
+            .all_tasks()
+            .unwrap()
+            .into_iter()
+            .map(|(_, t)| t)
+            .collect();
+        tasks.sort_by(|a, b| a.get_description().cmp(b.get_description()));
+        assert_eq!(tasks[0].get_description(), "call plumber");
+        assert_eq!(tasks[0].get_priority(), "H");
+        assert_eq!(tasks[0].get_project(), Some("home"));
+        assert!(tasks[0].has_tag(&tag!("phone")));
+        assert!(tasks[0].get_due().is_some());
+        assert_eq!(tasks[1].get_description(), "mow lawn");
+        assert_eq!(tasks[1].get_status(), Status::Completed);
+    }
+
+    #[test]
+    fn test_import_todotxt_failed_apply() {
+        let mut replica = test_replica();
+
+        // synthetic tags cannot be added
+        let todo = TodoTxt {
+            description: s!("call plumber"),
+            contexts: vec![tag!("PENDING")],
+            ..TodoTxt::default()
+        };
+        assert!(import_todotxt(&mut replica, todo).is_err());
+
+        // nothing is left behind
+        assert!(replica.all_tasks().unwrap().is_empty());
+    }
+}
@@ -1,9 +1,12 @@
+use crate::argparse::parse_duration;
 use anyhow::{anyhow, bail};
 use serde::{self, Deserialize, Deserializer};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use taskchampion::chrono::{DateTime, TimeZone, Utc};
-use taskchampion::{Replica, Uuid};
+use taskchampion::schema::{Key, Value as _};
+use taskchampion::{Replica, Tag, Uuid};
 use termcolor::{Color, ColorSpec, WriteColor};
 
 pub(crate) fn execute<W: WriteColor>(w: &mut W, replica: &mut Replica) -> Result<(), crate::Error> {
@@ -55,7 +58,7 @@ struct TwDateTime(#[serde(deserialize_with = "deserialize_tw_datetime")] DateTim
 impl TwDateTime {
     /// Generate the data-model style UNIX timestamp for this DateTime
     fn tc_timestamp(&self) -> String {
-        self.0.timestamp().to_string()
+        self.0.to_value()
     }
 }
 
@@ -65,18 +68,43 @@ struct Annotation {
     description: String,
 }
 
-fn import_task<W: WriteColor>(
-    w: &mut W,
-    replica: &mut Replica,
-    mut task_json: HashMap<String, Value>,
-) -> anyhow::Result<()> {
+/// Get the UUID of a task in the TaskWarrior JSON format
+pub(super) fn task_json_uuid(task_json: &HashMap<String, Value>) -> anyhow::Result<Uuid> {
     let uuid = task_json
         .get("uuid")
         .ok_or_else(|| anyhow!("task has no uuid"))?;
     let uuid = uuid
         .as_str()
         .ok_or_else(|| anyhow!("uuid is not a string"))?;
-    let uuid = Uuid::parse_str(uuid)?;
+    Ok(Uuid::parse_str(uuid)?)
+}
+
+fn import_task<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    task_json: HashMap<String, Value>,
+) -> anyhow::Result<()> {
+    let (uuid, description) = import_task_json(replica, task_json)?;
+
+    w.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+    write!(w, "{}", uuid)?;
+    w.reset()?;
+    writeln!(
+        w,
+        " {}",
+        description.unwrap_or_else(|| "(no description)".into())
+    )?;
+
+    Ok(())
+}
+
+/// Import a single task in the TaskWarrior JSON format, creating it or merging it into an
+/// existing task with the same UUID.  Returns the UUID and description of the task.
+pub(super) fn import_task_json(
+    replica: &mut Replica,
+    mut task_json: HashMap<String, Value>,
+) -> anyhow::Result<(Uuid, Option<String>)> {
+    let uuid = task_json_uuid(&task_json)?;
     replica.import_task_with_uuid(uuid)?;
 
     let mut description = None;
@@ -95,7 +123,7 @@ fn import_task<W: WriteColor>(
             "annotations" => {
                 let annotations: Vec<Annotation> = serde_json::from_value(v)?;
                 for ann in annotations {
-                    let k = format!("annotation_{}", ann.entry.tc_timestamp());
+                    let k = Key::Annotation(ann.entry.0).to_string();
                     replica.update_task(uuid, k, Some(ann.description))?;
                 }
             }
@@ -104,7 +132,7 @@ fn import_task<W: WriteColor>(
             "depends" => {
                 let deps: Vec<String> = serde_json::from_value(v)?;
                 for dep in deps {
-                    let k = format!("dep_{}", dep);
+                    let k = Key::Dependency(Uuid::parse_str(&dep)?).to_string();
                     replica.update_task(uuid, k, Some("".to_owned()))?;
                 }
             }
@@ -113,17 +141,28 @@ fn import_task<W: WriteColor>(
             "tags" => {
                 let tags: Vec<String> = serde_json::from_value(v)?;
                 for tag in tags {
-                    let k = format!("tag_{}", tag);
+                    let k = Key::Tag(Tag::try_from(&tag)?).to_string();
                     replica.update_task(uuid, k, Some("".to_owned()))?;
                 }
             }
 
             // convert all datetimes -> epoch integers
-            "end" | "entry" | "modified" | "wait" | "due" => {
+            "end" | "entry" | "modified" | "start" | "wait" | "due" | "scheduled" | "until" => {
                 let v: TwDateTime = serde_json::from_value(v)?;
                 replica.update_task(uuid, k, Some(v.tc_timestamp()))?;
             }
 
+            // convert durations such as `P7D` to seconds; named recurrences such as `monthly`
+            // are not supported, and are stored unchanged
+            "recur" => {
+                let v = stringify(v)?;
+                let v = match parse_duration(&v) {
+                    Some(dur) => dur.to_value(),
+                    None => v,
+                };
+                replica.update_task(uuid, k, Some(v))?;
+            }
+
             // everything else is inserted directly
             _ => {
                 let v = stringify(v)?;
@@ -136,16 +175,7 @@ fn import_task<W: WriteColor>(
         }
     }
 
-    w.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
-    write!(w, "{}", uuid)?;
-    w.reset()?;
-    writeln!(
-        w,
-        " {}",
-        description.unwrap_or_else(|| "(no description)".into())
-    )?;
-
-    Ok(())
+    Ok((uuid, description))
 }
 
 #[cfg(test)]
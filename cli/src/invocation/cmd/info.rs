@@ -1,18 +1,103 @@
-use crate::argparse::Filter;
+use super::export::task_to_json;
+use crate::argparse::{Filter, OutputFormat};
 use crate::invocation::filtered_tasks;
+use crate::invocation::report::{csv_row, markdown_row, markdown_separator};
+use crate::invocation::urgency::{urgency, urgency_terms};
+use crate::settings::Settings;
 use crate::table;
 use prettytable::{cell, row, Table};
-use taskchampion::{Replica, Status};
+use serde_json::{json, Value};
+use taskchampion::chrono::Utc;
+use taskchampion::{Replica, Status, Task, WorkingSet};
 use termcolor::WriteColor;
 
+/// Convert a task into a JSON object for machine-readable output.  This is the same format as
+/// `ta export`, with the addition of the task's urgency, or with `debug` the raw key/value pairs.
+fn task_info_json(task: &Task, working_set: &WorkingSet, debug: bool) -> Value {
+    if debug {
+        let mut obj = json!(task.get_taskmap());
+        obj["uuid"] = json!(task.get_uuid().to_string());
+        obj
+    } else {
+        let mut obj = task_to_json(task, working_set);
+        obj["urgency"] = json!(urgency(task, Utc::now()));
+        obj
+    }
+}
+
+/// Write the task's raw key/value pairs as tab-separated values, one per line, prefixed with the
+/// task's UUID.
+fn write_info_tsv<W: WriteColor>(w: &mut W, task: &Task) -> Result<(), crate::Error> {
+    write_info_rows(w, task, |row| {
+        row.iter()
+            .map(|v| v.replace(&['\t', '\n', '\r'][..], " "))
+            .collect::<Vec<_>>()
+            .join("\t")
+    })
+}
+
+/// Write the task's raw key/value pairs, one per line, prefixed with the task's UUID and
+/// formatted with `format_row`.
+fn write_info_rows<W: WriteColor, F: Fn(&[&str]) -> String>(
+    w: &mut W,
+    task: &Task,
+    format_row: F,
+) -> Result<(), crate::Error> {
+    let uuid = task.get_uuid().to_string();
+    let mut keys: Vec<_> = task.get_taskmap().keys().collect();
+    keys.sort();
+    for k in keys {
+        let v = &task.get_taskmap()[k];
+        writeln!(w, "{}", format_row(&[&uuid, k, v]))?;
+    }
+    Ok(())
+}
+
 pub(crate) fn execute<W: WriteColor>(
     w: &mut W,
     replica: &mut Replica,
+    settings: &Settings,
     filter: Filter,
     debug: bool,
+    format: OutputFormat,
 ) -> Result<(), crate::Error> {
     let working_set = replica.working_set()?;
 
+    match format {
+        OutputFormat::Table => {}
+        OutputFormat::Json => {
+            let tasks: Vec<_> = filtered_tasks(replica, &filter)?
+                .map(|task| task_info_json(&task, &working_set, debug))
+                .collect();
+            writeln!(w, "{:#}", Value::Array(tasks))?;
+            return Ok(());
+        }
+        OutputFormat::Tsv => {
+            writeln!(w, "uuid\tkey\tvalue")?;
+            for task in filtered_tasks(replica, &filter)? {
+                write_info_tsv(w, &task)?;
+            }
+            return Ok(());
+        }
+        OutputFormat::Csv => {
+            writeln!(w, "{}", csv_row(&["uuid", "key", "value"]))?;
+            for task in filtered_tasks(replica, &filter)? {
+                write_info_rows(w, &task, |row| csv_row(row))?;
+            }
+            return Ok(());
+        }
+        OutputFormat::Markdown => {
+            writeln!(w, "{}", markdown_row(&["uuid", "key", "value"]))?;
+            writeln!(w, "{}", markdown_separator(3))?;
+            for task in filtered_tasks(replica, &filter)? {
+                write_info_rows(w, &task, |row| markdown_row(row))?;
+            }
+            return Ok(());
+        }
+    }
+
+    let now = Utc::now();
+    let dates = &settings.dates;
     for task in filtered_tasks(replica, &filter)? {
         let uuid = task.get_uuid();
 
@@ -31,38 +116,87 @@ pub(crate) fn execute<W: WriteColor>(
             t.add_row(row![b->"Description", task.get_description()]);
             t.add_row(row![b->"Status", task.get_status()]);
             t.add_row(row![b->"Active", task.is_active()]);
+            if let Some(project) = task.get_project() {
+                t.add_row(row![b->"Project", project]);
+            }
+            if !task.get_priority().is_empty() {
+                t.add_row(row![b->"Priority", task.get_priority()]);
+            }
             let mut tags: Vec<_> = task.get_tags().map(|t| format!("+{}", t)).collect();
             if !tags.is_empty() {
                 tags.sort();
                 t.add_row(row![b->"Tags", tags.join(" ")]);
             }
+            if let Some(entry) = task.get_entry() {
+                t.add_row(row![b->"Entered", dates.datetime(entry, now)]);
+            }
+            if let Some(modified) = task.get_modified() {
+                t.add_row(row![b->"Modified", dates.datetime(modified, now)]);
+            }
             if let Some(wait) = task.get_wait() {
-                t.add_row(row![b->"Wait", wait]);
+                t.add_row(row![b->"Wait", dates.datetime(wait, now)]);
+            }
+            if let Some(scheduled) = task.get_scheduled() {
+                t.add_row(row![b->"Scheduled", dates.datetime(scheduled, now)]);
+            }
+            if let Some(due) = task.get_due() {
+                t.add_row(row![b->"Due", dates.datetime(due, now)]);
+            }
+            if let Some(until) = task.get_until() {
+                t.add_row(row![b->"Until", dates.datetime(until, now)]);
             }
             let mut annotations: Vec<_> = task.get_annotations().collect();
             annotations.sort();
             for ann in annotations {
-                t.add_row(row![b->"Annotation", format!("{}: {}", ann.entry, ann.description)]);
+                t.add_row(row![b->"Annotation", format!("{}: {}", dates.datetime(ann.entry, now), ann.description)]);
+            }
+            let mut links: Vec<_> = task.links().collect();
+            links.sort();
+            for link in links {
+                if let Some(label) = link.label {
+                    t.add_row(row![b->"Link", format!("{} <{}>", label, link.url)]);
+                } else {
+                    t.add_row(row![b->"Link", link.url]);
+                }
             }
 
             let mut deps: Vec<_> = task.get_dependencies().collect();
             deps.sort();
             for dep in deps {
-                let mut descr = None;
-                if let Some(task) = replica.get_task(dep)? {
-                    if task.get_status() == Status::Pending {
-                        if let Some(i) = working_set.by_uuid(dep) {
-                            descr = Some(format!("{} - {}", i, task.get_description()))
-                        } else {
-                            descr = Some(format!("{} - {}", dep, task.get_description()))
+                let descr = match replica.get_task(dep)? {
+                    Some(task) => {
+                        let descr = match working_set.by_uuid(dep) {
+                            Some(i) => format!("{} - {}", i, task.get_description()),
+                            None => format!("{} - {}", dep, task.get_description()),
+                        };
+                        // completed or deleted dependencies no longer block this task
+                        match task.get_status() {
+                            Status::Pending => descr,
+                            status => format!("{} ({})", descr, status),
                         }
                     }
-                }
+                    None => format!("{} (missing)", dep),
+                };
+                t.add_row(row![b->"Depends On", descr]);
+            }
 
-                if let Some(descr) = descr {
-                    t.add_row(row![b->"Depends On", descr]);
-                }
+            let now = Utc::now();
+            t.add_row(row![b->"Urgency", format!("{:.2}", urgency(&task, now))]);
+            for term in urgency_terms(&task, now) {
+                t.add_row(row![
+                    "",
+                    format!(
+                        "{:<12} {:>4.2} * {:>5.1} = {:>6.2}",
+                        term.name,
+                        term.factor,
+                        term.coefficient,
+                        term.value()
+                    )
+                ]);
             }
+
+            let unsynced = replica.has_local_changes(uuid)?;
+            t.add_row(row![b->"Unsynced Changes", if unsynced { "yes" } else { "no" }]);
         }
         t.print(w)?;
     }
@@ -88,7 +222,15 @@ mod test {
             ..Default::default()
         };
         let debug = false;
-        execute(&mut w, &mut replica, filter, debug).unwrap();
+        execute(
+            &mut w,
+            &mut replica,
+            &Settings::default(),
+            filter,
+            debug,
+            OutputFormat::Table,
+        )
+        .unwrap();
         assert!(w.into_string().contains("my task"));
     }
 
@@ -108,10 +250,111 @@ mod test {
             conditions: vec![Condition::IdList(vec![TaskId::Uuid(t2.get_uuid())])],
         };
         let debug = false;
-        execute(&mut w, &mut replica, filter, debug).unwrap();
+        execute(
+            &mut w,
+            &mut replica,
+            &Settings::default(),
+            filter,
+            debug,
+            OutputFormat::Table,
+        )
+        .unwrap();
         let s = w.into_string();
         // length of whitespace between these two strings is not important
         assert!(s.contains("Depends On"));
         assert!(s.contains("1 - my task"));
     }
+
+    #[test]
+    fn info_json() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let t = replica.new_task(Status::Pending, s!("my task")).unwrap();
+        let mut t = t.into_mut(&mut replica);
+        t.set_priority(s!("H")).unwrap();
+
+        execute(
+            &mut w,
+            &mut replica,
+            &Settings::default(),
+            Filter::default(),
+            false,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        let tasks: Vec<Value> = serde_json::from_str(&w.into_string()).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0]["description"], json!("my task"));
+        assert_eq!(tasks[0]["priority"], json!("H"));
+        assert!(tasks[0]["urgency"].as_f64().unwrap() >= 6.0);
+    }
+
+    #[test]
+    fn info_tsv() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let t = replica.new_task(Status::Pending, s!("my\ttask")).unwrap();
+        let uuid = t.get_uuid();
+
+        execute(
+            &mut w,
+            &mut replica,
+            &Settings::default(),
+            Filter::default(),
+            false,
+            OutputFormat::Tsv,
+        )
+        .unwrap();
+        let s = w.into_string();
+        let mut lines = s.lines();
+        assert_eq!(lines.next(), Some("uuid\tkey\tvalue"));
+        assert!(s.contains(&format!("{}\tdescription\tmy task\n", uuid)));
+        assert!(s.contains(&format!("{}\tstatus\tpending\n", uuid)));
+    }
+
+    #[test]
+    fn info_csv() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let t = replica.new_task(Status::Pending, s!("my, task")).unwrap();
+        let uuid = t.get_uuid();
+
+        execute(
+            &mut w,
+            &mut replica,
+            &Settings::default(),
+            Filter::default(),
+            false,
+            OutputFormat::Csv,
+        )
+        .unwrap();
+        let s = w.into_string();
+        let mut lines = s.lines();
+        assert_eq!(lines.next(), Some("uuid,key,value"));
+        assert!(s.contains(&format!("{},description,\"my, task\"\n", uuid)));
+        assert!(s.contains(&format!("{},status,pending\n", uuid)));
+    }
+
+    #[test]
+    fn info_markdown() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let t = replica.new_task(Status::Pending, s!("my | task")).unwrap();
+        let uuid = t.get_uuid();
+
+        execute(
+            &mut w,
+            &mut replica,
+            &Settings::default(),
+            Filter::default(),
+            false,
+            OutputFormat::Markdown,
+        )
+        .unwrap();
+        let s = w.into_string();
+        let mut lines = s.lines();
+        assert_eq!(lines.next(), Some("| uuid | key | value |"));
+        assert_eq!(lines.next(), Some("| --- | --- | --- |"));
+        assert!(s.contains(&format!("| {} | description | my \\| task |\n", uuid)));
+    }
 }
@@ -1,14 +1,31 @@
 //! Responsible for executing commands as parsed by [`crate::argparse`].
 
 pub(crate) mod add;
+pub(crate) mod burndown;
+pub(crate) mod calendar;
+pub(crate) mod completions;
 pub(crate) mod config;
+pub(crate) mod context;
+pub(crate) mod count;
+pub(crate) mod duplicate;
+pub(crate) mod edit;
+pub(crate) mod export;
 pub(crate) mod gc;
 pub(crate) mod help;
+pub(crate) mod ids;
+pub(crate) mod import;
 pub(crate) mod import_tdb2;
 pub(crate) mod import_tw;
 pub(crate) mod info;
 pub(crate) mod modify;
+pub(crate) mod pick;
+pub(crate) mod projects;
+pub(crate) mod prompt;
+pub(crate) mod reminders;
 pub(crate) mod report;
+pub(crate) mod show;
+pub(crate) mod stats;
 pub(crate) mod sync;
+pub(crate) mod tags;
 pub(crate) mod undo;
 pub(crate) mod version;
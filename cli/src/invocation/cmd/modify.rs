@@ -1,22 +1,40 @@
 use crate::argparse::Filter;
 use crate::invocation::util::{confirm, summarize_task};
-use crate::invocation::{apply_modification, filtered_tasks, ResolvedModification};
+use crate::invocation::{
+    apply_modification, describe_changes, filtered_tasks, preview_modification,
+    ResolvedModification,
+};
 use crate::settings::Settings;
-use taskchampion::Replica;
+use taskchampion::{Replica, Task};
 use termcolor::WriteColor;
 
-/// confirm modification of more than `modificationt_count_prompt` tasks, defaulting to 3
+/// The number of matching tasks to list before prompting for confirmation
+const MAX_LISTED_TASKS: usize = 10;
+
+/// confirm modification of more than `modification_count_prompt` tasks, defaulting to 3, listing
+/// the tasks that would be modified.  The prompt is skipped if `yes` is true or the
+/// `confirmation` setting is false.
 fn check_modification<W: WriteColor>(
     w: &mut W,
+    replica: &mut Replica,
     settings: &Settings,
-    affected_tasks: usize,
+    tasks: &[Task],
+    yes: bool,
 ) -> Result<bool, crate::Error> {
     let setting = settings.modification_count_prompt.unwrap_or(3);
-    if setting == 0 || affected_tasks <= setting as usize {
+    if yes || !settings.confirmation || setting == 0 || tasks.len() <= setting as usize {
         return Ok(true);
     }
 
-    let prompt = format!("Operation will modify {} tasks; continue?", affected_tasks,);
+    writeln!(w, "The filter matches these tasks:")?;
+    for task in tasks.iter().take(MAX_LISTED_TASKS) {
+        writeln!(w, "  {}", summarize_task(replica, task)?)?;
+    }
+    if tasks.len() > MAX_LISTED_TASKS {
+        writeln!(w, "  ..and {} more", tasks.len() - MAX_LISTED_TASKS)?;
+    }
+
+    let prompt = format!("Operation will modify {} tasks; continue?", tasks.len());
     if confirm(&prompt)? {
         return Ok(true);
     }
@@ -32,9 +50,12 @@ fn check_modification<W: WriteColor>(
         writeln!(
             w,
             "    ta config set modification_count_prompt {}",
-            affected_tasks + 1
+            tasks.len() + 1
+        )?;
+        writeln!(
+            w,
+            "Set it to 0 to disable the prompt entirely, or use `ta --yes`"
         )?;
-        writeln!(w, "Set it to 0 to disable the prompt entirely")?;
     }
     Ok(false)
 }
@@ -45,10 +66,24 @@ pub(in crate::invocation) fn execute<W: WriteColor>(
     settings: &Settings,
     filter: Filter,
     modification: ResolvedModification,
+    yes: bool,
+    dry_run: bool,
 ) -> Result<(), crate::Error> {
-    let tasks = filtered_tasks(replica, &filter)?;
+    let tasks: Vec<_> = filtered_tasks(replica, &filter)?.collect();
+
+    if dry_run {
+        for task in tasks {
+            let preview = preview_modification(&task, &modification)?;
+            writeln!(w, "would modify task {}", summarize_task(replica, &task)?)?;
+            for change in describe_changes(&task, &preview) {
+                writeln!(w, "  {}", change)?;
+            }
+        }
+        writeln!(w, "Dry run; no changes were made.")?;
+        return Ok(());
+    }
 
-    if !check_modification(w, settings, tasks.size_hint().0)? {
+    if !check_modification(w, replica, settings, &tasks, yes)? {
         return Ok(());
     }
 
@@ -91,7 +126,16 @@ mod test {
             description: DescriptionMod::Set(s!("new description")),
             ..Default::default()
         });
-        execute(&mut w, &mut replica, &settings, filter, modification).unwrap();
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            filter,
+            modification,
+            false,
+            false,
+        )
+        .unwrap();
 
         // check that the task appeared..
         let task = replica.get_task(task.get_uuid()).unwrap().unwrap();
@@ -103,4 +147,62 @@ mod test {
             format!("modified task 1 - new description\n")
         );
     }
+
+    #[test]
+    fn test_modify_dry_run() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let settings = Settings::default();
+
+        let task = replica
+            .new_task(Status::Pending, s!("old description"))
+            .unwrap();
+
+        let modification = ResolvedModification(Modification {
+            status: Some(Status::Completed),
+            ..Default::default()
+        });
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            Filter::default(),
+            modification,
+            false,
+            true,
+        )
+        .unwrap();
+
+        // the task is unchanged..
+        let task = replica.get_task(task.get_uuid()).unwrap().unwrap();
+        assert_eq!(task.get_status(), Status::Pending);
+
+        // ..but the change is described
+        let output = w.into_string();
+        assert!(output.starts_with("would modify task 1 - old description\n"));
+        assert!(output.contains("  status: \"pending\" -> \"completed\"\n"));
+        assert!(output.ends_with("Dry run; no changes were made.\n"));
+    }
+
+    #[test]
+    fn test_check_modification() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let mut settings = Settings::default();
+        for i in 0..5 {
+            replica
+                .new_task(Status::Pending, format!("t{}", i))
+                .unwrap();
+        }
+        let tasks: Vec<_> = filtered_tasks(&mut replica, &Filter::default())
+            .unwrap()
+            .collect();
+
+        // few enough tasks, or confirmation disabled, proceeds without a prompt
+        assert!(check_modification(&mut w, &mut replica, &settings, &tasks[..3], false).unwrap());
+        assert!(check_modification(&mut w, &mut replica, &settings, &tasks, true).unwrap());
+        settings.confirmation = false;
+        assert!(check_modification(&mut w, &mut replica, &settings, &tasks, false).unwrap());
+        assert_eq!(w.into_string(), "");
+    }
 }
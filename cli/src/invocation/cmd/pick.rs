@@ -0,0 +1,212 @@
+use crate::argparse::{Condition, Filter};
+use crate::invocation::filtered_tasks;
+use anyhow::anyhow;
+use console::{style, Key, Term};
+use taskchampion::{Replica, Status, Task, Uuid};
+use termcolor::WriteColor;
+
+/// The maximum number of matching tasks to show at once
+const MAX_SHOWN: usize = 10;
+
+/// Score how well `query` matches `text`, ignoring case, or return None if the characters of
+/// the query do not all appear in the text, in order.  Matches of consecutive characters and
+/// matches at the beginning of words score higher, and gaps between matches score lower.
+fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last_match: Option<usize> = None;
+
+    for q in query.chars().flat_map(char::to_lowercase) {
+        if q.is_whitespace() {
+            continue;
+        }
+        let offset = text[pos..].iter().position(|c| *c == q)?;
+        let i = pos + offset;
+
+        score += 1;
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 8;
+        }
+        match last_match {
+            Some(last) if last + 1 == i => score += 5,
+            Some(last) => score -= (i - last - 1).min(10) as i64,
+            None => {}
+        }
+
+        last_match = Some(i);
+        pos = i + 1;
+    }
+
+    Some(score)
+}
+
+/// Return the indexes of the texts matching `query`, best match first.  Texts with the same
+/// score remain in their original order.
+fn fuzzy_filter(query: &str, texts: &[String]) -> Vec<usize> {
+    let mut matches: Vec<(i64, usize)> = texts
+        .iter()
+        .enumerate()
+        .filter_map(|(i, text)| fuzzy_score(query, text).map(|score| (-score, i)))
+        .collect();
+    matches.sort();
+    matches.into_iter().map(|(_, i)| i).collect()
+}
+
+/// Describe the task in a single line, for display and matching
+fn describe(id: Option<usize>, task: &Task) -> String {
+    let mut text = match id {
+        Some(id) => format!("{} {}", id, task.get_description()),
+        None => format!(
+            "{} {}",
+            &task.get_uuid().to_string()[..8],
+            task.get_description()
+        ),
+    };
+    if let Some(project) = task.get_project() {
+        text.push_str(&format!(" project:{}", project));
+    }
+    for tag in task.get_tags().filter(|t| t.is_user()) {
+        text.push_str(&format!(" +{}", tag));
+    }
+    text
+}
+
+/// Interactively select one of the texts, returning its index, or None if the selection was
+/// cancelled.
+fn select(term: &Term, texts: &[String]) -> anyhow::Result<Option<usize>> {
+    let mut query = String::new();
+    let mut selected = 0;
+    let mut drawn = 0;
+
+    let result = loop {
+        let matches = fuzzy_filter(&query, texts);
+        selected = selected.min(matches.len().saturating_sub(1));
+        let first = selected.saturating_sub(MAX_SHOWN - 1);
+        let width = term.size().1 as usize;
+
+        term.clear_last_lines(drawn)?;
+        term.write_line(&format!(
+            "{} {}  ({}/{})",
+            style(">").bold(),
+            query,
+            matches.len(),
+            texts.len()
+        ))?;
+        drawn = 1;
+        for (i, &m) in matches.iter().enumerate().skip(first).take(MAX_SHOWN) {
+            let line: String = texts[m].chars().take(width.saturating_sub(3)).collect();
+            if i == selected {
+                term.write_line(&format!("{} {}", style(">").bold(), style(line).reverse()))?;
+            } else {
+                term.write_line(&format!("  {}", line))?;
+            }
+            drawn += 1;
+        }
+
+        match term.read_key()? {
+            Key::Enter => {
+                if let Some(&m) = matches.get(selected) {
+                    break Some(m);
+                }
+            }
+            Key::Escape | Key::Char('\u{3}') | Key::Char('\u{4}') => break None,
+            Key::Backspace => {
+                query.pop();
+                selected = 0;
+            }
+            Key::ArrowUp | Key::BackTab => selected = selected.saturating_sub(1),
+            Key::ArrowDown | Key::Tab => selected += 1,
+            Key::Char(c) if !c.is_control() => {
+                query.push(c);
+                selected = 0;
+            }
+            _ => {}
+        }
+    };
+
+    term.clear_last_lines(drawn)?;
+    Ok(result)
+}
+
+/// Interactively pick one of the tasks matching the filter, returning its uuid.  If `print` is
+/// true, the uuid is also written to the output.
+pub(in crate::invocation) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    mut filter: Filter,
+    print: bool,
+) -> Result<Option<Uuid>, crate::Error> {
+    // with no filter, offer only pending tasks
+    if filter.conditions.is_empty() {
+        filter.conditions.push(Condition::Status(Status::Pending));
+    }
+
+    let term = Term::stderr();
+    if !term.is_term() {
+        return Err(anyhow!("`pick` requires a terminal").into());
+    }
+
+    let working_set = replica.working_set()?;
+
+    // order by working-set id, with tasks not in the working set last
+    let mut tasks: Vec<_> = filtered_tasks(replica, &filter)?
+        .map(|t| {
+            let id = working_set.by_uuid(t.get_uuid());
+            (id.unwrap_or(usize::MAX), t.get_uuid(), describe(id, &t))
+        })
+        .collect();
+    tasks.sort();
+    if tasks.is_empty() {
+        return Err(anyhow!("No tasks match the filter").into());
+    }
+
+    let texts: Vec<String> = tasks.iter().map(|(_, _, text)| text.clone()).collect();
+    let uuid = select(&term, &texts)?.map(|i| tasks[i].1);
+
+    if let (Some(uuid), true) = (uuid, print) {
+        writeln!(w, "{}", uuid)?;
+    }
+    Ok(uuid)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "buy milk"), None);
+        assert_eq!(fuzzy_score("mb", "buy milk"), None);
+        assert!(fuzzy_score("BM", "buy milk").is_some());
+        // consecutive and word-start matches score higher than scattered matches
+        assert!(fuzzy_score("mil", "buy milk") > fuzzy_score("mil", "my mail"));
+        assert!(fuzzy_score("bm", "buy milk") > fuzzy_score("bm", "submarine"));
+    }
+
+    #[test]
+    fn test_fuzzy_filter() {
+        let texts = vec![s!("1 my mail"), s!("2 buy milk"), s!("3 walk dog")];
+        assert_eq!(fuzzy_filter("mil", &texts), vec![1, 0]);
+        assert_eq!(fuzzy_filter("", &texts), vec![0, 1, 2]);
+        assert_eq!(fuzzy_filter("cat", &texts), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut replica = test_replica();
+        let mut task = replica
+            .new_task(Status::Pending, s!("buy milk"))
+            .unwrap()
+            .into_mut(&mut replica);
+        task.set_project(Some(s!("home"))).unwrap();
+        task.add_tag(&tag!("shop")).unwrap();
+        let task = task.into_immut();
+
+        assert_eq!(describe(Some(3), &task), "3 buy milk project:home +shop");
+        assert!(describe(None, &task).starts_with(&task.get_uuid().to_string()[..8]));
+    }
+}
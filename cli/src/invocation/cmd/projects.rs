@@ -0,0 +1,135 @@
+use crate::table;
+use prettytable::{cell, row, Table};
+use std::collections::{BTreeMap, HashMap};
+use taskchampion::{ProjectSummary, Replica};
+use termcolor::WriteColor;
+
+/// Arrange the projects into a hierarchy, returning the depth, final component of the name, and
+/// summary (including sub-projects) of each project, in display order.  Parent projects which
+/// have no tasks of their own are included.
+fn project_tree(
+    projects: &HashMap<String, ProjectSummary>,
+) -> Vec<(usize, String, ProjectSummary)> {
+    // keying by the components of the name ensures that sub-projects sort immediately after
+    // their parents
+    let mut tree: BTreeMap<Vec<&str>, ProjectSummary> = BTreeMap::new();
+    for (name, summary) in projects {
+        let components: Vec<&str> = name.split('.').collect();
+        for i in 1..=components.len() {
+            tree.entry(components[..i].to_vec())
+                .or_default()
+                .add(summary);
+        }
+    }
+
+    tree.into_iter()
+        .map(|(components, summary)| {
+            let depth = components.len() - 1;
+            (depth, components[depth].to_owned(), summary)
+        })
+        .collect()
+}
+
+pub(crate) fn execute<W: WriteColor>(w: &mut W, replica: &mut Replica) -> Result<(), crate::Error> {
+    let projects = replica.projects()?;
+    if projects.is_empty() {
+        writeln!(w, "No projects.")?;
+        return Ok(());
+    }
+
+    let mut t = Table::new();
+    t.set_format(table::format());
+    t.set_titles(row![b->"Project", b->"Pending", b->"Completed", b->"Complete"]);
+    for (depth, name, summary) in project_tree(&projects) {
+        t.add_row(row![
+            format!("{}{}", "  ".repeat(depth), name),
+            r->summary.pending,
+            r->summary.completed,
+            r->format!("{:.0}%", summary.fraction_complete() * 100.0)
+        ]);
+    }
+    t.print(w)?;
+    writeln!(w, "{} projects", projects.len())?;
+
+    Ok(())
+}
+
+/// Rename a project, and its sub-projects, on all tasks
+pub(crate) fn rename<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    old: &str,
+    new: &str,
+) -> Result<(), crate::Error> {
+    let count = replica.rename_project(old, new)?;
+    writeln!(
+        w,
+        "renamed project {} to {} on {} task{}",
+        old,
+        new,
+        count,
+        if count == 1 { "" } else { "s" }
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+    use taskchampion::Status;
+
+    fn summary(pending: usize, completed: usize) -> ProjectSummary {
+        ProjectSummary { pending, completed }
+    }
+
+    #[test]
+    fn test_project_tree() {
+        let mut projects = HashMap::new();
+        projects.insert(s!("home.garden"), summary(2, 1));
+        projects.insert(s!("home-office"), summary(1, 0));
+        projects.insert(s!("home"), summary(1, 1));
+        projects.insert(s!("work.meetings.weekly"), summary(0, 3));
+
+        assert_eq!(
+            project_tree(&projects),
+            vec![
+                (0, s!("home"), summary(3, 2)),
+                (1, s!("garden"), summary(2, 1)),
+                (0, s!("home-office"), summary(1, 0)),
+                (0, s!("work"), summary(0, 3)),
+                (1, s!("meetings"), summary(0, 3)),
+                (2, s!("weekly"), summary(0, 3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_projects() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        execute(&mut w, &mut replica).unwrap();
+        assert_eq!(&w.into_string(), "No projects.\n");
+    }
+
+    #[test]
+    fn test_rename() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        for &project in &["home", "home.garden", "work"] {
+            let t = replica.new_task(Status::Pending, s!("t")).unwrap();
+            let mut t = t.into_mut(&mut replica);
+            t.set_project(Some(project.to_owned())).unwrap();
+        }
+
+        rename(&mut w, &mut replica, "home", "house").unwrap();
+        assert_eq!(
+            w.into_string(),
+            "renamed project home to house on 2 tasks\n"
+        );
+        let mut projects: Vec<_> = replica.projects().unwrap().keys().cloned().collect();
+        projects.sort();
+        assert_eq!(projects, vec!["house", "house.garden", "work"]);
+    }
+}
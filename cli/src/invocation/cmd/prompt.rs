@@ -0,0 +1,94 @@
+use crate::settings::format_duration;
+use taskchampion::chrono::Utc;
+use taskchampion::{Replica, Status, Task};
+use termcolor::WriteColor;
+
+/// The longest description to include in the prompt, in characters
+const MAX_DESCRIPTION: usize = 30;
+
+/// Describe the most recently started of the given active tasks, or None if there are none.
+fn describe_active(mut active: Vec<Task>) -> Option<String> {
+    active.sort_by_key(|t| std::cmp::Reverse(t.get_start()));
+    let task = active.first()?;
+
+    let description = task.get_description();
+    let mut text = if description.chars().count() > MAX_DESCRIPTION {
+        let truncated: String = description.chars().take(MAX_DESCRIPTION - 3).collect();
+        format!("working on: {}...", truncated)
+    } else {
+        format!("working on: {}", description)
+    };
+    if let Some(start) = task.get_start() {
+        text.push_str(&format!(" ({})", format_duration(Utc::now() - start)));
+    }
+    if active.len() > 1 {
+        text.push_str(&format!(" +{} more", active.len() - 1));
+    }
+    Some(text)
+}
+
+pub(crate) fn execute<W: WriteColor>(w: &mut W, replica: &mut Replica) -> Result<(), crate::Error> {
+    let active: Vec<Task> = replica
+        .all_tasks()?
+        .into_iter()
+        .map(|(_, t)| t)
+        .filter(|t| t.get_status() == Status::Pending && t.is_active())
+        .collect();
+    if let Some(text) = describe_active(active) {
+        writeln!(w, "{}", text)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_prompt_none_active() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("idle")).unwrap();
+
+        execute(&mut w, &mut replica).unwrap();
+        assert_eq!(w.into_string(), "");
+    }
+
+    #[test]
+    fn test_prompt_active() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("idle")).unwrap();
+        let mut t = replica
+            .new_task(Status::Pending, s!("write report"))
+            .unwrap()
+            .into_mut(&mut replica);
+        t.start().unwrap();
+
+        execute(&mut w, &mut replica).unwrap();
+        let output = w.into_string();
+        assert!(output.starts_with("working on: write report ("));
+        assert!(output.ends_with("s)\n"));
+    }
+
+    #[test]
+    fn test_prompt_several_active() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        for descr in &["one", "a very long description that will not fit"] {
+            let mut t = replica
+                .new_task(Status::Pending, descr.to_string())
+                .unwrap()
+                .into_mut(&mut replica);
+            t.start().unwrap();
+        }
+
+        execute(&mut w, &mut replica).unwrap();
+        let output = w.into_string();
+        assert!(output.starts_with("working on: "));
+        assert!(output.ends_with(" +1 more\n"));
+        assert!(!output.contains("will not fit"));
+    }
+}
@@ -0,0 +1,310 @@
+use crate::argparse::{parse_duration, OutputFormat};
+use crate::invocation::report::{csv_row, markdown_row, markdown_separator};
+use crate::settings::{DateDisplay, Settings};
+use crate::table;
+use anyhow::{anyhow, bail, Context};
+use prettytable::{cell, row, Table};
+use serde_json::{json, Value};
+use std::process::Command;
+use taskchampion::chrono::{DateTime, Duration, Utc};
+use taskchampion::{Reminder, Replica, WorkingSet};
+use termcolor::WriteColor;
+
+/// The command used by `--notify` when the `reminder_command` setting is not set
+const DEFAULT_COMMAND: &str = "notify-send";
+
+/// Get the window in which to look for reminders: the `--within` option, the `reminder_window`
+/// setting, or one hour.
+fn window(within: Option<Duration>, settings: &Settings) -> anyhow::Result<Duration> {
+    if let Some(within) = within {
+        return Ok(within);
+    }
+    match settings.reminder_window {
+        Some(ref window) => parse_duration(window)
+            .ok_or_else(|| anyhow!("reminder_window: `{}` is not a valid duration", window)),
+        None => Ok(Duration::hours(1)),
+    }
+}
+
+/// Get the values describing a reminder: the task's id (if in the working set), UUID, the kind
+/// of reminder, its time, and the task's description.
+fn reminder_values(reminder: &Reminder, working_set: &WorkingSet, time: String) -> [String; 5] {
+    [
+        working_set
+            .by_uuid(reminder.uuid)
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        reminder.uuid.to_string(),
+        reminder.kind.as_str().to_owned(),
+        time,
+        reminder.description.clone(),
+    ]
+}
+
+/// Run the notification command for a reminder.  The command is given a title and the task's
+/// description as arguments, as expected by `notify-send`, and details of the reminder in the
+/// environment.
+fn notify(
+    command: &str,
+    reminder: &Reminder,
+    working_set: &WorkingSet,
+    settings: &Settings,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let mut words = command.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| anyhow!("reminder_command is empty"))?;
+    let title = format!(
+        "Task {} {}",
+        reminder.kind.as_str(),
+        settings.dates.datetime(reminder.time, now)
+    );
+    let [id, uuid, kind, time, _] =
+        reminder_values(reminder, working_set, reminder.time.to_rfc3339());
+    let status = Command::new(program)
+        .args(words)
+        .arg(title)
+        .arg(&reminder.description)
+        .env("TASK_ID", id)
+        .env("TASK_UUID", uuid)
+        .env("REMINDER_KIND", kind)
+        .env("REMINDER_TIME", time)
+        .status()
+        .with_context(|| format!("Could not run reminder command `{}`", command))?;
+    if !status.success() {
+        bail!("Reminder command `{}` exited with {}", command, status);
+    }
+    Ok(())
+}
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    settings: &Settings,
+    within: Option<Duration>,
+    notify_each: bool,
+    format: OutputFormat,
+) -> Result<(), crate::Error> {
+    let now = Utc::now();
+    let reminders = replica.reminders(now, now + window(within, settings)?)?;
+    let working_set = replica.working_set()?;
+
+    match format {
+        OutputFormat::Table => {
+            if reminders.is_empty() {
+                writeln!(w, "No reminders.")?;
+            } else {
+                let mut t = Table::new();
+                t.set_format(table::format());
+                t.set_titles(row![b->"Id", b->"Reminder", b->"Time", b->"Description"]);
+                for reminder in &reminders {
+                    let time = settings.dates.datetime(reminder.time, now);
+                    let [id, _, kind, time, description] =
+                        reminder_values(reminder, &working_set, time);
+                    t.add_row(row![id, kind, time, description]);
+                }
+                t.print(w)?;
+            }
+        }
+        OutputFormat::Json => {
+            let reminders: Vec<_> = reminders
+                .iter()
+                .map(|r| {
+                    json!({
+                        "id": working_set.by_uuid(r.uuid),
+                        "uuid": r.uuid.to_string(),
+                        "kind": r.kind.as_str(),
+                        "time": r.time.to_rfc3339(),
+                        "description": r.description,
+                    })
+                })
+                .collect();
+            writeln!(w, "{:#}", Value::Array(reminders))?;
+        }
+        OutputFormat::Tsv => {
+            writeln!(w, "id\tuuid\tkind\ttime\tdescription")?;
+            for reminder in &reminders {
+                let values = reminder_values(reminder, &working_set, reminder.time.to_rfc3339());
+                let values: Vec<_> = values
+                    .iter()
+                    .map(|v| v.replace(&['\t', '\n', '\r'][..], " "))
+                    .collect();
+                writeln!(w, "{}", values.join("\t"))?;
+            }
+        }
+        OutputFormat::Csv => {
+            writeln!(
+                w,
+                "{}",
+                csv_row(&["id", "uuid", "kind", "time", "description"])
+            )?;
+            for reminder in &reminders {
+                // like reports, use ISO dates so that spreadsheets can interpret them
+                let time = DateDisplay::default().datetime(reminder.time, now);
+                writeln!(
+                    w,
+                    "{}",
+                    csv_row(&reminder_values(reminder, &working_set, time))
+                )?;
+            }
+        }
+        OutputFormat::Markdown => {
+            writeln!(
+                w,
+                "{}",
+                markdown_row(&["Id", "Reminder", "Time", "Description"])
+            )?;
+            writeln!(w, "{}", markdown_separator(4))?;
+            for reminder in &reminders {
+                let time = settings.dates.datetime(reminder.time, now);
+                let [id, _, kind, time, description] =
+                    reminder_values(reminder, &working_set, time);
+                writeln!(w, "{}", markdown_row(&[id, kind, time, description]))?;
+            }
+        }
+    }
+
+    if notify_each {
+        let command = settings
+            .reminder_command
+            .as_deref()
+            .unwrap_or(DEFAULT_COMMAND);
+        for reminder in &reminders {
+            notify(command, reminder, &working_set, settings, now)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+    use taskchampion::Status;
+
+    #[test]
+    fn test_window() {
+        let mut settings = Settings::default();
+        assert_eq!(window(None, &settings).unwrap(), Duration::hours(1));
+        settings.reminder_window = Some(s!("1d"));
+        assert_eq!(window(None, &settings).unwrap(), Duration::days(1));
+        assert_eq!(
+            window(Some(Duration::minutes(5)), &settings).unwrap(),
+            Duration::minutes(5)
+        );
+    }
+
+    fn add_due_task(replica: &mut Replica, description: &str, due: Duration) {
+        let mut t = replica
+            .new_task(Status::Pending, description.to_owned())
+            .unwrap()
+            .into_mut(replica);
+        t.set_due(Some(Utc::now() + due)).unwrap();
+    }
+
+    #[test]
+    fn test_reminders() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let settings = Settings::default();
+        add_due_task(&mut replica, "soon", Duration::minutes(30));
+        add_due_task(&mut replica, "later", Duration::hours(3));
+
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            None,
+            false,
+            OutputFormat::Markdown,
+        )
+        .unwrap();
+        let output = w.into_string();
+        assert!(output.starts_with("| Id | Reminder | Time | Description |\n"));
+        assert!(output.contains("| 1 | due | "));
+        assert!(output.contains("soon"));
+        assert!(!output.contains("later"));
+    }
+
+    #[test]
+    fn test_reminders_none() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let settings = Settings::default();
+        add_due_task(&mut replica, "later", Duration::hours(3));
+
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            None,
+            false,
+            OutputFormat::Table,
+        )
+        .unwrap();
+        assert_eq!(w.into_string(), "No reminders.\n");
+    }
+
+    #[test]
+    fn test_reminders_json() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let settings = Settings::default();
+        add_due_task(&mut replica, "soon", Duration::minutes(30));
+        add_due_task(&mut replica, "later", Duration::hours(3));
+
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            Some(Duration::days(1)),
+            false,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        let reminders: Vec<Value> = serde_json::from_str(&w.into_string()).unwrap();
+        assert_eq!(reminders.len(), 2);
+        assert_eq!(reminders[0]["description"], json!("soon"));
+        assert_eq!(reminders[0]["kind"], json!("due"));
+        assert_eq!(reminders[0]["id"], json!(1));
+        assert_eq!(reminders[1]["description"], json!("later"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reminders_notify() {
+        let mut replica = test_replica();
+        let mut settings = Settings::default();
+        add_due_task(&mut replica, "soon", Duration::minutes(30));
+
+        settings.reminder_command = Some(s!("true"));
+        let mut w = test_writer();
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            None,
+            true,
+            OutputFormat::Tsv,
+        )
+        .unwrap();
+
+        settings.reminder_command = Some(s!("false"));
+        let mut w = test_writer();
+        let err = execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            None,
+            true,
+            OutputFormat::Tsv,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Reminder command `false` exited"));
+    }
+}
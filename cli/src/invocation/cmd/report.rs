@@ -1,4 +1,4 @@
-use crate::argparse::Filter;
+use crate::argparse::{Filter, OutputFormat, ReportOptions};
 use crate::invocation::display_report;
 use crate::settings::Settings;
 use taskchampion::Replica;
@@ -10,8 +10,10 @@ pub(crate) fn execute<W: WriteColor>(
     settings: &Settings,
     report_name: String,
     filter: Filter,
+    options: ReportOptions,
+    format: OutputFormat,
 ) -> Result<(), crate::Error> {
-    display_report(w, replica, settings, report_name, filter)
+    display_report(w, replica, settings, report_name, filter, options, format)
 }
 
 #[cfg(test)]
@@ -20,6 +22,7 @@ mod test {
     use crate::argparse::Filter;
     use crate::invocation::test::*;
 
+    use taskchampion::chrono::{Duration, Utc};
     use taskchampion::Status;
 
     #[test]
@@ -37,7 +40,257 @@ mod test {
             ..Default::default()
         };
 
-        execute(&mut w, &mut replica, &settings, report_name, filter).unwrap();
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            report_name,
+            filter,
+            Default::default(),
+            OutputFormat::Table,
+        )
+        .unwrap();
         assert!(w.into_string().contains("my task"));
     }
+
+    #[test]
+    fn json_report() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let t = replica.new_task(Status::Pending, s!("my task")).unwrap();
+        let mut t = t.into_mut(&mut replica);
+        t.add_tag(&tag!("work")).unwrap();
+        t.set_project(Some(s!("acme"))).unwrap();
+        replica.new_task(Status::Pending, s!("other")).unwrap();
+
+        let settings = Default::default();
+        let options = ReportOptions {
+            columns: Some(vec![s!("id"), s!("description"), s!("tags"), s!("project")]),
+            sort: Some(vec![s!("id")]),
+            limit: None,
+        };
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            s!("next"),
+            Filter::default(),
+            options,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        let tasks: serde_json::Value = serde_json::from_str(&w.into_string()).unwrap();
+        assert_eq!(
+            tasks,
+            serde_json::json!([
+                {"id": 1, "description": "my task", "tags": ["PENDING", "UNBLOCKED", "work"], "project": "acme"},
+                {"id": 2, "description": "other", "tags": ["PENDING", "UNBLOCKED"], "project": null},
+            ])
+        );
+    }
+
+    #[test]
+    fn context_report() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let t = replica.new_task(Status::Pending, s!("my task")).unwrap();
+        let mut t = t.into_mut(&mut replica);
+        t.add_tag(&tag!("work")).unwrap();
+        replica.new_task(Status::Pending, s!("other")).unwrap();
+
+        let mut settings = Settings::default();
+        settings
+            .contexts
+            .insert(s!("work"), s!("+work or project:acme"));
+        settings.context = Some(s!("work"));
+        let options = ReportOptions {
+            columns: Some(vec![s!("description")]),
+            sort: None,
+            limit: None,
+        };
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            s!("next"),
+            Filter::default(),
+            options,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        let tasks: serde_json::Value = serde_json::from_str(&w.into_string()).unwrap();
+        assert_eq!(tasks, serde_json::json!([{"description": "my task"}]));
+    }
+
+    #[test]
+    fn tsv_report() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("my\ttask")).unwrap();
+
+        let settings = Default::default();
+        let options = ReportOptions {
+            columns: Some(vec![s!("id"), s!("description")]),
+            sort: None,
+            limit: None,
+        };
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            s!("next"),
+            Filter::default(),
+            options,
+            OutputFormat::Tsv,
+        )
+        .unwrap();
+        assert_eq!(w.into_string(), "id\tdescription\n1\tmy task\n");
+    }
+
+    #[test]
+    fn csv_report() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica
+            .new_task(Status::Pending, s!("my, \"task\""))
+            .unwrap();
+
+        let settings = Default::default();
+        let options = ReportOptions {
+            columns: Some(vec![s!("id"), s!("description")]),
+            sort: None,
+            limit: None,
+        };
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            s!("next"),
+            Filter::default(),
+            options,
+            OutputFormat::Csv,
+        )
+        .unwrap();
+        assert_eq!(w.into_string(), "id,description\n1,\"my, \"\"task\"\"\"\n");
+    }
+
+    #[test]
+    fn markdown_report() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("my | task")).unwrap();
+
+        let settings = Default::default();
+        let options = ReportOptions {
+            columns: Some(vec![s!("id"), s!("description")]),
+            sort: None,
+            limit: None,
+        };
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            s!("next"),
+            Filter::default(),
+            options,
+            OutputFormat::Markdown,
+        )
+        .unwrap();
+        assert_eq!(
+            w.into_string(),
+            "| id | description |\n| --- | --- |\n| 1 | my \\| task |\n"
+        );
+    }
+
+    #[test]
+    fn next_report_urgent_first() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let plain = replica.new_task(Status::Pending, s!("plain")).unwrap();
+        let mut urgent = replica
+            .new_task(Status::Pending, s!("urgent"))
+            .unwrap()
+            .into_mut(&mut replica);
+        urgent.add_tag(&tag!("next")).unwrap();
+        let mut waiting = replica
+            .new_task(Status::Pending, s!("waiting"))
+            .unwrap()
+            .into_mut(&mut replica);
+        waiting
+            .set_wait(Some(Utc::now() + Duration::days(3)))
+            .unwrap();
+        let mut blocked = replica
+            .new_task(Status::Pending, s!("blocked"))
+            .unwrap()
+            .into_mut(&mut replica);
+        blocked.add_dependency(plain.get_uuid()).unwrap();
+        // refresh the cached dependencies, as a new invocation would
+        replica.dependency_map(true).unwrap();
+
+        let settings = Settings::default();
+        let options = ReportOptions {
+            columns: Some(vec![s!("description")]),
+            ..Default::default()
+        };
+        execute(
+            &mut w,
+            &mut replica,
+            &settings,
+            settings.default_report.clone(),
+            Filter::default(),
+            options,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        let tasks: serde_json::Value = serde_json::from_str(&w.into_string()).unwrap();
+        assert_eq!(
+            tasks,
+            serde_json::json!([{"description": "urgent"}, {"description": "plain"}])
+        );
+    }
+
+    #[test]
+    fn waiting_report_by_wait_date() {
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("plain")).unwrap();
+        for &(description, days) in &[("later", 5), ("sooner", 2), ("unwaited", -1)] {
+            let mut task = replica
+                .new_task(Status::Pending, s!(description))
+                .unwrap()
+                .into_mut(&mut replica);
+            task.set_wait(Some(Utc::now() + Duration::days(days)))
+                .unwrap();
+        }
+
+        let settings = Settings::default();
+        let mut run_report = |report_name: &str| {
+            let mut w = test_writer();
+            let options = ReportOptions {
+                columns: Some(vec![s!("description")]),
+                sort: Some(vec![s!("wait"), s!("description")]),
+                ..Default::default()
+            };
+            execute(
+                &mut w,
+                &mut replica,
+                &settings,
+                s!(report_name),
+                Filter::default(),
+                options,
+                OutputFormat::Json,
+            )
+            .unwrap();
+            serde_json::from_str::<serde_json::Value>(&w.into_string()).unwrap()
+        };
+
+        assert_eq!(
+            run_report("waiting"),
+            serde_json::json!([{"description": "sooner"}, {"description": "later"}])
+        );
+        // a task whose wait date has passed is included in the next report
+        assert_eq!(
+            run_report("next"),
+            serde_json::json!([{"description": "plain"}, {"description": "unwaited"}])
+        );
+    }
 }
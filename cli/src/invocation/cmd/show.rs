@@ -0,0 +1,223 @@
+use crate::settings::{config_home, data_home, Settings};
+use std::collections::HashSet;
+use taskchampion::chrono::Local;
+use taskchampion::{Replica, Status};
+use termcolor::{ColorSpec, WriteColor};
+
+/// Get the top-level keys set in the configuration file, if it exists
+fn config_file_keys(settings: &Settings) -> anyhow::Result<HashSet<String>> {
+    let filename = match settings.filename {
+        Some(ref filename) if filename.exists() => filename,
+        _ => return Ok(HashSet::new()),
+    };
+    let config_toml = std::fs::read_to_string(filename)?.parse::<toml::Value>()?;
+    Ok(config_toml
+        .as_table()
+        .map(|t| t.keys().cloned().collect())
+        .unwrap_or_default())
+}
+
+fn heading<W: WriteColor>(w: &mut W, heading: &str) -> anyhow::Result<()> {
+    w.set_color(ColorSpec::new().set_bold(true))?;
+    write!(w, "{}:", heading)?;
+    w.reset()?;
+    write!(w, " ")?;
+    Ok(())
+}
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    settings: &Settings,
+) -> Result<(), crate::Error> {
+    super::version::execute(w)?;
+
+    for (name, base_dir) in [("Config home", config_home()), ("Data home", data_home())].iter() {
+        if let Some(base_dir) = base_dir {
+            heading(w, name)?;
+            match base_dir.env_var {
+                Some(var) => writeln!(w, "{} (from ${})", base_dir.path.display(), var)?,
+                None => writeln!(w, "{}", base_dir.path.display())?,
+            }
+        }
+    }
+    for (from, to) in &settings.migrations {
+        heading(w, "Migrated")?;
+        writeln!(w, "{} to {}", from.display(), to.display())?;
+    }
+
+    heading(w, "Configuration file")?;
+    match settings.filename {
+        Some(ref filename) if filename.exists() => writeln!(w, "{}", filename.display())?,
+        Some(ref filename) => writeln!(w, "{} (not found)", filename.display())?,
+        None => writeln!(w, "(none)")?,
+    }
+
+    let profile = settings
+        .profile
+        .as_ref()
+        .and_then(|name| settings.profiles.get(name).map(|p| (name, p)));
+    if let Some((name, _)) = profile {
+        heading(w, "Profile")?;
+        writeln!(w, "{}", name)?;
+    }
+
+    heading(w, "Data directory")?;
+    match (profile, Settings::data_dir_env_var()) {
+        (Some((_, p)), _) if p.overrides("data_dir") => {
+            writeln!(w, "{}", settings.data_dir.display())?
+        }
+        (_, Some(var)) => writeln!(w, "{} (from ${})", settings.data_dir.display(), var)?,
+        _ => writeln!(w, "{}", settings.data_dir.display())?,
+    }
+    heading(w, "Storage")?;
+    writeln!(
+        w,
+        "SQLite, {}",
+        settings.data_dir.join("taskchampion.sqlite3").display()
+    )?;
+
+    heading(w, "Sync server")?;
+    match (&settings.server_origin, &settings.server_client_key) {
+        (Some(origin), Some(_)) => writeln!(w, "remote, {}", origin)?,
+        _ => writeln!(w, "local, {}", settings.server_dir.display())?,
+    }
+
+    let (mut pending, mut waiting, mut completed, mut deleted) = (0, 0, 0, 0);
+    let tasks = replica.all_tasks()?;
+    for task in tasks.values() {
+        match task.get_status() {
+            Status::Pending if task.is_waiting() => waiting += 1,
+            Status::Pending => pending += 1,
+            Status::Completed => completed += 1,
+            Status::Deleted => deleted += 1,
+            Status::Unknown(_) => {}
+        }
+    }
+    heading(w, "Tasks")?;
+    writeln!(
+        w,
+        "{} ({} pending, {} waiting, {} completed, {} deleted)",
+        tasks.len(),
+        pending,
+        waiting,
+        completed,
+        deleted
+    )?;
+    heading(w, "Working set")?;
+    writeln!(w, "{} tasks", replica.working_set()?.len())?;
+
+    heading(w, "Unsynced operations")?;
+    writeln!(w, "{}", replica.num_local_operations()?)?;
+    heading(w, "Last sync")?;
+    match replica.last_sync()? {
+        Some(ts) => writeln!(
+            w,
+            "{}",
+            ts.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+        )?,
+        None => writeln!(w, "never")?,
+    }
+
+    writeln!(w)?;
+    w.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(w, "Configuration values:")?;
+    w.reset()?;
+    let file_keys = config_file_keys(settings)?;
+    let data_dir_env_var = Settings::data_dir_env_var();
+    for key in Settings::SETTABLE_KEYS {
+        let value = settings.get(key)?;
+        let source = match (value.as_ref(), profile, data_dir_env_var) {
+            (None, _, _) => String::new(),
+            (Some(_), Some((name, p)), _) if p.overrides(key) => {
+                format!(" (from profile {})", name)
+            }
+            (Some(_), _, Some(var)) if *key == "data_dir" => format!(" (from ${})", var),
+            (Some(_), _, _) if file_keys.contains(*key) => " (from configuration file)".into(),
+            (Some(_), _, _) => " (default)".into(),
+        };
+        writeln!(
+            w,
+            "  {}{}{}",
+            key,
+            super::config::describe_value(key, value),
+            source
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_show() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(
+            cfg_file.clone(),
+            "data_dir = \"/nowhere\"\nencryption_secret = \"shh\"\n",
+        )
+        .unwrap();
+        let settings = Settings::load_from_file(cfg_file, true).unwrap();
+
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        replica.new_task(Status::Pending, s!("one")).unwrap();
+        replica.new_task(Status::Completed, s!("two")).unwrap();
+
+        execute(&mut w, &mut replica, &settings).unwrap();
+        let output = w.into_string();
+        assert!(output.starts_with("TaskChampion "));
+        assert!(output.contains("Data directory: /nowhere\n"));
+        assert!(output.contains("Tasks: 2 (1 pending, 0 waiting, 1 completed, 0 deleted)\n"));
+        assert!(output.contains("Last sync: never\n"));
+        assert!(output.contains("  data_dir = /nowhere (from configuration file)\n"));
+        assert!(output.contains("  confirmation = true (default)\n"));
+        assert!(output.contains("  encryption_secret = (hidden) (from configuration file)\n"));
+        assert!(output.contains("  server_origin (not set)\n"));
+        assert!(!output.contains("shh"));
+    }
+
+    #[test]
+    fn test_show_migrations() {
+        let settings = Settings {
+            migrations: vec![(
+                PathBuf::from("/old/taskchampion.toml"),
+                PathBuf::from("/new/taskchampion/taskchampion.toml"),
+            )],
+            ..Settings::default()
+        };
+
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        execute(&mut w, &mut replica, &settings).unwrap();
+        let output = w.into_string();
+        assert!(output
+            .contains("Migrated: /old/taskchampion.toml to /new/taskchampion/taskchampion.toml\n"));
+    }
+
+    #[test]
+    fn test_show_profile() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(cfg_file.clone(), "[profiles.work]\ndata_dir = \"/work\"\n").unwrap();
+        let mut settings = Settings::load_from_file(cfg_file, true).unwrap();
+        settings.use_profile("work").unwrap();
+
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        execute(&mut w, &mut replica, &settings).unwrap();
+        let output = w.into_string();
+        assert!(output.contains("Profile: work\n"));
+        assert!(!output.contains("Migrated: "));
+        assert!(output.contains("Data directory: /work\n"));
+        assert!(output.contains("  data_dir = /work (from profile work)\n"));
+    }
+}
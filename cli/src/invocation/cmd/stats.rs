@@ -0,0 +1,198 @@
+use crate::argparse::Filter;
+use crate::invocation::filtered_tasks;
+use taskchampion::chrono::{DateTime, Duration, Local, Utc};
+use taskchampion::{Replica, Status, Task};
+use termcolor::{ColorSpec, WriteColor};
+
+/// The number of weeks of history to summarize
+const WEEKS: i32 = 8;
+
+/// The activity in a single week
+#[derive(Debug, PartialEq)]
+struct Week {
+    start: DateTime<Utc>,
+    added: usize,
+    completed: usize,
+}
+
+/// Statistics about a set of tasks
+#[derive(Debug, Default, PartialEq)]
+struct Stats {
+    pending: usize,
+    waiting: usize,
+    completed: usize,
+    deleted: usize,
+    /// The average age of pending (including waiting) tasks, if there are any
+    average_age: Option<Duration>,
+    /// Activity in each of the last few weeks, oldest first
+    weeks: Vec<Week>,
+}
+
+fn compute_stats(tasks: impl Iterator<Item = Task>, now: DateTime<Utc>) -> Stats {
+    let mut stats = Stats {
+        weeks: (0..WEEKS)
+            .rev()
+            .map(|i| Week {
+                start: now - Duration::weeks(i64::from(i) + 1),
+                added: 0,
+                completed: 0,
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let week_of = |ts: DateTime<Utc>| -> Option<usize> {
+        let weeks_ago = (now - ts).num_weeks();
+        if ts <= now && weeks_ago < i64::from(WEEKS) {
+            Some(WEEKS as usize - 1 - weeks_ago as usize)
+        } else {
+            None
+        }
+    };
+
+    let mut total_age = Duration::zero();
+    for task in tasks {
+        match task.get_status() {
+            Status::Pending => {
+                if task.is_waiting() {
+                    stats.waiting += 1;
+                } else {
+                    stats.pending += 1;
+                }
+                if let Some(entry) = task.get_entry() {
+                    total_age = total_age + (now - entry);
+                }
+            }
+            Status::Completed => {
+                stats.completed += 1;
+                if let Some(i) = task.get_end().and_then(week_of) {
+                    stats.weeks[i].completed += 1;
+                }
+            }
+            Status::Deleted => stats.deleted += 1,
+            Status::Unknown(_) => {}
+        }
+        if let Some(i) = task.get_entry().and_then(week_of) {
+            stats.weeks[i].added += 1;
+        }
+    }
+
+    let num_pending = (stats.pending + stats.waiting) as i32;
+    if num_pending > 0 {
+        stats.average_age = Some(total_age / num_pending);
+    }
+
+    stats
+}
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    filter: Filter,
+) -> Result<(), crate::Error> {
+    let stats = compute_stats(filtered_tasks(replica, &filter)?, Utc::now());
+
+    writeln!(w, "{:<12} {:>6}", "Pending", stats.pending)?;
+    writeln!(w, "{:<12} {:>6}", "Waiting", stats.waiting)?;
+    writeln!(w, "{:<12} {:>6}", "Completed", stats.completed)?;
+    writeln!(w, "{:<12} {:>6}", "Deleted", stats.deleted)?;
+    writeln!(
+        w,
+        "{:<12} {:>6}",
+        "Total",
+        stats.pending + stats.waiting + stats.completed + stats.deleted
+    )?;
+    if let Some(age) = stats.average_age {
+        writeln!(
+            w,
+            "Average age of pending tasks: {:.1} days",
+            age.num_seconds() as f64 / 86400.0
+        )?;
+    }
+
+    writeln!(w)?;
+    w.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(w, "{:<12} {:>6} {:>10}", "Week of", "Added", "Completed")?;
+    w.reset()?;
+    for week in stats.weeks {
+        writeln!(
+            w,
+            "{:<12} {:>6} {:>10}",
+            week.start
+                .with_timezone(&Local)
+                .format("%Y-%m-%d")
+                .to_string(),
+            week.added,
+            week.completed
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_compute_stats() {
+        let mut replica = test_replica();
+        let now = Utc::now();
+
+        let old = replica.new_task(Status::Pending, s!("old")).unwrap();
+        let mut old = old.into_mut(&mut replica);
+        old.set_entry(Some(now - Duration::days(20))).unwrap();
+
+        replica.new_task(Status::Pending, s!("new")).unwrap();
+
+        let done = replica.new_task(Status::Pending, s!("done")).unwrap();
+        let mut done = done.into_mut(&mut replica);
+        done.done().unwrap();
+
+        let waiting = replica.new_task(Status::Pending, s!("waiting")).unwrap();
+        let mut waiting = waiting.into_mut(&mut replica);
+        waiting.set_wait(Some(now + Duration::days(2))).unwrap();
+
+        replica.new_task(Status::Deleted, s!("deleted")).unwrap();
+
+        let stats = compute_stats(
+            replica.all_tasks().unwrap().into_iter().map(|(_, t)| t),
+            now,
+        );
+        assert_eq!(stats.pending, 2);
+        assert_eq!(stats.waiting, 1);
+        assert_eq!(stats.completed, 1);
+        assert_eq!(stats.deleted, 1);
+
+        // (20 + 0 + 0) / 3 days, give or take the time spent running this test
+        let age = stats.average_age.unwrap();
+        assert!(age.num_hours() >= 159 && age.num_hours() <= 161);
+
+        assert_eq!(stats.weeks.len(), WEEKS as usize);
+        let activity: Vec<_> = stats.weeks.iter().map(|w| (w.added, w.completed)).collect();
+        assert_eq!(
+            activity,
+            vec![
+                (0, 0),
+                (0, 0),
+                (0, 0),
+                (0, 0),
+                (0, 0),
+                (1, 0),
+                (0, 0),
+                (4, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        execute(&mut w, &mut replica, Filter::default()).unwrap();
+        let output = w.into_string();
+        assert!(output.starts_with("Pending           0\n"));
+        assert!(!output.contains("Average age"));
+    }
+}
@@ -1,7 +1,71 @@
-use crate::settings::Settings;
-use taskchampion::{server::Server, Error as TCError, Replica};
+use crate::settings::{is_secret_reference, resolve_secret, Settings};
+use anyhow::{anyhow, Context};
+use dialoguer::{Input, Password, Select};
+use std::path::PathBuf;
+use taskchampion::server::{Server, NIL_VERSION_ID};
+use taskchampion::{Error as TCError, Replica, ServerConfig, Uuid};
 use termcolor::WriteColor;
 
+/// The synchronization configuration chosen by `ta sync --init`
+#[derive(Debug, PartialEq)]
+pub(crate) enum SyncSetup {
+    /// Synchronize with a server in a local directory
+    Local { server_dir: PathBuf },
+    /// Synchronize with a TaskChampion sync server
+    Remote {
+        origin: String,
+        client_key: Uuid,
+        /// The secret, or a reference to it as allowed in the configuration file
+        encryption_secret: String,
+    },
+}
+
+impl SyncSetup {
+    fn server_config(&self) -> anyhow::Result<ServerConfig> {
+        Ok(match self {
+            SyncSetup::Local { server_dir } => ServerConfig::Local {
+                server_dir: server_dir.clone(),
+            },
+            SyncSetup::Remote {
+                origin,
+                client_key,
+                encryption_secret,
+            } => ServerConfig::Remote {
+                origin: origin.clone(),
+                client_key: *client_key,
+                encryption_secret: resolve_secret(encryption_secret)
+                    .context("encryption_secret")?
+                    .into_bytes(),
+            },
+        })
+    }
+
+    /// The configuration values to write for this setup.  A local setup removes any remote
+    /// server settings, since those take precedence.
+    fn config_values(&self) -> Vec<(&'static str, Option<String>)> {
+        match self {
+            SyncSetup::Local { server_dir } => vec![
+                (
+                    "server_dir",
+                    Some(server_dir.to_string_lossy().into_owned()),
+                ),
+                ("server_origin", None),
+                ("server_client_key", None),
+                ("encryption_secret", None),
+            ],
+            SyncSetup::Remote {
+                origin,
+                client_key,
+                encryption_secret,
+            } => vec![
+                ("server_origin", Some(origin.clone())),
+                ("server_client_key", Some(client_key.to_string())),
+                ("encryption_secret", Some(encryption_secret.clone())),
+            ],
+        }
+    }
+}
+
 pub(crate) fn execute<W: WriteColor>(
     w: &mut W,
     replica: &mut Replica,
@@ -13,27 +77,158 @@ pub(crate) fn execute<W: WriteColor>(
             writeln!(w, "sync complete.")?;
             Ok(())
         }
-        Err(e) => match e.downcast() {
-            Ok(TCError::OutOfSync) => {
-                writeln!(w, "This replica cannot be synchronized with the server.")?;
-                writeln!(
-                    w,
-                    "It may be too old, or some other failure may have occurred."
-                )?;
-                writeln!(
-                    w,
-                    "To start fresh, remove the local task database and run `ta sync` again."
-                )?;
-                writeln!(
-                    w,
-                    "Note that doing so will lose any un-synchronized local changes."
-                )?;
+        Err(TCError::OutOfSync) => {
+            writeln!(w, "This replica cannot be synchronized with the server.")?;
+            writeln!(
+                w,
+                "It may be too old, or some other failure may have occurred."
+            )?;
+            writeln!(
+                w,
+                "To start fresh, remove the local task database and run `ta sync` again."
+            )?;
+            writeln!(
+                w,
+                "Note that doing so will lose any un-synchronized local changes."
+            )?;
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Interactively configure synchronization, then perform the first sync.
+pub(crate) fn init<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    settings: &Settings,
+) -> Result<(), crate::Error> {
+    if let Some(ref profile) = settings.profile {
+        return Err(crate::Error::for_arguments(format!(
+            "sync --init configures the top-level settings, and cannot be used with profile `{}`",
+            profile
+        )));
+    }
+    if !atty::is(atty::Stream::Stdin) {
+        return Err(anyhow!("sync --init must be run interactively, in a terminal").into());
+    }
+    let setup = ask_setup(settings)?;
+    apply_setup(w, replica, settings, setup)
+}
+
+/// Ask the user how to synchronize, offering the current settings as defaults.
+fn ask_setup(settings: &Settings) -> anyhow::Result<SyncSetup> {
+    let kind = Select::new()
+        .with_prompt("Synchronize with")
+        .items(&[
+            "a TaskChampion sync server",
+            "a local directory (for a single replica)",
+        ])
+        .default(if settings.server_origin.is_some() {
+            0
+        } else {
+            1
+        })
+        .interact()?;
+
+    if kind == 1 {
+        let server_dir: String = Input::new()
+            .with_prompt("Server directory")
+            .default(settings.server_dir.to_string_lossy().into_owned())
+            .interact_text()?;
+        return Ok(SyncSetup::Local {
+            server_dir: server_dir.into(),
+        });
+    }
+
+    let mut origin = Input::<String>::new();
+    origin.with_prompt("Server URL");
+    if let Some(ref default) = settings.server_origin {
+        origin.default(default.clone());
+    }
+    let origin = origin
+        .validate_with(|s: &String| {
+            if s.starts_with("http://") || s.starts_with("https://") {
                 Ok(())
+            } else {
+                Err("must be an http:// or https:// URL")
             }
-            Ok(e) => Err(e.into()),
-            Err(e) => Err(e.into()),
-        },
+        })
+        .interact_text()?;
+
+    let default_key = settings
+        .server_client_key
+        .as_ref()
+        .and_then(|key| resolve_secret(key).ok())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let client_key: String = Input::new()
+        .with_prompt("Client key (the same for all replicas)")
+        .default(default_key)
+        .validate_with(|s: &String| Uuid::parse_str(s).map(|_| ()).map_err(|_| "not a UUID"))
+        .interact_text()?;
+
+    let mut encryption_secret = Password::new()
+        .with_prompt("Encryption secret (leave empty to generate one)")
+        .allow_empty_password(true)
+        .interact()?;
+    if encryption_secret.is_empty() {
+        encryption_secret = settings
+            .encryption_secret
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_simple().to_string());
+    }
+
+    Ok(SyncSetup::Remote {
+        origin: origin.trim_end_matches('/').to_owned(),
+        client_key: Uuid::parse_str(&client_key)?,
+        encryption_secret,
+    })
+}
+
+/// Verify that the server described by `setup` can be reached, then write the configuration and
+/// synchronize.
+fn apply_setup<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    settings: &Settings,
+    setup: SyncSetup,
+) -> Result<(), crate::Error> {
+    let mut server = setup.server_config()?.into_server()?;
+    if let Err(e) = server.get_child_version(NIL_VERSION_ID) {
+        return Err(anyhow!(
+            "Could not reach the server; the configuration was not changed: {}",
+            e
+        )
+        .into());
     }
+
+    let values = setup.config_values();
+    let values: Vec<(&str, Option<&str>)> = values
+        .iter()
+        .map(|(k, v)| (*k, v.as_ref().map(|v| v.as_ref())))
+        .collect();
+    let filename = settings.update(&values)?;
+    writeln!(w, "Configuration written to {:?}.", filename)?;
+
+    // a secret stored elsewhere, such as in a password manager, is not shown
+    match setup {
+        SyncSetup::Remote {
+            encryption_secret, ..
+        } if !is_secret_reference(&encryption_secret) => {
+            writeln!(
+                w,
+                "Your encryption secret is `{}`.  Keep it safe: it is needed to set up",
+                encryption_secret
+            )?;
+            writeln!(
+                w,
+                "other replicas, and the data on the server cannot be read without it."
+            )?;
+        }
+        _ => {}
+    }
+
+    execute(w, replica, settings, &mut server)
 }
 
 #[cfg(test)]
@@ -55,4 +250,60 @@ mod test {
         execute(&mut w, &mut replica, &settings, &mut server).unwrap();
         assert_eq!(&w.into_string(), "sync complete.\n")
     }
+
+    #[test]
+    fn test_apply_setup_local() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("config.toml");
+        std::fs::write(&cfg_file, "server_origin = \"https://example.com\"\n").unwrap();
+        let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
+
+        let server_dir = TempDir::new().unwrap();
+        let setup = SyncSetup::Local {
+            server_dir: server_dir.path().to_owned(),
+        };
+        apply_setup(&mut w, &mut replica, &settings, setup).unwrap();
+        assert!(w.into_string().ends_with("sync complete.\n"));
+
+        let settings = Settings::load_from_file(cfg_file, true).unwrap();
+        assert_eq!(settings.server_dir, server_dir.path());
+        assert_eq!(settings.server_origin, None);
+    }
+
+    #[test]
+    fn test_apply_setup_unreachable() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("config.toml");
+        let settings = Settings::load_from_file(cfg_file.clone(), false).unwrap();
+
+        let setup = SyncSetup::Remote {
+            origin: s!("http://127.0.0.1:1"),
+            client_key: Uuid::new_v4(),
+            encryption_secret: s!("sekrit"),
+        };
+        assert!(apply_setup(&mut w, &mut replica, &settings, setup).is_err());
+        assert!(!cfg_file.exists());
+    }
+
+    #[test]
+    fn test_apply_setup_missing_secret() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("config.toml");
+        let settings = Settings::load_from_file(cfg_file.clone(), false).unwrap();
+
+        let setup = SyncSetup::Remote {
+            origin: s!("http://127.0.0.1:1"),
+            client_key: Uuid::new_v4(),
+            encryption_secret: format!("file:{}", cfg_dir.path().join("nosuch").display()),
+        };
+        let err = apply_setup(&mut w, &mut replica, &settings, setup).unwrap_err();
+        assert!(err.to_string().starts_with("encryption_secret"));
+        assert!(!cfg_file.exists());
+    }
 }
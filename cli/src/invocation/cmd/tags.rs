@@ -0,0 +1,135 @@
+use crate::table;
+use prettytable::{cell, row, Table};
+use std::collections::BTreeMap;
+use taskchampion::{Replica, Status, Tag};
+use termcolor::WriteColor;
+
+/// Count the pending tasks having each tag, returning separate maps for user and synthetic tags
+pub(super) fn tag_counts(
+    replica: &mut Replica,
+) -> anyhow::Result<(BTreeMap<Tag, usize>, BTreeMap<Tag, usize>)> {
+    let mut user = BTreeMap::new();
+    let mut synthetic = BTreeMap::new();
+    for task in replica.all_tasks()?.values() {
+        if task.get_status() != Status::Pending {
+            continue;
+        }
+        for tag in task.get_tags() {
+            let counts = if tag.is_user() {
+                &mut user
+            } else {
+                &mut synthetic
+            };
+            *counts.entry(tag).or_insert(0) += 1;
+        }
+    }
+    Ok((user, synthetic))
+}
+
+fn print_counts<W: WriteColor>(
+    w: &mut W,
+    title: &str,
+    counts: BTreeMap<Tag, usize>,
+) -> anyhow::Result<()> {
+    let mut t = Table::new();
+    t.set_format(table::format());
+    t.set_titles(row![b->title, b->"Pending"]);
+    for (tag, count) in counts {
+        t.add_row(row![tag, r->count]);
+    }
+    t.print(w)?;
+    Ok(())
+}
+
+pub(crate) fn execute<W: WriteColor>(w: &mut W, replica: &mut Replica) -> Result<(), crate::Error> {
+    let (user, synthetic) = tag_counts(replica)?;
+
+    if user.is_empty() {
+        writeln!(w, "No tags.")?;
+    } else {
+        print_counts(w, "Tag", user)?;
+    }
+
+    if !synthetic.is_empty() {
+        writeln!(w)?;
+        print_counts(w, "Synthetic Tag", synthetic)?;
+    }
+
+    Ok(())
+}
+
+/// Rename a tag on all tasks
+pub(crate) fn rename<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    old: &Tag,
+    new: &Tag,
+) -> Result<(), crate::Error> {
+    let count = replica.rename_tag(old, new)?;
+    writeln!(
+        w,
+        "renamed tag {} to {} on {} task{}",
+        old,
+        new,
+        count,
+        if count == 1 { "" } else { "s" }
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_tag_counts() {
+        let mut replica = test_replica();
+        let t1 = replica.new_task(Status::Pending, s!("one")).unwrap();
+        let mut t1 = t1.into_mut(&mut replica);
+        t1.add_tag(&tag!("home")).unwrap();
+        t1.add_tag(&tag!("next")).unwrap();
+        t1.start().unwrap();
+
+        let t2 = replica.new_task(Status::Pending, s!("two")).unwrap();
+        let mut t2 = t2.into_mut(&mut replica);
+        t2.add_tag(&tag!("home")).unwrap();
+
+        // tags of completed tasks are not counted
+        let t3 = replica.new_task(Status::Completed, s!("three")).unwrap();
+        let mut t3 = t3.into_mut(&mut replica);
+        t3.add_tag(&tag!("old")).unwrap();
+
+        let (user, synthetic) = tag_counts(&mut replica).unwrap();
+        let user: Vec<_> = user.into_iter().map(|(t, c)| (t.to_string(), c)).collect();
+        assert_eq!(user, vec![(s!("home"), 2), (s!("next"), 1)]);
+        let synthetic: Vec<_> = synthetic
+            .into_iter()
+            .map(|(t, c)| (t.to_string(), c))
+            .collect();
+        assert_eq!(
+            synthetic,
+            vec![(s!("ACTIVE"), 1), (s!("PENDING"), 2), (s!("UNBLOCKED"), 2)]
+        );
+    }
+
+    #[test]
+    fn test_rename() {
+        let mut w = test_writer();
+        let mut replica = test_replica();
+        let t = replica.new_task(Status::Pending, s!("one")).unwrap();
+        let mut t = t.into_mut(&mut replica);
+        t.add_tag(&tag!("hom")).unwrap();
+        let uuid = t.get_uuid();
+
+        rename(&mut w, &mut replica, &tag!("hom"), &tag!("home")).unwrap();
+        assert_eq!(w.into_string(), "renamed tag hom to home on 1 task\n");
+        let t = replica.get_task(uuid).unwrap().unwrap();
+        assert!(t.has_tag(&tag!("home")));
+        assert!(!t.has_tag(&tag!("hom")));
+
+        let mut w = test_writer();
+        assert!(rename(&mut w, &mut replica, &tag!("home"), &tag!("BLOCKED")).is_err());
+    }
+}
@@ -1,12 +1,82 @@
+use crate::invocation::util::{confirm, summarize_task};
+use taskchampion::schema::Prop;
+use taskchampion::storage::ReplicaOp;
 use taskchampion::Replica;
 use termcolor::WriteColor;
 
-pub(crate) fn execute<W: WriteColor>(w: &mut W, replica: &mut Replica) -> Result<(), crate::Error> {
-    if replica.undo()? {
-        writeln!(w, "Undo successful.")?;
-    } else {
+/// Describe a value that may be missing
+fn describe_value(value: &Option<String>) -> String {
+    match value {
+        Some(v) => format!("{:?}", v),
+        None => "(none)".to_owned(),
+    }
+}
+
+/// Describe a single operation to be undone, or return None if the operation is not interesting
+/// to the user.
+fn describe_op(replica: &mut Replica, op: &ReplicaOp) -> anyhow::Result<Option<String>> {
+    let describe_task = |replica: &mut Replica, uuid| -> anyhow::Result<String> {
+        Ok(match replica.get_task(uuid)? {
+            Some(task) => summarize_task(replica, &task)?,
+            None => uuid.to_string(),
+        })
+    };
+    Ok(match op {
+        ReplicaOp::Create { uuid } => Some(format!("{}: created", describe_task(replica, *uuid)?)),
+        ReplicaOp::Delete { uuid, old_task } => {
+            Some(match old_task.get(Prop::Description.as_ref()) {
+                Some(description) => format!("{} - {}: deleted", uuid, description),
+                None => format!("{}: deleted", uuid),
+            })
+        }
+        // every change updates `modified`, so there is no need to show it
+        ReplicaOp::Update { property, .. } if property == Prop::Modified.as_ref() => None,
+        ReplicaOp::Update {
+            uuid,
+            property,
+            old_value,
+            value,
+            ..
+        } => Some(format!(
+            "{}: {}: {} -> {}",
+            describe_task(replica, *uuid)?,
+            property,
+            describe_value(old_value),
+            describe_value(value)
+        )),
+        ReplicaOp::UndoPoint => None,
+    })
+}
+
+pub(crate) fn execute<W: WriteColor>(
+    w: &mut W,
+    replica: &mut Replica,
+    count: usize,
+) -> Result<(), crate::Error> {
+    let ops = replica.get_undo_ops(count)?;
+    if ops.is_empty() {
         writeln!(w, "Nothing to undo.")?;
+        return Ok(());
+    }
+
+    writeln!(w, "The following changes will be undone:")?;
+    for op in &ops {
+        if let Some(description) = describe_op(replica, op)? {
+            writeln!(w, "  {}", description)?;
+        }
+    }
+
+    if !confirm("Undo these changes?")? {
+        writeln!(w, "Cancelled")?;
+        return Ok(());
     }
+
+    for _ in 0..count {
+        if !replica.undo()? {
+            break;
+        }
+    }
+    writeln!(w, "Undo successful.")?;
     Ok(())
 }
 
@@ -15,6 +85,7 @@ mod test {
     use super::*;
     use crate::invocation::test::*;
     use pretty_assertions::assert_eq;
+    use taskchampion::Status;
 
     #[test]
     fn test_undo() {
@@ -22,7 +93,41 @@ mod test {
         let mut replica = test_replica();
 
         // Note that the details of the actual undo operation are tested thoroughly in the taskchampion crate
-        execute(&mut w, &mut replica).unwrap();
+        execute(&mut w, &mut replica, 1).unwrap();
         assert_eq!(&w.into_string(), "Nothing to undo.\n")
     }
+
+    #[test]
+    fn test_describe_ops() {
+        let mut replica = test_replica();
+        let uuid = replica
+            .new_task(Status::Pending, s!("my task"))
+            .unwrap()
+            .get_uuid();
+        replica.add_undo_point(true).unwrap();
+        replica
+            .get_task(uuid)
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica)
+            .set_priority(s!("H"))
+            .unwrap();
+
+        let descriptions: Vec<_> = replica
+            .get_undo_ops(1)
+            .unwrap()
+            .iter()
+            .filter_map(|op| describe_op(&mut replica, op).unwrap())
+            .collect();
+        assert_eq!(
+            descriptions,
+            vec![s!("1 - my task: priority: (none) -> \"H\"")]
+        );
+
+        let ops = replica.get_undo_ops(2).unwrap();
+        assert_eq!(
+            describe_op(&mut replica, &ops[0]).unwrap(),
+            Some(s!("1 - my task: created"))
+        );
+    }
 }
@@ -1,47 +1,106 @@
-use crate::argparse::{Condition, Filter, TaskId};
+use crate::argparse::{Condition, DateProperty, Filter, Pattern, TaskId};
 use std::collections::HashSet;
+use taskchampion::chrono::prelude::*;
 use taskchampion::{Replica, Status, Task, Uuid, WorkingSet};
 
-fn match_task(filter: &Filter, task: &Task, uuid: Uuid, working_set: &WorkingSet) -> bool {
-    for cond in &filter.conditions {
-        match cond {
-            Condition::HasTag(ref tag) => {
-                if !task.has_tag(tag) {
-                    return false;
-                }
-            }
-            Condition::NoTag(ref tag) => {
-                if task.has_tag(tag) {
-                    return false;
-                }
-            }
-            Condition::Status(status) => {
-                if task.get_status() != *status {
-                    return false;
-                }
-            }
-            Condition::IdList(ids) => {
-                let uuid_str = uuid.to_string();
-                let mut found = false;
-                let working_set_id = working_set.by_uuid(uuid);
-
-                for id in ids {
-                    if match id {
-                        TaskId::WorkingSetId(i) => Some(*i) == working_set_id,
-                        TaskId::PartialUuid(partial) => uuid_str.starts_with(partial),
-                        TaskId::Uuid(i) => *i == uuid,
-                    } {
-                        found = true;
-                        break;
-                    }
-                }
-                if !found {
-                    return false;
-                }
+/// Rank a priority value for comparison; unrecognized or empty priorities rank lowest.
+fn priority_rank(priority: &str) -> u8 {
+    match priority {
+        "H" => 3,
+        "M" => 2,
+        "L" => 1,
+        _ => 0,
+    }
+}
+
+/// Determine whether `task_project` is `project` or one of its sub-projects.
+fn is_in_project(task_project: &str, project: &str) -> bool {
+    match task_project.strip_prefix(project) {
+        Some(rest) => rest.is_empty() || rest.starts_with('.'),
+        None => false,
+    }
+}
+
+/// Get the value of a timestamp-valued property of a task.
+fn get_date_property(task: &Task, prop: DateProperty) -> Option<DateTime<Utc>> {
+    match prop {
+        DateProperty::Due => task.get_due(),
+        DateProperty::Wait => task.get_wait(),
+        DateProperty::Scheduled => task.get_scheduled(),
+        DateProperty::Until => task.get_until(),
+        DateProperty::Entry => task.get_entry(),
+        DateProperty::Modified => task.get_modified(),
+        DateProperty::End => task.get_end(),
+    }
+}
+
+fn match_condition(cond: &Condition, task: &Task, uuid: Uuid, working_set: &WorkingSet) -> bool {
+    match cond {
+        Condition::HasTag(ref tag) => task.has_tag(tag),
+        Condition::NoTag(ref tag) => !task.has_tag(tag),
+        Condition::Status(status) => task.get_status() == *status,
+        Condition::IdList(ids) => {
+            let uuid_str = uuid.to_string();
+            let working_set_id = working_set.by_uuid(uuid);
+
+            ids.iter().any(|id| match id {
+                TaskId::WorkingSetId(i) => Some(*i) == working_set_id,
+                TaskId::PartialUuid(partial) => uuid_str.starts_with(partial),
+                TaskId::Uuid(i) => *i == uuid,
+            })
+        }
+        Condition::Due(due) => {
+            let same_day = |a: DateTime<Utc>, b: DateTime<Utc>| {
+                a.with_timezone(&Local).date() == b.with_timezone(&Local).date()
+            };
+            match (task.get_due(), due) {
+                (Some(task_due), Some(due)) => same_day(task_due, *due),
+                (None, None) => true,
+                _ => false,
             }
         }
+        Condition::PriorityAbove(priority) => {
+            priority_rank(task.get_priority()) > priority_rank(priority)
+        }
+        Condition::PriorityBelow(priority) => {
+            priority_rank(task.get_priority()) < priority_rank(priority)
+        }
+        Condition::Project(project) => match (task.get_project(), project) {
+            (Some(task_project), Some(project)) => is_in_project(task_project, project),
+            (None, None) => true,
+            _ => false,
+        },
+        Condition::Before(prop, ts) => {
+            matches!(get_date_property(task, *prop), Some(value) if value < *ts)
+        }
+        Condition::After(prop, ts) => {
+            matches!(get_date_property(task, *prop), Some(value) if value > *ts)
+        }
+        Condition::Matches(Pattern(re)) => {
+            re.is_match(task.get_description())
+                || task.get_annotations().any(|a| re.is_match(&a.description))
+        }
+        Condition::Annotation(Some(text)) => {
+            let text = text.to_lowercase();
+            task.get_annotations()
+                .any(|a| a.description.to_lowercase().contains(&text))
+        }
+        Condition::Annotation(None) => task.get_annotations().next().is_none(),
+        Condition::Not(cond) => !match_condition(cond, task, uuid, working_set),
+        Condition::And(conds) => conds
+            .iter()
+            .all(|c| match_condition(c, task, uuid, working_set)),
+        Condition::Or(conds) => conds
+            .iter()
+            .any(|c| match_condition(c, task, uuid, working_set)),
     }
-    true
+}
+
+fn match_task(filter: &Filter, task: &Task, uuid: Uuid, working_set: &WorkingSet) -> bool {
+    filter
+        .conditions
+        .iter()
+        .all(|cond| match_condition(cond, task, uuid, working_set))
 }
 
 // the universe of tasks we must consider
@@ -173,7 +232,8 @@ mod test {
     use super::*;
     use crate::invocation::test::*;
     use pretty_assertions::assert_eq;
-    use taskchampion::Status;
+    use taskchampion::chrono::Duration;
+    use taskchampion::{Annotation, Status};
 
     #[test]
     fn exact_ids() {
@@ -303,6 +363,391 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn blocked_filtering() -> anyhow::Result<()> {
+        let mut replica = test_replica();
+
+        let t1 = replica.new_task(Status::Pending, s!("A"))?;
+        let t2 = replica.new_task(Status::Pending, s!("B"))?;
+        let t3 = replica.new_task(Status::Pending, s!("C"))?;
+        let _t4 = replica.new_task(Status::Pending, s!("D"))?;
+        let (uuid2, uuid3) = (t2.get_uuid(), t3.get_uuid());
+
+        // A depends on B, and B depends on C
+        t1.into_mut(&mut replica).add_dependency(uuid2)?;
+        t2.into_mut(&mut replica).add_dependency(uuid3)?;
+        replica.dependency_map(true)?;
+
+        let filter = Filter {
+            conditions: vec![Condition::HasTag(tag!("BLOCKED"))],
+        };
+        let mut filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        filtered.sort();
+        assert_eq!(vec![s!("A"), s!("B")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::HasTag(tag!("BLOCKING"))],
+        };
+        let mut filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        filtered.sort();
+        assert_eq!(vec![s!("B"), s!("C")], filtered);
+
+        Ok(())
+    }
+
+    #[test]
+    fn status_and_overdue_filtering() -> anyhow::Result<()> {
+        let mut replica = test_replica();
+        let now = Utc::now();
+
+        let mut t = replica
+            .new_task(Status::Pending, s!("overdue"))?
+            .into_mut(&mut replica);
+        t.set_due(Some(now - Duration::days(1)))?;
+        let mut t = replica
+            .new_task(Status::Pending, s!("waiting"))?
+            .into_mut(&mut replica);
+        t.set_wait(Some(now + Duration::days(1)))?;
+        let mut t = replica
+            .new_task(Status::Pending, s!("done"))?
+            .into_mut(&mut replica);
+        t.set_due(Some(now - Duration::days(1)))?;
+        t.done()?;
+
+        let filter = Filter {
+            conditions: vec![Condition::parse_str("+OVERDUE")?],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("overdue")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::parse_str("status:waiting")?],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("waiting")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::parse_str("status:completed")?],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("done")], filtered);
+
+        Ok(())
+    }
+
+    #[test]
+    fn due_filtering() -> anyhow::Result<()> {
+        let mut replica = test_replica();
+        let today = Local::today().and_hms(12, 0, 0).with_timezone(&Utc);
+        let tomorrow = today + Duration::days(1);
+
+        let mut t1 = replica
+            .new_task(Status::Pending, s!("A"))?
+            .into_mut(&mut replica);
+        t1.set_due(Some(today))?;
+        let mut t2 = replica
+            .new_task(Status::Pending, s!("B"))?
+            .into_mut(&mut replica);
+        t2.set_due(Some(tomorrow))?;
+        let _t3 = replica.new_task(Status::Pending, s!("C"))?;
+
+        let filter = Filter {
+            conditions: vec![Condition::Due(Some(tomorrow + Duration::hours(1)))],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("B")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::Due(None)],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("C")], filtered);
+
+        Ok(())
+    }
+
+    #[test]
+    fn priority_filtering() -> anyhow::Result<()> {
+        let mut replica = test_replica();
+
+        for &(descr, priority) in &[("A", "H"), ("B", "M"), ("C", "L"), ("D", "")] {
+            let mut t = replica
+                .new_task(Status::Pending, s!(descr))?
+                .into_mut(&mut replica);
+            if !priority.is_empty() {
+                t.set_priority(s!(priority))?;
+            }
+        }
+
+        let filter = Filter {
+            conditions: vec![Condition::PriorityAbove(s!("L"))],
+        };
+        let mut filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        filtered.sort();
+        assert_eq!(vec![s!("A"), s!("B")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::PriorityBelow(s!("M"))],
+        };
+        let mut filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        filtered.sort();
+        assert_eq!(vec![s!("C"), s!("D")], filtered);
+
+        Ok(())
+    }
+
+    #[test]
+    fn project_filtering() -> anyhow::Result<()> {
+        let mut replica = test_replica();
+
+        for &(descr, project) in &[
+            ("A", Some("work")),
+            ("B", Some("work.client")),
+            ("C", Some("workshop")),
+            ("D", None),
+        ] {
+            replica
+                .new_task(Status::Pending, s!(descr))?
+                .into_mut(&mut replica)
+                .set_project(project.map(|p| p.to_owned()))?;
+        }
+
+        let filter = Filter {
+            conditions: vec![Condition::Project(Some(s!("work")))],
+        };
+        let mut filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        filtered.sort();
+        assert_eq!(vec![s!("A"), s!("B")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::Project(Some(s!("work.client")))],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("B")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::Project(None)],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("D")], filtered);
+
+        Ok(())
+    }
+
+    #[test]
+    fn annotation_filtering() -> anyhow::Result<()> {
+        let mut replica = test_replica();
+
+        for &(descr, annotation) in &[
+            ("A", Some("see JIRA-123")),
+            ("B", Some("see jira-456")),
+            ("C", None),
+        ] {
+            let mut t = replica
+                .new_task(Status::Pending, s!(descr))?
+                .into_mut(&mut replica);
+            if let Some(annotation) = annotation {
+                t.add_annotation(Annotation {
+                    entry: Utc::now(),
+                    description: s!(annotation),
+                })?;
+            }
+        }
+
+        let filter = Filter {
+            conditions: vec![Condition::Annotation(Some(s!("jira-123")))],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("A")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::Annotation(None)],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("C")], filtered);
+
+        Ok(())
+    }
+
+    #[test]
+    fn boolean_filtering() -> anyhow::Result<()> {
+        let mut replica = test_replica();
+
+        let mut t = replica
+            .new_task(Status::Pending, s!("A"))?
+            .into_mut(&mut replica);
+        t.set_project(Some(s!("home")))?;
+        let mut t = replica
+            .new_task(Status::Pending, s!("B"))?
+            .into_mut(&mut replica);
+        t.add_tag(&tag!("errand"))?;
+        t.add_tag(&tag!("waiting"))?;
+        let mut t = replica
+            .new_task(Status::Pending, s!("C"))?
+            .into_mut(&mut replica);
+        t.add_tag(&tag!("errand"))?;
+        replica.new_task(Status::Pending, s!("D"))?;
+
+        let filter = Filter {
+            conditions: vec![
+                Condition::Or(vec![
+                    Condition::Project(Some(s!("home"))),
+                    Condition::HasTag(tag!("errand")),
+                ]),
+                Condition::NoTag(tag!("waiting")),
+            ],
+        };
+        let mut filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        filtered.sort();
+        assert_eq!(vec![s!("A"), s!("C")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::Not(Box::new(Condition::And(vec![
+                Condition::HasTag(tag!("errand")),
+                Condition::HasTag(tag!("waiting")),
+            ])))],
+        };
+        let mut filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        filtered.sort();
+        assert_eq!(vec![s!("A"), s!("C"), s!("D")], filtered);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_filtering() -> anyhow::Result<()> {
+        let mut replica = test_replica();
+
+        replica.new_task(Status::Pending, s!("Send Invoice"))?;
+        let mut t2 = replica
+            .new_task(Status::Pending, s!("email client"))?
+            .into_mut(&mut replica);
+        t2.add_annotation(Annotation {
+            entry: Utc::now(),
+            description: s!("about the invoice"),
+        })?;
+        replica.new_task(Status::Pending, s!("walk dog"))?;
+
+        let filter = Filter {
+            conditions: vec![Condition::parse_str("/invoice/")?],
+        };
+        let mut filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        filtered.sort();
+        assert_eq!(vec![s!("Send Invoice"), s!("email client")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::parse_str("/^walk/")?],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("walk dog")], filtered);
+
+        Ok(())
+    }
+
+    #[test]
+    fn date_cmp_filtering() -> anyhow::Result<()> {
+        let mut replica = test_replica();
+        let now = Utc::now();
+
+        let mut t1 = replica
+            .new_task(Status::Pending, s!("A"))?
+            .into_mut(&mut replica);
+        t1.set_scheduled(Some(now - Duration::days(1)))?;
+        t1.set_until(Some(now + Duration::days(3)))?;
+        let mut t2 = replica
+            .new_task(Status::Pending, s!("B"))?
+            .into_mut(&mut replica);
+        t2.set_scheduled(Some(now + Duration::days(1)))?;
+        let _t3 = replica.new_task(Status::Pending, s!("C"))?;
+
+        let filter = Filter {
+            conditions: vec![Condition::Before(DateProperty::Scheduled, now)],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("A")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::After(DateProperty::Scheduled, now)],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("B")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::After(DateProperty::Until, now)],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("A")], filtered);
+
+        let mut t3 = replica
+            .new_task(Status::Pending, s!("D"))?
+            .into_mut(&mut replica);
+        t3.set_due(Some(now + Duration::days(2)))?;
+        t3.done()?;
+
+        let filter = Filter {
+            conditions: vec![Condition::Before(
+                DateProperty::Due,
+                now + Duration::days(7),
+            )],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("D")], filtered);
+
+        let filter = Filter {
+            conditions: vec![Condition::After(DateProperty::End, now - Duration::days(1))],
+        };
+        let filtered: Vec<_> = filtered_tasks(&mut replica, &filter)?
+            .map(|t| t.get_description().to_owned())
+            .collect();
+        assert_eq!(vec![s!("D")], filtered);
+
+        Ok(())
+    }
+
     #[test]
     fn pending_tasks() {
         let mut replica = test_replica();
@@ -1,21 +1,28 @@
 //! The invocation module handles invoking the commands parsed by the argparse module.
 
-use crate::argparse::{Command, Subcommand};
-use crate::settings::Settings;
+use crate::argparse::{ColorMode, Command, Subcommand};
+use crate::settings::{resolve_secret, Settings};
+use anyhow::Context;
 use taskchampion::{Replica, Server, ServerConfig, StorageConfig, Uuid};
 use termcolor::{ColorChoice, StandardStream};
 
 mod cmd;
 mod filter;
 mod modify;
+mod pager;
 mod report;
+mod urgency;
 mod util;
 
 #[cfg(test)]
 mod test;
 
 use filter::filtered_tasks;
-use modify::{apply_modification, resolve_modification, ResolvedModification};
+use modify::{
+    apply_modification, describe_changes, preview_modification, resolve_modification,
+    ResolvedModification,
+};
+use pager::paged;
 use report::display_report;
 
 /// Invoke the given Command in the context of the given settings
@@ -24,7 +31,13 @@ pub(crate) fn invoke(command: Command, settings: Settings) -> Result<(), crate::
     log::debug!("command: {:?}", command);
     log::debug!("settings: {:?}", settings);
 
-    let mut w = get_writer();
+    let mut w = get_writer(command.color);
+
+    if command.dry_run && !supports_dry_run(&command.subcommand) {
+        return Err(crate::Error::for_arguments(
+            "--dry-run is only supported for adding and modifying tasks, and for read-only subcommands",
+        ));
+    }
 
     // This function examines the command and breaks out the necessary bits to call one of the
     // `execute` functions in a submodule of `cmd`.
@@ -33,13 +46,24 @@ pub(crate) fn invoke(command: Command, settings: Settings) -> Result<(), crate::
     // getting the replica
     match command {
         Command {
-            subcommand: Subcommand::Help { summary },
+            subcommand: Subcommand::Help { summary, topic },
             command_name,
-        } => return cmd::help::execute(&mut w, command_name, summary),
+            color,
+            no_pager,
+            ..
+        } => {
+            return paged(color, &settings, no_pager, |w| {
+                cmd::help::execute(w, &settings, command_name, summary, topic)
+            })
+        }
         Command {
             subcommand: Subcommand::Config { config_operation },
             ..
         } => return cmd::config::execute(&mut w, config_operation, &settings),
+        Command {
+            subcommand: Subcommand::Context { context_operation },
+            ..
+        } => return cmd::context::execute(&mut w, context_operation, &settings),
         Command {
             subcommand: Subcommand::Version,
             ..
@@ -50,11 +74,25 @@ pub(crate) fn invoke(command: Command, settings: Settings) -> Result<(), crate::
     let mut replica = get_replica(&settings)?;
     match command {
         Command {
-            subcommand: Subcommand::Add { modification },
+            subcommand:
+                Subcommand::Add {
+                    modification,
+                    stdin,
+                },
+            yes,
+            dry_run,
             ..
         } => {
             let modification = resolve_modification(modification, &mut replica)?;
-            return cmd::add::execute(&mut w, &mut replica, modification);
+            return cmd::add::execute(
+                &mut w,
+                &mut replica,
+                &settings,
+                modification,
+                stdin,
+                yes,
+                dry_run,
+            );
         }
 
         Command {
@@ -63,10 +101,71 @@ pub(crate) fn invoke(command: Command, settings: Settings) -> Result<(), crate::
                     filter,
                     modification,
                 },
+            yes,
+            dry_run,
+            ..
+        } => {
+            let modification = resolve_modification(modification, &mut replica)?;
+            return cmd::modify::execute(
+                &mut w,
+                &mut replica,
+                &settings,
+                filter,
+                modification,
+                yes,
+                dry_run,
+            );
+        }
+
+        Command {
+            subcommand:
+                Subcommand::Duplicate {
+                    filter,
+                    modification,
+                },
             ..
         } => {
             let modification = resolve_modification(modification, &mut replica)?;
-            return cmd::modify::execute(&mut w, &mut replica, &settings, filter, modification);
+            return cmd::duplicate::execute(&mut w, &mut replica, filter, modification);
+        }
+
+        Command {
+            subcommand: Subcommand::Pick { filter, command },
+            command_name,
+            color,
+            format,
+            yes,
+            dry_run,
+            no_pager,
+            profile,
+            date_format,
+            verbosity,
+        } => {
+            let uuid = cmd::pick::execute(&mut w, &mut replica, filter, command.is_empty())?;
+            let uuid = match uuid {
+                Some(uuid) if !command.is_empty() => uuid,
+                _ => return Ok(()),
+            };
+
+            // run the command on the picked task, as if it had been given on the command line
+            drop(replica);
+            let uuid = uuid.to_string();
+            let mut argv = vec![command_name.as_str(), uuid.as_str()];
+            argv.extend(command.iter().map(|s| s.as_str()));
+            let argv = crate::argparse::expand_aliases(&argv[..], &settings.aliases)?;
+            let argv: Vec<&str> = argv.iter().map(|s| s.as_str()).collect();
+            let picked = Command {
+                color,
+                format,
+                yes,
+                dry_run,
+                no_pager,
+                profile,
+                date_format,
+                verbosity,
+                ..Command::from_argv(&argv[..])?
+            };
+            return invoke(picked, settings);
         }
 
         Command {
@@ -74,14 +173,85 @@ pub(crate) fn invoke(command: Command, settings: Settings) -> Result<(), crate::
                 Subcommand::Report {
                     report_name,
                     filter,
+                    options,
                 },
+            format,
+            color,
+            no_pager,
             ..
-        } => return cmd::report::execute(&mut w, &mut replica, &settings, report_name, filter),
+        } => {
+            return paged(color, &settings, no_pager, |w| {
+                let report_name = report_name.unwrap_or_else(|| settings.default_report.clone());
+                cmd::report::execute(
+                    w,
+                    &mut replica,
+                    &settings,
+                    report_name,
+                    filter,
+                    options,
+                    format,
+                )
+            })
+        }
 
         Command {
             subcommand: Subcommand::Info { filter, debug },
+            format,
+            ..
+        } => return cmd::info::execute(&mut w, &mut replica, &settings, filter, debug, format),
+
+        Command {
+            subcommand: Subcommand::Edit { filter },
+            ..
+        } => return cmd::edit::execute(&mut w, &mut replica, filter),
+
+        Command {
+            subcommand: Subcommand::Export { filter, format },
+            ..
+        } => return cmd::export::execute(&mut w, &mut replica, filter, format),
+
+        Command {
+            subcommand: Subcommand::Count { filter },
+            ..
+        } => return cmd::count::execute(&mut w, &mut replica, filter),
+
+        Command {
+            subcommand: Subcommand::Ids { filter, uuids },
+            ..
+        } => return cmd::ids::execute(&mut w, &mut replica, filter, uuids),
+
+        Command {
+            subcommand: Subcommand::Show,
+            ..
+        } => return cmd::show::execute(&mut w, &mut replica, &settings),
+
+        Command {
+            subcommand: Subcommand::Prompt,
+            ..
+        } => return cmd::prompt::execute(&mut w, &mut replica),
+
+        Command {
+            subcommand: Subcommand::Stats { filter },
+            ..
+        } => return cmd::stats::execute(&mut w, &mut replica, filter),
+
+        Command {
+            subcommand: Subcommand::Burndown { filter },
+            ..
+        } => return cmd::burndown::execute(&mut w, &mut replica, filter),
+
+        Command {
+            subcommand: Subcommand::Calendar { filter },
+            ..
+        } => return cmd::calendar::execute(&mut w, &mut replica, filter),
+
+        Command {
+            subcommand: Subcommand::Reminders { within, notify },
+            format,
             ..
-        } => return cmd::info::execute(&mut w, &mut replica, filter, debug),
+        } => {
+            return cmd::reminders::execute(&mut w, &mut replica, &settings, within, notify, format)
+        }
 
         Command {
             subcommand: Subcommand::Gc,
@@ -89,13 +259,45 @@ pub(crate) fn invoke(command: Command, settings: Settings) -> Result<(), crate::
         } => return cmd::gc::execute(&mut w, &mut replica),
 
         Command {
-            subcommand: Subcommand::Sync,
+            subcommand: Subcommand::Projects,
+            ..
+        } => return cmd::projects::execute(&mut w, &mut replica),
+
+        Command {
+            subcommand: Subcommand::Tags,
+            ..
+        } => return cmd::tags::execute(&mut w, &mut replica),
+
+        Command {
+            subcommand: Subcommand::RenameTag { old, new },
+            ..
+        } => return cmd::tags::rename(&mut w, &mut replica, &old, &new),
+
+        Command {
+            subcommand: Subcommand::RenameProject { old, new },
+            ..
+        } => return cmd::projects::rename(&mut w, &mut replica, &old, &new),
+
+        Command {
+            subcommand: Subcommand::Sync { init: true },
+            ..
+        } => return cmd::sync::init(&mut w, &mut replica, &settings),
+
+        Command {
+            subcommand: Subcommand::Sync { init: false },
             ..
         } => {
             let mut server = get_server(&settings)?;
             return cmd::sync::execute(&mut w, &mut replica, &settings, &mut server);
         }
 
+        Command {
+            subcommand: Subcommand::Import { path, format },
+            ..
+        } => {
+            return cmd::import::execute(&mut w, &mut replica, path, format);
+        }
+
         Command {
             subcommand: Subcommand::ImportTW,
             ..
@@ -111,10 +313,24 @@ pub(crate) fn invoke(command: Command, settings: Settings) -> Result<(), crate::
         }
 
         Command {
-            subcommand: Subcommand::Undo,
+            subcommand: Subcommand::Completions { shell },
+            command_name,
+            ..
+        } => {
+            return cmd::completions::execute(
+                &mut w,
+                &mut replica,
+                &settings,
+                &command_name,
+                shell,
+            );
+        }
+
+        Command {
+            subcommand: Subcommand::Undo { count },
             ..
         } => {
-            return cmd::undo::execute(&mut w, &mut replica);
+            return cmd::undo::execute(&mut w, &mut replica, count);
         }
 
         // handled in the first match, but here to ensure this match is exhaustive
@@ -126,6 +342,10 @@ pub(crate) fn invoke(command: Command, settings: Settings) -> Result<(), crate::
             subcommand: Subcommand::Config { .. },
             ..
         } => unreachable!(),
+        Command {
+            subcommand: Subcommand::Context { .. },
+            ..
+        } => unreachable!(),
         Command {
             subcommand: Subcommand::Version,
             ..
@@ -135,6 +355,24 @@ pub(crate) fn invoke(command: Command, settings: Settings) -> Result<(), crate::
 
 // utilities for invoke
 
+/// Determine whether the given subcommand can be run with `--dry-run`: either it supports
+/// previewing its changes, or it does not change anything.
+fn supports_dry_run(subcommand: &Subcommand) -> bool {
+    !matches!(
+        subcommand,
+        Subcommand::Config { .. }
+            | Subcommand::Context { .. }
+            | Subcommand::Duplicate { .. }
+            | Subcommand::Edit { .. }
+            | Subcommand::Gc
+            | Subcommand::Sync { .. }
+            | Subcommand::Import { .. }
+            | Subcommand::ImportTW
+            | Subcommand::ImportTDB2 { .. }
+            | Subcommand::Undo { .. }
+    )
+}
+
 /// Get the replica for this invocation
 fn get_replica(settings: &Settings) -> anyhow::Result<Replica> {
     let taskdb_dir = settings.data_dir.clone();
@@ -152,28 +390,42 @@ fn get_server(settings: &Settings) -> anyhow::Result<Box<dyn Server>> {
         settings.server_origin.as_ref(),
         settings.encryption_secret.as_ref(),
     ) {
-        let client_key = Uuid::parse_str(client_key)?;
+        let client_key = resolve_secret(client_key).context("server_client_key")?;
+        let client_key = Uuid::parse_str(&client_key)?;
+        let encryption_secret = resolve_secret(encryption_secret).context("encryption_secret")?;
 
         log::debug!("Using sync-server with origin {}", origin);
         log::debug!("Sync client ID: {}", client_key);
         ServerConfig::Remote {
             origin: origin.clone(),
             client_key,
-            encryption_secret: encryption_secret.as_bytes().to_vec(),
+            encryption_secret: encryption_secret.into_bytes(),
         }
     } else {
         let server_dir = settings.server_dir.clone();
         log::debug!("Using local sync-server at `{:?}`", server_dir);
         ServerConfig::Local { server_dir }
     };
-    config.into_server()
+    Ok(config.into_server()?)
 }
 
-/// Get a WriteColor implementation based on whether the output is a tty.
-fn get_writer() -> StandardStream {
-    StandardStream::stdout(if atty::is(atty::Stream::Stdout) {
-        ColorChoice::Auto
-    } else {
-        ColorChoice::Never
-    })
+/// Get a WriteColor implementation based on the `--color` option and, by default, whether the
+/// output is a tty and whether `NO_COLOR` is set.
+fn get_writer(color: ColorMode) -> StandardStream {
+    StandardStream::stdout(color_choice(color))
+}
+
+/// Determine whether to use color for output to stdout
+fn color_choice(color: ColorMode) -> ColorChoice {
+    match color {
+        ColorMode::Always => ColorChoice::Always,
+        ColorMode::Never => ColorChoice::Never,
+        ColorMode::Auto => {
+            if atty::is(atty::Stream::Stdout) && std::env::var_os("NO_COLOR").is_none() {
+                ColorChoice::Auto
+            } else {
+                ColorChoice::Never
+            }
+        }
+    }
 }
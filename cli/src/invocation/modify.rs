@@ -1,7 +1,9 @@
 use crate::argparse::{DescriptionMod, Modification, TaskId};
-use std::collections::HashSet;
+use anyhow::bail;
+use std::collections::{BTreeSet, HashSet};
 use taskchampion::chrono::Utc;
-use taskchampion::{Annotation, Replica, TaskMut};
+use taskchampion::schema::Prop;
+use taskchampion::{Annotation, Replica, StorageConfig, Task, TaskMut};
 
 /// A wrapper for Modification, promising that all TaskId instances are of variant TaskId::Uuid.
 pub(super) struct ResolvedModification(pub(super) Modification);
@@ -19,12 +21,21 @@ pub(super) fn resolve_modification(
         description: unres.description,
         status: unres.status,
         wait: unres.wait,
+        due: unres.due,
+        scheduled: unres.scheduled,
+        until: unres.until,
+        recur: unres.recur,
+        project: unres.project,
+        priority: unres.priority,
         active: unres.active,
         add_tags: unres.add_tags,
         remove_tags: unres.remove_tags,
         add_dependencies: resolve_task_ids(replica, unres.add_dependencies)?,
         remove_dependencies: resolve_task_ids(replica, unres.remove_dependencies)?,
         annotate: unres.annotate,
+        denotate: unres.denotate,
+        add_links: unres.add_links,
+        remove_links: unres.remove_links,
     }))
 }
 
@@ -92,6 +103,13 @@ pub(super) fn apply_modification(
     // unwrap the "Resolved" promise
     let modification = &modification.0;
 
+    // a recurring task must have a due date, so check that before making any changes
+    let recur = modification.recur.unwrap_or_else(|| task.get_recur());
+    let due = modification.due.unwrap_or_else(|| task.get_due());
+    if recur.is_some() && due.is_none() {
+        bail!("A recurring task must have a due date");
+    }
+
     match modification.description {
         DescriptionMod::Set(ref description) => task.set_description(description.clone())?,
         DescriptionMod::Prepend(ref description) => {
@@ -107,6 +125,14 @@ pub(super) fn apply_modification(
         task.set_status(status.clone())?;
     }
 
+    if let Some(ref project) = modification.project {
+        task.set_project(project.clone())?;
+    }
+
+    if let Some(ref priority) = modification.priority {
+        task.set_priority(priority.clone())?;
+    }
+
     if let Some(true) = modification.active {
         task.start()?;
     }
@@ -127,6 +153,22 @@ pub(super) fn apply_modification(
         task.set_wait(wait)?;
     }
 
+    if let Some(due) = modification.due {
+        task.set_due(due)?;
+    }
+
+    if let Some(scheduled) = modification.scheduled {
+        task.set_scheduled(scheduled)?;
+    }
+
+    if let Some(until) = modification.until {
+        task.set_until(until)?;
+    }
+
+    if let Some(recur) = modification.recur {
+        task.set_recur(recur)?;
+    }
+
     if let Some(ref ann) = modification.annotate {
         task.add_annotation(Annotation {
             entry: Utc::now(),
@@ -134,6 +176,24 @@ pub(super) fn apply_modification(
         })?;
     }
 
+    if let Some(ref pattern) = modification.denotate {
+        let matching: Vec<_> = task
+            .get_annotations()
+            .filter(|ann| ann.description.contains(pattern.as_str()))
+            .collect();
+        for ann in matching {
+            task.remove_annotation(ann.entry)?;
+        }
+    }
+
+    for url in modification.add_links.iter() {
+        task.add_link(url.clone())?;
+    }
+
+    for url in modification.remove_links.iter() {
+        task.remove_link(url)?;
+    }
+
     for tid in &modification.add_dependencies {
         if let TaskId::Uuid(u) = tid {
             task.add_dependency(*u)?;
@@ -157,11 +217,46 @@ pub(super) fn apply_modification(
     Ok(())
 }
 
+/// Apply the given modification to a copy of the task in a scratch replica, returning the modified
+/// copy.  The task itself is not changed.
+pub(super) fn preview_modification(
+    task: &Task,
+    modification: &ResolvedModification,
+) -> anyhow::Result<Task> {
+    let mut scratch = Replica::new(StorageConfig::InMemory.into_storage()?);
+    let uuid = task.get_uuid();
+    scratch.import_task_with_uuid(uuid)?;
+    for (k, v) in task.get_taskmap() {
+        scratch.update_task(uuid, k, Some(v))?;
+    }
+    let mut copy = scratch.get_task(uuid)?.unwrap().into_mut(&mut scratch);
+    apply_modification(&mut copy, modification)?;
+    Ok(copy.into_immut())
+}
+
+/// Describe the differences between the properties of two versions of a task, one line per
+/// property, ignoring the `modified` timestamp.
+pub(super) fn describe_changes(before: &Task, after: &Task) -> Vec<String> {
+    let before = before.get_taskmap();
+    let after = after.get_taskmap();
+    let keys: BTreeSet<_> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter(|k| *k != Prop::Modified.as_ref())
+        .filter_map(|k| match (before.get(k), after.get(k)) {
+            (Some(old), Some(new)) if old != new => Some(format!("{}: {:?} -> {:?}", k, old, new)),
+            (Some(old), None) => Some(format!("{}: {:?} -> (removed)", k, old)),
+            (None, Some(new)) => Some(format!("{}: {:?}", k, new)),
+            _ => None,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::invocation::test::*;
     use pretty_assertions::assert_eq;
+    use taskchampion::chrono::Duration;
     use taskchampion::{Status, Uuid};
 
     #[test]
@@ -184,6 +279,86 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_apply_recur_requires_due() {
+        let mut replica = test_replica();
+        let t1 = replica.new_task(Status::Pending, "a task".into()).unwrap();
+        let mut t1 = t1.into_mut(&mut replica);
+
+        let modi = Modification {
+            recur: Some(Some(Duration::days(7))),
+            ..Default::default()
+        };
+        let res = ResolvedModification(modi);
+        assert!(apply_modification(&mut t1, &res).is_err());
+        assert_eq!(t1.get_recur(), None);
+
+        let modi = Modification {
+            recur: Some(Some(Duration::days(7))),
+            due: Some(Some(Utc::now())),
+            ..Default::default()
+        };
+        let res = ResolvedModification(modi);
+        apply_modification(&mut t1, &res).unwrap();
+        assert_eq!(t1.get_recur(), Some(Duration::days(7)));
+
+        // removing the due date of a recurring task is also an error
+        let modi = Modification {
+            due: Some(None),
+            ..Default::default()
+        };
+        let res = ResolvedModification(modi);
+        assert!(apply_modification(&mut t1, &res).is_err());
+    }
+
+    #[test]
+    fn test_apply_append_prepend() {
+        let mut replica = test_replica();
+        let t1 = replica.new_task(Status::Pending, "call".into()).unwrap();
+        let mut t1 = t1.into_mut(&mut replica);
+
+        let modi = Modification {
+            description: DescriptionMod::Append("the plumber".into()),
+            ..Default::default()
+        };
+        apply_modification(&mut t1, &ResolvedModification(modi)).unwrap();
+        assert_eq!(t1.get_description(), "call the plumber");
+
+        let modi = Modification {
+            description: DescriptionMod::Prepend("urgently".into()),
+            ..Default::default()
+        };
+        apply_modification(&mut t1, &ResolvedModification(modi)).unwrap();
+        assert_eq!(t1.get_description(), "urgently call the plumber");
+    }
+
+    #[test]
+    fn test_apply_denotate() {
+        let mut replica = test_replica();
+        let t1 = replica.new_task(Status::Pending, "a task".into()).unwrap();
+        let mut t1 = t1.into_mut(&mut replica);
+        let now = Utc::now();
+        t1.add_annotation(Annotation {
+            entry: now - Duration::seconds(10),
+            description: "sent invoice".into(),
+        })
+        .unwrap();
+        t1.add_annotation(Annotation {
+            entry: now,
+            description: "called client".into(),
+        })
+        .unwrap();
+
+        let modi = Modification {
+            denotate: Some("invoice".into()),
+            ..Default::default()
+        };
+        apply_modification(&mut t1, &ResolvedModification(modi)).unwrap();
+
+        let anns: Vec<_> = t1.get_annotations().map(|a| a.description).collect();
+        assert_eq!(anns, vec![s!("called client")]);
+    }
+
     #[test]
     fn test_resolve_task_ids_empty() {
         let mut replica = test_replica();
@@ -244,4 +419,36 @@ mod test {
         let resolved = set![TaskId::Uuid(t1.get_uuid())];
         assert_eq!(resolve_task_ids(&mut replica, tids).unwrap(), resolved);
     }
+
+    #[test]
+    fn test_preview_modification() {
+        let mut replica = test_replica();
+        let mut task = replica
+            .new_task(Status::Pending, s!("a task"))
+            .unwrap()
+            .into_mut(&mut replica);
+        task.add_tag(&tag!("old")).unwrap();
+        let task = task.into_immut();
+
+        let modi = ResolvedModification(Modification {
+            description: DescriptionMod::Append(s!("today")),
+            add_tags: set![tag!("new")],
+            remove_tags: set![tag!("old")],
+            ..Default::default()
+        });
+        let preview = preview_modification(&task, &modi).unwrap();
+        assert_eq!(
+            describe_changes(&task, &preview),
+            vec![
+                s!("description: \"a task\" -> \"a task today\""),
+                s!("tag_new: \"\""),
+                s!("tag_old: \"\" -> (removed)"),
+            ]
+        );
+
+        // the task itself is unchanged
+        let task = replica.get_task(task.get_uuid()).unwrap().unwrap();
+        assert_eq!(task.get_description(), "a task");
+        assert!(task.has_tag(&tag!("old")));
+    }
 }
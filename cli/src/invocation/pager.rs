@@ -0,0 +1,100 @@
+//! Paging of long output, when it is written to a terminal.
+
+use crate::argparse::ColorMode;
+use crate::settings::Settings;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use termcolor::{Buffer, BufferWriter};
+
+/// The pager to use when neither the `pager` setting nor `$PAGER` is set
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Get the pager command and its arguments, or None if paging is disabled.  The `pager` setting
+/// takes precedence over `$PAGER`, and an empty value disables paging.
+fn pager_command(setting: Option<&str>, env_pager: Option<&str>) -> Option<Vec<String>> {
+    let pager = setting.or(env_pager).unwrap_or(DEFAULT_PAGER);
+    let command: Vec<String> = pager.split_whitespace().map(|s| s.to_owned()).collect();
+    if command.is_empty() {
+        None
+    } else {
+        Some(command)
+    }
+}
+
+/// Determine whether the output is too tall to fit in a terminal with the given number of rows,
+/// leaving room for the shell prompt that follows it.
+fn is_too_tall(output: &[u8], rows: usize) -> bool {
+    output.iter().filter(|b| **b == b'\n').count() >= rows
+}
+
+/// Run the pager, writing the output to its stdin.  This only fails if the pager cannot be
+/// started; an error writing to the pager means that the user exited it early.
+fn run_pager(command: &[String], output: &[u8]) -> std::io::Result<()> {
+    let mut child = Command::new(&command[0])
+        .args(&command[1..])
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(output);
+    }
+    let _ = child.wait();
+    Ok(())
+}
+
+/// Run `f`, writing its output to stdout.  If stdout is a terminal and the output is taller than
+/// the terminal, the output is shown with a pager instead.
+pub(super) fn paged<F>(
+    color: ColorMode,
+    settings: &Settings,
+    no_pager: bool,
+    f: F,
+) -> Result<(), crate::Error>
+where
+    F: FnOnce(&mut Buffer) -> Result<(), crate::Error>,
+{
+    let writer = BufferWriter::stdout(super::color_choice(color));
+    let mut buffer = writer.buffer();
+    let result = f(&mut buffer);
+
+    if !no_pager && atty::is(atty::Stream::Stdout) {
+        let env_pager = std::env::var("PAGER").ok();
+        let command = pager_command(settings.pager.as_deref(), env_pager.as_deref());
+        let rows = console::Term::stdout()
+            .size_checked()
+            .map(|(r, _)| r as usize);
+        if let (Some(command), Some(rows)) = (command, rows) {
+            if is_too_tall(buffer.as_slice(), rows) {
+                match run_pager(&command, buffer.as_slice()) {
+                    Ok(()) => return result,
+                    Err(e) => log::warn!("Could not run pager {:?}: {}", command[0], e),
+                }
+            }
+        }
+    }
+
+    writer.print(&buffer)?;
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_pager_command() {
+        assert_eq!(pager_command(None, None), Some(vec![s!("less"), s!("-R")]));
+        assert_eq!(pager_command(None, Some("more")), Some(vec![s!("more")]));
+        assert_eq!(
+            pager_command(Some("most -s"), Some("more")),
+            Some(vec![s!("most"), s!("-s")])
+        );
+        assert_eq!(pager_command(Some(""), Some("more")), None);
+    }
+
+    #[test]
+    fn test_is_too_tall() {
+        assert!(!is_too_tall(b"one\ntwo\n", 3));
+        assert!(is_too_tall(b"one\ntwo\nthree\n", 3));
+    }
+}
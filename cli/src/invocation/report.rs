@@ -1,15 +1,23 @@
-use crate::argparse::Filter;
+use crate::argparse::{
+    did_you_mean, subcommand_names, Filter, OutputFormat, ReportLimit, ReportOptions,
+};
 use crate::invocation::filtered_tasks;
-use crate::settings::{Column, Property, Report, Settings, SortBy};
+use crate::invocation::urgency::urgency;
+use crate::settings::{
+    format_duration, ColorRules, Column, DateDisplay, Property, Report, Settings, SortBy,
+};
 use crate::table;
-use anyhow::anyhow;
-use prettytable::{Row, Table};
+use prettytable::{Cell, Row, Table};
+use serde_json::{json, Map, Value};
 use std::cmp::Ordering;
+use std::io::Write;
+use taskchampion::chrono::prelude::*;
 use taskchampion::{Replica, Task, WorkingSet};
-use termcolor::WriteColor;
+use termcolor::{Ansi, ColorSpec, WriteColor};
 
 /// Sort tasks for the given report.
 fn sort_tasks(tasks: &mut Vec<Task>, report: &Report, working_set: &WorkingSet) {
+    let now = Utc::now();
     tasks.sort_by(|a, b| {
         for s in &report.sort {
             let ord = match s.sort_by {
@@ -28,6 +36,25 @@ fn sort_tasks(tasks: &mut Vec<Task>, report: &Report, working_set: &WorkingSet)
                 SortBy::Uuid => a.get_uuid().cmp(&b.get_uuid()),
                 SortBy::Description => a.get_description().cmp(b.get_description()),
                 SortBy::Wait => a.get_wait().cmp(&b.get_wait()),
+                SortBy::Due => match (a.get_due(), b.get_due()) {
+                    (Some(a_due), Some(b_due)) => a_due.cmp(&b_due),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                },
+                SortBy::Project => match (a.get_project(), b.get_project()) {
+                    (Some(a_project), Some(b_project)) => a_project.cmp(b_project),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                },
+                SortBy::Urgency => urgency(a, now)
+                    .partial_cmp(&urgency(b, now))
+                    .unwrap_or(Ordering::Equal),
+                // newer tasks (with later entry times) are younger
+                SortBy::Age => b.get_entry().cmp(&a.get_entry()),
+                // more recently started tasks have elapsed less time
+                SortBy::Elapsed => b.get_start().cmp(&a.get_start()),
             };
             // If this sort property is equal, go on to the next..
             if ord == Ordering::Equal {
@@ -45,7 +72,13 @@ fn sort_tasks(tasks: &mut Vec<Task>, report: &Report, working_set: &WorkingSet)
 }
 
 /// Generate the string representation for the given task and column.
-fn task_column(task: &Task, column: &Column, working_set: &WorkingSet) -> String {
+fn task_column(
+    task: &Task,
+    column: &Column,
+    working_set: &WorkingSet,
+    dates: &DateDisplay,
+) -> String {
+    let now = Utc::now();
     match column.property {
         Property::Id => {
             let uuid = task.get_uuid();
@@ -59,6 +92,11 @@ fn task_column(task: &Task, column: &Column, working_set: &WorkingSet) -> String
             let uuid = task.get_uuid();
             uuid.to_string()
         }
+        Property::ShortUuid => {
+            let mut uuid = task.get_uuid().to_string();
+            uuid.truncate(8);
+            uuid
+        }
         Property::Active => match task.is_active() {
             true => "*".to_owned(),
             false => "".to_owned(),
@@ -74,12 +112,330 @@ fn task_column(task: &Task, column: &Column, working_set: &WorkingSet) -> String
         }
         Property::Wait => {
             if task.is_waiting() {
-                task.get_wait().unwrap().format("%Y-%m-%d").to_string()
+                dates.date(task.get_wait().unwrap(), now)
+            } else {
+                "".to_owned()
+            }
+        }
+        Property::Due => match task.get_due() {
+            Some(due) => dates.date(due, now),
+            None => "".to_owned(),
+        },
+        Property::Recur => {
+            if task.get_parent().is_some() {
+                "r".to_owned()
+            } else if task.get_recur().is_some() {
+                "R".to_owned()
             } else {
                 "".to_owned()
             }
         }
+        Property::Project => task.get_project().unwrap_or("").to_owned(),
+        Property::Urgency => format!("{:.1}", urgency(task, now)),
+        Property::Age => match task.get_entry() {
+            Some(entry) => format_duration(now - entry),
+            None => "".to_owned(),
+        },
+        Property::Elapsed => match task.get_start() {
+            Some(start) => format_duration(now - start),
+            None => "".to_owned(),
+        },
+    }
+}
+
+/// Combine `over` into `spec`, with any colors or attributes set in `over` taking precedence.
+fn merge_color(spec: &mut ColorSpec, over: &ColorSpec) {
+    if let Some(fg) = over.fg() {
+        spec.set_fg(Some(*fg));
+    }
+    if let Some(bg) = over.bg() {
+        spec.set_bg(Some(*bg));
+    }
+    if over.bold() {
+        spec.set_bold(true);
+    }
+    if over.underline() {
+        spec.set_underline(true);
+    }
+    if over.italic() {
+        spec.set_italic(true);
+    }
+    if over.dimmed() {
+        spec.set_dimmed(true);
+    }
+    if over.intense() {
+        spec.set_intense(true);
+    }
+}
+
+/// Determine the color, if any, with which to display the given task, appearing in the given
+/// (zero-based) row of the report.
+fn task_row_color(task: &Task, row: usize, rules: &ColorRules) -> Option<ColorSpec> {
+    let mut spec = ColorSpec::new();
+    if row % 2 == 1 {
+        if let Some(ref alternate) = rules.alternate {
+            merge_color(&mut spec, alternate);
+        }
     }
+    for (tag, tag_spec) in &rules.tags {
+        if task.has_tag(tag) {
+            merge_color(&mut spec, tag_spec);
+        }
+    }
+    if task.is_active() {
+        if let Some(ref active) = rules.active {
+            merge_color(&mut spec, active);
+        }
+    }
+    if task.is_overdue() {
+        if let Some(ref overdue) = rules.overdue {
+            merge_color(&mut spec, overdue);
+        }
+    }
+    if spec.is_none() {
+        None
+    } else {
+        Some(spec)
+    }
+}
+
+/// Wrap the given string in the ANSI escape sequences for the given color.  The table
+/// implementation does not count these sequences when calculating column widths.
+fn colorize(value: String, spec: &ColorSpec) -> Result<String, crate::Error> {
+    let mut ansi = Ansi::new(vec![]);
+    ansi.set_color(spec)?;
+    ansi.write_all(value.as_bytes())?;
+    ansi.reset()?;
+    Ok(String::from_utf8(ansi.into_inner()).expect("ANSI output is valid UTF-8"))
+}
+
+/// Generate the JSON representation for the given task and column, for machine-readable output.
+fn task_column_json(task: &Task, column: &Column, working_set: &WorkingSet) -> Value {
+    fn timestamp(ts: Option<DateTime<Utc>>) -> Value {
+        match ts {
+            Some(ts) => json!(ts.to_rfc3339()),
+            None => Value::Null,
+        }
+    }
+    match column.property {
+        Property::Id => match working_set.by_uuid(task.get_uuid()) {
+            Some(i) => json!(i),
+            None => json!(task.get_uuid().to_string()),
+        },
+        Property::Active => json!(task.is_active()),
+        Property::Tags => {
+            let mut tags: Vec<_> = task.get_tags().map(|t| t.to_string()).collect();
+            tags.sort();
+            json!(tags)
+        }
+        Property::Wait => timestamp(task.get_wait().filter(|_| task.is_waiting())),
+        Property::Due => timestamp(task.get_due()),
+        Property::Project => json!(task.get_project()),
+        Property::Urgency => json!(urgency(task, Utc::now())),
+        _ => json!(task_column(
+            task,
+            column,
+            working_set,
+            &DateDisplay::default()
+        )),
+    }
+}
+
+/// Generate the tab-separated representation for the given task and column.  Tabs and newlines
+/// are replaced with spaces so that each task occupies a single line.  Dates are always in ISO
+/// format, regardless of the configured date format.
+fn task_column_tsv(task: &Task, column: &Column, working_set: &WorkingSet) -> String {
+    task_column(task, column, working_set, &DateDisplay::default())
+        .replace(&['\t', '\n', '\r'][..], " ")
+}
+
+/// Format a row of comma-separated values.  Values containing commas, quotes, or newlines are
+/// quoted, as described in RFC 4180.
+pub(super) fn csv_row<S: AsRef<str>>(values: &[S]) -> String {
+    values
+        .iter()
+        .map(|v| {
+            let v = v.as_ref();
+            if v.contains(&[',', '"', '\n', '\r'][..]) {
+                format!("\"{}\"", v.replace('"', "\"\""))
+            } else {
+                v.to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Format a row of a Markdown table.  Pipe characters are escaped and newlines are replaced with
+/// spaces, so that each value occupies a single cell.
+pub(super) fn markdown_row<S: AsRef<str>>(values: &[S]) -> String {
+    let cells: Vec<_> = values
+        .iter()
+        .map(|v| {
+            v.as_ref()
+                .replace('|', "\\|")
+                .replace(&['\n', '\r'][..], " ")
+        })
+        .collect();
+    format!("| {} |", cells.join(" | "))
+}
+
+/// Format the separator between the header and body of a Markdown table with the given number
+/// of columns.
+pub(super) fn markdown_separator(columns: usize) -> String {
+    markdown_row(&vec!["---"; columns])
+}
+
+/// Write the tasks as a table
+fn write_table<W: WriteColor>(
+    w: &mut W,
+    tasks: &[Task],
+    report: &Report,
+    settings: &Settings,
+    working_set: &WorkingSet,
+) -> Result<(), crate::Error> {
+    let mut t = Table::new();
+
+    // ..set up the column titles
+    t.set_format(table::format());
+    t.set_titles(report.columns.iter().map(|col| col.label.clone()).into());
+
+    // ..insert the data
+    let use_color = w.supports_color();
+    for (i, task) in tasks.iter().enumerate() {
+        let mut row = Row::empty();
+        let spec = if use_color {
+            task_row_color(task, i, &settings.colors)
+        } else {
+            None
+        };
+        for col in &report.columns {
+            let mut value = task_column(task, col, working_set, &settings.dates);
+            if let Some(ref spec) = spec {
+                value = colorize(value, spec)?;
+            }
+            row.add_cell(Cell::new(&value));
+        }
+        t.add_row(row);
+    }
+
+    // ..and display it
+    t.print(w)?;
+    Ok(())
+}
+
+/// Write the tasks as a JSON array containing an object for each task, keyed by property name.
+fn write_json<W: WriteColor>(
+    w: &mut W,
+    tasks: &[Task],
+    report: &Report,
+    working_set: &WorkingSet,
+) -> Result<(), crate::Error> {
+    writeln!(w, "[")?;
+    for (i, task) in tasks.iter().enumerate() {
+        let mut obj = Map::new();
+        for col in &report.columns {
+            obj.insert(
+                col.property.name().to_owned(),
+                task_column_json(task, col, working_set),
+            );
+        }
+        let sep = if i + 1 < tasks.len() { "," } else { "" };
+        writeln!(w, "{}{}", Value::Object(obj), sep)?;
+    }
+    writeln!(w, "]")?;
+    Ok(())
+}
+
+/// Write the tasks as tab-separated values, with a header line containing the property names.
+fn write_tsv<W: WriteColor>(
+    w: &mut W,
+    tasks: &[Task],
+    report: &Report,
+    working_set: &WorkingSet,
+) -> Result<(), crate::Error> {
+    let names: Vec<_> = report.columns.iter().map(|c| c.property.name()).collect();
+    writeln!(w, "{}", names.join("\t"))?;
+    for task in tasks {
+        let values: Vec<_> = report
+            .columns
+            .iter()
+            .map(|c| task_column_tsv(task, c, working_set))
+            .collect();
+        writeln!(w, "{}", values.join("\t"))?;
+    }
+    Ok(())
+}
+
+/// Write the tasks as comma-separated values, with a header line containing the column labels.
+/// Dates are always in ISO format, so that spreadsheets can interpret them.
+fn write_csv<W: WriteColor>(
+    w: &mut W,
+    tasks: &[Task],
+    report: &Report,
+    working_set: &WorkingSet,
+) -> Result<(), crate::Error> {
+    let labels: Vec<_> = report.columns.iter().map(|c| &c.label).collect();
+    writeln!(w, "{}", csv_row(&labels))?;
+    for task in tasks {
+        let values: Vec<_> = report
+            .columns
+            .iter()
+            .map(|c| task_column(task, c, working_set, &DateDisplay::default()))
+            .collect();
+        writeln!(w, "{}", csv_row(&values))?;
+    }
+    Ok(())
+}
+
+/// Write the tasks as a Markdown table, with a header row containing the column labels.
+fn write_markdown<W: WriteColor>(
+    w: &mut W,
+    tasks: &[Task],
+    report: &Report,
+    settings: &Settings,
+    working_set: &WorkingSet,
+) -> Result<(), crate::Error> {
+    let labels: Vec<_> = report.columns.iter().map(|c| &c.label).collect();
+    writeln!(w, "{}", markdown_row(&labels))?;
+    writeln!(w, "{}", markdown_separator(labels.len()))?;
+    for task in tasks {
+        let values: Vec<_> = report
+            .columns
+            .iter()
+            .map(|c| task_column(task, c, working_set, &settings.dates))
+            .collect();
+        writeln!(w, "{}", markdown_row(&values))?;
+    }
+    Ok(())
+}
+
+/// Construct an error for a report name that is not defined, suggesting a report or subcommand
+/// that may have been meant.
+fn unknown_report(settings: &Settings, report_name: &str) -> crate::Error {
+    let mut reports: Vec<&str> = settings.reports.keys().map(|k| k.as_ref()).collect();
+    reports.sort_unstable();
+    let subcommands = subcommand_names();
+    let candidates = reports.iter().chain(subcommands.iter()).copied();
+    crate::Error::for_arguments(match did_you_mean(report_name, candidates) {
+        Some(suggestion) => format!(
+            "unknown report or subcommand `{}`, did you mean `{}`?",
+            report_name, suggestion
+        ),
+        None => format!(
+            "unknown report or subcommand `{}`; defined reports are {}",
+            report_name,
+            reports.join(", ")
+        ),
+    })
+}
+
+/// Determine how many tasks fit in a terminal with the given number of rows, leaving room for
+/// the column headings, the context line (if any), the count of tasks not shown, and the shell
+/// prompt that follows the report.
+fn page_limit(rows: usize, has_context: bool) -> usize {
+    let reserved = if has_context { 4 } else { 3 };
+    rows.saturating_sub(reserved).max(1)
 }
 
 pub(super) fn display_report<W: WriteColor>(
@@ -88,19 +444,40 @@ pub(super) fn display_report<W: WriteColor>(
     settings: &Settings,
     report_name: String,
     filter: Filter,
+    options: ReportOptions,
+    format: OutputFormat,
 ) -> Result<(), crate::Error> {
-    let mut t = Table::new();
     let working_set = replica.working_set()?;
 
     // Get the report from settings
     let mut report = settings
         .reports
         .get(&report_name)
-        .ok_or_else(|| anyhow!("report `{}` not defined", report_name))?
+        .ok_or_else(|| unknown_report(settings, &report_name))?
         .clone();
 
-    // include any user-supplied filter conditions
+    // include any user-supplied filter conditions and the active context
     report.filter = report.filter.intersect(filter);
+    if let Some(context_filter) = settings.context_filter()? {
+        report.filter = report.filter.intersect(context_filter);
+    }
+
+    // apply any command-line overrides of the columns and sort order
+    if let Some(columns) = options.columns {
+        report.columns = columns
+            .iter()
+            .map(|c| c.parse())
+            .collect::<anyhow::Result<_>>()?;
+    }
+    if let Some(sort) = options.sort {
+        report.sort = sort
+            .iter()
+            .map(|s| s.parse())
+            .collect::<anyhow::Result<_>>()?;
+    }
+    if options.limit.is_some() {
+        report.limit = options.limit;
+    }
 
     // Get the tasks from the filter
     let mut tasks: Vec<_> = filtered_tasks(replica, &report.filter)?.collect();
@@ -108,22 +485,39 @@ pub(super) fn display_report<W: WriteColor>(
     // ..sort them as desired
     sort_tasks(&mut tasks, &report, &working_set);
 
-    // ..set up the column titles
-    t.set_format(table::format());
-    t.set_titles(report.columns.iter().map(|col| col.label.clone()).into());
+    // ..limit the number displayed
+    let total = tasks.len();
+    let limit = match report.limit {
+        Some(ReportLimit::Count(limit)) => Some(limit),
+        // with no terminal, there is no page to fill
+        Some(ReportLimit::Page) => console::Term::stdout()
+            .size_checked()
+            .map(|(rows, _)| page_limit(rows as usize, settings.context.is_some())),
+        None => None,
+    };
+    if let Some(limit) = limit {
+        tasks.truncate(limit);
+    }
 
-    // ..insert the data
-    for task in &tasks {
-        let row: Row = report
-            .columns
-            .iter()
-            .map(|col| task_column(task, col, &working_set))
-            .collect::<Row>();
-        t.add_row(row);
+    match format {
+        OutputFormat::Table => {
+            if let Some(ref context) = settings.context {
+                writeln!(w, "Context: {}", context)?;
+            }
+            write_table(w, &tasks, &report, settings, &working_set)?;
+            if tasks.len() < total {
+                writeln!(w, "{} more tasks not shown.", total - tasks.len())?;
+            }
+        }
+        OutputFormat::Json => write_json(w, &tasks, &report, &working_set)?,
+        OutputFormat::Tsv => write_tsv(w, &tasks, &report, &working_set)?,
+        OutputFormat::Csv => write_csv(w, &tasks, &report, &working_set)?,
+        OutputFormat::Markdown => write_markdown(w, &tasks, &report, settings, &working_set)?,
     }
 
-    // ..and display it
-    t.print(w)?;
+    if total == 0 {
+        return Err(crate::Error::NoMatches);
+    }
     Ok(())
 }
 
@@ -134,8 +528,9 @@ mod test {
     use crate::settings::Sort;
     use pretty_assertions::assert_eq;
     use std::convert::TryInto;
-    use taskchampion::chrono::{prelude::*, Duration};
+    use taskchampion::chrono::Duration;
     use taskchampion::{Status, Uuid};
+    use termcolor::Color;
 
     fn create_tasks(replica: &mut Replica) -> [Uuid; 3] {
         let t1 = replica.new_task(Status::Pending, s!("A")).unwrap();
@@ -271,6 +666,102 @@ mod test {
         assert_eq!(got_uuids, exp_uuids);
     }
 
+    #[test]
+    fn sorting_by_urgency() {
+        let mut replica = test_replica();
+        let uuids = create_tasks(&mut replica);
+
+        replica
+            .get_task(uuids[2])
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica)
+            .set_priority(s!("H"))
+            .unwrap();
+
+        let working_set = replica.working_set().unwrap();
+        let report = Report {
+            sort: vec!["urgency-".parse().unwrap(), "description".parse().unwrap()],
+            ..Default::default()
+        };
+
+        let mut tasks: Vec<_> = replica.all_tasks().unwrap().values().cloned().collect();
+        sort_tasks(&mut tasks, &report, &working_set);
+        let descriptions: Vec<_> = tasks.iter().map(|t| t.get_description()).collect();
+        assert_eq!(descriptions, vec!["C", "A", "B"]);
+    }
+
+    #[test]
+    fn sorting_by_project() {
+        let mut replica = test_replica();
+        let uuids = create_tasks(&mut replica);
+
+        for &(uuid, project) in &[(uuids[0], "work"), (uuids[1], "home")] {
+            replica
+                .get_task(uuid)
+                .unwrap()
+                .unwrap()
+                .into_mut(&mut replica)
+                .set_project(Some(s!(project)))
+                .unwrap();
+        }
+
+        let working_set = replica.working_set().unwrap();
+        let report = Report {
+            sort: vec!["project+".parse().unwrap()],
+            ..Default::default()
+        };
+
+        let mut tasks: Vec<_> = replica.all_tasks().unwrap().values().cloned().collect();
+        sort_tasks(&mut tasks, &report, &working_set);
+        let descriptions: Vec<_> = tasks.iter().map(|t| t.get_description()).collect();
+        assert_eq!(descriptions, vec!["B", "A", "C"]);
+    }
+
+    #[test]
+    fn sorting_by_due() {
+        let mut replica = test_replica();
+        let uuids = create_tasks(&mut replica);
+
+        replica
+            .get_task(uuids[0])
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica)
+            .set_due(Some(Utc::now() + Duration::days(3)))
+            .unwrap();
+
+        replica
+            .get_task(uuids[2])
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica)
+            .set_due(Some(Utc::now() + Duration::days(2)))
+            .unwrap();
+
+        let working_set = replica.working_set().unwrap();
+
+        let report = Report {
+            sort: vec![Sort {
+                ascending: true,
+                sort_by: SortBy::Due,
+            }],
+            ..Default::default()
+        };
+
+        let mut tasks: Vec<_> = replica.all_tasks().unwrap().values().cloned().collect();
+        sort_tasks(&mut tasks, &report, &working_set);
+        let got_uuids: Vec<_> = tasks.iter().map(|t| t.get_uuid()).collect();
+
+        let exp_uuids = vec![
+            uuids[2], // due:2d
+            uuids[0], // due:3d
+            uuids[1], // no due
+        ];
+
+        assert_eq!(got_uuids, exp_uuids);
+    }
+
     #[test]
     fn sorting_by_multiple() {
         let mut replica = test_replica();
@@ -315,13 +806,16 @@ mod test {
             label: s!(""),
             property: Property::Id,
         };
-        assert_eq!(task_column(&task, &column, &working_set), s!("1"));
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("1")
+        );
 
         // get the task that's not in the working set, which should show
         // a uuid for its id column
         let task = replica.get_task(uuids[1]).unwrap().unwrap();
         assert_eq!(
-            task_column(&task, &column, &working_set),
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
             uuids[1].to_string()
         );
     }
@@ -338,11 +832,71 @@ mod test {
             property: Property::Uuid,
         };
         assert_eq!(
-            task_column(&task, &column, &working_set),
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
             task.get_uuid().to_string()
         );
     }
 
+    #[test]
+    fn task_column_short_uuid() {
+        let mut replica = test_replica();
+        let uuids = create_tasks(&mut replica);
+        let working_set = replica.working_set().unwrap();
+
+        let task = replica.get_task(uuids[0]).unwrap().unwrap();
+        let column: Column = "uuid.short".parse().unwrap();
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            task.get_uuid().to_string()[..8]
+        );
+    }
+
+    #[test]
+    fn task_column_project() {
+        let mut replica = test_replica();
+        let uuids = create_tasks(&mut replica);
+        let working_set = replica.working_set().unwrap();
+
+        let mut task = replica
+            .get_task(uuids[0])
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica);
+        task.set_project(Some(s!("work.client"))).unwrap();
+        let task = task.into_immut();
+        let column: Column = "project".parse().unwrap();
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("work.client")
+        );
+
+        let task = replica.get_task(uuids[2]).unwrap().unwrap();
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("")
+        );
+    }
+
+    #[test]
+    fn task_column_urgency() {
+        let mut replica = test_replica();
+        let uuids = create_tasks(&mut replica);
+        let working_set = replica.working_set().unwrap();
+
+        let mut task = replica
+            .get_task(uuids[0])
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica);
+        task.set_priority(s!("H")).unwrap();
+        let task = task.into_immut();
+        let column: Column = "urgency".parse().unwrap();
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("6.0")
+        );
+    }
+
     #[test]
     fn task_column_active() {
         let mut replica = test_replica();
@@ -364,9 +918,15 @@ mod test {
         };
 
         let task = replica.get_task(uuids[0]).unwrap().unwrap();
-        assert_eq!(task_column(&task, &column, &working_set), s!("*"));
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("*")
+        );
         let task = replica.get_task(uuids[2]).unwrap().unwrap();
-        assert_eq!(task_column(&task, &column, &working_set), s!(""));
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("")
+        );
     }
 
     #[test]
@@ -380,7 +940,10 @@ mod test {
             label: s!(""),
             property: Property::Description,
         };
-        assert_eq!(task_column(&task, &column, &working_set), s!("C"));
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("C")
+        );
     }
 
     #[test]
@@ -405,13 +968,201 @@ mod test {
 
         let task = replica.get_task(uuids[0]).unwrap().unwrap();
         assert_eq!(
-            task_column(&task, &column, &working_set),
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
             s!("+PENDING +UNBLOCKED +bar +foo")
         );
         let task = replica.get_task(uuids[2]).unwrap().unwrap();
         assert_eq!(
-            task_column(&task, &column, &working_set),
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
             s!("+PENDING +UNBLOCKED")
         );
     }
+
+    #[test]
+    fn task_column_due() {
+        let mut replica = test_replica();
+        let uuids = create_tasks(&mut replica);
+        let working_set = replica.working_set().unwrap();
+
+        let due = Local.ymd(2021, 3, 4).and_hms(12, 0, 0).with_timezone(&Utc);
+        replica
+            .get_task(uuids[0])
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica)
+            .set_due(Some(due))
+            .unwrap();
+
+        let column = Column {
+            label: s!(""),
+            property: Property::Due,
+        };
+
+        let task = replica.get_task(uuids[0]).unwrap().unwrap();
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("2021-03-04")
+        );
+        let task = replica.get_task(uuids[1]).unwrap().unwrap();
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("")
+        );
+    }
+
+    #[test]
+    fn task_column_recur() {
+        let mut replica = test_replica();
+        let uuids = create_tasks(&mut replica);
+        let working_set = replica.working_set().unwrap();
+
+        replica
+            .get_task(uuids[0])
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica)
+            .set_recur(Some(Duration::days(7)))
+            .unwrap();
+        replica
+            .get_task(uuids[1])
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica)
+            .set_parent(Some(uuids[0]))
+            .unwrap();
+
+        let column = Column {
+            label: s!(""),
+            property: Property::Recur,
+        };
+
+        let task = replica.get_task(uuids[0]).unwrap().unwrap();
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("R")
+        );
+        let task = replica.get_task(uuids[1]).unwrap().unwrap();
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("r")
+        );
+        let task = replica.get_task(uuids[2]).unwrap().unwrap();
+        assert_eq!(
+            task_column(&task, &column, &working_set, &DateDisplay::default()),
+            s!("")
+        );
+    }
+
+    #[test]
+    fn task_row_color_overdue() {
+        let mut replica = test_replica();
+        let uuids = create_tasks(&mut replica);
+        let rules = ColorRules::default();
+
+        replica
+            .get_task(uuids[0])
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica)
+            .set_due(Some(Utc::now() - Duration::days(1)))
+            .unwrap();
+        replica
+            .get_task(uuids[1])
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica)
+            .set_due(Some(Utc::now() + Duration::days(1)))
+            .unwrap();
+
+        let task = replica.get_task(uuids[0]).unwrap().unwrap();
+        assert_eq!(task_row_color(&task, 0, &rules), rules.overdue);
+        let task = replica.get_task(uuids[1]).unwrap().unwrap();
+        assert!(task_row_color(&task, 0, &rules).is_none());
+        let task = replica.get_task(uuids[2]).unwrap().unwrap();
+        assert!(task_row_color(&task, 0, &rules).is_none());
+    }
+
+    #[test]
+    fn task_row_color_combined() {
+        let mut replica = test_replica();
+        let uuids = create_tasks(&mut replica);
+        let mut alternate = ColorSpec::new();
+        alternate.set_bg(Some(Color::Ansi256(235)));
+        let rules = ColorRules {
+            alternate: Some(alternate),
+            ..Default::default()
+        };
+
+        let mut task = replica
+            .get_task(uuids[0])
+            .unwrap()
+            .unwrap()
+            .into_mut(&mut replica);
+        task.add_tag(&("urgent".try_into().unwrap())).unwrap();
+        task.start().unwrap();
+        let task = task.into_immut();
+
+        let mut exp = ColorSpec::new();
+        exp.set_bold(true).set_underline(true);
+        assert_eq!(task_row_color(&task, 0, &rules), Some(exp.clone()));
+        exp.set_bg(Some(Color::Ansi256(235)));
+        assert_eq!(task_row_color(&task, 1, &rules), Some(exp));
+
+        let task = replica.get_task(uuids[2]).unwrap().unwrap();
+        assert!(task_row_color(&task, 0, &rules).is_none());
+        assert_eq!(task_row_color(&task, 1, &rules), rules.alternate);
+    }
+
+    #[test]
+    fn colorize_wraps_value() {
+        let mut spec = ColorSpec::new();
+        spec.set_fg(Some(Color::Red));
+        let value = colorize(s!("abc"), &spec).unwrap();
+        assert!(value.contains("abc"));
+        assert!(value.starts_with('\x1b'));
+    }
+
+    #[test]
+    fn page_limit_leaves_room() {
+        assert_eq!(page_limit(24, false), 21);
+        assert_eq!(page_limit(24, true), 20);
+        assert_eq!(page_limit(2, false), 1);
+    }
+
+    #[test]
+    fn limit_option_applied_after_sort() {
+        let mut replica = test_replica();
+        create_tasks(&mut replica);
+        let mut w = test_writer();
+        let options = ReportOptions {
+            sort: Some(vec![s!("description-")]),
+            limit: Some(ReportLimit::Count(1)),
+            ..Default::default()
+        };
+        display_report(
+            &mut w,
+            &mut replica,
+            &Settings::default(),
+            s!("next"),
+            Filter::default(),
+            options,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        let output = w.into_string();
+        assert!(output.contains("\"C\""));
+        assert!(!output.contains("\"A\""));
+    }
+
+    #[test]
+    fn unknown_report_suggestion() {
+        let settings = Settings::default();
+        let err = unknown_report(&settings, "lsit").to_string();
+        assert!(err.contains("unknown report or subcommand `lsit`, did you mean `list`?"));
+        let err = unknown_report(&settings, "don").to_string();
+        assert!(err.contains("did you mean `done`?"));
+        let err = unknown_report(&settings, "frobnicate").to_string();
+        assert!(err.contains("defined reports are "));
+        assert!(err.contains("list, next"));
+    }
 }
@@ -0,0 +1,168 @@
+//! Urgency is a numeric score indicating how important it is to work on a task, calculated in
+//! the same way as TaskWarrior.  It is the sum of a number of terms, each of which is a factor
+//! between 0 and 1 multiplied by a coefficient.
+
+use taskchampion::chrono::{DateTime, Utc};
+use taskchampion::{Status, Tag, Task};
+
+/// A single term contributing to a task's urgency
+#[derive(Debug, PartialEq)]
+pub(crate) struct UrgencyTerm {
+    /// The name of the term, such as "due"
+    pub(crate) name: &'static str,
+    /// The factor, between 0 and 1, describing how strongly the term applies to the task
+    pub(crate) factor: f64,
+    /// The coefficient by which the factor is multiplied
+    pub(crate) coefficient: f64,
+}
+
+impl UrgencyTerm {
+    /// The contribution of this term to the task's urgency
+    pub(crate) fn value(&self) -> f64 {
+        self.factor * self.coefficient
+    }
+}
+
+/// Scale a count of tags or annotations the way TaskWarrior does
+fn count_factor(count: usize) -> f64 {
+    match count {
+        0 => 0.0,
+        1 => 0.8,
+        2 => 0.9,
+        _ => 1.0,
+    }
+}
+
+/// Calculate the factor for the due date: 1.0 once a week overdue, falling linearly to 0.2 two
+/// weeks before the task is due.
+fn due_factor(due: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    let days_overdue = (now - due).num_seconds() as f64 / 86400.0;
+    if days_overdue >= 7.0 {
+        1.0
+    } else if days_overdue >= -14.0 {
+        ((days_overdue + 14.0) * 0.8 / 21.0) + 0.2
+    } else {
+        0.2
+    }
+}
+
+/// Calculate the non-zero terms of the task's urgency, as of `now`
+pub(crate) fn urgency_terms(task: &Task, now: DateTime<Utc>) -> Vec<UrgencyTerm> {
+    let mut terms = vec![];
+    let mut term = |name, factor: f64, coefficient| {
+        if factor != 0.0 {
+            terms.push(UrgencyTerm {
+                name,
+                factor,
+                coefficient,
+            });
+        }
+    };
+
+    let user_tags: Vec<Tag> = task.get_tags().filter(|t| t.is_user()).collect();
+    let next = user_tags.iter().any(|t| t.as_ref() == "next");
+    term("next", if next { 1.0 } else { 0.0 }, 15.0);
+
+    if let Some(due) = task.get_due() {
+        term("due", due_factor(due, now), 12.0);
+    }
+
+    let priority = match task.get_priority() {
+        "H" => 6.0,
+        "M" => 3.9,
+        "L" => 1.8,
+        _ => 0.0,
+    };
+    term("priority", if priority > 0.0 { 1.0 } else { 0.0 }, priority);
+
+    let scheduled = matches!(task.get_scheduled(), Some(s) if s < now);
+    term("scheduled", if scheduled { 1.0 } else { 0.0 }, 5.0);
+    term("active", if task.is_active() { 1.0 } else { 0.0 }, 4.0);
+
+    if let Some(entry) = task.get_entry() {
+        let age = (now - entry).num_seconds() as f64 / 86400.0 / 365.0;
+        term("age", age.max(0.0).min(1.0), 2.0);
+    }
+
+    term(
+        "annotations",
+        count_factor(task.get_annotations().count()),
+        1.0,
+    );
+    term("tags", count_factor(user_tags.len()), 1.0);
+    term(
+        "project",
+        if task.get_project().is_some() {
+            1.0
+        } else {
+            0.0
+        },
+        1.0,
+    );
+
+    term("blocking", if task.is_blocking() { 1.0 } else { 0.0 }, 8.0);
+    term("blocked", if task.is_blocked() { 1.0 } else { 0.0 }, -5.0);
+    let waiting = task.get_status() == Status::Pending && task.is_waiting();
+    term("waiting", if waiting { 1.0 } else { 0.0 }, -3.0);
+
+    terms
+}
+
+/// Calculate the urgency of the task, as of `now`
+pub(crate) fn urgency(task: &Task, now: DateTime<Utc>) -> f64 {
+    urgency_terms(task, now).iter().map(|t| t.value()).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::invocation::test::*;
+    use pretty_assertions::assert_eq;
+    use taskchampion::chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_due_factor() {
+        let now = Utc.ymd(2021, 6, 1).and_hms(0, 0, 0);
+        assert_eq!(due_factor(now - Duration::days(10), now), 1.0);
+        assert_eq!(due_factor(now + Duration::days(30), now), 0.2);
+        assert!((due_factor(now, now) - (14.0 * 0.8 / 21.0 + 0.2)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_urgency_empty_task() {
+        let mut replica = test_replica();
+        let task = replica.new_task(Status::Pending, s!("x")).unwrap();
+        let now = task.get_entry().unwrap();
+        assert_eq!(urgency_terms(&task, now), vec![]);
+        assert_eq!(urgency(&task, now), 0.0);
+    }
+
+    #[test]
+    fn test_urgency_terms() {
+        let mut replica = test_replica();
+        let mut task = replica
+            .new_task(Status::Pending, s!("x"))
+            .unwrap()
+            .into_mut(&mut replica);
+        task.add_tag(&tag!("next")).unwrap();
+        task.set_priority(s!("H")).unwrap();
+        task.set_project(Some(s!("home"))).unwrap();
+        let task = task.into_immut();
+        let now = task.get_entry().unwrap();
+
+        let terms: Vec<_> = urgency_terms(&task, now)
+            .iter()
+            .map(|t| (t.name, t.value()))
+            .collect();
+        assert_eq!(
+            terms,
+            vec![
+                ("next", 15.0),
+                ("priority", 6.0),
+                ("tags", 0.8),
+                ("project", 1.0)
+            ]
+        );
+        assert!((urgency(&task, now) - 22.8).abs() < 1e-9);
+    }
+}
@@ -26,7 +26,7 @@ Most of its functionality is in common functions to handle filtering tasks, modi
 
 ## Rust API
 
-Note that this crate does not expose a Rust API for use from other crates.
+Apart from `Settings`, which reads and validates the configuration file, this crate does not expose a Rust API for use from other crates.
 For the public TaskChampion Rust API, see the `taskchampion` crate.
 
 */
@@ -39,6 +39,7 @@ mod macros;
 mod argparse;
 mod errors;
 mod invocation;
+mod logging;
 mod settings;
 mod table;
 mod tdb2;
@@ -50,7 +51,8 @@ pub(crate) mod built_info {
 }
 
 pub(crate) use errors::Error;
-use settings::Settings;
+// used by frontends sharing the CLI's configuration file
+pub use settings::Settings;
 
 // used by the `generate` command
 pub use usage::Usage;
@@ -58,8 +60,6 @@ pub use usage::Usage;
 /// The main entry point for the command-line interface.  This builds an Invocation
 /// from the particulars of the operating-system interface, and then executes it.
 pub fn main() -> Result<(), Error> {
-    env_logger::init();
-
     // parse the command line into a vector of &str, failing if
     // there are invalid utf-8 sequences.
     let argv: Vec<String> = std::env::args_os()
@@ -68,11 +68,30 @@ pub fn main() -> Result<(), Error> {
         .map_err(|_| Error::for_arguments("arguments must be valid utf-8"))?;
     let argv: Vec<&str> = argv.iter().map(|s| s.as_ref()).collect();
 
-    // parse the command line
-    let command = argparse::Command::from_argv(&argv[..])?;
+    // log from `RUST_LOG` while reading the settings, until they can configure logging
+    logging::init_from_env()?;
 
     // load the application settings
-    let settings = Settings::read()?;
+    let mut settings = Settings::read()?;
+
+    // expand any aliases and parse the command line
+    let argv = argparse::expand_aliases(&argv[..], &settings.aliases)?;
+    let argv: Vec<&str> = argv.iter().map(|s| s.as_ref()).collect();
+    let command = argparse::Command::from_argv(&argv[..])?;
+
+    // switch to the selected profile, if any
+    if let Some(ref profile) = command.profile {
+        settings
+            .use_profile(profile)
+            .map_err(Error::for_arguments)?;
+    }
+
+    // override the configured date format, if requested
+    if let Some(ref date_format) = command.date_format {
+        settings.dates.format = date_format.parse().map_err(Error::for_arguments)?;
+    }
+
+    logging::configure(&settings, command.verbosity)?;
 
     invocation::invoke(command, settings)?;
     Ok(())
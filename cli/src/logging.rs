@@ -0,0 +1,178 @@
+//! Configuration of log output, from the `log` settings and the `-v` and `-q` options.
+//!
+//! Logging is initialized from `RUST_LOG` before the settings are read, so that messages about
+//! reading them can be seen, and then configured from the settings and command line.
+
+use crate::settings::Settings;
+use anyhow::Context;
+use env_logger::filter::{self, Filter};
+use lazy_static::lazy_static;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::{Mutex, RwLock};
+use taskchampion::chrono::{SecondsFormat, Utc};
+
+lazy_static! {
+    /// The installed logger
+    static ref LOGGER: SwitchLogger = SwitchLogger {
+        inner: RwLock::new(Box::new(stderr_logger("error"))),
+    };
+}
+
+/// The log levels, from least to most verbose
+const LEVELS: &[LevelFilter] = &[
+    LevelFilter::Off,
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+    LevelFilter::Trace,
+];
+
+/// Adjust a log level by the given verbosity, with each step making it one level more (positive)
+/// or less (negative) verbose.
+fn adjust_level(level: LevelFilter, verbosity: i8) -> LevelFilter {
+    let index = LEVELS.iter().position(|l| *l == level).unwrap_or(1) as i64;
+    let index = (index + verbosity as i64)
+        .max(0)
+        .min(LEVELS.len() as i64 - 1);
+    LEVELS[index as usize]
+}
+
+/// A logger that appends messages, each with a timestamp, to a file
+struct FileLogger {
+    filter: Filter,
+    file: Mutex<File>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.filter.matches(record) {
+            if let Ok(mut file) = self.file.lock() {
+                // there is nowhere to report a failure to write a log message
+                let _ = writeln!(
+                    file,
+                    "[{} {:5} {}] {}",
+                    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+                    record.level(),
+                    record.target(),
+                    record.args()
+                );
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// A logger that forwards messages to another logger, which can be replaced once the settings
+/// have been read
+struct SwitchLogger {
+    inner: RwLock<Box<dyn Log>>,
+}
+
+impl Log for SwitchLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner
+            .read()
+            .map(|l| l.enabled(metadata))
+            .unwrap_or(false)
+    }
+
+    fn log(&self, record: &Record) {
+        if let Ok(logger) = self.inner.read() {
+            logger.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(logger) = self.inner.read() {
+            logger.flush();
+        }
+    }
+}
+
+/// Create a logger writing messages matching the given filters to stderr
+fn stderr_logger(filters: &str) -> env_logger::Logger {
+    env_logger::Builder::new().parse_filters(filters).build()
+}
+
+/// Replace the logger to which the installed logger forwards messages
+fn switch<L: Log + 'static>(logger: L, level: LevelFilter) {
+    if let Ok(mut inner) = LOGGER.inner.write() {
+        *inner = Box::new(logger);
+    }
+    log::set_max_level(level);
+}
+
+/// Initialize logging from the `RUST_LOG` environment variable, writing messages to stderr.
+/// This is done before the settings are read, and [`configure`] then applies the settings.
+pub(crate) fn init_from_env() -> anyhow::Result<()> {
+    let filters = match std::env::var("RUST_LOG") {
+        Ok(filters) if !filters.is_empty() => filters,
+        _ => "error".to_owned(),
+    };
+    let logger = stderr_logger(&filters);
+    let level = logger.filter();
+    log::set_logger(&*LOGGER)?;
+    switch(logger, level);
+    Ok(())
+}
+
+/// Configure logging from the settings.  Without `-v` or `-q`, the `RUST_LOG` environment
+/// variable takes precedence over the `log.level` setting.  With them, the configured level (by
+/// default, `error`) is adjusted by the given verbosity.  Messages are written to stderr, or
+/// appended to the file given by `log.file`.
+pub(crate) fn configure(settings: &Settings, verbosity: i8) -> anyhow::Result<()> {
+    let filters = match std::env::var("RUST_LOG") {
+        Ok(filters) if verbosity == 0 && !filters.is_empty() => filters,
+        _ => {
+            let level = settings.log_level.unwrap_or(LevelFilter::Error);
+            adjust_level(level, verbosity).to_string()
+        }
+    };
+
+    if let Some(ref log_file) = settings.log_file {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_file)
+            .with_context(|| format!("could not open log file {:?}", log_file))?;
+        let logger = FileLogger {
+            filter: filter::Builder::new().parse(&filters).build(),
+            file: Mutex::new(file),
+        };
+        let level = logger.filter.filter();
+        switch(logger, level);
+    } else {
+        let logger = stderr_logger(&filters);
+        let level = logger.filter();
+        switch(logger, level);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_adjust_level() {
+        assert_eq!(adjust_level(LevelFilter::Error, 0), LevelFilter::Error);
+        assert_eq!(adjust_level(LevelFilter::Error, 1), LevelFilter::Warn);
+        assert_eq!(adjust_level(LevelFilter::Error, 3), LevelFilter::Debug);
+        assert_eq!(adjust_level(LevelFilter::Error, 10), LevelFilter::Trace);
+        assert_eq!(adjust_level(LevelFilter::Error, -1), LevelFilter::Off);
+        assert_eq!(adjust_level(LevelFilter::Info, -10), LevelFilter::Off);
+    }
+}
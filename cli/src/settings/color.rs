@@ -0,0 +1,212 @@
+//! This module contains the data structures used to define color rules for reports.
+
+use crate::settings::util::table_with_keys;
+use anyhow::{anyhow, bail, Result};
+use std::convert::TryFrom;
+use std::str::FromStr;
+use taskchampion::Tag;
+use termcolor::{Color, ColorSpec};
+
+/// Rules for coloring the rows of a report, based on the task in each row.  When several rules
+/// apply to the same task, they are combined, with later rules in this struct taking precedence
+/// over earlier rules.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ColorRules {
+    /// Color for every other row of the report
+    pub alternate: Option<ColorSpec>,
+    /// Colors for tasks with specific tags
+    pub tags: Vec<(Tag, ColorSpec)>,
+    /// Color for active tasks
+    pub active: Option<ColorSpec>,
+    /// Color for overdue tasks
+    pub overdue: Option<ColorSpec>,
+}
+
+impl Default for ColorRules {
+    fn default() -> Self {
+        let mut bold = ColorSpec::new();
+        bold.set_bold(true);
+        let mut underline = ColorSpec::new();
+        underline.set_underline(true);
+        let mut red = ColorSpec::new();
+        red.set_fg(Some(Color::Red));
+
+        ColorRules {
+            alternate: None,
+            tags: vec![(Tag::try_from("urgent").unwrap(), bold)],
+            active: Some(underline),
+            overdue: Some(red),
+        }
+    }
+}
+
+impl ColorRules {
+    /// Update these rules from a toml value.  This should be the `colors` value.  Rules that are
+    /// not mentioned are left unchanged, and tag rules are replaced by tag.  The error message
+    /// begins with any additional path information, e.g., `.tags.urgent: ..`.
+    pub(crate) fn update_from_toml(&mut self, cfg: &toml::Value) -> Result<()> {
+        let keys = ["alternate", "tags", "active", "overdue"];
+        let table = table_with_keys(cfg, &keys).map_err(|e| anyhow!(": {}", e))?;
+
+        fn get_spec(table: &toml::value::Table, name: &str) -> Result<Option<Option<ColorSpec>>> {
+            match table.get(name) {
+                Some(v) => Ok(Some(
+                    color_rule(v).map_err(|e| anyhow!(".{}: {}", name, e))?,
+                )),
+                None => Ok(None),
+            }
+        }
+
+        if let Some(spec) = get_spec(table, "alternate")? {
+            self.alternate = spec;
+        }
+        if let Some(spec) = get_spec(table, "active")? {
+            self.active = spec;
+        }
+        if let Some(spec) = get_spec(table, "overdue")? {
+            self.overdue = spec;
+        }
+
+        if let Some(v) = table.get("tags") {
+            let tags = v.as_table().ok_or_else(|| anyhow!(".tags: not a table"))?;
+            for (name, v) in tags {
+                let tag =
+                    Tag::try_from(name.as_str()).map_err(|e| anyhow!(".tags.{}: {}", name, e))?;
+                let spec = color_rule(v).map_err(|e| anyhow!(".tags.{}: {}", name, e))?;
+                self.tags.retain(|(t, _)| t != &tag);
+                if let Some(spec) = spec {
+                    self.tags.push((tag, spec));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a single color rule from a toml value, returning None for an empty string, which
+/// disables the rule.
+fn color_rule(cfg: &toml::Value) -> Result<Option<ColorSpec>> {
+    let s = cfg.as_str().ok_or_else(|| anyhow!("not a string"))?;
+    if s.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(parse_color_spec(s)?))
+}
+
+/// Parse a color specification such as `red`, `bold yellow`, or `underline white on blue`.
+/// Colors are any of the names or numbers accepted by termcolor, such as `red` or `235`.
+fn parse_color_spec(s: &str) -> Result<ColorSpec> {
+    let mut spec = ColorSpec::new();
+    let mut words = s.split_whitespace();
+    while let Some(word) = words.next() {
+        match word {
+            "bold" => {
+                spec.set_bold(true);
+            }
+            "underline" => {
+                spec.set_underline(true);
+            }
+            "italic" => {
+                spec.set_italic(true);
+            }
+            "dimmed" => {
+                spec.set_dimmed(true);
+            }
+            "bright" => {
+                spec.set_intense(true);
+            }
+            "on" => {
+                let color = words
+                    .next()
+                    .ok_or_else(|| anyhow!("expected a color after `on`"))?;
+                spec.set_bg(Some(parse_color(color)?));
+            }
+            _ => {
+                spec.set_fg(Some(parse_color(word)?));
+            }
+        }
+    }
+    Ok(spec)
+}
+
+fn parse_color(s: &str) -> Result<Color> {
+    match Color::from_str(s) {
+        Ok(color) => Ok(color),
+        Err(_) => bail!("invalid color `{}`", s),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use toml::toml;
+
+    #[test]
+    fn test_parse_color_spec() {
+        let mut exp = ColorSpec::new();
+        exp.set_bold(true)
+            .set_fg(Some(Color::Yellow))
+            .set_bg(Some(Color::Ansi256(235)));
+        assert_eq!(parse_color_spec("bold yellow on 235").unwrap(), exp);
+    }
+
+    #[test]
+    fn test_parse_color_spec_errors() {
+        assert_eq!(
+            &parse_color_spec("purple").unwrap_err().to_string(),
+            "invalid color `purple`"
+        );
+        assert_eq!(
+            &parse_color_spec("red on").unwrap_err().to_string(),
+            "expected a color after `on`"
+        );
+    }
+
+    #[test]
+    fn test_update_from_toml() {
+        let mut rules = ColorRules::default();
+        let val = toml! {
+            alternate = "on 235"
+            active = ""
+            [tags]
+            urgent = "magenta"
+            home = "green"
+        };
+        rules.update_from_toml(&val).unwrap();
+
+        assert_eq!(rules.alternate, Some(parse_color_spec("on 235").unwrap()));
+        assert_eq!(rules.active, None);
+        assert_eq!(rules.overdue, ColorRules::default().overdue);
+        assert_eq!(
+            rules.tags,
+            vec![
+                (
+                    Tag::try_from("home").unwrap(),
+                    parse_color_spec("green").unwrap()
+                ),
+                (
+                    Tag::try_from("urgent").unwrap(),
+                    parse_color_spec("magenta").unwrap()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_update_from_toml_errors() {
+        let mut rules = ColorRules::default();
+        let val = toml! { overdue = "purple" };
+        assert_eq!(
+            &rules.update_from_toml(&val).unwrap_err().to_string(),
+            ".overdue: invalid color `purple`"
+        );
+
+        let val = toml! { nosuch = "red" };
+        assert_eq!(
+            &rules.update_from_toml(&val).unwrap_err().to_string(),
+            ": unknown table key `nosuch`"
+        );
+    }
+}
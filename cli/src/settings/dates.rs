@@ -0,0 +1,256 @@
+//! This module contains the data structures used to configure the display of dates.
+
+use anyhow::{anyhow, bail, Result};
+use std::fmt;
+use std::str::FromStr;
+use taskchampion::chrono::format::{Item, StrftimeItems};
+use taskchampion::chrono::prelude::*;
+use taskchampion::chrono::Duration;
+
+/// The format in which dates are displayed in reports and `info`
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum DateFormat {
+    /// ISO 8601 dates, such as `2021-03-04`, with times as `2021-03-04 12:30:00`
+    Iso,
+    /// Relative to now, such as `in 3d` or `2w ago`
+    Relative,
+    /// A strftime-style pattern, such as `%d.%m.%Y`, used for both dates and times
+    Custom(String),
+}
+
+impl FromStr for DateFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<DateFormat> {
+        Ok(match s {
+            "iso" => DateFormat::Iso,
+            "relative" => DateFormat::Relative,
+            _ if s.contains('%') => {
+                if StrftimeItems::new(s).any(|item| item == Item::Error) {
+                    bail!("invalid date format pattern `{}`", s);
+                }
+                DateFormat::Custom(s.to_owned())
+            }
+            _ => bail!(
+                "date format must be `iso`, `relative`, or a pattern such as `%d.%m.%Y`, not `{}`",
+                s
+            ),
+        })
+    }
+}
+
+impl fmt::Display for DateFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateFormat::Iso => write!(f, "iso"),
+            DateFormat::Relative => write!(f, "relative"),
+            DateFormat::Custom(pattern) => write!(f, "{}", pattern),
+        }
+    }
+}
+
+/// The timezone in which dates are displayed
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Timezone {
+    /// The local timezone of the system
+    Local,
+    /// UTC
+    Utc,
+    /// A fixed offset from UTC
+    Fixed(FixedOffset),
+}
+
+impl FromStr for Timezone {
+    type Err = anyhow::Error;
+
+    /// Parse `local`, `utc`, or an offset such as `+05:30` or `-0800`
+    fn from_str(s: &str) -> Result<Timezone> {
+        let err = || anyhow!("timezone must be `local`, `utc`, or an offset such as `+05:30`");
+        Ok(match s {
+            "local" => Timezone::Local,
+            "utc" | "UTC" => Timezone::Utc,
+            _ => {
+                let sign = match s.chars().next() {
+                    Some('+') => 1,
+                    Some('-') => -1,
+                    _ => return Err(err()),
+                };
+                let digits = s[1..].replacen(':', "", 1);
+                if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(err());
+                }
+                let hours: i32 = digits[..2].parse()?;
+                let minutes: i32 = digits[2..].parse()?;
+                if minutes >= 60 {
+                    return Err(err());
+                }
+                let offset =
+                    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(err)?;
+                Timezone::Fixed(offset)
+            }
+        })
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Timezone::Local => write!(f, "local"),
+            Timezone::Utc => write!(f, "utc"),
+            Timezone::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+/// How dates are displayed to the user, combining a format and a timezone
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DateDisplay {
+    pub format: DateFormat,
+    pub timezone: Timezone,
+}
+
+impl Default for DateDisplay {
+    /// ISO dates in the local timezone
+    fn default() -> Self {
+        DateDisplay {
+            format: DateFormat::Iso,
+            timezone: Timezone::Local,
+        }
+    }
+}
+
+impl DateDisplay {
+    /// Format a timestamp as a date, relative to `now` if so configured.
+    pub(crate) fn date(&self, ts: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        self.display(ts, now, "%Y-%m-%d")
+    }
+
+    /// Format a timestamp as a date and time, relative to `now` if so configured.
+    pub(crate) fn datetime(&self, ts: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        self.display(ts, now, "%Y-%m-%d %H:%M:%S")
+    }
+
+    fn display(&self, ts: DateTime<Utc>, now: DateTime<Utc>, iso_pattern: &str) -> String {
+        let pattern = match self.format {
+            DateFormat::Iso => iso_pattern,
+            DateFormat::Relative => return format_relative(ts - now),
+            DateFormat::Custom(ref pattern) => pattern,
+        };
+        match self.timezone {
+            Timezone::Local => ts.with_timezone(&Local).format(pattern).to_string(),
+            Timezone::Utc => ts.format(pattern).to_string(),
+            Timezone::Fixed(offset) => ts.with_timezone(&offset).format(pattern).to_string(),
+        }
+    }
+}
+
+/// Format a duration, such as the age of a task, compactly, such as `3d` or `2w`.
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}min", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h", secs / 3600)
+    } else if secs < 86400 * 14 {
+        format!("{}d", secs / 86400)
+    } else if secs < 86400 * 90 {
+        format!("{}w", secs / (86400 * 7))
+    } else if secs < 86400 * 365 {
+        format!("{}mo", secs / (86400 * 30))
+    } else {
+        format!("{}y", secs / (86400 * 365))
+    }
+}
+
+/// Format the offset of a time from now, such as `in 3d` or `2w ago`.
+fn format_relative(offset: Duration) -> String {
+    if offset.num_seconds().abs() < 60 {
+        "now".to_owned()
+    } else if offset > Duration::zero() {
+        format!("in {}", format_duration(offset))
+    } else {
+        format!("{} ago", format_duration(-offset))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::seconds(5)), s!("5s"));
+        assert_eq!(format_duration(Duration::minutes(5)), s!("5min"));
+        assert_eq!(format_duration(Duration::hours(5)), s!("5h"));
+        assert_eq!(format_duration(Duration::days(5)), s!("5d"));
+        assert_eq!(format_duration(Duration::days(21)), s!("3w"));
+        assert_eq!(format_duration(Duration::days(120)), s!("4mo"));
+        assert_eq!(format_duration(Duration::days(800)), s!("2y"));
+    }
+
+    #[test]
+    fn test_date_format_from_str() {
+        assert_eq!("iso".parse::<DateFormat>().unwrap(), DateFormat::Iso);
+        assert_eq!(
+            "relative".parse::<DateFormat>().unwrap(),
+            DateFormat::Relative
+        );
+        assert_eq!(
+            "%d.%m.%Y".parse::<DateFormat>().unwrap(),
+            DateFormat::Custom(s!("%d.%m.%Y"))
+        );
+        assert!("%Q".parse::<DateFormat>().is_err());
+        assert!("fancy".parse::<DateFormat>().is_err());
+        for s in &["iso", "relative", "%d.%m.%Y"] {
+            assert_eq!(&s.parse::<DateFormat>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_timezone_from_str() {
+        assert_eq!("local".parse::<Timezone>().unwrap(), Timezone::Local);
+        assert_eq!("UTC".parse::<Timezone>().unwrap(), Timezone::Utc);
+        assert_eq!(
+            "+05:30".parse::<Timezone>().unwrap(),
+            Timezone::Fixed(FixedOffset::east(5 * 3600 + 30 * 60))
+        );
+        assert_eq!(
+            "-0800".parse::<Timezone>().unwrap(),
+            Timezone::Fixed(FixedOffset::west(8 * 3600))
+        );
+        assert!("Europe/Paris".parse::<Timezone>().is_err());
+        assert!("+5".parse::<Timezone>().is_err());
+        assert!("+05:75".parse::<Timezone>().is_err());
+        assert_eq!(&"+05:30".parse::<Timezone>().unwrap().to_string(), "+05:30");
+    }
+
+    #[test]
+    fn test_date_display() {
+        let now = Utc.ymd(2021, 3, 4).and_hms(23, 30, 0);
+        let ts = Utc.ymd(2021, 3, 1).and_hms(22, 0, 0);
+
+        let utc = DateDisplay {
+            format: DateFormat::Iso,
+            timezone: Timezone::Utc,
+        };
+        assert_eq!(utc.date(ts, now), "2021-03-01");
+        assert_eq!(utc.datetime(ts, now), "2021-03-01 22:00:00");
+
+        let tokyo = DateDisplay {
+            format: DateFormat::Custom(s!("%d.%m.%Y %H:%M")),
+            timezone: "+09:00".parse().unwrap(),
+        };
+        assert_eq!(tokyo.date(ts, now), "02.03.2021 07:00");
+
+        let relative = DateDisplay {
+            format: DateFormat::Relative,
+            timezone: Timezone::Utc,
+        };
+        assert_eq!(relative.date(ts, now), "3d ago");
+        assert_eq!(relative.date(now + Duration::hours(5), now), "in 5h");
+        assert_eq!(relative.datetime(now, now), "now");
+    }
+}
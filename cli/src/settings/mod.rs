@@ -3,9 +3,19 @@
 //! Configuration is stored in a "parsed" format, meaning that any syntax errors will be caught on
 //! startup and not just when those values are used.
 
+mod color;
+mod dates;
+mod paths;
+mod profile;
 mod report;
+mod secret;
 mod settings;
 mod util;
 
+pub(crate) use color::ColorRules;
+pub(crate) use dates::{format_duration, DateDisplay};
+pub(crate) use paths::{config_home, data_home};
 pub(crate) use report::{get_usage, Column, Property, Report, Sort, SortBy};
-pub(crate) use settings::Settings;
+pub(crate) use secret::{is_secret_reference, resolve_secret};
+pub(crate) use settings::DuplicateCheck;
+pub use settings::Settings;
@@ -0,0 +1,217 @@
+//! Locations of the configuration file and data directories, following the XDG base directory
+//! specification, and migration from the locations used by earlier versions.
+
+use anyhow::{Context, Result};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Whether this platform follows the XDG base directory specification.  Other platforms, such
+/// as macOS and Windows, use their own directories and ignore the XDG environment variables.
+const XDG_PLATFORM: bool = cfg!(all(unix, not(target_os = "macos")));
+
+/// A base directory, and the environment variable from which it was taken, if any
+#[derive(Debug, PartialEq)]
+pub(crate) struct BaseDir {
+    pub(crate) path: PathBuf,
+    pub(crate) env_var: Option<&'static str>,
+}
+
+/// Get a base directory from the given environment variable, if `xdg` is true and the variable
+/// is set to an absolute path, or else the given fallback.  The XDG specification requires
+/// relative paths to be ignored.
+fn base_dir(var: &'static str, fallback: Option<PathBuf>, xdg: bool) -> Option<BaseDir> {
+    match env::var_os(var).map(PathBuf::from) {
+        Some(path) if xdg && path.is_absolute() => Some(BaseDir {
+            path,
+            env_var: Some(var),
+        }),
+        _ => fallback.map(|path| BaseDir {
+            path,
+            env_var: None,
+        }),
+    }
+}
+
+/// The base directory for configuration: `$XDG_CONFIG_HOME` on XDG platforms, or the platform's
+/// configuration directory.
+pub(crate) fn config_home() -> Option<BaseDir> {
+    base_dir("XDG_CONFIG_HOME", dirs_next::config_dir(), XDG_PLATFORM)
+}
+
+/// The base directory for data: `$XDG_DATA_HOME` on XDG platforms, or the platform's local data
+/// directory.
+pub(crate) fn data_home() -> Option<BaseDir> {
+    base_dir("XDG_DATA_HOME", dirs_next::data_local_dir(), XDG_PLATFORM)
+}
+
+/// The default configuration file.  On XDG platforms this is in a `taskchampion` subdirectory
+/// of the configuration directory.  Elsewhere, that subdirectory may be the data directory (on
+/// macOS, both are `~/Library/Application Support`), so the file is directly in the
+/// configuration directory.
+pub(crate) fn config_file() -> Option<PathBuf> {
+    config_home().map(|dir| {
+        if XDG_PLATFORM {
+            dir.path.join("taskchampion").join("taskchampion.toml")
+        } else {
+            dir.path.join("taskchampion.toml")
+        }
+    })
+}
+
+/// The configuration file used by earlier versions, if it differs from [`config_file`]
+pub(crate) fn legacy_config_file() -> Option<PathBuf> {
+    if XDG_PLATFORM {
+        config_home().map(|dir| dir.path.join("taskchampion.toml"))
+    } else {
+        None
+    }
+}
+
+/// The default replica directory
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    data_home().map(|dir| dir.path.join("taskchampion"))
+}
+
+/// The default local sync server directory
+pub(crate) fn server_dir() -> Option<PathBuf> {
+    data_home().map(|dir| dir.path.join("taskchampion-sync-server"))
+}
+
+/// Move a file or directory from its legacy location to its new location, if it exists only in
+/// the legacy location.  Returns true if it was moved.
+pub(crate) fn migrate(legacy: &Path, new: &Path) -> Result<bool> {
+    if legacy == new || new.exists() || !legacy.exists() {
+        return Ok(false);
+    }
+    if let Some(dir) = new.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Could not create {:?} to migrate {:?}", dir, legacy))?;
+    }
+    fs::rename(legacy, new).with_context(|| {
+        format!(
+            "Could not move {:?} to {:?}; move it manually to continue using it",
+            legacy, new
+        )
+    })?;
+    Ok(true)
+}
+
+/// Move a file from its legacy location to its new location as with [`migrate`], returning the
+/// location to use and whether it was moved.  A failure to move the file is not fatal: it is
+/// logged, and the file is used in its legacy location.
+pub(crate) fn migrate_or_keep(legacy: PathBuf, new: PathBuf) -> (PathBuf, bool) {
+    match migrate(&legacy, &new) {
+        Ok(moved) => (new, moved),
+        Err(e) => {
+            log::warn!("{:#}", e);
+            (legacy, false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_base_dir() {
+        // these variables are unique to this test, so they do not interfere with others
+        env::set_var("TC_TEST_BASE_DIR_ABS", "/xdg/data");
+        env::set_var("TC_TEST_BASE_DIR_REL", "xdg/data");
+        env::remove_var("TC_TEST_BASE_DIR_UNSET");
+
+        let fallback = Some(BaseDir {
+            path: PathBuf::from("/fallback"),
+            env_var: None,
+        });
+        assert_eq!(
+            base_dir(
+                "TC_TEST_BASE_DIR_ABS",
+                Some(PathBuf::from("/fallback")),
+                true
+            ),
+            Some(BaseDir {
+                path: PathBuf::from("/xdg/data"),
+                env_var: Some("TC_TEST_BASE_DIR_ABS"),
+            })
+        );
+        assert_eq!(
+            base_dir(
+                "TC_TEST_BASE_DIR_ABS",
+                Some(PathBuf::from("/fallback")),
+                false
+            ),
+            fallback
+        );
+        assert_eq!(
+            base_dir(
+                "TC_TEST_BASE_DIR_REL",
+                Some(PathBuf::from("/fallback")),
+                true
+            ),
+            fallback
+        );
+        assert_eq!(base_dir("TC_TEST_BASE_DIR_UNSET", None, true), None);
+    }
+
+    #[test]
+    fn test_migrate() {
+        let tmp_dir = TempDir::new().unwrap();
+        let legacy = tmp_dir.path().join("taskchampion.toml");
+        let new = tmp_dir
+            .path()
+            .join("taskchampion")
+            .join("taskchampion.toml");
+        fs::write(&legacy, "pager = \"less\"\n").unwrap();
+
+        assert!(migrate(&legacy, &new).unwrap());
+        assert!(!legacy.exists());
+        assert_eq!(fs::read_to_string(&new).unwrap(), "pager = \"less\"\n");
+
+        // nothing left to migrate
+        assert!(!migrate(&legacy, &new).unwrap());
+    }
+
+    #[test]
+    fn test_migrate_new_exists() {
+        let tmp_dir = TempDir::new().unwrap();
+        let legacy = tmp_dir.path().join("old");
+        let new = tmp_dir.path().join("new");
+        fs::create_dir(&legacy).unwrap();
+        fs::create_dir(&new).unwrap();
+
+        assert!(!migrate(&legacy, &new).unwrap());
+        assert!(legacy.exists());
+        assert!(!migrate(&new, &new).unwrap());
+    }
+
+    #[test]
+    fn test_migrate_or_keep() {
+        let tmp_dir = TempDir::new().unwrap();
+        let legacy = tmp_dir.path().join("taskchampion.toml");
+        let new = tmp_dir
+            .path()
+            .join("taskchampion")
+            .join("taskchampion.toml");
+        fs::write(&legacy, "pager = \"less\"\n").unwrap();
+
+        // the new directory cannot be created where a file exists
+        fs::write(tmp_dir.path().join("taskchampion"), "").unwrap();
+        assert_eq!(
+            migrate_or_keep(legacy.clone(), new.clone()),
+            (legacy.clone(), false)
+        );
+        assert!(legacy.exists());
+
+        fs::remove_file(tmp_dir.path().join("taskchampion")).unwrap();
+        assert_eq!(
+            migrate_or_keep(legacy.clone(), new.clone()),
+            (new.clone(), true)
+        );
+        assert!(new.exists());
+        assert_eq!(migrate_or_keep(legacy, new.clone()), (new, false));
+    }
+}
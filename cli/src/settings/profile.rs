@@ -0,0 +1,110 @@
+//! This module contains the data structure used to define replica profiles.
+
+use crate::settings::util::table_with_keys;
+use anyhow::{anyhow, Result};
+use std::convert::TryFrom;
+use std::path::PathBuf;
+
+/// A profile selects a replica and the server with which it synchronizes.  Each setting given in
+/// the profile overrides the corresponding top-level setting when the profile is in use.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub(crate) struct Profile {
+    pub data_dir: Option<PathBuf>,
+    pub server_client_key: Option<String>,
+    pub server_origin: Option<String>,
+    pub encryption_secret: Option<String>,
+    pub server_dir: Option<PathBuf>,
+}
+
+impl Profile {
+    /// The settings that a profile can override
+    const KEYS: &'static [&'static str] = &[
+        "data_dir",
+        "server_client_key",
+        "server_origin",
+        "encryption_secret",
+        "server_dir",
+    ];
+
+    /// Determine whether this profile overrides the given top-level setting
+    pub(crate) fn overrides(&self, key: &str) -> bool {
+        match key {
+            "data_dir" => self.data_dir.is_some(),
+            "server_client_key" => self.server_client_key.is_some(),
+            "server_origin" => self.server_origin.is_some(),
+            "encryption_secret" => self.encryption_secret.is_some(),
+            "server_dir" => self.server_dir.is_some(),
+            _ => false,
+        }
+    }
+}
+
+impl TryFrom<&toml::Value> for Profile {
+    type Error = anyhow::Error;
+
+    /// Create a Profile from a toml value.  This should be the `profiles.<name>` value.  The
+    /// error message begins with any additional path information, e.g., `.data_dir: ..`.
+    fn try_from(cfg: &toml::Value) -> Result<Profile> {
+        let table = table_with_keys(cfg, Profile::KEYS).map_err(|e| anyhow!(": {}", e))?;
+
+        let get_str = |name: &str| -> Result<Option<String>> {
+            table
+                .get(name)
+                .map(|v| {
+                    v.as_str()
+                        .map(|s| s.to_owned())
+                        .ok_or_else(|| anyhow!(".{}: not a string", name))
+                })
+                .transpose()
+        };
+
+        Ok(Profile {
+            data_dir: get_str("data_dir")?.map(PathBuf::from),
+            server_client_key: get_str("server_client_key")?,
+            server_origin: get_str("server_origin")?,
+            encryption_secret: get_str("encryption_secret")?,
+            server_dir: get_str("server_dir")?.map(PathBuf::from),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use toml::toml;
+
+    #[test]
+    fn test_profile() {
+        let val = toml! {
+            data_dir = "/home/me/.tasks-work"
+            server_origin = "https://tasks.example.com"
+        };
+        let profile = Profile::try_from(&val).unwrap();
+        assert_eq!(
+            profile,
+            Profile {
+                data_dir: Some(PathBuf::from("/home/me/.tasks-work")),
+                server_origin: Some(s!("https://tasks.example.com")),
+                ..Default::default()
+            }
+        );
+        assert!(profile.overrides("data_dir"));
+        assert!(!profile.overrides("server_dir"));
+        assert!(!profile.overrides("pager"));
+    }
+
+    #[test]
+    fn test_profile_unknown_key() {
+        let val = toml! { pager = "more" };
+        let err = Profile::try_from(&val).unwrap_err().to_string();
+        assert_eq!(&err, ": unknown table key `pager`");
+    }
+
+    #[test]
+    fn test_profile_not_string() {
+        let val = toml! { data_dir = 10 };
+        let err = Profile::try_from(&val).unwrap_err().to_string();
+        assert_eq!(&err, ".data_dir: not a string");
+    }
+}
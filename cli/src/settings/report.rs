@@ -1,10 +1,11 @@
 //! This module contains the data structures used to define reports.
 
-use crate::argparse::{Condition, Filter};
+use crate::argparse::{Condition, Filter, ReportLimit};
 use crate::settings::util::table_with_keys;
 use crate::usage::{self, Usage};
 use anyhow::{anyhow, bail, Result};
 use std::convert::{TryFrom, TryInto};
+use std::str::FromStr;
 
 /// A report specifies a filter as well as a sort order and information about which
 /// task attributes to display
@@ -16,6 +17,8 @@ pub(crate) struct Report {
     pub sort: Vec<Sort>,
     /// Filter selecting tasks for this report
     pub filter: Filter,
+    /// Maximum number of tasks to display, if any
+    pub limit: Option<ReportLimit>,
 }
 
 /// A column to display in a report
@@ -38,6 +41,9 @@ pub(crate) enum Property {
     /// The task's full UUID
     Uuid,
 
+    /// The first eight characters of the task's UUID
+    ShortUuid,
+
     /// Whether the task is active or not
     Active,
 
@@ -49,6 +55,24 @@ pub(crate) enum Property {
 
     /// The task's wait date
     Wait,
+
+    /// The task's due date
+    Due,
+
+    /// Whether the task is a recurring task or an instance of one
+    Recur,
+
+    /// The task's project
+    Project,
+
+    /// The task's urgency
+    Urgency,
+
+    /// The time since the task was created
+    Age,
+
+    /// The time since the task was started, if it is active
+    Elapsed,
 }
 
 /// A sorting criterion for a sort operation.
@@ -77,6 +101,21 @@ pub(crate) enum SortBy {
 
     /// The task's wait date
     Wait,
+
+    /// The task's due date
+    Due,
+
+    /// The task's project
+    Project,
+
+    /// The task's urgency
+    Urgency,
+
+    /// The time since the task was created
+    Age,
+
+    /// The time since the task was started, if it is active
+    Elapsed,
 }
 
 // Conversions from settings::Settings.
@@ -96,7 +135,7 @@ impl TryFrom<&toml::Value> for Report {
     /// The error message begins with any additional path information, e.g., `.sort[1].sort_by:
     /// ..`.
     fn try_from(cfg: &toml::Value) -> Result<Report> {
-        let keys = ["sort", "columns", "filter"];
+        let keys = ["sort", "columns", "filter", "limit"];
         let table = table_with_keys(cfg, &keys).map_err(|e| anyhow!(": {}", e))?;
 
         let sort = match table.get("sort") {
@@ -137,10 +176,20 @@ impl TryFrom<&toml::Value> for Report {
             None => vec![],
         };
 
+        let limit = match table.get("limit") {
+            Some(v) => match (v.as_integer(), v.as_str()) {
+                (Some(n), _) if n > 0 => Some(ReportLimit::Count(n as usize)),
+                (_, Some("page")) => Some(ReportLimit::Page),
+                _ => bail!(".limit: not a positive integer or \"page\""),
+            },
+            None => None,
+        };
+
         Ok(Report {
             columns,
             sort,
             filter: Filter { conditions },
+            limit,
         })
     }
 }
@@ -148,7 +197,13 @@ impl TryFrom<&toml::Value> for Report {
 impl TryFrom<&toml::Value> for Column {
     type Error = anyhow::Error;
 
+    /// Create a Column from a toml value.  This is either a table with `label` and `property`, or
+    /// just a property name, which is then also used as the label.
     fn try_from(cfg: &toml::Value) -> Result<Column> {
+        if let Some(s) = cfg.as_str() {
+            return s.parse().map_err(|e| anyhow!(": {}", e));
+        }
+
         let keys = ["label", "property"];
         let table = table_with_keys(cfg, &keys).map_err(|e| anyhow!(": {}", e))?;
 
@@ -169,19 +224,68 @@ impl TryFrom<&toml::Value> for Column {
     }
 }
 
+impl FromStr for Column {
+    type Err = anyhow::Error;
+
+    /// Parse a column from a property name, using the name as the label
+    fn from_str(s: &str) -> Result<Column> {
+        Ok(Column {
+            label: s.to_owned(),
+            property: s.parse()?,
+        })
+    }
+}
+
 impl TryFrom<&toml::Value> for Property {
     type Error = anyhow::Error;
 
     fn try_from(cfg: &toml::Value) -> Result<Property> {
         let s = cfg.as_str().ok_or_else(|| anyhow!(": not a string"))?;
+        s.parse().map_err(|e| anyhow!(": {}", e))
+    }
+}
+
+impl Property {
+    /// The name of this property, as used in the configuration file and in machine-readable
+    /// output
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Property::Id => "id",
+            Property::Uuid => "uuid",
+            Property::ShortUuid => "uuid.short",
+            Property::Active => "active",
+            Property::Description => "description",
+            Property::Tags => "tags",
+            Property::Wait => "wait",
+            Property::Due => "due",
+            Property::Recur => "recur",
+            Property::Project => "project",
+            Property::Urgency => "urgency",
+            Property::Age => "age",
+            Property::Elapsed => "elapsed",
+        }
+    }
+}
+
+impl FromStr for Property {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Property> {
         Ok(match s {
             "id" => Property::Id,
             "uuid" => Property::Uuid,
+            "uuid.short" => Property::ShortUuid,
             "active" => Property::Active,
             "description" => Property::Description,
             "tags" => Property::Tags,
             "wait" => Property::Wait,
-            _ => bail!(": unknown property {}", s),
+            "due" => Property::Due,
+            "recur" => Property::Recur,
+            "project" => Property::Project,
+            "urgency" => Property::Urgency,
+            "age" => Property::Age,
+            "elapsed" => Property::Elapsed,
+            _ => bail!("unknown property {}", s),
         })
     }
 }
@@ -189,7 +293,13 @@ impl TryFrom<&toml::Value> for Property {
 impl TryFrom<&toml::Value> for Sort {
     type Error = anyhow::Error;
 
+    /// Create a Sort from a toml value.  This is either a table with `sort_by` and `ascending`,
+    /// or a string such as `due+` or `urgency-`.
     fn try_from(cfg: &toml::Value) -> Result<Sort> {
+        if let Some(s) = cfg.as_str() {
+            return s.parse().map_err(|e| anyhow!(": {}", e));
+        }
+
         let keys = ["ascending", "sort_by"];
         let table = table_with_keys(cfg, &keys).map_err(|e| anyhow!(": {}", e))?;
         let ascending = match table.get("ascending") {
@@ -208,17 +318,50 @@ impl TryFrom<&toml::Value> for Sort {
     }
 }
 
+impl FromStr for Sort {
+    type Err = anyhow::Error;
+
+    /// Parse a sort criterion such as `due`, `due+`, or `urgency-`, where a trailing `-`
+    /// indicates descending order.
+    fn from_str(s: &str) -> Result<Sort> {
+        let (sort_by, ascending) = if let Some(sort_by) = s.strip_suffix('-') {
+            (sort_by, false)
+        } else if let Some(sort_by) = s.strip_suffix('+') {
+            (sort_by, true)
+        } else {
+            (s, true)
+        };
+        Ok(Sort {
+            ascending,
+            sort_by: sort_by.parse()?,
+        })
+    }
+}
+
 impl TryFrom<&toml::Value> for SortBy {
     type Error = anyhow::Error;
 
     fn try_from(cfg: &toml::Value) -> Result<SortBy> {
         let s = cfg.as_str().ok_or_else(|| anyhow!(": not a string"))?;
+        s.parse().map_err(|e| anyhow!(": {}", e))
+    }
+}
+
+impl FromStr for SortBy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<SortBy> {
         Ok(match s {
             "id" => SortBy::Id,
             "uuid" => SortBy::Uuid,
             "description" => SortBy::Description,
             "wait" => SortBy::Wait,
-            _ => bail!(": unknown sort_by value `{}`", s),
+            "due" => SortBy::Due,
+            "project" => SortBy::Project,
+            "urgency" => SortBy::Urgency,
+            "age" => SortBy::Age,
+            "elapsed" => SortBy::Elapsed,
+            _ => bail!("unknown sort_by value `{}`", s),
         })
     }
 }
@@ -234,6 +377,11 @@ pub(crate) fn get_usage(u: &mut Usage) {
         as_sort_by: Some("Sort by the task's full UUID"),
         as_column: Some("The task's full UUID"),
     });
+    u.report_properties.push(usage::ReportProperty {
+        name: "uuid.short",
+        as_sort_by: None,
+        as_column: Some("The first eight characters of the task's UUID"),
+    });
     u.report_properties.push(usage::ReportProperty {
         name: "active",
         as_sort_by: None,
@@ -244,6 +392,16 @@ pub(crate) fn get_usage(u: &mut Usage) {
         as_sort_by: Some("Sort by the task's wait date, with non-waiting tasks first"),
         as_column: Some("Wait date of the task"),
     });
+    u.report_properties.push(usage::ReportProperty {
+        name: "due",
+        as_sort_by: Some("Sort by the task's due date, with tasks that have no due date last"),
+        as_column: Some("Due date of the task"),
+    });
+    u.report_properties.push(usage::ReportProperty {
+        name: "recur",
+        as_sort_by: None,
+        as_column: Some("`R` for a recurring task, or `r` for an instance of a recurring task"),
+    });
     u.report_properties.push(usage::ReportProperty {
         name: "description",
         as_sort_by: Some("Sort by the task's description"),
@@ -254,6 +412,28 @@ pub(crate) fn get_usage(u: &mut Usage) {
         as_sort_by: None,
         as_column: Some("The task's tags"),
     });
+    u.report_properties.push(usage::ReportProperty {
+        name: "project",
+        as_sort_by: Some("Sort by the task's project, with tasks that have no project last"),
+        as_column: Some("The task's project"),
+    });
+    u.report_properties.push(usage::ReportProperty {
+        name: "urgency",
+        as_sort_by: Some("Sort by the task's urgency, least urgent first"),
+        as_column: Some("The task's urgency"),
+    });
+    u.report_properties.push(usage::ReportProperty {
+        name: "age",
+        as_sort_by: Some("Sort by the task's age, newest first"),
+        as_column: Some("The time since the task was created"),
+    });
+    u.report_properties.push(usage::ReportProperty {
+        name: "elapsed",
+        as_sort_by: Some(
+            "Sort by the time since the task was started, most recently started first",
+        ),
+        as_column: Some("The time since the task was started, if it is active"),
+    });
 }
 
 #[cfg(test)]
@@ -279,6 +459,58 @@ mod test {
         );
         assert_eq!(report.columns, vec![]);
         assert_eq!(report.sort, vec![]);
+        assert_eq!(report.limit, None);
+    }
+
+    #[test]
+    fn test_report_limit() {
+        let val = toml! {
+            columns = []
+            limit = 10
+        };
+        let report = Report::try_from(val).unwrap();
+        assert_eq!(report.limit, Some(ReportLimit::Count(10)));
+    }
+
+    #[test]
+    fn test_report_limit_page() {
+        let val = toml! {
+            columns = []
+            limit = "page"
+        };
+        let report = Report::try_from(val).unwrap();
+        assert_eq!(report.limit, Some(ReportLimit::Page));
+    }
+
+    #[test]
+    fn test_report_limit_invalid() {
+        let val = toml! {
+            columns = []
+            limit = 0
+        };
+        let err = Report::try_from(val).unwrap_err().to_string();
+        assert_eq!(&err, ".limit: not a positive integer or \"page\"");
+    }
+
+    #[test]
+    fn test_report_filter_expression() {
+        let val = toml! {
+            columns = []
+            filter = ["status:pending", "(+work or +urgent)"]
+        };
+        let report = Report::try_from(val).unwrap();
+        assert_eq!(
+            report.filter,
+            Filter {
+                conditions: vec![
+                    Condition::Status(Status::Pending),
+                    Condition::Or(vec![
+                        Condition::HasTag("work".try_into().unwrap()),
+                        Condition::HasTag("urgent".try_into().unwrap()),
+                    ]),
+                ],
+            }
+        );
     }
 
     #[test]
@@ -460,6 +692,27 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_column_str() {
+        let val = toml::Value::String("urgency".to_owned());
+        assert_eq!(
+            Column::try_from(&val).unwrap(),
+            Column {
+                label: "urgency".to_owned(),
+                property: Property::Urgency,
+            }
+        );
+    }
+
+    #[test]
+    fn test_column_str_unknown() {
+        let val = toml::Value::String("nosuch".to_owned());
+        assert_eq!(
+            &Column::try_from(&val).unwrap_err().to_string(),
+            ": unknown property nosuch"
+        );
+    }
+
     #[test]
     fn test_property() {
         let val = toml::Value::String("uuid".to_owned());
@@ -467,6 +720,21 @@ mod test {
         assert_eq!(prop, Property::Uuid);
     }
 
+    #[test]
+    fn test_property_name() {
+        for name in &[
+            "id",
+            "uuid.short",
+            "description",
+            "urgency",
+            "age",
+            "elapsed",
+        ] {
+            let prop: Property = name.parse().unwrap();
+            assert_eq!(&prop.name(), name);
+        }
+    }
+
     #[test]
     fn test_property_invalid_type() {
         let val = toml::Value::Array(vec![]);
@@ -492,6 +760,38 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_sort_str() {
+        let sort = Sort::try_from(&toml::Value::String("urgency-".to_owned())).unwrap();
+        assert_eq!(
+            sort,
+            Sort {
+                ascending: false,
+                sort_by: SortBy::Urgency,
+            }
+        );
+        let sort: Sort = "due+".parse().unwrap();
+        assert_eq!(
+            sort,
+            Sort {
+                ascending: true,
+                sort_by: SortBy::Due,
+            }
+        );
+        let sort: Sort = "project".parse().unwrap();
+        assert_eq!(
+            sort,
+            Sort {
+                ascending: true,
+                sort_by: SortBy::Project,
+            }
+        );
+        assert_eq!(
+            &"nosuch-".parse::<Sort>().unwrap_err().to_string(),
+            "unknown sort_by value `nosuch`"
+        );
+    }
+
     #[test]
     fn test_sort_no_ascending() {
         let val = toml! {
@@ -0,0 +1,114 @@
+//! Support for configuration values that refer to secrets stored outside of the configuration
+//! file, so that they need not be stored there in plaintext.
+
+use anyhow::{bail, Context, Result};
+use std::process::Command;
+
+/// Prefix of a value giving a command that prints the secret
+const COMMAND_PREFIX: &str = "cmd:";
+
+/// Prefix of a value giving the path of a file containing the secret
+const FILE_PREFIX: &str = "file:";
+
+/// Prefix of a value giving the secret itself, for secrets that begin with one of the prefixes
+const PLAIN_PREFIX: &str = "plain:";
+
+/// Determine whether a configuration value refers to a secret stored elsewhere.
+pub(crate) fn is_secret_reference(value: &str) -> bool {
+    value.starts_with(COMMAND_PREFIX) || value.starts_with(FILE_PREFIX)
+}
+
+/// Get the secret to which a configuration value refers.  A value of the form `cmd:<command>`
+/// runs the command with the shell (`sh`, or `cmd` on Windows) and uses its output, and a value
+/// of the form `file:<path>` uses the content of the file, in either case without any trailing
+/// newline.  A value of the form `plain:<secret>` is the given secret, even if it begins with one
+/// of these prefixes.  Any other value is the secret itself.
+pub(crate) fn resolve_secret(value: &str) -> Result<String> {
+    let secret = if let Some(command) = value.strip_prefix(COMMAND_PREFIX) {
+        if command.trim().is_empty() {
+            bail!("No command given in `{}`", value);
+        }
+        let output = shell_command(command)
+            .output()
+            .with_context(|| format!("Could not run secret command `{}`", command))?;
+        if !output.status.success() {
+            bail!(
+                "Secret command `{}` exited with {}: {}",
+                command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        String::from_utf8(output.stdout)
+            .with_context(|| format!("Output of secret command `{}` is not UTF-8", command))?
+    } else if let Some(path) = value.strip_prefix(FILE_PREFIX) {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read secret from {:?}", path))?
+    } else {
+        return Ok(value.strip_prefix(PLAIN_PREFIX).unwrap_or(value).to_owned());
+    };
+    Ok(secret.trim_end_matches(&['\r', '\n'][..]).to_owned())
+}
+
+/// Create a command to run the given command line with the shell
+fn shell_command(command: &str) -> Command {
+    let mut cmd;
+    if cfg!(windows) {
+        cmd = Command::new("cmd");
+        cmd.arg("/C");
+    } else {
+        cmd = Command::new("sh");
+        cmd.arg("-c");
+    }
+    cmd.arg(command);
+    cmd
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plain() {
+        assert!(!is_secret_reference("shh"));
+        assert_eq!(resolve_secret("shh").unwrap(), "shh");
+
+        // the prefix allows secrets that look like references
+        assert!(!is_secret_reference("plain:cmd:shh"));
+        assert_eq!(resolve_secret("plain:cmd:shh").unwrap(), "cmd:shh");
+        assert_eq!(resolve_secret("plain:plain:shh").unwrap(), "plain:shh");
+    }
+
+    #[test]
+    fn test_file() {
+        let cfg_dir = TempDir::new().unwrap();
+        let path = cfg_dir.path().join("secret");
+        std::fs::write(&path, "from a file\n").unwrap();
+        let value = format!("file:{}", path.display());
+        assert!(is_secret_reference(&value));
+        assert_eq!(resolve_secret(&value).unwrap(), "from a file");
+
+        let value = format!("file:{}", cfg_dir.path().join("nosuch").display());
+        assert!(resolve_secret(&value).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command() {
+        assert!(is_secret_reference("cmd:echo hello"));
+        assert_eq!(
+            resolve_secret("cmd:echo from a command").unwrap(),
+            "from a command"
+        );
+        assert!(resolve_secret("cmd:false").is_err());
+        assert!(resolve_secret("cmd:").is_err());
+
+        // the command is run by the shell, so it may quote its arguments
+        assert_eq!(
+            resolve_secret("cmd:printf '%s' \"my  key\"").unwrap(),
+            "my  key"
+        );
+    }
+}
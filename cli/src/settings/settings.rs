@@ -1,18 +1,66 @@
-use super::util::table_with_keys;
-use super::{Column, Property, Report, Sort, SortBy};
-use crate::argparse::{Condition, Filter};
+use super::dates::{DateDisplay, DateFormat, Timezone};
+use super::paths;
+use super::profile::Profile;
+use super::secret::{is_secret_reference, resolve_secret};
+use super::util::{key_line, table_with_keys, unknown_key};
+use super::{ColorRules, Column, Property, Report, Sort, SortBy};
+use crate::argparse::{Condition, Filter, ReportLimit};
 use anyhow::{anyhow, bail, Context, Result};
+use log::LevelFilter;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::env;
+use std::ffi::OsString;
+use std::fmt;
 use std::fs;
 use std::path::PathBuf;
-use taskchampion::Status;
+use std::str::FromStr;
+use taskchampion::{Status, Tag, Uuid};
 use toml::value::Table;
 use toml_edit::Document;
 
+/// Whether `ta add` checks for existing tasks with similar descriptions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum DuplicateCheck {
+    /// Do not check
+    Off,
+    /// List similar tasks, but add the task anyway
+    Warn,
+    /// List similar tasks and ask whether to add the task
+    Prompt,
+}
+
+impl FromStr for DuplicateCheck {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<DuplicateCheck> {
+        Ok(match s {
+            "off" => DuplicateCheck::Off,
+            "warn" => DuplicateCheck::Warn,
+            "prompt" => DuplicateCheck::Prompt,
+            _ => bail!(
+                "duplicate check must be `off`, `warn`, or `prompt`, not `{}`",
+                s
+            ),
+        })
+    }
+}
+
+impl fmt::Display for DuplicateCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DuplicateCheck::Off => write!(f, "off"),
+            DuplicateCheck::Warn => write!(f, "warn"),
+            DuplicateCheck::Prompt => write!(f, "prompt"),
+        }
+    }
+}
+
+/// The CLI's settings, as read from its configuration file.  Unknown keys and invalid values in
+/// the file are errors when it is loaded, and [`Settings::validate`] checks the settings that are
+/// otherwise only checked when they are used.
 #[derive(Debug, PartialEq)]
-pub(crate) struct Settings {
+pub struct Settings {
     /// filename from which this configuration was loaded, if any
     pub(crate) filename: Option<PathBuf>,
 
@@ -20,6 +68,10 @@ pub(crate) struct Settings {
     /// prompt, and `None` means to use the default value.
     pub(crate) modification_count_prompt: Option<i64>,
 
+    /// Whether to prompt for confirmation at all; if false, `modification_count_prompt` is
+    /// ignored.
+    pub(crate) confirmation: bool,
+
     /// replica
     pub(crate) data_dir: PathBuf,
     pub(crate) avoid_snapshots: bool,
@@ -34,34 +86,155 @@ pub(crate) struct Settings {
 
     /// reports
     pub(crate) reports: HashMap<String, Report>,
+
+    /// the report to show when no subcommand or report name is given
+    pub(crate) default_report: String,
+
+    /// rules for coloring report rows
+    pub(crate) colors: ColorRules,
+
+    /// how dates are displayed in reports and `info`
+    pub(crate) dates: DateDisplay,
+
+    /// command aliases, mapping each alias to the arguments it expands to
+    pub(crate) aliases: HashMap<String, Vec<String>>,
+
+    /// contexts, mapping each context name to its filter expression
+    pub(crate) contexts: HashMap<String, String>,
+
+    /// the active context, if any
+    pub(crate) context: Option<String>,
+
+    /// the command used to page long output; `None` means to use `$PAGER` or the default, and
+    /// an empty string disables paging
+    pub(crate) pager: Option<String>,
+
+    /// the window in which `ta reminders` reports tasks, as a duration such as `1h`; `None`
+    /// means to use the default
+    pub(crate) reminder_window: Option<String>,
+
+    /// the command run for each reminder by `ta reminders --notify`; `None` means to use
+    /// `notify-send`
+    pub(crate) reminder_command: Option<String>,
+
+    /// whether `ta add` checks for pending tasks with similar descriptions
+    pub(crate) duplicate_check: DuplicateCheck,
+
+    /// profiles, each selecting a replica and sync server
+    pub(crate) profiles: HashMap<String, Profile>,
+
+    /// the profile in use, if any, as given by the `--profile` option
+    pub(crate) profile: Option<String>,
+
+    /// the level of log messages to write; `None` means to use `$RUST_LOG` or the default
+    pub(crate) log_level: Option<LevelFilter>,
+
+    /// the file to which log messages are appended, instead of stderr
+    pub(crate) log_file: Option<PathBuf>,
+
+    /// files and directories moved from their legacy locations when these settings were read,
+    /// as (from, to)
+    pub(crate) migrations: Vec<(PathBuf, PathBuf)>,
 }
 
 impl Settings {
-    pub(crate) fn read() -> Result<Self> {
-        if let Some(config_file) = env::var_os("TASKCHAMPION_CONFIG") {
-            log::debug!("Loading configuration from {:?}", config_file);
-            env::remove_var("TASKCHAMPION_CONFIG");
-            Self::load_from_file(config_file.into(), true)
-        } else if let Some(filename) = Settings::default_filename() {
+    /// The configuration keys that can be set with [`Settings::set`]
+    pub(crate) const SETTABLE_KEYS: &'static [&'static str] = &[
+        "data_dir",
+        "modification_count_prompt",
+        "confirmation",
+        "avoid_snapshots",
+        "server_client_key",
+        "server_origin",
+        "encryption_secret",
+        "server_dir",
+        "pager",
+        "default_report",
+        "date_format",
+        "timezone",
+        "reminder_window",
+        "reminder_command",
+        "duplicate_check",
+        // reports and colors are not allowed, since they are not strings
+    ];
+
+    /// Environment variables naming the configuration file, in order of precedence
+    const CONFIG_ENV_VARS: &'static [&'static str] = &["TASKCHAMPION_CONFIG", "TASKRC"];
+
+    /// Environment variables naming the data directory, in order of precedence
+    const DATA_ENV_VARS: &'static [&'static str] = &["TASKCHAMPION_DATA", "TASKDATA"];
+
+    /// Read the settings from the configuration file named in the environment, or from the
+    /// default configuration file if it exists.  The default configuration file is first moved
+    /// from the location used by earlier versions, if necessary; if it cannot be moved, it is
+    /// read from that location instead.
+    pub fn read() -> Result<Self> {
+        let mut migrations = vec![];
+        let mut settings = if let Some((var, config_file)) = first_env_var(Self::CONFIG_ENV_VARS) {
+            log::debug!("Loading configuration from {:?} (${})", config_file, var);
+            env::remove_var(var);
+            Self::load_from_file(config_file.into(), true)?
+        } else if let Some(mut filename) = Settings::default_filename() {
+            if let Some(legacy) = paths::legacy_config_file() {
+                let (file, moved) = paths::migrate_or_keep(legacy.clone(), filename.clone());
+                if moved {
+                    migrations.push((legacy, filename));
+                }
+                filename = file;
+            }
             log::debug!("Loading configuration from {:?} (optional)", filename);
-            Self::load_from_file(filename, false)
+            Self::load_from_file(filename, false)?
         } else {
-            Ok(Default::default())
+            Default::default()
+        };
+
+        settings.apply_data_env_var();
+        settings.migrations = migrations;
+        Ok(settings)
+    }
+
+    /// Re-read the configuration file from which these settings were loaded, such as when a
+    /// long-running frontend sees that the file has changed.  The profile in use, if any, is
+    /// applied to the new settings.
+    pub fn reload(&self) -> Result<Self> {
+        let mut settings = match self.filename {
+            Some(ref filename) => Self::load_from_file(filename.clone(), false)?,
+            None => Self::default(),
+        };
+        settings.apply_data_env_var();
+        if let Some(ref profile) = self.profile {
+            settings.use_profile(profile)?;
         }
+        Ok(settings)
+    }
+
+    /// Use the data directory in the environment, if any, which overrides that in the
+    /// configuration file.
+    fn apply_data_env_var(&mut self) {
+        if let Some((var, data_dir)) = first_env_var(Self::DATA_ENV_VARS) {
+            log::debug!("Using data directory {:?} (${})", data_dir, var);
+            self.data_dir = data_dir.into();
+        }
+    }
+
+    /// Get the name of the environment variable from which the data directory was taken, if any
+    pub(crate) fn data_dir_env_var() -> Option<&'static str> {
+        first_env_var(Self::DATA_ENV_VARS).map(|(var, _)| var)
     }
 
     /// Get the default filename for the configuration, or None if that cannot
     /// be determined.
     fn default_filename() -> Option<PathBuf> {
-        dirs_next::config_dir().map(|dir| dir.join("taskchampion.toml"))
+        paths::config_file()
     }
 
     /// Update this settings object with the contents of the given TOML file.  Top-level settings
-    /// are overwritten, and reports are overwritten by name.
-    pub(crate) fn load_from_file(config_file: PathBuf, required: bool) -> Result<Self> {
+    /// are overwritten, and reports are overwritten by name.  An unknown key is an error, giving
+    /// the line on which it appears.
+    pub fn load_from_file(config_file: PathBuf, required: bool) -> Result<Self> {
         let mut settings = Self::default();
 
-        let config_toml = match fs::read_to_string(config_file.clone()) {
+        let source = match fs::read_to_string(config_file.clone()) {
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
                 return if required {
                     Err(e.into())
@@ -74,14 +247,18 @@ impl Settings {
             Ok(s) => s,
         };
 
-        let config_toml = config_toml
+        let config_toml = source
             .parse::<toml::Value>()
             .with_context(|| format!("error while reading {:?}", config_file))?;
 
         settings.filename = Some(config_file.clone());
-        settings
-            .update_from_toml(&config_toml)
-            .with_context(|| format!("error while parsing {:?}", config_file))?;
+        settings.update_from_toml(&config_toml).map_err(|e| {
+            let location = match unknown_key(&e).and_then(|key| key_line(&source, &key)) {
+                Some(line) => format!(", line {}", line),
+                None => String::new(),
+            };
+            e.context(format!("error while parsing {:?}{}", config_file, location))
+        })?;
 
         Ok(settings)
     }
@@ -92,12 +269,26 @@ impl Settings {
         let table_keys = [
             "data_dir",
             "modification_count_prompt",
+            "confirmation",
             "avoid_snapshots",
             "server_client_key",
             "server_origin",
             "encryption_secret",
             "server_dir",
             "reports",
+            "colors",
+            "aliases",
+            "contexts",
+            "context",
+            "pager",
+            "default_report",
+            "date_format",
+            "timezone",
+            "reminder_window",
+            "reminder_command",
+            "duplicate_check",
+            "profiles",
+            "log",
         ];
         let table = table_with_keys(config_toml, &table_keys)?;
 
@@ -148,6 +339,10 @@ impl Settings {
             self.modification_count_prompt = Some(v);
         })?;
 
+        get_bool_cfg(table, "confirmation", |v| {
+            self.confirmation = v;
+        })?;
+
         get_bool_cfg(table, "avoid_snapshots", |v| {
             self.avoid_snapshots = v;
         })?;
@@ -178,25 +373,398 @@ impl Settings {
             }
         }
 
+        if let Some(v) = table.get("colors") {
+            self.colors
+                .update_from_toml(v)
+                .map_err(|e| anyhow!("colors{}", e))?;
+        }
+
+        if let Some(v) = table.get("aliases") {
+            let alias_cfgs = v
+                .as_table()
+                .ok_or_else(|| anyhow!(".aliases: not a table"))?;
+            for (name, cfg) in alias_cfgs {
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    bail!("aliases.{}: not a valid alias name", name);
+                }
+                let expansion: Vec<String> = if let Some(s) = cfg.as_str() {
+                    s.split_whitespace().map(|s| s.to_owned()).collect()
+                } else if let Some(a) = cfg.as_array() {
+                    a.iter()
+                        .map(|v| v.as_str().map(|s| s.to_owned()))
+                        .collect::<Option<_>>()
+                        .ok_or_else(|| anyhow!("aliases.{}: not an array of strings", name))?
+                } else {
+                    bail!("aliases.{}: not a string or array", name);
+                };
+                if expansion.is_empty() {
+                    bail!("aliases.{}: empty alias", name);
+                }
+                self.aliases.insert(name.clone(), expansion);
+            }
+        }
+
+        if let Some(v) = table.get("contexts") {
+            let context_cfgs = v
+                .as_table()
+                .ok_or_else(|| anyhow!(".contexts: not a table"))?;
+            for (name, cfg) in context_cfgs {
+                let expr = cfg
+                    .as_str()
+                    .ok_or_else(|| anyhow!("contexts.{}: not a string", name))?;
+                Self::parse_context(expr).map_err(|e| anyhow!("contexts.{}: {}", name, e))?;
+                self.contexts.insert(name.clone(), expr.to_owned());
+            }
+        }
+
+        get_str_cfg(table, "pager", |v| {
+            self.pager = Some(v);
+        })?;
+
+        get_str_cfg(table, "default_report", |v| {
+            self.default_report = v;
+        })?;
+
+        if let Some(v) = table.get("date_format") {
+            let v = v
+                .as_str()
+                .ok_or_else(|| anyhow!(".date_format: not a string"))?;
+            self.dates.format = v.parse().map_err(|e| anyhow!(".date_format: {}", e))?;
+        }
+
+        if let Some(v) = table.get("timezone") {
+            let v = v
+                .as_str()
+                .ok_or_else(|| anyhow!(".timezone: not a string"))?;
+            self.dates.timezone = v.parse().map_err(|e| anyhow!(".timezone: {}", e))?;
+        }
+
+        if let Some(v) = table.get("reminder_window") {
+            let v = v
+                .as_str()
+                .ok_or_else(|| anyhow!(".reminder_window: not a string"))?;
+            crate::argparse::parse_duration(v)
+                .ok_or_else(|| anyhow!(".reminder_window: `{}` is not a valid duration", v))?;
+            self.reminder_window = Some(v.to_owned());
+        }
+
+        get_str_cfg(table, "reminder_command", |v| {
+            self.reminder_command = Some(v);
+        })?;
+
+        if let Some(v) = table.get("duplicate_check") {
+            let v = v
+                .as_str()
+                .ok_or_else(|| anyhow!(".duplicate_check: not a string"))?;
+            self.duplicate_check = v.parse().map_err(|e| anyhow!(".duplicate_check: {}", e))?;
+        }
+
+        if let Some(v) = table.get("profiles") {
+            let profile_cfgs = v
+                .as_table()
+                .ok_or_else(|| anyhow!(".profiles: not a table"))?;
+            for (name, cfg) in profile_cfgs {
+                let profile =
+                    Profile::try_from(cfg).map_err(|e| anyhow!("profiles.{}{}", name, e))?;
+                self.profiles.insert(name.clone(), profile);
+            }
+        }
+
+        if let Some(v) = table.get("log") {
+            let log = table_with_keys(v, &["level", "file"]).map_err(|e| anyhow!("log: {}", e))?;
+            if let Some(level) = log.get("level") {
+                let level = level
+                    .as_str()
+                    .ok_or_else(|| anyhow!("log.level: not a string"))?;
+                self.log_level = Some(level.parse().map_err(|_| {
+                    anyhow!(
+                        "log.level: `{}` is not one of off, error, warn, info, debug, trace",
+                        level
+                    )
+                })?);
+            }
+            if let Some(file) = log.get("file") {
+                let file = file
+                    .as_str()
+                    .ok_or_else(|| anyhow!("log.file: not a string"))?;
+                self.log_file = Some(file.into());
+            }
+        }
+
+        get_str_cfg(table, "context", |v| {
+            self.context = Some(v);
+        })?;
+        if let Some(ref name) = self.context {
+            if !self.contexts.contains_key(name) {
+                bail!(".context: context `{}` is not defined", name);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse a context's filter expression into a single condition
+    fn parse_context(expr: &str) -> Result<Condition> {
+        Condition::parse_str(&format!("({})", expr))
+    }
+
+    /// Get the filter for the active context, if any.  This is intersected with the filter of
+    /// every report.
+    pub(crate) fn context_filter(&self) -> Result<Option<Filter>> {
+        let name = match self.context {
+            Some(ref name) => name,
+            None => return Ok(None),
+        };
+        let expr = self
+            .contexts
+            .get(name)
+            .ok_or_else(|| anyhow!("context `{}` is not defined", name))?;
+        Ok(Some(Filter {
+            conditions: vec![Self::parse_context(expr)?],
+        }))
+    }
+
+    /// Use the named profile, replacing the data directory and sync server settings with any
+    /// given in the profile.
+    pub(crate) fn use_profile(&mut self, name: &str) -> Result<()> {
+        let profile = match self.profiles.get(name) {
+            Some(profile) => profile.clone(),
+            None => {
+                let mut names: Vec<&str> = self.profiles.keys().map(|k| k.as_ref()).collect();
+                names.sort_unstable();
+                match crate::argparse::did_you_mean(name, names.iter().copied()) {
+                    Some(suggestion) => bail!(
+                        "profile `{}` is not defined, did you mean `{}`?",
+                        name,
+                        suggestion
+                    ),
+                    None if names.is_empty() => bail!("profile `{}` is not defined", name),
+                    None => bail!(
+                        "profile `{}` is not defined; defined profiles are {}",
+                        name,
+                        names.join(", ")
+                    ),
+                }
+            }
+        };
+
+        if let Some(data_dir) = profile.data_dir {
+            self.data_dir = data_dir;
+        }
+        if let Some(server_client_key) = profile.server_client_key {
+            self.server_client_key = Some(server_client_key);
+        }
+        if let Some(server_origin) = profile.server_origin {
+            self.server_origin = Some(server_origin);
+        }
+        if let Some(encryption_secret) = profile.encryption_secret {
+            self.encryption_secret = Some(encryption_secret);
+        }
+        if let Some(server_dir) = profile.server_dir {
+            self.server_dir = server_dir;
+        }
+        self.profile = Some(name.to_owned());
+        Ok(())
+    }
+
+    /// Check the settings that are not checked when the configuration file is loaded, but only
+    /// when they are used, such as the format of the sync server settings and the existence of
+    /// the default report.  All problems found are included in the error.
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = vec![];
+
+        for key in Self::SETTABLE_KEYS {
+            if let Some(value) = self.get(key)? {
+                if let Err(e) = Self::check_value(key, &value) {
+                    problems.push(e.to_string());
+                }
+            }
+        }
+
+        if !self.reports.contains_key(&self.default_report) {
+            problems.push(format!(
+                "default_report: report `{}` is not defined",
+                self.default_report
+            ));
+        }
+
+        let mut profile_names: Vec<&String> = self.profiles.keys().collect();
+        profile_names.sort_unstable();
+        for name in profile_names {
+            let profile = &self.profiles[name];
+            let values = [
+                ("server_client_key", &profile.server_client_key),
+                ("server_origin", &profile.server_origin),
+            ];
+            for (key, value) in values.iter() {
+                if let Some(value) = value {
+                    if let Err(e) = Self::check_value(key, value) {
+                        problems.push(format!("profiles.{}.{}", name, e));
+                    }
+                }
+            }
+        }
+
+        if !problems.is_empty() {
+            bail!("{}", problems.join("\n"));
+        }
         Ok(())
     }
 
     /// Set a value in the config file, modifying it in place.  Returns the filename.  The value is
     /// interpreted as the appropriate type for the configuration setting.
-    pub(crate) fn set(&self, key: &str, value: &str) -> Result<PathBuf> {
-        let allowed_keys = [
-            "data_dir",
-            "modification_count_prompt",
-            "server_client_key",
-            "server_origin",
-            "encryption_secret",
-            "server_dir",
-            // reports is not allowed, since it is not a string
-        ];
-        if !allowed_keys.contains(&key) {
-            bail!("No such configuration key {}", key);
+    pub fn set(&self, key: &str, value: &str) -> Result<PathBuf> {
+        self.update(&[(key, Some(value))])
+    }
+
+    /// Set or, for values of None, remove several values in the config file at once, modifying
+    /// it in place.  Nothing is written unless all of the values are valid.  Returns the
+    /// filename.
+    pub fn update(&self, values: &[(&str, Option<&str>)]) -> Result<PathBuf> {
+        for (key, value) in values {
+            Self::check_key(key)?;
+            if let Some(value) = value {
+                Self::check_value(key, value)?;
+            }
         }
 
+        self.edit(|document| {
+            for (key, value) in values {
+                let value = match value {
+                    Some(value) => value,
+                    None => {
+                        document.as_table_mut().remove(key);
+                        continue;
+                    }
+                };
+
+                // set the value as the correct type
+                match *key {
+                    // integers
+                    "modification_count_prompt" => {
+                        let value: i64 = value
+                            .parse()
+                            .map_err(|_| anyhow!("{} must be an integer", key))?;
+                        set_document_value(document, key, toml_edit::value(value));
+                    }
+
+                    // booleans
+                    "confirmation" | "avoid_snapshots" => {
+                        let value: bool = value
+                            .parse()
+                            .map_err(|_| anyhow!("{} must be true or false", key))?;
+                        set_document_value(document, key, toml_edit::value(value));
+                    }
+
+                    // most keys are strings
+                    _ => set_document_value(document, key, toml_edit::value(*value)),
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Check values that have a specific format
+    fn check_value(key: &str, value: &str) -> Result<()> {
+        match key {
+            "server_client_key" if !is_secret_reference(value) => {
+                Uuid::parse_str(&resolve_secret(value)?)
+                    .map_err(|_| anyhow!("{} must be a UUID", key))?;
+            }
+            "server_origin" if !value.starts_with("http://") && !value.starts_with("https://") => {
+                bail!("{} must be an http:// or https:// URL", key);
+            }
+            "date_format" => {
+                value.parse::<DateFormat>()?;
+            }
+            "timezone" => {
+                value.parse::<Timezone>()?;
+            }
+            "duplicate_check" => {
+                value.parse::<DuplicateCheck>()?;
+            }
+            "reminder_window" => {
+                crate::argparse::parse_duration(value)
+                    .ok_or_else(|| anyhow!("{}: `{}` is not a valid duration", key, value))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Get the current value of a configuration setting, as it would be given to
+    /// [`Settings::set`], or None if it is not set.  Default values are included.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Self::check_key(key)?;
+        Ok(match key {
+            "data_dir" => Some(self.data_dir.to_string_lossy().into_owned()),
+            "modification_count_prompt" => self.modification_count_prompt.map(|v| v.to_string()),
+            "confirmation" => Some(self.confirmation.to_string()),
+            "avoid_snapshots" => Some(self.avoid_snapshots.to_string()),
+            "server_client_key" => self.server_client_key.clone(),
+            "server_origin" => self.server_origin.clone(),
+            "encryption_secret" => self.encryption_secret.clone(),
+            "server_dir" => Some(self.server_dir.to_string_lossy().into_owned()),
+            "pager" => self.pager.clone(),
+            "default_report" => Some(self.default_report.clone()),
+            "date_format" => Some(self.dates.format.to_string()),
+            "timezone" => Some(self.dates.timezone.to_string()),
+            "reminder_window" => self.reminder_window.clone(),
+            "reminder_command" => self.reminder_command.clone(),
+            "duplicate_check" => Some(self.duplicate_check.to_string()),
+            _ => unreachable!(),
+        })
+    }
+
+    /// Check that the given key is one of [`Settings::SETTABLE_KEYS`]
+    fn check_key(key: &str) -> Result<()> {
+        if !Self::SETTABLE_KEYS.contains(&key) {
+            bail!(
+                "No such configuration key {}; valid keys are {}",
+                key,
+                Self::SETTABLE_KEYS.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Define a context in the config file, modifying it in place.  Returns the filename.
+    pub(crate) fn define_context(&self, name: &str, expr: &str) -> Result<PathBuf> {
+        if name.is_empty() || name == "none" || name.contains(char::is_whitespace) {
+            bail!("`{}` is not a valid context name", name);
+        }
+        Self::parse_context(expr)?;
+        self.edit(|document| {
+            if document["contexts"].is_none() {
+                document["contexts"] = toml_edit::table();
+            }
+            document["contexts"][name] = toml_edit::value(expr);
+            Ok(())
+        })
+    }
+
+    /// Set the active context in the config file, modifying it in place, or remove the active
+    /// context if `name` is None.  Returns the filename.
+    pub(crate) fn set_context(&self, name: Option<&str>) -> Result<PathBuf> {
+        if let Some(name) = name {
+            if !self.contexts.contains_key(name) {
+                bail!("context `{}` is not defined", name);
+            }
+        }
+        self.edit(|document| {
+            match name {
+                Some(name) => document["context"] = toml_edit::value(name),
+                None => {
+                    document.as_table_mut().remove("context");
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Apply the given function to the config file's document, writing the result back to the
+    /// file.  Returns the filename.
+    fn edit<F: FnOnce(&mut Document) -> Result<()>>(&self, f: F) -> Result<PathBuf> {
         let filename = if let Some(ref f) = self.filename {
             f.clone()
         } else {
@@ -223,17 +791,16 @@ impl Settings {
             Document::new()
         };
 
-        // set the value as the correct type
-        match key {
-            // integers
-            "modification_count_prompt" => {
-                let value: i64 = value.parse()?;
-                document[key] = toml_edit::value(value);
-            }
+        f(&mut document)?;
 
-            // most keys are strings
-            _ => document[key] = toml_edit::value(value),
-        }
+        // check that the result is a valid configuration before writing it
+        let config_toml = document
+            .to_string()
+            .parse::<toml::Value>()
+            .context("Updated configuration is not valid TOML")?;
+        Settings::default()
+            .update_from_toml(&config_toml)
+            .context("Updated configuration is not valid")?;
 
         fs::write(filename.clone(), document.to_string())
             .context("Could not write updated configuration file")?;
@@ -242,19 +809,32 @@ impl Settings {
     }
 }
 
+/// Set a top-level value in the document, keeping the whitespace and any comment around the
+/// existing value.
+fn set_document_value(document: &mut Document, key: &str, item: toml_edit::Item) {
+    document[key] = match (document[key].as_value(), item) {
+        (Some(existing), toml_edit::Item::Value(value)) => {
+            let decor = existing.decor();
+            toml_edit::Item::Value(toml_edit::decorated(value, decor.prefix(), decor.suffix()))
+        }
+        (_, item) => item,
+    };
+}
+
+/// Get the first of the given environment variables that is set to a non-empty value, along
+/// with that value.
+fn first_env_var(names: &[&'static str]) -> Option<(&'static str, OsString)> {
+    names
+        .iter()
+        .filter_map(|name| env::var_os(name).map(|value| (*name, value)))
+        .find(|(_, value)| !value.is_empty())
+}
+
 impl Default for Settings {
     fn default() -> Self {
-        let data_dir;
-        let server_dir;
-
-        if let Some(dir) = dirs_next::data_local_dir() {
-            data_dir = dir.join("taskchampion");
-            server_dir = dir.join("taskchampion-sync-server");
-        } else {
-            // fallback
-            data_dir = PathBuf::from(".");
-            server_dir = PathBuf::from(".");
-        }
+        // fall back to the current directory
+        let data_dir = paths::data_dir().unwrap_or_else(|| PathBuf::from("."));
+        let server_dir = paths::server_dir().unwrap_or_else(|| PathBuf::from("."));
 
         // define the default reports
         let mut reports = HashMap::new();
@@ -289,13 +869,19 @@ impl Default for Settings {
                     },
                 ],
                 filter: Default::default(),
+                limit: None,
             },
         );
 
+        // the most urgent tasks that can be worked on now, as many as fit in the terminal
         reports.insert(
             "next".to_owned(),
             Report {
                 sort: vec![
+                    Sort {
+                        ascending: false,
+                        sort_by: SortBy::Urgency,
+                    },
                     Sort {
                         ascending: true,
                         sort_by: SortBy::Id,
@@ -322,10 +908,159 @@ impl Default for Settings {
                         label: "tags".to_owned(),
                         property: Property::Tags,
                     },
+                    Column {
+                        label: "due".to_owned(),
+                        property: Property::Due,
+                    },
+                    Column {
+                        label: "urgency".to_owned(),
+                        property: Property::Urgency,
+                    },
                 ],
                 filter: Filter {
-                    conditions: vec![Condition::Status(Status::Pending)],
+                    conditions: vec![
+                        Condition::Status(Status::Pending),
+                        Condition::NoTag(Tag::try_from("WAITING").unwrap()),
+                        Condition::NoTag(Tag::try_from("BLOCKED").unwrap()),
+                    ],
                 },
+                limit: Some(ReportLimit::Page),
+            },
+        );
+
+        // reports of tasks that are blocked by, or blocking, other pending tasks
+        for &(name, tag) in &[("blocked", "BLOCKED"), ("blocking", "BLOCKING")] {
+            reports.insert(
+                name.to_owned(),
+                Report {
+                    sort: vec![
+                        Sort {
+                            ascending: true,
+                            sort_by: SortBy::Id,
+                        },
+                        Sort {
+                            ascending: true,
+                            sort_by: SortBy::Uuid,
+                        },
+                    ],
+                    columns: vec![
+                        Column {
+                            label: "id".to_owned(),
+                            property: Property::Id,
+                        },
+                        Column {
+                            label: "description".to_owned(),
+                            property: Property::Description,
+                        },
+                        Column {
+                            label: "active".to_owned(),
+                            property: Property::Active,
+                        },
+                        Column {
+                            label: "tags".to_owned(),
+                            property: Property::Tags,
+                        },
+                    ],
+                    filter: Filter {
+                        conditions: vec![
+                            Condition::Status(Status::Pending),
+                            Condition::HasTag(Tag::try_from(tag).unwrap()),
+                        ],
+                    },
+                    limit: None,
+                },
+            );
+        }
+
+        // tasks that have been started, most recently started first
+        reports.insert(
+            "active".to_owned(),
+            Report {
+                sort: vec![
+                    Sort {
+                        ascending: true,
+                        sort_by: SortBy::Elapsed,
+                    },
+                    Sort {
+                        ascending: true,
+                        sort_by: SortBy::Id,
+                    },
+                ],
+                columns: vec![
+                    Column {
+                        label: "id".to_owned(),
+                        property: Property::Id,
+                    },
+                    Column {
+                        label: "description".to_owned(),
+                        property: Property::Description,
+                    },
+                    Column {
+                        label: "elapsed".to_owned(),
+                        property: Property::Elapsed,
+                    },
+                    Column {
+                        label: "tags".to_owned(),
+                        property: Property::Tags,
+                    },
+                    Column {
+                        label: "due".to_owned(),
+                        property: Property::Due,
+                    },
+                ],
+                filter: Filter {
+                    conditions: vec![
+                        Condition::Status(Status::Pending),
+                        Condition::HasTag(Tag::try_from("ACTIVE").unwrap()),
+                    ],
+                },
+                limit: None,
+            },
+        );
+
+        // tasks that are waiting, those that will become pending soonest first
+        reports.insert(
+            "waiting".to_owned(),
+            Report {
+                sort: vec![
+                    Sort {
+                        ascending: true,
+                        sort_by: SortBy::Wait,
+                    },
+                    Sort {
+                        ascending: true,
+                        sort_by: SortBy::Id,
+                    },
+                ],
+                columns: vec![
+                    Column {
+                        label: "id".to_owned(),
+                        property: Property::Id,
+                    },
+                    Column {
+                        label: "wait".to_owned(),
+                        property: Property::Wait,
+                    },
+                    Column {
+                        label: "description".to_owned(),
+                        property: Property::Description,
+                    },
+                    Column {
+                        label: "tags".to_owned(),
+                        property: Property::Tags,
+                    },
+                    Column {
+                        label: "due".to_owned(),
+                        property: Property::Due,
+                    },
+                ],
+                filter: Filter {
+                    conditions: vec![
+                        Condition::Status(Status::Pending),
+                        Condition::HasTag(Tag::try_from("WAITING").unwrap()),
+                    ],
+                },
+                limit: None,
             },
         );
 
@@ -333,12 +1068,28 @@ impl Default for Settings {
             filename: None,
             data_dir,
             modification_count_prompt: None,
+            confirmation: true,
             avoid_snapshots: false,
             server_client_key: None,
             server_origin: None,
             encryption_secret: None,
             server_dir,
             reports,
+            default_report: "next".to_owned(),
+            colors: Default::default(),
+            dates: Default::default(),
+            aliases: HashMap::new(),
+            contexts: HashMap::new(),
+            context: None,
+            pager: None,
+            reminder_window: None,
+            reminder_command: None,
+            duplicate_check: DuplicateCheck::Off,
+            profiles: HashMap::new(),
+            profile: None,
+            log_level: None,
+            log_file: None,
+            migrations: vec![],
         }
     }
 }
@@ -380,11 +1131,103 @@ mod test {
         assert_eq!(settings.filename, Some(cfg_file));
     }
 
+    #[test]
+    fn test_load_from_file_unknown_key() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(
+            cfg_file.clone(),
+            "data_dir = \"/nowhere\"\n\n# the pager\npagr = \"less\"\n",
+        )
+        .unwrap();
+
+        let err = Settings::load_from_file(cfg_file.clone(), true).unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            format!(
+                "error while parsing {:?}, line 4: unknown table key `pagr`, did you mean `pager`?",
+                cfg_file
+            )
+        );
+    }
+
+    #[test]
+    fn test_load_from_file_unknown_nested_key() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(
+            cfg_file.clone(),
+            "[log]\nlevel = \"info\"\nfiel = \"x.log\"\n",
+        )
+        .unwrap();
+
+        let err = Settings::load_from_file(cfg_file.clone(), true).unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            format!(
+                "error while parsing {:?}, line 3: log: unknown table key `fiel`, did you mean `file`?",
+                cfg_file
+            )
+        );
+    }
+
+    #[test]
+    fn test_reload() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(
+            cfg_file.clone(),
+            "pager = \"less\"\n[profiles.work]\ndata_dir = \"/work\"\n",
+        )
+        .unwrap();
+        let mut settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
+        settings.use_profile("work").unwrap();
+
+        fs::write(
+            cfg_file.clone(),
+            "pager = \"more\"\n[profiles.work]\ndata_dir = \"/work2\"\n",
+        )
+        .unwrap();
+        let settings = settings.reload().unwrap();
+        assert_eq!(settings.pager, Some(s!("more")));
+        assert_eq!(settings.data_dir, PathBuf::from("/work2"));
+        assert_eq!(settings.profile, Some(s!("work")));
+        assert_eq!(settings.filename, Some(cfg_file));
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut settings = Settings::default();
+        settings.validate().unwrap();
+
+        settings.server_client_key = Some(s!("cmd:pass show tasks"));
+        settings.server_origin = Some(s!("https://tasks.example.com"));
+        settings.validate().unwrap();
+
+        settings.server_client_key = Some(s!("not-a-uuid"));
+        settings.default_report = s!("nosuch");
+        settings.profiles.insert(
+            s!("work"),
+            Profile {
+                server_origin: Some(s!("tasks.example.com")),
+                ..Profile::default()
+            },
+        );
+        let err = settings.validate().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "server_client_key must be a UUID\n\
+             default_report: report `nosuch` is not defined\n\
+             profiles.work.server_origin must be an http:// or https:// URL"
+        );
+    }
+
     #[test]
     fn test_update_from_toml_top_level_keys() {
         let val = toml! {
             data_dir = "/data"
             modification_count_prompt = 42
+            confirmation = false
             server_client_key = "sck"
             server_origin = "so"
             encryption_secret = "es"
@@ -395,6 +1238,7 @@ mod test {
 
         assert_eq!(settings.data_dir, PathBuf::from("/data"));
         assert_eq!(settings.modification_count_prompt, Some(42));
+        assert!(!settings.confirmation);
         assert_eq!(settings.server_client_key, Some("sck".to_owned()));
         assert_eq!(settings.server_origin, Some("so".to_owned()));
         assert_eq!(settings.encryption_secret, Some("es".to_owned()));
@@ -415,6 +1259,170 @@ mod test {
         // beyond existence of this report, we can rely on Report's unit tests
     }
 
+    #[test]
+    fn test_update_from_toml_aliases() {
+        let val = toml! {
+            [aliases]
+            in = "add +inbox"
+            rm = ["delete"]
+        };
+        let mut settings = Settings::default();
+        settings.update_from_toml(&val).unwrap();
+        assert_eq!(
+            settings.aliases.get("in"),
+            Some(&vec![s!("add"), s!("+inbox")])
+        );
+        assert_eq!(settings.aliases.get("rm"), Some(&vec![s!("delete")]));
+
+        let val = toml! {
+            [aliases]
+            nothing = ""
+        };
+        let mut settings = Settings::default();
+        let err = settings.update_from_toml(&val).unwrap_err().to_string();
+        assert_eq!(&err, "aliases.nothing: empty alias");
+    }
+
+    #[test]
+    fn test_update_from_toml_contexts() {
+        let val = toml! {
+            context = "work"
+            [contexts]
+            work = "project:acme or +work"
+        };
+        let mut settings = Settings::default();
+        settings.update_from_toml(&val).unwrap();
+        assert_eq!(settings.context, Some(s!("work")));
+        assert_eq!(
+            settings.context_filter().unwrap(),
+            Some(Filter {
+                conditions: vec![Condition::Or(vec![
+                    Condition::Project(Some(s!("acme"))),
+                    Condition::HasTag(tag!("work")),
+                ])]
+            })
+        );
+
+        let val = toml! {
+            context = "home"
+        };
+        let mut settings = Settings::default();
+        let err = settings.update_from_toml(&val).unwrap_err().to_string();
+        assert_eq!(&err, ".context: context `home` is not defined");
+    }
+
+    #[test]
+    fn test_update_from_toml_colors() {
+        let val = toml! {
+            [colors]
+            overdue = "bold red"
+        };
+        let mut settings = Settings::default();
+        settings.update_from_toml(&val).unwrap();
+        assert_ne!(settings.colors, ColorRules::default());
+
+        let val = toml! {
+            [colors]
+            overdue = 13
+        };
+        let mut settings = Settings::default();
+        let err = settings.update_from_toml(&val).unwrap_err().to_string();
+        assert_eq!(&err, "colors.overdue: not a string");
+    }
+
+    #[test]
+    fn test_update_from_toml_dates() {
+        let val = toml! {
+            date_format = "relative"
+            timezone = "utc"
+        };
+        let mut settings = Settings::default();
+        settings.update_from_toml(&val).unwrap();
+        assert_eq!(settings.dates.format, DateFormat::Relative);
+        assert_eq!(settings.dates.timezone, Timezone::Utc);
+
+        let val = toml! {
+            timezone = "somewhere"
+        };
+        let mut settings = Settings::default();
+        let err = settings.update_from_toml(&val).unwrap_err().to_string();
+        assert!(err.starts_with(".timezone: timezone must be"));
+    }
+
+    #[test]
+    fn test_update_from_toml_reminders() {
+        let val = toml! {
+            reminder_window = "30min"
+            reminder_command = "notify-send -u critical"
+        };
+        let mut settings = Settings::default();
+        settings.update_from_toml(&val).unwrap();
+        assert_eq!(settings.reminder_window, Some("30min".to_owned()));
+        assert_eq!(
+            settings.reminder_command,
+            Some("notify-send -u critical".to_owned())
+        );
+
+        let val = toml! {
+            reminder_window = "soon"
+        };
+        let mut settings = Settings::default();
+        let err = settings.update_from_toml(&val).unwrap_err().to_string();
+        assert_eq!(&err, ".reminder_window: `soon` is not a valid duration");
+    }
+
+    #[test]
+    fn test_update_from_toml_duplicate_check() {
+        let val = toml! {
+            duplicate_check = "prompt"
+        };
+        let mut settings = Settings::default();
+        settings.update_from_toml(&val).unwrap();
+        assert_eq!(settings.duplicate_check, DuplicateCheck::Prompt);
+
+        let val = toml! {
+            duplicate_check = "always"
+        };
+        let mut settings = Settings::default();
+        let err = settings.update_from_toml(&val).unwrap_err().to_string();
+        assert_eq!(
+            &err,
+            ".duplicate_check: duplicate check must be `off`, `warn`, or `prompt`, not `always`"
+        );
+    }
+
+    #[test]
+    fn test_update_from_toml_log() {
+        let val = toml! {
+            [log]
+            level = "debug"
+            file = "/tmp/ta.log"
+        };
+        let mut settings = Settings::default();
+        settings.update_from_toml(&val).unwrap();
+        assert_eq!(settings.log_level, Some(LevelFilter::Debug));
+        assert_eq!(settings.log_file, Some(PathBuf::from("/tmp/ta.log")));
+
+        let val = toml! {
+            [log]
+            level = "loud"
+        };
+        let mut settings = Settings::default();
+        let err = settings.update_from_toml(&val).unwrap_err().to_string();
+        assert_eq!(
+            &err,
+            "log.level: `loud` is not one of off, error, warn, info, debug, trace"
+        );
+
+        let val = toml! {
+            [log]
+            lvl = "debug"
+        };
+        let mut settings = Settings::default();
+        let err = settings.update_from_toml(&val).unwrap_err().to_string();
+        assert_eq!(&err, "log: unknown table key `lvl`");
+    }
+
     #[test]
     fn test_set_valid_key() {
         let cfg_dir = TempDir::new().unwrap();
@@ -425,6 +1433,10 @@ mod test {
         assert_eq!(settings.filename, Some(cfg_file.clone()));
         settings.set("data_dir", "/data").unwrap();
         settings.set("modification_count_prompt", "42").unwrap();
+        settings.set("confirmation", "false").unwrap();
+        settings
+            .set("server_client_key", "cmd:pass show taskchampion/client-key")
+            .unwrap();
 
         // load the file again and see the changes
         let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
@@ -432,6 +1444,64 @@ mod test {
         assert_eq!(settings.server_dir, PathBuf::from("/srv"));
         assert_eq!(settings.filename, Some(cfg_file));
         assert_eq!(settings.modification_count_prompt, Some(42));
+        assert!(!settings.confirmation);
+        assert_eq!(
+            settings.server_client_key,
+            Some(s!("cmd:pass show taskchampion/client-key"))
+        );
+    }
+
+    #[test]
+    fn test_update() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(
+            cfg_file.clone(),
+            "server_origin = \"https://example.com\"\nserver_dir = \"/srv\"",
+        )
+        .unwrap();
+
+        let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
+        settings
+            .update(&[("server_origin", None), ("server_dir", Some("/sync"))])
+            .unwrap();
+
+        // an invalid value prevents any changes
+        assert!(settings
+            .update(&[
+                ("server_dir", Some("/other")),
+                ("server_client_key", Some("abc"))
+            ])
+            .is_err());
+
+        let settings = Settings::load_from_file(cfg_file, true).unwrap();
+        assert_eq!(settings.server_origin, None);
+        assert_eq!(settings.server_dir, PathBuf::from("/sync"));
+    }
+
+    #[test]
+    fn test_define_and_set_context() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(cfg_file.clone(), "server_dir = \"/srv\"").unwrap();
+
+        let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
+        settings.define_context("work", "+work").unwrap();
+        assert!(settings.define_context("none", "+work").is_err());
+        assert!(settings.define_context("bad", "due:whenever").is_err());
+
+        let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
+        assert!(settings.set_context(Some("home")).is_err());
+        settings.set_context(Some("work")).unwrap();
+
+        let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
+        assert_eq!(settings.contexts.get("work"), Some(&s!("+work")));
+        assert_eq!(settings.context, Some(s!("work")));
+        assert_eq!(settings.server_dir, PathBuf::from("/srv"));
+        settings.set_context(None).unwrap();
+
+        let settings = Settings::load_from_file(cfg_file, true).unwrap();
+        assert_eq!(settings.context, None);
     }
 
     #[test]
@@ -442,8 +1512,150 @@ mod test {
 
         let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
         assert_eq!(settings.filename, Some(cfg_file.clone()));
+        assert_eq!(
+            settings
+                .set("modification_count_prompt", "a string?")
+                .unwrap_err()
+                .to_string(),
+            "modification_count_prompt must be an integer"
+        );
         assert!(settings
-            .set("modification_count_prompt", "a string?")
-            .is_err());
+            .set("sync.server_url", "https://example.com")
+            .unwrap_err()
+            .to_string()
+            .starts_with("No such configuration key sync.server_url; valid keys are data_dir, "));
+        assert!(settings.set("server_origin", "example.com").is_err());
+        assert!(settings.set("server_client_key", "abc").is_err());
+        assert!(settings.set("avoid_snapshots", "maybe").is_err());
+        assert!(settings.set("date_format", "fancy").is_err());
+        assert!(settings.set("timezone", "Mars/Olympus_Mons").is_err());
+        assert!(settings.set("reminder_window", "soon").is_err());
+        assert!(settings.set("duplicate_check", "sometimes").is_err());
+
+        // the file is unchanged
+        assert_eq!(
+            fs::read_to_string(cfg_file).unwrap(),
+            "server_dir = \"/srv\""
+        );
+    }
+
+    #[test]
+    fn test_get() {
+        let cfg_dir = TempDir::new().unwrap();
+        let cfg_file = cfg_dir.path().join("foo.toml");
+        fs::write(cfg_file.clone(), "server_dir = \"/srv\"").unwrap();
+
+        let settings = Settings::load_from_file(cfg_file.clone(), true).unwrap();
+        settings
+            .set("server_origin", "https://example.com")
+            .unwrap();
+        settings.set("avoid_snapshots", "true").unwrap();
+        settings.set("date_format", "%d.%m.%Y").unwrap();
+
+        let settings = Settings::load_from_file(cfg_file, true).unwrap();
+        assert_eq!(settings.get("server_dir").unwrap(), Some(s!("/srv")));
+        assert_eq!(
+            settings.get("server_origin").unwrap(),
+            Some(s!("https://example.com"))
+        );
+        assert_eq!(settings.get("avoid_snapshots").unwrap(), Some(s!("true")));
+        assert_eq!(settings.get("confirmation").unwrap(), Some(s!("true")));
+        assert_eq!(settings.get("default_report").unwrap(), Some(s!("next")));
+        assert_eq!(settings.get("date_format").unwrap(), Some(s!("%d.%m.%Y")));
+        assert_eq!(settings.get("timezone").unwrap(), Some(s!("local")));
+        assert_eq!(settings.get("encryption_secret").unwrap(), None);
+        assert!(settings.get("nosuch").is_err());
+
+        // every settable key can be fetched
+        for key in Settings::SETTABLE_KEYS {
+            assert!(settings.get(key).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_first_env_var() {
+        // these variables are unique to this test, so they do not interfere with others
+        env::remove_var("TC_TEST_FIRST_ENV_VAR_A");
+        env::set_var("TC_TEST_FIRST_ENV_VAR_B", "");
+        env::set_var("TC_TEST_FIRST_ENV_VAR_C", "/tmp/c");
+        env::set_var("TC_TEST_FIRST_ENV_VAR_D", "/tmp/d");
+
+        assert_eq!(
+            first_env_var(&[
+                "TC_TEST_FIRST_ENV_VAR_A",
+                "TC_TEST_FIRST_ENV_VAR_B",
+                "TC_TEST_FIRST_ENV_VAR_C",
+                "TC_TEST_FIRST_ENV_VAR_D",
+            ]),
+            Some(("TC_TEST_FIRST_ENV_VAR_C", OsString::from("/tmp/c")))
+        );
+        assert_eq!(
+            first_env_var(&["TC_TEST_FIRST_ENV_VAR_A", "TC_TEST_FIRST_ENV_VAR_B"]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_use_profile() {
+        let config = toml! {
+            data_dir = "/tasks"
+            server_dir = "/srv"
+            encryption_secret = "shh"
+
+            [profiles.work]
+            data_dir = "/tasks-work"
+            server_origin = "https://tasks.example.com"
+            server_client_key = "e4d1b4b6-0d3c-4c2e-9fb4-7a6a3b1f3c1d"
+
+            [profiles.home]
+            data_dir = "/tasks-home"
+        };
+        let mut settings = Settings::default();
+        settings.update_from_toml(&config).unwrap();
+        assert_eq!(settings.profiles.len(), 2);
+        assert_eq!(settings.data_dir, PathBuf::from("/tasks"));
+
+        settings.use_profile("work").unwrap();
+        assert_eq!(settings.profile, Some(s!("work")));
+        assert_eq!(settings.data_dir, PathBuf::from("/tasks-work"));
+        assert_eq!(
+            settings.server_origin,
+            Some(s!("https://tasks.example.com"))
+        );
+        // settings not in the profile are unchanged
+        assert_eq!(settings.server_dir, PathBuf::from("/srv"));
+        assert_eq!(settings.encryption_secret, Some(s!("shh")));
+    }
+
+    #[test]
+    fn test_use_profile_not_defined() {
+        let config = toml! {
+            [profiles.work]
+            data_dir = "/tasks-work"
+        };
+        let mut settings = Settings::default();
+        settings.update_from_toml(&config).unwrap();
+
+        let err = settings.use_profile("wrok").unwrap_err().to_string();
+        assert_eq!(err, "profile `wrok` is not defined, did you mean `work`?");
+        let err = settings.use_profile("personal").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "profile `personal` is not defined; defined profiles are work"
+        );
+        assert_eq!(settings.profile, None);
+    }
+
+    #[test]
+    fn test_profiles_invalid() {
+        let config = toml! {
+            [profiles.work]
+            color = "red"
+        };
+        let err = Settings::default()
+            .update_from_toml(&config)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "profiles.work: unknown table key `color`");
     }
 }
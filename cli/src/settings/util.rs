@@ -1,19 +1,62 @@
 use anyhow::{anyhow, bail, Result};
 use toml::value::Table;
 
+/// The prefix of the error message for an unknown key, used to find the key in the error.
+const UNKNOWN_KEY: &str = "unknown table key `";
+
 /// Check that the input is a table and contains no keys not in the given list, returning
-/// the table.
+/// the table.  The error for an unknown key suggests a similar known key, if there is one.
 pub(super) fn table_with_keys<'a>(cfg: &'a toml::Value, keys: &[&str]) -> Result<&'a Table> {
     let table = cfg.as_table().ok_or_else(|| anyhow!("not a table"))?;
 
     for tk in table.keys() {
         if !keys.iter().any(|k| k == tk) {
-            bail!("unknown table key `{}`", tk);
+            match crate::argparse::did_you_mean(tk, keys.iter().copied()) {
+                Some(suggestion) => {
+                    bail!("{}{}`, did you mean `{}`?", UNKNOWN_KEY, tk, suggestion)
+                }
+                None => bail!("{}{}`", UNKNOWN_KEY, tk),
+            }
         }
     }
     Ok(table)
 }
 
+/// Get the key named in an unknown-key error from [`table_with_keys`], if the error is one.
+pub(super) fn unknown_key(err: &anyhow::Error) -> Option<String> {
+    let msg = err.to_string();
+    let start = msg.find(UNKNOWN_KEY)? + UNKNOWN_KEY.len();
+    let len = msg[start..].find('`')?;
+    Some(msg[start..start + len].to_owned())
+}
+
+/// Find the (1-based) number of the line in the TOML source on which the given key is defined,
+/// either as a key or as part of a table header.  This is approximate, and returns the first
+/// such line.
+pub(super) fn key_line(source: &str, key: &str) -> Option<usize> {
+    source
+        .lines()
+        .position(|line| {
+            let line = line.trim_start();
+            if line.starts_with('#') {
+                return false;
+            }
+            let name = if let Some(header) = line.strip_prefix('[') {
+                header.trim_start_matches('[').split(']').next()
+            } else if line.contains('=') {
+                line.split('=').next()
+            } else {
+                None
+            };
+            name.map(|name| {
+                name.split('.')
+                    .any(|part| part.trim().trim_matches(|c| c == '"' || c == '\'') == key)
+            })
+            .unwrap_or(false)
+        })
+        .map(|i| i + 1)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -34,6 +77,32 @@ mod test {
         assert!(table_with_keys(&val, &["foo", "bar"]).is_err());
     }
 
+    #[test]
+    fn test_dissect_table_extra_suggestion() {
+        let val = toml! { lvel = 10 };
+        let err = table_with_keys(&val, &["level", "file"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown table key `lvel`, did you mean `level`?"
+        );
+        assert_eq!(unknown_key(&err), Some("lvel".to_owned()));
+    }
+
+    #[test]
+    fn test_unknown_key_other_error() {
+        assert_eq!(unknown_key(&anyhow!("not a table")), None);
+    }
+
+    #[test]
+    fn test_key_line() {
+        let source = "# pager = \"more\"\npager = \"less\"\n\n[reports.next]\n\"sort\" = []\n";
+        assert_eq!(key_line(source, "pager"), Some(2));
+        assert_eq!(key_line(source, "reports"), Some(4));
+        assert_eq!(key_line(source, "next"), Some(4));
+        assert_eq!(key_line(source, "sort"), Some(5));
+        assert_eq!(key_line(source, "columns"), None);
+    }
+
     #[test]
     fn test_dissect_table_not_a_table() {
         let val = toml::Value::Array(vec![]);
@@ -44,7 +44,11 @@ impl Usage {
             "TaskChampion {}: Personal task-tracking\n\n",
             env!("CARGO_PKG_VERSION")
         )?;
-        writeln!(w, "USAGE:\n  {} [args]\n", command_name)?;
+        writeln!(
+            w,
+            "USAGE:\n  {} [--color=always|never|auto] [--format table|json|tsv|csv|markdown] [args]\n",
+            command_name
+        )?;
         writeln!(w, "TaskChampion subcommands:")?;
         for subcommand in self.subcommands.iter() {
             subcommand.write_help(&mut w, command_name, summary)?;
@@ -79,10 +83,76 @@ impl Usage {
         }
         if !summary {
             writeln!(w, "\nSee `{} help` for more detail", command_name)?;
+        } else {
+            writeln!(
+                w,
+                "\nSee `{} help <subcommand>` for help with a single subcommand",
+                command_name
+            )?;
         }
         Ok(())
     }
 
+    /// Determine whether there is a subcommand with the given name, such as `modify` or `config`
+    pub(crate) fn has_subcommand(&self, name: &str) -> bool {
+        self.subcommands
+            .iter()
+            .any(|s| s.name.split_whitespace().next() == Some(name))
+    }
+
+    /// Write detailed help for the subcommands with the given name, such as `modify`, or
+    /// `config` for all of `config set`, `config get`, and `config list`.  This includes the
+    /// filters and modifications the subcommands accept.  Returns false if there is no such
+    /// subcommand.
+    pub(crate) fn write_subcommand_help<W: Write>(
+        &self,
+        mut w: W,
+        command_name: &str,
+        name: &str,
+    ) -> Result<bool> {
+        let subcommands: Vec<_> = self
+            .subcommands
+            .iter()
+            .filter(|s| s.name.split_whitespace().next() == Some(name))
+            .collect();
+        if subcommands.is_empty() {
+            return Ok(false);
+        }
+
+        writeln!(w, "USAGE:")?;
+        for subcommand in subcommands.iter() {
+            subcommand.write_help(&mut w, command_name, false)?;
+            subcommand.write_examples(&mut w, command_name)?;
+        }
+
+        let takes_filter = subcommands.iter().any(|s| s.syntax.contains("filter"));
+        let takes_modification = subcommands
+            .iter()
+            .any(|s| s.syntax.contains("[modification]"));
+        if takes_filter {
+            writeln!(w, "Filter Expressions:\n")?;
+            for filter in self.filters.iter() {
+                filter.write_help(&mut w, command_name, true)?;
+            }
+            writeln!(w)?;
+        }
+        if takes_modification {
+            writeln!(w, "Modifications:\n")?;
+            for modification in self.modifications.iter() {
+                modification.write_help(&mut w, command_name, true)?;
+            }
+            writeln!(w)?;
+        }
+        if takes_filter || takes_modification {
+            writeln!(
+                w,
+                "See `{} help` for more detail on filters and modifications",
+                command_name
+            )?;
+        }
+        Ok(true)
+    }
+
     #[cfg(feature = "usage-docs")]
     /// Substitute strings matching
     ///
@@ -166,6 +236,9 @@ pub(crate) struct Subcommand {
     /// Multi-line description of the subcommand.  It's OK for this to duplicate summary, as the
     /// two are not displayed together.
     pub(crate) description: &'static str,
+
+    /// Example invocations, without command_name
+    pub(crate) examples: &'static [&'static str],
 }
 
 impl Subcommand {
@@ -184,12 +257,30 @@ impl Subcommand {
         Ok(())
     }
 
+    fn write_examples<W: Write>(&self, mut w: W, command_name: &str) -> Result<()> {
+        if !self.examples.is_empty() {
+            writeln!(w, "\n    Examples:")?;
+            for example in self.examples {
+                writeln!(w, "      {} {}", command_name, example)?;
+            }
+        }
+        writeln!(w)?;
+        Ok(())
+    }
+
     #[cfg(feature = "usage-docs")]
     fn write_markdown<W: FmtWrite>(&self, mut w: W) -> Result<()> {
         writeln!(w, "### `ta {}` - {}", self.name, self.summary)?;
         writeln!(w, "```shell\nta {}\n```", self.syntax)?;
         writeln!(w, "{}", indented(self.description, ""))?;
         writeln!(w)?;
+        if !self.examples.is_empty() {
+            writeln!(w, "For example:\n```shell")?;
+            for example in self.examples {
+                writeln!(w, "ta {}", example)?;
+            }
+            writeln!(w, "```\n")?;
+        }
         Ok(())
     }
 }
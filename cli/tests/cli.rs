@@ -61,3 +61,47 @@ fn invalid_option() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn no_matches() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = TempDir::new().unwrap();
+    let mut cmd = test_cmd(&dir)?;
+
+    cmd.arg("count");
+    cmd.assert()
+        .failure()
+        .stdout("0\n")
+        .stderr("")
+        .code(predicate::eq(1));
+
+    Ok(())
+}
+
+#[test]
+fn data_dir_from_environment() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = TempDir::new().unwrap();
+    let data_dir = TempDir::new().unwrap();
+    let mut cmd = test_cmd(&dir)?;
+
+    cmd.env("TASKCHAMPION_DATA", data_dir.path());
+    cmd.args(&["add", "from the environment"]);
+    cmd.assert().success();
+    assert!(data_dir.path().join("taskchampion.sqlite3").exists());
+    assert!(!dir.path().join("taskchampion.sqlite3").exists());
+
+    Ok(())
+}
+
+#[test]
+fn logging_while_reading_settings() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = TempDir::new().unwrap();
+    let mut cmd = test_cmd(&dir)?;
+
+    cmd.env("RUST_LOG", "debug");
+    cmd.arg("version");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Loading configuration from"));
+
+    Ok(())
+}
@@ -66,7 +66,7 @@ fn make_suite_file(suites: &[&'static str]) {
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
 
-    let suites = &["uuid", "string", "task", "replica"];
+    let suites = &["uuid", "string", "task", "replica", "context"];
     link_libtaskchampion();
     build_bindings_tests(suites);
     make_suite_file(suites);
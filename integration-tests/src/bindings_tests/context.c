@@ -0,0 +1,112 @@
+#include <stdlib.h>
+#include <string.h>
+#include <sys/stat.h>
+#include "unity.h"
+#include "taskchampion.h"
+
+// creating and freeing an empty context does not crash
+static void test_context_creation(void) {
+    TCContext *ctx = tc_context_new();
+    TEST_ASSERT_NOT_NULL(ctx);
+    tc_context_free(ctx);
+}
+
+// a context frees the objects it owns, including a mutable task and its replica
+static void test_context_adopt(void) {
+    TCContext *ctx = tc_context_new();
+
+    TCReplica *rep = tc_context_adopt_replica(ctx, tc_replica_new_in_memory());
+    TEST_ASSERT_NOT_NULL(rep);
+    TEST_ASSERT_NULL(tc_replica_error(rep).ptr);
+
+    TCTask *task = tc_context_adopt_task(ctx,
+            tc_replica_new_task(rep, TC_STATUS_PENDING, tc_string_borrow("my task")));
+    TEST_ASSERT_NOT_NULL(task);
+
+    TCWorkingSet *ws = tc_context_adopt_working_set(ctx, tc_replica_working_set(rep));
+    TEST_ASSERT_NOT_NULL(ws);
+    TEST_ASSERT_EQUAL(1, tc_working_set_len(ws));
+
+    tc_task_to_mut(task, rep);
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_done(task));
+
+    const TCString *desc = tc_context_adopt_string(ctx, tc_task_get_description(task));
+    TEST_ASSERT_NOT_NULL(desc);
+    TEST_ASSERT_EQUAL_STRING("my task", tc_string_content(desc));
+
+    mkdir("test-context-server", 0755); // ignore error, if dir already exists
+    TCServer *server = tc_context_adopt_server(ctx,
+            tc_server_new_local(tc_string_borrow("test-context-server"), NULL));
+    TEST_ASSERT_NOT_NULL(server);
+
+    tc_context_free(ctx);
+}
+
+// a context frees the lists it owns
+static void test_context_adopt_lists(void) {
+    TCContext *ctx = tc_context_new();
+
+    TCReplica *rep = tc_context_adopt_replica(ctx, tc_replica_new_in_memory());
+    TCTask *task = tc_context_adopt_task(ctx,
+            tc_replica_new_task(rep, TC_STATUS_PENDING, tc_string_borrow("my task")));
+    TEST_ASSERT_NOT_NULL(task);
+
+    tc_task_to_mut(task, rep);
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_add_tag(task, tc_string_borrow("next")));
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_set_uda(task,
+                tc_string_borrow("ns"), tc_string_borrow("u"), tc_string_borrow("v")));
+    TCAnnotation ann;
+    ann.entry = 1644623411;
+    ann.description = tc_string_borrow("ann");
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_add_annotation(task, &ann));
+    tc_task_to_immut(task);
+
+    TCTaskList *tasks = tc_context_adopt_task_list(ctx, tc_replica_all_tasks(rep));
+    TEST_ASSERT_EQUAL(1, tasks->len);
+    TCTask *taken = tc_task_list_take(tasks, 0);
+    TEST_ASSERT_NOT_NULL(taken);
+    tc_task_free(taken);
+
+    const TCUuidList *uuids = tc_context_adopt_uuid_list(ctx, tc_replica_all_task_uuids(rep));
+    TEST_ASSERT_EQUAL(1, uuids->len);
+
+    const TCStringList *tags = tc_context_adopt_string_list(ctx, tc_task_get_tags(task));
+    TEST_ASSERT_GREATER_THAN(0, tags->len);
+
+    const TCKVList *taskmap = tc_context_adopt_kv_list(ctx, tc_task_get_taskmap(task));
+    TEST_ASSERT_GREATER_THAN(0, taskmap->len);
+
+    const TCAnnotationList *anns = tc_context_adopt_annotation_list(ctx,
+            tc_task_get_annotations(task));
+    TEST_ASSERT_EQUAL(1, anns->len);
+
+    const TCUdaList *udas = tc_context_adopt_uda_list(ctx, tc_task_get_udas(task));
+    TEST_ASSERT_EQUAL(1, udas->len);
+
+    TCLazyTaskList *lazy = tc_context_adopt_lazy_task_list(ctx, tc_replica_all_tasks_lazy(rep));
+    TEST_ASSERT_NOT_NULL(lazy);
+    TEST_ASSERT_EQUAL(1, tc_lazy_task_list_len(lazy));
+
+    tc_context_free(ctx);
+}
+
+// adopting NULL does nothing
+static void test_context_adopt_null(void) {
+    TCContext *ctx = tc_context_new();
+
+    TEST_ASSERT_NULL(tc_context_adopt_task(ctx, NULL));
+    TCString nullstr = { 0 };
+    TEST_ASSERT_NULL(tc_context_adopt_string(ctx, nullstr));
+
+    tc_context_free(ctx);
+}
+
+int context_tests(void) {
+    UNITY_BEGIN();
+    // each test case above should be named here, in order.
+    RUN_TEST(test_context_creation);
+    RUN_TEST(test_context_adopt);
+    RUN_TEST(test_context_adopt_lists);
+    RUN_TEST(test_context_adopt_null);
+    return UNITY_END();
+}
@@ -20,6 +20,36 @@ static void test_replica_creation_disk(void) {
     tc_replica_free(rep);
 }
 
+// a cloned handle to an on-disk replica sees changes made through the original
+static void test_replica_clone_handle(void) {
+    TCString err;
+    TCReplica *rep1 = tc_replica_new_on_disk(tc_string_borrow("test-db-clone"), NULL);
+    TEST_ASSERT_NOT_NULL(rep1);
+
+    TCReplica *rep2 = tc_replica_clone_handle(rep1, &err);
+    TEST_ASSERT_NOT_NULL(rep2);
+    TEST_ASSERT_NULL(err.ptr);
+
+    TCTask *task = tc_replica_new_task(rep1, TC_STATUS_PENDING, tc_string_borrow("my task"));
+    TEST_ASSERT_NOT_NULL(task);
+    TCUuid uuid = tc_task_get_uuid(task);
+    tc_task_free(task);
+
+    task = tc_replica_get_task(rep2, uuid);
+    TEST_ASSERT_NOT_NULL(task);
+    tc_task_free(task);
+
+    tc_replica_free(rep1);
+    tc_replica_free(rep2);
+
+    // in-memory replicas cannot be cloned
+    TCReplica *rep = tc_replica_new_in_memory();
+    TEST_ASSERT_NULL(tc_replica_clone_handle(rep, &err));
+    TEST_ASSERT_NOT_NULL(err.ptr);
+    tc_string_free(&err);
+    tc_replica_free(rep);
+}
+
 // undo on an empty in-memory TCReplica does nothing
 static void test_replica_undo_empty(void) {
     TCReplica *rep = tc_replica_new_in_memory();
@@ -124,6 +154,29 @@ static void test_replica_undo_empty_null_undone_out(void) {
     tc_replica_free(rep);
 }
 
+// undo reverts the operations since the last undo point, reporting how many were reverted
+static void test_replica_undo_task_creation(void) {
+    TCReplica *rep = tc_replica_new_in_memory();
+    TEST_ASSERT_NULL(tc_replica_error(rep).ptr);
+
+    TCTask *task = tc_replica_new_task(rep, TC_STATUS_PENDING, tc_string_borrow("my task"));
+    TEST_ASSERT_NOT_NULL(task);
+    TCUuid uuid = tc_task_get_uuid(task);
+    tc_task_free(task);
+
+    // the local operations include an undo point, which is not counted as reverted
+    int64_t num_ops = tc_replica_num_local_operations(rep);
+    TEST_ASSERT_GREATER_THAN(1, num_ops);
+
+    int undone;
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_replica_undo(rep, &undone));
+    TEST_ASSERT_EQUAL(num_ops - 1, undone);
+    TEST_ASSERT_EQUAL(0, tc_replica_num_local_operations(rep));
+    TEST_ASSERT_NULL(tc_replica_get_task(rep, uuid));
+
+    tc_replica_free(rep);
+}
+
 // creating a task succeeds and the resulting task looks good
 static void test_replica_task_creation(void) {
     TCReplica *rep = tc_replica_new_in_memory();
@@ -156,7 +209,7 @@ static void test_replica_task_creation(void) {
     tc_replica_free(rep);
 }
 
-// When tc_replica_undo is passed NULL for undone_out, it still succeeds
+// syncing with a local server succeeds, and creating a local server in a missing directory fails
 static void test_replica_sync_local(void) {
     TCReplica *rep = tc_replica_new_in_memory();
     TEST_ASSERT_NULL(tc_replica_error(rep).ptr);
@@ -182,7 +235,84 @@ static void test_replica_sync_local(void) {
     tc_string_free(&err);
 }
 
-// When tc_replica_undo is passed NULL for undone_out, it still succeeds
+// with automatic rebuilds disabled, syncing does not remove completed tasks from the working set
+static void test_replica_sync_no_auto_rebuild(void) {
+    TCReplica *rep = tc_replica_new_in_memory();
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_replica_set_auto_rebuild_working_set(rep, false));
+
+    TCTask *task = tc_replica_new_task(rep, TC_STATUS_PENDING, tc_string_borrow("my task"));
+    TEST_ASSERT_NOT_NULL(task);
+    TCUuid uuid = tc_task_get_uuid(task);
+    tc_task_to_mut(task, rep);
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_done(task));
+    tc_task_free(task);
+
+    mkdir("test-sync-server-no-rebuild", 0755); // ignore error, if dir already exists
+    TCServer *server = tc_server_new_local(tc_string_borrow("test-sync-server-no-rebuild"), NULL);
+    TEST_ASSERT_NOT_NULL(server);
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_replica_sync(rep, server, false));
+    tc_server_free(server);
+
+    TCWorkingSet *ws = tc_replica_working_set(rep);
+    TEST_ASSERT_EQUAL(1, tc_working_set_by_uuid(ws, uuid));
+    tc_working_set_free(ws);
+
+    // an explicit rebuild removes it
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_replica_rebuild_working_set(rep, false));
+    ws = tc_replica_working_set(rep);
+    TEST_ASSERT_EQUAL(0, tc_working_set_by_uuid(ws, uuid));
+    tc_working_set_free(ws);
+
+    tc_replica_free(rep);
+}
+
+typedef struct {
+    int calls;
+    bool pushed;
+    bool cancel;
+} sync_progress_t;
+
+static bool sync_progress(void *context, TCSyncPhase phase, size_t count) {
+    sync_progress_t *p = context;
+    p->calls++;
+    if (phase == TC_SYNC_PHASE_PUSHING) {
+        p->pushed = true;
+        TEST_ASSERT_GREATER_THAN(0, count);
+        return !p->cancel;
+    }
+    return true;
+}
+
+// syncing with progress reports the phases, and can be cancelled
+static void test_replica_sync_with_progress(void) {
+    TCReplica *rep = tc_replica_new_in_memory();
+    TEST_ASSERT_NOT_NULL(tc_replica_new_task(rep, TC_STATUS_PENDING, tc_string_borrow("my task")));
+
+    mkdir("test-sync-server-progress", 0755); // ignore error, if dir already exists
+    TCServer *server = tc_server_new_local(tc_string_borrow("test-sync-server-progress"), NULL);
+    TEST_ASSERT_NOT_NULL(server);
+
+    // cancelling leaves the local operations un-synchronized
+    int64_t num_ops = tc_replica_num_local_operations(rep);
+    sync_progress_t progress = { 0, false, true };
+    TEST_ASSERT_EQUAL(TC_RESULT_ERROR,
+            tc_replica_sync_with_progress(rep, server, false, sync_progress, &progress));
+    TEST_ASSERT_EQUAL(TC_ERROR_KIND_CANCELLED, tc_replica_error_kind(rep));
+    TEST_ASSERT_TRUE(progress.pushed);
+    TEST_ASSERT_EQUAL(num_ops, tc_replica_num_local_operations(rep));
+
+    progress = (sync_progress_t){ 0, false, false };
+    TEST_ASSERT_EQUAL(TC_RESULT_OK,
+            tc_replica_sync_with_progress(rep, server, false, sync_progress, &progress));
+    TEST_ASSERT_TRUE(progress.pushed);
+    TEST_ASSERT_GREATER_THAN(1, progress.calls);
+    TEST_ASSERT_EQUAL(0, tc_replica_num_local_operations(rep));
+
+    tc_server_free(server);
+    tc_replica_free(rep);
+}
+
+// a remote server can be created, even with a non-utf-8 encryption secret
 static void test_replica_remote_server(void) {
     TCString err;
     TCServer *server = tc_server_new_remote(
@@ -198,6 +328,30 @@ static void test_replica_remote_server(void) {
     tc_server_free(server);
 }
 
+// syncing with an unreachable remote server fails with a connection error
+static void test_replica_sync_unreachable(void) {
+    TCReplica *rep = tc_replica_new_in_memory();
+    TEST_ASSERT_NULL(tc_replica_error(rep).ptr);
+
+    TCString err;
+    TCServer *server = tc_server_new_remote(
+        tc_string_borrow("http://127.0.0.1:1"),
+        tc_uuid_new_v4(),
+        tc_string_borrow("sekrit"),
+        &err);
+    TEST_ASSERT_NOT_NULL(server);
+    TEST_ASSERT_NULL(err.ptr);
+
+    TEST_ASSERT_EQUAL(TC_RESULT_ERROR, tc_replica_sync(rep, server, false));
+    TEST_ASSERT_EQUAL(TC_ERROR_KIND_SYNC_CONNECTION, tc_replica_error_kind(rep));
+    err = tc_replica_error(rep);
+    TEST_ASSERT_NOT_NULL(err.ptr);
+    tc_string_free(&err);
+
+    tc_server_free(server);
+    tc_replica_free(rep);
+}
+
 // a replica with tasks in it returns an appropriate list of tasks and list of uuids
 static void test_replica_all_tasks(void) {
     TCReplica *rep = tc_replica_new_in_memory();
@@ -211,9 +365,10 @@ static void test_replica_all_tasks(void) {
     TCUuid uuid1 = tc_task_get_uuid(task1);
     tc_task_free(task1);
 
+    // a completed task is included, too
     TCTask *task2 = tc_replica_new_task(
             rep,
-            TC_STATUS_PENDING,
+            TC_STATUS_COMPLETED,
             tc_string_borrow("task2"));
     TEST_ASSERT_NOT_NULL(task2);
     TCUuid uuid2 = tc_task_get_uuid(task2);
@@ -224,7 +379,7 @@ static void test_replica_all_tasks(void) {
         TEST_ASSERT_NOT_NULL(tasks.items);
         TEST_ASSERT_EQUAL(2, tasks.len);
 
-        bool seen1, seen2 = false;
+        bool seen1 = false, seen2 = false;
         for (size_t i = 0; i < tasks.len; i++) {
             TCTask *task = tasks.items[i];
             TCString descr = tc_task_get_description(task);
@@ -247,7 +402,7 @@ static void test_replica_all_tasks(void) {
         TEST_ASSERT_NOT_NULL(uuids.items);
         TEST_ASSERT_EQUAL(2, uuids.len);
 
-        bool seen1, seen2 = false;
+        bool seen1 = false, seen2 = false;
         for (size_t i = 0; i < uuids.len; i++) {
             TCUuid uuid = uuids.items[i];
             if (0 == memcmp(&uuid1, &uuid, sizeof(TCUuid))) {
@@ -266,6 +421,81 @@ static void test_replica_all_tasks(void) {
     tc_replica_free(rep);
 }
 
+// tasks in a lazy list are read when they are accessed
+static void test_replica_all_tasks_lazy(void) {
+    TCReplica *rep = tc_replica_new_in_memory();
+    TEST_ASSERT_NULL(tc_replica_error(rep).ptr);
+
+    TCTask *task1 = tc_replica_new_task(
+            rep,
+            TC_STATUS_PENDING,
+            tc_string_borrow("task1"));
+    TEST_ASSERT_NOT_NULL(task1);
+    TCUuid uuid1 = tc_task_get_uuid(task1);
+    tc_task_free(task1);
+
+    TCLazyTaskList *tasks = tc_replica_all_tasks_lazy(rep);
+    TEST_ASSERT_NOT_NULL(tasks);
+    TEST_ASSERT_EQUAL(1, tc_lazy_task_list_len(tasks));
+
+    TCUuid uuid;
+    TEST_ASSERT_TRUE(tc_lazy_task_list_uuid(tasks, 0, &uuid));
+    TEST_ASSERT_EQUAL_MEMORY(uuid1.bytes, uuid.bytes, sizeof(uuid.bytes));
+    TEST_ASSERT_FALSE(tc_lazy_task_list_uuid(tasks, 1, &uuid));
+
+    // the task is read from the replica when accessed, so reflects later changes
+    task1 = tc_replica_get_task(rep, uuid1);
+    tc_task_to_mut(task1, rep);
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_set_description(task1, tc_string_borrow("updated")));
+    tc_task_to_immut(task1);
+    tc_task_free(task1);
+
+    TCTask *task = tc_lazy_task_list_get(tasks, 0);
+    TEST_ASSERT_NOT_NULL(task);
+    TCString descr = tc_task_get_description(task);
+    TEST_ASSERT_EQUAL_STRING("updated", tc_string_content(&descr));
+    tc_string_free(&descr);
+    tc_task_free(task);
+
+    TEST_ASSERT_NULL(tc_lazy_task_list_get(tasks, 1)); // out of bounds
+    TEST_ASSERT_NULL(tc_replica_error(rep).ptr);
+
+    tc_lazy_task_list_free(tasks);
+    tc_replica_free(rep);
+}
+
+// tc_replica_all_tags lists the distinct user tags of all tasks
+static void test_replica_all_tags(void) {
+    TCReplica *rep = tc_replica_new_in_memory();
+    TEST_ASSERT_NULL(tc_replica_error(rep).ptr);
+
+    TCTask *task1 = tc_replica_new_task(rep, TC_STATUS_PENDING, tc_string_borrow("task1"));
+    TEST_ASSERT_NOT_NULL(task1);
+    tc_task_to_mut(task1, rep);
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_add_tag(task1, tc_string_borrow("work")));
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_add_tag(task1, tc_string_borrow("next")));
+    tc_task_free(task1);
+
+    TCTask *task2 = tc_replica_new_task(rep, TC_STATUS_COMPLETED, tc_string_borrow("task2"));
+    TEST_ASSERT_NOT_NULL(task2);
+    tc_task_to_mut(task2, rep);
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_add_tag(task2, tc_string_borrow("work")));
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_add_tag(task2, tc_string_borrow("home")));
+    tc_task_free(task2);
+
+    TCStringList tags = tc_replica_all_tags(rep);
+    TEST_ASSERT_NOT_NULL(tags.items);
+    TEST_ASSERT_EQUAL(3, tags.len);
+    TEST_ASSERT_EQUAL_STRING("home", tc_string_content(&tags.items[0]));
+    TEST_ASSERT_EQUAL_STRING("next", tc_string_content(&tags.items[1]));
+    TEST_ASSERT_EQUAL_STRING("work", tc_string_content(&tags.items[2]));
+
+    tc_string_list_free(&tags);
+    TEST_ASSERT_NULL(tags.items);
+
+    tc_replica_free(rep);
+}
+
 // importing a task succeeds and the resulting task looks good
 static void test_replica_task_import(void) {
     TCReplica *rep = tc_replica_new_in_memory();
@@ -297,6 +527,49 @@ static void test_replica_task_import(void) {
     tc_replica_free(rep);
 }
 
+// creating a task with properties sets them all
+static void test_replica_new_task_full(void) {
+    TCReplica *rep = tc_replica_new_in_memory();
+    TEST_ASSERT_NULL(tc_replica_error(rep).ptr);
+
+    TCKV kvs[] = {
+        { tc_string_borrow("project"), tc_string_borrow("home") },
+        { tc_string_borrow("tag_next"), tc_string_borrow("") },
+        { tc_string_borrow("due"), tc_string_borrow("1600000000") },
+    };
+    TCTask *task = tc_replica_new_task_full(
+            rep,
+            TC_STATUS_PENDING,
+            tc_string_borrow("my task"),
+            kvs, 3);
+    TEST_ASSERT_NOT_NULL(task);
+
+    TEST_ASSERT_EQUAL(TC_STATUS_PENDING, tc_task_get_status(task));
+    TCString desc = tc_task_get_description(task);
+    TEST_ASSERT_EQUAL_STRING("my task", tc_string_content(&desc));
+    tc_string_free(&desc);
+    TEST_ASSERT_EQUAL(1600000000, tc_task_get_due(task));
+    TEST_ASSERT_TRUE(tc_task_get_entry(task) != 0);
+    TEST_ASSERT_TRUE(tc_task_has_tag(task, tc_string_borrow("next")));
+
+    TCKVList taskmap = tc_task_get_taskmap(task);
+    const TCString *project = tc_kv_list_get(&taskmap, tc_string_borrow("project"));
+    TEST_ASSERT_NOT_NULL(project);
+    TEST_ASSERT_EQUAL_STRING("home", tc_string_content(project));
+    tc_kv_list_free(&taskmap);
+
+    tc_task_free(task);
+
+    // no properties at all is fine, too
+    task = tc_replica_new_task_full(rep, TC_STATUS_COMPLETED, tc_string_borrow("done"), NULL, 0);
+    TEST_ASSERT_NOT_NULL(task);
+    TEST_ASSERT_EQUAL(TC_STATUS_COMPLETED, tc_task_get_status(task));
+    TEST_ASSERT_TRUE(tc_task_get_end(task) != 0);
+    tc_task_free(task);
+
+    tc_replica_free(rep);
+}
+
 // importing a task succeeds and the resulting task looks good
 static void test_replica_get_task_not_found(void) {
     TCReplica *rep = tc_replica_new_in_memory();
@@ -311,20 +584,49 @@ static void test_replica_get_task_not_found(void) {
     tc_replica_free(rep);
 }
 
+// a failed operation reports the kind of error, which is not cleared by tc_replica_error
+static void test_replica_error_kind(void) {
+    TCReplica *rep = tc_replica_new_in_memory();
+    TEST_ASSERT_EQUAL(TC_ERROR_KIND_NONE, tc_replica_error_kind(rep));
+
+    TCTask *task = tc_replica_new_task(rep, TC_STATUS_PENDING, tc_string_borrow("\xff\xfe"));
+    TEST_ASSERT_NULL(task);
+    TEST_ASSERT_EQUAL(TC_ERROR_KIND_INVALID_ARGUMENT, tc_replica_error_kind(rep));
+    TCString err = tc_replica_error(rep);
+    TEST_ASSERT_NOT_NULL(err.ptr);
+    tc_string_free(&err);
+    TEST_ASSERT_EQUAL(TC_ERROR_KIND_INVALID_ARGUMENT, tc_replica_error_kind(rep));
+
+    // a subsequent successful operation clears the error kind
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_replica_add_undo_point(rep, true));
+    TEST_ASSERT_EQUAL(TC_ERROR_KIND_NONE, tc_replica_error_kind(rep));
+
+    tc_replica_free(rep);
+}
+
 int replica_tests(void) {
     UNITY_BEGIN();
     // each test case above should be named here, in order.
     RUN_TEST(test_replica_creation);
     RUN_TEST(test_replica_creation_disk);
+    RUN_TEST(test_replica_clone_handle);
     RUN_TEST(test_replica_undo_empty);
     RUN_TEST(test_replica_add_undo_point);
     RUN_TEST(test_replica_working_set);
     RUN_TEST(test_replica_undo_empty_null_undone_out);
+    RUN_TEST(test_replica_undo_task_creation);
     RUN_TEST(test_replica_task_creation);
     RUN_TEST(test_replica_sync_local);
+    RUN_TEST(test_replica_sync_no_auto_rebuild);
     RUN_TEST(test_replica_remote_server);
+    RUN_TEST(test_replica_sync_unreachable);
+    RUN_TEST(test_replica_sync_with_progress);
     RUN_TEST(test_replica_all_tasks);
+    RUN_TEST(test_replica_all_tasks_lazy);
+    RUN_TEST(test_replica_all_tags);
     RUN_TEST(test_replica_task_import);
+    RUN_TEST(test_replica_new_task_full);
     RUN_TEST(test_replica_get_task_not_found);
+    RUN_TEST(test_replica_error_kind);
     return UNITY_END();
 }
@@ -111,6 +111,29 @@ static void test_string_clone_with_len_invalid_utf8(void) {
     TEST_ASSERT_NULL(s.ptr);
 }
 
+// tc_string_is_valid_utf8 distinguishes valid and invalid strings, and
+// tc_string_content_lossy replaces invalid sequences.
+static void test_string_content_lossy(void) {
+    TCString s = tc_string_clone_with_len("ab\xf0\x28\x8c\x28", 6);
+    TEST_ASSERT_NOT_NULL(s.ptr);
+    TEST_ASSERT_FALSE(tc_string_is_valid_utf8(&s));
+
+    // U+FFFD is \xef\xbf\xbd in UTF-8
+    TEST_ASSERT_EQUAL_STRING("ab\xef\xbf\xbd(\xef\xbf\xbd(", tc_string_content_lossy(&s));
+    TEST_ASSERT_TRUE(tc_string_is_valid_utf8(&s));
+    tc_string_free(&s);
+
+    s = tc_string_borrow("abcdef");
+    TEST_ASSERT_TRUE(tc_string_is_valid_utf8(&s));
+    TEST_ASSERT_EQUAL_STRING("abcdef", tc_string_content_lossy(&s));
+    tc_string_free(&s);
+
+    s = tc_string_clone_with_len("ab\0de", 5);
+    TEST_ASSERT_TRUE(tc_string_is_valid_utf8(&s));
+    TEST_ASSERT_NULL(tc_string_content_lossy(&s));
+    tc_string_free(&s);
+}
+
 int string_tests(void) {
     UNITY_BEGIN();
     // each test case above should be named here, in order.
@@ -121,5 +144,6 @@ int string_tests(void) {
     RUN_TEST(test_string_cloned_strings_echo);
     RUN_TEST(test_string_content_null_for_embedded_nuls);
     RUN_TEST(test_string_clone_with_len_invalid_utf8);
+    RUN_TEST(test_string_content_lossy);
     return UNITY_END();
 }
@@ -138,6 +138,46 @@ static void test_task_get_set_entry(void) {
     tc_replica_free(rep);
 }
 
+// updating due, scheduled, and end on a task works
+static void test_task_get_set_due_scheduled_end(void) {
+    TCReplica *rep = tc_replica_new_in_memory();
+    TEST_ASSERT_NULL(tc_replica_error(rep).ptr);
+
+    TCTask *task = tc_replica_new_task(
+            rep,
+            TC_STATUS_PENDING,
+            tc_string_borrow("my task"));
+    TEST_ASSERT_NOT_NULL(task);
+
+    TEST_ASSERT_EQUAL(0, tc_task_get_due(task));
+    TEST_ASSERT_EQUAL(0, tc_task_get_scheduled(task));
+    TEST_ASSERT_EQUAL(0, tc_task_get_end(task));
+
+    tc_task_to_mut(task, rep);
+
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_set_due(task, 1643679997));
+    TEST_ASSERT_EQUAL(1643679997, tc_task_get_due(task));
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_set_scheduled(task, 1643679000));
+    TEST_ASSERT_EQUAL(1643679000, tc_task_get_scheduled(task));
+
+    // completing the task sets end, which can then be overridden
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_done(task));
+    TEST_ASSERT_NOT_EQUAL(0, tc_task_get_end(task));
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_set_end(task, 1643680000));
+    TEST_ASSERT_EQUAL(1643680000, tc_task_get_end(task));
+
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_set_due(task, 0));
+    TEST_ASSERT_EQUAL(0, tc_task_get_due(task));
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_set_scheduled(task, 0));
+    TEST_ASSERT_EQUAL(0, tc_task_get_scheduled(task));
+    TEST_ASSERT_EQUAL(TC_RESULT_OK, tc_task_set_end(task, 0));
+    TEST_ASSERT_EQUAL(0, tc_task_get_end(task));
+
+    tc_task_free(task);
+
+    tc_replica_free(rep);
+}
+
 // updating wait on a task works
 static void test_task_get_set_wait_and_is_waiting(void) {
     TCReplica *rep = tc_replica_new_in_memory();
@@ -592,6 +632,26 @@ static void test_task_taskmap(void) {
     tckvlist_assert_key(&taskmap, "tag_next", "");
     tckvlist_assert_key(&taskmap, "status", "pending");
     tckvlist_assert_key(&taskmap, "description", "my task");
+
+    const TCString *status = tc_kv_list_get(&taskmap, tc_string_borrow("status"));
+    TEST_ASSERT_NOT_NULL(status);
+    TEST_ASSERT_EQUAL_STRING("pending", tc_string_content(status));
+    TEST_ASSERT_NULL(tc_kv_list_get(&taskmap, tc_string_borrow("no-such-key")));
+
+    TCStringList keys = tc_kv_list_keys(&taskmap);
+    TEST_ASSERT_EQUAL(taskmap.len, keys.len);
+    bool found_tag = false;
+    for (size_t i = 0; i < keys.len; i++) {
+        TEST_ASSERT_EQUAL_STRING(
+                tc_string_content(&taskmap.items[i].key),
+                tc_string_content(&keys.items[i]));
+        if (0 == strcmp("tag_next", tc_string_content(&keys.items[i]))) {
+            found_tag = true;
+        }
+    }
+    TEST_ASSERT_TRUE(found_tag);
+    tc_string_list_free(&keys);
+
     tc_kv_list_free(&taskmap);
 
     tc_task_free(task);
@@ -655,6 +715,7 @@ int task_tests(void) {
     RUN_TEST(test_task_get_set_entry);
     RUN_TEST(test_task_get_set_modified);
     RUN_TEST(test_task_get_set_wait_and_is_waiting);
+    RUN_TEST(test_task_get_set_due_scheduled_end);
     RUN_TEST(test_task_start_stop_is_active);
     RUN_TEST(test_task_done_and_delete);
     RUN_TEST(test_task_add_remove_has_tag);
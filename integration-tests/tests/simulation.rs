@@ -0,0 +1,34 @@
+use actix_web::{App, HttpServer};
+use taskchampion::{simulation, ServerConfig, Uuid};
+use taskchampion_sync_server::{storage::InMemoryStorage, Server};
+
+#[actix_rt::test]
+async fn simulation() -> anyhow::Result<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .filter_level(log::LevelFilter::Info)
+        .try_init();
+
+    let server = Server::new(Default::default(), Box::new(InMemoryStorage::new()));
+    let httpserver =
+        HttpServer::new(move || App::new().configure(|sc| server.config(sc))).bind("0.0.0.0:0")?;
+
+    // bind was to :0, so the kernel will have selected an unused port
+    let port = httpserver.addrs()[0].port();
+
+    httpserver.run();
+
+    // simulate several replicas syncing through the sync server, each simulation with its own
+    // client key so that they do not interfere
+    for seed in 0..3 {
+        let server = ServerConfig::Remote {
+            origin: format!("http://127.0.0.1:{}", port),
+            client_key: Uuid::new_v4(),
+            encryption_secret: b"abc123".to_vec(),
+        }
+        .into_server()?;
+        simulation::simulate(server, 3, 100, seed)?;
+    }
+
+    Ok(())
+}
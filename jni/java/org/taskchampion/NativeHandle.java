@@ -0,0 +1,39 @@
+package org.taskchampion;
+
+/**
+ * A Java object that owns a value in the native library, identified by a handle.  The value is
+ * freed when the object is closed, after which any other use of the object throws
+ * {@link IllegalStateException}.
+ *
+ * <p>These objects are not thread-safe.
+ */
+abstract class NativeHandle implements AutoCloseable {
+    static {
+        System.loadLibrary("taskchampion_jni");
+    }
+
+    private long handle;
+
+    NativeHandle(long handle) {
+        this.handle = handle;
+    }
+
+    /** Get the handle, throwing if this object has been closed. */
+    long handle() {
+        if (handle == 0) {
+            throw new IllegalStateException(getClass().getSimpleName() + " has been closed");
+        }
+        return handle;
+    }
+
+    /** Free the native value with the given handle. */
+    abstract void free(long handle);
+
+    @Override
+    public void close() {
+        if (handle != 0) {
+            free(handle);
+            handle = 0;
+        }
+    }
+}
@@ -0,0 +1,132 @@
+package org.taskchampion;
+
+/**
+ * A replica of the task database.  See the Rust documentation for {@code taskchampion::Replica}.
+ *
+ * <p>Close the replica when it is no longer needed.  A replica is not thread-safe, and neither
+ * are the tasks and working sets read from it.
+ */
+public final class Replica extends NativeHandle {
+    private Replica(long handle) {
+        super(handle);
+    }
+
+    /** Open the on-disk replica in the given directory, creating it if it does not exist. */
+    public static Replica open(String path) {
+        return new Replica(nativeOpen(path));
+    }
+
+    /** Create a new replica that is stored in memory and discarded when it is closed. */
+    public static Replica inMemory() {
+        return new Replica(nativeNewInMemory());
+    }
+
+    /** Create a new task with the given status and description. */
+    public Task newTask(Status status, String description) {
+        return new Task(nativeNewTask(handle(), status.ordinal(), description));
+    }
+
+    /**
+     * Create a new task with the given UUID, or get the existing task if one exists.  This is
+     * useful for importing tasks from another source.
+     */
+    public Task importTaskWithUuid(String uuid) {
+        return new Task(nativeImportTaskWithUuid(handle(), uuid));
+    }
+
+    /** Get the task with the given UUID, or null if it does not exist. */
+    public Task getTask(String uuid) {
+        long task = nativeGetTask(handle(), uuid);
+        return task == 0 ? null : new Task(task);
+    }
+
+    /** Get all tasks, in no particular order. */
+    public Task[] allTasks() {
+        long[] handles = nativeAllTasks(handle());
+        Task[] tasks = new Task[handles.length];
+        for (int i = 0; i < handles.length; i++) {
+            tasks[i] = new Task(handles[i]);
+        }
+        return tasks;
+    }
+
+    /** Get the UUIDs of all tasks. */
+    public String[] allTaskUuids() {
+        return nativeAllTaskUuids(handle());
+    }
+
+    /** Get the current working set. */
+    public WorkingSet workingSet() {
+        return new WorkingSet(nativeWorkingSet(handle()));
+    }
+
+    /**
+     * Rebuild the working set, so that it contains exactly the pending tasks.  If {@code
+     * renumber} is true, existing tasks may be moved to new indices.
+     */
+    public void rebuildWorkingSet(boolean renumber) {
+        nativeRebuildWorkingSet(handle(), renumber);
+    }
+
+    /**
+     * Add an undo point, if one has not already been added by this replica.  If {@code force} is
+     * true, an undo point is always added.
+     */
+    public void addUndoPoint(boolean force) {
+        nativeAddUndoPoint(handle(), force);
+    }
+
+    /**
+     * Undo local operations until the most recent undo point, returning false if there are no
+     * local operations to undo.
+     */
+    public boolean undo() {
+        return nativeUndo(handle());
+    }
+
+    /** Get the number of local, un-synchronized operations, not including undo points. */
+    public long numLocalOperations() {
+        return nativeNumLocalOperations(handle());
+    }
+
+    /**
+     * Synchronize this replica with the given server.  This blocks until synchronization is
+     * complete, so on Android it must not be called from the main thread.
+     */
+    public void sync(Server server, boolean avoidSnapshots) {
+        nativeSync(handle(), server.handle(), avoidSnapshots);
+    }
+
+    @Override
+    void free(long handle) {
+        nativeFree(handle);
+    }
+
+    private static native long nativeOpen(String path);
+
+    private static native long nativeNewInMemory();
+
+    private static native void nativeFree(long handle);
+
+    private static native long nativeNewTask(long handle, int status, String description);
+
+    private static native long nativeImportTaskWithUuid(long handle, String uuid);
+
+    private static native long nativeGetTask(long handle, String uuid);
+
+    private static native long[] nativeAllTasks(long handle);
+
+    private static native String[] nativeAllTaskUuids(long handle);
+
+    private static native long nativeWorkingSet(long handle);
+
+    private static native void nativeRebuildWorkingSet(long handle, boolean renumber);
+
+    private static native void nativeAddUndoPoint(long handle, boolean force);
+
+    private static native boolean nativeUndo(long handle);
+
+    private static native long nativeNumLocalOperations(long handle);
+
+    private static native void nativeSync(long handle, long server, boolean avoidSnapshots);
+}
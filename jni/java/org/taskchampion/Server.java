@@ -0,0 +1,37 @@
+package org.taskchampion;
+
+/** A sync server, to be passed to {@link Replica#sync}. */
+public final class Server extends NativeHandle {
+    private Server(long handle) {
+        super(handle);
+    }
+
+    /**
+     * Create a server that stores its data in a local directory, for synchronizing replicas on
+     * the same device.
+     */
+    public static Server local(String serverDir) {
+        return new Server(nativeNewLocal(serverDir));
+    }
+
+    /**
+     * Create a server that connects to a taskchampion-sync-server at the given origin.  The client
+     * key is a UUID identifying this user's task data on the server, and the encryption secret is
+     * used to encrypt that data.
+     */
+    public static Server remote(String origin, String clientKey, String encryptionSecret) {
+        return new Server(nativeNewRemote(origin, clientKey, encryptionSecret));
+    }
+
+    @Override
+    void free(long handle) {
+        nativeFree(handle);
+    }
+
+    private static native long nativeNewLocal(String serverDir);
+
+    private static native long nativeNewRemote(
+            String origin, String clientKey, String encryptionSecret);
+
+    private static native void nativeFree(long handle);
+}
@@ -0,0 +1,13 @@
+package org.taskchampion;
+
+/** The status of a task.  The order of these values is shared with the native library. */
+public enum Status {
+    PENDING,
+    COMPLETED,
+    DELETED,
+    /**
+     * A status in the task database that was not recognized.  Tasks with this status should be
+     * ignored, and it cannot be given to {@link Task#setStatus}.
+     */
+    UNKNOWN,
+}
@@ -0,0 +1,258 @@
+package org.taskchampion;
+
+import java.time.Instant;
+
+/**
+ * A task.  The getters return a snapshot of the task as of when it was read from the replica, or
+ * as of its last modification through this object.  Each modification takes the replica
+ * containing the task, and immediately updates the task in that replica.
+ */
+public final class Task extends NativeHandle {
+    Task(long handle) {
+        super(handle);
+    }
+
+    private static Instant toInstant(Long secs) {
+        return secs == null ? null : Instant.ofEpochSecond(secs);
+    }
+
+    private static Long fromInstant(Instant instant) {
+        return instant == null ? null : instant.getEpochSecond();
+    }
+
+    public String getUuid() {
+        return nativeGetUuid(handle());
+    }
+
+    public Status getStatus() {
+        return Status.values()[nativeGetStatus(handle())];
+    }
+
+    public String getDescription() {
+        return nativeGetDescription(handle());
+    }
+
+    public String getPriority() {
+        return nativeGetPriority(handle());
+    }
+
+    /** Get the task's project, or null if it has none. */
+    public String getProject() {
+        return nativeGetProject(handle());
+    }
+
+    public Instant getEntry() {
+        return toInstant(nativeGetEntry(handle()));
+    }
+
+    public Instant getWait() {
+        return toInstant(nativeGetWait(handle()));
+    }
+
+    public Instant getDue() {
+        return toInstant(nativeGetDue(handle()));
+    }
+
+    public Instant getModified() {
+        return toInstant(nativeGetModified(handle()));
+    }
+
+    public Instant getStart() {
+        return toInstant(nativeGetStart(handle()));
+    }
+
+    public Instant getEnd() {
+        return toInstant(nativeGetEnd(handle()));
+    }
+
+    public boolean isWaiting() {
+        return nativeIsWaiting(handle());
+    }
+
+    public boolean isActive() {
+        return nativeIsActive(handle());
+    }
+
+    public boolean isBlocked() {
+        return nativeIsBlocked(handle());
+    }
+
+    public boolean isBlocking() {
+        return nativeIsBlocking(handle());
+    }
+
+    /**
+     * Check whether the task has the given tag, which may be a user tag or a synthetic tag such
+     * as {@code PENDING}.
+     */
+    public boolean hasTag(String tag) {
+        return nativeHasTag(handle(), tag);
+    }
+
+    /** Get the task's tags, including synthetic tags. */
+    public String[] getTags() {
+        return nativeGetTags(handle());
+    }
+
+    /** Get the value of a user-defined attribute, or null if it is not set. */
+    public String getUda(String namespace, String key) {
+        return nativeGetUda(handle(), namespace, key);
+    }
+
+    /** Get the UUIDs of the tasks on which this task depends. */
+    public String[] getDependencies() {
+        return nativeGetDependencies(handle());
+    }
+
+    public void setDescription(Replica replica, String description) {
+        nativeSetDescription(replica.handle(), handle(), description);
+    }
+
+    /** Set the task's status.  The {@link Status#UNKNOWN} status cannot be set. */
+    public void setStatus(Replica replica, Status status) {
+        nativeSetStatus(replica.handle(), handle(), status.ordinal());
+    }
+
+    public void setPriority(Replica replica, String priority) {
+        nativeSetPriority(replica.handle(), handle(), priority);
+    }
+
+    /** Set the task's project, or remove it if {@code project} is null. */
+    public void setProject(Replica replica, String project) {
+        nativeSetProject(replica.handle(), handle(), project);
+    }
+
+    /** Set the task's wait time, or remove it if {@code wait} is null. */
+    public void setWait(Replica replica, Instant wait) {
+        nativeSetWait(replica.handle(), handle(), fromInstant(wait));
+    }
+
+    /** Set the task's due time, or remove it if {@code due} is null. */
+    public void setDue(Replica replica, Instant due) {
+        nativeSetDue(replica.handle(), handle(), fromInstant(due));
+    }
+
+    public void start(Replica replica) {
+        nativeStart(replica.handle(), handle());
+    }
+
+    public void stop(Replica replica) {
+        nativeStop(replica.handle(), handle());
+    }
+
+    public void done(Replica replica) {
+        nativeDone(replica.handle(), handle());
+    }
+
+    public void delete(Replica replica) {
+        nativeDelete(replica.handle(), handle());
+    }
+
+    public void addTag(Replica replica, String tag) {
+        nativeAddTag(replica.handle(), handle(), tag);
+    }
+
+    public void removeTag(Replica replica, String tag) {
+        nativeRemoveTag(replica.handle(), handle(), tag);
+    }
+
+    public void setUda(Replica replica, String namespace, String key, String value) {
+        nativeSetUda(replica.handle(), handle(), namespace, key, value);
+    }
+
+    public void removeUda(Replica replica, String namespace, String key) {
+        nativeRemoveUda(replica.handle(), handle(), namespace, key);
+    }
+
+    public void addDependency(Replica replica, String dep) {
+        nativeAddDependency(replica.handle(), handle(), dep);
+    }
+
+    public void removeDependency(Replica replica, String dep) {
+        nativeRemoveDependency(replica.handle(), handle(), dep);
+    }
+
+    @Override
+    public String toString() {
+        return "Task(" + getUuid() + ", " + getDescription() + ")";
+    }
+
+    @Override
+    void free(long handle) {
+        nativeFree(handle);
+    }
+
+    private static native void nativeFree(long handle);
+
+    private static native String nativeGetUuid(long handle);
+
+    private static native int nativeGetStatus(long handle);
+
+    private static native String nativeGetDescription(long handle);
+
+    private static native String nativeGetPriority(long handle);
+
+    private static native String nativeGetProject(long handle);
+
+    private static native Long nativeGetEntry(long handle);
+
+    private static native Long nativeGetWait(long handle);
+
+    private static native Long nativeGetDue(long handle);
+
+    private static native Long nativeGetModified(long handle);
+
+    private static native Long nativeGetStart(long handle);
+
+    private static native Long nativeGetEnd(long handle);
+
+    private static native boolean nativeIsWaiting(long handle);
+
+    private static native boolean nativeIsActive(long handle);
+
+    private static native boolean nativeIsBlocked(long handle);
+
+    private static native boolean nativeIsBlocking(long handle);
+
+    private static native boolean nativeHasTag(long handle, String tag);
+
+    private static native String[] nativeGetTags(long handle);
+
+    private static native String nativeGetUda(long handle, String namespace, String key);
+
+    private static native String[] nativeGetDependencies(long handle);
+
+    private static native void nativeSetDescription(long rep, long handle, String description);
+
+    private static native void nativeSetStatus(long rep, long handle, int status);
+
+    private static native void nativeSetPriority(long rep, long handle, String priority);
+
+    private static native void nativeSetProject(long rep, long handle, String project);
+
+    private static native void nativeSetWait(long rep, long handle, Long wait);
+
+    private static native void nativeSetDue(long rep, long handle, Long due);
+
+    private static native void nativeStart(long rep, long handle);
+
+    private static native void nativeStop(long rep, long handle);
+
+    private static native void nativeDone(long rep, long handle);
+
+    private static native void nativeDelete(long rep, long handle);
+
+    private static native void nativeAddTag(long rep, long handle, String tag);
+
+    private static native void nativeRemoveTag(long rep, long handle, String tag);
+
+    private static native void nativeSetUda(
+            long rep, long handle, String namespace, String key, String value);
+
+    private static native void nativeRemoveUda(
+            long rep, long handle, String namespace, String key);
+
+    private static native void nativeAddDependency(long rep, long handle, String dep);
+
+    private static native void nativeRemoveDependency(long rep, long handle, String dep);
+}
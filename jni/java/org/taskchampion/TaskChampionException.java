@@ -0,0 +1,13 @@
+package org.taskchampion;
+
+/**
+ * An error from TaskChampion, such as a failure to read the task database or to contact the sync
+ * server.  The message includes the full chain of causes reported by the native library.
+ */
+public class TaskChampionException extends RuntimeException {
+    private static final long serialVersionUID = 1L;
+
+    public TaskChampionException(String message) {
+        super(message);
+    }
+}
@@ -0,0 +1,46 @@
+package org.taskchampion;
+
+/**
+ * A snapshot of a replica's working set, mapping small integers to the UUIDs of pending tasks.  It
+ * is not updated when the replica changes.
+ */
+public final class WorkingSet extends NativeHandle {
+    WorkingSet(long handle) {
+        super(handle);
+    }
+
+    /** Get the number of tasks in the working set. */
+    public int size() {
+        return nativeSize(handle());
+    }
+
+    /** Get the largest index in the working set, or zero if it is empty. */
+    public int largestIndex() {
+        return nativeLargestIndex(handle());
+    }
+
+    /** Get the UUID of the task at the given index, or null if there is no such task. */
+    public String byIndex(int index) {
+        return nativeByIndex(handle(), index);
+    }
+
+    /** Get the index of the task with the given UUID, or zero if it is not in the working set. */
+    public int byUuid(String uuid) {
+        return nativeByUuid(handle(), uuid);
+    }
+
+    @Override
+    void free(long handle) {
+        nativeFree(handle);
+    }
+
+    private static native void nativeFree(long handle);
+
+    private static native int nativeSize(long handle);
+
+    private static native int nativeLargestIndex(long handle);
+
+    private static native String nativeByIndex(long handle, int index);
+
+    private static native int nativeByUuid(long handle, String uuid);
+}
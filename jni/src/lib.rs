@@ -0,0 +1,14 @@
+//! JNI bindings for TaskChampion, for use from Java and Kotlin, e.g., in Android applications.
+//!
+//! This crate builds a shared library, `libtaskchampion_jni`, implementing the native methods of
+//! the Java classes in the `org.taskchampion` package, found in the `java/` directory.  Each Java
+//! object owns a heap-allocated Rust value, identified by a `long` handle, which is freed when the
+//! object is closed.  Errors are thrown as `org.taskchampion.TaskChampionException`, or as
+//! `IllegalArgumentException` for invalid arguments such as malformed UUIDs.
+
+mod replica;
+mod server;
+mod status;
+mod task;
+mod util;
+mod working_set;
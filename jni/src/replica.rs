@@ -0,0 +1,206 @@
+use crate::status;
+use crate::util::{
+    free_handle, from_handle, get_string, get_uuid, into_handle, new_string_array, wrap,
+};
+use jni::objects::{JClass, JString};
+use jni::sys::{jboolean, jint, jlong, jlongArray, jobjectArray, JNI_FALSE};
+use jni::JNIEnv;
+use std::path::PathBuf;
+use taskchampion::{Replica, Server, StorageConfig, Task, WorkingSet};
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeOpen<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    path: JString<'local>,
+) -> jlong {
+    wrap(&mut env, 0, |env| {
+        let taskdb_dir = PathBuf::from(get_string(env, &path)?);
+        let storage = StorageConfig::OnDisk { taskdb_dir }.into_storage()?;
+        Ok(into_handle(Replica::new(storage)))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeNewInMemory<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> jlong {
+    wrap(&mut env, 0, |_| {
+        let storage = StorageConfig::InMemory.into_storage()?;
+        Ok(into_handle(Replica::new(storage)))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeFree<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+) {
+    // SAFETY: rep is a valid, open Replica handle, and Java does not use it after this call
+    unsafe { free_handle::<Replica>(rep) }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeNewTask<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    status: jint,
+    description: JString<'local>,
+) -> jlong {
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    wrap(&mut env, 0, |env| {
+        let status = status::from_ordinal(status)?;
+        let description = get_string(env, &description)?;
+        Ok(into_handle(rep.new_task(status, description)?))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeImportTaskWithUuid<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    uuid: JString<'local>,
+) -> jlong {
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    wrap(&mut env, 0, |env| {
+        let uuid = get_uuid(env, &uuid)?;
+        Ok(into_handle(rep.import_task_with_uuid(uuid)?))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeGetTask<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    uuid: JString<'local>,
+) -> jlong {
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    wrap(&mut env, 0, |env| {
+        let uuid = get_uuid(env, &uuid)?;
+        Ok(rep.get_task(uuid)?.map(into_handle).unwrap_or(0))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeAllTasks<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+) -> jlongArray {
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    wrap(&mut env, std::ptr::null_mut(), |env| {
+        let handles: Vec<jlong> = rep
+            .all_tasks()?
+            .into_values()
+            .map(into_handle::<Task>)
+            .collect();
+        let array = env.new_long_array(handles.len() as i32)?;
+        env.set_long_array_region(&array, 0, &handles)?;
+        Ok(array.into_raw())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeAllTaskUuids<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+) -> jobjectArray {
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    wrap(&mut env, std::ptr::null_mut(), |env| {
+        let uuids = rep.all_task_uuids()?;
+        new_string_array(env, uuids.iter().map(|u| u.to_string()))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeWorkingSet<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+) -> jlong {
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    wrap(&mut env, 0, |_| {
+        Ok(into_handle::<WorkingSet>(rep.working_set()?))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeRebuildWorkingSet<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    renumber: jboolean,
+) {
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    wrap(&mut env, (), |_| {
+        Ok(rep.rebuild_working_set(renumber != JNI_FALSE)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeAddUndoPoint<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    force: jboolean,
+) {
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    wrap(
+        &mut env,
+        (),
+        |_| Ok(rep.add_undo_point(force != JNI_FALSE)?),
+    )
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeUndo<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+) -> jboolean {
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    wrap(&mut env, JNI_FALSE, |_| Ok(rep.undo()?.into()))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeNumLocalOperations<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+) -> jlong {
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    wrap(&mut env, 0, |_| Ok(rep.num_local_operations()? as jlong))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Replica_nativeSync<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    server: jlong,
+    avoid_snapshots: jboolean,
+) {
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    // SAFETY: server is a valid, open Server handle
+    let server = unsafe { from_handle::<Box<dyn Server>>(server) };
+    wrap(&mut env, (), |_| {
+        Ok(rep.sync(server, avoid_snapshots != JNI_FALSE)?)
+    })
+}
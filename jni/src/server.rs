@@ -0,0 +1,47 @@
+use crate::util::{free_handle, get_string, get_uuid, into_handle, wrap};
+use jni::objects::{JClass, JString};
+use jni::sys::jlong;
+use jni::JNIEnv;
+use std::path::PathBuf;
+use taskchampion::{Server, ServerConfig};
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Server_nativeNewLocal<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    server_dir: JString<'local>,
+) -> jlong {
+    wrap(&mut env, 0, |env| {
+        let server_dir = PathBuf::from(get_string(env, &server_dir)?);
+        let server = ServerConfig::Local { server_dir }.into_server()?;
+        Ok(into_handle::<Box<dyn Server>>(server))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Server_nativeNewRemote<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    origin: JString<'local>,
+    client_key: JString<'local>,
+    encryption_secret: JString<'local>,
+) -> jlong {
+    wrap(&mut env, 0, |env| {
+        let config = ServerConfig::Remote {
+            origin: get_string(env, &origin)?,
+            client_key: get_uuid(env, &client_key)?,
+            encryption_secret: get_string(env, &encryption_secret)?.into_bytes(),
+        };
+        Ok(into_handle::<Box<dyn Server>>(config.into_server()?))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Server_nativeFree<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    server: jlong,
+) {
+    // SAFETY: server is a valid, open Server handle, and Java does not use it after this call
+    unsafe { free_handle::<Box<dyn Server>>(server) }
+}
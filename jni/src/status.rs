@@ -0,0 +1,31 @@
+use crate::util::{Error, Result};
+use jni::sys::jint;
+use taskchampion::Status;
+
+// These values are the ordinals of the `org.taskchampion.Status` enum.
+const PENDING: jint = 0;
+const COMPLETED: jint = 1;
+const DELETED: jint = 2;
+const UNKNOWN: jint = 3;
+
+/// Convert a task status into the ordinal of the corresponding Java enum value.
+pub(crate) fn to_ordinal(status: Status) -> jint {
+    match status {
+        Status::Pending => PENDING,
+        Status::Completed => COMPLETED,
+        Status::Deleted => DELETED,
+        Status::Unknown(_) => UNKNOWN,
+    }
+}
+
+/// Convert the ordinal of a Java enum value into a task status, failing for `UNKNOWN`.
+pub(crate) fn from_ordinal(ordinal: jint) -> Result<Status> {
+    match ordinal {
+        PENDING => Ok(Status::Pending),
+        COMPLETED => Ok(Status::Completed),
+        DELETED => Ok(Status::Deleted),
+        _ => Err(Error::InvalidArgument(
+            "cannot use the UNKNOWN status".into(),
+        )),
+    }
+}
@@ -0,0 +1,478 @@
+use crate::status;
+use crate::util::{
+    free_handle, from_handle, get_opt_string, get_string, get_timestamp, get_uuid, new_opt_string,
+    new_string_array, new_timestamp, wrap, Error, Result,
+};
+use jni::objects::{JClass, JObject, JString};
+use jni::sys::{jboolean, jint, jlong, jobject, jobjectArray, jstring, JNI_FALSE};
+use jni::JNIEnv;
+use std::convert::TryFrom;
+use taskchampion::chrono::{DateTime, Utc};
+use taskchampion::{Replica, Tag, Task, TaskMut};
+
+/// Parse a tag given as a Java string.
+fn get_tag(env: &mut JNIEnv, tag: &JString) -> Result<Tag> {
+    let tag = get_string(env, tag)?;
+    Tag::try_from(&tag).map_err(|e| Error::InvalidArgument(e.to_string()))
+}
+
+/// Apply a modification to a task in the given replica, updating the task behind the handle.
+fn modify<'local, F>(env: &mut JNIEnv<'local>, rep: jlong, task: jlong, f: F)
+where
+    F: FnOnce(&mut JNIEnv<'local>, &mut TaskMut) -> Result<()>,
+{
+    // SAFETY: rep is a valid, open Replica handle
+    let rep = unsafe { from_handle::<Replica>(rep) };
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    wrap(env, (), |env| {
+        let mut task_mut = task.clone().into_mut(rep);
+        let res = f(env, &mut task_mut);
+        *task = task_mut.into_immut();
+        res
+    })
+}
+
+/// Get a timestamp property of a task, as a `java.lang.Long` or null.
+fn timestamp_property<'local, F>(env: &mut JNIEnv<'local>, task: jlong, f: F) -> jobject
+where
+    F: FnOnce(&Task) -> Option<DateTime<Utc>>,
+{
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    wrap(env, std::ptr::null_mut(), |env| {
+        Ok(new_timestamp(env, f(task))?.into_raw())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeFree<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) {
+    // SAFETY: task is a valid, open Task handle, and Java does not use it after this call
+    unsafe { free_handle::<Task>(task) }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetUuid<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jstring {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    wrap(&mut env, std::ptr::null_mut(), |env| {
+        Ok(env.new_string(task.get_uuid().to_string())?.into_raw())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetStatus<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jint {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    status::to_ordinal(task.get_status())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetDescription<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jstring {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    wrap(&mut env, std::ptr::null_mut(), |env| {
+        Ok(env.new_string(task.get_description())?.into_raw())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetPriority<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jstring {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    wrap(&mut env, std::ptr::null_mut(), |env| {
+        Ok(env.new_string(task.get_priority())?.into_raw())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetProject<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jstring {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    wrap(&mut env, std::ptr::null_mut(), |env| {
+        Ok(new_opt_string(env, task.get_project())?.into_raw())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetEntry<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jobject {
+    timestamp_property(&mut env, task, Task::get_entry)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetWait<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jobject {
+    timestamp_property(&mut env, task, Task::get_wait)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetDue<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jobject {
+    timestamp_property(&mut env, task, Task::get_due)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetModified<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jobject {
+    timestamp_property(&mut env, task, Task::get_modified)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetStart<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jobject {
+    timestamp_property(&mut env, task, Task::get_start)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetEnd<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jobject {
+    timestamp_property(&mut env, task, Task::get_end)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeIsWaiting<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jboolean {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    task.is_waiting().into()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeIsActive<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jboolean {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    task.is_active().into()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeIsBlocked<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jboolean {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    task.is_blocked().into()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeIsBlocking<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jboolean {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    task.is_blocking().into()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeHasTag<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+    tag: JString<'local>,
+) -> jboolean {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    wrap(&mut env, JNI_FALSE, |env| {
+        Ok(task.has_tag(&get_tag(env, &tag)?).into())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetTags<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jobjectArray {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    wrap(&mut env, std::ptr::null_mut(), |env| {
+        new_string_array(env, task.get_tags().map(|t| t.to_string()))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetUda<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+    namespace: JString<'local>,
+    key: JString<'local>,
+) -> jstring {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    wrap(&mut env, std::ptr::null_mut(), |env| {
+        let namespace = get_string(env, &namespace)?;
+        let key = get_string(env, &key)?;
+        Ok(new_opt_string(env, task.get_uda(&namespace, &key))?.into_raw())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeGetDependencies<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    task: jlong,
+) -> jobjectArray {
+    // SAFETY: task is a valid, open Task handle
+    let task = unsafe { from_handle::<Task>(task) };
+    wrap(&mut env, std::ptr::null_mut(), |env| {
+        new_string_array(env, task.get_dependencies().map(|u| u.to_string()))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeSetDescription<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    description: JString<'local>,
+) {
+    modify(&mut env, rep, task, |env, task| {
+        Ok(task.set_description(get_string(env, &description)?)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeSetStatus<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    status: jint,
+) {
+    modify(&mut env, rep, task, |_, task| {
+        Ok(task.set_status(status::from_ordinal(status)?)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeSetPriority<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    priority: JString<'local>,
+) {
+    modify(&mut env, rep, task, |env, task| {
+        Ok(task.set_priority(get_string(env, &priority)?)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeSetProject<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    project: JString<'local>,
+) {
+    modify(&mut env, rep, task, |env, task| {
+        Ok(task.set_project(get_opt_string(env, &project)?)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeSetWait<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    wait: JObject<'local>,
+) {
+    modify(&mut env, rep, task, |env, task| {
+        Ok(task.set_wait(get_timestamp(env, &wait)?)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeSetDue<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    due: JObject<'local>,
+) {
+    modify(&mut env, rep, task, |env, task| {
+        Ok(task.set_due(get_timestamp(env, &due)?)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeStart<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+) {
+    modify(&mut env, rep, task, |_, task| Ok(task.start()?))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeStop<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+) {
+    modify(&mut env, rep, task, |_, task| Ok(task.stop()?))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeDone<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+) {
+    modify(&mut env, rep, task, |_, task| Ok(task.done()?))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeDelete<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+) {
+    modify(&mut env, rep, task, |_, task| Ok(task.delete()?))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeAddTag<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    tag: JString<'local>,
+) {
+    modify(&mut env, rep, task, |env, task| {
+        Ok(task.add_tag(&get_tag(env, &tag)?)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeRemoveTag<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    tag: JString<'local>,
+) {
+    modify(&mut env, rep, task, |env, task| {
+        Ok(task.remove_tag(&get_tag(env, &tag)?)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeSetUda<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    namespace: JString<'local>,
+    key: JString<'local>,
+    value: JString<'local>,
+) {
+    modify(&mut env, rep, task, |env, task| {
+        let namespace = get_string(env, &namespace)?;
+        let key = get_string(env, &key)?;
+        let value = get_string(env, &value)?;
+        Ok(task.set_uda(namespace, key, value)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeRemoveUda<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    namespace: JString<'local>,
+    key: JString<'local>,
+) {
+    modify(&mut env, rep, task, |env, task| {
+        let namespace = get_string(env, &namespace)?;
+        let key = get_string(env, &key)?;
+        Ok(task.remove_uda(namespace, key)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeAddDependency<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    dep: JString<'local>,
+) {
+    modify(&mut env, rep, task, |env, task| {
+        Ok(task.add_dependency(get_uuid(env, &dep)?)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_Task_nativeRemoveDependency<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rep: jlong,
+    task: jlong,
+    dep: JString<'local>,
+) {
+    modify(&mut env, rep, task, |env, task| {
+        Ok(task.remove_dependency(get_uuid(env, &dep)?)?)
+    })
+}
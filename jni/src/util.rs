@@ -0,0 +1,166 @@
+use jni::objects::{JObject, JString, JValue};
+use jni::sys::{jlong, jobjectArray};
+use jni::JNIEnv;
+use taskchampion::chrono::{DateTime, TimeZone, Utc};
+use taskchampion::Uuid;
+
+/// An error in a native method, which is thrown as a Java exception.
+pub(crate) enum Error {
+    /// An error from TaskChampion, thrown as `org.taskchampion.TaskChampionException`.
+    TaskChampion(taskchampion::Error),
+    /// An invalid argument, such as a malformed UUID, thrown as `IllegalArgumentException`.
+    InvalidArgument(String),
+    /// An error calling into the JVM.  Most such errors leave a Java exception pending.
+    Jni(jni::errors::Error),
+}
+
+impl From<taskchampion::Error> for Error {
+    fn from(err: taskchampion::Error) -> Error {
+        Error::TaskChampion(err)
+    }
+}
+
+impl From<jni::errors::Error> for Error {
+    fn from(err: jni::errors::Error) -> Error {
+        Error::Jni(err)
+    }
+}
+
+pub(crate) type Result<T> = std::result::Result<T, Error>;
+
+/// Throw the given error as a Java exception.
+fn throw(env: &mut JNIEnv, err: Error) {
+    // if an exception is already pending, let it propagate
+    if env.exception_check().unwrap_or(false) {
+        return;
+    }
+    let (class, msg) = match err {
+        Error::TaskChampion(e) => ("org/taskchampion/TaskChampionException", format!("{:#}", e)),
+        Error::InvalidArgument(msg) => ("java/lang/IllegalArgumentException", msg),
+        Error::Jni(e) => ("java/lang/RuntimeException", e.to_string()),
+    };
+    // if even this fails, there is nothing more to be done
+    let _ = env.throw_new(class, msg);
+}
+
+/// Call `f`, converting any error into a Java exception and returning `err_value` in that case.
+/// The JVM ignores the return value of a native method when an exception is pending.
+pub(crate) fn wrap<'local, T, F>(env: &mut JNIEnv<'local>, err_value: T, f: F) -> T
+where
+    F: FnOnce(&mut JNIEnv<'local>) -> Result<T>,
+{
+    match f(env) {
+        Ok(v) => v,
+        Err(e) => {
+            throw(env, e);
+            err_value
+        }
+    }
+}
+
+/// Move a value to the heap, returning a handle for the Java object that owns it.
+pub(crate) fn into_handle<T>(value: T) -> jlong {
+    Box::into_raw(Box::new(value)) as jlong
+}
+
+/// Borrow the value behind a handle.
+///
+/// # Safety
+///
+/// The handle must have been returned from `into_handle::<T>` and not yet freed, and no other
+/// reference to the value may exist.  The Java classes ensure this by checking for closed
+/// handles, and are not thread-safe.
+pub(crate) unsafe fn from_handle<'a, T>(handle: jlong) -> &'a mut T {
+    debug_assert!(handle != 0);
+    // SAFETY: see docstring
+    unsafe { &mut *(handle as *mut T) }
+}
+
+/// Free the value behind a handle.
+///
+/// # Safety
+///
+/// The handle must have been returned from `into_handle::<T>` and not yet freed.  It is invalid
+/// after this call.
+pub(crate) unsafe fn free_handle<T>(handle: jlong) {
+    debug_assert!(handle != 0);
+    // SAFETY: see docstring
+    drop(unsafe { Box::from_raw(handle as *mut T) });
+}
+
+/// Get a Rust string from a Java string, which must not be null.
+pub(crate) fn get_string(env: &mut JNIEnv, s: &JString) -> Result<String> {
+    if s.is_null() {
+        return Err(Error::InvalidArgument("string must not be null".into()));
+    }
+    Ok(env.get_string(s)?.into())
+}
+
+/// Get a Rust string from a Java string, which may be null.
+pub(crate) fn get_opt_string(env: &mut JNIEnv, s: &JString) -> Result<Option<String>> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    Ok(Some(env.get_string(s)?.into()))
+}
+
+/// Parse a UUID given as a Java string.
+pub(crate) fn get_uuid(env: &mut JNIEnv, s: &JString) -> Result<Uuid> {
+    let s = get_string(env, s)?;
+    Uuid::parse_str(&s).map_err(|e| Error::InvalidArgument(format!("invalid UUID: {}", e)))
+}
+
+/// Create a Java string, or null for `None`.
+pub(crate) fn new_opt_string<'local>(
+    env: &mut JNIEnv<'local>,
+    s: Option<&str>,
+) -> Result<JObject<'local>> {
+    Ok(match s {
+        Some(s) => env.new_string(s)?.into(),
+        None => JObject::null(),
+    })
+}
+
+/// Create a Java `String[]` from the given strings.
+pub(crate) fn new_string_array<I, S>(env: &mut JNIEnv, strings: I) -> Result<jobjectArray>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let strings: Vec<S> = strings.into_iter().collect();
+    let array = env.new_object_array(strings.len() as i32, "java/lang/String", JObject::null())?;
+    for (i, s) in strings.iter().enumerate() {
+        let s = env.new_string(s.as_ref())?;
+        env.set_object_array_element(&array, i as i32, s)?;
+    }
+    Ok(array.into_raw())
+}
+
+/// Convert a timestamp into a `java.lang.Long` number of seconds since the epoch, or null for
+/// `None`.  The Java classes convert this to an `Instant`.
+pub(crate) fn new_timestamp<'local>(
+    env: &mut JNIEnv<'local>,
+    ts: Option<DateTime<Utc>>,
+) -> Result<JObject<'local>> {
+    Ok(match ts {
+        Some(ts) => env
+            .call_static_method(
+                "java/lang/Long",
+                "valueOf",
+                "(J)Ljava/lang/Long;",
+                &[JValue::Long(ts.timestamp())],
+            )?
+            .l()?,
+        None => JObject::null(),
+    })
+}
+
+/// Convert a `java.lang.Long` number of seconds since the epoch, which may be null, into a
+/// timestamp.
+pub(crate) fn get_timestamp(env: &mut JNIEnv, ts: &JObject) -> Result<Option<DateTime<Utc>>> {
+    if ts.is_null() {
+        return Ok(None);
+    }
+    let secs = env.call_method(ts, "longValue", "()J", &[])?.j()?;
+    Ok(Some(Utc.timestamp(secs, 0)))
+}
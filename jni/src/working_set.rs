@@ -0,0 +1,71 @@
+use crate::util::{free_handle, from_handle, get_uuid, new_opt_string, wrap};
+use jni::objects::{JClass, JString};
+use jni::sys::{jint, jlong, jstring};
+use jni::JNIEnv;
+use taskchampion::WorkingSet;
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_WorkingSet_nativeFree<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    ws: jlong,
+) {
+    // SAFETY: ws is a valid, open WorkingSet handle, and Java does not use it after this call
+    unsafe { free_handle::<WorkingSet>(ws) }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_WorkingSet_nativeSize<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    ws: jlong,
+) -> jint {
+    // SAFETY: ws is a valid, open WorkingSet handle
+    let ws = unsafe { from_handle::<WorkingSet>(ws) };
+    ws.len() as jint
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_WorkingSet_nativeLargestIndex<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    ws: jlong,
+) -> jint {
+    // SAFETY: ws is a valid, open WorkingSet handle
+    let ws = unsafe { from_handle::<WorkingSet>(ws) };
+    ws.largest_index() as jint
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_WorkingSet_nativeByIndex<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    ws: jlong,
+    index: jint,
+) -> jstring {
+    // SAFETY: ws is a valid, open WorkingSet handle
+    let ws = unsafe { from_handle::<WorkingSet>(ws) };
+    wrap(&mut env, std::ptr::null_mut(), |env| {
+        let uuid = if index > 0 {
+            ws.by_index(index as usize).map(|u| u.to_string())
+        } else {
+            None
+        };
+        Ok(new_opt_string(env, uuid.as_deref())?.into_raw())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_taskchampion_WorkingSet_nativeByUuid<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    ws: jlong,
+    uuid: JString<'local>,
+) -> jint {
+    // SAFETY: ws is a valid, open WorkingSet handle
+    let ws = unsafe { from_handle::<WorkingSet>(ws) };
+    wrap(&mut env, 0, |env| {
+        let uuid = get_uuid(env, &uuid)?;
+        Ok(ws.by_uuid(uuid).unwrap_or(0) as jint)
+    })
+}
@@ -0,0 +1,196 @@
+package org.taskchampion;
+
+import java.io.File;
+import java.nio.file.Files;
+import java.time.Instant;
+import java.util.Arrays;
+
+/**
+ * Tests for the Java bindings.  These use no test framework, so that they can be run with just a
+ * JDK; see the README for the command.
+ */
+public class ReplicaTest {
+    private static int failures = 0;
+
+    private static void check(boolean cond, String what) {
+        if (!cond) {
+            throw new AssertionError(what);
+        }
+    }
+
+    private interface Test {
+        void run() throws Exception;
+    }
+
+    private static void run(String name, Test test) {
+        try {
+            test.run();
+            System.out.println("ok   " + name);
+        } catch (Throwable e) {
+            failures++;
+            System.out.println("FAIL " + name + ": " + e);
+        }
+    }
+
+    static void testNewTask() {
+        try (Replica rep = Replica.inMemory();
+                Task task = rep.newTask(Status.PENDING, "a task")) {
+            check(task.getStatus() == Status.PENDING, "status");
+            check(task.getDescription().equals("a task"), "description");
+            check(task.getProject() == null, "project");
+            check(task.getEntry() != null, "entry");
+            check(task.getWait() == null, "wait");
+            check(task.hasTag("PENDING"), "PENDING tag");
+            check(rep.allTaskUuids().length == 1, "all uuids");
+            check(rep.numLocalOperations() > 0, "local operations");
+        }
+    }
+
+    static void testModify() {
+        try (Replica rep = Replica.inMemory();
+                Task task = rep.newTask(Status.PENDING, "a task")) {
+            Instant due = Instant.ofEpochSecond(1700000000);
+            task.setDescription(rep, "changed");
+            task.setProject(rep, "home");
+            task.setDue(rep, due);
+            task.addTag(rep, "next");
+            task.setUda(rep, "ns", "key", "value");
+            task.start(rep);
+            check(task.getDescription().equals("changed"), "description");
+            check(task.isActive(), "active");
+
+            try (Task reread = rep.getTask(task.getUuid())) {
+                check(reread.getDescription().equals("changed"), "reread description");
+                check(reread.getProject().equals("home"), "project");
+                check(due.equals(reread.getDue()), "due");
+                check(reread.hasTag("next"), "tag");
+                check(Arrays.asList(reread.getTags()).contains("next"), "tags");
+                check(reread.getUda("ns", "key").equals("value"), "uda");
+            }
+
+            task.setProject(rep, null);
+            task.done(rep);
+            check(task.getProject() == null, "project removed");
+            check(task.getStatus() == Status.COMPLETED, "completed");
+            check(task.getEnd() != null, "end");
+        }
+    }
+
+    static void testDependencies() {
+        try (Replica rep = Replica.inMemory();
+                Task t1 = rep.newTask(Status.PENDING, "one");
+                Task t2 = rep.newTask(Status.PENDING, "two")) {
+            t1.addDependency(rep, t2.getUuid());
+            check(Arrays.asList(t1.getDependencies()).contains(t2.getUuid()), "dependency");
+            t1.removeDependency(rep, t2.getUuid());
+            check(t1.getDependencies().length == 0, "dependency removed");
+        }
+    }
+
+    static void testGetMissingTask() {
+        try (Replica rep = Replica.inMemory()) {
+            check(rep.getTask("d5de6bd6-3d7e-4b9b-8d0b-e6de2b3b1e4e") == null, "missing task");
+        }
+    }
+
+    static void testWorkingSet() {
+        try (Replica rep = Replica.inMemory();
+                Task task = rep.newTask(Status.PENDING, "a task")) {
+            rep.rebuildWorkingSet(true);
+            try (WorkingSet ws = rep.workingSet()) {
+                check(ws.size() == 1, "size");
+                check(ws.largestIndex() == 1, "largest index");
+                check(task.getUuid().equals(ws.byIndex(1)), "by index");
+                check(ws.byIndex(2) == null, "missing index");
+                check(ws.byUuid(task.getUuid()) == 1, "by uuid");
+            }
+        }
+    }
+
+    static void testUndo() {
+        try (Replica rep = Replica.inMemory()) {
+            rep.addUndoPoint(true);
+            rep.newTask(Status.PENDING, "a task").close();
+            check(rep.undo(), "undo");
+            check(rep.allTasks().length == 0, "no tasks");
+            check(!rep.undo(), "nothing to undo");
+        }
+    }
+
+    static void testSync() throws Exception {
+        File dir = Files.createTempDirectory("tc-jni").toFile();
+        // the local server does not create its directory
+        new File(dir, "server").mkdir();
+        try (Replica rep1 = Replica.open(new File(dir, "rep1").getPath());
+                Replica rep2 = Replica.open(new File(dir, "rep2").getPath());
+                Server server = Server.local(new File(dir, "server").getPath())) {
+            rep1.newTask(Status.PENDING, "a task").close();
+            rep1.sync(server, false);
+            rep2.sync(server, false);
+            Task[] tasks = rep2.allTasks();
+            check(tasks.length == 1, "synced task");
+            check(tasks[0].getDescription().equals("a task"), "synced description");
+            tasks[0].close();
+        }
+    }
+
+    static void testExceptions() {
+        try (Replica rep = Replica.inMemory();
+                Task task = rep.newTask(Status.PENDING, "a task")) {
+            try {
+                rep.getTask("not-a-uuid");
+                check(false, "invalid uuid accepted");
+            } catch (IllegalArgumentException e) {
+                check(e.getMessage().contains("invalid UUID"), "uuid message");
+            }
+            try {
+                task.addTag(rep, "bad tag");
+                check(false, "invalid tag accepted");
+            } catch (IllegalArgumentException e) {
+                // expected
+            }
+            try {
+                task.setStatus(rep, Status.UNKNOWN);
+                check(false, "unknown status accepted");
+            } catch (IllegalArgumentException e) {
+                // expected
+            }
+        }
+
+        Replica closed = Replica.inMemory();
+        closed.close();
+        try {
+            closed.allTasks();
+            check(false, "closed replica used");
+        } catch (IllegalStateException e) {
+            // expected
+        }
+    }
+
+    static void testTaskChampionException() throws Exception {
+        File file = Files.createTempFile("tc-jni", "").toFile();
+        try {
+            // a replica cannot be opened where a regular file exists
+            Replica.open(file.getPath()).close();
+            check(false, "replica opened in a file");
+        } catch (TaskChampionException e) {
+            check(!e.getMessage().isEmpty(), "message");
+        }
+    }
+
+    public static void main(String[] args) {
+        run("newTask", ReplicaTest::testNewTask);
+        run("modify", ReplicaTest::testModify);
+        run("dependencies", ReplicaTest::testDependencies);
+        run("getMissingTask", ReplicaTest::testGetMissingTask);
+        run("workingSet", ReplicaTest::testWorkingSet);
+        run("undo", ReplicaTest::testUndo);
+        run("sync", ReplicaTest::testSync);
+        run("exceptions", ReplicaTest::testExceptions);
+        run("taskChampionException", ReplicaTest::testTaskChampionException);
+        if (failures > 0) {
+            System.out.println(failures + " test(s) failed");
+            System.exit(1);
+        }
+    }
+}
@@ -73,4 +73,11 @@
  * function, a list's ownership is with the C caller, which must eventually
  * free the list.  List data must be freed with the `tc_…_list_free` function.
  * It is an error to free any value in the `items` array of a list.
+ *
+ * ## Contexts
+ *
+ * As an alternative to freeing each value individually, replicas, tasks,
+ * servers, working sets, and strings can be given to a TCContext with the
+ * `tc_context_adopt_…` functions.  The context then owns those values, and
+ * frees them all when it is freed with `tc_context_free`.
  */
@@ -0,0 +1,283 @@
+use crate::traits::*;
+use crate::types::*;
+use crate::{
+    tc_annotation_list_free, tc_kv_list_free, tc_lazy_task_list_free, tc_replica_free,
+    tc_server_free, tc_string_free, tc_string_list_free, tc_task_free, tc_task_list_free,
+    tc_uda_list_free, tc_uuid_list_free, tc_working_set_free,
+};
+
+/// TCContext owns a collection of TC objects, and frees them all at once in `tc_context_free`.
+/// This is useful for bindings in garbage-collected languages, where tracking the ownership of
+/// each individual object is error-prone.  Using a TCContext is optional.
+///
+/// An object is given to a context with one of the `tc_context_adopt_…` functions, typically
+/// immediately after it is created:
+///
+/// ```text
+/// TCContext *ctx = tc_context_new();
+/// TCReplica *rep = tc_context_adopt_replica(ctx, tc_replica_new_in_memory());
+/// TCTask *task = tc_context_adopt_task(ctx,
+///     tc_replica_new_task(rep, TC_STATUS_PENDING, tc_string_borrow("my task")));
+/// // ... use rep and task ...
+/// tc_context_free(ctx); // frees task, then rep
+/// ```
+///
+/// Objects are freed in the reverse of the order in which they were adopted, so an object that
+/// depends on another, such as a mutable task on its replica, should be adopted after it.
+///
+/// # Safety
+///
+/// Once adopted, an object is owned by the context and remains valid until the context is freed.
+/// It must not be freed by the caller, and must not be passed to any function that takes
+/// ownership of it.  An object must not be adopted more than once, or by more than one context.
+///
+/// When a `*TCContext` is returned from a function, it is owned by the caller and must later be
+/// freed with `tc_context_free`.  Once freed, the context and every object it owns become invalid
+/// and must not be used again.
+///
+/// TCContext is not threadsafe.
+pub struct TCContext {
+    /// Adopted objects, in the order they were adopted
+    owned: Vec<Owned>,
+}
+
+impl PassByPointer for TCContext {}
+
+/// An object owned by a TCContext
+enum Owned {
+    Replica(*mut TCReplica),
+    Task(*mut TCTask),
+    Server(*mut TCServer),
+    WorkingSet(*mut TCWorkingSet),
+    LazyTaskList(*mut TCLazyTaskList),
+    // boxed so that the TCString does not move when more objects are adopted
+    String(Box<TCString>),
+    // lists are boxed for the same reason
+    TaskList(Box<TCTaskList>),
+    UuidList(Box<TCUuidList>),
+    StringList(Box<TCStringList>),
+    KVList(Box<TCKVList>),
+    AnnotationList(Box<TCAnnotationList>),
+    UdaList(Box<TCUdaList>),
+}
+
+impl Drop for TCContext {
+    fn drop(&mut self) {
+        while let Some(owned) = self.owned.pop() {
+            // SAFETY (for all of the following):
+            //  - the pointer is not NULL (checked when adopted)
+            //  - the object is valid and owned by this context (promised by caller of adopt)
+            //  - the object will not be used after this point (promised by caller of adopt)
+            match owned {
+                Owned::Replica(rep) => unsafe { tc_replica_free(rep) },
+                Owned::Task(task) => unsafe { tc_task_free(task) },
+                Owned::Server(server) => unsafe { tc_server_free(server) },
+                Owned::WorkingSet(ws) => unsafe { tc_working_set_free(ws) },
+                Owned::LazyTaskList(list) => unsafe { tc_lazy_task_list_free(list) },
+                Owned::String(mut tcstring) => unsafe { tc_string_free(&mut *tcstring) },
+                Owned::TaskList(mut list) => unsafe { tc_task_list_free(&mut *list) },
+                Owned::UuidList(mut list) => unsafe { tc_uuid_list_free(&mut *list) },
+                Owned::StringList(mut list) => unsafe { tc_string_list_free(&mut *list) },
+                Owned::KVList(mut list) => unsafe { tc_kv_list_free(&mut *list) },
+                Owned::AnnotationList(mut list) => unsafe { tc_annotation_list_free(&mut *list) },
+                Owned::UdaList(mut list) => unsafe { tc_uda_list_free(&mut *list) },
+            }
+        }
+    }
+}
+
+/// Utility function to add an object to a TCContext.
+fn adopt(ctx: *mut TCContext, owned: Owned) {
+    // SAFETY:
+    //  - ctx is not NULL (promised by caller)
+    //  - *ctx is a valid TCContext (promised by caller)
+    //  - ctx is not modified by anything else (not threadsafe)
+    let ctx: &mut TCContext = unsafe { TCContext::from_ptr_arg_ref_mut(ctx) };
+    ctx.owned.push(owned);
+}
+
+/// Create a new, empty TCContext.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_new() -> *mut TCContext {
+    // SAFETY:
+    //  - caller promises to free this value
+    unsafe { TCContext::return_ptr(TCContext { owned: Vec::new() }) }
+}
+
+/// Give a replica to the context, returning the same pointer.  If `rep` is NULL, nothing is
+/// adopted and NULL is returned, so this can be applied directly to the result of a constructor.
+///
+/// Tasks made mutable with this replica must be adopted after it, or freed before the context.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_replica(
+    ctx: *mut TCContext,
+    rep: *mut TCReplica,
+) -> *mut TCReplica {
+    if !rep.is_null() {
+        adopt(ctx, Owned::Replica(rep));
+    }
+    rep
+}
+
+/// Give a task to the context, returning the same pointer.  If `task` is NULL, nothing is adopted
+/// and NULL is returned.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_task(
+    ctx: *mut TCContext,
+    task: *mut TCTask,
+) -> *mut TCTask {
+    if !task.is_null() {
+        adopt(ctx, Owned::Task(task));
+    }
+    task
+}
+
+/// Give a server to the context, returning the same pointer.  If `server` is NULL, nothing is
+/// adopted and NULL is returned.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_server(
+    ctx: *mut TCContext,
+    server: *mut TCServer,
+) -> *mut TCServer {
+    if !server.is_null() {
+        adopt(ctx, Owned::Server(server));
+    }
+    server
+}
+
+/// Give a working set to the context, returning the same pointer.  If `ws` is NULL, nothing is
+/// adopted and NULL is returned.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_working_set(
+    ctx: *mut TCContext,
+    ws: *mut TCWorkingSet,
+) -> *mut TCWorkingSet {
+    if !ws.is_null() {
+        adopt(ctx, Owned::WorkingSet(ws));
+    }
+    ws
+}
+
+/// Give a lazy task list to the context, returning the same pointer.  If `list` is NULL, nothing
+/// is adopted and NULL is returned.
+///
+/// The list reads tasks from its replica, so it must be adopted after that replica.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_lazy_task_list(
+    ctx: *mut TCContext,
+    list: *mut TCLazyTaskList,
+) -> *mut TCLazyTaskList {
+    if !list.is_null() {
+        adopt(ctx, Owned::LazyTaskList(list));
+    }
+    list
+}
+
+/// Give a string to the context, returning a pointer to it that remains valid until the context
+/// is freed.  The pointer can be passed to functions that borrow a string, such as
+/// `tc_string_content`, but the string must not be passed by value to any function that takes
+/// ownership of it.  If the string is NULL, nothing is adopted and NULL is returned.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_string(
+    ctx: *mut TCContext,
+    tcstring: TCString,
+) -> *const TCString {
+    if tcstring.is_null() {
+        return std::ptr::null();
+    }
+    let tcstring = Box::new(tcstring);
+    let ptr: *const TCString = &*tcstring;
+    adopt(ctx, Owned::String(tcstring));
+    ptr
+}
+
+/// Give a task list to the context, returning a pointer to it that remains valid until the
+/// context is freed.  Items may be taken from the list with `tc_task_list_take`, after which they
+/// are owned by the caller and not by the context.  The list must not be freed by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_task_list(
+    ctx: *mut TCContext,
+    tasks: TCTaskList,
+) -> *mut TCTaskList {
+    let mut tasks = Box::new(tasks);
+    let ptr: *mut TCTaskList = &mut *tasks;
+    adopt(ctx, Owned::TaskList(tasks));
+    ptr
+}
+
+/// Give a UUID list to the context, returning a pointer to it that remains valid until the
+/// context is freed.  The list must not be freed by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_uuid_list(
+    ctx: *mut TCContext,
+    uuids: TCUuidList,
+) -> *const TCUuidList {
+    let uuids = Box::new(uuids);
+    let ptr: *const TCUuidList = &*uuids;
+    adopt(ctx, Owned::UuidList(uuids));
+    ptr
+}
+
+/// Give a string list to the context, returning a pointer to it that remains valid until the
+/// context is freed.  The list must not be freed by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_string_list(
+    ctx: *mut TCContext,
+    strings: TCStringList,
+) -> *const TCStringList {
+    let strings = Box::new(strings);
+    let ptr: *const TCStringList = &*strings;
+    adopt(ctx, Owned::StringList(strings));
+    ptr
+}
+
+/// Give a key/value list to the context, returning a pointer to it that remains valid until the
+/// context is freed.  The list must not be freed by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_kv_list(
+    ctx: *mut TCContext,
+    kvs: TCKVList,
+) -> *const TCKVList {
+    let kvs = Box::new(kvs);
+    let ptr: *const TCKVList = &*kvs;
+    adopt(ctx, Owned::KVList(kvs));
+    ptr
+}
+
+/// Give an annotation list to the context, returning a pointer to it that remains valid until the
+/// context is freed.  The list must not be freed by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_annotation_list(
+    ctx: *mut TCContext,
+    anns: TCAnnotationList,
+) -> *const TCAnnotationList {
+    let anns = Box::new(anns);
+    let ptr: *const TCAnnotationList = &*anns;
+    adopt(ctx, Owned::AnnotationList(anns));
+    ptr
+}
+
+/// Give a UDA list to the context, returning a pointer to it that remains valid until the
+/// context is freed.  The list must not be freed by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_adopt_uda_list(
+    ctx: *mut TCContext,
+    udas: TCUdaList,
+) -> *const TCUdaList {
+    let udas = Box::new(udas);
+    let ptr: *const TCUdaList = &*udas;
+    adopt(ctx, Owned::UdaList(udas));
+    ptr
+}
+
+/// Free a context, and every object it owns.  The context and its objects must not be used after
+/// this function returns, and the context must not be freed more than once.
+#[no_mangle]
+pub unsafe extern "C" fn tc_context_free(ctx: *mut TCContext) {
+    // SAFETY:
+    //  - ctx is not NULL (promised by caller)
+    //  - ctx is valid (promised by caller)
+    //  - caller will not use ctx after this call (promised by caller)
+    let ctx = unsafe { TCContext::take_from_ptr_arg(ctx) };
+    drop(ctx);
+}
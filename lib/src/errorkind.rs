@@ -0,0 +1,136 @@
+use taskchampion::Error;
+
+/// The kind of error from a TC operation, allowing C callers to react to categories of failure
+/// without parsing the error message.
+/// cbindgen:prefix-with-name
+/// cbindgen:rename-all=ScreamingSnakeCase
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TCErrorKind {
+    /// No error occurred.
+    None,
+    /// An error that does not fall into any of the other kinds.
+    Other,
+    /// An error reading or writing files, such as a missing directory or a full disk.
+    Io,
+    /// The task database is corrupt, or is not a task database.
+    CorruptData,
+    /// The sync server could not be reached.
+    SyncConnection,
+    /// The sync server rejected this replica, or its data could not be decrypted, typically
+    /// because the encryption secret is wrong.
+    SyncAuth,
+    /// The replica is too far out of date to be synchronized with the server.
+    OutOfSync,
+    /// An argument was invalid, such as a string that is not valid UTF-8.
+    InvalidArgument,
+    /// Synchronization was cancelled by the progress function.
+    Cancelled,
+}
+
+impl TCErrorKind {
+    /// Determine the kind of the given error, based on the first error in its chain that can be
+    /// categorized.
+    pub(crate) fn of(err: &anyhow::Error) -> TCErrorKind {
+        for cause in err.chain() {
+            if let Some(e) = cause.downcast_ref::<Error>() {
+                return match e {
+                    Error::OutOfSync => TCErrorKind::OutOfSync,
+                    Error::Decryption | Error::SyncAuth(_) => TCErrorKind::SyncAuth,
+                    Error::SyncConnection(_) => TCErrorKind::SyncConnection,
+                    Error::Database(_) | Error::Corruption(_) => TCErrorKind::CorruptData,
+                    Error::SyncCancelled => TCErrorKind::Cancelled,
+                    Error::InvalidTag(_) => TCErrorKind::InvalidArgument,
+                    // the underlying cause, later in the chain, may be more specific
+                    Error::Storage(_) | Error::Other(_) => continue,
+                    _ => TCErrorKind::Other,
+                };
+            }
+            if let Some(e) = cause.downcast_ref::<ureq::Error>() {
+                return match e {
+                    ureq::Error::Status(401, _) | ureq::Error::Status(403, _) => {
+                        TCErrorKind::SyncAuth
+                    }
+                    ureq::Error::Status(_, _) => TCErrorKind::Other,
+                    ureq::Error::Transport(_) => TCErrorKind::SyncConnection,
+                };
+            }
+            if let Some(rusqlite::Error::SqliteFailure(e, _)) =
+                cause.downcast_ref::<rusqlite::Error>()
+            {
+                use rusqlite::ErrorCode::*;
+                match e.code {
+                    DatabaseCorrupt | NotADatabase => return TCErrorKind::CorruptData,
+                    CannotOpen | SystemIoFailure | DiskFull | PermissionDenied | ReadOnly => {
+                        return TCErrorKind::Io
+                    }
+                    _ => {}
+                }
+            }
+            if cause.is::<std::io::Error>() {
+                return TCErrorKind::Io;
+            }
+            if cause.is::<std::str::Utf8Error>() {
+                return TCErrorKind::InvalidArgument;
+            }
+        }
+        TCErrorKind::Other
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anyhow::Context;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn kind_of_taskchampion_errors() {
+        assert_eq!(
+            TCErrorKind::of(&Error::OutOfSync.into()),
+            TCErrorKind::OutOfSync
+        );
+        assert_eq!(
+            TCErrorKind::of(&Error::Decryption.into()),
+            TCErrorKind::SyncAuth
+        );
+        assert_eq!(
+            TCErrorKind::of(&Error::Database("bad".into()).into()),
+            TCErrorKind::CorruptData
+        );
+        assert_eq!(
+            TCErrorKind::of(&Error::InvalidTag("+bad".into()).into()),
+            TCErrorKind::InvalidArgument
+        );
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        assert_eq!(
+            TCErrorKind::of(&Error::from(io_err).into()),
+            TCErrorKind::Io
+        );
+    }
+
+    #[test]
+    fn kind_of_context() {
+        let err: anyhow::Error = std::io::Error::new(std::io::ErrorKind::NotFound, "gone").into();
+        let err = err.context("while opening");
+        assert_eq!(TCErrorKind::of(&err), TCErrorKind::Io);
+    }
+
+    #[test]
+    fn kind_of_invalid_utf8() {
+        let bytes = vec![0xff, 0xfe];
+        let res = std::str::from_utf8(&bytes).context("invalid string");
+        assert_eq!(
+            TCErrorKind::of(&res.unwrap_err()),
+            TCErrorKind::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn kind_of_other() {
+        assert_eq!(
+            TCErrorKind::of(&anyhow::anyhow!("oops")),
+            TCErrorKind::Other
+        );
+    }
+}
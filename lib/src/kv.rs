@@ -78,6 +78,64 @@ impl CList for TCKVList {
     }
 }
 
+/// Get the value for the given key in a TCKVList, or NULL if the key is not present.  If the key
+/// appears more than once, the first matching value is returned.
+///
+/// The returned TCString is borrowed from the list: it must not be freed, and is only valid until
+/// the list is freed.  The list itself is not modified.
+///
+/// This function takes ownership of the `key` string.
+#[no_mangle]
+pub unsafe extern "C" fn tc_kv_list_get(tckvs: *mut TCKVList, key: TCString) -> *const TCString {
+    debug_assert!(!tckvs.is_null());
+    // SAFETY:
+    //  - key is a valid TCString (promised by caller)
+    //  - caller will not use key after this call (convention)
+    let key = unsafe { TCString::val_from_arg(key) };
+    // SAFETY:
+    //  - tckvs is not NULL and points to a valid TCKVList (promised by caller)
+    //  - the reference is dropped before this function returns
+    let tckvs = unsafe { &mut *tckvs };
+    for tckv in tckvs.slice().iter() {
+        // SAFETY:
+        //  - tckv.key is a valid TCString that is not NULL (field docstring)
+        //  - the slice is dropped at the end of this iteration
+        if unsafe { tckv.key.as_bytes() } == key.as_bytes() {
+            return &tckv.value;
+        }
+    }
+    std::ptr::null()
+}
+
+/// Get the keys of a TCKVList, in the order they appear in the list.  The list itself is not
+/// modified.
+///
+/// The caller must free the returned TCStringList instance.  The TCStringList instance does not
+/// depend on the TCKVList, which may be freed first.
+#[no_mangle]
+pub unsafe extern "C" fn tc_kv_list_keys(tckvs: *mut TCKVList) -> TCStringList {
+    debug_assert!(!tckvs.is_null());
+    // SAFETY:
+    //  - tckvs is not NULL and points to a valid TCKVList (promised by caller)
+    //  - the reference is dropped before this function returns
+    let tckvs = unsafe { &mut *tckvs };
+    let keys: Vec<TCString> = tckvs
+        .slice()
+        .iter()
+        .map(|tckv| {
+            // SAFETY:
+            //  - tckv.key is a valid TCString that is not NULL (field docstring)
+            let key = unsafe { tckv.key.as_bytes() }.to_vec();
+            // SAFETY:
+            //  - this TCString will be freed via tc_string_list_free.
+            unsafe { TCString::return_val(RustString::Bytes(key)) }
+        })
+        .collect();
+    // SAFETY:
+    //  - caller will free this list
+    unsafe { TCStringList::return_val(keys) }
+}
+
 /// Free a TCKVList instance.  The instance, and all TCKVs it contains, must not be used after
 /// this call.
 ///
@@ -94,6 +152,18 @@ pub unsafe extern "C" fn tc_kv_list_free(tckvs: *mut TCKVList) {
 #[cfg(test)]
 mod test {
     use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn make_list() -> TCKVList {
+        let vec = vec![
+            TCKV::as_ctype((RustString::from("status"), RustString::from("pending"))),
+            TCKV::as_ctype((
+                RustString::from("description"),
+                RustString::Bytes(b"my task".to_vec()),
+            )),
+        ];
+        unsafe { TCKVList::return_val(vec) }
+    }
 
     #[test]
     fn empty_list_has_non_null_pointer() {
@@ -112,4 +182,30 @@ mod test {
         assert_eq!(tckvs.len, 0);
         assert_eq!(tckvs._capacity, 0);
     }
+
+    #[test]
+    fn get() {
+        let mut tckvs = make_list();
+        let value =
+            unsafe { tc_kv_list_get(&mut tckvs, TCString::return_val("description".into())) };
+        assert!(!value.is_null());
+        assert_eq!(unsafe { (*value).as_bytes() }, b"my task");
+        let value = unsafe { tc_kv_list_get(&mut tckvs, TCString::return_val("due".into())) };
+        assert!(value.is_null());
+        unsafe { tc_kv_list_free(&mut tckvs) };
+    }
+
+    #[test]
+    fn keys() {
+        let mut tckvs = make_list();
+        let mut keys = unsafe { tc_kv_list_keys(&mut tckvs) };
+        unsafe { tc_kv_list_free(&mut tckvs) };
+        let collected: Vec<_> = keys
+            .slice()
+            .iter()
+            .map(|k| unsafe { k.as_bytes() }.to_vec())
+            .collect();
+        unsafe { crate::string::tc_string_list_free(&mut keys) };
+        assert_eq!(collected, vec![b"status".to_vec(), b"description".to_vec()]);
+    }
 }
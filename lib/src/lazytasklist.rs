@@ -0,0 +1,121 @@
+use crate::traits::*;
+use crate::types::*;
+use taskchampion::Uuid;
+
+/// A TCLazyTaskList represents the tasks in a replica, without reading them.  Each task is only
+/// read from the replica, and converted into a TCTask, when it is accessed with
+/// `tc_lazy_task_list_get`.  This avoids the cost of reading every task in a large replica when
+/// only some of them are needed, such as to display a page of tasks.
+///
+/// The list contains the tasks that existed when it was created, in no particular order.  A task
+/// deleted from the replica after that point remains in the list, but cannot be read.
+///
+/// # Safety
+///
+/// The `*TCLazyTaskList` returned from `tc_replica_all_tasks_lazy` is owned by the caller and
+/// must later be freed to avoid a memory leak.  It refers to the replica from which it was
+/// generated, so that replica must not be freed before the list.
+///
+/// Any function taking a `*TCLazyTaskList` requires:
+///  - the pointer must not be NUL;
+///  - the pointer must be one previously returned from `tc_replica_all_tasks_lazy`
+///  - the memory referenced by the pointer must never be accessed by C code; and
+///  - except for `tc_lazy_task_list_free`, ownership of a `*TCLazyTaskList` remains with the caller.
+///
+/// Once passed to `tc_lazy_task_list_free`, a `*TCLazyTaskList` becomes invalid and must not be
+/// used again.
+///
+/// TCLazyTaskList is not threadsafe.
+pub struct TCLazyTaskList {
+    /// The replica from which tasks are read
+    rep: *mut TCReplica,
+
+    /// The UUIDs of the tasks in the list
+    uuids: Vec<Uuid>,
+}
+
+impl PassByPointer for TCLazyTaskList {}
+
+impl TCLazyTaskList {
+    /// Create a new list of the given tasks in the given replica.
+    ///
+    /// # Safety
+    ///
+    /// The replica must outlive the list.
+    pub(crate) unsafe fn new(rep: *mut TCReplica, uuids: Vec<Uuid>) -> TCLazyTaskList {
+        TCLazyTaskList { rep, uuids }
+    }
+}
+
+/// Utility function to get a shared reference to the TCLazyTaskList.
+fn wrap<T, F>(list: *mut TCLazyTaskList, f: F) -> T
+where
+    F: FnOnce(&TCLazyTaskList) -> T,
+{
+    // SAFETY:
+    //  - list is not null (promised by caller)
+    //  - list outlives 'a (promised by caller)
+    let list: &TCLazyTaskList = unsafe { TCLazyTaskList::from_ptr_arg_ref(list) };
+    f(list)
+}
+
+/// Get the number of tasks in the list.
+#[no_mangle]
+pub unsafe extern "C" fn tc_lazy_task_list_len(list: *mut TCLazyTaskList) -> usize {
+    wrap(list, |list| list.uuids.len())
+}
+
+/// Get the UUID of the task at the given index, without reading the task.  Returns true if the
+/// index is in bounds.  If not, returns false and does not change uuid_out.
+#[no_mangle]
+pub unsafe extern "C" fn tc_lazy_task_list_uuid(
+    list: *mut TCLazyTaskList,
+    index: usize,
+    uuid_out: *mut TCUuid,
+) -> bool {
+    debug_assert!(!uuid_out.is_null());
+    wrap(list, |list| {
+        if let Some(uuid) = list.uuids.get(index) {
+            // SAFETY:
+            //  - uuid_out is not NULL (promised by caller)
+            //  - alignment is not required
+            unsafe { TCUuid::val_to_arg_out(*uuid, uuid_out) };
+            true
+        } else {
+            false
+        }
+    })
+}
+
+/// Read the task at the given index from the replica.  The returned task is owned by the caller,
+/// just as if it had been returned from `tc_replica_get_task`, and each call returns a new TCTask.
+///
+/// Returns NULL if the index is out of bounds, if the task no longer exists, or on error.  Errors
+/// are reported by `tc_replica_error` for the list's replica, which must not have a mutable task
+/// at the time of this call.
+#[no_mangle]
+pub unsafe extern "C" fn tc_lazy_task_list_get(
+    list: *mut TCLazyTaskList,
+    index: usize,
+) -> *mut TCTask {
+    let (rep, uuid) = wrap(list, |list| (list.rep, list.uuids.get(index).copied()));
+    if let Some(uuid) = uuid {
+        // SAFETY:
+        //  - rep is a valid TCReplica (list must not outlive its replica)
+        //  - uuid is a valid TCUuid and need not be freed
+        unsafe { crate::replica::tc_replica_get_task(rep, TCUuid::return_val(uuid)) }
+    } else {
+        std::ptr::null_mut()
+    }
+}
+
+/// Free a TCLazyTaskList.  The list may not be used after this function returns and must not be
+/// freed more than once.  Tasks returned from `tc_lazy_task_list_get` are not affected.
+#[no_mangle]
+pub unsafe extern "C" fn tc_lazy_task_list_free(list: *mut TCLazyTaskList) {
+    // SAFETY:
+    //  - list is valid and not NULL (promised by caller)
+    //  - caller will not use list after this call (promised by caller)
+    let list = unsafe { TCLazyTaskList::take_from_ptr_arg(list) };
+    drop(list);
+}
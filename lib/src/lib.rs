@@ -20,8 +20,14 @@ pub mod annotation;
 pub use annotation::*;
 pub mod atomic;
 pub use atomic::*;
+pub mod context;
+pub use context::*;
+pub mod errorkind;
+pub use errorkind::*;
 pub mod kv;
 pub use kv::*;
+pub mod lazytasklist;
+pub use lazytasklist::*;
 pub mod replica;
 pub use replica::*;
 pub mod result;
@@ -43,10 +49,12 @@ pub use workingset::*;
 
 pub(crate) mod types {
     pub(crate) use crate::annotation::{TCAnnotation, TCAnnotationList};
+    pub(crate) use crate::errorkind::TCErrorKind;
     pub(crate) use crate::kv::{TCKVList, TCKV};
+    pub(crate) use crate::lazytasklist::TCLazyTaskList;
     pub(crate) use crate::replica::TCReplica;
     pub(crate) use crate::result::TCResult;
-    pub(crate) use crate::server::TCServer;
+    pub(crate) use crate::server::{TCServer, TCSyncPhase, TCSyncProgressFn};
     pub(crate) use crate::status::TCStatus;
     pub(crate) use crate::string::{RustString, TCString, TCStringList};
     pub(crate) use crate::task::{TCTask, TCTaskList};
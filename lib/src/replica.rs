@@ -1,7 +1,10 @@
 use crate::traits::*;
 use crate::types::*;
 use crate::util::err_to_ruststring;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
 use std::ptr::NonNull;
+use taskchampion::storage::TaskMap;
 use taskchampion::{Replica, StorageConfig};
 
 /// A replica represents an instance of a user's task data, providing an easy interface
@@ -10,7 +13,8 @@ use taskchampion::{Replica, StorageConfig};
 /// # Error Handling
 ///
 /// When a `tc_replica_..` function that returns a TCResult returns TC_RESULT_ERROR, then
-/// `tc_replica_error` will return the error message.
+/// `tc_replica_error` will return the error message, and `tc_replica_error_kind` the kind of
+/// error.
 ///
 /// # Safety
 ///
@@ -25,7 +29,10 @@ use taskchampion::{Replica, StorageConfig};
 ///
 /// Once passed to `tc_replica_free`, a `*TCReplica` becomes invalid and must not be used again.
 ///
-/// TCReplicas are not threadsafe.
+/// TCReplicas are not threadsafe: a TCReplica, and the TCTasks and other values obtained from it,
+/// must only be used by one thread at a time.  To use the same on-disk task database from
+/// several threads, such as to synchronize in a background thread, give each thread its own
+/// handle with `tc_replica_clone_handle`.
 pub struct TCReplica {
     /// The wrapped Replica
     inner: Replica,
@@ -35,6 +42,12 @@ pub struct TCReplica {
 
     /// The error from the most recent operation, if any
     error: Option<RustString<'static>>,
+
+    /// The kind of error from the most recent operation
+    error_kind: TCErrorKind,
+
+    /// The directory containing the task database, if it is on disk
+    taskdb_dir: Option<PathBuf>,
 }
 
 impl PassByPointer for TCReplica {}
@@ -64,6 +77,8 @@ impl From<Replica> for TCReplica {
             inner: rep,
             mut_borrowed: false,
             error: None,
+            error_kind: TCErrorKind::None,
+            taskdb_dir: None,
         }
     }
 }
@@ -85,9 +100,11 @@ where
         panic!("replica is borrowed and cannot be used");
     }
     rep.error = None;
+    rep.error_kind = TCErrorKind::None;
     match f(&mut rep.inner) {
         Ok(v) => v,
         Err(e) => {
+            rep.error_kind = TCErrorKind::of(&e);
             rep.error = Some(err_to_ruststring(e));
             err_value
         }
@@ -148,23 +165,80 @@ pub unsafe extern "C" fn tc_replica_new_on_disk(
             //  - path is valid (promised by caller)
             //  - caller will not use path after this call (convention)
             let mut path = unsafe { TCString::val_from_arg(path) };
-            let storage = StorageConfig::OnDisk {
-                taskdb_dir: path.to_path_buf_mut()?,
-            }
-            .into_storage()?;
+            let taskdb_dir = path.to_path_buf_mut()?;
+            let rep = new_on_disk(taskdb_dir)?;
+
+            // SAFETY:
+            // - caller promises to free this value
+            Ok(unsafe { rep.return_ptr() })
+        },
+        error_out,
+        std::ptr::null_mut(),
+    )
+}
+
+/// Create a new handle to the same on-disk task database as the given replica.  The new handle
+/// is independent of the original: it may be used on another thread while the original is in
+/// use, and must be freed separately.
+///
+/// Every operation on the database, even one that only reads, locks it, and an operation waits
+/// up to five seconds for an operation through another handle to complete.  After that, it
+/// fails with TC_ERROR_KIND_OTHER, and an error message saying that the database is locked.
+/// A sync holds the lock for its whole duration, including its requests to the server, so
+/// operations on other handles during a sync that takes longer than five seconds fail, and
+/// should be retried once the sync completes.
+///
+/// Changes made through one handle are visible through the other once the operation making them
+/// completes, but values such as TCTasks and TCWorkingSets are not updated; fetch them again to
+/// see the changes.
+///
+/// Replicas with in-memory databases cannot be cloned.  On error, a string is written to the
+/// error_out parameter (if it is not NULL) and NULL is returned.  The caller must free this
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn tc_replica_clone_handle(
+    rep: *mut TCReplica,
+    error_out: *mut TCString,
+) -> *mut TCReplica {
+    debug_assert!(!rep.is_null());
+    // SAFETY:
+    //  - rep is not NULL (promised by caller)
+    //  - *rep is a valid TCReplica (promised by caller)
+    //  - rep is valid for the duration of this function
+    //  - rep is not modified by anything else (not threadsafe)
+    let rep: &TCReplica = unsafe { TCReplica::from_ptr_arg_ref(rep) };
+    let taskdb_dir = rep.taskdb_dir.clone();
+    wrap_constructor(
+        || {
+            let taskdb_dir = taskdb_dir
+                .ok_or_else(|| anyhow::anyhow!("an in-memory replica cannot be cloned"))?;
+            let rep = new_on_disk(taskdb_dir)?;
 
             // SAFETY:
             // - caller promises to free this value
-            Ok(unsafe { TCReplica::from(Replica::new(storage)).return_ptr() })
+            Ok(unsafe { rep.return_ptr() })
         },
         error_out,
         std::ptr::null_mut(),
     )
 }
 
-/// Get a list of all tasks in the replica.
+/// Create a TCReplica with an on-disk database in the given directory.
+fn new_on_disk(taskdb_dir: PathBuf) -> anyhow::Result<TCReplica> {
+    let storage = StorageConfig::OnDisk {
+        taskdb_dir: taskdb_dir.clone(),
+    }
+    .into_storage()?;
+    let mut rep = TCReplica::from(Replica::new(storage));
+    rep.taskdb_dir = Some(taskdb_dir);
+    Ok(rep)
+}
+
+/// Get a list of all tasks in the replica, regardless of their status.
 ///
 /// Returns a TCTaskList with a NULL items field on error.
+///
+/// The caller must free the list, and any tasks not taken from it, with `tc_task_list_free`.
 #[no_mangle]
 pub unsafe extern "C" fn tc_replica_all_tasks(rep: *mut TCReplica) -> TCTaskList {
     wrap(
@@ -195,6 +269,31 @@ pub unsafe extern "C" fn tc_replica_all_tasks(rep: *mut TCReplica) -> TCTaskList
     )
 }
 
+/// Get a lazy list of all tasks in the replica, regardless of their status.  Unlike
+/// `tc_replica_all_tasks`, this does not read the tasks; each is read when it is accessed with
+/// `tc_lazy_task_list_get`.
+///
+/// Returns NULL on error.
+///
+/// The caller must free the list with `tc_lazy_task_list_free`, before freeing the replica.
+#[no_mangle]
+pub unsafe extern "C" fn tc_replica_all_tasks_lazy(rep: *mut TCReplica) -> *mut TCLazyTaskList {
+    let tcrep = rep;
+    wrap(
+        rep,
+        |rep| {
+            let uuids = rep.all_task_uuids()?;
+            // SAFETY:
+            // - the replica outlives the list (promised by caller)
+            let list = unsafe { TCLazyTaskList::new(tcrep, uuids) };
+            // SAFETY:
+            // - caller promises to free this list
+            Ok(unsafe { list.return_ptr() })
+        },
+        std::ptr::null_mut(),
+    )
+}
+
 /// Get a list of all uuids for tasks in the replica.
 ///
 /// Returns a TCUuidList with a NULL items field on error.
@@ -220,6 +319,39 @@ pub unsafe extern "C" fn tc_replica_all_task_uuids(rep: *mut TCReplica) -> TCUui
     )
 }
 
+/// Get a list of the distinct user tags of all tasks in the replica, in sorted order.  Synthetic
+/// tags such as `PENDING` are not included.
+///
+/// Returns a TCStringList with a NULL items field on error.
+///
+/// The caller must free the list with `tc_string_list_free`.
+#[no_mangle]
+pub unsafe extern "C" fn tc_replica_all_tags(rep: *mut TCReplica) -> TCStringList {
+    wrap(
+        rep,
+        |rep| {
+            let mut tags = BTreeSet::new();
+            for task in rep.all_tasks()?.values() {
+                tags.extend(
+                    task.get_tags()
+                        .filter(|t| t.is_user())
+                        .map(|t| t.to_string()),
+                );
+            }
+            let tags: Vec<_> = tags
+                .into_iter()
+                // SAFETY:
+                //  - this TCString will be freed via tc_string_list_free.
+                .map(|t| unsafe { TCString::return_val(t.into()) })
+                .collect();
+            // SAFETY:
+            //  - value will be freed (promised by caller)
+            Ok(unsafe { TCStringList::return_val(tags) })
+        },
+        TCStringList::null_value(),
+    )
+}
+
 /// Get the current working set for this replica.  The resulting value must be freed
 /// with tc_working_set_free.
 ///
@@ -263,6 +395,58 @@ pub unsafe extern "C" fn tc_replica_get_task(rep: *mut TCReplica, tcuuid: TCUuid
     )
 }
 
+/// Create a new task with the given status and description, and with the given additional
+/// properties, in a single transaction.  This is useful for importing tasks, as it avoids the
+/// overhead of setting each property separately.
+///
+/// The properties are given as an array of `kvs_len` TCKV values, using the keys of the task
+/// data model (as returned from `tc_task_get_taskmap`).  The `status` and `description` keys are
+/// ignored.  The `entry` and `modified` properties default to the current time.  Ownership of
+/// the strings in the TCKV values passes to this function, but the array itself remains owned by
+/// the caller.  `kvs` may be NULL if `kvs_len` is zero.
+///
+/// Returns the task, or NULL on error.
+#[no_mangle]
+pub unsafe extern "C" fn tc_replica_new_task_full(
+    rep: *mut TCReplica,
+    status: TCStatus,
+    description: TCString,
+    kvs: *mut TCKV,
+    kvs_len: usize,
+) -> *mut TCTask {
+    // SAFETY:
+    //  - description is valid (promised by caller)
+    //  - caller will not use description after this call (convention)
+    let mut description = unsafe { TCString::val_from_arg(description) };
+    debug_assert!(!kvs.is_null() || kvs_len == 0);
+    let kvs: Vec<_> = (0..kvs_len)
+        .map(|i| {
+            // SAFETY:
+            //  - kvs points to an array of at least kvs_len valid TCKVs (promised by caller)
+            //  - caller will not use the strings in the TCKV after this call (convention)
+            unsafe { TCKV::val_from_arg(std::ptr::read(kvs.add(i))) }
+        })
+        .collect();
+    wrap(
+        rep,
+        |rep| {
+            let mut properties = TaskMap::new();
+            for (mut key, mut value) in kvs {
+                properties.insert(key.as_str()?.to_string(), value.as_str()?.to_string());
+            }
+            let task = rep.new_task_with_properties(
+                status.into(),
+                description.as_str()?.to_string(),
+                properties,
+            )?;
+            // SAFETY:
+            // - caller promises to free this task
+            Ok(unsafe { TCTask::from(task).return_ptr() })
+        },
+        std::ptr::null_mut(),
+    )
+}
+
 /// Create a new task.  The task must not already exist.
 ///
 /// Returns the task, or NULL on error.
@@ -338,16 +522,54 @@ pub unsafe extern "C" fn tc_replica_sync(
     )
 }
 
+/// Synchronize this replica with a server, like `tc_replica_sync`, calling the given function to
+/// report progress.  The `context` pointer is passed to the function unchanged.
+///
+/// If the function returns false, synchronization is cancelled: TC_RESULT_ERROR is returned,
+/// `tc_replica_error_kind` returns TC_ERROR_KIND_CANCELLED, and the replica is unchanged.  This
+/// is not possible in the TC_SYNC_PHASE_SENDING_SNAPSHOT phase, when the server has already
+/// accepted the new version; returning false then only skips the snapshot.
+///
+/// The `server` argument remains owned by the caller, and must be freed explicitly.
+#[no_mangle]
+pub unsafe extern "C" fn tc_replica_sync_with_progress(
+    rep: *mut TCReplica,
+    server: *mut TCServer,
+    avoid_snapshots: bool,
+    progress: TCSyncProgressFn,
+    context: *mut libc::c_void,
+) -> TCResult {
+    wrap(
+        rep,
+        |rep| {
+            debug_assert!(!server.is_null());
+            // SAFETY:
+            //  - server is not NULL
+            //  - *server is a valid TCServer (promised by caller)
+            //  - server is valid for the lifetime of tc_replica_sync_with_progress (not threadsafe)
+            //  - server will not be accessed simultaneously (not threadsafe)
+            let server = unsafe { TCServer::from_ptr_arg_ref_mut(server) };
+            rep.sync_with_progress(server.as_mut(), avoid_snapshots, &mut |p| {
+                let (phase, count) = TCSyncPhase::of(p);
+                progress(context, phase, count)
+            })?;
+            Ok(TCResult::Ok)
+        },
+        TCResult::Error,
+    )
+}
+
 /// Undo local operations until the most recent UndoPoint.
 ///
-/// If undone_out is not NULL, then on success it is set to 1 if operations were undone, or 0 if
-/// there are no operations that can be done.
+/// If undone_out is not NULL, then on success it is set to the number of operations that were
+/// undone, or 0 if there are no operations that can be undone.
 #[no_mangle]
 pub unsafe extern "C" fn tc_replica_undo(rep: *mut TCReplica, undone_out: *mut i32) -> TCResult {
     wrap(
         rep,
         |rep| {
-            let undone = if rep.undo()? { 1 } else { 0 };
+            let num_ops = rep.get_undo_ops(1)?.len();
+            let undone = if rep.undo()? { num_ops as i32 } else { 0 };
             if !undone_out.is_null() {
                 // SAFETY:
                 //  - undone_out is not NULL (just checked)
@@ -360,7 +582,8 @@ pub unsafe extern "C" fn tc_replica_undo(rep: *mut TCReplica, undone_out: *mut i
     )
 }
 
-/// Get the number of local, un-synchronized operations, or -1 on error
+/// Get the number of local, un-synchronized operations (including undo points), or -1 on error.
+/// This can be used to warn the user about changes that have not yet been synchronized.
 #[no_mangle]
 pub unsafe extern "C" fn tc_replica_num_local_operations(rep: *mut TCReplica) -> i64 {
     wrap(
@@ -407,6 +630,25 @@ pub unsafe extern "C" fn tc_replica_rebuild_working_set(
     )
 }
 
+/// Set whether the working set is rebuilt automatically after each sync.  This is enabled by
+/// default.  When disabled, the working set changes only when tasks become pending or when
+/// `tc_replica_rebuild_working_set` is called, so the caller controls when working-set indices
+/// change.
+#[no_mangle]
+pub unsafe extern "C" fn tc_replica_set_auto_rebuild_working_set(
+    rep: *mut TCReplica,
+    auto_rebuild: bool,
+) -> TCResult {
+    wrap(
+        rep,
+        |rep| {
+            rep.set_auto_rebuild_working_set(auto_rebuild);
+            Ok(TCResult::Ok)
+        },
+        TCResult::Error,
+    )
+}
+
 /// Get the latest error for a replica, or a string with NULL ptr if no error exists.  Subsequent
 /// calls to this function will return NULL.  The rep pointer must not be NULL.  The caller must
 /// free the returned string.
@@ -427,6 +669,20 @@ pub unsafe extern "C" fn tc_replica_error(rep: *mut TCReplica) -> TCString {
     }
 }
 
+/// Get the kind of the latest error for a replica, or TC_ERROR_KIND_NONE if the most recent
+/// operation succeeded.  Unlike `tc_replica_error`, this does not clear the error, so the two may
+/// be called in either order.  The rep pointer must not be NULL.
+#[no_mangle]
+pub unsafe extern "C" fn tc_replica_error_kind(rep: *mut TCReplica) -> TCErrorKind {
+    // SAFETY:
+    //  - rep is not NULL (promised by caller)
+    //  - *rep is a valid TCReplica (promised by caller)
+    //  - rep is valid for the duration of this function
+    //  - rep is not modified by anything else (not threadsafe)
+    let rep: &TCReplica = unsafe { TCReplica::from_ptr_arg_ref(rep) };
+    rep.error_kind
+}
+
 /// Free a replica.  The replica may not be used after this function returns and must not be freed
 /// more than once.
 #[no_mangle]
@@ -1,7 +1,7 @@
 use crate::traits::*;
 use crate::types::*;
 use crate::util::err_to_ruststring;
-use taskchampion::{Server, ServerConfig};
+use taskchampion::{Server, ServerConfig, SyncProgress};
 
 /// TCServer represents an interface to a sync server.  Aside from new and free, a server
 /// has no C-accessible API, but is designed to be passed to `tc_replica_sync`.
@@ -25,6 +25,49 @@ impl AsMut<Box<dyn Server>> for TCServer {
     }
 }
 
+/// The phase of a synchronization, as reported to a TCSyncProgressFn.
+/// cbindgen:prefix-with-name
+/// cbindgen:rename-all=ScreamingSnakeCase
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TCSyncPhase {
+    /// A snapshot of the task database is being downloaded and applied.  This occurs only for an
+    /// empty replica.
+    ApplyingSnapshot,
+    /// Versions are being downloaded from the server and applied.  The count is the number of
+    /// versions applied so far.
+    Pulling,
+    /// A new version of local changes is being sent to the server.  The count is the number of
+    /// local operations it contains.
+    Pushing,
+    /// A snapshot of the task database is being sent to the server.  The server has already
+    /// accepted the new version, so returning false skips the snapshot but does not cancel.
+    SendingSnapshot,
+}
+
+/// A function called to report the progress of `tc_replica_sync_with_progress`.  It is given the
+/// context pointer passed to that function, the current phase, and a count whose meaning depends
+/// on the phase (see TCSyncPhase), or zero.  The server does not indicate how many versions
+/// remain to be downloaded, so the total amount of work is not known in advance.
+///
+/// The function should return true to continue synchronizing, or false to cancel.  The function
+/// is called on the thread that called `tc_replica_sync_with_progress`, and must not use the
+/// replica being synchronized.
+pub type TCSyncProgressFn =
+    extern "C" fn(context: *mut libc::c_void, phase: TCSyncPhase, count: usize) -> bool;
+
+impl TCSyncPhase {
+    /// Split a progress report into its phase and count.
+    pub(crate) fn of(progress: SyncProgress) -> (TCSyncPhase, usize) {
+        match progress {
+            SyncProgress::ApplyingSnapshot => (TCSyncPhase::ApplyingSnapshot, 0),
+            SyncProgress::Pulling { versions } => (TCSyncPhase::Pulling, versions),
+            SyncProgress::Pushing { operations } => (TCSyncPhase::Pushing, operations),
+            SyncProgress::SendingSnapshot => (TCSyncPhase::SendingSnapshot, 0),
+        }
+    }
+}
+
 /// Utility function to allow using `?` notation to return an error value.  
 fn wrap<T, F>(f: F, error_out: *mut TCString, err_value: T) -> T
 where
@@ -17,7 +17,8 @@ use std::path::PathBuf;
 /// # UTF-8
 ///
 /// TaskChampion expects all strings to be valid UTF-8. `tc_string_…` functions will fail if given
-/// a `*TCString` containing invalid UTF-8.
+/// a `*TCString` containing invalid UTF-8.  Use `tc_string_is_valid_utf8` to check a string, and
+/// `tc_string_content_lossy` to access a string that may contain invalid UTF-8.
 ///
 /// # Safety
 ///
@@ -102,6 +103,31 @@ impl TCString {
     pub(crate) fn is_null(&self) -> bool {
         self.ptr.is_null()
     }
+
+    /// Get the content of this string as bytes, without taking ownership of it.
+    ///
+    /// # Safety
+    ///
+    /// - self must be a valid TCString that is not NULL
+    /// - the returned slice must not be used after the TCString is modified or freed
+    pub(crate) unsafe fn as_bytes(&self) -> &[u8] {
+        debug_assert!(!self.is_null());
+        match self.ty {
+            ty if ty == TCStringType::CString as u8 || ty == TCStringType::CStr as u8 => {
+                // SAFETY:
+                //  - ptr points to a NUL-terminated string (see TCStringType)
+                //  - data is not modified for the returned lifetime (promised by caller)
+                unsafe { CStr::from_ptr(self.ptr as *const c_char) }.to_bytes()
+            }
+            ty if ty == TCStringType::String as u8 || ty == TCStringType::Bytes as u8 => {
+                // SAFETY:
+                //  - ptr and len came from a String or Vec<u8>, so the slice is valid
+                //  - data is not modified for the returned lifetime (promised by caller)
+                unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+            }
+            _ => unreachable!(),
+        }
+    }
 }
 
 #[derive(PartialEq, Debug)]
@@ -262,6 +288,16 @@ impl<'a> RustString<'a> {
         }
     }
 
+    /// Replace any invalid UTF-8 sequences in the RustString, in place, with U+FFFD REPLACEMENT
+    /// CHARACTER.  Valid strings are unchanged; otherwise the RustString has variant
+    /// RustString::String on return.
+    fn replace_invalid_utf8(&mut self) {
+        if std::str::from_utf8(self.as_bytes()).is_err() {
+            let string = String::from_utf8_lossy(self.as_bytes()).into_owned();
+            *self = RustString::String(string);
+        }
+    }
+
     /// Convert the RustString, in place, into one of the C variants.  If this is not
     /// possible, such as if the string contains an embedded NUL, then the string
     /// remains unchanged.
@@ -546,6 +582,60 @@ pub unsafe extern "C" fn tc_string_content_with_len(
     }
 }
 
+/// Get the content of the string as a regular C string, replacing any invalid UTF-8 sequences with
+/// U+FFFD REPLACEMENT CHARACTER.  The given string must be valid.  The returned value is NULL only
+/// if the string contains NUL bytes.  The returned C string is valid until the TCString is freed or
+/// passed to another TC API function.
+///
+/// The replacement is made in place, so the original bytes of an invalid string are not available
+/// from the TCString after this call.  Use `tc_string_content_with_len` first if they are needed.
+///
+/// This function takes the TCString by pointer because it may be modified in-place.  The pointer
+/// must not be NULL.
+///
+/// This function does _not_ take ownership of the TCString.
+#[no_mangle]
+pub unsafe extern "C" fn tc_string_content_lossy(tcstring: *const TCString) -> *const libc::c_char {
+    // SAFETY;
+    //  - tcstring is not NULL (promised by caller)
+    //  - *tcstring is valid (promised by caller)
+    //  - *tcstring is not accessed concurrently (single-threaded)
+    unsafe {
+        wrap(tcstring, |rstring| {
+            rstring.replace_invalid_utf8();
+
+            // the string is now valid, so this will not fail
+            let _ = rstring.bytes_to_string();
+            rstring.string_to_cstring();
+
+            match &rstring {
+                RustString::CString(cstring) => cstring.as_ptr(),
+                RustString::String(_) => std::ptr::null(), // string_to_cstring failed
+                RustString::CStr(cstr) => cstr.as_ptr(),
+                RustString::Bytes(_) => unreachable!(), // eliminated above
+                RustString::Null => unreachable!(),
+            }
+        })
+    }
+}
+
+/// Determine whether the string contains valid UTF-8.  Strings for which this returns false can
+/// still be accessed with `tc_string_content_with_len` or `tc_string_content_lossy`, but will be
+/// rejected by functions that require text, such as `tc_task_set_description`.  The given string
+/// must be valid and must not be NULL.
+///
+/// This function does _not_ take ownership of the TCString.
+#[no_mangle]
+pub unsafe extern "C" fn tc_string_is_valid_utf8(tcstring: *const TCString) -> bool {
+    debug_assert!(!tcstring.is_null());
+    // SAFETY:
+    //  - tcstring is not NULL (promised by caller)
+    //  - *tcstring is valid (promised by caller)
+    //  - the slice is dropped before this function returns
+    let bytes = unsafe { (*tcstring).as_bytes() };
+    std::str::from_utf8(bytes).is_ok()
+}
+
 /// Free a TCString.  The given string must not be NULL.  The string must not be used
 /// after this function returns, and must not be freed more than once.
 #[no_mangle]
@@ -675,6 +765,24 @@ mod test {
         assert_eq!(make_invalid_bytes().as_bytes(), INVALID_UTF8);
     }
 
+    #[test]
+    fn invalid_bytes_replace_invalid_utf8() {
+        let mut tcstring = make_invalid_bytes();
+        tcstring.replace_invalid_utf8();
+        assert_eq!(tcstring, RustString::String("abc\u{fffd}(\u{fffd}(".into()));
+    }
+
+    #[test]
+    fn valid_replace_invalid_utf8() {
+        let mut tcstring = make_cstr();
+        tcstring.replace_invalid_utf8();
+        assert_eq!(tcstring, make_cstr()); // unchanged
+
+        let mut tcstring = make_bytes();
+        tcstring.replace_invalid_utf8();
+        assert_eq!(tcstring, make_bytes()); // unchanged
+    }
+
     #[test]
     fn cstring_string_to_cstring() {
         let mut tcstring = make_cstring();
@@ -165,7 +165,7 @@ impl TryFrom<RustString<'static>> for Tag {
 
     fn try_from(mut rstring: RustString) -> Result<Tag, anyhow::Error> {
         let tagstr = rstring.as_str()?;
-        Tag::from_str(tagstr)
+        Ok(Tag::from_str(tagstr)?)
     }
 }
 
@@ -324,6 +324,24 @@ pub unsafe extern "C" fn tc_task_get_modified(task: *mut TCTask) -> libc::time_t
     wrap(task, |task| libc::time_t::as_ctype(task.get_modified()))
 }
 
+/// Get the due timestamp for a task, or 0 if not set.
+#[no_mangle]
+pub unsafe extern "C" fn tc_task_get_due(task: *mut TCTask) -> libc::time_t {
+    wrap(task, |task| libc::time_t::as_ctype(task.get_due()))
+}
+
+/// Get the scheduled timestamp for a task, or 0 if not set.
+#[no_mangle]
+pub unsafe extern "C" fn tc_task_get_scheduled(task: *mut TCTask) -> libc::time_t {
+    wrap(task, |task| libc::time_t::as_ctype(task.get_scheduled()))
+}
+
+/// Get the end timestamp for a task (when it was completed or deleted), or 0 if not set.
+#[no_mangle]
+pub unsafe extern "C" fn tc_task_get_end(task: *mut TCTask) -> libc::time_t {
+    wrap(task, |task| libc::time_t::as_ctype(task.get_end()))
+}
+
 /// Check if a task is waiting.
 #[no_mangle]
 pub unsafe extern "C" fn tc_task_is_waiting(task: *mut TCTask) -> bool {
@@ -376,7 +394,7 @@ pub unsafe extern "C" fn tc_task_get_tags(task: *mut TCTask) -> TCStringList {
 
 /// Get the annotations for the task.
 ///
-/// The caller must free the returned TCAnnotationList instance.  The TCStringList instance does not
+/// The caller must free the returned TCAnnotationList instance.  The TCAnnotationList instance does not
 /// reference the task and the two may be freed in any order.
 #[no_mangle]
 pub unsafe extern "C" fn tc_task_get_annotations(task: *mut TCTask) -> TCAnnotationList {
@@ -443,7 +461,9 @@ pub unsafe extern "C" fn tc_task_get_legacy_uda(task: *mut TCTask, key: TCString
 
 /// Get all UDAs for this task.
 ///
-/// Legacy UDAs are represented with an empty string in the ns field.
+/// Each key is split into a namespace and key on the first `.` character.  Legacy UDAs, without
+/// a `.`, are represented with an empty string in the ns field.  The caller must free the
+/// returned list.
 #[no_mangle]
 pub unsafe extern "C" fn tc_task_get_udas(task: *mut TCTask) -> TCUdaList {
     wrap(task, |task| {
@@ -556,6 +576,52 @@ pub unsafe extern "C" fn tc_task_set_wait(task: *mut TCTask, wait: libc::time_t)
     )
 }
 
+/// Set a mutable task's due timestamp.  Pass due=0 to unset the due field.
+#[no_mangle]
+pub unsafe extern "C" fn tc_task_set_due(task: *mut TCTask, due: libc::time_t) -> TCResult {
+    wrap_mut(
+        task,
+        |task| {
+            // SAFETY: any time_t value is a valid timestamp
+            task.set_due(unsafe { due.from_ctype() })?;
+            Ok(TCResult::Ok)
+        },
+        TCResult::Error,
+    )
+}
+
+/// Set a mutable task's scheduled timestamp.  Pass scheduled=0 to unset the scheduled field.
+#[no_mangle]
+pub unsafe extern "C" fn tc_task_set_scheduled(
+    task: *mut TCTask,
+    scheduled: libc::time_t,
+) -> TCResult {
+    wrap_mut(
+        task,
+        |task| {
+            // SAFETY: any time_t value is a valid timestamp
+            task.set_scheduled(unsafe { scheduled.from_ctype() })?;
+            Ok(TCResult::Ok)
+        },
+        TCResult::Error,
+    )
+}
+
+/// Set a mutable task's end timestamp.  This is set automatically when the task is completed or
+/// deleted, so this is only needed to override that time.  Pass end=0 to unset the end field.
+#[no_mangle]
+pub unsafe extern "C" fn tc_task_set_end(task: *mut TCTask, end: libc::time_t) -> TCResult {
+    wrap_mut(
+        task,
+        |task| {
+            // SAFETY: any time_t value is a valid timestamp
+            task.set_end(unsafe { end.from_ctype() })?;
+            Ok(TCResult::Ok)
+        },
+        TCResult::Error,
+    )
+}
+
 /// Set a mutable task's modified timestamp.  The value cannot be zero.
 #[no_mangle]
 pub unsafe extern "C" fn tc_task_set_modified(
@@ -727,7 +793,7 @@ pub unsafe extern "C" fn tc_task_set_uda(
     )
 }
 
-/// Remove a UDA fraom a mutable task.
+/// Remove a UDA from a mutable task.
 #[no_mangle]
 pub unsafe extern "C" fn tc_task_remove_uda(
     task: *mut TCTask,
@@ -773,7 +839,7 @@ pub unsafe extern "C" fn tc_task_set_legacy_uda(
     )
 }
 
-/// Remove a UDA fraom a mutable task.
+/// Remove a legacy UDA from a mutable task.
 #[no_mangle]
 pub unsafe extern "C" fn tc_task_remove_legacy_uda(task: *mut TCTask, key: TCString) -> TCResult {
     // safety:
@@ -790,7 +856,9 @@ pub unsafe extern "C" fn tc_task_remove_legacy_uda(task: *mut TCTask, key: TCStr
     )
 }
 
-/// Get all dependencies for a task.
+/// Get all dependencies for a task, as the UUIDs of the tasks on which it depends.
+///
+/// The caller must free the returned list with `tc_uuid_list_free`.
 #[no_mangle]
 pub unsafe extern "C" fn tc_task_get_dependencies(task: *mut TCTask) -> TCUuidList {
     wrap(task, |task| {
@@ -808,7 +876,7 @@ pub unsafe extern "C" fn tc_task_get_dependencies(task: *mut TCTask) -> TCUuidLi
     })
 }
 
-/// Add a dependency.
+/// Add a dependency to a mutable task, so that it depends on the task with the given UUID.
 #[no_mangle]
 pub unsafe extern "C" fn tc_task_add_dependency(task: *mut TCTask, dep: TCUuid) -> TCResult {
     // SAFETY:
@@ -824,7 +892,7 @@ pub unsafe extern "C" fn tc_task_add_dependency(task: *mut TCTask, dep: TCUuid)
     )
 }
 
-/// Remove a dependency.
+/// Remove a dependency from a mutable task.
 #[no_mangle]
 pub unsafe extern "C" fn tc_task_remove_dependency(task: *mut TCTask, dep: TCUuid) -> TCResult {
     // SAFETY:
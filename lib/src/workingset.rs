@@ -18,9 +18,9 @@ use taskchampion::{Uuid, WorkingSet};
 ///  - the pointer must not be NUL;
 ///  - the pointer must be one previously returned from `tc_replica_working_set`
 ///  - the memory referenced by the pointer must never be accessed by C code; and
-///  - except for `tc_replica_free`, ownership of a `*TCWorkingSet` remains with the caller.
+///  - except for `tc_working_set_free`, ownership of a `*TCWorkingSet` remains with the caller.
 ///
-/// Once passed to `tc_replica_free`, a `*TCWorkingSet` becomes invalid and must not be used again.
+/// Once passed to `tc_working_set_free`, a `*TCWorkingSet` becomes invalid and must not be used again.
 ///
 /// TCWorkingSet is not threadsafe.
 pub struct TCWorkingSet(WorkingSet);
@@ -96,7 +96,7 @@ pub unsafe extern "C" fn tc_working_set_by_uuid(ws: *mut TCWorkingSet, uuid: TCU
 #[no_mangle]
 pub unsafe extern "C" fn tc_working_set_free(ws: *mut TCWorkingSet) {
     // SAFETY:
-    //  - rep is not NULL (promised by caller)
+    //  - ws is not NULL (promised by caller)
     //  - caller will not use the TCWorkingSet after this (promised by caller)
     let ws = unsafe { TCWorkingSet::take_from_ptr_arg(ws) };
     drop(ws);
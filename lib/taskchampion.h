@@ -73,6 +73,13 @@
  * function, a list's ownership is with the C caller, which must eventually
  * free the list.  List data must be freed with the `tc_…_list_free` function.
  * It is an error to free any value in the `items` array of a list.
+ *
+ * ## Contexts
+ *
+ * As an alternative to freeing each value individually, replicas, tasks,
+ * servers, working sets, and strings can be given to a TCContext with the
+ * `tc_context_adopt_…` functions.  The context then owns those values, and
+ * frees them all when it is freed with `tc_context_free`.
  */
 
 
@@ -88,6 +95,50 @@
  */
 #define TC_UUID_STRING_BYTES 36
 
+/**
+ * The kind of error from a TC operation, allowing C callers to react to categories of failure
+ * without parsing the error message.
+ */
+typedef enum TCErrorKind {
+  /**
+   * No error occurred.
+   */
+  TC_ERROR_KIND_NONE,
+  /**
+   * An error that does not fall into any of the other kinds.
+   */
+  TC_ERROR_KIND_OTHER,
+  /**
+   * An error reading or writing files, such as a missing directory or a full disk.
+   */
+  TC_ERROR_KIND_IO,
+  /**
+   * The task database is corrupt, or is not a task database.
+   */
+  TC_ERROR_KIND_CORRUPT_DATA,
+  /**
+   * The sync server could not be reached.
+   */
+  TC_ERROR_KIND_SYNC_CONNECTION,
+  /**
+   * The sync server rejected this replica, or its data could not be decrypted, typically
+   * because the encryption secret is wrong.
+   */
+  TC_ERROR_KIND_SYNC_AUTH,
+  /**
+   * The replica is too far out of date to be synchronized with the server.
+   */
+  TC_ERROR_KIND_OUT_OF_SYNC,
+  /**
+   * An argument was invalid, such as a string that is not valid UTF-8.
+   */
+  TC_ERROR_KIND_INVALID_ARGUMENT,
+  /**
+   * Synchronization was cancelled by the progress function.
+   */
+  TC_ERROR_KIND_CANCELLED,
+} TCErrorKind;
+
 /**
  * A result from a TC operation.  Typically if this value is TC_RESULT_ERROR,
  * the associated object's `tc_.._error` method will return an error message.
@@ -118,6 +169,94 @@ typedef enum TCStatus {
   TC_STATUS_UNKNOWN,
 } TCStatus;
 
+/**
+ * The phase of a synchronization, as reported to a TCSyncProgressFn.
+ */
+typedef enum TCSyncPhase {
+  /**
+   * A snapshot of the task database is being downloaded and applied.  This occurs only for an
+   * empty replica.
+   */
+  TC_SYNC_PHASE_APPLYING_SNAPSHOT,
+  /**
+   * Versions are being downloaded from the server and applied.  The count is the number of
+   * versions applied so far.
+   */
+  TC_SYNC_PHASE_PULLING,
+  /**
+   * A new version of local changes is being sent to the server.  The count is the number of
+   * local operations it contains.
+   */
+  TC_SYNC_PHASE_PUSHING,
+  /**
+   * A snapshot of the task database is being sent to the server.  The server has already
+   * accepted the new version, so returning false skips the snapshot but does not cancel.
+   */
+  TC_SYNC_PHASE_SENDING_SNAPSHOT,
+} TCSyncPhase;
+
+/**
+ * TCContext owns a collection of TC objects, and frees them all at once in `tc_context_free`.
+ * This is useful for bindings in garbage-collected languages, where tracking the ownership of
+ * each individual object is error-prone.  Using a TCContext is optional.
+ *
+ * An object is given to a context with one of the `tc_context_adopt_…` functions, typically
+ * immediately after it is created:
+ *
+ * ```text
+ * TCContext *ctx = tc_context_new();
+ * TCReplica *rep = tc_context_adopt_replica(ctx, tc_replica_new_in_memory());
+ * TCTask *task = tc_context_adopt_task(ctx,
+ *     tc_replica_new_task(rep, TC_STATUS_PENDING, tc_string_borrow("my task")));
+ * // ... use rep and task ...
+ * tc_context_free(ctx); // frees task, then rep
+ * ```
+ *
+ * Objects are freed in the reverse of the order in which they were adopted, so an object that
+ * depends on another, such as a mutable task on its replica, should be adopted after it.
+ *
+ * # Safety
+ *
+ * Once adopted, an object is owned by the context and remains valid until the context is freed.
+ * It must not be freed by the caller, and must not be passed to any function that takes
+ * ownership of it.  An object must not be adopted more than once, or by more than one context.
+ *
+ * When a `*TCContext` is returned from a function, it is owned by the caller and must later be
+ * freed with `tc_context_free`.  Once freed, the context and every object it owns become invalid
+ * and must not be used again.
+ *
+ * TCContext is not threadsafe.
+ */
+typedef struct TCContext TCContext;
+
+/**
+ * A TCLazyTaskList represents the tasks in a replica, without reading them.  Each task is only
+ * read from the replica, and converted into a TCTask, when it is accessed with
+ * `tc_lazy_task_list_get`.  This avoids the cost of reading every task in a large replica when
+ * only some of them are needed, such as to display a page of tasks.
+ *
+ * The list contains the tasks that existed when it was created, in no particular order.  A task
+ * deleted from the replica after that point remains in the list, but cannot be read.
+ *
+ * # Safety
+ *
+ * The `*TCLazyTaskList` returned from `tc_replica_all_tasks_lazy` is owned by the caller and
+ * must later be freed to avoid a memory leak.  It refers to the replica from which it was
+ * generated, so that replica must not be freed before the list.
+ *
+ * Any function taking a `*TCLazyTaskList` requires:
+ *  - the pointer must not be NUL;
+ *  - the pointer must be one previously returned from `tc_replica_all_tasks_lazy`
+ *  - the memory referenced by the pointer must never be accessed by C code; and
+ *  - except for `tc_lazy_task_list_free`, ownership of a `*TCLazyTaskList` remains with the caller.
+ *
+ * Once passed to `tc_lazy_task_list_free`, a `*TCLazyTaskList` becomes invalid and must not be
+ * used again.
+ *
+ * TCLazyTaskList is not threadsafe.
+ */
+typedef struct TCLazyTaskList TCLazyTaskList;
+
 /**
  * A replica represents an instance of a user's task data, providing an easy interface
  * for querying and modifying that data.
@@ -125,7 +264,8 @@ typedef enum TCStatus {
  * # Error Handling
  *
  * When a `tc_replica_..` function that returns a TCResult returns TC_RESULT_ERROR, then
- * `tc_replica_error` will return the error message.
+ * `tc_replica_error` will return the error message, and `tc_replica_error_kind` the kind of
+ * error.
  *
  * # Safety
  *
@@ -140,7 +280,10 @@ typedef enum TCStatus {
  *
  * Once passed to `tc_replica_free`, a `*TCReplica` becomes invalid and must not be used again.
  *
- * TCReplicas are not threadsafe.
+ * TCReplicas are not threadsafe: a TCReplica, and the TCTasks and other values obtained from it,
+ * must only be used by one thread at a time.  To use the same on-disk task database from
+ * several threads, such as to synchronize in a background thread, give each thread its own
+ * handle with `tc_replica_clone_handle`.
  */
 typedef struct TCReplica TCReplica;
 
@@ -204,9 +347,9 @@ typedef struct TCTask TCTask;
  *  - the pointer must not be NUL;
  *  - the pointer must be one previously returned from `tc_replica_working_set`
  *  - the memory referenced by the pointer must never be accessed by C code; and
- *  - except for `tc_replica_free`, ownership of a `*TCWorkingSet` remains with the caller.
+ *  - except for `tc_working_set_free`, ownership of a `*TCWorkingSet` remains with the caller.
  *
- * Once passed to `tc_replica_free`, a `*TCWorkingSet` becomes invalid and must not be used again.
+ * Once passed to `tc_working_set_free`, a `*TCWorkingSet` becomes invalid and must not be used again.
  *
  * TCWorkingSet is not threadsafe.
  */
@@ -226,7 +369,8 @@ typedef struct TCWorkingSet TCWorkingSet;
  * # UTF-8
  *
  * TaskChampion expects all strings to be valid UTF-8. `tc_string_…` functions will fail if given
- * a `*TCString` containing invalid UTF-8.
+ * a `*TCString` containing invalid UTF-8.  Use `tc_string_is_valid_utf8` to check a string, and
+ * `tc_string_content_lossy` to access a string that may contain invalid UTF-8.
  *
  * # Safety
  *
@@ -307,38 +451,6 @@ typedef struct TCAnnotationList {
   struct TCAnnotation *items;
 } TCAnnotationList;
 
-/**
- * TCKV contains a key/value pair that is part of a task.
- *
- * Neither key nor value are ever NULL.  They remain owned by the TCKV and
- * will be freed when it is freed with tc_kv_list_free.
- */
-typedef struct TCKV {
-  struct TCString key;
-  struct TCString value;
-} TCKV;
-
-/**
- * TCKVList represents a list of key/value pairs.
- *
- * The content of this struct must be treated as read-only.
- */
-typedef struct TCKVList {
-  /**
-   * number of key/value pairs in items
-   */
-  size_t len;
-  /**
-   * total size of items (internal use only)
-   */
-  size_t _capacity;
-  /**
-   * array of TCKV's. these remain owned by the TCKVList instance and will be freed by
-   * tc_kv_list_free.  This pointer is never NULL for a valid TCKVList.
-   */
-  struct TCKV *items;
-} TCKVList;
-
 /**
  * TCTaskList represents a list of tasks.
  *
@@ -416,6 +528,38 @@ typedef struct TCStringList {
   struct TCString *items;
 } TCStringList;
 
+/**
+ * TCKV contains a key/value pair that is part of a task.
+ *
+ * Neither key nor value are ever NULL.  They remain owned by the TCKV and
+ * will be freed when it is freed with tc_kv_list_free.
+ */
+typedef struct TCKV {
+  struct TCString key;
+  struct TCString value;
+} TCKV;
+
+/**
+ * TCKVList represents a list of key/value pairs.
+ *
+ * The content of this struct must be treated as read-only.
+ */
+typedef struct TCKVList {
+  /**
+   * number of key/value pairs in items
+   */
+  size_t len;
+  /**
+   * total size of items (internal use only)
+   */
+  size_t _capacity;
+  /**
+   * array of TCKV's. these remain owned by the TCKVList instance and will be freed by
+   * tc_kv_list_free.  This pointer is never NULL for a valid TCKVList.
+   */
+  struct TCKV *items;
+} TCKVList;
+
 /**
  * TCUda contains the details of a UDA.
  */
@@ -455,6 +599,18 @@ typedef struct TCUdaList {
   struct TCUda *items;
 } TCUdaList;
 
+/**
+ * A function called to report the progress of `tc_replica_sync_with_progress`.  It is given the
+ * context pointer passed to that function, the current phase, and a count whose meaning depends
+ * on the phase (see TCSyncPhase), or zero.  The server does not indicate how many versions
+ * remain to be downloaded, so the total amount of work is not known in advance.
+ *
+ * The function should return true to continue synchronizing, or false to cancel.  The function
+ * is called on the thread that called `tc_replica_sync_with_progress`, and must not use the
+ * replica being synchronized.
+ */
+typedef bool (*TCSyncProgressFn)(void *context, enum TCSyncPhase phase, size_t count);
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
@@ -473,6 +629,119 @@ void tc_annotation_free(struct TCAnnotation *tcann);
  */
 void tc_annotation_list_free(struct TCAnnotationList *tcanns);
 
+/**
+ * Create a new, empty TCContext.
+ */
+struct TCContext *tc_context_new(void);
+
+/**
+ * Give a replica to the context, returning the same pointer.  If `rep` is NULL, nothing is
+ * adopted and NULL is returned, so this can be applied directly to the result of a constructor.
+ *
+ * Tasks made mutable with this replica must be adopted after it, or freed before the context.
+ */
+struct TCReplica *tc_context_adopt_replica(struct TCContext *ctx, struct TCReplica *rep);
+
+/**
+ * Give a task to the context, returning the same pointer.  If `task` is NULL, nothing is adopted
+ * and NULL is returned.
+ */
+struct TCTask *tc_context_adopt_task(struct TCContext *ctx, struct TCTask *task);
+
+/**
+ * Give a server to the context, returning the same pointer.  If `server` is NULL, nothing is
+ * adopted and NULL is returned.
+ */
+struct TCServer *tc_context_adopt_server(struct TCContext *ctx, struct TCServer *server);
+
+/**
+ * Give a working set to the context, returning the same pointer.  If `ws` is NULL, nothing is
+ * adopted and NULL is returned.
+ */
+struct TCWorkingSet *tc_context_adopt_working_set(struct TCContext *ctx, struct TCWorkingSet *ws);
+
+/**
+ * Give a lazy task list to the context, returning the same pointer.  If `list` is NULL, nothing
+ * is adopted and NULL is returned.
+ *
+ * The list reads tasks from its replica, so it must be adopted after that replica.
+ */
+struct TCLazyTaskList *tc_context_adopt_lazy_task_list(struct TCContext *ctx,
+                                                       struct TCLazyTaskList *list);
+
+/**
+ * Give a string to the context, returning a pointer to it that remains valid until the context
+ * is freed.  The pointer can be passed to functions that borrow a string, such as
+ * `tc_string_content`, but the string must not be passed by value to any function that takes
+ * ownership of it.  If the string is NULL, nothing is adopted and NULL is returned.
+ */
+const struct TCString *tc_context_adopt_string(struct TCContext *ctx, struct TCString tcstring);
+
+/**
+ * Give a task list to the context, returning a pointer to it that remains valid until the
+ * context is freed.  Items may be taken from the list with `tc_task_list_take`, after which they
+ * are owned by the caller and not by the context.  The list must not be freed by the caller.
+ */
+struct TCTaskList *tc_context_adopt_task_list(struct TCContext *ctx, struct TCTaskList tasks);
+
+/**
+ * Give a UUID list to the context, returning a pointer to it that remains valid until the
+ * context is freed.  The list must not be freed by the caller.
+ */
+const struct TCUuidList *tc_context_adopt_uuid_list(struct TCContext *ctx, struct TCUuidList uuids);
+
+/**
+ * Give a string list to the context, returning a pointer to it that remains valid until the
+ * context is freed.  The list must not be freed by the caller.
+ */
+const struct TCStringList *tc_context_adopt_string_list(struct TCContext *ctx,
+                                                        struct TCStringList strings);
+
+/**
+ * Give a key/value list to the context, returning a pointer to it that remains valid until the
+ * context is freed.  The list must not be freed by the caller.
+ */
+const struct TCKVList *tc_context_adopt_kv_list(struct TCContext *ctx, struct TCKVList kvs);
+
+/**
+ * Give an annotation list to the context, returning a pointer to it that remains valid until the
+ * context is freed.  The list must not be freed by the caller.
+ */
+const struct TCAnnotationList *tc_context_adopt_annotation_list(struct TCContext *ctx,
+                                                                struct TCAnnotationList anns);
+
+/**
+ * Give a UDA list to the context, returning a pointer to it that remains valid until the
+ * context is freed.  The list must not be freed by the caller.
+ */
+const struct TCUdaList *tc_context_adopt_uda_list(struct TCContext *ctx, struct TCUdaList udas);
+
+/**
+ * Free a context, and every object it owns.  The context and its objects must not be used after
+ * this function returns, and the context must not be freed more than once.
+ */
+void tc_context_free(struct TCContext *ctx);
+
+/**
+ * Get the value for the given key in a TCKVList, or NULL if the key is not present.  If the key
+ * appears more than once, the first matching value is returned.
+ *
+ * The returned TCString is borrowed from the list: it must not be freed, and is only valid until
+ * the list is freed.  The list itself is not modified.
+ *
+ * This function takes ownership of the `key` string.
+ */
+const struct TCString *tc_kv_list_get(struct TCKVList *tckvs, struct TCString key);
+
+/**
+ * Get the keys of a TCKVList, in the order they appear in the list.  The list itself is not
+ * modified.
+ *
+ * The caller must free the returned TCStringList instance.  The TCStringList instance does not
+ * depend on the TCKVList, which may be freed first.
+ */
+struct TCStringList tc_kv_list_keys(struct TCKVList *tckvs);
+
 /**
  * Free a TCKVList instance.  The instance, and all TCKVs it contains, must not be used after
  * this call.
@@ -481,6 +750,33 @@ void tc_annotation_list_free(struct TCAnnotationList *tcanns);
  */
 void tc_kv_list_free(struct TCKVList *tckvs);
 
+/**
+ * Get the number of tasks in the list.
+ */
+size_t tc_lazy_task_list_len(struct TCLazyTaskList *list);
+
+/**
+ * Get the UUID of the task at the given index, without reading the task.  Returns true if the
+ * index is in bounds.  If not, returns false and does not change uuid_out.
+ */
+bool tc_lazy_task_list_uuid(struct TCLazyTaskList *list, size_t index, struct TCUuid *uuid_out);
+
+/**
+ * Read the task at the given index from the replica.  The returned task is owned by the caller,
+ * just as if it had been returned from `tc_replica_get_task`, and each call returns a new TCTask.
+ *
+ * Returns NULL if the index is out of bounds, if the task no longer exists, or on error.  Errors
+ * are reported by `tc_replica_error` for the list's replica, which must not have a mutable task
+ * at the time of this call.
+ */
+struct TCTask *tc_lazy_task_list_get(struct TCLazyTaskList *list, size_t index);
+
+/**
+ * Free a TCLazyTaskList.  The list may not be used after this function returns and must not be
+ * freed more than once.  Tasks returned from `tc_lazy_task_list_get` are not affected.
+ */
+void tc_lazy_task_list_free(struct TCLazyTaskList *list);
+
 /**
  * Create a new TCReplica with an in-memory database.  The contents of the database will be
  * lost when it is freed with tc_replica_free.
@@ -495,12 +791,47 @@ struct TCReplica *tc_replica_new_in_memory(void);
 struct TCReplica *tc_replica_new_on_disk(struct TCString path, struct TCString *error_out);
 
 /**
- * Get a list of all tasks in the replica.
+ * Create a new handle to the same on-disk task database as the given replica.  The new handle
+ * is independent of the original: it may be used on another thread while the original is in
+ * use, and must be freed separately.
+ *
+ * Every operation on the database, even one that only reads, locks it, and an operation waits
+ * up to five seconds for an operation through another handle to complete.  After that, it
+ * fails with TC_ERROR_KIND_OTHER, and an error message saying that the database is locked.
+ * A sync holds the lock for its whole duration, including its requests to the server, so
+ * operations on other handles during a sync that takes longer than five seconds fail, and
+ * should be retried once the sync completes.
+ *
+ * Changes made through one handle are visible through the other once the operation making them
+ * completes, but values such as TCTasks and TCWorkingSets are not updated; fetch them again to
+ * see the changes.
+ *
+ * Replicas with in-memory databases cannot be cloned.  On error, a string is written to the
+ * error_out parameter (if it is not NULL) and NULL is returned.  The caller must free this
+ * string.
+ */
+struct TCReplica *tc_replica_clone_handle(struct TCReplica *rep, struct TCString *error_out);
+
+/**
+ * Get a list of all tasks in the replica, regardless of their status.
  *
  * Returns a TCTaskList with a NULL items field on error.
+ *
+ * The caller must free the list, and any tasks not taken from it, with `tc_task_list_free`.
  */
 struct TCTaskList tc_replica_all_tasks(struct TCReplica *rep);
 
+/**
+ * Get a lazy list of all tasks in the replica, regardless of their status.  Unlike
+ * `tc_replica_all_tasks`, this does not read the tasks; each is read when it is accessed with
+ * `tc_lazy_task_list_get`.
+ *
+ * Returns NULL on error.
+ *
+ * The caller must free the list with `tc_lazy_task_list_free`, before freeing the replica.
+ */
+struct TCLazyTaskList *tc_replica_all_tasks_lazy(struct TCReplica *rep);
+
 /**
  * Get a list of all uuids for tasks in the replica.
  *
@@ -510,6 +841,16 @@ struct TCTaskList tc_replica_all_tasks(struct TCReplica *rep);
  */
 struct TCUuidList tc_replica_all_task_uuids(struct TCReplica *rep);
 
+/**
+ * Get a list of the distinct user tags of all tasks in the replica, in sorted order.  Synthetic
+ * tags such as `PENDING` are not included.
+ *
+ * Returns a TCStringList with a NULL items field on error.
+ *
+ * The caller must free the list with `tc_string_list_free`.
+ */
+struct TCStringList tc_replica_all_tags(struct TCReplica *rep);
+
 /**
  * Get the current working set for this replica.  The resulting value must be freed
  * with tc_working_set_free.
@@ -526,6 +867,25 @@ struct TCWorkingSet *tc_replica_working_set(struct TCReplica *rep);
  */
 struct TCTask *tc_replica_get_task(struct TCReplica *rep, struct TCUuid tcuuid);
 
+/**
+ * Create a new task with the given status and description, and with the given additional
+ * properties, in a single transaction.  This is useful for importing tasks, as it avoids the
+ * overhead of setting each property separately.
+ *
+ * The properties are given as an array of `kvs_len` TCKV values, using the keys of the task
+ * data model (as returned from `tc_task_get_taskmap`).  The `status` and `description` keys are
+ * ignored.  The `entry` and `modified` properties default to the current time.  Ownership of
+ * the strings in the TCKV values passes to this function, but the array itself remains owned by
+ * the caller.  `kvs` may be NULL if `kvs_len` is zero.
+ *
+ * Returns the task, or NULL on error.
+ */
+struct TCTask *tc_replica_new_task_full(struct TCReplica *rep,
+                                        enum TCStatus status,
+                                        struct TCString description,
+                                        struct TCKV *kvs,
+                                        size_t kvs_len);
+
 /**
  * Create a new task.  The task must not already exist.
  *
@@ -549,16 +909,34 @@ struct TCTask *tc_replica_import_task_with_uuid(struct TCReplica *rep, struct TC
  */
 TCResult tc_replica_sync(struct TCReplica *rep, struct TCServer *server, bool avoid_snapshots);
 
+/**
+ * Synchronize this replica with a server, like `tc_replica_sync`, calling the given function to
+ * report progress.  The `context` pointer is passed to the function unchanged.
+ *
+ * If the function returns false, synchronization is cancelled: TC_RESULT_ERROR is returned,
+ * `tc_replica_error_kind` returns TC_ERROR_KIND_CANCELLED, and the replica is unchanged.  This
+ * is not possible in the TC_SYNC_PHASE_SENDING_SNAPSHOT phase, when the server has already
+ * accepted the new version; returning false then only skips the snapshot.
+ *
+ * The `server` argument remains owned by the caller, and must be freed explicitly.
+ */
+TCResult tc_replica_sync_with_progress(struct TCReplica *rep,
+                                       struct TCServer *server,
+                                       bool avoid_snapshots,
+                                       TCSyncProgressFn progress,
+                                       void *context);
+
 /**
  * Undo local operations until the most recent UndoPoint.
  *
- * If undone_out is not NULL, then on success it is set to 1 if operations were undone, or 0 if
- * there are no operations that can be done.
+ * If undone_out is not NULL, then on success it is set to the number of operations that were
+ * undone, or 0 if there are no operations that can be undone.
  */
 TCResult tc_replica_undo(struct TCReplica *rep, int32_t *undone_out);
 
 /**
- * Get the number of local, un-synchronized operations, or -1 on error
+ * Get the number of local, un-synchronized operations (including undo points), or -1 on error.
+ * This can be used to warn the user about changes that have not yet been synchronized.
  */
 int64_t tc_replica_num_local_operations(struct TCReplica *rep);
 
@@ -577,6 +955,14 @@ TCResult tc_replica_add_undo_point(struct TCReplica *rep, bool force);
  */
 TCResult tc_replica_rebuild_working_set(struct TCReplica *rep, bool renumber);
 
+/**
+ * Set whether the working set is rebuilt automatically after each sync.  This is enabled by
+ * default.  When disabled, the working set changes only when tasks become pending or when
+ * `tc_replica_rebuild_working_set` is called, so the caller controls when working-set indices
+ * change.
+ */
+TCResult tc_replica_set_auto_rebuild_working_set(struct TCReplica *rep, bool auto_rebuild);
+
 /**
  * Get the latest error for a replica, or a string with NULL ptr if no error exists.  Subsequent
  * calls to this function will return NULL.  The rep pointer must not be NULL.  The caller must
@@ -584,6 +970,13 @@ TCResult tc_replica_rebuild_working_set(struct TCReplica *rep, bool renumber);
  */
 struct TCString tc_replica_error(struct TCReplica *rep);
 
+/**
+ * Get the kind of the latest error for a replica, or TC_ERROR_KIND_NONE if the most recent
+ * operation succeeded.  Unlike `tc_replica_error`, this does not clear the error, so the two may
+ * be called in either order.  The rep pointer must not be NULL.
+ */
+enum TCErrorKind tc_replica_error_kind(struct TCReplica *rep);
+
 /**
  * Free a replica.  The replica may not be used after this function returns and must not be freed
  * more than once.
@@ -631,7 +1024,7 @@ void tc_server_free(struct TCServer *server);
  *
  * For example:
  *
- * ```
+ * ```text
  * char *url = get_item_url(..); // dynamically allocate C string
  * tc_task_annotate(task, tc_string_borrow(url)); // TCString created, passed, and freed
  * free(url); // string is no longer referenced and can be freed
@@ -684,6 +1077,32 @@ const char *tc_string_content(const struct TCString *tcstring);
  */
 const char *tc_string_content_with_len(const struct TCString *tcstring, size_t *len_out);
 
+/**
+ * Get the content of the string as a regular C string, replacing any invalid UTF-8 sequences with
+ * U+FFFD REPLACEMENT CHARACTER.  The given string must be valid.  The returned value is NULL only
+ * if the string contains NUL bytes.  The returned C string is valid until the TCString is freed or
+ * passed to another TC API function.
+ *
+ * The replacement is made in place, so the original bytes of an invalid string are not available
+ * from the TCString after this call.  Use `tc_string_content_with_len` first if they are needed.
+ *
+ * This function takes the TCString by pointer because it may be modified in-place.  The pointer
+ * must not be NULL.
+ *
+ * This function does _not_ take ownership of the TCString.
+ */
+const char *tc_string_content_lossy(const struct TCString *tcstring);
+
+/**
+ * Determine whether the string contains valid UTF-8.  Strings for which this returns false can
+ * still be accessed with `tc_string_content_with_len` or `tc_string_content_lossy`, but will be
+ * rejected by functions that require text, such as `tc_task_set_description`.  The given string
+ * must be valid and must not be NULL.
+ *
+ * This function does _not_ take ownership of the TCString.
+ */
+bool tc_string_is_valid_utf8(const struct TCString *tcstring);
+
 /**
  * Free a TCString.  The given string must not be NULL.  The string must not be used
  * after this function returns, and must not be freed more than once.
@@ -765,6 +1184,21 @@ time_t tc_task_get_wait(struct TCTask *task);
  */
 time_t tc_task_get_modified(struct TCTask *task);
 
+/**
+ * Get the due timestamp for a task, or 0 if not set.
+ */
+time_t tc_task_get_due(struct TCTask *task);
+
+/**
+ * Get the scheduled timestamp for a task, or 0 if not set.
+ */
+time_t tc_task_get_scheduled(struct TCTask *task);
+
+/**
+ * Get the end timestamp for a task (when it was completed or deleted), or 0 if not set.
+ */
+time_t tc_task_get_end(struct TCTask *task);
+
 /**
  * Check if a task is waiting.
  */
@@ -792,7 +1226,7 @@ struct TCStringList tc_task_get_tags(struct TCTask *task);
 /**
  * Get the annotations for the task.
  *
- * The caller must free the returned TCAnnotationList instance.  The TCStringList instance does not
+ * The caller must free the returned TCAnnotationList instance.  The TCAnnotationList instance does not
  * reference the task and the two may be freed in any order.
  */
 struct TCAnnotationList tc_task_get_annotations(struct TCTask *task);
@@ -814,7 +1248,9 @@ struct TCString tc_task_get_legacy_uda(struct TCTask *task, struct TCString key)
 /**
  * Get all UDAs for this task.
  *
- * Legacy UDAs are represented with an empty string in the ns field.
+ * Each key is split into a namespace and key on the first `.` character.  Legacy UDAs, without
+ * a `.`, are represented with an empty string in the ns field.  The caller must free the
+ * returned list.
  */
 struct TCUdaList tc_task_get_udas(struct TCTask *task);
 
@@ -847,6 +1283,22 @@ TCResult tc_task_set_entry(struct TCTask *task, time_t entry);
  */
 TCResult tc_task_set_wait(struct TCTask *task, time_t wait);
 
+/**
+ * Set a mutable task's due timestamp.  Pass due=0 to unset the due field.
+ */
+TCResult tc_task_set_due(struct TCTask *task, time_t due);
+
+/**
+ * Set a mutable task's scheduled timestamp.  Pass scheduled=0 to unset the scheduled field.
+ */
+TCResult tc_task_set_scheduled(struct TCTask *task, time_t scheduled);
+
+/**
+ * Set a mutable task's end timestamp.  This is set automatically when the task is completed or
+ * deleted, so this is only needed to override that time.  Pass end=0 to unset the end field.
+ */
+TCResult tc_task_set_end(struct TCTask *task, time_t end);
+
 /**
  * Set a mutable task's modified timestamp.  The value cannot be zero.
  */
@@ -902,7 +1354,7 @@ TCResult tc_task_set_uda(struct TCTask *task,
                          struct TCString value);
 
 /**
- * Remove a UDA fraom a mutable task.
+ * Remove a UDA from a mutable task.
  */
 TCResult tc_task_remove_uda(struct TCTask *task, struct TCString ns, struct TCString key);
 
@@ -912,22 +1364,24 @@ TCResult tc_task_remove_uda(struct TCTask *task, struct TCString ns, struct TCSt
 TCResult tc_task_set_legacy_uda(struct TCTask *task, struct TCString key, struct TCString value);
 
 /**
- * Remove a UDA fraom a mutable task.
+ * Remove a legacy UDA from a mutable task.
  */
 TCResult tc_task_remove_legacy_uda(struct TCTask *task, struct TCString key);
 
 /**
- * Get all dependencies for a task.
+ * Get all dependencies for a task, as the UUIDs of the tasks on which it depends.
+ *
+ * The caller must free the returned list with `tc_uuid_list_free`.
  */
 struct TCUuidList tc_task_get_dependencies(struct TCTask *task);
 
 /**
- * Add a dependency.
+ * Add a dependency to a mutable task, so that it depends on the task with the given UUID.
  */
 TCResult tc_task_add_dependency(struct TCTask *task, struct TCUuid dep);
 
 /**
- * Remove a dependency.
+ * Remove a dependency from a mutable task.
  */
 TCResult tc_task_remove_dependency(struct TCTask *task, struct TCUuid dep);
 
@@ -0,0 +1,33 @@
+//! Python bindings for TaskChampion, built with [pyo3](https://pyo3.rs).
+//!
+//! The Python API mirrors the Rust API of the `taskchampion` crate, with UUIDs represented as
+//! strings and timestamps as timezone-aware `datetime.datetime` values.  Build the module with
+//! [maturin](https://github.com/PyO3/maturin), e.g., `maturin develop` in this directory.
+
+use pyo3::prelude::*;
+
+mod replica;
+mod server;
+mod status;
+mod task;
+mod util;
+mod working_set;
+
+pub use replica::Replica;
+pub use server::Server;
+pub use status::Status;
+pub use task::{Task, TaskMut};
+pub use working_set::WorkingSet;
+
+#[pymodule]
+#[pyo3(name = "taskchampion")]
+fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Replica>()?;
+    m.add_class::<Server>()?;
+    m.add_class::<Status>()?;
+    m.add_class::<Task>()?;
+    m.add_class::<TaskMut>()?;
+    m.add_class::<WorkingSet>()?;
+    m.add("Error", m.py().get_type::<util::Error>())?;
+    Ok(())
+}
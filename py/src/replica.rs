@@ -0,0 +1,135 @@
+use crate::util::{into_pyerr, uuid_from_str};
+use crate::{Server, Status, Task, WorkingSet};
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use taskchampion::{Replica as TCReplica, StorageConfig};
+
+/// A replica of the task database.  See the Rust documentation for
+/// [`taskchampion::Replica`](https://docs.rs/taskchampion/latest/taskchampion/struct.Replica.html).
+///
+/// A replica may only be used from the Python thread that created it.
+#[pyclass(unsendable)]
+pub struct Replica(pub(crate) TCReplica);
+
+/// A value that is asserted to be safe to send to another thread.  This is used to release the
+/// GIL around long-running operations on values that are not `Send`.  Such operations run on the
+/// current thread, and `unsendable` pyclasses cannot be accessed from other threads meanwhile.
+struct AssertSend<T>(T);
+
+// SAFETY: see above; the value never actually leaves the current thread.
+unsafe impl<T> Send for AssertSend<T> {}
+
+#[pymethods]
+impl Replica {
+    /// Open the on-disk replica in the given directory, creating it if it does not exist.
+    #[new]
+    fn new(path: PathBuf) -> PyResult<Replica> {
+        let storage = StorageConfig::OnDisk { taskdb_dir: path }
+            .into_storage()
+            .map_err(into_pyerr)?;
+        Ok(Replica(TCReplica::new(storage)))
+    }
+
+    /// Create a new replica that is stored in memory and discarded when it is no longer used.
+    #[staticmethod]
+    fn new_in_memory() -> PyResult<Replica> {
+        let storage = StorageConfig::InMemory.into_storage().map_err(into_pyerr)?;
+        Ok(Replica(TCReplica::new(storage)))
+    }
+
+    /// Create a new task with the given status and description.
+    fn new_task(&mut self, status: Status, description: String) -> PyResult<Task> {
+        let task = self
+            .0
+            .new_task(status.into_tc()?, description)
+            .map_err(into_pyerr)?;
+        Ok(Task(task))
+    }
+
+    /// Create a new task with the given UUID, or get the existing task if one exists.  This is
+    /// useful for importing tasks from another source.
+    fn import_task_with_uuid(&mut self, uuid: &str) -> PyResult<Task> {
+        let task = self
+            .0
+            .import_task_with_uuid(uuid_from_str(uuid)?)
+            .map_err(into_pyerr)?;
+        Ok(Task(task))
+    }
+
+    /// Get all tasks, as a dictionary keyed by UUID.
+    fn all_tasks(&mut self) -> PyResult<HashMap<String, Task>> {
+        Ok(self
+            .0
+            .all_tasks()
+            .map_err(into_pyerr)?
+            .into_iter()
+            .map(|(uuid, task)| (uuid.to_string(), Task(task)))
+            .collect())
+    }
+
+    /// Get the UUIDs of all tasks.
+    fn all_task_uuids(&mut self) -> PyResult<Vec<String>> {
+        Ok(self
+            .0
+            .all_task_uuids()
+            .map_err(into_pyerr)?
+            .into_iter()
+            .map(|uuid| uuid.to_string())
+            .collect())
+    }
+
+    /// Get the task with the given UUID, or None if it does not exist.
+    fn get_task(&mut self, uuid: &str) -> PyResult<Option<Task>> {
+        Ok(self
+            .0
+            .get_task(uuid_from_str(uuid)?)
+            .map_err(into_pyerr)?
+            .map(Task))
+    }
+
+    /// Get the current working set.
+    fn working_set(&mut self) -> PyResult<WorkingSet> {
+        Ok(WorkingSet(self.0.working_set().map_err(into_pyerr)?))
+    }
+
+    /// Rebuild the working set, so that it contains exactly the pending tasks.  If `renumber` is
+    /// true, existing tasks may be moved to new indices.
+    fn rebuild_working_set(&mut self, renumber: bool) -> PyResult<()> {
+        self.0.rebuild_working_set(renumber).map_err(into_pyerr)
+    }
+
+    /// Add an undo point, if one has not already been added by this replica.  If `force` is
+    /// true, an undo point is always added.
+    fn add_undo_point(&mut self, force: bool) -> PyResult<()> {
+        self.0.add_undo_point(force).map_err(into_pyerr)
+    }
+
+    /// Undo local operations until the most recent undo point, returning false if there are no
+    /// local operations to undo.
+    fn undo(&mut self) -> PyResult<bool> {
+        self.0.undo().map_err(into_pyerr)
+    }
+
+    /// Get the number of local, un-synchronized operations, not including undo points.
+    fn num_local_operations(&mut self) -> PyResult<usize> {
+        self.0.num_local_operations().map_err(into_pyerr)
+    }
+
+    /// Synchronize this replica with the given server.  Other Python threads may run while the
+    /// synchronization is in progress.
+    #[pyo3(signature = (server, avoid_snapshots = false))]
+    fn sync(
+        &mut self,
+        py: Python,
+        mut server: PyRefMut<Server>,
+        avoid_snapshots: bool,
+    ) -> PyResult<()> {
+        let args = AssertSend((&mut self.0, &mut server.0));
+        py.allow_threads(move || {
+            let AssertSend((rep, server)) = args;
+            rep.sync(server, avoid_snapshots)
+        })
+        .map_err(into_pyerr)
+    }
+}
@@ -0,0 +1,32 @@
+use crate::util::{into_pyerr, uuid_from_str};
+use pyo3::prelude::*;
+use std::path::PathBuf;
+use taskchampion::{Server as TCServer, ServerConfig};
+
+/// A sync server, to be passed to `Replica.sync`.
+#[pyclass(unsendable)]
+pub struct Server(pub(crate) Box<dyn TCServer>);
+
+#[pymethods]
+impl Server {
+    /// Create a server that stores its data in a local directory, for synchronizing replicas on
+    /// the same machine.
+    #[staticmethod]
+    fn local(server_dir: PathBuf) -> PyResult<Server> {
+        let config = ServerConfig::Local { server_dir };
+        Ok(Server(config.into_server().map_err(into_pyerr)?))
+    }
+
+    /// Create a server that connects to a taskchampion-sync-server at the given origin.  The
+    /// client key is a UUID identifying this user's task data on the server, and the encryption
+    /// secret is used to encrypt that data.
+    #[staticmethod]
+    fn remote(origin: String, client_key: &str, encryption_secret: &str) -> PyResult<Server> {
+        let config = ServerConfig::Remote {
+            origin,
+            client_key: uuid_from_str(client_key)?,
+            encryption_secret: encryption_secret.as_bytes().to_vec(),
+        };
+        Ok(Server(config.into_server().map_err(into_pyerr)?))
+    }
+}
@@ -0,0 +1,38 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use taskchampion::Status as TCStatus;
+
+/// The status of a task.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Pending,
+    Completed,
+    Deleted,
+    /// A status in the task database that was not recognized.  Tasks with this status should be
+    /// ignored, and it cannot be given to `TaskMut.set_status`.
+    Unknown,
+}
+
+impl From<TCStatus> for Status {
+    fn from(status: TCStatus) -> Status {
+        match status {
+            TCStatus::Pending => Status::Pending,
+            TCStatus::Completed => Status::Completed,
+            TCStatus::Deleted => Status::Deleted,
+            TCStatus::Unknown(_) => Status::Unknown,
+        }
+    }
+}
+
+impl Status {
+    /// Convert this status into the Rust type, failing for Unknown.
+    pub(crate) fn into_tc(self) -> PyResult<TCStatus> {
+        match self {
+            Status::Pending => Ok(TCStatus::Pending),
+            Status::Completed => Ok(TCStatus::Completed),
+            Status::Deleted => Ok(TCStatus::Deleted),
+            Status::Unknown => Err(PyValueError::new_err("cannot use the Unknown status")),
+        }
+    }
+}
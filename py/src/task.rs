@@ -0,0 +1,285 @@
+use crate::util::{from_datetime, into_pyerr, to_datetime, uuid_from_str};
+use crate::{Replica, Status};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDateTime;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use taskchampion::{Annotation, Tag, Task as TCTask, TaskMut as TCTaskMut};
+
+/// Parse a tag, raising ValueError if it is invalid.
+fn tag_from_str(tag: &str) -> PyResult<Tag> {
+    Tag::try_from(tag).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// A task, with getters for its properties.  This is a snapshot of the task when it was read from
+/// the replica.  Use `into_mut` to modify it.
+#[pyclass(unsendable)]
+#[derive(Clone)]
+pub struct Task(pub(crate) TCTask);
+
+#[pymethods]
+impl Task {
+    fn get_uuid(&self) -> String {
+        self.0.get_uuid().to_string()
+    }
+
+    fn get_status(&self) -> Status {
+        self.0.get_status().into()
+    }
+
+    fn get_description(&self) -> &str {
+        self.0.get_description()
+    }
+
+    fn get_priority(&self) -> &str {
+        self.0.get_priority()
+    }
+
+    fn get_project(&self) -> Option<&str> {
+        self.0.get_project()
+    }
+
+    fn get_entry<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDateTime>>> {
+        to_datetime(py, self.0.get_entry())
+    }
+
+    fn get_wait<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDateTime>>> {
+        to_datetime(py, self.0.get_wait())
+    }
+
+    fn get_due<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDateTime>>> {
+        to_datetime(py, self.0.get_due())
+    }
+
+    fn get_scheduled<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDateTime>>> {
+        to_datetime(py, self.0.get_scheduled())
+    }
+
+    fn get_modified<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDateTime>>> {
+        to_datetime(py, self.0.get_modified())
+    }
+
+    fn get_start<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDateTime>>> {
+        to_datetime(py, self.0.get_start())
+    }
+
+    fn get_end<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDateTime>>> {
+        to_datetime(py, self.0.get_end())
+    }
+
+    fn is_waiting(&self) -> bool {
+        self.0.is_waiting()
+    }
+
+    fn is_active(&self) -> bool {
+        self.0.is_active()
+    }
+
+    fn is_blocked(&self) -> bool {
+        self.0.is_blocked()
+    }
+
+    fn is_blocking(&self) -> bool {
+        self.0.is_blocking()
+    }
+
+    /// Check whether the task has the given tag, which may be a user tag or a synthetic tag
+    /// such as `PENDING`.
+    fn has_tag(&self, tag: &str) -> PyResult<bool> {
+        Ok(self.0.has_tag(&tag_from_str(tag)?))
+    }
+
+    /// Get the task's tags, including synthetic tags.
+    fn get_tags(&self) -> Vec<String> {
+        self.0.get_tags().map(|t| t.to_string()).collect()
+    }
+
+    /// Get the task's annotations, as a list of (entry, description) pairs.
+    fn get_annotations<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<Vec<(Bound<'py, PyDateTime>, String)>> {
+        self.0
+            .get_annotations()
+            .map(|a| Ok((to_datetime(py, Some(a.entry))?.unwrap(), a.description)))
+            .collect()
+    }
+
+    /// Get the value of a user-defined attribute, or None if it is not set.
+    fn get_uda(&self, namespace: &str, key: &str) -> Option<&str> {
+        self.0.get_uda(namespace, key)
+    }
+
+    /// Get all user-defined attributes, as a dictionary keyed by (namespace, key).
+    fn get_udas(&self) -> HashMap<(String, String), String> {
+        self.0
+            .get_udas()
+            .map(|((ns, k), v)| ((ns.to_owned(), k.to_owned()), v.to_owned()))
+            .collect()
+    }
+
+    /// Get the UUIDs of the tasks on which this task depends.
+    fn get_dependencies(&self) -> Vec<String> {
+        self.0.get_dependencies().map(|u| u.to_string()).collect()
+    }
+
+    /// Get the underlying key/value properties of the task.
+    fn get_taskmap(&self) -> HashMap<String, String> {
+        self.0.get_taskmap().clone()
+    }
+
+    /// Get a mutable version of this task, for modifications in the given replica.  This task
+    /// is not updated by those modifications.
+    #[pyo3(name = "into_mut")]
+    fn make_mut(&self, replica: Py<Replica>) -> TaskMut {
+        TaskMut {
+            task: self.0.clone(),
+            replica,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Task({}, {:?})",
+            self.0.get_uuid(),
+            self.0.get_description()
+        )
+    }
+}
+
+/// A task that can be modified.  Each setter immediately updates the replica.
+#[pyclass(unsendable)]
+pub struct TaskMut {
+    task: TCTask,
+    replica: Py<Replica>,
+}
+
+impl TaskMut {
+    /// Apply a modification to the task in its replica, keeping the updated task.
+    fn modify<F>(&mut self, py: Python, f: F) -> PyResult<()>
+    where
+        F: FnOnce(&mut TCTaskMut) -> taskchampion::Result<()>,
+    {
+        let mut replica = self.replica.borrow_mut(py);
+        let mut task = self.task.clone().into_mut(&mut replica.0);
+        let res = f(&mut task);
+        self.task = task.into_immut();
+        res.map_err(into_pyerr)
+    }
+}
+
+#[pymethods]
+impl TaskMut {
+    /// Get a snapshot of the task, including all modifications so far.
+    #[pyo3(name = "into_immut")]
+    fn snapshot(&self) -> Task {
+        Task(self.task.clone())
+    }
+
+    fn set_description(&mut self, py: Python, description: String) -> PyResult<()> {
+        self.modify(py, |t| t.set_description(description))
+    }
+
+    fn set_status(&mut self, py: Python, status: Status) -> PyResult<()> {
+        let status = status.into_tc()?;
+        self.modify(py, |t| t.set_status(status))
+    }
+
+    fn set_priority(&mut self, py: Python, priority: String) -> PyResult<()> {
+        self.modify(py, |t| t.set_priority(priority))
+    }
+
+    #[pyo3(signature = (project))]
+    fn set_project(&mut self, py: Python, project: Option<String>) -> PyResult<()> {
+        self.modify(py, |t| t.set_project(project))
+    }
+
+    #[pyo3(signature = (entry))]
+    fn set_entry(&mut self, py: Python, entry: Option<Bound<'_, PyDateTime>>) -> PyResult<()> {
+        let entry = from_datetime(entry.as_ref())?;
+        self.modify(py, |t| t.set_entry(entry))
+    }
+
+    #[pyo3(signature = (wait))]
+    fn set_wait(&mut self, py: Python, wait: Option<Bound<'_, PyDateTime>>) -> PyResult<()> {
+        let wait = from_datetime(wait.as_ref())?;
+        self.modify(py, |t| t.set_wait(wait))
+    }
+
+    #[pyo3(signature = (due))]
+    fn set_due(&mut self, py: Python, due: Option<Bound<'_, PyDateTime>>) -> PyResult<()> {
+        let due = from_datetime(due.as_ref())?;
+        self.modify(py, |t| t.set_due(due))
+    }
+
+    #[pyo3(signature = (scheduled))]
+    fn set_scheduled(
+        &mut self,
+        py: Python,
+        scheduled: Option<Bound<'_, PyDateTime>>,
+    ) -> PyResult<()> {
+        let scheduled = from_datetime(scheduled.as_ref())?;
+        self.modify(py, |t| t.set_scheduled(scheduled))
+    }
+
+    fn start(&mut self, py: Python) -> PyResult<()> {
+        self.modify(py, |t| t.start())
+    }
+
+    fn stop(&mut self, py: Python) -> PyResult<()> {
+        self.modify(py, |t| t.stop())
+    }
+
+    fn done(&mut self, py: Python) -> PyResult<()> {
+        self.modify(py, |t| t.done())
+    }
+
+    fn delete(&mut self, py: Python) -> PyResult<()> {
+        self.modify(py, |t| t.delete())
+    }
+
+    fn add_tag(&mut self, py: Python, tag: &str) -> PyResult<()> {
+        let tag = tag_from_str(tag)?;
+        self.modify(py, |t| t.add_tag(&tag))
+    }
+
+    fn remove_tag(&mut self, py: Python, tag: &str) -> PyResult<()> {
+        let tag = tag_from_str(tag)?;
+        self.modify(py, |t| t.remove_tag(&tag))
+    }
+
+    fn add_annotation(
+        &mut self,
+        py: Python,
+        entry: &Bound<'_, PyDateTime>,
+        description: String,
+    ) -> PyResult<()> {
+        let entry = from_datetime(Some(entry))?.unwrap();
+        self.modify(py, |t| t.add_annotation(Annotation { entry, description }))
+    }
+
+    /// Remove the annotation with the given entry time, if it exists.
+    fn remove_annotation(&mut self, py: Python, entry: &Bound<'_, PyDateTime>) -> PyResult<()> {
+        let entry = from_datetime(Some(entry))?.unwrap();
+        self.modify(py, |t| t.remove_annotation(entry))
+    }
+
+    fn set_uda(&mut self, py: Python, namespace: &str, key: &str, value: &str) -> PyResult<()> {
+        self.modify(py, |t| t.set_uda(namespace, key, value))
+    }
+
+    fn remove_uda(&mut self, py: Python, namespace: &str, key: &str) -> PyResult<()> {
+        self.modify(py, |t| t.remove_uda(namespace, key))
+    }
+
+    fn add_dependency(&mut self, py: Python, dep: &str) -> PyResult<()> {
+        let dep = uuid_from_str(dep)?;
+        self.modify(py, |t| t.add_dependency(dep))
+    }
+
+    fn remove_dependency(&mut self, py: Python, dep: &str) -> PyResult<()> {
+        let dep = uuid_from_str(dep)?;
+        self.modify(py, |t| t.remove_dependency(dep))
+    }
+}
@@ -0,0 +1,53 @@
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{timezone_utc, PyDateTime};
+use taskchampion::chrono::{DateTime, TimeZone, Utc};
+use taskchampion::Uuid;
+
+create_exception!(
+    taskchampion,
+    Error,
+    PyException,
+    "An error from TaskChampion, such as a failure to read the task database or to contact the \
+     sync server."
+);
+
+/// Convert an error from TaskChampion into a Python exception, including the full chain of
+/// context in the message.
+pub(crate) fn into_pyerr(err: taskchampion::Error) -> PyErr {
+    Error::new_err(format!("{:#}", err))
+}
+
+/// Parse a UUID given as a string.
+pub(crate) fn uuid_from_str(uuid: &str) -> PyResult<Uuid> {
+    Uuid::parse_str(uuid).map_err(|e| PyValueError::new_err(format!("invalid UUID: {}", e)))
+}
+
+/// Convert a timestamp into a timezone-aware `datetime.datetime` in UTC.
+pub(crate) fn to_datetime(
+    py: Python<'_>,
+    ts: Option<DateTime<Utc>>,
+) -> PyResult<Option<Bound<'_, PyDateTime>>> {
+    let ts = match ts {
+        Some(ts) => ts,
+        None => return Ok(None),
+    };
+    let utc = timezone_utc(py);
+    Ok(Some(PyDateTime::from_timestamp(
+        py,
+        ts.timestamp() as f64,
+        Some(&utc),
+    )?))
+}
+
+/// Convert a `datetime.datetime` into a timestamp, truncated to whole seconds.  As for
+/// `datetime.timestamp()`, naive values are interpreted in the local timezone.
+pub(crate) fn from_datetime(dt: Option<&Bound<'_, PyDateTime>>) -> PyResult<Option<DateTime<Utc>>> {
+    let dt = match dt {
+        Some(dt) => dt,
+        None => return Ok(None),
+    };
+    let secs: f64 = dt.call_method0("timestamp")?.extract()?;
+    Ok(Some(Utc.timestamp(secs.floor() as i64, 0)))
+}
@@ -0,0 +1,36 @@
+use crate::util::uuid_from_str;
+use pyo3::prelude::*;
+use taskchampion::WorkingSet as TCWorkingSet;
+
+/// A snapshot of a replica's working set, mapping small integers to the UUIDs of pending tasks.
+/// It is not updated when the replica changes.
+#[pyclass]
+pub struct WorkingSet(pub(crate) TCWorkingSet);
+
+#[pymethods]
+impl WorkingSet {
+    /// Get the number of tasks in the working set.
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Get the largest index in the working set, or zero if it is empty.
+    fn largest_index(&self) -> usize {
+        self.0.largest_index()
+    }
+
+    /// Get the UUID of the task at the given index, or None if there is no such task.
+    fn by_index(&self, index: usize) -> Option<String> {
+        self.0.by_index(index).map(|u| u.to_string())
+    }
+
+    /// Get the index of the task with the given UUID, or None if it is not in the working set.
+    fn by_uuid(&self, uuid: &str) -> PyResult<Option<usize>> {
+        Ok(self.0.by_uuid(uuid_from_str(uuid)?))
+    }
+
+    /// Get a list of (index, UUID) pairs for all tasks in the working set, in order.
+    fn items(&self) -> Vec<(usize, String)> {
+        self.0.iter().map(|(i, u)| (i, u.to_string())).collect()
+    }
+}
@@ -0,0 +1,90 @@
+from datetime import datetime, timezone
+
+import pytest
+
+from taskchampion import Error, Replica, Server, Status
+
+
+def test_new_task():
+    rep = Replica.new_in_memory()
+    task = rep.new_task(Status.Pending, "a task")
+    assert task.get_description() == "a task"
+    assert task.get_status() == Status.Pending
+    assert rep.get_task(task.get_uuid()).get_description() == "a task"
+    assert rep.all_task_uuids() == [task.get_uuid()]
+    assert list(rep.all_tasks().keys()) == [task.get_uuid()]
+
+
+def test_get_missing_task():
+    rep = Replica.new_in_memory()
+    assert rep.get_task("00000000-0000-0000-0000-000000000000") is None
+    with pytest.raises(ValueError):
+        rep.get_task("not-a-uuid")
+
+
+def test_modify_task():
+    rep = Replica.new_in_memory()
+    task = rep.new_task(Status.Pending, "a task").into_mut(rep)
+    task.set_description("a better task")
+    task.add_tag("home")
+    due = datetime(2030, 1, 2, 3, 4, 5, tzinfo=timezone.utc)
+    task.set_due(due)
+    task.set_uda("github", "id", "123")
+    task.start()
+    task = task.into_immut()
+
+    assert task.get_description() == "a better task"
+    assert task.has_tag("home")
+    assert task.get_due() == due
+    assert task.get_uda("github", "id") == "123"
+    assert task.is_active()
+
+    task = rep.get_task(task.get_uuid())
+    assert task.get_description() == "a better task"
+    assert "home" in task.get_tags()
+
+
+def test_invalid_modification():
+    rep = Replica.new_in_memory()
+    task = rep.new_task(Status.Pending, "a task").into_mut(rep)
+    with pytest.raises(ValueError):
+        task.add_tag("not a tag")
+    with pytest.raises(Error):
+        task.set_uda("", "description", "sneaky")
+
+
+def test_working_set():
+    rep = Replica.new_in_memory()
+    t1 = rep.new_task(Status.Pending, "one")
+    t2 = rep.new_task(Status.Pending, "two")
+    t1.into_mut(rep).done()
+    rep.rebuild_working_set(True)
+
+    ws = rep.working_set()
+    assert len(ws) == 1
+    assert ws.by_index(1) == t2.get_uuid()
+    assert ws.by_uuid(t1.get_uuid()) is None
+    assert ws.items() == [(1, t2.get_uuid())]
+
+
+def test_undo():
+    rep = Replica.new_in_memory()
+    rep.add_undo_point(True)
+    rep.new_task(Status.Pending, "a task")
+    assert rep.num_local_operations() > 0
+    assert rep.undo()
+    assert rep.all_task_uuids() == []
+
+
+def test_sync(tmp_path):
+    server_dir = tmp_path / "server"
+    server_dir.mkdir()
+    server = Server.local(str(server_dir))
+    rep1 = Replica(str(tmp_path / "rep1"))
+    rep2 = Replica(str(tmp_path / "rep2"))
+
+    task = rep1.new_task(Status.Pending, "a task")
+    rep1.sync(server)
+    assert rep1.num_local_operations() == 0
+    rep2.sync(server, avoid_snapshots=True)
+    assert rep2.get_task(task.get_uuid()).get_description() == "a task"
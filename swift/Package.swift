@@ -0,0 +1,17 @@
+// swift-tools-version:5.5
+import PackageDescription
+
+let package = Package(
+    name: "TaskChampion",
+    platforms: [.iOS(.v13), .macOS(.v10_15)],
+    products: [
+        .library(name: "TaskChampion", targets: ["TaskChampion"]),
+    ],
+    targets: [
+        // The C API, from lib/taskchampion.h.  The taskchampionlib static library must be built
+        // separately and made available to the linker; see README.md.
+        .systemLibrary(name: "CTaskChampion", path: "Sources/CTaskChampion"),
+        .target(name: "TaskChampion", dependencies: ["CTaskChampion"]),
+        .testTarget(name: "TaskChampionTests", dependencies: ["TaskChampion"]),
+    ]
+)
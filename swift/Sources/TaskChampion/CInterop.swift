@@ -0,0 +1,76 @@
+import CTaskChampion
+import Foundation
+
+// Helpers for converting values to and from the C API.  These follow the ownership rules
+// described in taskchampion.h: a TCString passed to a tc_… function is owned by that function,
+// and strings and lists returned from tc_… functions must be freed by the caller.
+
+/// Create a TCString containing a copy of the given string, to be passed to a tc_… function.
+func tcString(_ string: String) -> TCString {
+    return tc_string_clone(string)
+}
+
+/// Convert a TCString returned from a tc_… function into a Swift string, and free it.  Invalid
+/// UTF-8 is replaced with U+FFFD.  Returns nil if the string has a NULL ptr field.
+func takeString(_ tcstring: TCString) -> String? {
+    var tcstring = tcstring
+    guard tcstring.ptr != nil else {
+        return nil
+    }
+    defer { tc_string_free(&tcstring) }
+    return tc_string_content_lossy(&tcstring).map { String(cString: $0) }
+}
+
+/// Convert a TCStringList returned from a tc_… function into an array of strings, and free it.
+func takeStringList(_ list: TCStringList) -> [String] {
+    var list = list
+    defer { tc_string_list_free(&list) }
+    guard let items = list.items else {
+        return []
+    }
+    return (0..<list.len).compactMap { i in
+        tc_string_content_lossy(items + i).map { String(cString: $0) }
+    }
+}
+
+/// Convert a TCUuidList returned from a tc_… function into an array of UUIDs, and free it.
+/// Returns nil if the list has a NULL items field, signalling an error.
+func takeUuidList(_ list: TCUuidList) -> [UUID]? {
+    var list = list
+    defer { tc_uuid_list_free(&list) }
+    guard let items = list.items else {
+        return nil
+    }
+    return (0..<list.len).map { UUID(items[$0]) }
+}
+
+/// Convert a timestamp from the C API, where zero means "not set", into a date.
+func date(_ timestamp: time_t) -> Date? {
+    return timestamp == 0 ? nil : Date(timeIntervalSince1970: TimeInterval(timestamp))
+}
+
+/// Convert a date into a timestamp for the C API, where zero means "not set".  Fractional seconds
+/// are discarded.
+func timestamp(_ date: Date?) -> time_t {
+    guard let date = date else {
+        return 0
+    }
+    return time_t(date.timeIntervalSince1970.rounded(.down))
+}
+
+extension UUID {
+    init(_ tcuuid: TCUuid) {
+        self.init(uuid: tcuuid.bytes)
+    }
+
+    var tcUuid: TCUuid {
+        return TCUuid(bytes: uuid)
+    }
+}
+
+/// Check whether a result returned from a tc_… function indicates success.  This compares with
+/// the value of TC_RESULT_OK, since the C enum defining that constant is shadowed by the TCResult
+/// typedef.
+func succeeded(_ result: Int32) -> Bool {
+    return result == 0
+}
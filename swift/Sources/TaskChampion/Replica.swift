@@ -0,0 +1,190 @@
+import CTaskChampion
+import Foundation
+
+/// A replica of the task database.  See the Rust documentation for
+/// [`taskchampion::Replica`](https://docs.rs/taskchampion/latest/taskchampion/struct.Replica.html).
+///
+/// A replica may be used from any thread or task.  Operations on a replica are serialized, and
+/// `sync` runs in the background without blocking the caller.
+public final class Replica: @unchecked Sendable {
+    private let rep: OpaquePointer
+    private let queue = DispatchQueue(label: "org.taskchampion.Replica")
+
+    private init(_ rep: OpaquePointer) {
+        self.rep = rep
+    }
+
+    /// Open the on-disk replica in the given directory, creating it if it does not exist.
+    public convenience init(path: String) throws {
+        var error = TCString()
+        guard let rep = tc_replica_new_on_disk(tcString(path), &error) else {
+            throw TaskChampionError(.other, takeString(error) ?? "unknown error")
+        }
+        self.init(rep)
+    }
+
+    /// Create a new replica that is stored in memory and discarded when it is no longer used.
+    public static func inMemory() -> Replica {
+        return Replica(tc_replica_new_in_memory())
+    }
+
+    deinit {
+        tc_replica_free(rep)
+    }
+
+    /// Call `f` with the TCReplica, serialized with all other uses of this replica.
+    func withReplica<T>(_ f: (OpaquePointer) throws -> T) rethrows -> T {
+        return try queue.sync { try f(rep) }
+    }
+
+    /// Throw the replica's latest error, if any.
+    func checkError(_ rep: OpaquePointer) throws {
+        let kind = tc_replica_error_kind(rep)
+        if let message = takeString(tc_replica_error(rep)) {
+            throw TaskChampionError(TaskChampionError.Kind(kind), message)
+        }
+    }
+
+    /// Throw the replica's latest error if the result is not TC_RESULT_OK.
+    private func check(_ rep: OpaquePointer, _ result: Int32) throws {
+        if !succeeded(result) {
+            try checkError(rep)
+            throw TaskChampionError(.other, "unknown error")
+        }
+    }
+
+    /// Create a new task with the given status and description.
+    public func newTask(status: Status, description: String) throws -> Task {
+        return try withReplica { rep in
+            guard let task = tc_replica_new_task(rep, status.tcStatus, tcString(description))
+            else {
+                try checkError(rep)
+                throw TaskChampionError(.other, "unknown error")
+            }
+            defer { tc_task_free(task) }
+            return Task(task)
+        }
+    }
+
+    /// Create a new task with the given UUID, or get the existing task if one exists.  This is
+    /// useful for importing tasks from another source.
+    public func importTask(uuid: UUID) throws -> Task {
+        return try withReplica { rep in
+            guard let task = tc_replica_import_task_with_uuid(rep, uuid.tcUuid) else {
+                try checkError(rep)
+                throw TaskChampionError(.other, "unknown error")
+            }
+            defer { tc_task_free(task) }
+            return Task(task)
+        }
+    }
+
+    /// Get the task with the given UUID, or nil if it does not exist.
+    public func task(uuid: UUID) throws -> Task? {
+        return try withReplica { rep in
+            guard let task = tc_replica_get_task(rep, uuid.tcUuid) else {
+                try checkError(rep)
+                return nil
+            }
+            defer { tc_task_free(task) }
+            return Task(task)
+        }
+    }
+
+    /// Get all tasks, keyed by UUID.
+    public func allTasks() throws -> [UUID: Task] {
+        return try withReplica { rep in
+            var list = tc_replica_all_tasks(rep)
+            defer { tc_task_list_free(&list) }
+            guard let items = list.items else {
+                try checkError(rep)
+                throw TaskChampionError(.other, "unknown error")
+            }
+            var tasks = [UUID: Task]()
+            for i in 0..<list.len {
+                if let item = items[i] {
+                    let task = Task(item)
+                    tasks[task.uuid] = task
+                }
+            }
+            return tasks
+        }
+    }
+
+    /// Get the UUIDs of all tasks.
+    public func allTaskUuids() throws -> [UUID] {
+        return try withReplica { rep in
+            guard let uuids = takeUuidList(tc_replica_all_task_uuids(rep)) else {
+                try checkError(rep)
+                throw TaskChampionError(.other, "unknown error")
+            }
+            return uuids
+        }
+    }
+
+    /// Get the current working set.
+    public func workingSet() throws -> WorkingSet {
+        return try withReplica { rep in
+            guard let ws = tc_replica_working_set(rep) else {
+                try checkError(rep)
+                throw TaskChampionError(.other, "unknown error")
+            }
+            defer { tc_working_set_free(ws) }
+            return WorkingSet(ws)
+        }
+    }
+
+    /// Rebuild the working set, so that it contains exactly the pending tasks.  If `renumber` is
+    /// true, existing tasks may be moved to new indices.
+    public func rebuildWorkingSet(renumber: Bool) throws {
+        try withReplica { rep in
+            try check(rep, tc_replica_rebuild_working_set(rep, renumber))
+        }
+    }
+
+    /// Add an undo point, if one has not already been added by this replica.  If `force` is
+    /// true, an undo point is always added.
+    public func addUndoPoint(force: Bool) throws {
+        try withReplica { rep in
+            try check(rep, tc_replica_add_undo_point(rep, force))
+        }
+    }
+
+    /// Undo local operations until the most recent undo point, returning false if there are no
+    /// local operations to undo.
+    public func undo() throws -> Bool {
+        return try withReplica { rep in
+            var undone: Int32 = 0
+            try check(rep, tc_replica_undo(rep, &undone))
+            return undone > 0
+        }
+    }
+
+    /// Get the number of local, un-synchronized operations, not including undo points.
+    public func numLocalOperations() throws -> Int {
+        return try withReplica { rep in
+            let count = tc_replica_num_local_operations(rep)
+            if count < 0 {
+                try checkError(rep)
+                throw TaskChampionError(.other, "unknown error")
+            }
+            return Int(count)
+        }
+    }
+
+    /// Synchronize this replica with the given server.  Synchronization runs in the background,
+    /// and other operations on this replica wait until it is complete.
+    public func sync(with server: Server, avoidSnapshots: Bool = false) async throws {
+        try await withCheckedThrowingContinuation { (cont: CheckedContinuation<Void, Error>) in
+            queue.async {
+                cont.resume(
+                    with: Result(catching: {
+                        try server.withServer { server in
+                            try self.check(
+                                self.rep, tc_replica_sync(self.rep, server, avoidSnapshots))
+                        }
+                    }))
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+import CTaskChampion
+import Foundation
+
+/// A sync server, to be passed to `Replica.sync`.
+public final class Server: @unchecked Sendable {
+    private let server: OpaquePointer
+    private let lock = NSLock()
+
+    private init(_ server: OpaquePointer) {
+        self.server = server
+    }
+
+    /// Create a server that stores its data in a local directory, for synchronizing replicas on
+    /// the same device.
+    public static func local(serverDir: String) throws -> Server {
+        var error = TCString()
+        guard let server = tc_server_new_local(tcString(serverDir), &error) else {
+            throw TaskChampionError(.other, takeString(error) ?? "unknown error")
+        }
+        return Server(server)
+    }
+
+    /// Create a server that connects to a taskchampion-sync-server at the given origin.  The
+    /// client key identifies this user's task data on the server, and the encryption secret is
+    /// used to encrypt that data.
+    public static func remote(origin: String, clientKey: UUID, encryptionSecret: String) throws
+        -> Server
+    {
+        var error = TCString()
+        guard
+            let server = tc_server_new_remote(
+                tcString(origin), clientKey.tcUuid, tcString(encryptionSecret), &error)
+        else {
+            throw TaskChampionError(.other, takeString(error) ?? "unknown error")
+        }
+        return Server(server)
+    }
+
+    deinit {
+        tc_server_free(server)
+    }
+
+    /// Call `f` with the TCServer, serialized with all other uses of this server.
+    func withServer<T>(_ f: (OpaquePointer) throws -> T) rethrows -> T {
+        lock.lock()
+        defer { lock.unlock() }
+        return try f(server)
+    }
+}
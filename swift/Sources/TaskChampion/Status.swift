@@ -0,0 +1,29 @@
+import CTaskChampion
+
+/// The status of a task.
+public enum Status: Equatable {
+    case pending
+    case completed
+    case deleted
+    /// A status in the task database that was not recognized.  Tasks with this status should be
+    /// ignored, and it cannot be given to `TaskMut.setStatus`.
+    case unknown
+
+    init(_ status: TCStatus) {
+        switch status {
+        case TC_STATUS_PENDING: self = .pending
+        case TC_STATUS_COMPLETED: self = .completed
+        case TC_STATUS_DELETED: self = .deleted
+        default: self = .unknown
+        }
+    }
+
+    var tcStatus: TCStatus {
+        switch self {
+        case .pending: return TC_STATUS_PENDING
+        case .completed: return TC_STATUS_COMPLETED
+        case .deleted: return TC_STATUS_DELETED
+        case .unknown: return TC_STATUS_UNKNOWN
+        }
+    }
+}
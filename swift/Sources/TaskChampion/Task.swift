@@ -0,0 +1,228 @@
+import CTaskChampion
+import Foundation
+
+/// An annotation on a task.
+public struct Annotation: Equatable {
+    public var entry: Date
+    public var description: String
+
+    public init(entry: Date, description: String) {
+        self.entry = entry
+        self.description = description
+    }
+}
+
+/// A user-defined attribute of a task.
+public struct Uda: Equatable {
+    public var namespace: String
+    public var key: String
+    public var value: String
+}
+
+/// A snapshot of a task, as of when it was read from the replica.  Tasks are values, and are not
+/// updated when the replica changes.  Use `Replica.update` to modify a task.
+public struct Task: Identifiable, Equatable {
+    public let uuid: UUID
+    public let status: Status
+    public let description: String
+    public let entry: Date?
+    public let wait: Date?
+    public let due: Date?
+    public let scheduled: Date?
+    public let modified: Date?
+    public let end: Date?
+    public let isWaiting: Bool
+    public let isActive: Bool
+    /// The task's tags, including synthetic tags such as `PENDING`.
+    public let tags: [String]
+    public let annotations: [Annotation]
+    public let udas: [Uda]
+    /// The UUIDs of the tasks on which this task depends.
+    public let dependencies: [UUID]
+
+    public var id: UUID {
+        return uuid
+    }
+
+    /// Read all properties of the given TCTask, which remains owned by the caller.
+    init(_ task: OpaquePointer) {
+        uuid = UUID(tc_task_get_uuid(task))
+        status = Status(tc_task_get_status(task))
+        description = takeString(tc_task_get_description(task)) ?? ""
+        entry = date(tc_task_get_entry(task))
+        wait = date(tc_task_get_wait(task))
+        due = date(tc_task_get_due(task))
+        scheduled = date(tc_task_get_scheduled(task))
+        modified = date(tc_task_get_modified(task))
+        end = date(tc_task_get_end(task))
+        isWaiting = tc_task_is_waiting(task)
+        isActive = tc_task_is_active(task)
+        tags = takeStringList(tc_task_get_tags(task))
+        dependencies = takeUuidList(tc_task_get_dependencies(task)) ?? []
+
+        var anns = tc_task_get_annotations(task)
+        annotations = (0..<anns.len).compactMap { i in
+            guard let items = anns.items,
+                  let entry = date(items[i].entry),
+                  let description = tc_string_content_lossy(&items[i].description)
+            else {
+                return nil
+            }
+            return Annotation(entry: entry, description: String(cString: description))
+        }
+        tc_annotation_list_free(&anns)
+
+        var tcudas = tc_task_get_udas(task)
+        udas = (0..<tcudas.len).compactMap { i in
+            guard let items = tcudas.items,
+                  let ns = tc_string_content_lossy(&items[i].ns),
+                  let key = tc_string_content_lossy(&items[i].key),
+                  let value = tc_string_content_lossy(&items[i].value)
+            else {
+                return nil
+            }
+            return Uda(
+                namespace: String(cString: ns),
+                key: String(cString: key),
+                value: String(cString: value))
+        }
+        tc_uda_list_free(&tcudas)
+    }
+
+    /// Check whether the task has the given tag, which may be a user tag or a synthetic tag.
+    public func hasTag(_ tag: String) -> Bool {
+        return tags.contains(tag)
+    }
+
+    /// Get the value of a user-defined attribute, or nil if it is not set.
+    public func uda(namespace: String, key: String) -> String? {
+        return udas.first { $0.namespace == namespace && $0.key == key }?.value
+    }
+}
+
+/// A task that is being modified, within a call to `Replica.update`.  Each modification
+/// immediately updates the replica.  A TaskMut must not be used after `Replica.update` returns.
+public final class TaskMut {
+    fileprivate var task: OpaquePointer?
+
+    init(_ task: OpaquePointer) {
+        self.task = task
+    }
+
+    /// Run the given tc_task_… function on the mutable task, throwing its error on failure.
+    private func check(_ f: (OpaquePointer) -> Int32) throws {
+        guard let task = task else {
+            preconditionFailure("TaskMut used after Replica.update returned")
+        }
+        if !succeeded(f(task)) {
+            let message = takeString(tc_task_error(task)) ?? "unknown error"
+            throw TaskChampionError(.other, message)
+        }
+    }
+
+    public func setDescription(_ description: String) throws {
+        try check { tc_task_set_description($0, tcString(description)) }
+    }
+
+    /// Set the task's status.  The `.unknown` status cannot be set.
+    public func setStatus(_ status: Status) throws {
+        if status == .unknown {
+            throw TaskChampionError(.invalidArgument, "cannot set the unknown status")
+        }
+        try check { tc_task_set_status($0, status.tcStatus) }
+    }
+
+    /// Set the task's wait time, or remove it if `wait` is nil.
+    public func setWait(_ wait: Date?) throws {
+        try check { tc_task_set_wait($0, timestamp(wait)) }
+    }
+
+    /// Set the task's due time, or remove it if `due` is nil.
+    public func setDue(_ due: Date?) throws {
+        try check { tc_task_set_due($0, timestamp(due)) }
+    }
+
+    /// Set the task's scheduled time, or remove it if `scheduled` is nil.
+    public func setScheduled(_ scheduled: Date?) throws {
+        try check { tc_task_set_scheduled($0, timestamp(scheduled)) }
+    }
+
+    public func start() throws {
+        try check { tc_task_start($0) }
+    }
+
+    public func stop() throws {
+        try check { tc_task_stop($0) }
+    }
+
+    public func done() throws {
+        try check { tc_task_done($0) }
+    }
+
+    public func delete() throws {
+        try check { tc_task_delete($0) }
+    }
+
+    public func addTag(_ tag: String) throws {
+        try check { tc_task_add_tag($0, tcString(tag)) }
+    }
+
+    public func removeTag(_ tag: String) throws {
+        try check { tc_task_remove_tag($0, tcString(tag)) }
+    }
+
+    public func addAnnotation(_ annotation: Annotation) throws {
+        var tcann = TCAnnotation(
+            entry: timestamp(annotation.entry), description: tcString(annotation.description))
+        try check { tc_task_add_annotation($0, &tcann) }
+    }
+
+    /// Remove the annotation with the given entry time, if it exists.
+    public func removeAnnotation(entry: Date) throws {
+        try check { tc_task_remove_annotation($0, Int64(timestamp(entry))) }
+    }
+
+    public func setUda(namespace: String, key: String, value: String) throws {
+        try check { tc_task_set_uda($0, tcString(namespace), tcString(key), tcString(value)) }
+    }
+
+    public func removeUda(namespace: String, key: String) throws {
+        try check { tc_task_remove_uda($0, tcString(namespace), tcString(key)) }
+    }
+
+    public func addDependency(_ dep: UUID) throws {
+        try check { tc_task_add_dependency($0, dep.tcUuid) }
+    }
+
+    public func removeDependency(_ dep: UUID) throws {
+        try check { tc_task_remove_dependency($0, dep.tcUuid) }
+    }
+
+    /// Invalidate this TaskMut, at the end of `Replica.update`.
+    fileprivate func finish() {
+        task = nil
+    }
+}
+
+extension Replica {
+    /// Modify the task with the given UUID, returning the updated task.  The modifications made
+    /// by `body` are applied immediately, even if it later throws.  The body must not call other
+    /// methods of this replica.
+    @discardableResult
+    public func update(_ uuid: UUID, _ body: (TaskMut) throws -> Void) throws -> Task {
+        return try withReplica { rep in
+            guard let task = tc_replica_get_task(rep, uuid.tcUuid) else {
+                try checkError(rep)
+                throw TaskChampionError(.invalidArgument, "no task with UUID \(uuid)")
+            }
+            defer { tc_task_free(task) }
+            tc_task_to_mut(task, rep)
+            let taskMut = TaskMut(task)
+            let result = Result(catching: { try body(taskMut) })
+            taskMut.finish()
+            tc_task_to_immut(task)
+            try result.get()
+            return Task(task)
+        }
+    }
+}
@@ -0,0 +1,50 @@
+import CTaskChampion
+
+/// An error from TaskChampion.  The `kind` allows callers to react to categories of failure
+/// without parsing the message.
+public struct TaskChampionError: Error, CustomStringConvertible {
+    public enum Kind {
+        /// An error that does not fall into any of the other kinds.
+        case other
+        /// An error reading or writing files, such as a missing directory or a full disk.
+        case io
+        /// The task database is corrupt, or is not a task database.
+        case corruptData
+        /// The sync server could not be reached.
+        case syncConnection
+        /// The sync server rejected this replica, or its data could not be decrypted, typically
+        /// because the encryption secret is wrong.
+        case syncAuth
+        /// The replica is too far out of date to be synchronized with the server.
+        case outOfSync
+        /// An argument was invalid, such as a malformed tag.
+        case invalidArgument
+        /// Synchronization was cancelled.
+        case cancelled
+
+        init(_ kind: TCErrorKind) {
+            switch kind {
+            case TC_ERROR_KIND_IO: self = .io
+            case TC_ERROR_KIND_CORRUPT_DATA: self = .corruptData
+            case TC_ERROR_KIND_SYNC_CONNECTION: self = .syncConnection
+            case TC_ERROR_KIND_SYNC_AUTH: self = .syncAuth
+            case TC_ERROR_KIND_OUT_OF_SYNC: self = .outOfSync
+            case TC_ERROR_KIND_INVALID_ARGUMENT: self = .invalidArgument
+            case TC_ERROR_KIND_CANCELLED: self = .cancelled
+            default: self = .other
+            }
+        }
+    }
+
+    public let kind: Kind
+    public let message: String
+
+    init(_ kind: Kind, _ message: String) {
+        self.kind = kind
+        self.message = message
+    }
+
+    public var description: String {
+        return message
+    }
+}
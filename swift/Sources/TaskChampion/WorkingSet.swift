@@ -0,0 +1,46 @@
+import CTaskChampion
+import Foundation
+
+/// A snapshot of a replica's working set, mapping small integers to the UUIDs of pending tasks.
+/// It is not updated when the replica changes.
+public struct WorkingSet: Equatable {
+    private let uuids: [Int: UUID]
+
+    /// The largest index in the working set, or zero if it is empty.
+    public let largestIndex: Int
+
+    /// Read the content of the given TCWorkingSet, which remains owned by the caller.
+    init(_ ws: OpaquePointer) {
+        largestIndex = tc_working_set_largest_index(ws)
+        var uuids = [Int: UUID]()
+        if largestIndex > 0 {
+            for index in 1...largestIndex {
+                var uuid = TCUuid()
+                if tc_working_set_by_index(ws, index, &uuid) {
+                    uuids[index] = UUID(uuid)
+                }
+            }
+        }
+        self.uuids = uuids
+    }
+
+    /// The number of tasks in the working set.
+    public var count: Int {
+        return uuids.count
+    }
+
+    /// Get the UUID of the task at the given index, or nil if there is no such task.
+    public func uuid(at index: Int) -> UUID? {
+        return uuids[index]
+    }
+
+    /// Get the index of the task with the given UUID, or nil if it is not in the working set.
+    public func index(of uuid: UUID) -> Int? {
+        return uuids.first { $0.value == uuid }?.key
+    }
+
+    /// The (index, UUID) pairs for all tasks in the working set, in order.
+    public var items: [(index: Int, uuid: UUID)] {
+        return uuids.sorted { $0.key < $1.key }.map { (index: $0.key, uuid: $0.value) }
+    }
+}
@@ -0,0 +1,107 @@
+import Foundation
+import XCTest
+
+@testable import TaskChampion
+
+final class ReplicaTests: XCTestCase {
+    func testNewTask() throws {
+        let rep = Replica.inMemory()
+        let task = try rep.newTask(status: .pending, description: "a task")
+        XCTAssertEqual(task.status, .pending)
+        XCTAssertEqual(task.description, "a task")
+        XCTAssertNotNil(task.entry)
+        XCTAssertNil(task.wait)
+        XCTAssertTrue(task.hasTag("PENDING"))
+        XCTAssertEqual(try rep.allTaskUuids(), [task.uuid])
+        XCTAssertEqual(try rep.task(uuid: task.uuid), task)
+    }
+
+    func testMissingTask() throws {
+        let rep = Replica.inMemory()
+        XCTAssertNil(try rep.task(uuid: UUID()))
+        XCTAssertThrowsError(try rep.update(UUID()) { try $0.done() })
+    }
+
+    func testUpdate() throws {
+        let rep = Replica.inMemory()
+        let task = try rep.newTask(status: .pending, description: "a task")
+        let due = Date(timeIntervalSince1970: 1_700_000_000)
+        let updated = try rep.update(task.uuid) { task in
+            try task.setDescription("changed")
+            try task.setDue(due)
+            try task.addTag("next")
+            try task.setUda(namespace: "ns", key: "key", value: "value")
+            try task.addAnnotation(Annotation(entry: due, description: "a note"))
+            try task.start()
+        }
+        XCTAssertEqual(updated.description, "changed")
+        XCTAssertEqual(updated.due, due)
+        XCTAssertTrue(updated.hasTag("next"))
+        XCTAssertEqual(updated.uda(namespace: "ns", key: "key"), "value")
+        XCTAssertEqual(updated.annotations, [Annotation(entry: due, description: "a note")])
+        XCTAssertTrue(updated.isActive)
+        XCTAssertEqual(try rep.task(uuid: task.uuid), updated)
+
+        // the original value is unchanged
+        XCTAssertEqual(task.description, "a task")
+
+        let done = try rep.update(task.uuid) { try $0.done() }
+        XCTAssertEqual(done.status, .completed)
+        XCTAssertNotNil(done.end)
+    }
+
+    func testUpdateErrors() throws {
+        let rep = Replica.inMemory()
+        let task = try rep.newTask(status: .pending, description: "a task")
+        XCTAssertThrowsError(try rep.update(task.uuid) { try $0.addTag("bad tag") })
+        XCTAssertThrowsError(try rep.update(task.uuid) { try $0.setStatus(.unknown) }) { error in
+            XCTAssertEqual((error as? TaskChampionError)?.kind, .invalidArgument)
+        }
+    }
+
+    func testDependencies() throws {
+        let rep = Replica.inMemory()
+        let t1 = try rep.newTask(status: .pending, description: "one")
+        let t2 = try rep.newTask(status: .pending, description: "two")
+        let updated = try rep.update(t1.uuid) { try $0.addDependency(t2.uuid) }
+        XCTAssertEqual(updated.dependencies, [t2.uuid])
+    }
+
+    func testWorkingSet() throws {
+        let rep = Replica.inMemory()
+        let task = try rep.newTask(status: .pending, description: "a task")
+        try rep.rebuildWorkingSet(renumber: true)
+        let ws = try rep.workingSet()
+        XCTAssertEqual(ws.count, 1)
+        XCTAssertEqual(ws.largestIndex, 1)
+        XCTAssertEqual(ws.uuid(at: 1), task.uuid)
+        XCTAssertNil(ws.uuid(at: 2))
+        XCTAssertEqual(ws.index(of: task.uuid), 1)
+    }
+
+    func testUndo() throws {
+        let rep = Replica.inMemory()
+        try rep.addUndoPoint(force: true)
+        _ = try rep.newTask(status: .pending, description: "a task")
+        XCTAssertTrue(try rep.undo())
+        XCTAssertEqual(try rep.allTasks().count, 0)
+        XCTAssertFalse(try rep.undo())
+    }
+
+    func testSync() async throws {
+        let dir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        let serverDir = dir.appendingPathComponent("server")
+        try FileManager.default.createDirectory(at: serverDir, withIntermediateDirectories: true)
+        defer { try? FileManager.default.removeItem(at: dir) }
+
+        let rep1 = try Replica(path: dir.appendingPathComponent("rep1").path)
+        let rep2 = try Replica(path: dir.appendingPathComponent("rep2").path)
+        let server = try Server.local(serverDir: serverDir.path)
+
+        let task = try rep1.newTask(status: .pending, description: "a task")
+        try await rep1.sync(with: server)
+        try await rep2.sync(with: server)
+        XCTAssertEqual(try rep2.task(uuid: task.uuid)?.description, "a task")
+    }
+}
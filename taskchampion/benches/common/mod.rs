@@ -0,0 +1,69 @@
+//! Utilities shared by the benchmarks, for building replicas of a realistic size and shape.
+
+// each benchmark uses only some of these utilities
+#![allow(dead_code)]
+
+use std::path::Path;
+use taskchampion::storage::{SqliteStorage, Storage, TaskMap};
+use taskchampion::{Replica, Uuid};
+
+/// Fixed timestamp for generated tasks, so that runs are comparable.
+const BASE_TIME: i64 = 1_600_000_000;
+
+/// Generate the properties of the `i`th task in a benchmark replica.  The mix is roughly what a
+/// long-lived replica looks like: most tasks completed or deleted, with a minority pending, and
+/// a scattering of projects, tags, due dates, and annotations.
+pub fn task_properties(i: usize) -> TaskMap {
+    let mut task = TaskMap::new();
+    let status = match i % 10 {
+        0..=2 => "pending",
+        3..=8 => "completed",
+        _ => "deleted",
+    };
+    let entry = BASE_TIME + i as i64 * 60;
+    task.insert("status".into(), status.into());
+    task.insert("description".into(), format!("task number {}", i));
+    task.insert("entry".into(), entry.to_string());
+    task.insert("modified".into(), (entry + 30).to_string());
+    if status != "pending" {
+        task.insert("end".into(), (entry + 30).to_string());
+    }
+    if i % 4 != 3 {
+        task.insert("project".into(), format!("project{}", i % 20));
+    }
+    if i % 3 == 1 {
+        task.insert("tag_next".into(), "".into());
+    }
+    if i % 7 == 2 {
+        task.insert("tag_home".into(), "".into());
+    }
+    if i % 5 == 4 {
+        task.insert("due".into(), (entry + 86400).to_string());
+    }
+    if i % 11 == 5 {
+        task.insert(format!("annotation_{}", entry + 10), "an annotation".into());
+    }
+    task
+}
+
+/// Create an on-disk storage in `dir` containing `num_tasks` tasks, with no operations and an
+/// empty working set.  The tasks are written in a single transaction, which is much faster than
+/// creating them through a `Replica`.
+pub fn populated_storage(dir: &Path, num_tasks: usize) -> Box<dyn Storage> {
+    let mut storage = SqliteStorage::new(dir).unwrap();
+    {
+        let mut txn = storage.txn().unwrap();
+        for i in 0..num_tasks {
+            txn.set_task(Uuid::new_v4(), task_properties(i)).unwrap();
+        }
+        txn.commit().unwrap();
+    }
+    Box::new(storage)
+}
+
+/// Create a replica in `dir` containing `num_tasks` tasks, with its working set built.
+pub fn populated_replica(dir: &Path, num_tasks: usize) -> Replica {
+    let mut replica = Replica::new(populated_storage(dir, num_tasks));
+    replica.rebuild_working_set(true).unwrap();
+    replica
+}
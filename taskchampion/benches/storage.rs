@@ -0,0 +1,37 @@
+//! Benchmarks for reading tasks from on-disk storage.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use taskchampion::Replica;
+use tempfile::TempDir;
+
+mod common;
+
+/// Read all tasks from a replica, including building the `Task` values.
+fn all_tasks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("all_tasks");
+    group.sample_size(10);
+    for num_tasks in [10_000, 100_000].iter() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut replica = Replica::new(common::populated_storage(tmp_dir.path(), *num_tasks));
+        group.bench_with_input(BenchmarkId::from_parameter(num_tasks), num_tasks, |b, _| {
+            b.iter(|| replica.all_tasks().unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Read only the UUIDs of all tasks from a replica.
+fn all_task_uuids(c: &mut Criterion) {
+    let mut group = c.benchmark_group("all_task_uuids");
+    for num_tasks in [10_000, 100_000].iter() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut replica = Replica::new(common::populated_storage(tmp_dir.path(), *num_tasks));
+        group.bench_with_input(BenchmarkId::from_parameter(num_tasks), num_tasks, |b, _| {
+            b.iter(|| replica.all_task_uuids().unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, all_tasks, all_task_uuids);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+//! Benchmarks for synchronization, dominated by applying operations from the server.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use taskchampion::storage::InMemoryStorage;
+use taskchampion::{Replica, ServerConfig, Status};
+use tempfile::TempDir;
+
+mod common;
+
+fn new_inmemory_replica() -> Replica {
+    Replica::new(Box::new(InMemoryStorage::new()))
+}
+
+/// Sync a new, empty replica from a server holding the operations that created `num_tasks`
+/// tasks, applying all of those operations.
+fn apply_remote_operations(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply_remote_operations");
+    group.sample_size(10);
+    for num_tasks in [1_000, 10_000].iter() {
+        let server_dir = TempDir::new().unwrap();
+        let mut server = ServerConfig::Local {
+            server_dir: server_dir.path().into(),
+        }
+        .into_server()
+        .unwrap();
+
+        // populate the server from another replica
+        let mut source = new_inmemory_replica();
+        for i in 0..*num_tasks {
+            let mut properties = common::task_properties(i);
+            let description = properties.remove("description").unwrap();
+            source
+                .new_task_with_properties(Status::Pending, description, properties)
+                .unwrap();
+        }
+        source.sync(&mut server, true).unwrap();
+
+        group.bench_with_input(BenchmarkId::from_parameter(num_tasks), num_tasks, |b, _| {
+            b.iter_batched(
+                new_inmemory_replica,
+                |mut replica| replica.sync(&mut server, true).unwrap(),
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, apply_remote_operations);
+criterion_main!(benches);
@@ -0,0 +1,89 @@
+//! Benchmarks for operations on the task database as a whole: rebuilding the working set and
+//! scanning tasks with a filter.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::convert::TryFrom;
+use taskchampion::{Status, Tag};
+use tempfile::TempDir;
+
+mod common;
+
+/// Rebuild the working set, both preserving and renumbering existing indices.
+fn rebuild_working_set(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rebuild_working_set");
+    group.sample_size(10);
+    for num_tasks in [10_000, 100_000].iter() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut replica = common::populated_replica(tmp_dir.path(), *num_tasks);
+        for renumber in [false, true].iter() {
+            let id = format!("{}/renumber={}", num_tasks, renumber);
+            group.bench_function(BenchmarkId::from_parameter(id), |b| {
+                b.iter(|| replica.rebuild_working_set(*renumber).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Evaluate a typical report filter (pending tasks in a project with a tag, that are not
+/// waiting or blocked) against every task, as the CLI does for filters that are not limited
+/// to the working set.
+fn filter_all_tasks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filter_all_tasks");
+    group.sample_size(10);
+    let tag = Tag::try_from("next").unwrap();
+    for num_tasks in [10_000, 100_000].iter() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut replica = common::populated_replica(tmp_dir.path(), *num_tasks);
+        group.bench_with_input(BenchmarkId::from_parameter(num_tasks), num_tasks, |b, _| {
+            b.iter(|| {
+                replica
+                    .all_tasks()
+                    .unwrap()
+                    .values()
+                    .filter(|t| {
+                        t.get_status() == Status::Pending
+                            && t.get_project() == Some("project1")
+                            && t.has_tag(&tag)
+                            && !t.is_waiting()
+                            && !t.is_blocked()
+                    })
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Evaluate the same filter against only the tasks in the working set, as the CLI does for
+/// filters that only match pending tasks.
+fn filter_working_set(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filter_working_set");
+    let tag = Tag::try_from("next").unwrap();
+    for num_tasks in [10_000, 100_000].iter() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut replica = common::populated_replica(tmp_dir.path(), *num_tasks);
+        group.bench_with_input(BenchmarkId::from_parameter(num_tasks), num_tasks, |b, _| {
+            b.iter(|| {
+                let working_set = replica.working_set().unwrap();
+                let mut count = 0;
+                for (_, uuid) in working_set.iter() {
+                    let t = replica.get_task(uuid).unwrap().unwrap();
+                    if t.get_project() == Some("project1") && t.has_tag(&tag) {
+                        count += 1;
+                    }
+                }
+                count
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    rebuild_working_set,
+    filter_all_tasks,
+    filter_working_set
+);
+criterion_main!(benches);
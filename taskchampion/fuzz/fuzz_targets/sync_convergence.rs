@@ -0,0 +1,137 @@
+//! Apply arbitrary operations to two replicas sharing a server, interleaved with syncs, and check
+//! that the replicas converge to the same state after a final round of syncs.
+
+#![no_main]
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use std::collections::HashMap;
+use taskchampion::server::{
+    AddVersionResult, GetVersionResult, HistorySegment, Server, Snapshot, SnapshotUrgency,
+    VersionId, NIL_VERSION_ID,
+};
+use taskchampion::{Replica, StorageConfig, Uuid};
+
+#[derive(Arbitrary, Debug)]
+enum Property {
+    Description,
+    Project,
+    Status,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Action {
+    Create {
+        replica: bool,
+        task: u8,
+    },
+    Update {
+        replica: bool,
+        task: u8,
+        property: Property,
+        value: Option<bool>,
+    },
+    Sync {
+        replica: bool,
+    },
+}
+
+/// A minimal in-memory server, keeping a linear sequence of versions.
+#[derive(Default)]
+struct FuzzServer {
+    latest_version_id: VersionId,
+    // indexed by parent version ID
+    versions: HashMap<VersionId, (VersionId, HistorySegment)>,
+}
+
+impl Server for FuzzServer {
+    fn add_version(
+        &mut self,
+        parent_version_id: VersionId,
+        history_segment: HistorySegment,
+    ) -> taskchampion::Result<(AddVersionResult, SnapshotUrgency)> {
+        if self.latest_version_id != NIL_VERSION_ID && parent_version_id != self.latest_version_id {
+            return Ok((
+                AddVersionResult::ExpectedParentVersion(self.latest_version_id),
+                SnapshotUrgency::None,
+            ));
+        }
+        let version_id = Uuid::new_v4();
+        self.versions
+            .insert(parent_version_id, (version_id, history_segment));
+        self.latest_version_id = version_id;
+        Ok((AddVersionResult::Ok(version_id), SnapshotUrgency::None))
+    }
+
+    fn get_child_version(
+        &mut self,
+        parent_version_id: VersionId,
+    ) -> taskchampion::Result<GetVersionResult> {
+        Ok(match self.versions.get(&parent_version_id) {
+            Some((version_id, history_segment)) => GetVersionResult::Version {
+                version_id: *version_id,
+                parent_version_id,
+                history_segment: history_segment.clone(),
+            },
+            None => GetVersionResult::NoSuchVersion,
+        })
+    }
+
+    fn add_snapshot(
+        &mut self,
+        _version_id: VersionId,
+        _snapshot: Snapshot,
+    ) -> taskchampion::Result<()> {
+        Ok(())
+    }
+
+    fn get_snapshot(&mut self) -> taskchampion::Result<Option<(VersionId, Snapshot)>> {
+        Ok(None)
+    }
+}
+
+fn task_uuid(task: u8) -> Uuid {
+    // a small number of tasks makes conflicting operations likely
+    Uuid::from_u128(u128::from(task % 4) + 1)
+}
+
+fuzz_target!(|actions: Vec<Action>| {
+    let mut server: Box<dyn Server> = Box::new(FuzzServer::default());
+    let mut replicas = [
+        Replica::new(StorageConfig::InMemory.into_storage().unwrap()),
+        Replica::new(StorageConfig::InMemory.into_storage().unwrap()),
+    ];
+
+    for action in actions {
+        // operations that are invalid in a replica's current state are ignored
+        match action {
+            Action::Create { replica, task } => {
+                let _ = replicas[replica as usize].import_task_with_uuid(task_uuid(task));
+            }
+            Action::Update {
+                replica,
+                task,
+                property,
+                value,
+            } => {
+                let property = match property {
+                    Property::Description => "description",
+                    Property::Project => "project",
+                    Property::Status => "status",
+                };
+                let value = value.map(|v| if v { "a" } else { "b" });
+                let _ = replicas[replica as usize].update_task(task_uuid(task), property, value);
+            }
+            Action::Sync { replica } => {
+                replicas[replica as usize].sync(&mut server, false).unwrap();
+            }
+        }
+    }
+
+    replicas[0].sync(&mut server, false).unwrap();
+    replicas[1].sync(&mut server, false).unwrap();
+    replicas[0].sync(&mut server, false).unwrap();
+    assert_eq!(
+        replicas[0].all_tasks().unwrap(),
+        replicas[1].all_tasks().unwrap()
+    );
+});
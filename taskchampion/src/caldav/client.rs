@@ -0,0 +1,299 @@
+use crate::errors::{Error, Result};
+use std::time::Duration;
+
+/// A calendar object resource in a CalDAV collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resource {
+    /// The resource's href, as given by the server; typically an absolute path
+    pub href: String,
+    /// The resource's entity tag, which changes whenever the resource changes
+    pub etag: String,
+}
+
+/// A client for a single CalDAV collection of VTODOs.
+///
+/// [`HttpClient`] implements this trait for CalDAV servers.  Other implementations are useful
+/// for testing.
+pub trait CalDavClient {
+    /// List the resources in the collection.
+    fn list(&mut self) -> Result<Vec<Resource>>;
+
+    /// Get the content of the resource with the given href, as an iCalendar object, and its
+    /// entity tag.
+    fn get(&mut self, href: &str) -> Result<(String, String)>;
+
+    /// Create or replace the resource with the given href.  If `etag` is given, the resource is
+    /// only replaced if it has not changed since that entity tag was retrieved; otherwise, it is
+    /// only created if it does not exist.  Returns the new entity tag, if the server provided
+    /// one.
+    fn put(&mut self, href: &str, ical: &str, etag: Option<&str>) -> Result<Option<String>>;
+
+    /// Get the href for a new resource with the given name in the collection.
+    fn new_href(&self, name: &str) -> String;
+}
+
+/// A client for a collection on a CalDAV server, such as Nextcloud or iCloud, using HTTP basic
+/// authentication.
+pub struct HttpClient {
+    /// The URL of the collection, with a trailing slash
+    collection_url: String,
+    /// The scheme, host, and port of the collection URL
+    origin: String,
+    authorization: String,
+    agent: ureq::Agent,
+}
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/></d:prop></d:propfind>"#;
+
+impl HttpClient {
+    /// Create a new client for the collection at the given URL, such as
+    /// `https://cloud.example.com/remote.php/dav/calendars/alice/tasks/`.
+    pub fn new(collection_url: &str, username: &str, password: &str) -> Result<HttpClient> {
+        let mut collection_url = collection_url.to_owned();
+        if !collection_url.ends_with('/') {
+            collection_url.push('/');
+        }
+        let origin = collection_url
+            .find("://")
+            .and_then(|i| {
+                collection_url[i + 3..]
+                    .find('/')
+                    .map(|j| collection_url[..i + 3 + j].to_owned())
+            })
+            .ok_or_else(|| anyhow::anyhow!("Invalid CalDAV URL {}", collection_url))?;
+        Ok(HttpClient {
+            collection_url,
+            origin,
+            authorization: format!(
+                "Basic {}",
+                base64::encode(format!("{}:{}", username, password))
+            ),
+            agent: ureq::AgentBuilder::new()
+                .timeout_connect(Duration::from_secs(10))
+                .timeout_read(Duration::from_secs(60))
+                .build(),
+        })
+    }
+
+    /// Get the URL for the given href, which may be an absolute URL, an absolute path, or a path
+    /// relative to the collection.
+    fn url(&self, href: &str) -> String {
+        if href.contains("://") {
+            href.to_owned()
+        } else if href.starts_with('/') {
+            format!("{}{}", self.origin, href)
+        } else {
+            format!("{}{}", self.collection_url, href)
+        }
+    }
+
+    /// The path of the collection, as it appears in hrefs
+    fn collection_path(&self) -> &str {
+        &self.collection_url[self.origin.len()..]
+    }
+}
+
+impl CalDavClient for HttpClient {
+    fn list(&mut self) -> Result<Vec<Resource>> {
+        let body = self
+            .agent
+            .request("PROPFIND", &self.collection_url)
+            .set("Authorization", &self.authorization)
+            .set("Depth", "1")
+            .set("Content-Type", "application/xml; charset=utf-8")
+            .send_string(PROPFIND_BODY)?
+            .into_string()
+            .map_err(|e| Error::SyncConnection(e.into()))?;
+        let collection_path = self.collection_path().to_owned();
+        Ok(parse_multistatus(&body)
+            .drain(..)
+            // the collection itself is included in the response
+            .filter(|r| r.href != collection_path && r.href != self.collection_url)
+            .collect())
+    }
+
+    fn get(&mut self, href: &str) -> Result<(String, String)> {
+        let resp = self
+            .agent
+            .get(&self.url(href))
+            .set("Authorization", &self.authorization)
+            .call()?;
+        let etag = resp.header("ETag").unwrap_or_default().to_owned();
+        let ical = resp
+            .into_string()
+            .map_err(|e| Error::SyncConnection(e.into()))?;
+        Ok((ical, etag))
+    }
+
+    fn put(&mut self, href: &str, ical: &str, etag: Option<&str>) -> Result<Option<String>> {
+        let req = self
+            .agent
+            .put(&self.url(href))
+            .set("Authorization", &self.authorization)
+            .set("Content-Type", "text/calendar; charset=utf-8");
+        let req = match etag {
+            Some(etag) => req.set("If-Match", etag),
+            None => req.set("If-None-Match", "*"),
+        };
+        match req.send_string(ical) {
+            Ok(resp) => Ok(resp.header("ETag").map(|e| e.to_owned())),
+            Err(ureq::Error::Status(412, _)) => Err(Error::Other(anyhow::anyhow!(
+                "{} was modified on the CalDAV server during synchronization",
+                href
+            ))),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn new_href(&self, name: &str) -> String {
+        format!("{}{}", self.collection_path(), name)
+    }
+}
+
+/// Parse a WebDAV multistatus response, returning the resources that have an entity tag.
+pub(super) fn parse_multistatus(xml: &str) -> Vec<Resource> {
+    elements(xml, "response")
+        .drain(..)
+        .filter_map(|response| {
+            let href = elements(response, "href")
+                .first()
+                .map(|h| unescape_xml(h))?;
+            let etag = elements(response, "getetag")
+                .first()
+                .map(|e| unescape_xml(e))?;
+            Some(Resource {
+                href: href.trim().to_owned(),
+                etag: etag.trim().to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Find the content of each element with the given local name, ignoring any namespace prefix.
+/// Elements with the same name must not be nested.
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut res = vec![];
+    let mut rest = xml;
+    while let Some(start) = find_tag(rest, name, false) {
+        let content_start = match rest[start..].find('>') {
+            Some(i) => start + i + 1,
+            None => break,
+        };
+        // an empty element such as `<d:getetag/>`
+        if rest[..content_start].ends_with("/>") {
+            res.push("");
+            rest = &rest[content_start..];
+            continue;
+        }
+        let content = &rest[content_start..];
+        match find_tag(content, name, true) {
+            Some(end) => {
+                res.push(&content[..end]);
+                rest = &content[end..];
+            }
+            None => break,
+        }
+    }
+    res
+}
+
+/// Find the position of the first opening (or closing) tag with the given local name.
+fn find_tag(xml: &str, name: &str, closing: bool) -> Option<usize> {
+    let mut pos = 0;
+    while let Some(i) = xml[pos..].find('<') {
+        let start = pos + i;
+        let mut tag = &xml[start + 1..];
+        pos = start + 1;
+        if closing {
+            match tag.strip_prefix('/') {
+                Some(t) => tag = t,
+                None => continue,
+            }
+        } else if tag.starts_with('/') {
+            continue;
+        }
+        let tag_name = tag
+            .split(|c: char| c == '>' || c == '/' || c.is_whitespace())
+            .next()
+            .unwrap_or("");
+        let local_name = tag_name.rsplit(':').next().unwrap_or("");
+        if local_name == name {
+            return Some(start);
+        }
+    }
+    None
+}
+
+/// Replace the predefined XML entities in a text value
+fn unescape_xml(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn multistatus() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/">
+ <d:response>
+  <d:href>/dav/calendars/alice/tasks/</d:href>
+  <d:propstat><d:prop><d:getetag/></d:prop><d:status>HTTP/1.1 404 Not Found</d:status></d:propstat>
+ </d:response>
+ <d:response>
+  <d:href>/dav/calendars/alice/tasks/one.ics</d:href>
+  <d:propstat><d:prop><d:getetag>&quot;abc&quot;</d:getetag></d:prop></d:propstat>
+ </d:response>
+ <response xmlns="DAV:">
+  <href>/dav/calendars/alice/tasks/two%20b.ics</href>
+  <propstat><prop><getetag>"def"</getetag></prop></propstat>
+ </response>
+</d:multistatus>"#;
+        assert_eq!(
+            parse_multistatus(xml),
+            vec![
+                Resource {
+                    href: "/dav/calendars/alice/tasks/".into(),
+                    etag: "".into(),
+                },
+                Resource {
+                    href: "/dav/calendars/alice/tasks/one.ics".into(),
+                    etag: "\"abc\"".into(),
+                },
+                Resource {
+                    href: "/dav/calendars/alice/tasks/two%20b.ics".into(),
+                    etag: "\"def\"".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn urls() {
+        let client = HttpClient::new("https://example.com:8443/dav/tasks", "u", "p").unwrap();
+        assert_eq!(client.collection_path(), "/dav/tasks/");
+        assert_eq!(client.new_href("x.ics"), "/dav/tasks/x.ics");
+        assert_eq!(
+            client.url("/dav/tasks/x.ics"),
+            "https://example.com:8443/dav/tasks/x.ics"
+        );
+        assert_eq!(
+            client.url("x.ics"),
+            "https://example.com:8443/dav/tasks/x.ics"
+        );
+        assert_eq!(client.url("https://other/x.ics"), "https://other/x.ics");
+        assert_eq!(client.authorization, "Basic dTpw");
+    }
+
+    #[test]
+    fn invalid_url() {
+        assert!(HttpClient::new("tasks", "u", "p").is_err());
+    }
+}
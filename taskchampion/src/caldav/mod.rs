@@ -0,0 +1,565 @@
+/*!
+
+This module mirrors tasks to and from a collection of `VTODO`s on a
+[CalDAV](https://tools.ietf.org/html/rfc4791) server, so that they can be viewed and edited in
+applications such as Nextcloud Tasks and Apple Reminders.  It is available with the `caldav`
+feature.
+
+Each synchronized task is linked to a resource in the collection by user-defined attributes (UDAs)
+in the `caldav` namespace:
+
+ * `caldav.href` - the resource's href on the server
+ * `caldav.etag` - the resource's entity tag when it was last synchronized
+ * `caldav.fingerprint` - a summary of the task's content when it was last synchronized, used to
+   detect local changes
+ * `caldav.uid` - the VTODO's UID, if it is not the task's UUID
+
+Tasks are represented as described for [`Replica::export_ical`], and changes on the server to a
+VTODO's summary, status, dates, priority, and categories are applied to the linked task.
+Annotations are only sent to the server, as the VTODO's description.
+
+```no_run
+use taskchampion::caldav::{CalDavBridge, Direction, HttpClient};
+use taskchampion::{Replica, StorageConfig};
+# fn main() -> taskchampion::Result<()> {
+let mut replica = Replica::new(StorageConfig::InMemory.into_storage()?);
+let client = HttpClient::new(
+    "https://cloud.example.com/remote.php/dav/calendars/alice/tasks/",
+    "alice",
+    "app-password",
+)?;
+let mut bridge = CalDavBridge::new(client, Direction::TwoWay);
+let summary = bridge.sync(&mut replica)?;
+println!("{} pushed, {} pulled", summary.pushed, summary.pulled);
+# Ok(())
+# }
+```
+
+*/
+
+use crate::errors::Result;
+use crate::ical::{self, VTodo};
+use crate::replica::Replica;
+use crate::task::{Status, Task, TaskMut};
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+mod client;
+
+pub use client::{CalDavClient, HttpClient, Resource};
+
+/// The namespace of the UDAs in which synchronization state is stored
+const NAMESPACE: &str = "caldav";
+const HREF: &str = "href";
+const ETAG: &str = "etag";
+const FINGERPRINT: &str = "fingerprint";
+const UID: &str = "uid";
+
+/// The direction in which changes are synchronized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Send tasks to the server, overwriting any changes made there
+    Push,
+    /// Receive tasks from the server, ignoring any local changes
+    Pull,
+    /// Send and receive changes.  When a task has changed both locally and on the server, the
+    /// most recently modified version is kept.
+    TwoWay,
+}
+
+/// The number of tasks changed by a synchronization.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Summary {
+    /// Tasks created or updated on the server
+    pub pushed: usize,
+    /// Tasks created or updated locally from the server
+    pub pulled: usize,
+    /// Tasks deleted locally because they were removed from the server
+    pub deleted: usize,
+}
+
+/// A bridge between a replica and a CalDAV collection.
+pub struct CalDavBridge<C: CalDavClient> {
+    client: C,
+    direction: Direction,
+}
+
+impl<C: CalDavClient> CalDavBridge<C> {
+    /// Create a new bridge using the given client.
+    pub fn new(client: C, direction: Direction) -> CalDavBridge<C> {
+        CalDavBridge { client, direction }
+    }
+
+    /// Synchronize the replica with the collection.
+    ///
+    /// Pending tasks that are not yet linked to a resource are pushed to the server, and
+    /// resources on the server that are not linked to a task are pulled into new tasks, as
+    /// allowed by the direction.  Linked tasks remain linked when they are completed or deleted,
+    /// so that those changes are synchronized, too.
+    pub fn sync(&mut self, replica: &mut Replica) -> Result<Summary> {
+        let mut summary = Summary::default();
+        let remote: HashMap<String, String> = self
+            .client
+            .list()?
+            .drain(..)
+            .map(|r| (r.href, r.etag))
+            .collect();
+        let mut linked = HashSet::new();
+
+        let mut tasks = replica.all_tasks()?;
+        let mut uuids: Vec<Uuid> = tasks.keys().copied().collect();
+        uuids.sort();
+        for uuid in uuids {
+            let task = tasks.remove(&uuid).unwrap();
+            let href = match task.get_uda(NAMESPACE, HREF) {
+                Some(href) => href.to_owned(),
+                None => {
+                    if self.direction != Direction::Pull && task.get_status() == Status::Pending {
+                        let href = self.client.new_href(&format!("{}.ics", uuid));
+                        self.push(replica, task, &href, None)?;
+                        summary.pushed += 1;
+                    }
+                    continue;
+                }
+            };
+            linked.insert(href.clone());
+
+            let etag = match remote.get(&href) {
+                Some(etag) => etag,
+                None => {
+                    // the resource was removed from the server
+                    if self.direction == Direction::Push {
+                        self.push(replica, task, &href, None)?;
+                        summary.pushed += 1;
+                    } else {
+                        let mut task = task.into_mut(replica);
+                        if task.get_status() != Status::Deleted {
+                            task.delete()?;
+                            summary.deleted += 1;
+                        }
+                        for key in &[HREF, ETAG, FINGERPRINT, UID] {
+                            task.remove_uda(NAMESPACE, key)?;
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            let remote_changed = task.get_uda(NAMESPACE, ETAG) != Some(etag);
+            let local_changed =
+                task.get_uda(NAMESPACE, FINGERPRINT) != Some(&local_vtodo(&task).fingerprint());
+            match self.direction {
+                Direction::Push if remote_changed || local_changed => {
+                    self.push(replica, task, &href, Some(etag))?;
+                    summary.pushed += 1;
+                }
+                Direction::Pull | Direction::TwoWay if remote_changed => {
+                    let (content, new_etag) = self.client.get(&href)?;
+                    let vtodo = match VTodo::parse(&content) {
+                        Some(vtodo) => vtodo,
+                        None => continue,
+                    };
+                    // when both have changed, keep the most recent modification
+                    if self.direction == Direction::TwoWay
+                        && local_changed
+                        && vtodo.last_modified < task.get_modified()
+                    {
+                        self.push(replica, task, &href, Some(etag))?;
+                        summary.pushed += 1;
+                        continue;
+                    }
+                    let etag = if new_etag.is_empty() {
+                        etag.clone()
+                    } else {
+                        new_etag
+                    };
+                    pull(task.into_mut(replica), &vtodo, &href, &etag)?;
+                    summary.pulled += 1;
+                }
+                Direction::TwoWay if local_changed => {
+                    self.push(replica, task, &href, Some(etag))?;
+                    summary.pushed += 1;
+                }
+                _ => {}
+            }
+        }
+
+        if self.direction != Direction::Push {
+            let mut unlinked: Vec<(&String, &String)> = remote
+                .iter()
+                .filter(|(href, _)| !linked.contains(*href))
+                .collect();
+            unlinked.sort();
+            for (href, etag) in unlinked {
+                let (content, new_etag) = self.client.get(href)?;
+                let vtodo = match VTodo::parse(&content) {
+                    Some(vtodo) => vtodo,
+                    // not a VTODO, such as an event in a shared calendar
+                    None => continue,
+                };
+                let etag = if new_etag.is_empty() {
+                    etag.clone()
+                } else {
+                    new_etag
+                };
+                let task = new_task(replica, &vtodo)?;
+                pull(task.into_mut(replica), &vtodo, href, &etag)?;
+                summary.pulled += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Create or update the resource for this task on the server, and record the result.
+    fn push(
+        &mut self,
+        replica: &mut Replica,
+        task: Task,
+        href: &str,
+        etag: Option<&str>,
+    ) -> Result<()> {
+        let vtodo = local_vtodo(&task);
+        let mut content = vec![];
+        ical::write_calendar(&mut content, vec![vtodo.clone()])?;
+        let content = String::from_utf8(content).expect("iCalendar output is valid UTF-8");
+        let new_etag = self.client.put(href, &content, etag)?;
+
+        let mut task = task.into_mut(replica);
+        set_state(&mut task, href, &new_etag.unwrap_or_default(), &vtodo)
+    }
+}
+
+/// Get the VTODO for a local task, with the UID of the resource to which it is linked.
+fn local_vtodo(task: &Task) -> VTodo {
+    let mut vtodo = VTodo::from_task(task);
+    if let Some(uid) = task.get_uda(NAMESPACE, UID) {
+        vtodo.uid = uid.to_owned();
+    }
+    vtodo
+}
+
+/// Find or create the task for a VTODO that is not linked to a task.  If the VTODO's UID is a
+/// UUID, such as for a task previously pushed from another replica, the task has that UUID.
+fn new_task(replica: &mut Replica, vtodo: &VTodo) -> Result<Task> {
+    if let Ok(uuid) = Uuid::parse_str(&vtodo.uid) {
+        if let Some(task) = replica.get_task(uuid)? {
+            return Ok(task);
+        }
+        let task = replica.import_task_with_uuid(uuid)?;
+        let mut task = task.into_mut(replica);
+        task.set_status(Status::Pending)?;
+        task.set_entry(Some(vtodo.created.unwrap_or_else(Utc::now)))?;
+        return Ok(task.into_immut());
+    }
+    replica.new_task(Status::Pending, vtodo.summary.clone())
+}
+
+/// Apply a VTODO from the server to a task, and record the result.
+fn pull(mut task: TaskMut, vtodo: &VTodo, href: &str, etag: &str) -> Result<()> {
+    vtodo.apply(&mut task)?;
+    if vtodo.uid != task.get_uuid().to_string() && task.get_uda(NAMESPACE, UID).is_none() {
+        task.set_uda(NAMESPACE, UID, vtodo.uid.clone())?;
+    }
+    let local = local_vtodo(&task);
+    set_state(&mut task, href, etag, &local)
+}
+
+/// Record the synchronization state of a task, setting only the UDAs that have changed.
+fn set_state(task: &mut TaskMut, href: &str, etag: &str, vtodo: &VTodo) -> Result<()> {
+    let fingerprint = vtodo.fingerprint();
+    for (key, value) in &[(HREF, href), (ETAG, etag), (FINGERPRINT, &fingerprint)] {
+        if task.get_uda(NAMESPACE, key) != Some(value) {
+            task.set_uda(NAMESPACE, key, *value)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Error;
+    use chrono::{Duration, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    /// A CalDAV collection in memory, with entity tags counting modifications
+    #[derive(Default)]
+    struct TestClient {
+        resources: HashMap<String, (String, u32)>,
+        generation: u32,
+    }
+
+    impl TestClient {
+        fn etag(generation: u32) -> String {
+            format!("\"{}\"", generation)
+        }
+
+        fn modify(&mut self, href: &str, f: impl FnOnce(&mut VTodo)) {
+            let (content, _) = self.resources[href].clone();
+            let mut vtodo = VTodo::parse(&content).unwrap();
+            f(&mut vtodo);
+            let mut content = vec![];
+            ical::write_calendar(&mut content, vec![vtodo]).unwrap();
+            self.generation += 1;
+            self.resources.insert(
+                href.to_owned(),
+                (String::from_utf8(content).unwrap(), self.generation),
+            );
+        }
+
+        fn vtodo(&self, href: &str) -> VTodo {
+            VTodo::parse(&self.resources[href].0).unwrap()
+        }
+    }
+
+    impl CalDavClient for TestClient {
+        fn list(&mut self) -> Result<Vec<Resource>> {
+            Ok(self
+                .resources
+                .iter()
+                .map(|(href, (_, generation))| Resource {
+                    href: href.clone(),
+                    etag: TestClient::etag(*generation),
+                })
+                .collect())
+        }
+
+        fn get(&mut self, href: &str) -> Result<(String, String)> {
+            let (content, generation) = self.resources[href].clone();
+            Ok((content, TestClient::etag(generation)))
+        }
+
+        fn put(&mut self, href: &str, ical: &str, etag: Option<&str>) -> Result<Option<String>> {
+            let current = self.resources.get(href).map(|(_, g)| TestClient::etag(*g));
+            if current.as_deref() != etag {
+                return Err(Error::Other(anyhow::anyhow!("precondition failed")));
+            }
+            self.generation += 1;
+            self.resources
+                .insert(href.to_owned(), (ical.to_owned(), self.generation));
+            Ok(Some(TestClient::etag(self.generation)))
+        }
+
+        fn new_href(&self, name: &str) -> String {
+            format!("/tasks/{}", name)
+        }
+    }
+
+    fn bridge(direction: Direction) -> CalDavBridge<TestClient> {
+        CalDavBridge::new(TestClient::default(), direction)
+    }
+
+    fn get_task(rep: &mut Replica, uuid: Uuid) -> Task {
+        rep.get_task(uuid).unwrap().unwrap()
+    }
+
+    #[test]
+    fn push_new_tasks() -> Result<()> {
+        let mut rep = Replica::new_inmemory();
+        let t = rep.new_task(Status::Pending, "pending".into())?;
+        rep.new_task(Status::Completed, "completed".into())?;
+        let mut bridge = bridge(Direction::TwoWay);
+
+        let summary = bridge.sync(&mut rep)?;
+        assert_eq!(
+            summary,
+            Summary {
+                pushed: 1,
+                ..Default::default()
+            }
+        );
+        let href = format!("/tasks/{}.ics", t.get_uuid());
+        assert_eq!(bridge.client.resources.len(), 1);
+        assert_eq!(bridge.client.vtodo(&href).summary, "pending");
+        let t = get_task(&mut rep, t.get_uuid());
+        assert_eq!(t.get_uda(NAMESPACE, HREF), Some(href.as_str()));
+        assert_eq!(t.get_uda(NAMESPACE, ETAG), Some("\"1\""));
+
+        // nothing has changed, so a second sync does nothing
+        assert_eq!(bridge.sync(&mut rep)?, Summary::default());
+        Ok(())
+    }
+
+    #[test]
+    fn push_local_change() -> Result<()> {
+        let mut rep = Replica::new_inmemory();
+        let t = rep.new_task(Status::Pending, "task".into())?;
+        let mut bridge = bridge(Direction::TwoWay);
+        bridge.sync(&mut rep)?;
+
+        let mut t = get_task(&mut rep, t.get_uuid()).into_mut(&mut rep);
+        t.done()?;
+        let uuid = t.get_uuid();
+        let summary = bridge.sync(&mut rep)?;
+        assert_eq!(summary.pushed, 1);
+
+        let href = format!("/tasks/{}.ics", uuid);
+        assert_eq!(bridge.client.vtodo(&href).status, Some(Status::Completed));
+        assert_eq!(bridge.sync(&mut rep)?, Summary::default());
+        Ok(())
+    }
+
+    #[test]
+    fn pull_remote_change() -> Result<()> {
+        let mut rep = Replica::new_inmemory();
+        let t = rep.new_task(Status::Pending, "task".into())?;
+        let mut bridge = bridge(Direction::TwoWay);
+        bridge.sync(&mut rep)?;
+
+        let href = format!("/tasks/{}.ics", t.get_uuid());
+        bridge.client.modify(&href, |v| {
+            v.summary = "changed remotely".into();
+            v.last_modified = Some(Utc::now() + Duration::seconds(10));
+        });
+        let summary = bridge.sync(&mut rep)?;
+        assert_eq!(
+            summary,
+            Summary {
+                pulled: 1,
+                ..Default::default()
+            }
+        );
+        let t = get_task(&mut rep, t.get_uuid());
+        assert_eq!(t.get_description(), "changed remotely");
+        assert_eq!(bridge.sync(&mut rep)?, Summary::default());
+        Ok(())
+    }
+
+    #[test]
+    fn conflict_newest_wins() -> Result<()> {
+        let mut rep = Replica::new_inmemory();
+        let t = rep.new_task(Status::Pending, "task".into())?;
+        let uuid = t.get_uuid();
+        let href = format!("/tasks/{}.ics", uuid);
+        let mut bridge = bridge(Direction::TwoWay);
+        bridge.sync(&mut rep)?;
+
+        // the remote change is older than the local change
+        bridge.client.modify(&href, |v| {
+            v.summary = "remote".into();
+            v.last_modified = Some(Utc.ymd(2000, 1, 1).and_hms(0, 0, 0));
+        });
+        let mut t = get_task(&mut rep, uuid).into_mut(&mut rep);
+        t.set_description("local".into())?;
+        assert_eq!(bridge.sync(&mut rep)?.pushed, 1);
+        assert_eq!(bridge.client.vtodo(&href).summary, "local");
+
+        // the remote change is newer than the local change
+        bridge.client.modify(&href, |v| {
+            v.summary = "remote".into();
+            v.last_modified = Some(Utc::now() + Duration::days(1));
+        });
+        let mut t = get_task(&mut rep, uuid).into_mut(&mut rep);
+        t.set_description("local again".into())?;
+        assert_eq!(bridge.sync(&mut rep)?.pulled, 1);
+        assert_eq!(get_task(&mut rep, uuid).get_description(), "remote");
+        Ok(())
+    }
+
+    #[test]
+    fn pull_new_resources() -> Result<()> {
+        let mut rep = Replica::new_inmemory();
+        let mut bridge = bridge(Direction::Pull);
+        let uuid = Uuid::new_v4();
+        for (href, uid) in &[
+            ("/tasks/a.ics", "a@example.com".to_owned()),
+            ("/tasks/b.ics", uuid.to_string()),
+        ] {
+            let vtodo = VTodo {
+                uid: uid.clone(),
+                summary: format!("from {}", href),
+                status: Some(Status::Pending),
+                ..Default::default()
+            };
+            let mut content = vec![];
+            ical::write_calendar(&mut content, vec![vtodo])?;
+            bridge
+                .client
+                .put(href, &String::from_utf8(content).unwrap(), None)?;
+        }
+
+        assert_eq!(bridge.sync(&mut rep)?.pulled, 2);
+        let tasks = rep.all_tasks()?;
+        assert_eq!(tasks.len(), 2);
+
+        // a UUID UID is used as the task's UUID
+        let t = &tasks[&uuid];
+        assert_eq!(t.get_description(), "from /tasks/b.ics");
+        assert_eq!(t.get_status(), Status::Pending);
+        assert!(t.get_entry().is_some());
+        assert_eq!(t.get_uda(NAMESPACE, UID), None);
+
+        // other UIDs are stored as a UDA
+        let t = tasks.values().find(|t| t.get_uuid() != uuid).unwrap();
+        assert_eq!(t.get_description(), "from /tasks/a.ics");
+        assert_eq!(t.get_uda(NAMESPACE, UID), Some("a@example.com"));
+        assert_eq!(t.get_uda(NAMESPACE, HREF), Some("/tasks/a.ics"));
+
+        // and preserved when the task is pushed back
+        let mut two_way = CalDavBridge::new(bridge.client, Direction::TwoWay);
+        let mut t = t.clone().into_mut(&mut rep);
+        t.set_description("changed".into())?;
+        assert_eq!(two_way.sync(&mut rep)?.pushed, 1);
+        let vtodo = two_way.client.vtodo("/tasks/a.ics");
+        assert_eq!(vtodo.uid, "a@example.com");
+        assert_eq!(vtodo.summary, "changed");
+        Ok(())
+    }
+
+    #[test]
+    fn remote_deletion() -> Result<()> {
+        let mut rep = Replica::new_inmemory();
+        let t = rep.new_task(Status::Pending, "task".into())?;
+        let mut bridge = bridge(Direction::TwoWay);
+        bridge.sync(&mut rep)?;
+
+        bridge.client.resources.clear();
+        let summary = bridge.sync(&mut rep)?;
+        assert_eq!(
+            summary,
+            Summary {
+                deleted: 1,
+                ..Default::default()
+            }
+        );
+        let t = get_task(&mut rep, t.get_uuid());
+        assert_eq!(t.get_status(), Status::Deleted);
+        assert_eq!(t.get_uda(NAMESPACE, HREF), None);
+
+        // the deleted task is not pushed again
+        assert_eq!(bridge.sync(&mut rep)?, Summary::default());
+        Ok(())
+    }
+
+    #[test]
+    fn push_only() -> Result<()> {
+        let mut rep = Replica::new_inmemory();
+        let t = rep.new_task(Status::Pending, "task".into())?;
+        let href = format!("/tasks/{}.ics", t.get_uuid());
+        let mut bridge = bridge(Direction::Push);
+        bridge.sync(&mut rep)?;
+
+        // remote changes are overwritten
+        bridge.client.modify(&href, |v| v.summary = "remote".into());
+        assert_eq!(bridge.sync(&mut rep)?.pushed, 1);
+        assert_eq!(bridge.client.vtodo(&href).summary, "task");
+
+        // and removed resources are re-created
+        bridge.client.resources.clear();
+        assert_eq!(bridge.sync(&mut rep)?.pushed, 1);
+        assert_eq!(bridge.client.vtodo(&href).summary, "task");
+        Ok(())
+    }
+
+    #[test]
+    fn pull_only() -> Result<()> {
+        let mut rep = Replica::new_inmemory();
+        rep.new_task(Status::Pending, "task".into())?;
+        let mut bridge = bridge(Direction::Pull);
+        assert_eq!(bridge.sync(&mut rep)?, Summary::default());
+        assert!(bridge.client.resources.is_empty());
+        Ok(())
+    }
+}
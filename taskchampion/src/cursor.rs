@@ -0,0 +1,85 @@
+use crate::errors::Result;
+use crate::server::SyncOp;
+use serde::{Deserialize, Serialize};
+
+/// An OperationsCursor marks how far a replica's unsynchronized operations have been exported
+/// with [`Replica::export_operations`](crate::Replica::export_operations).  The default value
+/// is the start of the operations.
+///
+/// The cursor identifies the operations already exported, not just their number, so that it
+/// can detect when they have since been undone or synchronized.  It can be serialized, such as
+/// to be kept between runs of a replication pipeline.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationsCursor {
+    /// The number of operations exported
+    position: usize,
+    /// A fingerprint of the exported operations
+    fingerprint: u64,
+}
+
+impl OperationsCursor {
+    /// Create a cursor following the given operations, which are all of the operations exported
+    /// so far.
+    pub(crate) fn after(ops: &[SyncOp]) -> Result<Self> {
+        Ok(OperationsCursor {
+            position: ops.len(),
+            fingerprint: fingerprint(ops)?,
+        })
+    }
+
+    /// The number of operations exported before this cursor
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+}
+
+/// Compute a fingerprint of the given operations, using the 64-bit FNV-1a hash of their
+/// serialization.  Unlike the standard library's hasher, this is stable between releases.
+fn fingerprint(ops: &[SyncOp]) -> Result<u64> {
+    // no operations have a zero fingerprint, so that the default cursor is the start
+    if ops.is_empty() {
+        return Ok(0);
+    }
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for op in ops {
+        for byte in serde_json::to_vec(op).map_err(anyhow::Error::from)? {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use uuid::Uuid;
+
+    #[test]
+    fn cursor_after() {
+        let uuid1 = Uuid::new_v4();
+        let uuid2 = Uuid::new_v4();
+        let ops = vec![
+            SyncOp::Create { uuid: uuid1 },
+            SyncOp::Create { uuid: uuid2 },
+        ];
+        let cursor = OperationsCursor::after(&ops).unwrap();
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor, OperationsCursor::after(&ops).unwrap());
+
+        // different operations at the same position give a different cursor
+        let other = OperationsCursor::after(&[
+            SyncOp::Create { uuid: uuid1 },
+            SyncOp::Delete { uuid: uuid2 },
+        ])
+        .unwrap();
+        assert_eq!(other.position(), 2);
+        assert_ne!(cursor, other);
+
+        assert_eq!(
+            OperationsCursor::after(&[]).unwrap(),
+            OperationsCursor::default()
+        );
+    }
+}
@@ -1,15 +1,163 @@
 use thiserror::Error;
 
-#[derive(Debug, Error, Eq, PartialEq, Clone)]
+#[derive(Debug, Error)]
 #[non_exhaustive]
 /// Errors returned from taskchampion operations
 pub enum Error {
     /// A task-database-related error
     #[error("Task Database Error: {0}")]
     Database(String),
+    /// An error reading or writing the replica's storage.
+    #[error("Storage Error: {0:#}")]
+    Storage(#[source] anyhow::Error),
+    /// The replica's storage contains data that cannot be interpreted, or is not a task database.
+    #[error("Task data is corrupt: {0:#}")]
+    Corruption(#[source] anyhow::Error),
     /// An error specifically indicating that the local replica cannot
     /// be synchronized with the sever, due to being out of date or some
     /// other irrecoverable error.
     #[error("Local replica is out of sync with the server")]
     OutOfSync,
+    /// The sync server could not be reached.
+    #[error("Could not connect to the server: {0:#}")]
+    SyncConnection(#[source] anyhow::Error),
+    /// The sync server rejected this replica's credentials.
+    #[error("The server rejected this replica's credentials: {0:#}")]
+    SyncAuth(#[source] anyhow::Error),
+    /// Data from the server could not be decrypted, typically because the encryption secret does
+    /// not match the one with which it was encrypted.
+    #[error("Could not decrypt data from the server; check the encryption secret")]
+    Decryption,
+    /// Synchronization was cancelled by the progress callback given to
+    /// [`Replica::sync_with_progress`](crate::Replica::sync_with_progress).
+    #[error("Synchronization was cancelled")]
+    SyncCancelled,
+    /// A tag name is not valid, or the tag cannot be modified.
+    #[error("Invalid tag: {0}")]
+    InvalidTag(String),
+    /// A new task was not created because its description is very similar to that of the given
+    /// pending task; see [`Replica::set_duplicate_check`](crate::Replica::set_duplicate_check).
+    #[error("Task {0} has a very similar description")]
+    Duplicate(uuid::Uuid),
+    /// A change to a task was not made because the task has changed since it was read; see
+    /// [`Task::into_mut_checked`](crate::Task::into_mut_checked).
+    #[error("Task {0} has been changed by another writer")]
+    Conflict(uuid::Uuid),
+    /// Operations exported up to an [`OperationsCursor`](crate::OperationsCursor) have since been
+    /// undone or synchronized, so the cursor no longer marks a position in the replica's
+    /// operations.
+    #[error("The operations exported before this cursor have been undone or synchronized")]
+    StaleCursor,
+    /// An error that does not fall into any of the other kinds.
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+/// A result from a taskchampion operation.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl From<anyhow::Error> for Error {
+    fn from(err: anyhow::Error) -> Error {
+        // an Error that was converted to anyhow::Error, perhaps with added context, keeps its
+        // variant when converted back
+        if err.is::<Error>() {
+            return err.downcast().unwrap();
+        }
+
+        // otherwise, categorize the error by the first cause that can be categorized
+        for cause in err.chain() {
+            #[cfg(feature = "storage-sqlite")]
+            if let Some(e) = cause.downcast_ref::<rusqlite::Error>() {
+                return sqlite_error_kind(e)(err);
+            }
+            #[cfg(any(feature = "server-sync", feature = "caldav"))]
+            if let Some(e) = cause.downcast_ref::<ureq::Error>() {
+                return ureq_error_kind(e)(err);
+            }
+            if cause.is::<std::io::Error>() {
+                return Error::Storage(err);
+            }
+        }
+        Error::Other(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        Error::Storage(err.into())
+    }
+}
+
+#[cfg(feature = "storage-sqlite")]
+impl From<rusqlite::Error> for Error {
+    fn from(err: rusqlite::Error) -> Error {
+        sqlite_error_kind(&err)(err.into())
+    }
+}
+
+#[cfg(any(feature = "server-sync", feature = "caldav"))]
+impl From<ureq::Error> for Error {
+    fn from(err: ureq::Error) -> Error {
+        ureq_error_kind(&err)(err.into())
+    }
+}
+
+#[cfg(feature = "storage-sqlite")]
+fn sqlite_error_kind(err: &rusqlite::Error) -> fn(anyhow::Error) -> Error {
+    use rusqlite::ErrorCode::*;
+    match err {
+        rusqlite::Error::SqliteFailure(e, _)
+            if e.code == DatabaseCorrupt || e.code == NotADatabase =>
+        {
+            Error::Corruption
+        }
+        rusqlite::Error::FromSqlConversionFailure(..) | rusqlite::Error::InvalidColumnType(..) => {
+            Error::Corruption
+        }
+        _ => Error::Storage,
+    }
+}
+
+#[cfg(any(feature = "server-sync", feature = "caldav"))]
+fn ureq_error_kind(err: &ureq::Error) -> fn(anyhow::Error) -> Error {
+    match err {
+        ureq::Error::Status(401, _) | ureq::Error::Status(403, _) => Error::SyncAuth,
+        ureq::Error::Status(..) => Error::Other,
+        ureq::Error::Transport(_) => Error::SyncConnection,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_anyhow_keeps_variant() {
+        let err = anyhow::Error::from(Error::OutOfSync).context("while syncing");
+        assert!(matches!(Error::from(err), Error::OutOfSync));
+    }
+
+    #[test]
+    fn from_anyhow_other() {
+        let err = anyhow::anyhow!("uhoh");
+        assert!(matches!(Error::from(err), Error::Other(_)));
+    }
+
+    #[cfg(feature = "storage-sqlite")]
+    #[test]
+    fn from_anyhow_sqlite() {
+        use anyhow::Context;
+        let err: anyhow::Result<()> = Err(rusqlite::Error::InvalidQuery).context("querying");
+        assert!(matches!(Error::from(err.unwrap_err()), Error::Storage(_)));
+    }
+
+    #[cfg(feature = "storage-sqlite")]
+    #[test]
+    fn from_sqlite_corruption() {
+        let err = rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_NOTADB),
+            None,
+        );
+        assert!(matches!(Error::from(err), Error::Corruption(_)));
+    }
 }
@@ -0,0 +1,669 @@
+//! Conversion of tasks to and from iCalendar ([RFC 5545](https://tools.ietf.org/html/rfc5545))
+//! `VTODO` components, for display in calendar applications.
+
+use crate::task::{Status, Task, Timestamp};
+use chrono::Utc;
+use std::io::{self, Write};
+#[cfg(feature = "caldav")]
+use {
+    crate::errors::Result,
+    crate::task::{Tag, TaskMut},
+    chrono::{DateTime, NaiveDate, NaiveDateTime},
+    std::convert::TryFrom,
+};
+
+/// The product identifier for generated calendars
+const PRODID: &str = concat!(
+    "-//TaskChampion//TaskChampion ",
+    env!("CARGO_PKG_VERSION"),
+    "//EN"
+);
+
+/// The maximum length of a content line, in octets, excluding the line break
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Write a `VCALENDAR` object containing the given `VTODO` components.
+pub(crate) fn write_calendar<W: Write>(
+    w: &mut W,
+    vtodos: impl IntoIterator<Item = VTodo>,
+) -> io::Result<()> {
+    write_line(w, "BEGIN", "VCALENDAR")?;
+    write_line(w, "VERSION", "2.0")?;
+    write_line(w, "PRODID", PRODID)?;
+    for vtodo in vtodos {
+        vtodo.write(w)?;
+    }
+    write_line(w, "END", "VCALENDAR")
+}
+
+/// The properties of a task that are represented in a `VTODO` component, using TaskChampion's
+/// representations of status and priority.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct VTodo {
+    pub(crate) uid: String,
+    pub(crate) summary: String,
+    /// The task's annotations, one per line
+    pub(crate) description: Option<String>,
+    pub(crate) created: Option<Timestamp>,
+    pub(crate) last_modified: Option<Timestamp>,
+    /// The task's scheduled time
+    pub(crate) dtstart: Option<Timestamp>,
+    pub(crate) due: Option<Timestamp>,
+    pub(crate) completed: Option<Timestamp>,
+    pub(crate) status: Option<Status>,
+    /// The task's priority, `H`, `M`, or `L`
+    pub(crate) priority: Option<String>,
+    /// The task's user tags
+    pub(crate) categories: Vec<String>,
+}
+
+impl VTodo {
+    /// Represent the given task as a VTODO.
+    pub(crate) fn from_task(task: &Task) -> VTodo {
+        let mut annotations: Vec<_> = task.get_annotations().collect();
+        annotations.sort();
+        let description: Vec<_> = annotations.drain(..).map(|a| a.description).collect();
+
+        let status = task.get_status();
+        let priority = match task.get_priority() {
+            p @ "H" | p @ "M" | p @ "L" => Some(p.to_owned()),
+            _ => None,
+        };
+
+        let mut categories: Vec<_> = task
+            .get_tags()
+            .filter(|t| t.is_user())
+            .map(|t| t.to_string())
+            .collect();
+        categories.sort();
+
+        VTodo {
+            uid: task.get_uuid().to_string(),
+            summary: task.get_description().to_owned(),
+            description: if description.is_empty() {
+                None
+            } else {
+                Some(description.join("\n"))
+            },
+            created: task.get_entry(),
+            last_modified: task.get_modified(),
+            dtstart: task.get_scheduled(),
+            due: task.get_due(),
+            completed: if status == Status::Completed {
+                task.get_end()
+            } else {
+                None
+            },
+            status: Some(status),
+            priority,
+            categories,
+        }
+    }
+
+    /// Write this VTODO as a component.
+    pub(crate) fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        write_line(w, "BEGIN", "VTODO")?;
+        write_line(w, "UID", &self.uid)?;
+
+        // DTSTAMP is required; without a METHOD property, it gives the time the task was last
+        // modified.
+        let dtstamp = self.last_modified.unwrap_or_else(Utc::now);
+        write_line(w, "DTSTAMP", &datetime(dtstamp))?;
+        if let Some(modified) = self.last_modified {
+            write_line(w, "LAST-MODIFIED", &datetime(modified))?;
+        }
+
+        self.write_content(w)?;
+        write_line(w, "END", "VTODO")
+    }
+
+    /// Write the properties describing the task, excluding its UID and the times at which it was
+    /// modified.
+    fn write_content<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        if let Some(entry) = self.created {
+            write_line(w, "CREATED", &datetime(entry))?;
+        }
+
+        write_line(w, "SUMMARY", &text(&self.summary))?;
+        if let Some(ref description) = self.description {
+            write_line(w, "DESCRIPTION", &text(description))?;
+        }
+
+        if let Some(scheduled) = self.dtstart {
+            write_line(w, "DTSTART", &datetime(scheduled))?;
+        }
+        if let Some(due) = self.due {
+            write_line(w, "DUE", &datetime(due))?;
+        }
+
+        match self.status {
+            Some(Status::Pending) => write_line(w, "STATUS", "NEEDS-ACTION")?,
+            Some(Status::Completed) => write_line(w, "STATUS", "COMPLETED")?,
+            Some(Status::Deleted) => write_line(w, "STATUS", "CANCELLED")?,
+            // other statuses have no iCalendar equivalent
+            Some(Status::Unknown(_)) | None => {}
+        }
+        if let Some(end) = self.completed {
+            write_line(w, "COMPLETED", &datetime(end))?;
+        }
+
+        // iCalendar priorities range from 1 (highest) to 9 (lowest), with 5 for "medium"
+        let priority = match self.priority.as_deref() {
+            Some("H") => Some("1"),
+            Some("M") => Some("5"),
+            Some("L") => Some("9"),
+            _ => None,
+        };
+        if let Some(priority) = priority {
+            write_line(w, "PRIORITY", priority)?;
+        }
+
+        if !self.categories.is_empty() {
+            let categories: Vec<_> = self.categories.iter().map(|c| text(c)).collect();
+            write_line(w, "CATEGORIES", &categories.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// Summarize the content of this VTODO, excluding its UID and modification times, as a short
+    /// string.  Two VTODOs with the same content have the same fingerprint.
+    #[cfg(feature = "caldav")]
+    pub(crate) fn fingerprint(&self) -> String {
+        let mut content = vec![];
+        self.write_content(&mut content)
+            .expect("writing to a Vec cannot fail");
+        // 64-bit FNV-1a, which is stable across platforms and Rust versions
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for b in content {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        format!("{:016x}", hash)
+    }
+
+    /// Parse the first VTODO component in the given iCalendar object, returning None if there is
+    /// no VTODO or it has no UID.
+    ///
+    /// Times with a `TZID` parameter, and floating times, are interpreted as UTC, and dates as
+    /// midnight UTC.  Properties of nested components, such as `VALARM`, are ignored.
+    #[cfg(feature = "caldav")]
+    pub(crate) fn parse(ical: &str) -> Option<VTodo> {
+        let unfolded = ical
+            .replace("\r\n ", "")
+            .replace("\r\n\t", "")
+            .replace("\n ", "")
+            .replace("\n\t", "");
+
+        let mut vtodo = VTodo::default();
+        let mut found = false;
+        // the stack of components enclosing the current line
+        let mut components: Vec<&str> = vec![];
+        for line in unfolded.lines() {
+            let (name, value) = match split_line(line) {
+                Some(nv) => nv,
+                None => continue,
+            };
+            match name.as_str() {
+                "BEGIN" => {
+                    components.push(value);
+                    continue;
+                }
+                "END" => {
+                    if components.pop() == Some("VTODO") && found {
+                        break;
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            if components.last() != Some(&"VTODO") {
+                continue;
+            }
+            found = true;
+            match name.as_str() {
+                "UID" => vtodo.uid = value.to_owned(),
+                "SUMMARY" => vtodo.summary = unescape(value),
+                "DESCRIPTION" => vtodo.description = Some(unescape(value)),
+                "CREATED" => vtodo.created = parse_datetime(value),
+                "LAST-MODIFIED" => vtodo.last_modified = parse_datetime(value),
+                "DTSTART" => vtodo.dtstart = parse_datetime(value),
+                "DUE" => vtodo.due = parse_datetime(value),
+                "COMPLETED" => vtodo.completed = parse_datetime(value),
+                "STATUS" => {
+                    vtodo.status = match value {
+                        "NEEDS-ACTION" | "IN-PROCESS" => Some(Status::Pending),
+                        "COMPLETED" => Some(Status::Completed),
+                        "CANCELLED" => Some(Status::Deleted),
+                        _ => None,
+                    }
+                }
+                "PRIORITY" => {
+                    vtodo.priority = match value.trim().parse::<u8>() {
+                        Ok(1..=4) => Some("H".into()),
+                        Ok(5) => Some("M".into()),
+                        Ok(6..=9) => Some("L".into()),
+                        _ => None,
+                    }
+                }
+                "CATEGORIES" => vtodo.categories.extend(split_list(value)),
+                _ => {}
+            }
+        }
+
+        if vtodo.uid.is_empty() {
+            return None;
+        }
+        Some(vtodo)
+    }
+
+    /// Apply this VTODO's summary, status, dates, priority, and categories to the given task.
+    /// The task's annotations are not changed, and properties are only set if they differ, to
+    /// avoid unnecessary operations.
+    #[cfg(feature = "caldav")]
+    pub(crate) fn apply(&self, task: &mut TaskMut) -> Result<()> {
+        if task.get_description() != self.summary {
+            task.set_description(self.summary.clone())?;
+        }
+        if let Some(ref status) = self.status {
+            if &task.get_status() != status {
+                task.set_status(status.clone())?;
+            }
+        }
+        if self.status == Some(Status::Completed)
+            && self.completed.is_some()
+            && task.get_end() != self.completed
+        {
+            task.set_end(self.completed)?;
+        }
+        if task.get_entry().is_none() && self.created.is_some() {
+            task.set_entry(self.created)?;
+        }
+        if task.get_scheduled() != self.dtstart {
+            task.set_scheduled(self.dtstart)?;
+        }
+        if task.get_due() != self.due {
+            task.set_due(self.due)?;
+        }
+        let priority = self.priority.clone().unwrap_or_default();
+        if task.get_priority() != priority {
+            task.set_priority(priority)?;
+        }
+
+        let categories: Vec<Tag> = self
+            .categories
+            .iter()
+            .filter_map(|c| Tag::try_from(c.as_str()).ok())
+            .filter(|t| t.is_user())
+            .collect();
+        let tags: Vec<Tag> = task.get_tags().filter(|t| t.is_user()).collect();
+        for tag in &tags {
+            if !categories.contains(tag) {
+                task.remove_tag(tag)?;
+            }
+        }
+        for tag in &categories {
+            if !tags.contains(tag) {
+                task.add_tag(tag)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Format a timestamp as an iCalendar DATE-TIME in UTC, such as `20211231T175614Z`
+fn datetime(ts: Timestamp) -> String {
+    ts.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a string as an iCalendar TEXT value
+fn text(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                res.push('\\');
+                res.push(c);
+            }
+            '\n' => res.push_str("\\n"),
+            '\r' => {}
+            _ => res.push(c),
+        }
+    }
+    res
+}
+
+/// Split an unfolded content line into its upper-cased name and its value, ignoring any
+/// parameters.
+#[cfg(feature = "caldav")]
+fn split_line(line: &str) -> Option<(String, &str)> {
+    let line = line.trim_end_matches('\r');
+    // the value begins after the first colon that is not in a quoted parameter value
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let name = line[..colon].split(';').next()?;
+    Some((name.to_ascii_uppercase(), &line[colon + 1..]))
+}
+
+/// Parse an iCalendar DATE or DATE-TIME value
+#[cfg(feature = "caldav")]
+fn parse_datetime(value: &str) -> Option<Timestamp> {
+    let value = value.trim().trim_end_matches('Z');
+    if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Some(DateTime::from_utc(dt, Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()
+        .map(|d| DateTime::from_utc(d.and_hms(0, 0, 0), Utc))
+}
+
+/// Unescape an iCalendar TEXT value
+#[cfg(feature = "caldav")]
+fn unescape(value: &str) -> String {
+    let mut res = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => res.push('\n'),
+                Some(c) => res.push(c),
+                None => {}
+            }
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
+
+/// Split a comma-separated list of TEXT values, such as CATEGORIES, and unescape each one
+#[cfg(feature = "caldav")]
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                items.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&value[start..]);
+    items
+        .drain(..)
+        .map(unescape)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Write a content line, folding it so that no line exceeds 75 octets.  Continuation lines begin
+/// with a space, which counts toward their length.
+fn write_line<W: Write>(w: &mut W, name: &str, value: &str) -> io::Result<()> {
+    let line = format!("{}:{}", name, value);
+    let mut start = 0;
+    let mut limit = MAX_LINE_OCTETS;
+    while line.len() - start > limit {
+        // fold at a character boundary, so that multi-byte characters are not split
+        let mut end = start + limit;
+        while !line.is_char_boundary(end) {
+            end -= 1;
+        }
+        w.write_all(&line.as_bytes()[start..end])?;
+        w.write_all(b"\r\n ")?;
+        start = end;
+        limit = MAX_LINE_OCTETS - 1;
+    }
+    w.write_all(&line.as_bytes()[start..])?;
+    w.write_all(b"\r\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::task::{Annotation, Tag};
+    use crate::Replica;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+    use std::convert::TryInto;
+
+    fn to_string<F: FnOnce(&mut Vec<u8>) -> io::Result<()>>(f: F) -> String {
+        let mut w = vec![];
+        f(&mut w).unwrap();
+        String::from_utf8(w).unwrap()
+    }
+
+    #[test]
+    fn text_escaping() {
+        assert_eq!(text("a; b, c\\d\r\ne"), "a\\; b\\, c\\\\d\\ne");
+    }
+
+    #[test]
+    fn short_line() {
+        assert_eq!(
+            to_string(|w| write_line(w, "SUMMARY", "hello")),
+            "SUMMARY:hello\r\n"
+        );
+    }
+
+    #[test]
+    fn folded_line() {
+        let value = "x".repeat(200);
+        let output = to_string(|w| write_line(w, "SUMMARY", &value));
+        for line in output.split("\r\n") {
+            assert!(line.len() <= 75);
+        }
+        let unfolded = output.replace("\r\n ", "");
+        assert_eq!(unfolded, format!("SUMMARY:{}\r\n", value));
+    }
+
+    #[test]
+    fn folded_line_multibyte() {
+        let value = "é".repeat(100);
+        let output = to_string(|w| write_line(w, "SUMMARY", &value));
+        for line in output.split("\r\n") {
+            assert!(line.len() <= 75);
+        }
+        let unfolded = output.replace("\r\n ", "");
+        assert_eq!(unfolded, format!("SUMMARY:{}\r\n", value));
+    }
+
+    #[test]
+    fn vtodo() {
+        let mut rep = Replica::new_inmemory();
+        let mut t = rep
+            .new_task(Status::Completed, "buy milk, eggs".into())
+            .unwrap()
+            .into_mut(&mut rep);
+        let ts = |h| Utc.ymd(2022, 1, 2).and_hms(h, 0, 0);
+        t.set_entry(Some(ts(1))).unwrap();
+        t.set_scheduled(Some(ts(2))).unwrap();
+        t.set_due(Some(ts(3))).unwrap();
+        t.set_end(Some(ts(4))).unwrap();
+        t.set_priority("H".into()).unwrap();
+        let tag: Tag = "shopping".try_into().unwrap();
+        t.add_tag(&tag).unwrap();
+        t.add_tag(&"errand".try_into().unwrap()).unwrap();
+        t.add_annotation(Annotation {
+            entry: ts(5),
+            description: "two dozen".into(),
+        })
+        .unwrap();
+        t.set_modified(ts(6)).unwrap();
+        let t = t.into_immut();
+
+        let output = to_string(|w| VTodo::from_task(&t).write(w));
+        assert_eq!(
+            output,
+            format!(
+                "BEGIN:VTODO\r\n\
+                 UID:{}\r\n\
+                 DTSTAMP:20220102T060000Z\r\n\
+                 LAST-MODIFIED:20220102T060000Z\r\n\
+                 CREATED:20220102T010000Z\r\n\
+                 SUMMARY:buy milk\\, eggs\r\n\
+                 DESCRIPTION:two dozen\r\n\
+                 DTSTART:20220102T020000Z\r\n\
+                 DUE:20220102T030000Z\r\n\
+                 STATUS:COMPLETED\r\n\
+                 COMPLETED:20220102T040000Z\r\n\
+                 PRIORITY:1\r\n\
+                 CATEGORIES:errand,shopping\r\n\
+                 END:VTODO\r\n",
+                t.get_uuid()
+            )
+        );
+    }
+
+    #[test]
+    fn vtodo_status() {
+        let mut rep = Replica::new_inmemory();
+        let pending = rep.new_task(Status::Pending, "p".into()).unwrap();
+        let deleted = rep.new_task(Status::Deleted, "d".into()).unwrap();
+        let write = |t| to_string(|w| VTodo::from_task(t).write(w));
+        assert!(write(&pending).contains("\r\nSTATUS:NEEDS-ACTION\r\n"));
+        assert!(write(&deleted).contains("\r\nSTATUS:CANCELLED\r\n"));
+    }
+
+    #[test]
+    fn calendar() {
+        let output = to_string(|w| write_calendar(w, vec![]));
+        assert_eq!(
+            output,
+            format!(
+                "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:{}\r\nEND:VCALENDAR\r\n",
+                PRODID
+            )
+        );
+    }
+
+    #[cfg(feature = "caldav")]
+    #[test]
+    fn parse_vtodo() {
+        let ical = "BEGIN:VCALENDAR\r\n\
+                    VERSION:2.0\r\n\
+                    BEGIN:VTODO\r\n\
+                    UID:abc@example.com\r\n\
+                    SUMMARY:buy milk\\, eggs\\; and a very long summary that is folded across more \r\n \
+                    than one line\r\n\
+                    DESCRIPTION:line one\\nline two\r\n\
+                    DTSTART;VALUE=DATE:20220102\r\n\
+                    DUE;TZID=\"Europe/Berlin\":20220103T040506\r\n\
+                    STATUS:IN-PROCESS\r\n\
+                    PRIORITY:2\r\n\
+                    CATEGORIES:home,errand\r\n\
+                    CATEGORIES:a\\,b\r\n\
+                    BEGIN:VALARM\r\n\
+                    DESCRIPTION:alarm\r\n\
+                    END:VALARM\r\n\
+                    END:VTODO\r\n\
+                    END:VCALENDAR\r\n";
+        assert_eq!(
+            VTodo::parse(ical),
+            Some(VTodo {
+                uid: "abc@example.com".into(),
+                summary: "buy milk, eggs; and a very long summary that is folded across more than one line".into(),
+                description: Some("line one\nline two".into()),
+                dtstart: Some(Utc.ymd(2022, 1, 2).and_hms(0, 0, 0)),
+                due: Some(Utc.ymd(2022, 1, 3).and_hms(4, 5, 6)),
+                status: Some(Status::Pending),
+                priority: Some("H".into()),
+                categories: vec!["home".into(), "errand".into(), "a,b".into()],
+                ..Default::default()
+            })
+        );
+    }
+
+    #[cfg(feature = "caldav")]
+    #[test]
+    fn parse_no_vtodo() {
+        assert_eq!(
+            VTodo::parse(
+                "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:x\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+            ),
+            None
+        );
+    }
+
+    #[cfg(feature = "caldav")]
+    #[test]
+    fn write_parse_round_trip() {
+        let mut rep = Replica::new_inmemory();
+        let mut t = rep
+            .new_task(Status::Completed, "a\\b;c,d".into())
+            .unwrap()
+            .into_mut(&mut rep);
+        t.set_due(Some(Utc.ymd(2022, 1, 2).and_hms(3, 4, 5)))
+            .unwrap();
+        t.set_priority("M".into()).unwrap();
+        t.add_tag(&"home".try_into().unwrap()).unwrap();
+        let t = t.into_immut();
+
+        let vtodo = VTodo::from_task(&t);
+        let output = to_string(|w| vtodo.write(w));
+        assert_eq!(VTodo::parse(&output), Some(vtodo));
+    }
+
+    #[cfg(feature = "caldav")]
+    #[test]
+    fn fingerprint() {
+        let vtodo = VTodo {
+            uid: "a".into(),
+            summary: "task".into(),
+            ..Default::default()
+        };
+        let mut modified = vtodo.clone();
+        modified.last_modified = Some(Utc::now());
+        assert_eq!(vtodo.fingerprint(), modified.fingerprint());
+        modified.summary = "changed".into();
+        assert_ne!(vtodo.fingerprint(), modified.fingerprint());
+    }
+
+    #[cfg(feature = "caldav")]
+    #[test]
+    fn apply() {
+        let mut rep = Replica::new_inmemory();
+        let mut t = rep
+            .new_task(Status::Pending, "old".into())
+            .unwrap()
+            .into_mut(&mut rep);
+        t.add_tag(&"stale".try_into().unwrap()).unwrap();
+        t.add_tag(&"home".try_into().unwrap()).unwrap();
+        t.set_priority("L".into()).unwrap();
+
+        let vtodo = VTodo {
+            uid: "a".into(),
+            summary: "new".into(),
+            due: Some(Utc.ymd(2022, 1, 2).and_hms(3, 4, 5)),
+            completed: Some(Utc.ymd(2022, 1, 3).and_hms(0, 0, 0)),
+            status: Some(Status::Completed),
+            priority: None,
+            categories: vec!["home".into(), "work".into(), "not valid".into()],
+            ..Default::default()
+        };
+        vtodo.apply(&mut t).unwrap();
+        let t = t.into_immut();
+        assert_eq!(t.get_description(), "new");
+        assert_eq!(t.get_status(), Status::Completed);
+        assert_eq!(t.get_end(), Some(Utc.ymd(2022, 1, 3).and_hms(0, 0, 0)));
+        assert_eq!(t.get_due(), Some(Utc.ymd(2022, 1, 2).and_hms(3, 4, 5)));
+        assert_eq!(t.get_priority(), "");
+        let mut tags: Vec<_> = t
+            .get_tags()
+            .filter(|t| t.is_user())
+            .map(|t| t.to_string())
+            .collect();
+        tags.sort();
+        assert_eq!(tags, vec!["home", "work"]);
+    }
+}
@@ -34,6 +34,22 @@ Create a server with [`ServerConfig`](crate::ServerConfig).
 The [`server`](crate::server) module defines the interface a server must meet.
 Users can define their own server impelementations.
 
+# Feature Flags
+
+Support for some optional functionality is controlled by feature flags.  The first two are
+enabled by default.
+
+ * `storage-sqlite` - store task data locally in SQLite, and sync to a local server
+ * `server-sync` - sync to a remote taskchampion-sync-server
+ * `caldav` - the [`caldav`](crate::caldav) module, mirroring tasks to and from a CalDAV server
+ * `tracing` - emit [`tracing`](https://docs.rs/tracing) spans for storage transactions, sync
+   phases, and HTTP requests, with fields such as operation counts and byte sizes
+ * `test-support` - the [`simulation`](crate::simulation) module, for testing that replicas
+   syncing through a [`Server`](crate::Server) implementation converge
+
+Disabling both (`default-features = false`) leaves only in-memory storage and no built-in
+servers, but allows the crate to be built for targets such as `wasm32-unknown-unknown`.
+
 # See Also
 
 See the [TaskChampion Book](http://taskchampion.github.com/taskchampion)
@@ -48,22 +64,39 @@ This crate supports Rust version 1.47 and higher.
 // NOTE: it's important that this 'mod' comes first so that the macros can be used in other modules
 mod macros;
 
+#[cfg(feature = "caldav")]
+pub mod caldav;
+mod cursor;
 mod depmap;
 mod errors;
+mod ical;
+mod maintenance;
+mod project;
+mod reminder;
 mod replica;
+pub mod schema;
 pub mod server;
+mod similarity;
+#[cfg(any(test, feature = "test-support"))]
+pub mod simulation;
 pub mod storage;
 mod task;
 mod taskdb;
+pub mod todotxt;
 mod utils;
 mod workingset;
 
+pub use cursor::OperationsCursor;
 pub use depmap::DependencyMap;
-pub use errors::Error;
+pub use errors::{Error, Result};
+pub use maintenance::MaintenanceReport;
+pub use project::ProjectSummary;
+pub use reminder::{Reminder, ReminderKind};
 pub use replica::Replica;
-pub use server::{Server, ServerConfig};
+pub use server::{Server, ServerConfig, SyncOp};
 pub use storage::StorageConfig;
-pub use task::{Annotation, Status, Tag, Task, TaskMut};
+pub use task::{Annotation, Link, Status, Tag, Task, TaskMut};
+pub use taskdb::SyncProgress;
 pub use workingset::WorkingSet;
 
 /// Re-exported type from the `uuid` crate, for ease of compatibility for consumers of this crate.
@@ -15,3 +15,21 @@ macro_rules! set(
         }
      };
 );
+
+/// Enter a debug-level tracing span, which lasts until the end of the enclosing block.  This does
+/// nothing unless the `tracing` feature is enabled.
+macro_rules! enter_span(
+    { $($arg:tt)* } => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($($arg)*).entered();
+    };
+);
+
+/// Record the value of a field of the current tracing span, which must have been declared when
+/// the span was entered.  This does nothing unless the `tracing` feature is enabled.
+macro_rules! record_span(
+    { $field:literal, $value:expr } => {
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record($field, &$value);
+    };
+);
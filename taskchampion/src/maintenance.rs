@@ -0,0 +1,20 @@
+use uuid::Uuid;
+
+/// A MaintenanceReport describes the changes made by
+/// [`Replica::maintenance`](crate::Replica::maintenance).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MaintenanceReport {
+    /// Dependencies on tasks that do not exist, which were removed, as pairs of the UUID of the
+    /// dependent task and the UUID of the missing task.
+    pub dangling_dependencies: Vec<(Uuid, Uuid)>,
+
+    /// Pending tasks whose `until` time had passed, which were deleted.
+    pub expired_tasks: Vec<Uuid>,
+}
+
+impl MaintenanceReport {
+    /// Determine whether the maintenance pass made no changes.
+    pub fn is_empty(&self) -> bool {
+        self.dangling_dependencies.is_empty() && self.expired_tasks.is_empty()
+    }
+}
@@ -0,0 +1,49 @@
+/// A ProjectSummary counts the tasks in a single project, as returned from
+/// [`Replica::projects`](crate::Replica::projects).
+///
+/// Projects are often arranged in a hierarchy, with components separated by `.`, such as
+/// `home.garden`.  The counts in a summary include only tasks directly in the project, and not
+/// tasks in its sub-projects.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProjectSummary {
+    /// The number of pending tasks in the project
+    pub pending: usize,
+    /// The number of completed tasks in the project
+    pub completed: usize,
+}
+
+impl ProjectSummary {
+    /// The fraction of tasks in the project that are completed, between 0.0 and 1.0.  A project
+    /// with no tasks is considered complete.
+    pub fn fraction_complete(&self) -> f64 {
+        let total = self.pending + self.completed;
+        if total == 0 {
+            1.0
+        } else {
+            self.completed as f64 / total as f64
+        }
+    }
+
+    /// Add the counts from another summary to this one
+    pub fn add(&mut self, other: &ProjectSummary) {
+        self.pending += other.pending;
+        self.completed += other.completed;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn fraction_complete() {
+        let mut summary = ProjectSummary::default();
+        assert_eq!(summary.fraction_complete(), 1.0);
+        summary.add(&ProjectSummary {
+            pending: 3,
+            completed: 1,
+        });
+        assert_eq!(summary.fraction_complete(), 0.25);
+    }
+}
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// The time that causes a task to be included in [`Replica::reminders`](crate::Replica::reminders).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReminderKind {
+    /// The task is due
+    Due,
+    /// The task is scheduled to start
+    Scheduled,
+}
+
+impl ReminderKind {
+    /// The name of the task property giving the reminder's time
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReminderKind::Due => "due",
+            ReminderKind::Scheduled => "scheduled",
+        }
+    }
+}
+
+/// A Reminder describes a pending task that is due or scheduled within a window of time, as
+/// returned from [`Replica::reminders`](crate::Replica::reminders).  A task that is both due
+/// and scheduled within the window has a reminder for each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reminder {
+    /// The task's UUID
+    pub uuid: Uuid,
+    /// The task's description
+    pub description: String,
+    /// Whether the task is due or scheduled at `time`
+    pub kind: ReminderKind,
+    /// The time at which the task is due or scheduled
+    pub time: DateTime<Utc>,
+}
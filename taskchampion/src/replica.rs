@@ -1,13 +1,22 @@
+use crate::cursor::OperationsCursor;
 use crate::depmap::DependencyMap;
+use crate::errors::{Error, Result};
+use crate::ical::{self, VTodo};
+use crate::maintenance::MaintenanceReport;
+use crate::project::ProjectSummary;
+use crate::reminder::{Reminder, ReminderKind};
+use crate::schema::{self, Key, Prop};
 use crate::server::{Server, SyncOp};
-use crate::storage::{Storage, TaskMap};
-use crate::task::{Status, Task};
-use crate::taskdb::TaskDb;
+use crate::similarity;
+use crate::storage::{ReplicaOp, Storage, TaskMap};
+use crate::task::{Status, Tag, Task};
+use crate::taskdb::{SyncProgress, TaskDb};
 use crate::workingset::WorkingSet;
 use anyhow::Context;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use log::trace;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::rc::Rc;
 use uuid::Uuid;
 
@@ -27,23 +36,44 @@ use uuid::Uuid;
 /// A replica maintains a "working set" of tasks that are of current concern to the user,
 /// specifically pending tasks.  These are indexed with small, easy-to-type integers.  Newly
 /// pending tasks are automatically added to the working set, and the working set is "renumbered"
-/// during the garbage-collection process.
+/// during the garbage-collection process.  Tasks that are no longer pending are also removed from
+/// the working set after each sync, unless this is disabled with
+/// [`set_auto_rebuild_working_set`](Replica::set_auto_rebuild_working_set).
 pub struct Replica {
     taskdb: TaskDb,
 
+    /// If true, the working set is rebuilt (without renumbering) after each sync.
+    auto_rebuild_working_set: bool,
+
     /// If true, this replica has already added an undo point.
     added_undo_point: bool,
 
     /// The dependency map for this replica, if it has been calculated.
     depmap: Option<Rc<DependencyMap>>,
+
+    /// Cached task data, if enabled with [`set_task_cache`](Replica::set_task_cache).
+    task_cache: Option<TaskCache>,
+
+    /// The similarity at which new tasks are considered duplicates, if enabled with
+    /// [`set_duplicate_check`](Replica::set_duplicate_check).
+    duplicate_threshold: Option<f64>,
+}
+
+/// Task data cached by a replica, valid while the TaskDb's modification count is unchanged.
+struct TaskCache {
+    modification_count: u64,
+    tasks: HashMap<Uuid, Option<(TaskMap, u64)>>,
 }
 
 impl Replica {
     pub fn new(storage: Box<dyn Storage>) -> Replica {
         Replica {
             taskdb: TaskDb::new(storage),
+            auto_rebuild_working_set: true,
             added_undo_point: false,
             depmap: None,
+            task_cache: None,
+            duplicate_threshold: None,
         }
     }
 
@@ -63,7 +93,7 @@ impl Replica {
         uuid: Uuid,
         property: S1,
         value: Option<S2>,
-    ) -> anyhow::Result<TaskMap>
+    ) -> Result<TaskMap>
     where
         S1: Into<String>,
         S2: Into<String>,
@@ -77,29 +107,136 @@ impl Replica {
         })
     }
 
+    /// Update a task as for [`Replica::update_task`], returning the resulting TaskMap and the
+    /// task's new revision.  If `revision` is given, the update is only made if the task has
+    /// that revision.
+    pub(crate) fn update_task_at_revision(
+        &mut self,
+        uuid: Uuid,
+        property: &str,
+        value: Option<String>,
+        revision: Option<u64>,
+    ) -> Result<(TaskMap, u64)> {
+        self.add_undo_point(false)?;
+        self.taskdb.apply_at_revision(
+            SyncOp::Update {
+                uuid,
+                property: property.into(),
+                value,
+                timestamp: Utc::now(),
+            },
+            revision,
+        )
+    }
+
     /// Add the given uuid to the working set, returning its index.
-    pub(crate) fn add_to_working_set(&mut self, uuid: Uuid) -> anyhow::Result<usize> {
+    pub(crate) fn add_to_working_set(&mut self, uuid: Uuid) -> Result<usize> {
         self.taskdb.add_to_working_set(uuid)
     }
 
     /// Get all tasks represented as a map keyed by UUID
-    pub fn all_tasks(&mut self) -> anyhow::Result<HashMap<Uuid, Task>> {
+    pub fn all_tasks(&mut self) -> Result<HashMap<Uuid, Task>> {
         let depmap = self.dependency_map(false)?;
         let mut res = HashMap::new();
-        for (uuid, tm) in self.taskdb.all_tasks()?.drain(..) {
-            res.insert(uuid, Task::new(uuid, tm, depmap.clone()));
+        for (uuid, tm, revision) in self.taskdb.all_tasks_with_revisions()?.drain(..) {
+            res.insert(
+                uuid,
+                Task::new(uuid, tm, depmap.clone()).with_revision(revision),
+            );
         }
         Ok(res)
     }
 
+    /// Write the tasks for which `filter` returns true to `writer` as an iCalendar
+    /// ([RFC 5545](https://tools.ietf.org/html/rfc5545)) object, with a `VTODO` component for
+    /// each task.  This includes each task's due date, priority, status, and tags (as
+    /// `CATEGORIES`), so that tasks can be displayed in calendar applications.
+    pub fn export_ical<W, F>(&mut self, mut writer: W, filter: F) -> Result<()>
+    where
+        W: Write,
+        F: Fn(&Task) -> bool,
+    {
+        let mut tasks: Vec<Task> = self
+            .all_tasks()?
+            .drain()
+            .map(|(_, task)| task)
+            .filter(|task| filter(task))
+            .collect();
+        // sort for stable output
+        tasks.sort_by_key(|task| task.get_uuid());
+        ical::write_calendar(&mut writer, tasks.iter().map(VTodo::from_task))
+            .map_err(|e| Error::Other(e.into()))
+    }
+
     /// Get the UUIDs of all tasks
-    pub fn all_task_uuids(&mut self) -> anyhow::Result<Vec<Uuid>> {
+    pub fn all_task_uuids(&mut self) -> Result<Vec<Uuid>> {
         self.taskdb.all_task_uuids()
     }
 
+    /// Summarize the projects of all pending and completed tasks, keyed by project name.
+    /// Deleted tasks, and tasks without a project, are not included.
+    pub fn projects(&mut self) -> Result<HashMap<String, ProjectSummary>> {
+        let mut res: HashMap<String, ProjectSummary> = HashMap::new();
+        for (_, tm) in self.taskdb.all_tasks()? {
+            let project = match schema::get_value::<String>(&tm, Prop::Project) {
+                Some(project) if !project.is_empty() => project,
+                _ => continue,
+            };
+            let status = schema::get_value(&tm, Prop::Status).unwrap_or(Status::Pending);
+            let summary = match status {
+                Status::Pending => ProjectSummary {
+                    pending: 1,
+                    completed: 0,
+                },
+                Status::Completed => ProjectSummary {
+                    pending: 0,
+                    completed: 1,
+                },
+                _ => continue,
+            };
+            res.entry(project).or_default().add(&summary);
+        }
+        Ok(res)
+    }
+
+    /// Get reminders for the pending tasks that are due or scheduled at or after `start` and
+    /// before `end`, ordered by time.  Tasks that are waiting at `start` are not included.
+    ///
+    /// Running this periodically with consecutive windows, such as from cron, gives each
+    /// reminder exactly once.
+    pub fn reminders(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<Reminder>> {
+        let mut res = vec![];
+        for (uuid, task) in self.all_tasks()? {
+            if task.get_status() != Status::Pending {
+                continue;
+            }
+            if matches!(task.get_wait(), Some(wait) if wait > start) {
+                continue;
+            }
+            let times = [
+                (ReminderKind::Due, task.get_due()),
+                (ReminderKind::Scheduled, task.get_scheduled()),
+            ];
+            for (kind, time) in times.iter() {
+                if let Some(time) = *time {
+                    if time >= start && time < end {
+                        res.push(Reminder {
+                            uuid,
+                            description: task.get_description().to_owned(),
+                            kind: *kind,
+                            time,
+                        });
+                    }
+                }
+            }
+        }
+        res.sort_by_key(|r| (r.time, r.uuid, r.kind));
+        Ok(res)
+    }
+
     /// Get the "working set" for this replica.  This is a snapshot of the current state,
     /// and it is up to the caller to decide how long to store this value.
-    pub fn working_set(&mut self) -> anyhow::Result<WorkingSet> {
+    pub fn working_set(&mut self) -> Result<WorkingSet> {
         Ok(WorkingSet::new(self.taskdb.working_set()?))
     }
 
@@ -111,21 +248,32 @@ impl Replica {
     ///
     /// If `force` is true, then the result is re-calculated from the current state of the replica,
     /// although previously-returned dependency maps are not updated.
-    pub fn dependency_map(&mut self, force: bool) -> anyhow::Result<Rc<DependencyMap>> {
+    pub fn dependency_map(&mut self, force: bool) -> Result<Rc<DependencyMap>> {
         if force || self.depmap.is_none() {
             let mut dm = DependencyMap::new();
             let ws = self.working_set()?;
+
+            // the working set may contain tasks that are no longer pending, so check the status
+            // of each one. note: we can't use self.get_task here, as that depends on a
+            // DependencyMap
+            let mut pending = HashMap::new();
             for i in 1..=ws.largest_index() {
                 if let Some(u) = ws.by_index(i) {
-                    // note: we can't use self.get_task here, as that depends on a
-                    // DependencyMap
                     if let Some(taskmap) = self.taskdb.get_task(u)? {
-                        for p in taskmap.keys() {
-                            if let Some(dep_str) = p.strip_prefix("dep_") {
-                                if let Ok(dep) = Uuid::parse_str(dep_str) {
-                                    dm.add_dependency(u, dep);
-                                }
-                            }
+                        let status = schema::get_value(&taskmap, Prop::Status);
+                        if status == Some(Status::Pending) {
+                            pending.insert(u, taskmap);
+                        }
+                    }
+                }
+            }
+
+            // only dependencies between two pending tasks are unresolved
+            for (u, taskmap) in pending.iter() {
+                for k in taskmap.keys() {
+                    if let Key::Dependency(dep) = Key::parse(k) {
+                        if pending.contains_key(&dep) {
+                            dm.add_dependency(*u, dep);
                         }
                     }
                 }
@@ -138,16 +286,99 @@ impl Replica {
     }
 
     /// Get an existing task by its UUID
-    pub fn get_task(&mut self, uuid: Uuid) -> anyhow::Result<Option<Task>> {
+    pub fn get_task(&mut self, uuid: Uuid) -> Result<Option<Task>> {
         let depmap = self.dependency_map(false)?;
         Ok(self
-            .taskdb
-            .get_task(uuid)?
-            .map(move |tm| Task::new(uuid, tm, depmap)))
+            .get_taskmap(uuid)?
+            .map(move |(tm, revision)| Task::new(uuid, tm, depmap).with_revision(revision)))
+    }
+
+    /// Get an existing task's TaskMap and revision, using the task cache if it is enabled.
+    fn get_taskmap(&mut self, uuid: Uuid) -> Result<Option<(TaskMap, u64)>> {
+        let modification_count = self.taskdb.modification_count();
+        let cache = match self.task_cache {
+            Some(ref mut cache) => cache,
+            None => return self.taskdb.get_task_with_revision(uuid),
+        };
+
+        // any modification since the cache was filled invalidates all entries
+        if cache.modification_count != modification_count {
+            cache.tasks.clear();
+            cache.modification_count = modification_count;
+        }
+
+        if let Some(taskmap) = cache.tasks.get(&uuid) {
+            return Ok(taskmap.clone());
+        }
+        let taskmap = self.taskdb.get_task_with_revision(uuid)?;
+        cache.tasks.insert(uuid, taskmap.clone());
+        Ok(taskmap)
+    }
+
+    /// Set whether this replica caches task data read by [`get_task`](Replica::get_task).  This
+    /// is disabled by default.  Enabling it is useful for interactive applications that read the
+    /// same tasks repeatedly.
+    ///
+    /// The cache is invalidated by any modification made through this replica, but not by
+    /// modifications made to the same storage by other means, such as another process.
+    pub fn set_task_cache(&mut self, enabled: bool) {
+        if !enabled {
+            self.task_cache = None;
+        } else if self.task_cache.is_none() {
+            self.task_cache = Some(TaskCache {
+                modification_count: self.taskdb.modification_count(),
+                tasks: HashMap::new(),
+            });
+        }
+    }
+
+    /// Find pending tasks with descriptions similar to the given description, most similar first.
+    ///
+    /// Descriptions are compared as sets of words, ignoring case, punctuation, and word order.
+    /// The similarity is the fraction of the words in either description that appear in both,
+    /// from 0.0 (no words in common) to 1.0 (the same words), and tasks with a similarity of at
+    /// least `threshold` are returned.  This is useful for detecting tasks that have been entered
+    /// twice, such as on two devices between synchronizations.
+    pub fn similar_tasks(&mut self, description: &str, threshold: f64) -> Result<Vec<Task>> {
+        let tokens = similarity::tokens(description);
+        let mut similar: Vec<(f64, Task)> = self
+            .all_tasks()?
+            .drain()
+            .filter(|(_, task)| task.get_status() == Status::Pending)
+            .map(|(_, task)| {
+                let task_tokens = similarity::tokens(task.get_description());
+                (similarity::similarity(&tokens, &task_tokens), task)
+            })
+            .filter(|(s, _)| *s >= threshold)
+            .collect();
+        similar.sort_by(|(s1, t1), (s2, t2)| {
+            s2.partial_cmp(s1)
+                .unwrap()
+                .then(t1.get_uuid().cmp(&t2.get_uuid()))
+        });
+        Ok(similar.drain(..).map(|(_, task)| task).collect())
+    }
+
+    /// Set whether [`new_task`](Replica::new_task) checks for duplicates.  With a threshold, a
+    /// new pending task is not created if any pending task has a description at least that
+    /// similar, as determined by [`similar_tasks`](Replica::similar_tasks), and
+    /// [`Error::Duplicate`] is returned instead.  This is disabled by default.
+    pub fn set_duplicate_check(&mut self, threshold: Option<f64>) {
+        self.duplicate_threshold = threshold;
     }
 
     /// Create a new task.
-    pub fn new_task(&mut self, status: Status, description: String) -> anyhow::Result<Task> {
+    ///
+    /// If duplicate checking is enabled with [`set_duplicate_check`](Replica::set_duplicate_check),
+    /// this fails with [`Error::Duplicate`] when a similar pending task exists.
+    pub fn new_task(&mut self, status: Status, description: String) -> Result<Task> {
+        if let Some(threshold) = self.duplicate_threshold {
+            if status == Status::Pending {
+                if let Some(task) = self.similar_tasks(&description, threshold)?.first() {
+                    return Err(Error::Duplicate(task.get_uuid()));
+                }
+            }
+        }
         let uuid = Uuid::new_v4();
         self.add_undo_point(false)?;
         let taskmap = self.taskdb.apply(SyncOp::Create { uuid })?;
@@ -160,20 +391,103 @@ impl Replica {
         Ok(task.into_immut())
     }
 
+    /// Create a new task with the given status and description, and with the given additional
+    /// properties, in a single transaction.  This is useful for importing tasks, as it avoids
+    /// the overhead of setting each property separately.
+    ///
+    /// The properties are given in the low-level task data model (see [`Replica::update_task`]),
+    /// and the `status` and `description` properties are ignored.  The `entry` and `modified`
+    /// properties default to the current time, and `end` defaults to the current time for a
+    /// completed or deleted task.
+    pub fn new_task_with_properties(
+        &mut self,
+        status: Status,
+        description: String,
+        mut properties: TaskMap,
+    ) -> Result<Task> {
+        let uuid = Uuid::new_v4();
+        let now = Utc::now();
+        properties.insert("status".into(), status.to_taskmap().into());
+        properties.insert("description".into(), description);
+        for property in ["entry", "modified"].iter() {
+            properties
+                .entry((*property).into())
+                .or_insert_with(|| now.timestamp().to_string());
+        }
+        if status == Status::Completed || status == Status::Deleted {
+            properties
+                .entry("end".into())
+                .or_insert_with(|| now.timestamp().to_string());
+        }
+
+        let mut ops = vec![SyncOp::Create { uuid }];
+        for (property, value) in properties {
+            ops.push(SyncOp::Update {
+                uuid,
+                property,
+                value: Some(value),
+                timestamp: now,
+            });
+        }
+
+        self.add_undo_point(false)?;
+        let taskmap = self.taskdb.apply_all(ops)?;
+        if status == Status::Pending {
+            self.add_to_working_set(uuid)?;
+        }
+        let depmap = self.dependency_map(true)?;
+        let revision = self.taskdb.task_revision(uuid)?;
+        trace!("task {} created with properties", uuid);
+        Ok(Task::new(uuid, taskmap, depmap).with_revision(revision))
+    }
+
     /// Create a new, empty task with the given UUID.  This is useful for importing tasks, but
     /// otherwise should be avoided in favor of `new_task`.  If the task already exists, this
     /// does nothing and returns the existing task.
-    pub fn import_task_with_uuid(&mut self, uuid: Uuid) -> anyhow::Result<Task> {
+    pub fn import_task_with_uuid(&mut self, uuid: Uuid) -> Result<Task> {
         self.add_undo_point(false)?;
         let taskmap = self.taskdb.apply(SyncOp::Create { uuid })?;
         let depmap = self.dependency_map(false)?;
-        Ok(Task::new(uuid, taskmap, depmap))
+        let revision = self.taskdb.task_revision(uuid)?;
+        Ok(Task::new(uuid, taskmap, depmap).with_revision(revision))
+    }
+
+    /// Create a new task with the same properties as an existing task, such as its description,
+    /// tags, annotations, and dependencies.  The new task is pending, with a new entry time, and
+    /// is neither started nor ended.  If the existing task is an instance of a recurring task, the
+    /// new task is not.
+    pub fn duplicate_task(&mut self, uuid: Uuid) -> Result<Task> {
+        let original = self
+            .taskdb
+            .get_task(uuid)?
+            .ok_or_else(|| Error::Database(format!("Task {} does not exist", uuid)))?;
+
+        let new_uuid = Uuid::new_v4();
+        self.add_undo_point(false)?;
+        self.taskdb.apply(SyncOp::Create { uuid: new_uuid })?;
+        for (property, value) in original {
+            // these properties describe the existing task's history, and are not copied
+            if ["entry", "modified", "start", "end", "status", "parent"].contains(&&*property) {
+                continue;
+            }
+            self.update_task(new_uuid, property, Some(value))?;
+        }
+
+        let depmap = self.dependency_map(true)?;
+        let (taskmap, revision) = self.taskdb.get_task_with_revision(new_uuid)?.unwrap();
+        let mut task = Task::new(new_uuid, taskmap, depmap)
+            .with_revision(revision)
+            .into_mut(self);
+        task.set_status(Status::Pending)?;
+        task.set_entry(Some(Utc::now()))?;
+        trace!("task {} duplicated as {}", uuid, new_uuid);
+        Ok(task.into_immut())
     }
 
     /// Delete a task.  The task must exist.  Note that this is different from setting status to
     /// Deleted; this is the final purge of the task.  This is not a public method as deletion
     /// should only occur through expiration.
-    fn delete_task(&mut self, uuid: Uuid) -> anyhow::Result<()> {
+    fn delete_task(&mut self, uuid: Uuid) -> Result<()> {
         self.add_undo_point(false)?;
         self.taskdb.apply(SyncOp::Delete { uuid })?;
         trace!("task {} deleted", uuid);
@@ -190,30 +504,119 @@ impl Replica {
     ///
     /// Set this to true on systems more constrained in CPU, memory, or bandwidth than a typical desktop
     /// system
-    pub fn sync(
+    ///
+    /// The replica's storage is locked for the whole sync, including requests to the server.
+    /// With SQLite storage shared by several replicas, operations on the others fail after five
+    /// seconds if the sync has not finished; see `SqliteStorage`.
+    pub fn sync(&mut self, server: &mut Box<dyn Server>, avoid_snapshots: bool) -> Result<()> {
+        self.sync_with_progress(server, avoid_snapshots, &mut |_| true)
+    }
+
+    /// Set whether the working set is rebuilt automatically after each sync.  This is enabled by
+    /// default.  When disabled, the working set changes only when tasks become pending or when
+    /// [`rebuild_working_set`](Replica::rebuild_working_set) is called, allowing the application
+    /// to control exactly when working-set indices change.
+    pub fn set_auto_rebuild_working_set(&mut self, auto_rebuild: bool) {
+        self.auto_rebuild_working_set = auto_rebuild;
+    }
+
+    /// Like [`Replica::sync`], but calling `progress` as the sync proceeds, so that it can be
+    /// shown to the user.  If `progress` returns false, the sync is cancelled, no changes are
+    /// made to the replica, and the result is an error that downcasts to
+    /// [`Error::SyncCancelled`](crate::Error::SyncCancelled).  Once the server has accepted the
+    /// replica's new version, the sync can no longer be cancelled, and returning false for
+    /// [`SyncProgress::SendingSnapshot`] only skips the snapshot.
+    pub fn sync_with_progress(
         &mut self,
         server: &mut Box<dyn Server>,
         avoid_snapshots: bool,
-    ) -> anyhow::Result<()> {
+        progress: &mut dyn FnMut(SyncProgress) -> bool,
+    ) -> Result<()> {
         self.taskdb
-            .sync(server, avoid_snapshots)
+            .sync(server, avoid_snapshots, progress)
             .context("Failed to synchronize with server")?;
-        self.rebuild_working_set(false)
-            .context("Failed to rebuild working set after sync")?;
+        if self.auto_rebuild_working_set {
+            self.rebuild_working_set(false)
+                .context("Failed to rebuild working set after sync")?;
+        }
         Ok(())
     }
 
+    /// Get the changes made in this replica that have not yet been synchronized, as the
+    /// operations that a sync would send to the server, following the given cursor.  Returns the
+    /// operations and a cursor following them.
+    ///
+    /// Passing the cursor returned by the previous call allows changes to be exported
+    /// incrementally, such as to build a custom replication pipeline; start with
+    /// [`OperationsCursor::default()`].  If operations exported before the cursor have since been
+    /// undone, or removed by a sync, the result is [`Error::StaleCursor`], as the replica no
+    /// longer contains the changes the consumer has seen.
+    ///
+    /// The serde serialization of [`SyncOp`], such as with `serde_json`, is the format used by
+    /// the sync protocol, and is stable.
+    pub fn export_operations(
+        &mut self,
+        since: OperationsCursor,
+    ) -> Result<(Vec<SyncOp>, OperationsCursor)> {
+        let mut ops: Vec<SyncOp> = self
+            .taskdb
+            .unsynced_operations()?
+            .into_iter()
+            .filter_map(|op| op.into_sync())
+            .collect();
+        let position = since.position();
+        if position > ops.len() || OperationsCursor::after(&ops[..position])? != since {
+            return Err(Error::StaleCursor);
+        }
+        let cursor = OperationsCursor::after(&ops)?;
+        Ok((ops.split_off(position), cursor))
+    }
+
+    /// Apply operations, such as those exported from another replica with
+    /// [`export_operations`](Replica::export_operations), in a single transaction.
+    ///
+    /// As in a sync, an operation that cannot be applied, such as an update to a task that
+    /// another replica has deleted, is skipped and the remaining operations are applied.  The
+    /// skipped operations are returned.
+    ///
+    /// The operations are recorded as changes in this replica, so they are included in the next
+    /// sync and can be undone.  As after a sync, the working set is rebuilt unless disabled with
+    /// [`set_auto_rebuild_working_set`](Replica::set_auto_rebuild_working_set).
+    pub fn apply_operations(&mut self, ops: Vec<SyncOp>) -> Result<Vec<SyncOp>> {
+        if ops.is_empty() {
+            return Ok(vec![]);
+        }
+        self.add_undo_point(false)?;
+        let skipped = self.taskdb.apply_valid(ops)?;
+        if self.auto_rebuild_working_set {
+            self.rebuild_working_set(false)?;
+        }
+        Ok(skipped)
+    }
+
+    /// Get the time of the last successful sync, or None if this replica has never been synced.
+    pub fn last_sync(&mut self) -> Result<Option<DateTime<Utc>>> {
+        self.taskdb.last_sync()
+    }
+
     /// Undo local operations until the most recent UndoPoint, returning false if there are no
     /// local operations to undo.
-    pub fn undo(&mut self) -> anyhow::Result<bool> {
+    pub fn undo(&mut self) -> Result<bool> {
         self.taskdb.undo()
     }
 
+    /// Get the local operations that would be reversed by calling [`undo`](Replica::undo)
+    /// `steps` times, in the order in which they were applied.  This is useful for showing the
+    /// user what an undo will do before doing it.
+    pub fn get_undo_ops(&mut self, steps: usize) -> Result<Vec<ReplicaOp>> {
+        self.taskdb.get_undo_ops(steps)
+    }
+
     /// Rebuild this replica's working set, based on whether tasks are pending or not.  If
     /// `renumber` is true, then existing tasks may be moved to new working-set indices; in any
     /// case, on completion all pending tasks are in the working set and all non- pending tasks are
     /// not.
-    pub fn rebuild_working_set(&mut self, renumber: bool) -> anyhow::Result<()> {
+    pub fn rebuild_working_set(&mut self, renumber: bool) -> Result<()> {
         let pending = String::from(Status::Pending.to_taskmap());
         self.taskdb
             .rebuild_working_set(|t| t.get("status") == Some(&pending), renumber)?;
@@ -227,7 +630,7 @@ impl Replica {
     ///
     /// Tasks are eligible for expiration when they have status Deleted and have not been modified
     /// for 180 days (about six months). Note that completed tasks are not eligible.
-    pub fn expire_tasks(&mut self) -> anyhow::Result<()> {
+    pub fn expire_tasks(&mut self) -> Result<()> {
         let six_mos_ago = Utc::now() - Duration::days(180);
         self.all_tasks()?
             .iter()
@@ -243,11 +646,119 @@ impl Replica {
         Ok(())
     }
 
+    /// Repair inconsistencies that can accumulate in the task data, returning a report of what was
+    /// changed.
+    ///
+    /// This removes dependencies on tasks that do not exist, such as tasks that have been
+    /// expired with [`Replica::expire_tasks`] or that were never synchronized to this replica.
+    /// It also deletes pending tasks whose `until` time has passed.  These are ordinary changes
+    /// to the tasks, so they are synchronized to other replicas.
+    pub fn maintenance(&mut self) -> Result<MaintenanceReport> {
+        let mut report = MaintenanceReport::default();
+        let mut tasks = self.all_tasks()?;
+        let existing: HashSet<Uuid> = tasks.keys().copied().collect();
+        let mut uuids: Vec<Uuid> = existing.iter().copied().collect();
+        uuids.sort();
+        for uuid in uuids {
+            let mut dangling: Vec<Uuid> = tasks[&uuid]
+                .get_dependencies()
+                .filter(|dep| !existing.contains(dep))
+                .collect();
+            if dangling.is_empty() {
+                continue;
+            }
+            dangling.sort();
+            let mut task = tasks.remove(&uuid).unwrap().into_mut(self);
+            for dep in dangling {
+                trace!("removing dependency of {} on missing task {}", uuid, dep);
+                task.remove_dependency(dep)?;
+                report.dangling_dependencies.push((uuid, dep));
+            }
+        }
+
+        let now = Utc::now();
+        let mut expired: Vec<Uuid> = self
+            .all_tasks()?
+            .into_iter()
+            .filter(|(_, t)| t.get_status() == Status::Pending)
+            .filter(|(_, t)| matches!(t.get_until(), Some(until) if until <= now))
+            .map(|(u, _)| u)
+            .collect();
+        expired.sort();
+        for uuid in expired {
+            trace!("deleting task {} as its until time has passed", uuid);
+            if let Some(task) = self.get_task(uuid)? {
+                task.into_mut(self).delete()?;
+                report.expired_tasks.push(uuid);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Rename a tag on every task having it, returning the number of tasks changed.  Tasks
+    /// which already have the new tag simply lose the old one.
+    ///
+    /// All of the changes are made after a new UndoPoint, so they can be undone together.
+    pub fn rename_tag(&mut self, old: &Tag, new: &Tag) -> Result<usize> {
+        for tag in &[old, new] {
+            if tag.is_synthetic() {
+                return Err(Error::InvalidTag(format!(
+                    "{} is synthetic and cannot be renamed",
+                    tag
+                )));
+            }
+        }
+        if old == new {
+            return Ok(0);
+        }
+        self.add_undo_point(true)?;
+        let mut count = 0;
+        for (_, task) in self.all_tasks()? {
+            if !task.has_tag(old) {
+                continue;
+            }
+            let mut task = task.into_mut(self);
+            task.remove_tag(old)?;
+            task.add_tag(new)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Rename a project on every task in it, returning the number of tasks changed.
+    /// Sub-projects are renamed as well, so renaming `home` to `house` moves tasks in
+    /// `home.garden` to `house.garden`.
+    ///
+    /// All of the changes are made after a new UndoPoint, so they can be undone together.
+    pub fn rename_project(&mut self, old: &str, new: &str) -> Result<usize> {
+        if old.is_empty() || new.is_empty() {
+            return Err(anyhow::anyhow!("Project names must not be empty").into());
+        }
+        if old == new {
+            return Ok(0);
+        }
+        self.add_undo_point(true)?;
+        let mut count = 0;
+        for (_, task) in self.all_tasks()? {
+            let renamed = match task.get_project() {
+                Some(project) if project == old => new.to_owned(),
+                Some(project) => match project.strip_prefix(old) {
+                    Some(rest) if rest.starts_with('.') => format!("{}{}", new, rest),
+                    _ => continue,
+                },
+                None => continue,
+            };
+            task.into_mut(self).set_project(Some(renamed))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
     /// Add an UndoPoint, if one has not already been added by this Replica.  This occurs
     /// automatically when a change is made.  The `force` flag allows forcing a new UndoPoint
     /// even if one has already been created by this Replica, and may be useful when a Replica
     /// instance is held for a long time and used to apply more than one user-visible change.
-    pub fn add_undo_point(&mut self, force: bool) -> anyhow::Result<()> {
+    pub fn add_undo_point(&mut self, force: bool) -> Result<()> {
         if force || !self.added_undo_point {
             self.taskdb.add_undo_point()?;
             self.added_undo_point = true;
@@ -256,9 +767,15 @@ impl Replica {
     }
 
     /// Get the number of operations local to this replica and not yet synchronized to the server.
-    pub fn num_local_operations(&mut self) -> anyhow::Result<usize> {
+    pub fn num_local_operations(&mut self) -> Result<usize> {
         self.taskdb.num_operations()
     }
+
+    /// Determine whether the given task has local changes that have not yet been synchronized
+    /// to the server.
+    pub fn has_local_changes(&mut self, uuid: Uuid) -> Result<bool> {
+        self.taskdb.has_operations_for(uuid)
+    }
 }
 
 #[cfg(test)]
@@ -269,6 +786,7 @@ mod tests {
     use chrono::TimeZone;
     use pretty_assertions::assert_eq;
     use std::collections::HashSet;
+    use std::convert::TryInto;
     use uuid::Uuid;
 
     #[test]
@@ -281,6 +799,84 @@ mod tests {
         assert!(t.get_modified().is_some());
     }
 
+    #[test]
+    fn new_task_with_properties() {
+        let mut rep = Replica::new_inmemory();
+
+        let mut props = TaskMap::new();
+        props.insert("project".into(), "home".into());
+        props.insert("tag_next".into(), "".into());
+        props.insert("entry".into(), "1600000000".into());
+        props.insert("status".into(), "deleted".into()); // ignored
+        let t = rep
+            .new_task_with_properties(Status::Pending, "a task".into(), props)
+            .unwrap();
+        assert_eq!(t.get_description(), "a task");
+        assert_eq!(t.get_status(), Status::Pending);
+        assert_eq!(t.get_project(), Some("home"));
+        assert!(t.has_tag(&"next".try_into().unwrap()));
+        assert_eq!(t.get_entry(), Some(Utc.timestamp(1600000000, 0)));
+        assert!(t.get_modified().is_some());
+        assert_eq!(t.get_end(), None);
+        assert_eq!(rep.get_task(t.get_uuid()).unwrap().unwrap(), t);
+
+        // the task is in the working set
+        let ws = rep.working_set().unwrap();
+        assert!(ws.by_uuid(t.get_uuid()).is_some());
+
+        // an undo point and a Create are added, with one Update for each of the six properties
+        assert_eq!(rep.num_local_operations().unwrap(), 8);
+    }
+
+    #[test]
+    fn new_task_with_properties_completed() {
+        let mut rep = Replica::new_inmemory();
+
+        let t = rep
+            .new_task_with_properties(Status::Completed, "a task".into(), TaskMap::new())
+            .unwrap();
+        assert_eq!(t.get_status(), Status::Completed);
+        assert!(t.get_end().is_some());
+        let ws = rep.working_set().unwrap();
+        assert_eq!(ws.by_uuid(t.get_uuid()), None);
+    }
+
+    #[test]
+    fn duplicate_task() {
+        let mut rep = Replica::new_inmemory();
+
+        let t = rep.new_task(Status::Pending, "a task".into()).unwrap();
+        let mut t = t.into_mut(&mut rep);
+        t.set_project(Some("home".into())).unwrap();
+        t.add_tag(&"next".try_into().unwrap()).unwrap();
+        t.start().unwrap();
+        t.done().unwrap();
+        let t = t.into_immut();
+
+        let dup = rep.duplicate_task(t.get_uuid()).unwrap();
+        assert_ne!(dup.get_uuid(), t.get_uuid());
+        assert_eq!(dup.get_description(), "a task");
+        assert_eq!(dup.get_project(), Some("home"));
+        assert!(dup.has_tag(&"next".try_into().unwrap()));
+        assert_eq!(dup.get_status(), Status::Pending);
+        assert!(!dup.is_active());
+        assert_eq!(dup.get_end(), None);
+
+        // the duplicate is in the working set
+        let ws = rep.working_set().unwrap();
+        assert!(ws.by_uuid(dup.get_uuid()).is_some());
+
+        // the original is unchanged
+        let t = rep.get_task(t.get_uuid()).unwrap().unwrap();
+        assert_eq!(t.get_status(), Status::Completed);
+    }
+
+    #[test]
+    fn duplicate_task_does_not_exist() {
+        let mut rep = Replica::new_inmemory();
+        assert!(rep.duplicate_task(Uuid::new_v4()).is_err());
+    }
+
     #[test]
     fn modify_task() {
         let mut rep = Replica::new_inmemory();
@@ -408,6 +1004,452 @@ mod tests {
         assert_eq!(rep.num_local_operations().unwrap(), 10);
     }
 
+    #[test]
+    fn projects() {
+        let mut rep = Replica::new_inmemory();
+        let mut add = |project: Option<&str>, status| {
+            let t = rep.new_task(Status::Pending, "task".into()).unwrap();
+            let mut t = t.into_mut(&mut rep);
+            t.set_project(project.map(|p| p.to_owned())).unwrap();
+            t.set_status(status).unwrap();
+        };
+        add(Some("home"), Status::Pending);
+        add(Some("home"), Status::Completed);
+        add(Some("home.garden"), Status::Pending);
+        add(Some("work"), Status::Deleted);
+        add(None, Status::Pending);
+
+        let projects = rep.projects().unwrap();
+        assert_eq!(projects.len(), 2);
+        assert_eq!(
+            projects.get("home"),
+            Some(&ProjectSummary {
+                pending: 1,
+                completed: 1
+            })
+        );
+        assert_eq!(
+            projects.get("home.garden"),
+            Some(&ProjectSummary {
+                pending: 1,
+                completed: 0
+            })
+        );
+    }
+
+    #[test]
+    fn similar_tasks() {
+        let mut rep = Replica::new_inmemory();
+        let exact = rep.new_task(Status::Pending, "Buy milk".into()).unwrap();
+        let close = rep
+            .new_task(Status::Pending, "buy milk, eggs".into())
+            .unwrap();
+        rep.new_task(Status::Pending, "call mom".into()).unwrap();
+        rep.new_task(Status::Completed, "buy milk".into()).unwrap();
+
+        let uuids = |tasks: Vec<Task>| tasks.iter().map(|t| t.get_uuid()).collect::<Vec<_>>();
+        assert_eq!(
+            uuids(rep.similar_tasks("milk: buy", 0.5).unwrap()),
+            vec![exact.get_uuid(), close.get_uuid()]
+        );
+        assert_eq!(
+            uuids(rep.similar_tasks("milk: buy", 0.8).unwrap()),
+            vec![exact.get_uuid()]
+        );
+        assert!(rep.similar_tasks("walk dog", 0.5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn new_task_duplicate_check() {
+        let mut rep = Replica::new_inmemory();
+        let t1 = rep.new_task(Status::Pending, "buy milk".into()).unwrap();
+
+        // disabled by default
+        let t2 = rep.new_task(Status::Pending, "Buy milk".into()).unwrap();
+
+        rep.set_duplicate_check(Some(0.8));
+        match rep.new_task(Status::Pending, "buy MILK".into()) {
+            Err(Error::Duplicate(uuid)) => {
+                assert!(uuid == t1.get_uuid() || uuid == t2.get_uuid())
+            }
+            r => panic!("unexpected result {:?}", r),
+        }
+        rep.new_task(Status::Pending, "buy bread".into()).unwrap();
+        // only new pending tasks are checked
+        rep.new_task(Status::Completed, "buy milk".into()).unwrap();
+    }
+
+    #[test]
+    fn export_apply_operations() {
+        let mut rep1 = Replica::new_inmemory();
+        let mut rep2 = Replica::new_inmemory();
+
+        let t = rep1.new_task(Status::Pending, "one".into()).unwrap();
+        let (exported, cursor) = rep1.export_operations(OperationsCursor::default()).unwrap();
+        assert!(matches!(exported[0], SyncOp::Create { uuid } if uuid == t.get_uuid()));
+        assert_eq!(rep2.apply_operations(exported).unwrap(), vec![]);
+
+        let mut t = t.into_mut(&mut rep1);
+        t.done().unwrap();
+        let t = t.into_immut();
+
+        // only the new operations are exported, and they survive serialization
+        let (more, _) = rep1.export_operations(cursor).unwrap();
+        assert!(!more.is_empty());
+        assert!(more.iter().all(|op| !matches!(op, SyncOp::Create { .. })));
+        let json = serde_json::to_string(&more).unwrap();
+        let more: Vec<SyncOp> = serde_json::from_str(&json).unwrap();
+        assert_eq!(rep2.apply_operations(more).unwrap(), vec![]);
+
+        let t2 = rep2.get_task(t.get_uuid()).unwrap().unwrap();
+        assert_eq!(t2.get_taskmap(), t.get_taskmap());
+        assert_eq!(t2.get_status(), Status::Completed);
+
+        // applied operations are recorded, to be synchronized
+        assert_eq!(
+            rep2.export_operations(OperationsCursor::default())
+                .unwrap()
+                .0,
+            rep1.export_operations(OperationsCursor::default())
+                .unwrap()
+                .0
+        );
+
+        // as in a sync, an update to a missing task is skipped, and the rest are applied
+        let missing = SyncOp::Update {
+            uuid: Uuid::new_v4(),
+            property: "description".into(),
+            value: Some("two".into()),
+            timestamp: Utc::now(),
+        };
+        let ops = vec![
+            missing.clone(),
+            SyncOp::Update {
+                uuid: t.get_uuid(),
+                property: "description".into(),
+                value: Some("uno".into()),
+                timestamp: Utc::now(),
+            },
+        ];
+        assert_eq!(rep2.apply_operations(ops).unwrap(), vec![missing]);
+        let t2 = rep2.get_task(t.get_uuid()).unwrap().unwrap();
+        assert_eq!(t2.get_description(), "uno");
+    }
+
+    #[test]
+    fn export_operations_after_undo() {
+        let mut rep = Replica::new_inmemory();
+        rep.new_task(Status::Pending, "one".into()).unwrap();
+        let (_, cursor) = rep.export_operations(OperationsCursor::default()).unwrap();
+        let (ops, same) = rep.export_operations(cursor).unwrap();
+        assert!(ops.is_empty());
+        assert_eq!(same, cursor);
+
+        // undoing exported operations and making other changes invalidates the cursor, even
+        // though the number of operations is unchanged
+        assert!(rep.undo().unwrap());
+        rep.new_task(Status::Pending, "two".into()).unwrap();
+        assert!(matches!(
+            rep.export_operations(cursor),
+            Err(Error::StaleCursor)
+        ));
+
+        // a sync removes the operations, also invalidating the cursor
+        let (_, cursor) = rep.export_operations(OperationsCursor::default()).unwrap();
+        let mut server = crate::server::test::TestServer::new().server();
+        rep.sync(&mut server, false).unwrap();
+        assert!(matches!(
+            rep.export_operations(cursor),
+            Err(Error::StaleCursor)
+        ));
+    }
+
+    #[test]
+    fn task_revision() {
+        let mut rep = Replica::new_inmemory();
+        let t = rep.new_task(Status::Pending, "one".into()).unwrap();
+        let uuid = t.get_uuid();
+        assert!(t.revision() > 0);
+        assert_eq!(
+            rep.get_task(uuid).unwrap().unwrap().revision(),
+            t.revision()
+        );
+        assert_eq!(rep.all_tasks().unwrap()[&uuid].revision(), t.revision());
+
+        // a checked change succeeds, and updates the revision
+        let mut t = t.into_mut_checked(&mut rep);
+        t.set_description("uno".into()).unwrap();
+        t.set_priority("H".into()).unwrap();
+        let t = t.into_immut();
+        let stale = rep.get_task(uuid).unwrap().unwrap();
+        assert_eq!(stale.revision(), t.revision());
+
+        // another writer changes the task
+        let mut t = t.into_mut(&mut rep);
+        t.set_description("eins".into()).unwrap();
+        let t = t.into_immut();
+        assert!(t.revision() > stale.revision());
+
+        // so a checked change based on the stale task fails
+        let mut stale = stale.into_mut_checked(&mut rep);
+        assert!(matches!(
+            stale.set_description("one".into()),
+            Err(Error::Conflict(u)) if u == uuid
+        ));
+        let t = rep.get_task(uuid).unwrap().unwrap();
+        assert_eq!(t.get_description(), "eins");
+    }
+
+    #[test]
+    fn rename_tag() {
+        let mut rep = Replica::new_inmemory();
+        let home: Tag = "home".try_into().unwrap();
+        let house: Tag = "house".try_into().unwrap();
+        let work: Tag = "work".try_into().unwrap();
+        let mut uuids = vec![];
+        for tags in vec![vec![&home], vec![&home, &house], vec![&work]] {
+            let mut t = rep
+                .new_task(Status::Pending, "t".into())
+                .unwrap()
+                .into_mut(&mut rep);
+            for tag in tags {
+                t.add_tag(tag).unwrap();
+            }
+            uuids.push(t.get_uuid());
+        }
+
+        assert_eq!(rep.rename_tag(&home, &house).unwrap(), 2);
+        let tags = |rep: &mut Replica, uuid| {
+            let mut tags: Vec<String> = rep
+                .get_task(uuid)
+                .unwrap()
+                .unwrap()
+                .get_tags()
+                .filter(|t| t.is_user())
+                .map(|t| t.to_string())
+                .collect();
+            tags.sort();
+            tags
+        };
+        assert_eq!(tags(&mut rep, uuids[0]), vec!["house"]);
+        assert_eq!(tags(&mut rep, uuids[1]), vec!["house"]);
+        assert_eq!(tags(&mut rep, uuids[2]), vec!["work"]);
+
+        // the rename is undone as a unit
+        assert!(rep.undo().unwrap());
+        assert_eq!(tags(&mut rep, uuids[0]), vec!["home"]);
+        assert_eq!(tags(&mut rep, uuids[1]), vec!["home", "house"]);
+
+        let pending: Tag = "PENDING".try_into().unwrap();
+        assert!(rep.rename_tag(&pending, &work).is_err());
+    }
+
+    #[test]
+    fn rename_project() {
+        let mut rep = Replica::new_inmemory();
+        let mut uuids = vec![];
+        for &project in &["home", "home.garden", "homework", "work"] {
+            let mut t = rep
+                .new_task(Status::Pending, "t".into())
+                .unwrap()
+                .into_mut(&mut rep);
+            t.set_project(Some(project.into())).unwrap();
+            uuids.push(t.get_uuid());
+        }
+
+        assert_eq!(rep.rename_project("home", "house").unwrap(), 2);
+        let projects: Vec<String> = uuids
+            .iter()
+            .map(|u| {
+                rep.get_task(*u)
+                    .unwrap()
+                    .unwrap()
+                    .get_project()
+                    .unwrap()
+                    .to_owned()
+            })
+            .collect();
+        assert_eq!(projects, vec!["house", "house.garden", "homework", "work"]);
+
+        assert!(rep.undo().unwrap());
+        let t = rep.get_task(uuids[1]).unwrap().unwrap();
+        assert_eq!(t.get_project(), Some("home.garden"));
+
+        assert!(rep.rename_project("", "x").is_err());
+    }
+
+    #[test]
+    fn maintenance() {
+        let mut rep = Replica::new_inmemory();
+        let t1 = rep.new_task(Status::Pending, "one".into()).unwrap();
+        let t2 = rep.new_task(Status::Pending, "two".into()).unwrap();
+        let t3 = rep.new_task(Status::Deleted, "three".into()).unwrap();
+        let missing = Uuid::new_v4();
+
+        let mut t1 = t1.into_mut(&mut rep);
+        t1.add_dependency(t2.get_uuid()).unwrap();
+        t1.add_dependency(t3.get_uuid()).unwrap();
+        t1.add_dependency(missing).unwrap();
+        let t1 = t1.into_immut();
+        let mut t2 = t2.into_mut(&mut rep);
+        t2.add_dependency(t3.get_uuid()).unwrap();
+        let t2 = t2.into_immut();
+
+        // t3 is removed entirely, as if expired
+        rep.delete_task(t3.get_uuid()).unwrap();
+
+        let report = rep.maintenance().unwrap();
+        let mut expected = vec![
+            (t1.get_uuid(), t3.get_uuid()),
+            (t1.get_uuid(), missing),
+            (t2.get_uuid(), t3.get_uuid()),
+        ];
+        expected.sort();
+        assert_eq!(report.dangling_dependencies, expected);
+
+        let t1 = rep.get_task(t1.get_uuid()).unwrap().unwrap();
+        assert_eq!(
+            t1.get_dependencies().collect::<Vec<_>>(),
+            vec![t2.get_uuid()]
+        );
+        let t2 = rep.get_task(t2.get_uuid()).unwrap().unwrap();
+        assert_eq!(t2.get_dependencies().count(), 0);
+
+        // a second pass has nothing to do
+        assert!(rep.maintenance().unwrap().is_empty());
+    }
+
+    #[test]
+    fn maintenance_until() {
+        let mut rep = Replica::new_inmemory();
+        let mut uuids = vec![];
+        for (status, until) in vec![
+            (Status::Pending, Some(Utc::now() - Duration::days(1))),
+            (Status::Pending, Some(Utc::now() + Duration::days(1))),
+            (Status::Pending, None),
+            (Status::Completed, Some(Utc::now() - Duration::days(1))),
+        ] {
+            let mut t = rep.new_task(status, "t".into()).unwrap().into_mut(&mut rep);
+            t.set_until(until).unwrap();
+            uuids.push(t.get_uuid());
+        }
+
+        let report = rep.maintenance().unwrap();
+        assert_eq!(report.expired_tasks, vec![uuids[0]]);
+        let statuses: Vec<Status> = uuids
+            .iter()
+            .map(|u| rep.get_task(*u).unwrap().unwrap().get_status())
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Status::Deleted,
+                Status::Pending,
+                Status::Pending,
+                Status::Completed
+            ]
+        );
+
+        // the deletion is recorded as an operation, to be synchronized
+        let ops = rep.get_undo_ops(1).unwrap();
+        assert!(ops.iter().any(|op| matches!(
+            op,
+            ReplicaOp::Update { uuid, property, value: Some(value), .. }
+                if *uuid == uuids[0] && property == "status" && value == "deleted"
+        )));
+
+        assert!(rep.maintenance().unwrap().is_empty());
+    }
+
+    #[test]
+    fn reminders() {
+        let mut rep = Replica::new_inmemory();
+        let start = Utc.ymd(2022, 1, 1).and_hms(12, 0, 0);
+        let mut add = |description: &str, due, scheduled, wait, status| {
+            let t = rep.new_task(Status::Pending, description.into()).unwrap();
+            let mut t = t.into_mut(&mut rep);
+            t.set_due(due).unwrap();
+            t.set_scheduled(scheduled).unwrap();
+            t.set_wait(wait).unwrap();
+            t.set_status(status).unwrap();
+            t.get_uuid()
+        };
+        let hours = |h| Some(start + Duration::hours(h));
+        let both = add("both", hours(2), hours(0), None, Status::Pending);
+        let due = add("due", hours(1), hours(-1), None, Status::Pending);
+        add("later", hours(3), None, None, Status::Pending);
+        add("earlier", hours(-1), None, None, Status::Pending);
+        add("completed", hours(1), None, None, Status::Completed);
+        add("waiting", hours(1), None, hours(1), Status::Pending);
+        let waited = add("waited", hours(1), None, hours(-1), Status::Pending);
+
+        let reminders = rep.reminders(start, start + Duration::hours(3)).unwrap();
+        let summary: Vec<_> = reminders.iter().map(|r| (r.uuid, r.kind, r.time)).collect();
+        let mut at_one = vec![
+            (due, ReminderKind::Due, hours(1).unwrap()),
+            (waited, ReminderKind::Due, hours(1).unwrap()),
+        ];
+        at_one.sort();
+        let mut expected = vec![(both, ReminderKind::Scheduled, start)];
+        expected.append(&mut at_one);
+        expected.push((both, ReminderKind::Due, hours(2).unwrap()));
+        assert_eq!(summary, expected);
+        assert_eq!(reminders[0].description, "both");
+    }
+
+    #[test]
+    fn export_ical() {
+        let mut rep = Replica::new_inmemory();
+        let t1 = rep.new_task(Status::Pending, "one".into()).unwrap();
+        let t2 = rep.new_task(Status::Completed, "two".into()).unwrap();
+
+        let mut output = vec![];
+        rep.export_ical(&mut output, |t| t.get_status() == Status::Pending)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(output.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(output.matches("BEGIN:VTODO").count(), 1);
+        assert!(output.contains(&format!("UID:{}", t1.get_uuid())));
+        assert!(!output.contains(&format!("UID:{}", t2.get_uuid())));
+    }
+
+    #[test]
+    fn has_local_changes() {
+        let mut rep = Replica::new_inmemory();
+        let t1 = rep.new_task(Status::Pending, "one".into()).unwrap();
+        let uuid2 = Uuid::new_v4();
+
+        assert!(rep.has_local_changes(t1.get_uuid()).unwrap());
+        assert!(!rep.has_local_changes(uuid2).unwrap());
+
+        rep.sync(&mut crate::server::test::TestServer::new().server(), false)
+            .unwrap();
+        assert!(!rep.has_local_changes(t1.get_uuid()).unwrap());
+    }
+
+    #[test]
+    fn sync_rebuilds_working_set() {
+        let mut server = crate::server::test::TestServer::new().server();
+        for &auto_rebuild in &[true, false] {
+            let mut rep = Replica::new_inmemory();
+            rep.set_auto_rebuild_working_set(auto_rebuild);
+            let t = rep.new_task(Status::Pending, "a task".into()).unwrap();
+            let mut t = t.into_mut(&mut rep);
+            t.done().unwrap();
+            let uuid = t.get_uuid();
+
+            rep.sync(&mut server, false).unwrap();
+
+            let ws = rep.working_set().unwrap();
+            if auto_rebuild {
+                assert_eq!(ws.by_uuid(uuid), None);
+            } else {
+                assert_eq!(ws.by_uuid(uuid), Some(1));
+            }
+        }
+    }
+
     #[test]
     fn delete_task() {
         let mut rep = Replica::new_inmemory();
@@ -470,6 +1512,26 @@ mod tests {
         assert_eq!(rep.get_task(uuid).unwrap(), None);
     }
 
+    #[test]
+    fn get_task_cached() {
+        let mut rep = Replica::new_inmemory();
+        rep.set_task_cache(true);
+        let uuid = Uuid::new_v4();
+        assert_eq!(rep.get_task(uuid).unwrap(), None);
+
+        let t = rep.import_task_with_uuid(uuid).unwrap();
+        let mut t = t.into_mut(&mut rep);
+        t.set_description("cached".into()).unwrap();
+        let t = t.into_immut();
+        assert_eq!(rep.get_task(uuid).unwrap(), Some(t.clone()));
+
+        // a second read is served from the cache
+        assert_eq!(rep.get_task(uuid).unwrap(), Some(t));
+
+        rep.undo().unwrap();
+        assert_eq!(rep.get_task(uuid).unwrap(), None);
+    }
+
     #[test]
     fn expire() {
         let mut rep = Replica::new_inmemory();
@@ -562,4 +1624,21 @@ mod tests {
             set![uuids[1], uuids[2]]
         );
     }
+
+    #[test]
+    fn dependency_map_resolved_dependencies() {
+        let mut rep = Replica::new_inmemory();
+
+        let t1 = rep.new_task(Status::Pending, "t1".into()).unwrap();
+        let t2 = rep.new_task(Status::Pending, "t2".into()).unwrap();
+        let (uuid1, uuid2) = (t1.get_uuid(), t2.get_uuid());
+
+        // t1 depends on t2, which is then completed
+        t1.into_mut(&mut rep).add_dependency(uuid2).unwrap();
+        t2.into_mut(&mut rep).done().unwrap();
+
+        let dm = rep.dependency_map(true).unwrap();
+        assert_eq!(dm.dependencies(uuid1).collect::<HashSet<_>>(), set![]);
+        assert_eq!(dm.dependents(uuid2).collect::<HashSet<_>>(), set![]);
+    }
 }
@@ -0,0 +1,317 @@
+/*!
+
+This module defines the keys and value formats of the [task data model](https://taskchampion.github.io/taskchampion/tasks.html),
+translating between the strings in a [`TaskMap`](crate::storage::TaskMap) and typed values.
+
+[`Task`](crate::Task) and [`TaskMut`](crate::TaskMut) use these definitions for all of their
+getters and setters, and most users will not need this module.  It is useful for code that works
+with TaskMaps directly, such as importers and debugging tools.
+
+Every [`Value`] round-trips: `V::from_value(&v.to_value())` is `Some(v)`.  Likewise, every
+well-formed [`Key`] round-trips: `Key::parse(&k.to_string())` is `k`.
+
+```
+use taskchampion::schema::{Key, Prop, Value};
+use taskchampion::chrono::{DateTime, TimeZone, Utc};
+# use std::convert::TryInto;
+
+let key = Key::parse("tag_home");
+assert_eq!(key, Key::Tag("home".try_into().unwrap()));
+assert_eq!(key.to_string(), "tag_home");
+
+assert_eq!(Key::parse("due"), Key::Prop(Prop::Due));
+let due = Utc.timestamp(1600000000, 0);
+assert_eq!(due.to_value(), "1600000000");
+assert_eq!(DateTime::<Utc>::from_value("1600000000"), Some(due));
+```
+
+*/
+
+use crate::storage::TaskMap;
+use crate::task::{Status, Tag, Timestamp};
+use chrono::{Duration, TimeZone, Utc};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// A property defined by the task data model, with a fixed key.  This does not include the
+/// properties that carry data in their keys, such as `tag_<tag>`; see [`Key`].
+///
+/// The key for each property is its kebab-case name, available from `as_ref`.
+#[derive(
+    strum_macros::AsRefStr,
+    strum_macros::EnumString,
+    strum_macros::EnumIter,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Prop {
+    /// The one-line summary of the task, a string
+    Description,
+    /// The time of the last modification of the task, a [`Timestamp`]
+    Modified,
+    /// The time at which the task was started, a [`Timestamp`]; absent if the task is not active
+    Start,
+    /// The task's [`Status`]
+    Status,
+    /// The task's priority, a string
+    Priority,
+    /// The time before which the task is hidden, a [`Timestamp`]
+    Wait,
+    /// The time by which the task should be completed, a [`Timestamp`]
+    Due,
+    /// The time before which work on the task should not begin, a [`Timestamp`]
+    Scheduled,
+    /// The time after which the task is no longer relevant, a [`Timestamp`]
+    Until,
+    /// The interval between instances of a recurring task, a [`Duration`]
+    Recur,
+    /// The recurring task of which this task is an instance, a [`Uuid`]
+    Parent,
+    /// The task's project, a string
+    Project,
+    /// The time at which the task was completed or deleted, a [`Timestamp`]
+    End,
+    /// The time at which the task was created, a [`Timestamp`]
+    Entry,
+}
+
+/// A key in a [`TaskMap`](crate::storage::TaskMap), as interpreted by the task data model.
+///
+/// The `Display` implementation gives the string form of the key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// A property with a fixed key
+    Prop(Prop),
+    /// `tag_<tag>`: the task has the given tag.  The value is empty.
+    Tag(Tag),
+    /// `annotation_<timestamp>`: an annotation created at the given time.  The value is the
+    /// annotation's description.
+    Annotation(Timestamp),
+    /// `dep_<uuid>`: the task depends on the given task.  The value is empty.
+    Dependency(Uuid),
+    /// `link_<url>`: the task links to the given URL.  The value is the link's label, or empty.
+    Link(String),
+    /// A key reserved by the data model, such as `tag_123`, that is not well-formed.  These keys
+    /// are ignored, and are not available as UDAs.
+    Malformed(String),
+    /// Any other key is a user-defined attribute (UDA).
+    Uda(String),
+}
+
+impl Key {
+    /// Interpret a key from a TaskMap.  This never fails: keys that are not otherwise defined are
+    /// UDAs.
+    pub fn parse(key: &str) -> Key {
+        if let Ok(prop) = Prop::from_str(key) {
+            return Key::Prop(prop);
+        }
+        let malformed = || Key::Malformed(key.to_owned());
+        if let Some(tag) = key.strip_prefix("tag_") {
+            return Tag::from_str(tag)
+                .map(Key::Tag)
+                .unwrap_or_else(|_| malformed());
+        }
+        if let Some(ts) = key.strip_prefix("annotation_") {
+            return Timestamp::from_value(ts)
+                .map(Key::Annotation)
+                .unwrap_or_else(malformed);
+        }
+        if let Some(uuid) = key.strip_prefix("dep_") {
+            return Uuid::from_value(uuid)
+                .map(Key::Dependency)
+                .unwrap_or_else(malformed);
+        }
+        if let Some(url) = key.strip_prefix("link_") {
+            if url.is_empty() {
+                return malformed();
+            }
+            return Key::Link(url.to_owned());
+        }
+        Key::Uda(key.to_owned())
+    }
+
+    /// True if this key is defined by the data model, including malformed keys that are reserved
+    /// by the data model.  All other keys are UDAs.
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Key::Uda(_))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Prop(prop) => f.write_str(prop.as_ref()),
+            Key::Tag(tag) => write!(f, "tag_{}", tag),
+            Key::Annotation(ts) => write!(f, "annotation_{}", ts.to_value()),
+            Key::Dependency(uuid) => write!(f, "dep_{}", uuid),
+            Key::Link(url) => write!(f, "link_{}", url),
+            Key::Malformed(key) | Key::Uda(key) => f.write_str(key),
+        }
+    }
+}
+
+impl From<Prop> for Key {
+    fn from(prop: Prop) -> Key {
+        Key::Prop(prop)
+    }
+}
+
+/// A type that can be stored as a value in a [`TaskMap`](crate::storage::TaskMap).
+pub trait Value: Sized {
+    /// Parse a value from a TaskMap, returning None if it is not valid.
+    fn from_value(value: &str) -> Option<Self>;
+
+    /// Format this value for storage in a TaskMap.
+    fn to_value(&self) -> String;
+}
+
+impl Value for String {
+    fn from_value(value: &str) -> Option<Self> {
+        Some(value.to_owned())
+    }
+
+    fn to_value(&self) -> String {
+        self.clone()
+    }
+}
+
+/// Get the value of a property from a TaskMap, or None if it is not set or is not valid.
+pub fn get_value<V: Value>(taskmap: &TaskMap, property: Prop) -> Option<V> {
+    taskmap
+        .get(property.as_ref())
+        .and_then(|v| V::from_value(v))
+}
+
+/// Timestamps are stored as an integer number of seconds since the UNIX epoch.  Values outside
+/// the range of a Timestamp are not valid.
+impl Value for Timestamp {
+    fn from_value(value: &str) -> Option<Self> {
+        value
+            .parse()
+            .ok()
+            .and_then(|ts| Utc.timestamp_opt(ts, 0).single())
+    }
+
+    fn to_value(&self) -> String {
+        self.timestamp().to_string()
+    }
+}
+
+/// Durations are stored as an integer number of seconds.  Values outside the range of a
+/// Duration are not valid.
+impl Value for Duration {
+    fn from_value(value: &str) -> Option<Self> {
+        value
+            .parse::<i64>()
+            .ok()
+            .and_then(|secs| secs.checked_mul(1000))
+            .map(Duration::milliseconds)
+    }
+
+    fn to_value(&self) -> String {
+        self.num_seconds().to_string()
+    }
+}
+
+impl Value for Uuid {
+    fn from_value(value: &str) -> Option<Self> {
+        Uuid::parse_str(value).ok()
+    }
+
+    fn to_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Statuses other than pending, completed, and deleted are parsed as [`Status::Unknown`].
+impl Value for Status {
+    fn from_value(value: &str) -> Option<Self> {
+        Some(Status::from_taskmap(value))
+    }
+
+    fn to_value(&self) -> String {
+        self.to_taskmap().to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use std::convert::TryInto;
+    use strum::IntoEnumIterator;
+
+    #[rstest]
+    #[case::prop("due", Key::Prop(Prop::Due))]
+    #[case::tag("tag_home", Key::Tag("home".try_into().unwrap()))]
+    #[case::synthetic_tag("tag_PENDING", Key::Tag("PENDING".try_into().unwrap()))]
+    #[case::annotation("annotation_1635301873", Key::Annotation(Utc.timestamp(1635301873, 0)))]
+    #[case::dependency(
+        "dep_d394be59-60e6-499e-b7e7-ca0142648409",
+        Key::Dependency(Uuid::parse_str("d394be59-60e6-499e-b7e7-ca0142648409").unwrap())
+    )]
+    #[case::link("link_https://example.com", Key::Link("https://example.com".into()))]
+    #[case::uda("devsync.github.issue-id", Key::Uda("devsync.github.issue-id".into()))]
+    #[case::legacy_uda("estimate", Key::Uda("estimate".into()))]
+    fn key_round_trip(#[case] s: &'static str, #[case] key: Key) {
+        assert_eq!(Key::parse(s), key);
+        assert_eq!(key.to_string(), s);
+    }
+
+    #[rstest]
+    #[case::empty_tag("tag_")]
+    #[case::invalid_tag("tag_123")]
+    #[case::empty_annotation("annotation_")]
+    #[case::invalid_annotation("annotation_abcde")]
+    #[case::out_of_range_annotation("annotation_99999999999999999")]
+    #[case::invalid_dependency("dep_xyz")]
+    #[case::empty_link("link_")]
+    fn key_malformed(#[case] s: &'static str) {
+        let key = Key::parse(s);
+        assert_eq!(key, Key::Malformed(s.into()));
+        assert!(key.is_known());
+        assert_eq!(key.to_string(), s);
+    }
+
+    #[test]
+    fn prop_round_trip() {
+        for prop in Prop::iter() {
+            assert_eq!(Key::parse(prop.as_ref()), Key::Prop(prop));
+            assert_eq!(Key::from(prop).to_string(), prop.as_ref());
+        }
+    }
+
+    fn value_round_trip<V: Value + PartialEq + fmt::Debug>(v: V) {
+        assert_eq!(V::from_value(&v.to_value()), Some(v));
+    }
+
+    #[test]
+    fn values_round_trip() {
+        value_round_trip(String::from("a string"));
+        value_round_trip(String::new());
+        value_round_trip(Utc.timestamp(1635301873, 0));
+        value_round_trip(Utc.timestamp(-10, 0));
+        value_round_trip(Duration::days(7));
+        value_round_trip(Uuid::new_v4());
+        value_round_trip(Status::Pending);
+        value_round_trip(Status::Completed);
+        value_round_trip(Status::Deleted);
+        value_round_trip(Status::Unknown("wishful".into()));
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert_eq!(Timestamp::from_value("yesterday"), None);
+        assert_eq!(Timestamp::from_value("99999999999999999"), None);
+        assert_eq!(Duration::from_value("P7D"), None);
+        assert_eq!(Duration::from_value("99999999999999999"), None);
+        assert_eq!(Uuid::from_value("not-a-uuid"), None);
+    }
+}
@@ -1,16 +1,25 @@
 use super::types::Server;
-use super::{LocalServer, RemoteServer};
+#[cfg(feature = "storage-sqlite")]
+use super::LocalServer;
+#[cfg(feature = "server-sync")]
+use super::RemoteServer;
+use crate::errors::Result;
+#[cfg(feature = "storage-sqlite")]
 use std::path::PathBuf;
+#[cfg(feature = "server-sync")]
 use uuid::Uuid;
 
-/// The configuration for a replica's access to a sync server.
+/// The configuration for a replica's access to a sync server.  The available variants depend on
+/// the crate features that are enabled.
 pub enum ServerConfig {
     /// A local task database, for situations with a single replica.
+    #[cfg(feature = "storage-sqlite")]
     Local {
         /// Path containing the server's DB
         server_dir: PathBuf,
     },
     /// A remote taskchampion-sync-server instance
+    #[cfg(feature = "server-sync")]
     Remote {
         /// Sync server "origin"; a URL with schema and hostname but no path or trailing `/`
         origin: String,
@@ -26,9 +35,15 @@ pub enum ServerConfig {
 
 impl ServerConfig {
     /// Get a server based on this configuration
-    pub fn into_server(self) -> anyhow::Result<Box<dyn Server>> {
+    #[cfg_attr(
+        not(any(feature = "storage-sqlite", feature = "server-sync")),
+        allow(unreachable_code)
+    )]
+    pub fn into_server(self) -> Result<Box<dyn Server>> {
         Ok(match self {
+            #[cfg(feature = "storage-sqlite")]
             ServerConfig::Local { server_dir } => Box::new(LocalServer::new(server_dir)?),
+            #[cfg(feature = "server-sync")]
             ServerConfig::Remote {
                 origin,
                 client_key,
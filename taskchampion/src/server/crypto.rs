@@ -1,5 +1,6 @@
 /// This module implements the encryption specified in the sync-protocol
 /// document.
+use crate::errors::Error;
 use ring::{aead, digest, pbkdf2, rand, rand::SecureRandom};
 use std::io::Read;
 use uuid::Uuid;
@@ -93,7 +94,7 @@ impl Cryptor {
         let plaintext = self
             .key
             .open_in_place(nonce, aad, payload.as_mut())
-            .map_err(|_| anyhow::anyhow!("error while creating AEAD key"))?;
+            .map_err(|_| Error::Decryption)?;
 
         Ok(Unsealed {
             version_id,
@@ -277,7 +278,11 @@ mod test {
 
         let secret = Secret(b"DIFFERENT_SECRET".to_vec());
         let cryptor = Cryptor::new(client_key, &secret).unwrap();
-        assert!(cryptor.unseal(sealed).is_err());
+        let err = cryptor.unseal(sealed).map(|_| ()).unwrap_err();
+        assert!(matches!(
+            err.downcast::<Error>().unwrap(),
+            Error::Decryption
+        ));
     }
 
     #[test]
@@ -12,15 +12,20 @@ However, users who wish to implement their own server interfaces can implement t
 pub(crate) mod test;
 
 mod config;
+#[cfg(feature = "server-sync")]
 mod crypto;
+#[cfg(feature = "storage-sqlite")]
 mod local;
 mod op;
+#[cfg(feature = "server-sync")]
 mod remote;
 mod types;
 
 pub use config::ServerConfig;
+#[cfg(feature = "storage-sqlite")]
 pub use local::LocalServer;
+#[cfg(feature = "server-sync")]
 pub use remote::RemoteServer;
 pub use types::*;
 
-pub(crate) use op::SyncOp;
+pub use op::SyncOp;
@@ -31,6 +31,13 @@ pub enum SyncOp {
 use SyncOp::*;
 
 impl SyncOp {
+    /// Get the UUID of the task this operation applies to.
+    pub(crate) fn uuid(&self) -> Uuid {
+        match self {
+            Create { uuid } | Delete { uuid } | Update { uuid, .. } => *uuid,
+        }
+    }
+
     // Transform takes two operations A and B that happened concurrently and produces two
     // operations A' and B' such that `apply(apply(S, A), B') = apply(apply(S, B), A')`. This
     // function is used to serialize operations in a process similar to a Git "rebase".
@@ -1,3 +1,4 @@
+use crate::errors::Result;
 use crate::server::{
     AddVersionResult, GetVersionResult, HistorySegment, Server, Snapshot, SnapshotUrgency,
     VersionId,
@@ -31,7 +32,7 @@ impl RemoteServer {
         origin: String,
         client_key: Uuid,
         encryption_secret: Vec<u8>,
-    ) -> anyhow::Result<RemoteServer> {
+    ) -> Result<RemoteServer> {
         Ok(RemoteServer {
             origin,
             client_key,
@@ -45,7 +46,7 @@ impl RemoteServer {
 }
 
 /// Read a UUID-bearing header or fail trying
-fn get_uuid_header(resp: &ureq::Response, name: &str) -> anyhow::Result<Uuid> {
+fn get_uuid_header(resp: &ureq::Response, name: &str) -> Result<Uuid> {
     let value = resp
         .header(name)
         .ok_or_else(|| anyhow::anyhow!("Response does not have {} header", name))?;
@@ -71,7 +72,7 @@ impl Server for RemoteServer {
         &mut self,
         parent_version_id: VersionId,
         history_segment: HistorySegment,
-    ) -> anyhow::Result<(AddVersionResult, SnapshotUrgency)> {
+    ) -> Result<(AddVersionResult, SnapshotUrgency)> {
         let url = format!(
             "{}/v1/client/add-version/{}",
             self.origin, parent_version_id
@@ -81,6 +82,7 @@ impl Server for RemoteServer {
             payload: history_segment,
         };
         let sealed = self.cryptor.seal(unsealed)?;
+        enter_span!("http.add_version", bytes = sealed.as_ref().len());
         match self
             .agent
             .post(&url)
@@ -106,14 +108,12 @@ impl Server for RemoteServer {
         }
     }
 
-    fn get_child_version(
-        &mut self,
-        parent_version_id: VersionId,
-    ) -> anyhow::Result<GetVersionResult> {
+    fn get_child_version(&mut self, parent_version_id: VersionId) -> Result<GetVersionResult> {
         let url = format!(
             "{}/v1/client/get-child-version/{}",
             self.origin, parent_version_id
         );
+        enter_span!("http.get_child_version", bytes = tracing::field::Empty);
         match self
             .agent
             .get(&url)
@@ -126,6 +126,7 @@ impl Server for RemoteServer {
                 let sealed =
                     Sealed::from_resp(resp, parent_version_id, HISTORY_SEGMENT_CONTENT_TYPE)?;
                 let history_segment = self.cryptor.unseal(sealed)?.payload;
+                record_span!("bytes", history_segment.len());
                 Ok(GetVersionResult::Version {
                     version_id,
                     parent_version_id,
@@ -139,13 +140,14 @@ impl Server for RemoteServer {
         }
     }
 
-    fn add_snapshot(&mut self, version_id: VersionId, snapshot: Snapshot) -> anyhow::Result<()> {
+    fn add_snapshot(&mut self, version_id: VersionId, snapshot: Snapshot) -> Result<()> {
         let url = format!("{}/v1/client/add-snapshot/{}", self.origin, version_id);
         let unsealed = Unsealed {
             version_id,
             payload: snapshot,
         };
         let sealed = self.cryptor.seal(unsealed)?;
+        enter_span!("http.add_snapshot", bytes = sealed.as_ref().len());
         Ok(self
             .agent
             .post(&url)
@@ -155,8 +157,9 @@ impl Server for RemoteServer {
             .map(|_| ())?)
     }
 
-    fn get_snapshot(&mut self) -> anyhow::Result<Option<(VersionId, Snapshot)>> {
+    fn get_snapshot(&mut self) -> Result<Option<(VersionId, Snapshot)>> {
         let url = format!("{}/v1/client/snapshot", self.origin);
+        enter_span!("http.get_snapshot", bytes = tracing::field::Empty);
         match self
             .agent
             .get(&url)
@@ -167,6 +170,7 @@ impl Server for RemoteServer {
                 let version_id = get_uuid_header(&resp, "X-Version-Id")?;
                 let sealed = Sealed::from_resp(resp, version_id, SNAPSHOT_CONTENT_TYPE)?;
                 let snapshot = self.cryptor.unseal(sealed)?.payload;
+                record_span!("bytes", snapshot.len());
                 Ok(Some((version_id, snapshot)))
             }
             Err(ureq::Error::Status(status, _)) if status == 404 => Ok(None),
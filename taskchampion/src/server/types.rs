@@ -1,3 +1,4 @@
+use crate::errors::Result;
 use uuid::Uuid;
 
 /// Versions are referred to with sha2 hashes.
@@ -55,16 +56,13 @@ pub trait Server {
         &mut self,
         parent_version_id: VersionId,
         history_segment: HistorySegment,
-    ) -> anyhow::Result<(AddVersionResult, SnapshotUrgency)>;
+    ) -> Result<(AddVersionResult, SnapshotUrgency)>;
 
     /// Get the version with the given parent VersionId
-    fn get_child_version(
-        &mut self,
-        parent_version_id: VersionId,
-    ) -> anyhow::Result<GetVersionResult>;
+    fn get_child_version(&mut self, parent_version_id: VersionId) -> Result<GetVersionResult>;
 
     /// Add a snapshot on the server
-    fn add_snapshot(&mut self, version_id: VersionId, snapshot: Snapshot) -> anyhow::Result<()>;
+    fn add_snapshot(&mut self, version_id: VersionId, snapshot: Snapshot) -> Result<()>;
 
-    fn get_snapshot(&mut self) -> anyhow::Result<Option<(VersionId, Snapshot)>>;
+    fn get_snapshot(&mut self) -> Result<Option<(VersionId, Snapshot)>>;
 }
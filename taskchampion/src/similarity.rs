@@ -0,0 +1,49 @@
+//! Comparison of task descriptions, used to detect tasks that may have been entered twice.
+
+use std::collections::HashSet;
+
+/// Split a description into normalized tokens: lowercase words of letters and digits, ignoring
+/// punctuation, repetition, and word order.
+pub(crate) fn tokens(description: &str) -> HashSet<String> {
+    description
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// The similarity of two sets of tokens, as the number of tokens in both divided by the number of
+/// tokens in either (the Jaccard index), between 0.0 and 1.0.  Empty sets are not similar to
+/// anything.
+pub(crate) fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[test]
+    fn normalized_tokens() {
+        let mut tokens: Vec<_> = tokens("Buy milk, MILK and eggs!").drain().collect();
+        tokens.sort();
+        assert_eq!(tokens, vec!["and", "buy", "eggs", "milk"]);
+    }
+
+    #[rstest]
+    #[case::identical("buy milk", "buy milk", 1.0)]
+    #[case::normalized("Buy milk!", "milk, buy", 1.0)]
+    #[case::extra_word("buy milk", "buy milk today", 2.0 / 3.0)]
+    #[case::different("buy milk", "call mom", 0.0)]
+    #[case::empty("", "", 0.0)]
+    #[case::one_empty("buy milk", "...", 0.0)]
+    fn similarity_cases(#[case] a: &str, #[case] b: &str, #[case] expected: f64) {
+        assert_eq!(similarity(&tokens(a), &tokens(b)), expected);
+    }
+}
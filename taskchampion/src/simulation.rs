@@ -0,0 +1,202 @@
+/*!
+
+This module simulates several replicas synchronizing through a single server, applying random
+interleavings of modifications and syncs, and checks that the replicas converge to identical task
+data.  It is used to test TaskChampion itself, and is available with the `test-support` feature so
+that authors of [`Server`] implementations can check those implementations, too.
+
+```
+use taskchampion::simulation::Simulation;
+use taskchampion::ServerConfig;
+# fn main() -> anyhow::Result<()> {
+# let tmp_dir = tempfile::TempDir::new()?;
+let server = ServerConfig::Local {
+    server_dir: tmp_dir.path().into(),
+}
+.into_server()?;
+let mut sim = Simulation::new(server, 3, 12345);
+sim.run(100)?;
+sim.sync_all()?;
+sim.check_converged()?;
+# Ok(())
+# }
+```
+
+*/
+
+use crate::server::Server;
+use crate::storage::{InMemoryStorage, TaskMap};
+use crate::{Replica, Status, Tag};
+use anyhow::Context;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use uuid::Uuid;
+
+/// A simulation of several replicas sharing a server.
+///
+/// All randomness is derived from the seed given to [`Simulation::new`], so a failing simulation
+/// can be reproduced by running it again with the same seed.
+pub struct Simulation {
+    server: Box<dyn Server>,
+    replicas: Vec<Replica>,
+    rng: Rng,
+}
+
+impl Simulation {
+    /// Create a new simulation of `num_replicas` replicas, each with in-memory storage, that
+    /// sync to the given server.  The server should be empty.
+    pub fn new(server: Box<dyn Server>, num_replicas: usize, seed: u64) -> Simulation {
+        Simulation {
+            server,
+            replicas: (0..num_replicas)
+                .map(|_| Replica::new(Box::new(InMemoryStorage::new())))
+                .collect(),
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Get the replica with the given index.
+    pub fn replica(&mut self, index: usize) -> &mut Replica {
+        &mut self.replicas[index]
+    }
+
+    /// Perform `steps` random steps.
+    pub fn run(&mut self, steps: usize) -> anyhow::Result<()> {
+        for _ in 0..steps {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Perform a single random step: on a randomly selected replica, create a task, modify a
+    /// task, or sync.
+    pub fn step(&mut self) -> anyhow::Result<()> {
+        let index = self.rng.below(self.replicas.len());
+        let replica = &mut self.replicas[index];
+        match self.rng.below(6) {
+            0 => {
+                let description = format!("task {}", self.rng.below(1000));
+                replica.new_task(Status::Pending, description)?;
+            }
+            1..=4 => {
+                let uuids = replica.all_task_uuids()?;
+                if uuids.is_empty() {
+                    return Ok(());
+                }
+                let uuid = uuids[self.rng.below(uuids.len())];
+                let task = replica.get_task(uuid)?.unwrap();
+                let mut task = task.into_mut(replica);
+                let tag: Tag = ["home", "work", "next"][self.rng.below(3)].try_into()?;
+                match self.rng.below(6) {
+                    0 => task.set_description(format!("task {}", self.rng.below(1000)))?,
+                    1 => task.set_status(Status::Completed)?,
+                    2 => task.set_status(Status::Pending)?,
+                    3 => task.set_project(Some(format!("proj{}", self.rng.below(3))))?,
+                    4 => task.add_tag(&tag)?,
+                    _ => task.remove_tag(&tag)?,
+                }
+            }
+            _ => replica
+                .sync(&mut self.server, false)
+                .with_context(|| format!("Syncing replica {}", index))?,
+        }
+        Ok(())
+    }
+
+    /// Sync every replica twice, in order, so that all changes reach all replicas.
+    pub fn sync_all(&mut self) -> anyhow::Result<()> {
+        for _ in 0..2 {
+            for (index, replica) in self.replicas.iter_mut().enumerate() {
+                replica
+                    .sync(&mut self.server, false)
+                    .with_context(|| format!("Syncing replica {}", index))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that all replicas contain identical tasks, returning an error describing the first
+    /// difference if not.  This is typically called after [`Simulation::sync_all`].
+    pub fn check_converged(&mut self) -> anyhow::Result<()> {
+        let mut expected: Option<HashMap<Uuid, TaskMap>> = None;
+        for (index, replica) in self.replicas.iter_mut().enumerate() {
+            let tasks: HashMap<Uuid, TaskMap> = replica
+                .all_tasks()?
+                .drain()
+                .map(|(uuid, task)| (uuid, task.get_taskmap().clone()))
+                .collect();
+            match expected {
+                None => expected = Some(tasks),
+                Some(ref expected) => {
+                    if let Some((uuid, _)) = expected.iter().find(|(u, t)| tasks.get(u) != Some(t))
+                    {
+                        anyhow::bail!("Replicas 0 and {} differ on task {}", index, uuid);
+                    }
+                    if let Some(uuid) = tasks.keys().find(|u| !expected.contains_key(u)) {
+                        anyhow::bail!("Replicas 0 and {} differ on task {}", index, uuid);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run a simulation of `num_replicas` replicas for `steps` steps, then sync all replicas and
+/// check that they have converged.
+pub fn simulate(
+    server: Box<dyn Server>,
+    num_replicas: usize,
+    steps: usize,
+    seed: u64,
+) -> anyhow::Result<()> {
+    let mut sim = Simulation::new(server, num_replicas, seed);
+    sim.run(steps)
+        .and_then(|_| sim.sync_all())
+        .and_then(|_| sim.check_converged())
+        .with_context(|| format!("Simulation with seed {}", seed))
+}
+
+/// A small xorshift pseudo-random number generator, so that simulations are reproducible
+/// without depending on a random-number crate.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // the state must never be zero
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    /// Get a random number in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::server::test::TestServer;
+
+    #[test]
+    fn simulate_converges() -> anyhow::Result<()> {
+        for seed in 0..20 {
+            simulate(Box::new(TestServer::new()), 3, 200, seed)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn check_converged_detects_difference() -> anyhow::Result<()> {
+        let mut sim = Simulation::new(Box::new(TestServer::new()), 2, 1);
+        sim.replica(0)
+            .new_task(Status::Pending, "unsynced".into())?;
+        assert!(sim.check_converged().is_err());
+        sim.sync_all()?;
+        sim.check_converged()?;
+        Ok(())
+    }
+}
@@ -1,9 +1,14 @@
-use super::{InMemoryStorage, SqliteStorage, Storage};
+#[cfg(feature = "storage-sqlite")]
+use super::SqliteStorage;
+use super::{InMemoryStorage, Storage};
+use crate::errors::Result;
+#[cfg(feature = "storage-sqlite")]
 use std::path::PathBuf;
 
 /// The configuration required for a replica's storage.
 pub enum StorageConfig {
     /// Store the data on disk.  This is the common choice.
+    #[cfg(feature = "storage-sqlite")]
     OnDisk {
         /// Path containing the task DB.
         taskdb_dir: PathBuf,
@@ -13,8 +18,9 @@ pub enum StorageConfig {
 }
 
 impl StorageConfig {
-    pub fn into_storage(self) -> anyhow::Result<Box<dyn Storage>> {
+    pub fn into_storage(self) -> Result<Box<dyn Storage>> {
         Ok(match self {
+            #[cfg(feature = "storage-sqlite")]
             StorageConfig::OnDisk { taskdb_dir } => Box::new(SqliteStorage::new(taskdb_dir)?),
             StorageConfig::InMemory => Box::new(InMemoryStorage::new()),
         })
@@ -1,16 +1,23 @@
 #![allow(clippy::new_without_default)]
 
+use crate::errors::{Error, Result};
 use crate::storage::{ReplicaOp, Storage, StorageTxn, TaskMap, VersionId, DEFAULT_BASE_VERSION};
+use chrono::{DateTime, Utc};
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 #[derive(PartialEq, Debug, Clone)]
 struct Data {
     tasks: HashMap<Uuid, TaskMap>,
     base_version: VersionId,
+    last_sync: Option<DateTime<Utc>>,
     operations: Vec<ReplicaOp>,
     working_set: Vec<Option<Uuid>>,
+    // tasks changed since the last call to clear_changed_tasks, or None if not tracked
+    changed_tasks: Option<HashSet<Uuid>>,
+    // revision of each task, incremented on every change
+    revisions: HashMap<Uuid, u64>,
 }
 
 struct Txn<'t> {
@@ -37,35 +44,49 @@ impl<'t> Txn<'t> {
             &self.storage.data
         }
     }
+
+    fn record_change(&mut self, uuid: Uuid) {
+        let data = self.mut_data_ref();
+        *data.revisions.entry(uuid).or_insert(0) += 1;
+        if let Some(ref mut changed_tasks) = data.changed_tasks {
+            changed_tasks.insert(uuid);
+        }
+    }
 }
 
 impl<'t> StorageTxn for Txn<'t> {
-    fn get_task(&mut self, uuid: Uuid) -> anyhow::Result<Option<TaskMap>> {
+    fn get_task(&mut self, uuid: Uuid) -> Result<Option<TaskMap>> {
         match self.data_ref().tasks.get(&uuid) {
             None => Ok(None),
             Some(t) => Ok(Some(t.clone())),
         }
     }
 
-    fn create_task(&mut self, uuid: Uuid) -> anyhow::Result<bool> {
+    fn create_task(&mut self, uuid: Uuid) -> Result<bool> {
         if let ent @ Entry::Vacant(_) = self.mut_data_ref().tasks.entry(uuid) {
             ent.or_insert_with(TaskMap::new);
+            self.record_change(uuid);
             Ok(true)
         } else {
             Ok(false)
         }
     }
 
-    fn set_task(&mut self, uuid: Uuid, task: TaskMap) -> anyhow::Result<()> {
+    fn set_task(&mut self, uuid: Uuid, task: TaskMap) -> Result<()> {
         self.mut_data_ref().tasks.insert(uuid, task);
+        self.record_change(uuid);
         Ok(())
     }
 
-    fn delete_task(&mut self, uuid: Uuid) -> anyhow::Result<bool> {
-        Ok(self.mut_data_ref().tasks.remove(&uuid).is_some())
+    fn delete_task(&mut self, uuid: Uuid) -> Result<bool> {
+        let deleted = self.mut_data_ref().tasks.remove(&uuid).is_some();
+        if deleted {
+            self.record_change(uuid);
+        }
+        Ok(deleted)
     }
 
-    fn all_tasks<'a>(&mut self) -> anyhow::Result<Vec<(Uuid, TaskMap)>> {
+    fn all_tasks<'a>(&mut self) -> Result<Vec<(Uuid, TaskMap)>> {
         Ok(self
             .data_ref()
             .tasks
@@ -74,62 +95,95 @@ impl<'t> StorageTxn for Txn<'t> {
             .collect())
     }
 
-    fn all_task_uuids<'a>(&mut self) -> anyhow::Result<Vec<Uuid>> {
+    fn all_task_uuids<'a>(&mut self) -> Result<Vec<Uuid>> {
         Ok(self.data_ref().tasks.keys().copied().collect())
     }
 
-    fn base_version(&mut self) -> anyhow::Result<VersionId> {
+    fn base_version(&mut self) -> Result<VersionId> {
         Ok(self.data_ref().base_version)
     }
 
-    fn set_base_version(&mut self, version: VersionId) -> anyhow::Result<()> {
+    fn set_base_version(&mut self, version: VersionId) -> Result<()> {
         self.mut_data_ref().base_version = version;
         Ok(())
     }
 
-    fn operations(&mut self) -> anyhow::Result<Vec<ReplicaOp>> {
+    fn last_sync(&mut self) -> Result<Option<DateTime<Utc>>> {
+        Ok(self.data_ref().last_sync)
+    }
+
+    fn set_last_sync(&mut self, time: DateTime<Utc>) -> Result<()> {
+        self.mut_data_ref().last_sync = Some(time);
+        Ok(())
+    }
+
+    fn operations(&mut self) -> Result<Vec<ReplicaOp>> {
         Ok(self.data_ref().operations.clone())
     }
 
-    fn num_operations(&mut self) -> anyhow::Result<usize> {
+    fn num_operations(&mut self) -> Result<usize> {
         Ok(self.data_ref().operations.len())
     }
 
-    fn add_operation(&mut self, op: ReplicaOp) -> anyhow::Result<()> {
+    fn add_operation(&mut self, op: ReplicaOp) -> Result<()> {
         self.mut_data_ref().operations.push(op);
         Ok(())
     }
 
-    fn set_operations(&mut self, ops: Vec<ReplicaOp>) -> anyhow::Result<()> {
+    fn set_operations(&mut self, ops: Vec<ReplicaOp>) -> Result<()> {
         self.mut_data_ref().operations = ops;
         Ok(())
     }
 
-    fn get_working_set(&mut self) -> anyhow::Result<Vec<Option<Uuid>>> {
+    fn get_working_set(&mut self) -> Result<Vec<Option<Uuid>>> {
         Ok(self.data_ref().working_set.clone())
     }
 
-    fn add_to_working_set(&mut self, uuid: Uuid) -> anyhow::Result<usize> {
+    fn add_to_working_set(&mut self, uuid: Uuid) -> Result<usize> {
         let working_set = &mut self.mut_data_ref().working_set;
         working_set.push(Some(uuid));
         Ok(working_set.len())
     }
 
-    fn set_working_set_item(&mut self, index: usize, uuid: Option<Uuid>) -> anyhow::Result<()> {
+    fn set_working_set_item(&mut self, index: usize, uuid: Option<Uuid>) -> Result<()> {
         let working_set = &mut self.mut_data_ref().working_set;
         if index >= working_set.len() {
-            anyhow::bail!("Index {} is not in the working set", index);
+            return Err(Error::Database(format!(
+                "Index {} is not in the working set",
+                index
+            )));
         }
         working_set[index] = uuid;
         Ok(())
     }
 
-    fn clear_working_set(&mut self) -> anyhow::Result<()> {
+    fn clear_working_set(&mut self) -> Result<()> {
         self.mut_data_ref().working_set = vec![None];
         Ok(())
     }
 
-    fn commit(&mut self) -> anyhow::Result<()> {
+    fn changed_tasks(&mut self) -> Result<Option<Vec<Uuid>>> {
+        Ok(self
+            .data_ref()
+            .changed_tasks
+            .as_ref()
+            .map(|changed_tasks| changed_tasks.iter().copied().collect()))
+    }
+
+    fn clear_changed_tasks(&mut self) -> Result<()> {
+        self.mut_data_ref().changed_tasks = Some(HashSet::new());
+        Ok(())
+    }
+
+    fn task_revision(&mut self, uuid: Uuid) -> Result<u64> {
+        Ok(self.data_ref().revisions.get(&uuid).copied().unwrap_or(0))
+    }
+
+    fn task_revisions(&mut self) -> Result<HashMap<Uuid, u64>> {
+        Ok(self.data_ref().revisions.clone())
+    }
+
+    fn commit(&mut self) -> Result<()> {
         // copy the new_data back into storage to commit the transaction
         if let Some(data) = self.new_data.take() {
             self.storage.data = data;
@@ -151,15 +205,18 @@ impl InMemoryStorage {
             data: Data {
                 tasks: HashMap::new(),
                 base_version: DEFAULT_BASE_VERSION,
+                last_sync: None,
                 operations: vec![],
                 working_set: vec![None],
+                changed_tasks: None,
+                revisions: HashMap::new(),
             },
         }
     }
 }
 
 impl Storage for InMemoryStorage {
-    fn txn<'a>(&'a mut self) -> anyhow::Result<Box<dyn StorageTxn + 'a>> {
+    fn txn<'a>(&'a mut self) -> Result<Box<dyn StorageTxn + 'a>> {
         Ok(Box::new(Txn {
             storage: self,
             new_data: None,
@@ -175,6 +232,30 @@ mod test {
     // (note: this module is heavily used in tests so most of its functionality is well-tested
     // elsewhere and not tested here)
 
+    #[test]
+    fn task_revisions() -> anyhow::Result<()> {
+        let mut storage = InMemoryStorage::new();
+        let uuid = Uuid::new_v4();
+
+        {
+            let mut txn = storage.txn()?;
+            assert_eq!(txn.task_revision(uuid)?, 0);
+            txn.create_task(uuid)?;
+            txn.set_task(uuid, TaskMap::new())?;
+            txn.commit()?;
+        }
+
+        {
+            let mut txn = storage.txn()?;
+            assert_eq!(txn.task_revision(uuid)?, 2);
+            txn.delete_task(uuid)?;
+            assert_eq!(txn.task_revision(uuid)?, 3);
+            assert_eq!(txn.task_revisions()?.get(&uuid), Some(&3));
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn get_working_set_empty() -> anyhow::Result<()> {
         let mut storage = InMemoryStorage::new();
@@ -5,17 +5,20 @@ It defines a [trait](crate::storage::Storage) for storage implementations, and p
 Typical uses of this crate do not interact directly with this module; [`StorageConfig`](crate::StorageConfig) is sufficient.
 However, users who wish to implement their own storage backends can implement the traits defined here and pass the result to [`Replica`](crate::Replica).
 */
-use anyhow::Result;
+use crate::errors::Result;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use uuid::Uuid;
 
 mod config;
 mod inmemory;
 mod op;
+#[cfg(feature = "storage-sqlite")]
 pub(crate) mod sqlite;
 
 pub use config::StorageConfig;
 pub use inmemory::InMemoryStorage;
+#[cfg(feature = "storage-sqlite")]
 pub use sqlite::SqliteStorage;
 
 pub use op::ReplicaOp;
@@ -78,6 +81,12 @@ pub trait StorageTxn {
     /// Set the current base_version for this storage.
     fn set_base_version(&mut self, version: VersionId) -> Result<()>;
 
+    /// Get the time of the last successful sync, or None if this storage has never been synced.
+    fn last_sync(&mut self) -> Result<Option<DateTime<Utc>>>;
+
+    /// Set the time of the last successful sync.
+    fn set_last_sync(&mut self, time: DateTime<Utc>) -> Result<()>;
+
     /// Get the current set of outstanding operations (operations that have not been sync'd to the
     /// server yet)
     fn operations(&mut self) -> Result<Vec<ReplicaOp>>;
@@ -109,6 +118,37 @@ pub trait StorageTxn {
     /// Note that this is the only way items are removed from the set.
     fn clear_working_set(&mut self) -> Result<()>;
 
+    /// Get the uuids of all tasks that have been created, modified, or deleted since the last
+    /// call to `clear_changed_tasks`, in undefined order.  This allows the working set to be
+    /// rebuilt without reading every task.
+    ///
+    /// Returns None if changes are not tracked, or have not been tracked since the last call to
+    /// `clear_changed_tasks`, in which case any task may have changed.  The default
+    /// implementation always returns None.
+    fn changed_tasks(&mut self) -> Result<Option<Vec<Uuid>>> {
+        Ok(None)
+    }
+
+    /// Forget all changed tasks, and track changes to tasks from this point onward.
+    fn clear_changed_tasks(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Get the revision of a task: a number which increases each time the task is created,
+    /// modified, or deleted in this storage, whether by local changes, undo, or synchronization.
+    /// Revisions are not synchronized, so they are only meaningful within this storage.
+    ///
+    /// Returns 0 for a task that has never been stored.  The default implementation always
+    /// returns 0, meaning that revisions are not tracked.
+    fn task_revision(&mut self, _uuid: Uuid) -> Result<u64> {
+        Ok(0)
+    }
+
+    /// Get the revisions of all tasks with a nonzero revision, as for `task_revision`.
+    fn task_revisions(&mut self) -> Result<HashMap<Uuid, u64>> {
+        Ok(HashMap::new())
+    }
+
     /// Check whether this storage is entirely empty
     #[allow(clippy::wrong_self_convention)] // mut is required here for storage access
     fn is_empty(&mut self) -> Result<bool> {
@@ -1,8 +1,12 @@
+use crate::errors::{Error, Result};
 use crate::storage::{ReplicaOp, Storage, StorageTxn, TaskMap, VersionId, DEFAULT_BASE_VERSION};
 use anyhow::Context;
+use chrono::{DateTime, Utc};
 use rusqlite::types::{FromSql, ToSql};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 use uuid::Uuid;
 
 #[derive(Debug, thiserror::Error)]
@@ -11,6 +15,12 @@ enum SqliteError {
     TransactionAlreadyCommitted,
 }
 
+impl From<SqliteError> for Error {
+    fn from(err: SqliteError) -> Error {
+        Error::Storage(err.into())
+    }
+}
+
 /// Newtype to allow implementing `FromSql` for foreign `uuid::Uuid`
 pub(crate) struct StoredUuid(pub(crate) Uuid);
 
@@ -70,19 +80,31 @@ impl ToSql for ReplicaOp {
     }
 }
 
+/// How long a transaction waits for a transaction in another SqliteStorage sharing the same
+/// database before failing with SQLITE_BUSY.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// SqliteStorage is an on-disk storage backed by SQLite3.
+///
+/// Several instances may share a database, such as to use it from several threads.  Every
+/// transaction, even one that only reads, takes the database's write lock, so a transaction waits
+/// for any other instance's transaction to finish, for up to five seconds, and then fails with an
+/// [`Error::Storage`] wrapping SQLITE_BUSY.  A sync holds its transaction for all of its requests
+/// to the server, so a sync that takes longer than that causes other instances' operations to
+/// fail.
 pub struct SqliteStorage {
     con: Connection,
 }
 
 impl SqliteStorage {
-    pub fn new<P: AsRef<Path>>(directory: P) -> anyhow::Result<SqliteStorage> {
+    pub fn new<P: AsRef<Path>>(directory: P) -> Result<SqliteStorage> {
         // Ensure parent folder exists
         std::fs::create_dir_all(&directory)?;
 
         // Open (or create) database
         let db_file = directory.as_ref().join("taskchampion.sqlite3");
         let con = Connection::open(db_file)?;
+        con.busy_timeout(BUSY_TIMEOUT)?;
 
         // Initialize database
         let queries = vec![
@@ -90,6 +112,8 @@ impl SqliteStorage {
             "CREATE TABLE IF NOT EXISTS sync_meta (key STRING PRIMARY KEY, value STRING);",
             "CREATE TABLE IF NOT EXISTS tasks (uuid STRING PRIMARY KEY, data STRING);",
             "CREATE TABLE IF NOT EXISTS working_set (id INTEGER PRIMARY KEY, uuid STRING);",
+            "CREATE TABLE IF NOT EXISTS changed_tasks (uuid STRING PRIMARY KEY);",
+            "CREATE TABLE IF NOT EXISTS task_revisions (uuid STRING PRIMARY KEY, revision INTEGER);",
         ];
         for q in queries {
             con.execute(q, []).context("Creating table")?;
@@ -104,13 +128,13 @@ struct Txn<'t> {
 }
 
 impl<'t> Txn<'t> {
-    fn get_txn(&self) -> Result<&rusqlite::Transaction<'t>, SqliteError> {
+    fn get_txn(&self) -> std::result::Result<&rusqlite::Transaction<'t>, SqliteError> {
         self.txn
             .as_ref()
             .ok_or(SqliteError::TransactionAlreadyCommitted)
     }
 
-    fn get_next_working_set_number(&self) -> anyhow::Result<usize> {
+    fn get_next_working_set_number(&self) -> Result<usize> {
         let t = self.get_txn()?;
         let next_id: Option<usize> = t
             .query_row(
@@ -123,17 +147,39 @@ impl<'t> Txn<'t> {
 
         Ok(next_id.unwrap_or(0))
     }
+
+    fn record_change(&self, uuid: Uuid) -> Result<()> {
+        let t = self.get_txn()?;
+        t.execute(
+            "INSERT OR IGNORE INTO changed_tasks (uuid) VALUES (?)",
+            [&StoredUuid(uuid)],
+        )
+        .context("Record changed task query")?;
+        t.execute(
+            "INSERT INTO task_revisions (uuid, revision) VALUES (?, 1)
+                ON CONFLICT (uuid) DO UPDATE SET revision = revision + 1",
+            [&StoredUuid(uuid)],
+        )
+        .context("Increment task revision query")?;
+        Ok(())
+    }
 }
 
 impl Storage for SqliteStorage {
-    fn txn<'a>(&'a mut self) -> anyhow::Result<Box<dyn StorageTxn + 'a>> {
-        let txn = self.con.transaction()?;
+    fn txn<'a>(&'a mut self) -> Result<Box<dyn StorageTxn + 'a>> {
+        enter_span!("sqlite.begin");
+        // Begin the transaction immediately, so that when several SqliteStorage instances share a
+        // database, a transaction waits (up to BUSY_TIMEOUT) for the others to finish when it
+        // begins, rather than failing when it first writes.
+        let txn = self
+            .con
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
         Ok(Box::new(Txn { txn: Some(txn) }))
     }
 }
 
 impl<'t> StorageTxn for Txn<'t> {
-    fn get_task(&mut self, uuid: Uuid) -> anyhow::Result<Option<TaskMap>> {
+    fn get_task(&mut self, uuid: Uuid) -> Result<Option<TaskMap>> {
         let t = self.get_txn()?;
         let result: Option<StoredTaskMap> = t
             .query_row(
@@ -147,7 +193,7 @@ impl<'t> StorageTxn for Txn<'t> {
         Ok(result.map(|t| t.0))
     }
 
-    fn create_task(&mut self, uuid: Uuid) -> anyhow::Result<bool> {
+    fn create_task(&mut self, uuid: Uuid) -> Result<bool> {
         let t = self.get_txn()?;
         let count: usize = t.query_row(
             "SELECT count(uuid) FROM tasks WHERE uuid = ?",
@@ -164,28 +210,33 @@ impl<'t> StorageTxn for Txn<'t> {
             params![&StoredUuid(uuid), &StoredTaskMap(data)],
         )
         .context("Create task query")?;
+        self.record_change(uuid)?;
         Ok(true)
     }
 
-    fn set_task(&mut self, uuid: Uuid, task: TaskMap) -> anyhow::Result<()> {
+    fn set_task(&mut self, uuid: Uuid, task: TaskMap) -> Result<()> {
         let t = self.get_txn()?;
         t.execute(
             "INSERT OR REPLACE INTO tasks (uuid, data) VALUES (?, ?)",
             params![&StoredUuid(uuid), &StoredTaskMap(task)],
         )
         .context("Update task query")?;
+        self.record_change(uuid)?;
         Ok(())
     }
 
-    fn delete_task(&mut self, uuid: Uuid) -> anyhow::Result<bool> {
+    fn delete_task(&mut self, uuid: Uuid) -> Result<bool> {
         let t = self.get_txn()?;
         let changed = t
             .execute("DELETE FROM tasks WHERE uuid = ?", [&StoredUuid(uuid)])
             .context("Delete task query")?;
+        if changed > 0 {
+            self.record_change(uuid)?;
+        }
         Ok(changed > 0)
     }
 
-    fn all_tasks(&mut self) -> anyhow::Result<Vec<(Uuid, TaskMap)>> {
+    fn all_tasks(&mut self) -> Result<Vec<(Uuid, TaskMap)>> {
         let t = self.get_txn()?;
 
         let mut q = t.prepare("SELECT uuid, data FROM tasks")?;
@@ -202,7 +253,7 @@ impl<'t> StorageTxn for Txn<'t> {
         Ok(ret)
     }
 
-    fn all_task_uuids(&mut self) -> anyhow::Result<Vec<Uuid>> {
+    fn all_task_uuids(&mut self) -> Result<Vec<Uuid>> {
         let t = self.get_txn()?;
 
         let mut q = t.prepare("SELECT uuid FROM tasks")?;
@@ -218,7 +269,7 @@ impl<'t> StorageTxn for Txn<'t> {
         Ok(ret)
     }
 
-    fn base_version(&mut self) -> anyhow::Result<VersionId> {
+    fn base_version(&mut self) -> Result<VersionId> {
         let t = self.get_txn()?;
 
         let version: Option<StoredUuid> = t
@@ -231,7 +282,7 @@ impl<'t> StorageTxn for Txn<'t> {
         Ok(version.map(|u| u.0).unwrap_or(DEFAULT_BASE_VERSION))
     }
 
-    fn set_base_version(&mut self, version: VersionId) -> anyhow::Result<()> {
+    fn set_base_version(&mut self, version: VersionId) -> Result<()> {
         let t = self.get_txn()?;
         t.execute(
             "INSERT OR REPLACE INTO sync_meta (key, value) VALUES (?, ?)",
@@ -241,7 +292,37 @@ impl<'t> StorageTxn for Txn<'t> {
         Ok(())
     }
 
-    fn operations(&mut self) -> anyhow::Result<Vec<ReplicaOp>> {
+    fn last_sync(&mut self) -> Result<Option<DateTime<Utc>>> {
+        let t = self.get_txn()?;
+
+        let time: Option<String> = t
+            .query_row(
+                "SELECT value FROM sync_meta WHERE key = 'last_sync'",
+                [],
+                |r| r.get("value"),
+            )
+            .optional()?;
+        match time {
+            Some(time) => Ok(Some(
+                DateTime::parse_from_rfc3339(&time)
+                    .context("Parsing last sync time")?
+                    .with_timezone(&Utc),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    fn set_last_sync(&mut self, time: DateTime<Utc>) -> Result<()> {
+        let t = self.get_txn()?;
+        t.execute(
+            "INSERT OR REPLACE INTO sync_meta (key, value) VALUES (?, ?)",
+            params!["last_sync", time.to_rfc3339()],
+        )
+        .context("Set last sync time")?;
+        Ok(())
+    }
+
+    fn operations(&mut self) -> Result<Vec<ReplicaOp>> {
         let t = self.get_txn()?;
 
         let mut q = t.prepare("SELECT data FROM operations ORDER BY id ASC")?;
@@ -257,13 +338,13 @@ impl<'t> StorageTxn for Txn<'t> {
         Ok(ret)
     }
 
-    fn num_operations(&mut self) -> anyhow::Result<usize> {
+    fn num_operations(&mut self) -> Result<usize> {
         let t = self.get_txn()?;
         let count: usize = t.query_row("SELECT count(*) FROM operations", [], |x| x.get(0))?;
         Ok(count)
     }
 
-    fn add_operation(&mut self, op: ReplicaOp) -> anyhow::Result<()> {
+    fn add_operation(&mut self, op: ReplicaOp) -> Result<()> {
         let t = self.get_txn()?;
 
         t.execute("INSERT INTO operations (data) VALUES (?)", params![&op])
@@ -271,7 +352,7 @@ impl<'t> StorageTxn for Txn<'t> {
         Ok(())
     }
 
-    fn set_operations(&mut self, ops: Vec<ReplicaOp>) -> anyhow::Result<()> {
+    fn set_operations(&mut self, ops: Vec<ReplicaOp>) -> Result<()> {
         let t = self.get_txn()?;
         t.execute("DELETE FROM operations", [])
             .context("Clear all existing operations")?;
@@ -284,7 +365,7 @@ impl<'t> StorageTxn for Txn<'t> {
         Ok(())
     }
 
-    fn get_working_set(&mut self) -> anyhow::Result<Vec<Option<Uuid>>> {
+    fn get_working_set(&mut self) -> Result<Vec<Option<Uuid>>> {
         let t = self.get_txn()?;
 
         let mut q = t.prepare("SELECT id, uuid FROM working_set ORDER BY id ASC")?;
@@ -296,7 +377,7 @@ impl<'t> StorageTxn for Txn<'t> {
             })
             .context("Get working set query")?;
 
-        let rows: Vec<Result<(usize, Uuid), _>> = rows.collect();
+        let rows: Vec<std::result::Result<(usize, Uuid), _>> = rows.collect();
         let mut res = Vec::with_capacity(rows.len());
         for _ in 0..self
             .get_next_working_set_number()
@@ -312,7 +393,7 @@ impl<'t> StorageTxn for Txn<'t> {
         Ok(res)
     }
 
-    fn add_to_working_set(&mut self, uuid: Uuid) -> anyhow::Result<usize> {
+    fn add_to_working_set(&mut self, uuid: Uuid) -> Result<usize> {
         let t = self.get_txn()?;
 
         let next_working_id = self.get_next_working_set_number()?;
@@ -326,7 +407,7 @@ impl<'t> StorageTxn for Txn<'t> {
         Ok(next_working_id)
     }
 
-    fn set_working_set_item(&mut self, index: usize, uuid: Option<Uuid>) -> anyhow::Result<()> {
+    fn set_working_set_item(&mut self, index: usize, uuid: Option<Uuid>) -> Result<()> {
         let t = self.get_txn()?;
         match uuid {
             // Add or override item
@@ -341,14 +422,86 @@ impl<'t> StorageTxn for Txn<'t> {
         Ok(())
     }
 
-    fn clear_working_set(&mut self) -> anyhow::Result<()> {
+    fn clear_working_set(&mut self) -> Result<()> {
         let t = self.get_txn()?;
         t.execute("DELETE FROM working_set", [])
             .context("Clear working set query")?;
         Ok(())
     }
 
-    fn commit(&mut self) -> anyhow::Result<()> {
+    fn changed_tasks(&mut self) -> Result<Option<Vec<Uuid>>> {
+        let t = self.get_txn()?;
+
+        // changes are only known to be complete once clear_changed_tasks has been called, as
+        // databases created by older versions did not track them
+        let tracked: Option<String> = t
+            .query_row(
+                "SELECT value FROM sync_meta WHERE key = 'changed_tasks_tracked'",
+                [],
+                |r| r.get("value"),
+            )
+            .optional()?;
+        if tracked.is_none() {
+            return Ok(None);
+        }
+
+        let mut q = t.prepare("SELECT uuid FROM changed_tasks")?;
+        let rows = q.query_map([], |r| {
+            let uuid: StoredUuid = r.get("uuid")?;
+            Ok(uuid.0)
+        })?;
+
+        let mut ret = vec![];
+        for r in rows {
+            ret.push(r?);
+        }
+        Ok(Some(ret))
+    }
+
+    fn clear_changed_tasks(&mut self) -> Result<()> {
+        let t = self.get_txn()?;
+        t.execute("DELETE FROM changed_tasks", [])
+            .context("Clear changed tasks query")?;
+        t.execute(
+            "INSERT OR REPLACE INTO sync_meta (key, value) VALUES (?, ?)",
+            params!["changed_tasks_tracked", "true"],
+        )
+        .context("Track changed tasks")?;
+        Ok(())
+    }
+
+    fn task_revision(&mut self, uuid: Uuid) -> Result<u64> {
+        let t = self.get_txn()?;
+        let revision: Option<i64> = t
+            .query_row(
+                "SELECT revision FROM task_revisions WHERE uuid = ?",
+                [&StoredUuid(uuid)],
+                |r| r.get("revision"),
+            )
+            .optional()?;
+        Ok(revision.unwrap_or(0) as u64)
+    }
+
+    fn task_revisions(&mut self) -> Result<HashMap<Uuid, u64>> {
+        let t = self.get_txn()?;
+
+        let mut q = t.prepare("SELECT uuid, revision FROM task_revisions")?;
+        let rows = q.query_map([], |r| {
+            let uuid: StoredUuid = r.get("uuid")?;
+            let revision: i64 = r.get("revision")?;
+            Ok((uuid.0, revision as u64))
+        })?;
+
+        let mut ret = HashMap::new();
+        for r in rows {
+            let (uuid, revision) = r?;
+            ret.insert(uuid, revision);
+        }
+        Ok(ret)
+    }
+
+    fn commit(&mut self) -> Result<()> {
+        enter_span!("sqlite.commit");
         let t = self
             .txn
             .take()
@@ -362,6 +515,7 @@ impl<'t> StorageTxn for Txn<'t> {
 mod test {
     use super::*;
     use crate::storage::taskmap_with;
+    use chrono::TimeZone;
     use pretty_assertions::assert_eq;
     use tempfile::TempDir;
 
@@ -431,6 +585,36 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_concurrent_instances() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut storage1 = SqliteStorage::new(&tmp_dir.path())?;
+        let mut storage2 = SqliteStorage::new(&tmp_dir.path())?;
+        let uuid1 = Uuid::new_v4();
+        let uuid2 = Uuid::new_v4();
+
+        // each transaction reads and then writes, which would fail with SQLITE_BUSY if the
+        // transactions were interleaved
+        let read_then_create = |storage: &mut SqliteStorage, uuid| -> anyhow::Result<()> {
+            let mut txn = storage.txn()?;
+            txn.all_task_uuids()?;
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            txn.create_task(uuid)?;
+            Ok(txn.commit()?)
+        };
+        let handle = std::thread::spawn(move || read_then_create(&mut storage2, uuid2));
+        read_then_create(&mut storage1, uuid1)?;
+        handle.join().unwrap()?;
+
+        let mut txn = storage1.txn()?;
+        let mut uuids = txn.all_task_uuids()?;
+        uuids.sort();
+        let mut exp = vec![uuid1, uuid2];
+        exp.sort();
+        assert_eq!(uuids, exp);
+        Ok(())
+    }
+
     #[test]
     fn test_create_exists() -> anyhow::Result<()> {
         let tmp_dir = TempDir::new()?;
@@ -606,6 +790,24 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_last_sync() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut storage = SqliteStorage::new(&tmp_dir.path())?;
+        let time = Utc.ymd(2022, 3, 4).and_hms(5, 6, 7);
+        {
+            let mut txn = storage.txn()?;
+            assert_eq!(txn.last_sync()?, None);
+            txn.set_last_sync(time)?;
+            txn.commit()?;
+        }
+        {
+            let mut txn = storage.txn()?;
+            assert_eq!(txn.last_sync()?, Some(time));
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_operations() -> anyhow::Result<()> {
         let tmp_dir = TempDir::new()?;
@@ -807,4 +1009,73 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn changed_tasks() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut storage = SqliteStorage::new(&tmp_dir.path())?;
+        let uuid1 = Uuid::new_v4();
+        let uuid2 = Uuid::new_v4();
+
+        // changes are not tracked until clear_changed_tasks is called
+        {
+            let mut txn = storage.txn()?;
+            txn.create_task(uuid1)?;
+            assert_eq!(txn.changed_tasks()?, None);
+            txn.clear_changed_tasks()?;
+            txn.commit()?;
+        }
+
+        {
+            let mut txn = storage.txn()?;
+            assert_eq!(txn.changed_tasks()?, Some(vec![]));
+            txn.create_task(uuid2)?;
+            txn.delete_task(uuid1)?;
+            txn.commit()?;
+        }
+
+        {
+            let mut txn = storage.txn()?;
+            let mut changed = txn.changed_tasks()?.unwrap();
+            changed.sort();
+            let mut exp = vec![uuid1, uuid2];
+            exp.sort();
+            assert_eq!(changed, exp);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn task_revisions() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut storage = SqliteStorage::new(tmp_dir.path())?;
+        let uuid1 = Uuid::new_v4();
+        let uuid2 = Uuid::new_v4();
+
+        {
+            let mut txn = storage.txn()?;
+            assert_eq!(txn.task_revision(uuid1)?, 0);
+            txn.create_task(uuid1)?;
+            txn.set_task(uuid1, TaskMap::new())?;
+            txn.create_task(uuid2)?;
+            txn.commit()?;
+        }
+
+        {
+            let mut txn = storage.txn()?;
+            assert_eq!(txn.task_revision(uuid1)?, 2);
+            txn.delete_task(uuid1)?;
+            txn.commit()?;
+        }
+
+        {
+            let mut txn = storage.txn()?;
+            let revisions = txn.task_revisions()?;
+            assert_eq!(revisions.get(&uuid1), Some(&3));
+            assert_eq!(revisions.get(&uuid2), Some(&1));
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,11 @@
+/// A link attached to a task, such as a URL for a related ticket or document.
+///
+/// Links are distinct from free-text [annotations](super::Annotation): each link has a URL and an
+/// optional label, and a task has at most one link with a given URL.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct Link {
+    /// URL of the link
+    pub url: String,
+    /// Optional human-readable label for the link
+    pub label: Option<String>,
+}
@@ -2,11 +2,13 @@
 use chrono::prelude::*;
 
 mod annotation;
+mod link;
 mod status;
 mod tag;
 mod task;
 
 pub use annotation::Annotation;
+pub use link::Link;
 pub use status::Status;
 pub use tag::Tag;
 pub use task::{Task, TaskMut};
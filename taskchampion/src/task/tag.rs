@@ -1,3 +1,4 @@
+use crate::errors::Error;
 use std::convert::TryFrom;
 use std::fmt;
 use std::str::FromStr;
@@ -43,11 +44,11 @@ impl Tag {
 }
 
 impl FromStr for Tag {
-    type Err = anyhow::Error;
+    type Err = Error;
 
-    fn from_str(value: &str) -> Result<Tag, anyhow::Error> {
-        fn err(value: &str) -> Result<Tag, anyhow::Error> {
-            anyhow::bail!("invalid tag {:?}", value)
+    fn from_str(value: &str) -> Result<Tag, Error> {
+        fn err(value: &str) -> Result<Tag, Error> {
+            Err(Error::InvalidTag(format!("{:?}", value)))
         }
 
         // first, look for synthetic tags
@@ -78,7 +79,7 @@ impl FromStr for Tag {
 }
 
 impl TryFrom<&str> for Tag {
-    type Error = anyhow::Error;
+    type Error = Error;
 
     fn try_from(value: &str) -> Result<Tag, Self::Error> {
         Self::from_str(value)
@@ -86,7 +87,7 @@ impl TryFrom<&str> for Tag {
 }
 
 impl TryFrom<&String> for Tag {
-    type Error = anyhow::Error;
+    type Error = Error;
 
     fn try_from(value: &String) -> Result<Tag, Self::Error> {
         Self::from_str(&value[..])
@@ -137,6 +138,7 @@ pub(super) enum SyntheticTag {
     Blocked,
     Unblocked,
     Blocking,
+    Overdue,
 }
 
 #[cfg(test)]
@@ -168,7 +170,7 @@ mod test {
         let tag: Result<Tag, _> = s.try_into();
         assert_eq!(
             tag.unwrap_err().to_string(),
-            format!("invalid tag \"{}\"", s)
+            format!("Invalid tag: \"{}\"", s)
         );
     }
 }
@@ -1,20 +1,23 @@
 use super::tag::{SyntheticTag, TagInner};
-use super::{Annotation, Status, Tag, Timestamp};
+use super::{Annotation, Link, Status, Tag, Timestamp};
 use crate::depmap::DependencyMap;
+use crate::errors::{Error, Result};
 use crate::replica::Replica;
+use crate::schema::{self, Key, Prop, Value};
 use crate::storage::TaskMap;
 use chrono::prelude::*;
+use chrono::Duration;
 use log::trace;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::convert::AsRef;
-use std::convert::TryInto;
 use std::rc::Rc;
-use std::str::FromStr;
 use uuid::Uuid;
 
 /* The Task and TaskMut classes wrap the underlying [`TaskMap`], which is a simple key/value map.
- * They provide semantic meaning to that TaskMap according to the TaskChampion data model.  For
- * example, [`get_status`](Task::get_status) and [`set_status`](TaskMut::set_status) translate from
- * strings in the TaskMap to [`Status`].
+ * They provide semantic meaning to that TaskMap according to the TaskChampion data model, as
+ * defined in the [`schema`](crate::schema) module.  For example, [`get_status`](Task::get_status)
+ * and [`set_status`](TaskMut::set_status) translate from strings in the TaskMap to [`Status`].
  *
  * The same approach applies for more complex data such as dependencies or annotations.  Users of
  * this API should only need the [`get_taskmap`](Task::get_taskmap) method for debugging purposes,
@@ -35,7 +38,18 @@ use uuid::Uuid;
 pub struct Task {
     uuid: Uuid,
     taskmap: TaskMap,
+    revision: u64,
     depmap: Rc<DependencyMap>,
+    derived: RefCell<Derived>,
+}
+
+/// Values derived from a task's TaskMap, computed when first requested.  This must be reset
+/// whenever the TaskMap changes.
+#[derive(Debug, Clone, Default)]
+struct Derived {
+    timestamps: HashMap<Prop, Option<DateTime<Utc>>>,
+    user_tags: Option<Rc<Vec<Tag>>>,
+    annotations: Option<Rc<Vec<Annotation>>>,
 }
 
 impl PartialEq for Task {
@@ -55,21 +69,8 @@ pub struct TaskMut<'r> {
     task: Task,
     replica: &'r mut Replica,
     updated_modified: bool,
-}
-
-/// An enum containing all of the key names defined in the data model, with the exception
-/// of the properties containing data (`tag_..`, etc.)
-#[derive(strum_macros::AsRefStr, strum_macros::EnumString)]
-#[strum(serialize_all = "kebab-case")]
-enum Prop {
-    Description,
-    Modified,
-    Start,
-    Status,
-    Priority,
-    Wait,
-    End,
-    Entry,
+    /// If true, changes are only made if the task's revision has not changed
+    checked: bool,
 }
 
 #[allow(clippy::ptr_arg)]
@@ -99,14 +100,29 @@ impl Task {
         Task {
             uuid,
             taskmap,
+            revision: 0,
             depmap,
+            derived: RefCell::new(Derived::default()),
         }
     }
 
+    pub(crate) fn with_revision(mut self, revision: u64) -> Task {
+        self.revision = revision;
+        self
+    }
+
     pub fn get_uuid(&self) -> Uuid {
         self.uuid
     }
 
+    /// Get the revision of this task at the time it was read.  The revision increases every time
+    /// the task changes in the replica's storage, including changes made by other processes and
+    /// by synchronization, so a changed revision indicates that this Task is out of date.
+    /// Revisions are not synchronized, and are only comparable within a single replica.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
     pub fn get_taskmap(&self) -> &TaskMap {
         &self.taskmap
     }
@@ -118,14 +134,26 @@ impl Task {
             task: self,
             replica,
             updated_modified: false,
+            checked: false,
+        }
+    }
+
+    /// Prepare to mutate this task, as for [`into_mut`](Task::into_mut), but only if it has not
+    /// changed since it was read.  Each change made with the resulting TaskMut fails with
+    /// [`Error::Conflict`] if the task's revision is no longer that of this Task, such as when
+    /// another process has modified the task.  Changes made with the TaskMut itself update its
+    /// revision, so a sequence of changes succeeds unless the task is changed by another writer.
+    pub fn into_mut_checked(self, replica: &mut Replica) -> TaskMut<'_> {
+        TaskMut {
+            task: self,
+            replica,
+            updated_modified: false,
+            checked: true,
         }
     }
 
     pub fn get_status(&self) -> Status {
-        self.taskmap
-            .get(Prop::Status.as_ref())
-            .map(|s| Status::from_taskmap(s))
-            .unwrap_or(Status::Pending)
+        self.get_value(Prop::Status).unwrap_or(Status::Pending)
     }
 
     pub fn get_description(&self) -> &str {
@@ -136,7 +164,7 @@ impl Task {
     }
 
     pub fn get_entry(&self) -> Option<DateTime<Utc>> {
-        self.get_timestamp(Prop::Entry.as_ref())
+        self.get_timestamp(Prop::Entry)
     }
 
     pub fn get_priority(&self) -> &str {
@@ -146,10 +174,19 @@ impl Task {
             .unwrap_or("")
     }
 
+    /// Get the task's project, if any.  Projects are hierarchical, with levels separated by `.`,
+    /// such as `work.client`.
+    pub fn get_project(&self) -> Option<&str> {
+        self.taskmap
+            .get(Prop::Project.as_ref())
+            .map(|s| s.as_ref())
+            .filter(|s: &&str| !s.is_empty())
+    }
+
     /// Get the wait time.  If this value is set, it will be returned, even
     /// if it is in the past.
     pub fn get_wait(&self) -> Option<DateTime<Utc>> {
-        self.get_timestamp(Prop::Wait.as_ref())
+        self.get_timestamp(Prop::Wait)
     }
 
     /// Determine whether this task is waiting now.
@@ -160,6 +197,32 @@ impl Task {
         false
     }
 
+    /// Get the due time.  If this value is set, it will be returned, even
+    /// if it is in the past.
+    pub fn get_due(&self) -> Option<DateTime<Utc>> {
+        self.get_timestamp(Prop::Due)
+    }
+
+    /// Get the scheduled time, before which the task should not be started.
+    pub fn get_scheduled(&self) -> Option<DateTime<Utc>> {
+        self.get_timestamp(Prop::Scheduled)
+    }
+
+    /// Get the until time, after which the task is no longer relevant.
+    pub fn get_until(&self) -> Option<DateTime<Utc>> {
+        self.get_timestamp(Prop::Until)
+    }
+
+    /// Get the recurrence interval, if this task is a recurring task.
+    pub fn get_recur(&self) -> Option<Duration> {
+        self.get_value(Prop::Recur)
+    }
+
+    /// Get the parent recurring task of which this task is an instance, if any.
+    pub fn get_parent(&self) -> Option<Uuid> {
+        self.get_value(Prop::Parent)
+    }
+
     /// Determine whether this task is active -- that is, that it has been started
     /// and not stopped.
     pub fn is_active(&self) -> bool {
@@ -176,6 +239,14 @@ impl Task {
         self.depmap.dependents(self.uuid).next().is_some()
     }
 
+    /// Determine whether this task is overdue -- that is, pending with a due time in the past.
+    pub fn is_overdue(&self) -> bool {
+        if self.get_status() != Status::Pending {
+            return false;
+        }
+        matches!(self.get_due(), Some(due) if due < Utc::now())
+    }
+
     /// Determine whether a given synthetic tag is present on this task.  All other
     /// synthetic tag calculations are based on this one.
     fn has_synthetic_tag(&self, synth: &SyntheticTag) -> bool {
@@ -188,13 +259,16 @@ impl Task {
             SyntheticTag::Blocked => self.is_blocked(),
             SyntheticTag::Unblocked => !self.is_blocked(),
             SyntheticTag::Blocking => self.is_blocking(),
+            SyntheticTag::Overdue => self.is_overdue(),
         }
     }
 
     /// Check if this task has the given tag
     pub fn has_tag(&self, tag: &Tag) -> bool {
         match tag.inner() {
-            TagInner::User(s) => self.taskmap.contains_key(&format!("tag_{}", s)),
+            TagInner::User(_) => self
+                .taskmap
+                .contains_key(&Key::Tag(tag.clone()).to_string()),
             TagInner::Synthetic(st) => self.has_synthetic_tag(st),
         }
     }
@@ -203,17 +277,9 @@ impl Task {
     pub fn get_tags(&self) -> impl Iterator<Item = Tag> + '_ {
         use strum::IntoEnumIterator;
 
-        self.taskmap
-            .iter()
-            .filter_map(|(k, _)| {
-                if let Some(tag) = k.strip_prefix("tag_") {
-                    if let Ok(tag) = tag.try_into() {
-                        return Some(tag);
-                    }
-                    // note that invalid "tag_*" are ignored
-                }
-                None
-            })
+        let user_tags = self.get_user_tags();
+        (0..user_tags.len())
+            .map(move |i| user_tags[i].clone())
             .chain(
                 SyntheticTag::iter()
                     .filter(move |st| self.has_synthetic_tag(st))
@@ -223,18 +289,43 @@ impl Task {
 
     /// Iterate over the task's annotations, in arbitrary order.
     pub fn get_annotations(&self) -> impl Iterator<Item = Annotation> + '_ {
-        self.taskmap.iter().filter_map(|(k, v)| {
-            if let Some(ts) = k.strip_prefix("annotation_") {
-                if let Ok(ts) = ts.parse::<i64>() {
-                    return Some(Annotation {
-                        entry: Utc.timestamp(ts, 0),
-                        description: v.to_owned(),
-                    });
-                }
-                // note that invalid "annotation_*" are ignored
-            }
-            None
-        })
+        let annotations = self
+            .derived
+            .borrow_mut()
+            .annotations
+            .get_or_insert_with(|| {
+                Rc::new(
+                    self.taskmap
+                        .iter()
+                        .filter_map(|(k, v)| match Key::parse(k) {
+                            Key::Annotation(entry) => Some(Annotation {
+                                entry,
+                                description: v.to_owned(),
+                            }),
+                            _ => None,
+                        })
+                        .collect(),
+                )
+            })
+            .clone();
+        (0..annotations.len()).map(move |i| annotations[i].clone())
+    }
+
+    /// Iterate over the task's links, in arbitrary order.
+    pub fn links(&self) -> impl Iterator<Item = Link> + '_ {
+        self.taskmap
+            .iter()
+            .filter_map(|(k, v)| match Key::parse(k) {
+                Key::Link(url) => Some(Link {
+                    url,
+                    label: if v.is_empty() {
+                        None
+                    } else {
+                        Some(v.to_owned())
+                    },
+                }),
+                _ => None,
+            })
     }
 
     /// Get the named user defined attributes (UDA).  This will return None
@@ -273,7 +364,17 @@ impl Task {
 
     /// Get the modification time for this task.
     pub fn get_modified(&self) -> Option<DateTime<Utc>> {
-        self.get_timestamp(Prop::Modified.as_ref())
+        self.get_timestamp(Prop::Modified)
+    }
+
+    /// Get the time at which this task was completed or deleted.
+    pub fn get_end(&self) -> Option<DateTime<Utc>> {
+        self.get_timestamp(Prop::End)
+    }
+
+    /// Get the time at which this task was started, if it is active.
+    pub fn get_start(&self) -> Option<DateTime<Utc>> {
+        self.get_timestamp(Prop::Start)
     }
 
     /// Get the UUIDs of tasks on which this task depends.
@@ -281,34 +382,55 @@ impl Task {
     /// This includes all dependencies, regardless of their status.  In fact, it may include
     /// dependencies that do not exist.
     pub fn get_dependencies(&self) -> impl Iterator<Item = Uuid> + '_ {
-        self.taskmap.iter().filter_map(|(p, _)| {
-            if let Some(dep_str) = p.strip_prefix("dep_") {
-                if let Ok(u) = Uuid::parse_str(dep_str) {
-                    return Some(u);
-                }
-                // (un-parseable dep_.. properties are ignored)
-            }
-            None
+        self.taskmap.keys().filter_map(|k| match Key::parse(k) {
+            Key::Dependency(u) => Some(u),
+            _ => None,
         })
     }
 
     // -- utility functions
 
+    /// Replace this task's TaskMap, resetting any values derived from it.
+    fn set_taskmap(&mut self, taskmap: TaskMap) {
+        self.taskmap = taskmap;
+        self.derived = RefCell::new(Derived::default());
+    }
+
+    /// Get the task's user (non-synthetic) tags.
+    fn get_user_tags(&self) -> Rc<Vec<Tag>> {
+        self.derived
+            .borrow_mut()
+            .user_tags
+            .get_or_insert_with(|| {
+                Rc::new(
+                    self.taskmap
+                        .keys()
+                        .filter_map(|k| match Key::parse(k) {
+                            Key::Tag(tag) => Some(tag),
+                            _ => None,
+                        })
+                        .collect(),
+                )
+            })
+            .clone()
+    }
+
     fn is_known_key(key: &str) -> bool {
-        Prop::from_str(key).is_ok()
-            || key.starts_with("tag_")
-            || key.starts_with("annotation_")
-            || key.starts_with("dep_")
+        Key::parse(key).is_known()
     }
 
-    fn get_timestamp(&self, property: &str) -> Option<DateTime<Utc>> {
-        if let Some(ts) = self.taskmap.get(property) {
-            if let Ok(ts) = ts.parse() {
-                return Some(Utc.timestamp(ts, 0));
-            }
-            // if the value does not parse as an integer, default to None
-        }
-        None
+    /// Get the value of a property, or None if it is not set or is not valid.
+    fn get_value<V: Value>(&self, property: Prop) -> Option<V> {
+        schema::get_value(&self.taskmap, property)
+    }
+
+    fn get_timestamp(&self, property: Prop) -> Option<DateTime<Utc>> {
+        *self
+            .derived
+            .borrow_mut()
+            .timestamps
+            .entry(property)
+            .or_insert_with(|| self.get_value(property))
     }
 }
 
@@ -320,12 +442,12 @@ impl<'r> TaskMut<'r> {
 
     /// Set the task's status.  This also adds the task to the working set if the
     /// new status puts it in that set.
-    pub fn set_status(&mut self, status: Status) -> anyhow::Result<()> {
+    pub fn set_status(&mut self, status: Status) -> Result<()> {
         match status {
             Status::Pending => {
                 // clear "end" when a task becomes "pending"
                 if self.taskmap.contains_key(Prop::End.as_ref()) {
-                    self.set_timestamp(Prop::End.as_ref(), None)?;
+                    self.set_value::<Timestamp>(Prop::End, None)?;
                 }
                 let uuid = self.uuid;
                 self.replica.add_to_working_set(uuid)?;
@@ -333,53 +455,81 @@ impl<'r> TaskMut<'r> {
             Status::Completed | Status::Deleted => {
                 // set "end" when a task is deleted or completed
                 if !self.taskmap.contains_key(Prop::End.as_ref()) {
-                    self.set_timestamp(Prop::End.as_ref(), Some(Utc::now()))?;
+                    self.set_value(Prop::End, Some(Utc::now()))?;
                 }
             }
             _ => {}
         }
-        self.set_string(
-            Prop::Status.as_ref(),
-            Some(String::from(status.to_taskmap())),
-        )
+        self.set_value(Prop::Status, Some(status))
     }
 
-    pub fn set_description(&mut self, description: String) -> anyhow::Result<()> {
-        self.set_string(Prop::Description.as_ref(), Some(description))
+    pub fn set_description(&mut self, description: String) -> Result<()> {
+        self.set_value(Prop::Description, Some(description))
     }
 
-    pub fn set_priority(&mut self, priority: String) -> anyhow::Result<()> {
-        self.set_string(Prop::Priority.as_ref(), Some(priority))
+    pub fn set_priority(&mut self, priority: String) -> Result<()> {
+        self.set_value(Prop::Priority, Some(priority))
     }
 
-    pub fn set_entry(&mut self, entry: Option<DateTime<Utc>>) -> anyhow::Result<()> {
-        self.set_timestamp(Prop::Entry.as_ref(), entry)
+    pub fn set_scheduled(&mut self, scheduled: Option<DateTime<Utc>>) -> Result<()> {
+        self.set_value(Prop::Scheduled, scheduled)
     }
 
-    pub fn set_wait(&mut self, wait: Option<DateTime<Utc>>) -> anyhow::Result<()> {
-        self.set_timestamp(Prop::Wait.as_ref(), wait)
+    pub fn set_until(&mut self, until: Option<DateTime<Utc>>) -> Result<()> {
+        self.set_value(Prop::Until, until)
     }
 
-    pub fn set_modified(&mut self, modified: DateTime<Utc>) -> anyhow::Result<()> {
-        self.set_timestamp(Prop::Modified.as_ref(), Some(modified))
+    pub fn set_recur(&mut self, recur: Option<Duration>) -> Result<()> {
+        self.set_value(Prop::Recur, recur)
+    }
+
+    pub fn set_parent(&mut self, parent: Option<Uuid>) -> Result<()> {
+        self.set_value(Prop::Parent, parent)
+    }
+
+    pub fn set_project(&mut self, project: Option<String>) -> Result<()> {
+        self.set_value(Prop::Project, project)
+    }
+
+    pub fn set_entry(&mut self, entry: Option<DateTime<Utc>>) -> Result<()> {
+        self.set_value(Prop::Entry, entry)
+    }
+
+    pub fn set_wait(&mut self, wait: Option<DateTime<Utc>>) -> Result<()> {
+        self.set_value(Prop::Wait, wait)
+    }
+
+    pub fn set_due(&mut self, due: Option<DateTime<Utc>>) -> Result<()> {
+        self.set_value(Prop::Due, due)
+    }
+
+    /// Set the time at which the task was completed or deleted.  This is set automatically by
+    /// [`TaskMut::set_status`], so this is only needed to override that time, such as when
+    /// importing tasks.
+    pub fn set_end(&mut self, end: Option<DateTime<Utc>>) -> Result<()> {
+        self.set_value(Prop::End, end)
+    }
+
+    pub fn set_modified(&mut self, modified: DateTime<Utc>) -> Result<()> {
+        self.set_value(Prop::Modified, Some(modified))
     }
 
     /// Start the task by creating "start": "<timestamp>", if the task is not already
     /// active.
-    pub fn start(&mut self) -> anyhow::Result<()> {
+    pub fn start(&mut self) -> Result<()> {
         if self.is_active() {
             return Ok(());
         }
-        self.set_timestamp(Prop::Start.as_ref(), Some(Utc::now()))
+        self.set_value(Prop::Start, Some(Utc::now()))
     }
 
     /// Stop the task by removing the `start` key
-    pub fn stop(&mut self) -> anyhow::Result<()> {
-        self.set_timestamp(Prop::Start.as_ref(), None)
+    pub fn stop(&mut self) -> Result<()> {
+        self.set_value::<Timestamp>(Prop::Start, None)
     }
 
     /// Mark this task as complete
-    pub fn done(&mut self) -> anyhow::Result<()> {
+    pub fn done(&mut self) -> Result<()> {
         self.set_status(Status::Completed)
     }
 
@@ -387,38 +537,63 @@ impl<'r> TaskMut<'r> {
     ///
     /// Note that this does not delete the task.  It merely marks the task as
     /// deleted.
-    pub fn delete(&mut self) -> anyhow::Result<()> {
+    pub fn delete(&mut self) -> Result<()> {
         self.set_status(Status::Deleted)
     }
 
     /// Add a tag to this task.  Does nothing if the tag is already present.
-    pub fn add_tag(&mut self, tag: &Tag) -> anyhow::Result<()> {
+    pub fn add_tag(&mut self, tag: &Tag) -> Result<()> {
         if tag.is_synthetic() {
-            anyhow::bail!("Synthetic tags cannot be modified");
+            return Err(Error::InvalidTag(format!(
+                "{} is synthetic and cannot be modified",
+                tag
+            )));
         }
-        self.set_string(format!("tag_{}", tag), Some("".to_owned()))
+        self.set_string(Key::Tag(tag.clone()).to_string(), Some("".to_owned()))
     }
 
     /// Remove a tag from this task.  Does nothing if the tag is not present.
-    pub fn remove_tag(&mut self, tag: &Tag) -> anyhow::Result<()> {
+    pub fn remove_tag(&mut self, tag: &Tag) -> Result<()> {
         if tag.is_synthetic() {
-            anyhow::bail!("Synthetic tags cannot be modified");
+            return Err(Error::InvalidTag(format!(
+                "{} is synthetic and cannot be modified",
+                tag
+            )));
         }
-        self.set_string(format!("tag_{}", tag), None)
+        self.set_string(Key::Tag(tag.clone()).to_string(), None)
     }
 
     /// Add a new annotation.  Note that annotations with the same entry time
     /// will overwrite one another.
-    pub fn add_annotation(&mut self, ann: Annotation) -> anyhow::Result<()> {
+    pub fn add_annotation(&mut self, ann: Annotation) -> Result<()> {
         self.set_string(
-            format!("annotation_{}", ann.entry.timestamp()),
+            Key::Annotation(ann.entry).to_string(),
             Some(ann.description),
         )
     }
 
     /// Remove an annotation, based on its entry time.
-    pub fn remove_annotation(&mut self, entry: Timestamp) -> anyhow::Result<()> {
-        self.set_string(format!("annotation_{}", entry.timestamp()), None)
+    pub fn remove_annotation(&mut self, entry: Timestamp) -> Result<()> {
+        self.set_string(Key::Annotation(entry).to_string(), None)
+    }
+
+    /// Add a link to the given URL, without a label.  A link with the same URL will be replaced.
+    pub fn add_link(&mut self, url: String) -> Result<()> {
+        self.add_link_with_label(url, String::new())
+    }
+
+    /// Add a link to the given URL, with a label.  An empty label is the same as no label.  A
+    /// link with the same URL will be replaced.
+    pub fn add_link_with_label(&mut self, url: String, label: String) -> Result<()> {
+        if url.is_empty() {
+            return Err(anyhow::anyhow!("link URL must not be empty").into());
+        }
+        self.set_string(Key::Link(url).to_string(), Some(label))
+    }
+
+    /// Remove a link, based on its URL.
+    pub fn remove_link(&mut self, url: &str) -> Result<()> {
+        self.set_string(Key::Link(url.to_owned()).to_string(), None)
     }
 
     /// Set a user-defined attribute (UDA).  This will fail if the key is defined by the data
@@ -428,18 +603,14 @@ impl<'r> TaskMut<'r> {
         namespace: impl AsRef<str>,
         key: impl AsRef<str>,
         value: impl Into<String>,
-    ) -> anyhow::Result<()> {
+    ) -> Result<()> {
         let key = uda_tuple_to_string(namespace, key);
         self.set_legacy_uda(key, value)
     }
 
     /// Remove a user-defined attribute (UDA).  This will fail if the key is defined by the data
     /// model.
-    pub fn remove_uda(
-        &mut self,
-        namespace: impl AsRef<str>,
-        key: impl AsRef<str>,
-    ) -> anyhow::Result<()> {
+    pub fn remove_uda(&mut self, namespace: impl AsRef<str>, key: impl AsRef<str>) -> Result<()> {
         let key = uda_tuple_to_string(namespace, key);
         self.remove_legacy_uda(key)
     }
@@ -449,60 +620,54 @@ impl<'r> TaskMut<'r> {
         &mut self,
         key: impl Into<String>,
         value: impl Into<String>,
-    ) -> anyhow::Result<()> {
+    ) -> Result<()> {
         let key = key.into();
         if Task::is_known_key(&key) {
-            anyhow::bail!(
+            return Err(anyhow::anyhow!(
                 "Property name {} as special meaning in a task and cannot be used as a UDA",
                 key
-            );
+            )
+            .into());
         }
         self.set_string(key, Some(value.into()))
     }
 
     /// Remove a user-defined attribute (UDA), where the key is a legacy key.
-    pub fn remove_legacy_uda(&mut self, key: impl Into<String>) -> anyhow::Result<()> {
+    pub fn remove_legacy_uda(&mut self, key: impl Into<String>) -> Result<()> {
         let key = key.into();
         if Task::is_known_key(&key) {
-            anyhow::bail!(
+            return Err(anyhow::anyhow!(
                 "Property name {} as special meaning in a task and cannot be used as a UDA",
                 key
-            );
+            )
+            .into());
         }
         self.set_string(key, None)
     }
 
     /// Add a dependency.
-    pub fn add_dependency(&mut self, dep: Uuid) -> anyhow::Result<()> {
-        let key = format!("dep_{}", dep);
-        self.set_string(key, Some("".to_string()))
+    pub fn add_dependency(&mut self, dep: Uuid) -> Result<()> {
+        self.set_string(Key::Dependency(dep).to_string(), Some("".to_string()))
     }
 
     /// Remove a dependency.
-    pub fn remove_dependency(&mut self, dep: Uuid) -> anyhow::Result<()> {
-        let key = format!("dep_{}", dep);
-        self.set_string(key, None)
+    pub fn remove_dependency(&mut self, dep: Uuid) -> Result<()> {
+        self.set_string(Key::Dependency(dep).to_string(), None)
     }
 
     // -- utility functions
 
-    fn update_modified(&mut self) -> anyhow::Result<()> {
+    fn update_modified(&mut self) -> Result<()> {
         if !self.updated_modified {
-            let now = format!("{}", Utc::now().timestamp());
+            let now = Utc::now().to_value();
             trace!("task {}: set property modified={:?}", self.task.uuid, now);
-            self.task.taskmap =
-                self.replica
-                    .update_task(self.task.uuid, Prop::Modified.as_ref(), Some(now))?;
+            self.update(Prop::Modified.as_ref(), Some(now))?;
             self.updated_modified = true;
         }
         Ok(())
     }
 
-    fn set_string<S: Into<String>>(
-        &mut self,
-        property: S,
-        value: Option<String>,
-    ) -> anyhow::Result<()> {
+    fn set_string<S: Into<String>>(&mut self, property: S, value: Option<String>) -> Result<()> {
         let property = property.into();
         // updated the modified timestamp unless we are setting it explicitly
         if &property != "modified" {
@@ -515,27 +680,36 @@ impl<'r> TaskMut<'r> {
             trace!("task {}: remove property {}", self.task.uuid, property);
         }
 
-        self.task.taskmap = self
-            .replica
-            .update_task(self.task.uuid, &property, value.as_ref())?;
+        self.update(&property, value)
+    }
 
+    /// Update a property in the replica, checking the revision if required, and update this
+    /// task to match.
+    fn update(&mut self, property: &str, value: Option<String>) -> Result<()> {
+        let revision = if self.checked {
+            Some(self.task.revision)
+        } else {
+            None
+        };
+        let (taskmap, revision) =
+            self.replica
+                .update_task_at_revision(self.task.uuid, property, value, revision)?;
+        self.task.set_taskmap(taskmap);
+        self.task.revision = revision;
         Ok(())
     }
 
-    fn set_timestamp(
-        &mut self,
-        property: &str,
-        value: Option<DateTime<Utc>>,
-    ) -> anyhow::Result<()> {
-        self.set_string(property, value.map(|v| v.timestamp().to_string()))
+    fn set_value<V: Value>(&mut self, property: Prop, value: Option<V>) -> Result<()> {
+        self.set_string(property.as_ref(), value.map(|v| v.to_value()))
     }
 
     /// Used by tests to ensure that updates are properly written
     #[cfg(test)]
-    fn reload(&mut self) -> anyhow::Result<()> {
+    fn reload(&mut self) -> Result<()> {
         let uuid = self.uuid;
         let task = self.replica.get_task(uuid)?.unwrap();
-        self.task.taskmap = task.taskmap;
+        self.task.set_taskmap(task.taskmap);
+        self.task.revision = task.revision;
         Ok(())
     }
 }
@@ -657,6 +831,112 @@ mod test {
         assert_eq!(task.get_wait(), Some(ts));
     }
 
+    #[test]
+    fn test_overdue() {
+        let task = |status: &str, due: DateTime<Utc>| {
+            Task::new(
+                Uuid::new_v4(),
+                vec![
+                    (String::from("status"), String::from(status)),
+                    (String::from("due"), format!("{}", due.timestamp())),
+                ]
+                .drain(..)
+                .collect(),
+                dm(),
+            )
+        };
+        let past = Utc.ymd(1970, 1, 1).and_hms(0, 0, 0);
+        let future = Utc.ymd(3000, 1, 1).and_hms(0, 0, 0);
+
+        assert!(task("pending", past).is_overdue());
+        assert!(task("pending", past).has_tag(&stag(SyntheticTag::Overdue)));
+        assert!(!task("pending", future).is_overdue());
+        assert!(!task("completed", past).is_overdue());
+        assert!(!Task::new(Uuid::new_v4(), TaskMap::new(), dm()).is_overdue());
+    }
+
+    #[test]
+    fn test_due() {
+        let ts = Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+        let task = Task::new(
+            Uuid::new_v4(),
+            vec![(String::from("due"), format!("{}", ts.timestamp()))]
+                .drain(..)
+                .collect(),
+            dm(),
+        );
+
+        assert_eq!(task.get_due(), Some(ts));
+    }
+
+    #[test]
+    fn test_set_due() {
+        with_mut_task(|mut task| {
+            let ts = Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+            task.set_due(Some(ts)).unwrap();
+            assert_eq!(task.get_due(), Some(ts));
+            task.reload().unwrap();
+            assert_eq!(task.get_due(), Some(ts));
+
+            task.set_due(None).unwrap();
+            assert_eq!(task.get_due(), None);
+        });
+    }
+
+    #[test]
+    fn test_set_end() {
+        with_mut_task(|mut task| {
+            let ts = Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+            task.done().unwrap();
+            assert!(task.get_end().is_some());
+            task.set_end(Some(ts)).unwrap();
+            task.reload().unwrap();
+            assert_eq!(task.get_end(), Some(ts));
+
+            task.set_end(None).unwrap();
+            assert_eq!(task.get_end(), None);
+        });
+    }
+
+    #[test]
+    fn test_set_scheduled_until() {
+        with_mut_task(|mut task| {
+            let ts1 = Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+            let ts2 = Utc.ymd(2022, 2, 1).and_hms(0, 0, 0);
+            task.set_scheduled(Some(ts1)).unwrap();
+            task.set_until(Some(ts2)).unwrap();
+            task.reload().unwrap();
+            assert_eq!(task.get_scheduled(), Some(ts1));
+            assert_eq!(task.get_until(), Some(ts2));
+            assert_eq!(task.taskmap["scheduled"], format!("{}", ts1.timestamp()));
+            assert_eq!(task.taskmap["until"], format!("{}", ts2.timestamp()));
+
+            task.set_scheduled(None).unwrap();
+            task.set_until(None).unwrap();
+            assert_eq!(task.get_scheduled(), None);
+            assert_eq!(task.get_until(), None);
+        });
+    }
+
+    #[test]
+    fn test_set_recur_parent() {
+        with_mut_task(|mut task| {
+            let parent = Uuid::new_v4();
+            task.set_recur(Some(Duration::days(7))).unwrap();
+            task.set_parent(Some(parent)).unwrap();
+            task.reload().unwrap();
+            assert_eq!(task.get_recur(), Some(Duration::days(7)));
+            assert_eq!(task.get_parent(), Some(parent));
+            assert_eq!(task.taskmap["recur"], "604800");
+            assert_eq!(task.taskmap["parent"], parent.to_string());
+
+            task.set_recur(None).unwrap();
+            task.set_parent(None).unwrap();
+            assert_eq!(task.get_recur(), None);
+            assert_eq!(task.get_parent(), None);
+        });
+    }
+
     #[test]
     fn test_has_tag() {
         let task = Task::new(
@@ -821,6 +1101,94 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_links() {
+        let task = Task::new(
+            Uuid::new_v4(),
+            vec![
+                (
+                    String::from("link_https://example.com/1"),
+                    String::from("ticket 1"),
+                ),
+                (String::from("link_https://example.com/2"), String::from("")),
+                (String::from("link_"), String::from("invalid")),
+            ]
+            .drain(..)
+            .collect(),
+            dm(),
+        );
+
+        let mut links: Vec<_> = task.links().collect();
+        links.sort();
+        assert_eq!(
+            links,
+            vec![
+                Link {
+                    url: "https://example.com/1".into(),
+                    label: Some("ticket 1".into()),
+                },
+                Link {
+                    url: "https://example.com/2".into(),
+                    label: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_link() {
+        with_mut_task(|mut task| {
+            task.add_link("https://example.com".into()).unwrap();
+            let k = "link_https://example.com";
+            assert_eq!(task.taskmap[k], "".to_owned());
+            task.reload().unwrap();
+            assert_eq!(task.taskmap[k], "".to_owned());
+
+            task.add_link_with_label("https://example.com".into(), "example".into())
+                .unwrap();
+            assert_eq!(task.taskmap[k], "example".to_owned());
+
+            assert!(task.add_link("".into()).is_err());
+        });
+    }
+
+    #[test]
+    fn test_remove_link() {
+        with_mut_task(|mut task| {
+            task.set_string("link_https://example.com/1", Some("".into()))
+                .unwrap();
+            task.set_string("link_https://example.com/2", Some("".into()))
+                .unwrap();
+
+            task.remove_link("https://example.com/1").unwrap();
+
+            task.reload().unwrap();
+
+            let links: Vec<_> = task.links().collect();
+            assert_eq!(
+                links,
+                vec![Link {
+                    url: "https://example.com/2".into(),
+                    label: None,
+                }]
+            );
+        });
+    }
+
+    #[test]
+    fn test_set_get_project() {
+        with_mut_task(|mut task| {
+            assert_eq!(task.get_project(), None);
+            task.set_project(Some("work.client".into())).unwrap();
+            assert_eq!(task.get_project(), Some("work.client"));
+            task.reload().unwrap();
+            assert_eq!(task.get_project(), Some("work.client"));
+            task.set_project(None).unwrap();
+            assert_eq!(task.get_project(), None);
+            assert!(!task.taskmap.contains_key("project"));
+        });
+    }
+
     #[test]
     fn test_set_get_priority() {
         with_mut_task(|mut task| {
@@ -838,6 +1206,7 @@ mod test {
             task.set_status(Status::Pending).unwrap();
             assert_eq!(task.get_status(), Status::Pending);
             assert!(!task.taskmap.contains_key("end"));
+            assert_eq!(task.get_end(), None);
             assert!(task.has_tag(&stag(SyntheticTag::Pending)));
             assert!(!task.has_tag(&stag(SyntheticTag::Completed)));
         });
@@ -849,6 +1218,7 @@ mod test {
             task.set_status(Status::Completed).unwrap();
             assert_eq!(task.get_status(), Status::Completed);
             assert!(task.taskmap.contains_key("end"));
+            assert!(task.get_end().is_some());
             assert!(!task.has_tag(&stag(SyntheticTag::Pending)));
             assert!(task.has_tag(&stag(SyntheticTag::Completed)));
         });
@@ -868,8 +1238,10 @@ mod test {
     #[test]
     fn test_start() {
         with_mut_task(|mut task| {
+            assert_eq!(task.get_start(), None);
             task.start().unwrap();
             assert!(task.taskmap.contains_key("start"));
+            assert!(task.get_start().is_some());
 
             task.reload().unwrap();
             assert!(task.taskmap.contains_key("start"));
@@ -945,6 +1317,30 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_derived_values_reset() {
+        with_mut_task(|mut task| {
+            // read each derived value once, so that it is cached
+            assert!(!task.has_tag(&utag("abc")));
+            assert_eq!(task.get_tags().filter(|t| t == &utag("abc")).count(), 0);
+            assert_eq!(task.get_annotations().count(), 0);
+            assert_eq!(task.get_due(), None);
+
+            let due = Utc.ymd(2033, 1, 1).and_hms(0, 0, 0);
+            task.add_tag(&utag("abc")).unwrap();
+            task.add_annotation(Annotation {
+                entry: Utc.timestamp(1635301900, 0),
+                description: "hello".into(),
+            })
+            .unwrap();
+            task.set_due(Some(due)).unwrap();
+
+            assert_eq!(task.get_tags().filter(|t| t == &utag("abc")).count(), 1);
+            assert_eq!(task.get_annotations().count(), 1);
+            assert_eq!(task.get_due(), Some(due));
+        });
+    }
+
     #[test]
     fn test_remove_tags() {
         with_mut_task(|mut task| {
@@ -970,10 +1366,17 @@ mod test {
                 ("start".into(), "not a uda".into()),
                 ("status".into(), "not a uda".into()),
                 ("wait".into(), "not a uda".into()),
+                ("due".into(), "not a uda".into()),
+                ("project".into(), "not a uda".into()),
+                ("scheduled".into(), "not a uda".into()),
+                ("until".into(), "not a uda".into()),
+                ("recur".into(), "not a uda".into()),
+                ("parent".into(), "not a uda".into()),
                 ("start".into(), "not a uda".into()),
                 ("tag_abc".into(), "not a uda".into()),
                 ("dep_1234".into(), "not a uda".into()),
                 ("annotation_1234".into(), "not a uda".into()),
+                ("link_https://example.com".into(), "not a uda".into()),
                 ("githubid".into(), "123".into()),
                 ("jira.url".into(), "h://x".into()),
             ]
@@ -6,13 +6,14 @@ use crate::storage::{ReplicaOp, StorageTxn, TaskMap};
 /// ReplicaOp to the list of operations.  Returns the TaskMap of the task after the
 /// operation has been applied (or an empty TaskMap for Delete).  It is not an error
 /// to create an existing task, nor to delete a nonexistent task.
+///
+/// The caller is responsible for committing the transaction.
 pub(super) fn apply_and_record(txn: &mut dyn StorageTxn, op: SyncOp) -> anyhow::Result<TaskMap> {
     match op {
         SyncOp::Create { uuid } => {
             let created = txn.create_task(uuid)?;
             if created {
                 txn.add_operation(ReplicaOp::Create { uuid })?;
-                txn.commit()?;
                 Ok(TaskMap::new())
             } else {
                 Ok(txn
@@ -28,7 +29,6 @@ pub(super) fn apply_and_record(txn: &mut dyn StorageTxn, op: SyncOp) -> anyhow::
                     uuid,
                     old_task: task,
                 })?;
-                txn.commit()?;
                 Ok(TaskMap::new())
             } else {
                 Ok(TaskMap::new())
@@ -56,7 +56,6 @@ pub(super) fn apply_and_record(txn: &mut dyn StorageTxn, op: SyncOp) -> anyhow::
                     value,
                     timestamp,
                 })?;
-                txn.commit()?;
                 Ok(task)
             } else {
                 Err(Error::Database(format!("Task {} does not exist", uuid)).into())
@@ -345,6 +344,7 @@ mod tests {
             let mut txn = db.storage.txn()?;
             let taskmap = apply_and_record(txn.as_mut(), op1)?;
             assert_eq!(taskmap.len(), 0);
+            txn.commit()?;
         }
 
         let op2 = SyncOp::Update {
@@ -1,5 +1,8 @@
+use crate::errors::{Error, Result};
 use crate::server::{Server, SyncOp};
 use crate::storage::{ReplicaOp, Storage, TaskMap};
+use chrono::{DateTime, Utc};
+use log::warn;
 use uuid::Uuid;
 
 mod apply;
@@ -8,17 +11,25 @@ mod sync;
 mod undo;
 mod working_set;
 
+pub use sync::SyncProgress;
+
 /// A TaskDb is the backend for a replica.  It manages the storage, operations, synchronization,
 /// and so on, and all the invariants that come with it.  It leaves the meaning of particular task
 /// properties to the replica and task implementations.
 pub struct TaskDb {
     storage: Box<dyn Storage>,
+
+    /// A counter incremented on every operation that may modify tasks.
+    modification_count: u64,
 }
 
 impl TaskDb {
     /// Create a new TaskDb with the given backend storage
     pub fn new(storage: Box<dyn Storage>) -> TaskDb {
-        TaskDb { storage }
+        TaskDb {
+            storage,
+            modification_count: 0,
+        }
     }
 
     #[cfg(test)]
@@ -36,60 +47,164 @@ impl TaskDb {
     /// Aside from synchronization operations, this is the only way to modify the TaskDb.  In cases
     /// where an operation does not make sense, this function will do nothing and return an error
     /// (but leave the TaskDb in a consistent state).
-    pub fn apply(&mut self, op: SyncOp) -> anyhow::Result<TaskMap> {
+    pub fn apply(&mut self, op: SyncOp) -> Result<TaskMap> {
+        enter_span!("taskdb.apply");
+        self.modification_count += 1;
+        let mut txn = self.storage.txn()?;
+        let taskmap = apply::apply_and_record(txn.as_mut(), op)?;
+        txn.commit()?;
+        Ok(taskmap)
+    }
+
+    /// Apply an operation to the TaskDb as for [`TaskDb::apply`], returning the resulting TaskMap
+    /// and the task's new revision.  If `revision` is given, the operation is only applied if
+    /// the task currently has that revision, and otherwise fails with [`Error::Conflict`].
+    pub fn apply_at_revision(
+        &mut self,
+        op: SyncOp,
+        revision: Option<u64>,
+    ) -> Result<(TaskMap, u64)> {
+        enter_span!("taskdb.apply");
+        self.modification_count += 1;
+        let uuid = op.uuid();
+        let mut txn = self.storage.txn()?;
+        if let Some(revision) = revision {
+            if txn.task_revision(uuid)? != revision {
+                return Err(Error::Conflict(uuid));
+            }
+        }
+        let taskmap = apply::apply_and_record(txn.as_mut(), op)?;
+        let revision = txn.task_revision(uuid)?;
+        txn.commit()?;
+        Ok((taskmap, revision))
+    }
+
+    /// Apply a sequence of operations to the TaskDb in a single transaction, as for
+    /// [`TaskDb::apply`].  Returns the TaskMap resulting from the last operation.  If any
+    /// operation fails, none of the operations are applied.
+    pub fn apply_all(&mut self, ops: Vec<SyncOp>) -> Result<TaskMap> {
+        enter_span!("taskdb.apply_all", operations = ops.len());
+        self.modification_count += 1;
         let mut txn = self.storage.txn()?;
-        apply::apply_and_record(txn.as_mut(), op)
+        let mut taskmap = TaskMap::new();
+        for op in ops {
+            taskmap = apply::apply_and_record(txn.as_mut(), op)?;
+        }
+        txn.commit()?;
+        Ok(taskmap)
+    }
+
+    /// Apply the given operations in a single transaction, skipping any that are not valid, such
+    /// as an update to a task that does not exist, as a sync does.  Returns the skipped
+    /// operations.
+    pub fn apply_valid(&mut self, ops: Vec<SyncOp>) -> Result<Vec<SyncOp>> {
+        enter_span!("taskdb.apply_valid", operations = ops.len());
+        self.modification_count += 1;
+        let mut txn = self.storage.txn()?;
+        let mut skipped = vec![];
+        for op in ops {
+            match apply::apply_and_record(txn.as_mut(), op.clone()).map_err(Error::from) {
+                Ok(_) => {}
+                Err(Error::Database(e)) => {
+                    warn!("Invalid operation {:?}: {} (skipped)", op, e);
+                    skipped.push(op);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        txn.commit()?;
+        Ok(skipped)
     }
 
     /// Add an UndoPoint operation to the list of replica operations.
-    pub fn add_undo_point(&mut self) -> anyhow::Result<()> {
+    pub fn add_undo_point(&mut self) -> Result<()> {
         let mut txn = self.storage.txn()?;
         txn.add_operation(ReplicaOp::UndoPoint)?;
         txn.commit()
     }
 
+    /// Get the modification count, which changes whenever this TaskDb performs an operation that
+    /// may modify tasks.  Modifications made to the storage by other means are not counted.
+    pub fn modification_count(&self) -> u64 {
+        self.modification_count
+    }
+
     /// Get all tasks.
-    pub fn all_tasks(&mut self) -> anyhow::Result<Vec<(Uuid, TaskMap)>> {
+    pub fn all_tasks(&mut self) -> Result<Vec<(Uuid, TaskMap)>> {
         let mut txn = self.storage.txn()?;
         txn.all_tasks()
     }
 
     /// Get the UUIDs of all tasks
-    pub fn all_task_uuids(&mut self) -> anyhow::Result<Vec<Uuid>> {
+    pub fn all_task_uuids(&mut self) -> Result<Vec<Uuid>> {
         let mut txn = self.storage.txn()?;
         txn.all_task_uuids()
     }
 
     /// Get the working set
-    pub fn working_set(&mut self) -> anyhow::Result<Vec<Option<Uuid>>> {
+    pub fn working_set(&mut self) -> Result<Vec<Option<Uuid>>> {
         let mut txn = self.storage.txn()?;
         txn.get_working_set()
     }
 
+    /// Get all tasks, with their revisions.
+    pub fn all_tasks_with_revisions(&mut self) -> Result<Vec<(Uuid, TaskMap, u64)>> {
+        let mut txn = self.storage.txn()?;
+        let revisions = txn.task_revisions()?;
+        Ok(txn
+            .all_tasks()?
+            .into_iter()
+            .map(|(uuid, taskmap)| {
+                let revision = revisions.get(&uuid).copied().unwrap_or(0);
+                (uuid, taskmap, revision)
+            })
+            .collect())
+    }
+
     /// Get a single task, by uuid.
-    pub fn get_task(&mut self, uuid: Uuid) -> anyhow::Result<Option<TaskMap>> {
+    pub fn get_task(&mut self, uuid: Uuid) -> Result<Option<TaskMap>> {
         let mut txn = self.storage.txn()?;
         txn.get_task(uuid)
     }
 
+    /// Get a single task and its revision, by uuid.
+    pub fn get_task_with_revision(&mut self, uuid: Uuid) -> Result<Option<(TaskMap, u64)>> {
+        let mut txn = self.storage.txn()?;
+        match txn.get_task(uuid)? {
+            Some(taskmap) => Ok(Some((taskmap, txn.task_revision(uuid)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the revision of a task, by uuid.
+    pub fn task_revision(&mut self, uuid: Uuid) -> Result<u64> {
+        let mut txn = self.storage.txn()?;
+        txn.task_revision(uuid)
+    }
+
     /// Rebuild the working set using a function to identify tasks that should be in the set.  This
     /// renumbers the existing working-set tasks to eliminate gaps, and also adds any tasks that
     /// are not already in the working set but should be.  The rebuild occurs in a single
     /// trasnsaction against the storage backend.
-    pub fn rebuild_working_set<F>(
-        &mut self,
-        in_working_set: F,
-        renumber: bool,
-    ) -> anyhow::Result<()>
+    pub fn rebuild_working_set<F>(&mut self, in_working_set: F, renumber: bool) -> Result<()>
     where
         F: Fn(&TaskMap) -> bool,
     {
-        working_set::rebuild(self.storage.txn()?.as_mut(), in_working_set, renumber)
+        enter_span!(
+            "taskdb.rebuild_working_set",
+            renumber,
+            tasks = tracing::field::Empty
+        );
+        Ok(working_set::rebuild(
+            self.storage.txn()?.as_mut(),
+            in_working_set,
+            renumber,
+        )?)
     }
 
     /// Add the given uuid to the working set and return its index; if it is already in the working
     /// set, its index is returned.  This does *not* renumber any existing tasks.
-    pub fn add_to_working_set(&mut self, uuid: Uuid) -> anyhow::Result<usize> {
+    pub fn add_to_working_set(&mut self, uuid: Uuid) -> Result<usize> {
         let mut txn = self.storage.txn()?;
         // search for an existing entry for this task..
         for (i, elt) in txn.get_working_set()?.iter().enumerate() {
@@ -112,28 +227,67 @@ impl TaskDb {
     ///
     /// Set this to true on systems more constrained in CPU, memory, or bandwidth than a typical desktop
     /// system
+    ///
+    /// The `progress` function is called as the sync proceeds.  If it returns false before the
+    /// new version is sent to the server, the sync is cancelled and no changes are made; after
+    /// that, returning false only skips sending a snapshot.
     pub fn sync(
         &mut self,
         server: &mut Box<dyn Server>,
         avoid_snapshots: bool,
-    ) -> anyhow::Result<()> {
+        progress: &mut dyn FnMut(SyncProgress) -> bool,
+    ) -> Result<()> {
+        enter_span!("taskdb.sync", avoid_snapshots);
+        self.modification_count += 1;
+        let mut txn = self.storage.txn()?;
+        Ok(sync::sync(server, txn.as_mut(), avoid_snapshots, progress)?)
+    }
+
+    /// Get the time of the last successful sync, if any
+    pub fn last_sync(&mut self) -> Result<Option<DateTime<Utc>>> {
         let mut txn = self.storage.txn()?;
-        sync::sync(server, txn.as_mut(), avoid_snapshots)
+        txn.last_sync()
     }
 
     /// Undo local operations until the most recent UndoPoint, returning false if there are no
     /// local operations to undo.
-    pub fn undo(&mut self) -> anyhow::Result<bool> {
+    pub fn undo(&mut self) -> Result<bool> {
+        enter_span!("taskdb.undo");
+        self.modification_count += 1;
+        let mut txn = self.storage.txn()?;
+        Ok(undo::undo(txn.as_mut())?)
+    }
+
+    /// Get the local operations that `steps` calls to `undo` would reverse, in the order in
+    /// which they were applied.
+    pub fn get_undo_ops(&mut self, steps: usize) -> Result<Vec<ReplicaOp>> {
         let mut txn = self.storage.txn()?;
-        undo::undo(txn.as_mut())
+        Ok(undo::get_undo_ops(txn.as_mut(), steps)?)
+    }
+
+    /// Get the un-synchronized operations in storage.
+    pub fn unsynced_operations(&mut self) -> Result<Vec<ReplicaOp>> {
+        let mut txn = self.storage.txn()?;
+        txn.operations()
     }
 
     /// Get the number of un-synchronized operations in storage.
-    pub fn num_operations(&mut self) -> anyhow::Result<usize> {
+    pub fn num_operations(&mut self) -> Result<usize> {
         let mut txn = self.storage.txn().unwrap();
         txn.num_operations()
     }
 
+    /// Determine whether there are any un-synchronized operations affecting the given task.
+    pub fn has_operations_for(&mut self, uuid: Uuid) -> Result<bool> {
+        let mut txn = self.storage.txn()?;
+        Ok(txn.operations()?.iter().any(|op| match op {
+            ReplicaOp::Create { uuid: u }
+            | ReplicaOp::Delete { uuid: u, .. }
+            | ReplicaOp::Update { uuid: u, .. } => *u == uuid,
+            ReplicaOp::UndoPoint => false,
+        }))
+    }
+
     // functions for supporting tests
 
     #[cfg(test)]
@@ -171,7 +325,7 @@ mod tests {
     use super::*;
     use crate::server::test::TestServer;
     use crate::storage::{InMemoryStorage, ReplicaOp};
-    use chrono::Utc;
+    use chrono::{TimeZone, Utc};
     use pretty_assertions::assert_eq;
     use proptest::prelude::*;
     use uuid::Uuid;
@@ -189,6 +343,52 @@ mod tests {
         assert_eq!(db.operations(), vec![ReplicaOp::Create { uuid }]);
     }
 
+    #[test]
+    fn test_apply_all() {
+        let mut db = TaskDb::new_inmemory();
+        let uuid = Uuid::new_v4();
+        let now = Utc::now();
+        let taskmap = db
+            .apply_all(vec![
+                SyncOp::Create { uuid },
+                SyncOp::Update {
+                    uuid,
+                    property: "title".into(),
+                    value: Some("my task".into()),
+                    timestamp: now,
+                },
+            ])
+            .unwrap();
+
+        assert_eq!(taskmap.get("title"), Some(&"my task".to_owned()));
+        assert_eq!(
+            db.sorted_tasks(),
+            vec![(uuid, vec![("title".into(), "my task".into())])]
+        );
+        assert_eq!(db.operations().len(), 2);
+    }
+
+    #[test]
+    fn test_apply_all_failure() {
+        let mut db = TaskDb::new_inmemory();
+        let uuid = Uuid::new_v4();
+        // updating a nonexistent task fails, so the create is not applied either
+        assert!(db
+            .apply_all(vec![
+                SyncOp::Create { uuid },
+                SyncOp::Update {
+                    uuid: Uuid::new_v4(),
+                    property: "title".into(),
+                    value: Some("my task".into()),
+                    timestamp: Utc::now(),
+                },
+            ])
+            .is_err());
+
+        assert_eq!(db.sorted_tasks(), vec![]);
+        assert_eq!(db.operations(), vec![]);
+    }
+
     #[test]
     fn test_add_undo_point() {
         let mut db = TaskDb::new_inmemory();
@@ -252,12 +452,78 @@ mod tests {
                             println!("  {:?} (ignored)", e);
                         }
                     },
-                    Action::Sync => db.sync(&mut server, false).unwrap(),
+                    Action::Sync => db.sync(&mut server, false, &mut |_| true).unwrap(),
                 }
             }
 
-            assert_eq!(dbs[0].sorted_tasks(), dbs[0].sorted_tasks());
+            assert_eq!(dbs[0].sorted_tasks(), dbs[1].sorted_tasks());
             assert_eq!(dbs[1].sorted_tasks(), dbs[2].sorted_tasks());
         }
     }
+
+    fn operation_strategy() -> impl Strategy<Value = SyncOp> {
+        let uuid = prop_oneof![
+            Just(Uuid::parse_str("83a2f9ef-f455-4195-b92e-a54c161eebfc").unwrap()),
+            Just(Uuid::parse_str("56e0be07-c61f-494c-a54c-bdcfdd52d2a7").unwrap()),
+            Just(Uuid::parse_str("4b7ed904-f7b0-4293-8a10-ad452422c7b3").unwrap()),
+        ];
+        prop_oneof![
+            uuid.clone().prop_map(|uuid| SyncOp::Create { uuid }),
+            uuid.clone().prop_map(|uuid| SyncOp::Delete { uuid }),
+            (
+                uuid,
+                "(title|project|status)",
+                proptest::option::of("[ab]"),
+                0i64..3,
+            )
+                .prop_map(|(uuid, property, value, secs)| SyncOp::Update {
+                    uuid,
+                    property,
+                    value,
+                    timestamp: Utc.timestamp(1_600_000_000 + secs, 0),
+                }),
+        ]
+    }
+
+    fn operation_sequence_strategy() -> impl Strategy<Value = Vec<SyncOp>> {
+        proptest::collection::vec(operation_strategy(), 0..8)
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig {
+          cases: 512, .. ProptestConfig::default()
+        })]
+        #[test]
+        // check that two replicas which share a common history, then diverge by applying
+        // arbitrary sequences of operations, converge again after syncing.  Operations that are
+        // invalid when applied locally are ignored.
+        fn divergent_replicas_converge(
+            base in operation_sequence_strategy(),
+            ops1 in operation_sequence_strategy(),
+            ops2 in operation_sequence_strategy(),
+        ) {
+            let mut server: Box<dyn Server> = Box::new(TestServer::new());
+            let mut db1 = newdb();
+            let mut db2 = newdb();
+
+            for op in base {
+                let _ = db1.apply(op);
+            }
+            db1.sync(&mut server, false, &mut |_| true).unwrap();
+            db2.sync(&mut server, false, &mut |_| true).unwrap();
+            assert_eq!(db1.sorted_tasks(), db2.sorted_tasks());
+
+            for op in ops1 {
+                let _ = db1.apply(op);
+            }
+            for op in ops2 {
+                let _ = db2.apply(op);
+            }
+
+            db1.sync(&mut server, false, &mut |_| true).unwrap();
+            db2.sync(&mut server, false, &mut |_| true).unwrap();
+            db1.sync(&mut server, false, &mut |_| true).unwrap();
+            assert_eq!(db1.sorted_tasks(), db2.sorted_tasks());
+        }
+    }
 }
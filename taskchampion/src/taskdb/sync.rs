@@ -2,6 +2,7 @@ use super::{apply, snapshot};
 use crate::server::{AddVersionResult, GetVersionResult, Server, SnapshotUrgency, SyncOp};
 use crate::storage::StorageTxn;
 use crate::Error;
+use chrono::Utc;
 use log::{info, trace, warn};
 use serde::{Deserialize, Serialize};
 use std::str;
@@ -11,16 +12,54 @@ struct Version {
     operations: Vec<SyncOp>,
 }
 
-/// Sync to the given server, pulling remote changes and pushing local changes.
+/// The progress of a synchronization, as reported to the callback given to
+/// [`Replica::sync_with_progress`](crate::Replica::sync_with_progress).
+///
+/// The server does not indicate how many versions remain to be downloaded, so the total amount of
+/// work is not known in advance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncProgress {
+    /// A snapshot of the task database is being downloaded and applied.  This occurs only for an
+    /// empty replica.
+    ApplyingSnapshot,
+    /// Versions are being downloaded from the server and applied; `versions` have been applied
+    /// so far.
+    Pulling { versions: usize },
+    /// A new version containing `operations` local operations is being sent to the server.
+    Pushing { operations: usize },
+    /// A snapshot of the task database is being sent to the server.  The new version has already
+    /// been accepted by the server at this point, so returning false skips the snapshot but does
+    /// not cancel the sync.
+    SendingSnapshot,
+}
+
+/// Sync to the given server, pulling remote changes and pushing local changes.  The `progress`
+/// function is called as the sync proceeds, and can return false to cancel it, in which case
+/// [`Error::SyncCancelled`] is returned and no changes are committed.  Cancellation is only
+/// possible until the new version is sent to the server; after that, the sync always completes
+/// so that the replica agrees with the server.
 pub(super) fn sync(
     server: &mut Box<dyn Server>,
     txn: &mut dyn StorageTxn,
     avoid_snapshots: bool,
+    progress: &mut dyn FnMut(SyncProgress) -> bool,
 ) -> anyhow::Result<()> {
+    let mut report = |p: SyncProgress| -> anyhow::Result<()> {
+        if progress(p) {
+            Ok(())
+        } else {
+            info!("sync cancelled");
+            Err(Error::SyncCancelled.into())
+        }
+    };
+
     // if this taskdb is entirely empty, then start by getting and applying a snapshot
     if txn.is_empty()? {
+        enter_span!("sync.apply_snapshot", bytes = tracing::field::Empty);
         trace!("storage is empty; attempting to apply a snapshot");
+        report(SyncProgress::ApplyingSnapshot)?;
         if let Some((version, snap)) = server.get_snapshot()? {
+            record_span!("bytes", snap.len());
             snapshot::apply_snapshot(txn, version, snap.as_ref())?;
             trace!("applied snapshot for version {}", version);
         }
@@ -41,27 +80,42 @@ pub(super) fn sync(
             .collect();
 
         // first pull changes and "rebase" on top of them
-        loop {
-            trace!("beginning sync inner loop");
-            if let GetVersionResult::Version {
-                version_id,
-                history_segment,
-                ..
-            } = server.get_child_version(base_version_id)?
-            {
-                let version_str = str::from_utf8(&history_segment).unwrap();
-                let version: Version = serde_json::from_str(version_str).unwrap();
-
-                // apply this verison and update base_version in storage
-                info!("applying version {:?} from server", version_id);
-                apply_version(txn, &mut local_ops, version)?;
-                txn.set_base_version(version_id)?;
-                base_version_id = version_id;
-            } else {
-                info!("no child versions of {:?}", base_version_id);
-                // at the moment, no more child versions, so we can try adding our own
-                break;
+        {
+            enter_span!(
+                "sync.pull",
+                versions = tracing::field::Empty,
+                bytes = tracing::field::Empty
+            );
+            let mut versions = 0;
+            let mut bytes = 0;
+            loop {
+                trace!("beginning sync inner loop");
+                report(SyncProgress::Pulling { versions })?;
+                if let GetVersionResult::Version {
+                    version_id,
+                    history_segment,
+                    ..
+                } = server.get_child_version(base_version_id)?
+                {
+                    bytes += history_segment.len();
+                    let version_str = str::from_utf8(&history_segment).unwrap();
+                    let version: Version = serde_json::from_str(version_str).unwrap();
+
+                    // apply this verison and update base_version in storage
+                    info!("applying version {:?} from server", version_id);
+                    apply_version(txn, &mut local_ops, version)?;
+                    txn.set_base_version(version_id)?;
+                    base_version_id = version_id;
+                    versions += 1;
+                } else {
+                    info!("no child versions of {:?}", base_version_id);
+                    // at the moment, no more child versions, so we can try adding our own
+                    break;
+                }
             }
+            trace!("pulled {} versions ({} bytes)", versions, bytes);
+            record_span!("versions", versions);
+            record_span!("bytes", bytes);
         }
 
         if local_ops.is_empty() {
@@ -70,13 +124,22 @@ pub(super) fn sync(
             break;
         }
 
+        enter_span!(
+            "sync.push",
+            operations = local_ops.len(),
+            bytes = tracing::field::Empty
+        );
         trace!("sending {} operations to the server", local_ops.len());
+        report(SyncProgress::Pushing {
+            operations: local_ops.len(),
+        })?;
 
         // now make a version of our local changes and push those
         let new_version = Version {
             operations: local_ops,
         };
-        let history_segment = serde_json::to_string(&new_version).unwrap().into();
+        let history_segment: Vec<u8> = serde_json::to_string(&new_version).unwrap().into();
+        record_span!("bytes", history_segment.len());
         info!("sending new version to server");
         let (res, snapshot_urgency) = server.add_version(base_version_id, history_segment)?;
         match res {
@@ -91,8 +154,16 @@ pub(super) fn sync(
                     SnapshotUrgency::Low
                 };
                 if snapshot_urgency >= base_urgency {
-                    let snapshot = snapshot::make_snapshot(txn)?;
-                    server.add_snapshot(new_version_id, snapshot)?;
+                    enter_span!("sync.send_snapshot", bytes = tracing::field::Empty);
+                    // the server already has the new version, so the sync must be committed
+                    // even if the caller asks to cancel; only the snapshot is skipped
+                    if progress(SyncProgress::SendingSnapshot) {
+                        let snapshot = snapshot::make_snapshot(txn)?;
+                        record_span!("bytes", snapshot.len());
+                        server.add_snapshot(new_version_id, snapshot)?;
+                    } else {
+                        info!("snapshot skipped at the caller's request");
+                    }
                 }
 
                 break;
@@ -113,6 +184,7 @@ pub(super) fn sync(
     }
 
     txn.set_operations(vec![])?;
+    txn.set_last_sync(Utc::now())?;
     txn.commit()?;
     Ok(())
 }
@@ -199,10 +271,16 @@ mod test {
         let mut server: Box<dyn Server> = TestServer::new().server();
 
         let mut db1 = newdb();
-        sync(&mut server, db1.storage.txn()?.as_mut(), false).unwrap();
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
 
         let mut db2 = newdb();
-        sync(&mut server, db2.storage.txn()?.as_mut(), false).unwrap();
+        sync(&mut server, db2.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
 
         // make some changes in parallel to db1 and db2..
         let uuid1 = Uuid::new_v4();
@@ -226,9 +304,18 @@ mod test {
         .unwrap();
 
         // and synchronize those around
-        sync(&mut server, db1.storage.txn()?.as_mut(), false).unwrap();
-        sync(&mut server, db2.storage.txn()?.as_mut(), false).unwrap();
-        sync(&mut server, db1.storage.txn()?.as_mut(), false).unwrap();
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
+        sync(&mut server, db2.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
         assert_eq!(db1.sorted_tasks(), db2.sorted_tasks());
 
         // now make updates to the same task on both sides
@@ -248,23 +335,56 @@ mod test {
         .unwrap();
 
         // and synchronize those around
-        sync(&mut server, db1.storage.txn()?.as_mut(), false).unwrap();
-        sync(&mut server, db2.storage.txn()?.as_mut(), false).unwrap();
-        sync(&mut server, db1.storage.txn()?.as_mut(), false).unwrap();
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
+        sync(&mut server, db2.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
         assert_eq!(db1.sorted_tasks(), db2.sorted_tasks());
 
         Ok(())
     }
 
+    #[test]
+    fn test_sync_records_last_sync() -> anyhow::Result<()> {
+        let mut server: Box<dyn Server> = TestServer::new().server();
+
+        let mut db = newdb();
+        assert_eq!(db.last_sync()?, None);
+
+        let before = Utc::now();
+        sync(&mut server, db.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
+        let last_sync = db.last_sync()?.unwrap();
+        assert!(last_sync >= before && last_sync <= Utc::now());
+
+        Ok(())
+    }
+
     #[test]
     fn test_sync_create_delete() -> anyhow::Result<()> {
         let mut server: Box<dyn Server> = TestServer::new().server();
 
         let mut db1 = newdb();
-        sync(&mut server, db1.storage.txn()?.as_mut(), false).unwrap();
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
 
         let mut db2 = newdb();
-        sync(&mut server, db2.storage.txn()?.as_mut(), false).unwrap();
+        sync(&mut server, db2.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
 
         // create and update a task..
         let uuid = Uuid::new_v4();
@@ -278,9 +398,18 @@ mod test {
         .unwrap();
 
         // and synchronize those around
-        sync(&mut server, db1.storage.txn()?.as_mut(), false).unwrap();
-        sync(&mut server, db2.storage.txn()?.as_mut(), false).unwrap();
-        sync(&mut server, db1.storage.txn()?.as_mut(), false).unwrap();
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
+        sync(&mut server, db2.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
         assert_eq!(db1.sorted_tasks(), db2.sorted_tasks());
 
         // delete and re-create the task on db1
@@ -303,9 +432,18 @@ mod test {
         })
         .unwrap();
 
-        sync(&mut server, db1.storage.txn()?.as_mut(), false).unwrap();
-        sync(&mut server, db2.storage.txn()?.as_mut(), false).unwrap();
-        sync(&mut server, db1.storage.txn()?.as_mut(), false).unwrap();
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
+        sync(&mut server, db2.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })
+        .unwrap();
         assert_eq!(db1.sorted_tasks(), db2.sorted_tasks());
 
         Ok(())
@@ -328,7 +466,9 @@ mod test {
         })?;
 
         test_server.set_snapshot_urgency(SnapshotUrgency::High);
-        sync(&mut server, db1.storage.txn()?.as_mut(), false)?;
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })?;
 
         // assert that a snapshot was added
         let base_version = db1.storage.txn()?.base_version()?;
@@ -347,7 +487,9 @@ mod test {
             value: Some("my first task, updated".into()),
             timestamp: Utc::now(),
         })?;
-        sync(&mut server, db1.storage.txn()?.as_mut(), false)?;
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })?;
 
         // delete the first version, so that db2 *must* initialize from
         // the snapshot
@@ -355,7 +497,9 @@ mod test {
 
         // sync to a new DB and check that we got the expected results
         let mut db2 = newdb();
-        sync(&mut server, db2.storage.txn()?.as_mut(), false)?;
+        sync(&mut server, db2.storage.txn()?.as_mut(), false, &mut |_| {
+            true
+        })?;
 
         let task = db2.get_task(uuid)?.unwrap();
         assert_eq!(task.get("title").unwrap(), "my first task, updated");
@@ -374,7 +518,10 @@ mod test {
         db1.apply(SyncOp::Create { uuid }).unwrap();
 
         test_server.set_snapshot_urgency(SnapshotUrgency::Low);
-        sync(&mut server, db1.storage.txn()?.as_mut(), true).unwrap();
+        sync(&mut server, db1.storage.txn()?.as_mut(), true, &mut |_| {
+            true
+        })
+        .unwrap();
 
         // assert that a snapshot was not added, because we indicated
         // we wanted to avoid snapshots and it was only low urgency
@@ -382,4 +529,115 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_sync_progress() -> anyhow::Result<()> {
+        let test_server = TestServer::new();
+        let mut server: Box<dyn Server> = test_server.server();
+
+        let mut db1 = newdb();
+        let uuid = Uuid::new_v4();
+        db1.apply(SyncOp::Create { uuid })?;
+        db1.add_undo_point()?;
+        test_server.set_snapshot_urgency(SnapshotUrgency::High);
+        let mut reports = vec![];
+        sync(&mut server, db1.storage.txn()?.as_mut(), false, &mut |p| {
+            reports.push(p);
+            true
+        })?;
+        assert_eq!(
+            reports,
+            vec![
+                SyncProgress::Pulling { versions: 0 },
+                SyncProgress::Pushing { operations: 1 },
+                SyncProgress::SendingSnapshot,
+            ]
+        );
+
+        // a new, empty replica applies the snapshot and then the versions after it
+        let mut db2 = newdb();
+        let mut reports = vec![];
+        sync(&mut server, db2.storage.txn()?.as_mut(), false, &mut |p| {
+            reports.push(p);
+            true
+        })?;
+        assert_eq!(
+            reports,
+            vec![
+                SyncProgress::ApplyingSnapshot,
+                SyncProgress::Pulling { versions: 0 },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_cancelled() -> anyhow::Result<()> {
+        let test_server = TestServer::new();
+        let mut server: Box<dyn Server> = test_server.server();
+
+        let mut db = newdb();
+        let uuid = Uuid::new_v4();
+        db.apply(SyncOp::Create { uuid })?;
+
+        // cancel when about to push
+        let err = sync(&mut server, db.storage.txn()?.as_mut(), false, &mut |p| {
+            !matches!(p, SyncProgress::Pushing { .. })
+        })
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast::<Error>().unwrap(),
+            Error::SyncCancelled
+        ));
+
+        // nothing was sent to the server, and the local operations remain
+        let mut server: Box<dyn Server> = test_server.server();
+        assert!(matches!(
+            server.get_child_version(crate::server::NIL_VERSION_ID)?,
+            GetVersionResult::NoSuchVersion
+        ));
+        assert_eq!(db.num_operations()?, 1);
+        assert_eq!(db.last_sync()?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_cancelled_at_snapshot() -> anyhow::Result<()> {
+        let test_server = TestServer::new();
+        let mut server: Box<dyn Server> = test_server.server();
+
+        let mut db = newdb();
+        let uuid = Uuid::new_v4();
+        db.apply(SyncOp::Create { uuid })?;
+        test_server.set_snapshot_urgency(SnapshotUrgency::High);
+
+        // asking to cancel once the version is sent only skips the snapshot
+        sync(&mut server, db.storage.txn()?.as_mut(), false, &mut |p| {
+            p != SyncProgress::SendingSnapshot
+        })?;
+        assert_eq!(test_server.snapshot(), None);
+        assert_eq!(db.num_operations()?, 0);
+        assert!(db.last_sync()?.is_some());
+
+        // syncing again pushes nothing, so the server has exactly one version
+        let mut reports = vec![];
+        sync(&mut server, db.storage.txn()?.as_mut(), false, &mut |p| {
+            reports.push(p);
+            true
+        })?;
+        assert_eq!(reports, vec![SyncProgress::Pulling { versions: 0 }]);
+
+        let version_id = match server.get_child_version(crate::server::NIL_VERSION_ID)? {
+            GetVersionResult::Version { version_id, .. } => version_id,
+            GetVersionResult::NoSuchVersion => panic!("version was not sent"),
+        };
+        assert!(matches!(
+            server.get_child_version(version_id)?,
+            GetVersionResult::NoSuchVersion
+        ));
+
+        Ok(())
+    }
 }
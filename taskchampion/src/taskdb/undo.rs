@@ -30,6 +30,30 @@ pub(super) fn undo(txn: &mut dyn StorageTxn) -> anyhow::Result<bool> {
     Ok(applied)
 }
 
+/// Get the local operations that `steps` calls to [`undo`] would reverse, in the order in which
+/// they were originally applied.  UndoPoints are not included.
+pub(super) fn get_undo_ops(
+    txn: &mut dyn StorageTxn,
+    steps: usize,
+) -> anyhow::Result<Vec<ReplicaOp>> {
+    let local_ops = txn.operations()?;
+    let mut start = local_ops.len();
+    let mut remaining = steps;
+
+    while start > 0 && remaining > 0 {
+        start -= 1;
+        if local_ops[start] == ReplicaOp::UndoPoint {
+            remaining -= 1;
+        }
+    }
+
+    Ok(local_ops[start..]
+        .iter()
+        .filter(|op| **op != ReplicaOp::UndoPoint)
+        .cloned()
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,4 +138,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_get_undo_ops() -> anyhow::Result<()> {
+        let mut db = TaskDb::new_inmemory();
+        let uuid1 = Uuid::new_v4();
+        let uuid2 = Uuid::new_v4();
+
+        db.add_undo_point()?;
+        db.apply(SyncOp::Create { uuid: uuid1 })?;
+        db.add_undo_point()?;
+        db.apply(SyncOp::Create { uuid: uuid2 })?;
+        db.apply(SyncOp::Delete { uuid: uuid1 })?;
+
+        let mut txn = db.storage.txn()?;
+        assert_eq!(get_undo_ops(txn.as_mut(), 0)?, vec![]);
+        assert_eq!(
+            get_undo_ops(txn.as_mut(), 1)?,
+            vec![
+                ReplicaOp::Create { uuid: uuid2 },
+                ReplicaOp::Delete {
+                    uuid: uuid1,
+                    old_task: Default::default()
+                },
+            ]
+        );
+        assert_eq!(get_undo_ops(txn.as_mut(), 2)?.len(), 3);
+        // more steps than there are undo points returns all operations
+        assert_eq!(get_undo_ops(txn.as_mut(), 10)?.len(), 3);
+
+        Ok(())
+    }
 }
@@ -5,57 +5,97 @@ use std::collections::HashSet;
 /// renumbers the existing working-set tasks to eliminate gaps, and also adds any tasks that
 /// are not already in the working set but should be.  The rebuild occurs in a single
 /// trasnsaction against the storage backend.
+///
+/// If the storage tracks the tasks changed since the last rebuild, only those tasks are examined,
+/// since no other task can have entered or left the working set.  This assumes that
+/// `in_working_set` is the same function in every rebuild.  Otherwise, every task is examined.
 pub fn rebuild<F>(txn: &mut dyn StorageTxn, in_working_set: F, renumber: bool) -> anyhow::Result<()>
 where
     F: Fn(&TaskMap) -> bool,
 {
-    let mut new_ws = vec![None]; // index 0 is always None
-    let mut seen = HashSet::new();
+    let old_ws = txn.get_working_set()?;
+
+    // Determine the tasks that should be in the working set (`members`, in the order in which
+    // they should be added), and those that should not (`non_members`).  Tasks in neither set
+    // are unchanged.
+    let mut members = vec![];
+    let mut non_members = HashSet::new();
+    if let Some(changed) = txn.changed_tasks()? {
+        log::debug!(
+            "Rebuilding working set from {} changed tasks",
+            changed.len()
+        );
+        record_span!("tasks", changed.len());
+        for uuid in changed {
+            match txn.get_task(uuid)? {
+                Some(task) if in_working_set(&task) => members.push(uuid),
+                _ => {
+                    non_members.insert(uuid);
+                }
+            }
+        }
+    } else {
+        log::debug!("Rebuilding working set from all tasks");
+        let all_tasks = txn.all_tasks()?;
+        record_span!("tasks", all_tasks.len());
+        let mut member_set = HashSet::new();
+        for (uuid, task) in all_tasks {
+            if in_working_set(&task) {
+                members.push(uuid);
+                member_set.insert(uuid);
+            }
+        }
+        // anything else in the working set, including deleted tasks, is not a member
+        non_members.extend(old_ws.iter().flatten().filter(|u| !member_set.contains(u)));
+    }
 
     // The goal here is for existing working-set items to be "compressed' down to index 1, so
     // we begin by scanning the current working set and inserting any tasks that should still
     // be in the set into new_ws, implicitly dropping any tasks that are no longer in the
     // working set.
-    for elt in txn.get_working_set()?.drain(1..) {
-        if let Some(uuid) = elt {
-            if let Some(task) = txn.get_task(uuid)? {
-                if in_working_set(&task) {
-                    new_ws.push(Some(uuid));
-                    seen.insert(uuid);
-                    continue;
-                }
+    let mut new_ws = vec![None]; // index 0 is always None
+    let mut seen = HashSet::new();
+    for elt in old_ws.iter().skip(1) {
+        match elt {
+            Some(uuid) if !non_members.contains(uuid) => {
+                new_ws.push(Some(*uuid));
+                seen.insert(*uuid);
             }
+            // if we are not renumbering, then insert a blank working-set entry here
+            _ if !renumber => new_ws.push(None),
+            _ => {}
         }
+    }
 
-        // if we are not renumbering, then insert a blank working-set entry here
-        if !renumber {
-            new_ws.push(None);
+    // Now add the tasks that should be in this list but are not at the end of the list, whether
+    // renumbering or not
+    for uuid in members {
+        if seen.insert(uuid) {
+            new_ws.push(Some(uuid));
         }
     }
 
-    // if renumbering, clear the working set and re-add
     if renumber {
-        txn.clear_working_set()?;
-        for elt in new_ws.drain(1..new_ws.len()).flatten() {
-            txn.add_to_working_set(elt)?;
+        // if renumbering, clear the working set and re-add, unless nothing has changed
+        if new_ws != old_ws {
+            txn.clear_working_set()?;
+            for elt in new_ws.drain(1..).flatten() {
+                txn.add_to_working_set(elt)?;
+            }
         }
     } else {
-        // ..otherwise, just clear the None items determined above from the working set
-        for (i, elt) in new_ws.iter().enumerate().skip(1) {
-            if elt.is_none() {
+        // ..otherwise, clear the items removed above, and add the new items
+        for (i, (old, new)) in old_ws.iter().zip(new_ws.iter()).enumerate().skip(1) {
+            if old.is_some() && new.is_none() {
                 txn.set_working_set_item(i, None)?;
             }
         }
-    }
-
-    // Now go hunting for tasks that should be in this list but are not, adding them at the
-    // end of the list, whether renumbering or not
-    for (uuid, task) in txn.all_tasks()? {
-        if !seen.contains(&uuid) && in_working_set(&task) {
-            txn.add_to_working_set(uuid)?;
+        for elt in new_ws.drain(old_ws.len()..).flatten() {
+            txn.add_to_working_set(elt)?;
         }
     }
 
+    txn.clear_changed_tasks()?;
     txn.commit()?;
     Ok(())
 }
@@ -164,4 +204,49 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn rebuild_working_set_incremental() -> anyhow::Result<()> {
+        let mut db = TaskDb::new_inmemory();
+        let in_working_set = |t: &TaskMap| t.get("status").map(|s| s.as_str()) == Some("pending");
+        let set_status = |db: &mut TaskDb, uuid: Uuid, status: &str| {
+            db.apply(SyncOp::Update {
+                uuid,
+                property: String::from("status"),
+                value: Some(status.into()),
+                timestamp: Utc::now(),
+            })
+        };
+
+        let uuid1 = Uuid::new_v4();
+        let uuid2 = Uuid::new_v4();
+        let uuid3 = Uuid::new_v4();
+        db.apply(SyncOp::Create { uuid: uuid1 })?;
+        set_status(&mut db, uuid1, "pending")?;
+
+        // the first rebuild examines all tasks
+        rebuild(db.storage.txn()?.as_mut(), in_working_set, false)?;
+        assert_eq!(db.working_set()?, vec![None, Some(uuid1)]);
+        assert_eq!(db.storage.txn()?.changed_tasks()?, Some(vec![]));
+
+        db.apply(SyncOp::Create { uuid: uuid2 })?;
+        set_status(&mut db, uuid2, "pending")?;
+        rebuild(db.storage.txn()?.as_mut(), in_working_set, false)?;
+        assert_eq!(db.working_set()?, vec![None, Some(uuid1), Some(uuid2)]);
+
+        // subsequent rebuilds examine only changed tasks
+        set_status(&mut db, uuid1, "completed")?;
+        db.apply(SyncOp::Create { uuid: uuid3 })?;
+        set_status(&mut db, uuid3, "pending")?;
+        rebuild(db.storage.txn()?.as_mut(), in_working_set, false)?;
+        assert_eq!(
+            db.working_set()?,
+            vec![None, None, Some(uuid2), Some(uuid3)]
+        );
+
+        rebuild(db.storage.txn()?.as_mut(), in_working_set, true)?;
+        assert_eq!(db.working_set()?, vec![None, Some(uuid2), Some(uuid3)]);
+
+        Ok(())
+    }
 }
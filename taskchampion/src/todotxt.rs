@@ -0,0 +1,386 @@
+/*!
+
+This module converts tasks to and from the [todo.txt](https://github.com/todotxt/todo.txt)
+format, in which each task is a single line such as
+
+```text
+(A) 2022-01-01 call the plumber +home @phone due:2022-01-05
+```
+
+The todo.txt format carries less information than a TaskChampion task, so the conversion is
+lossy:
+
+ * Priorities `H`, `M`, and `L` correspond to `(A)`, `(B)`, and `(C)`.  Other single-letter
+   priorities are used unchanged, and any other priority is omitted.
+ * The task's project is written as a `+project`, and its user tags as `@context`s.  When parsing,
+   the first `+project` becomes the task's project, and any others remain in the description.
+ * The completion date, creation date, and `due:` date are dates, without a time of day, in UTC.
+ * Deleted tasks are written as completed, since todo.txt has no equivalent.
+ * Completed tasks with a priority carry it as `pri:A`, following a common todo.txt convention.
+
+```
+use taskchampion::todotxt::TodoTxt;
+use taskchampion::{Replica, Status, StorageConfig};
+
+let mut replica = Replica::new(StorageConfig::InMemory.into_storage()?);
+let todo = TodoTxt::parse("(A) call the plumber +home @phone due:2022-01-05").unwrap();
+assert_eq!(todo.description, "call the plumber");
+
+let task = replica.new_task(Status::Pending, todo.description.clone())?;
+let mut task = task.into_mut(&mut replica);
+todo.apply(&mut task)?;
+let task = task.into_immut();
+assert_eq!(task.get_priority(), "H");
+assert_eq!(task.get_project(), Some("home"));
+# Ok::<(), taskchampion::Error>(())
+```
+
+*/
+
+use crate::errors::Result;
+use crate::task::{Status, Tag, Task, TaskMut, Timestamp};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+/// The format of dates in todo.txt
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// A task, as represented by a line in a todo.txt file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TodoTxt {
+    /// True if the line begins with `x`, marking the task as completed
+    pub completed: bool,
+    /// The priority, an uppercase letter
+    pub priority: Option<char>,
+    /// The date on which the task was completed; this is only present for completed tasks
+    pub completion_date: Option<NaiveDate>,
+    /// The date on which the task was created
+    pub creation_date: Option<NaiveDate>,
+    /// The remaining words of the line, excluding those interpreted as other fields
+    pub description: String,
+    /// The task's project, from the first `+project` in the line
+    pub project: Option<String>,
+    /// The task's contexts, from the `@context` words in the line
+    pub contexts: Vec<Tag>,
+    /// The date on which the task is due, from a `due:` key
+    pub due: Option<NaiveDate>,
+}
+
+impl TodoTxt {
+    /// Parse a line in the todo.txt format.  This returns None for blank lines.
+    ///
+    /// Parsing is permissive: any words that cannot be interpreted, such as an invalid
+    /// `due:` date or a context that is not a valid tag, remain in the description.
+    pub fn parse(line: &str) -> Option<TodoTxt> {
+        let mut words = line.split_whitespace().peekable();
+        words.peek()?;
+
+        let mut todo = TodoTxt::default();
+        let next_date = |words: &mut std::iter::Peekable<std::str::SplitWhitespace>| {
+            let date = words
+                .peek()
+                .and_then(|w| NaiveDate::parse_from_str(w, DATE_FORMAT).ok());
+            if date.is_some() {
+                words.next();
+            }
+            date
+        };
+
+        if words.peek() == Some(&"x") {
+            words.next();
+            todo.completed = true;
+            todo.completion_date = next_date(&mut words);
+            if todo.completion_date.is_some() {
+                todo.creation_date = next_date(&mut words);
+            }
+        } else {
+            if let Some(word) = words.peek() {
+                todo.priority = parse_priority(word);
+                if todo.priority.is_some() {
+                    words.next();
+                }
+            }
+            todo.creation_date = next_date(&mut words);
+        }
+
+        let mut description = vec![];
+        for word in words {
+            if let Some(project) = word.strip_prefix('+') {
+                if todo.project.is_none() && !project.is_empty() {
+                    todo.project = Some(project.to_owned());
+                    continue;
+                }
+            } else if let Some(context) = word.strip_prefix('@') {
+                if let Ok(tag) = Tag::from_str(context) {
+                    if tag.is_user() {
+                        todo.contexts.push(tag);
+                        continue;
+                    }
+                }
+            } else if let Some(due) = word.strip_prefix("due:") {
+                if let Ok(due) = NaiveDate::parse_from_str(due, DATE_FORMAT) {
+                    todo.due = Some(due);
+                    continue;
+                }
+            } else if let Some(pri) = word.strip_prefix("pri:") {
+                if let Some(priority) = single_letter(pri) {
+                    todo.priority = Some(priority);
+                    continue;
+                }
+            }
+            description.push(word);
+        }
+        todo.description = description.join(" ");
+
+        Some(todo)
+    }
+
+    /// Represent the given task in the todo.txt format.
+    pub fn from_task(task: &Task) -> TodoTxt {
+        let priority = match task.get_priority() {
+            "H" => Some('A'),
+            "M" => Some('B'),
+            "L" => Some('C'),
+            p => single_letter(p),
+        };
+        let completed = matches!(task.get_status(), Status::Completed | Status::Deleted);
+        let mut contexts: Vec<Tag> = task.get_tags().filter(|t| t.is_user()).collect();
+        contexts.sort();
+        TodoTxt {
+            completed,
+            priority,
+            completion_date: if completed {
+                task.get_end().map(|ts| ts.date().naive_utc())
+            } else {
+                None
+            },
+            creation_date: task.get_entry().map(|ts| ts.date().naive_utc()),
+            description: task.get_description().replace('\n', " "),
+            project: task.get_project().map(|p| p.to_owned()),
+            contexts,
+            due: task.get_due().map(|ts| ts.date().naive_utc()),
+        }
+    }
+
+    /// Apply the properties of this todo.txt task to the given task, setting its status,
+    /// priority, dates, project, and tags.  The task's description is not changed.
+    pub fn apply(&self, task: &mut TaskMut) -> Result<()> {
+        if self.completed {
+            task.set_status(Status::Completed)?;
+            if let Some(date) = self.completion_date {
+                task.set_end(Some(timestamp(date)))?;
+            }
+        } else {
+            task.set_status(Status::Pending)?;
+        }
+        if let Some(priority) = self.priority {
+            let priority = match priority {
+                'A' => "H".to_owned(),
+                'B' => "M".to_owned(),
+                'C' => "L".to_owned(),
+                p => p.to_string(),
+            };
+            task.set_priority(priority)?;
+        }
+        if let Some(date) = self.creation_date {
+            task.set_entry(Some(timestamp(date)))?;
+        }
+        if let Some(due) = self.due {
+            task.set_due(Some(timestamp(due)))?;
+        }
+        if let Some(ref project) = self.project {
+            task.set_project(Some(project.clone()))?;
+        }
+        for tag in &self.contexts {
+            task.add_tag(tag)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TodoTxt {
+    /// Format this task as a todo.txt line, without a trailing newline.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut words = vec![];
+        if self.completed {
+            words.push("x".to_owned());
+            // the creation date can only be given after a completion date
+            if let Some(date) = self.completion_date {
+                words.push(date.format(DATE_FORMAT).to_string());
+                if let Some(date) = self.creation_date {
+                    words.push(date.format(DATE_FORMAT).to_string());
+                }
+            }
+        } else {
+            if let Some(priority) = self.priority {
+                words.push(format!("({})", priority));
+            }
+            if let Some(date) = self.creation_date {
+                words.push(date.format(DATE_FORMAT).to_string());
+            }
+        }
+        if !self.description.is_empty() {
+            words.push(self.description.clone());
+        }
+        if let Some(ref project) = self.project {
+            words.push(format!("+{}", project));
+        }
+        for context in &self.contexts {
+            words.push(format!("@{}", context));
+        }
+        if let Some(due) = self.due {
+            words.push(format!("due:{}", due.format(DATE_FORMAT)));
+        }
+        if self.completed {
+            if let Some(priority) = self.priority {
+                words.push(format!("pri:{}", priority));
+            }
+        }
+        f.write_str(&words.join(" "))
+    }
+}
+
+/// Parse a priority of the form `(A)`
+fn parse_priority(word: &str) -> Option<char> {
+    word.strip_prefix('(')
+        .and_then(|w| w.strip_suffix(')'))
+        .and_then(single_letter)
+}
+
+/// Parse a single uppercase letter
+fn single_letter(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_uppercase() => Some(c),
+        _ => None,
+    }
+}
+
+/// Convert a date to a timestamp at midnight UTC
+fn timestamp(date: NaiveDate) -> Timestamp {
+    DateTime::from_utc(date.and_hms(0, 0, 0), Utc)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Replica;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use std::convert::TryInto;
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        Some(NaiveDate::from_ymd(y, m, d))
+    }
+
+    fn tag(s: &str) -> Tag {
+        s.try_into().unwrap()
+    }
+
+    #[test]
+    fn parse_blank() {
+        assert_eq!(TodoTxt::parse(""), None);
+        assert_eq!(TodoTxt::parse("   "), None);
+    }
+
+    #[test]
+    fn parse_pending() {
+        assert_eq!(
+            TodoTxt::parse("(A) 2022-01-01 call the plumber +home @phone due:2022-01-05"),
+            Some(TodoTxt {
+                completed: false,
+                priority: Some('A'),
+                completion_date: None,
+                creation_date: date(2022, 1, 1),
+                description: "call the plumber".into(),
+                project: Some("home".into()),
+                contexts: vec![tag("phone")],
+                due: date(2022, 1, 5),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_completed() {
+        assert_eq!(
+            TodoTxt::parse("x 2022-01-03 2022-01-01 mow the lawn pri:B"),
+            Some(TodoTxt {
+                completed: true,
+                priority: Some('B'),
+                completion_date: date(2022, 1, 3),
+                creation_date: date(2022, 1, 1),
+                description: "mow the lawn".into(),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[rstest]
+    #[case::second_project("a +one +two", "a +two")]
+    #[case::invalid_context("a @123", "a @123")]
+    #[case::synthetic_context("a @PENDING", "a @PENDING")]
+    #[case::invalid_due("a due:tomorrow", "a due:tomorrow")]
+    #[case::lowercase_priority("(a) b", "(a) b")]
+    #[case::x_in_description("xylophone lesson", "xylophone lesson")]
+    fn parse_description(#[case] line: &str, #[case] description: &str) {
+        assert_eq!(TodoTxt::parse(line).unwrap().description, description);
+    }
+
+    #[rstest]
+    #[case::pending("(A) 2022-01-01 call the plumber +home @phone due:2022-01-05")]
+    #[case::completed("x 2022-01-03 2022-01-01 mow the lawn pri:B")]
+    #[case::completed_no_dates("x mow the lawn")]
+    #[case::minimal("mow the lawn")]
+    fn round_trip(#[case] line: &str) {
+        assert_eq!(TodoTxt::parse(line).unwrap().to_string(), line);
+    }
+
+    #[test]
+    fn from_task() {
+        let mut rep = Replica::new_inmemory();
+        let mut t = rep
+            .new_task(Status::Completed, "mow\nthe lawn".into())
+            .unwrap()
+            .into_mut(&mut rep);
+        t.set_entry(Some(Utc.ymd(2022, 1, 1).and_hms(10, 0, 0)))
+            .unwrap();
+        t.set_end(Some(Utc.ymd(2022, 1, 3).and_hms(23, 0, 0)))
+            .unwrap();
+        t.set_due(Some(Utc.ymd(2022, 1, 2).and_hms(12, 0, 0)))
+            .unwrap();
+        t.set_priority("L".into()).unwrap();
+        t.set_project(Some("home.garden".into())).unwrap();
+        t.add_tag(&tag("outside")).unwrap();
+        t.add_tag(&tag("errand")).unwrap();
+        let t = t.into_immut();
+
+        assert_eq!(
+            TodoTxt::from_task(&t).to_string(),
+            "x 2022-01-03 2022-01-01 mow the lawn +home.garden @errand @outside \
+             due:2022-01-02 pri:C"
+        );
+    }
+
+    #[test]
+    fn apply() {
+        let mut rep = Replica::new_inmemory();
+        let todo =
+            TodoTxt::parse("x 2022-01-03 2022-01-01 mow the lawn +home @outside pri:D").unwrap();
+        let t = rep
+            .new_task(Status::Pending, todo.description.clone())
+            .unwrap();
+        let mut t = t.into_mut(&mut rep);
+        todo.apply(&mut t).unwrap();
+        let t = t.into_immut();
+
+        assert_eq!(t.get_status(), Status::Completed);
+        assert_eq!(t.get_description(), "mow the lawn");
+        assert_eq!(t.get_priority(), "D");
+        assert_eq!(t.get_entry(), Some(Utc.ymd(2022, 1, 1).and_hms(0, 0, 0)));
+        assert_eq!(t.get_end(), Some(Utc.ymd(2022, 1, 3).and_hms(0, 0, 0)));
+        assert_eq!(t.get_project(), Some("home"));
+        assert!(t.has_tag(&tag("outside")));
+    }
+}
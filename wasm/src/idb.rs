@@ -0,0 +1,252 @@
+use crate::storage::{OperationsUpdate, Persist, Snapshot, Update};
+use anyhow::{anyhow, Context, Result};
+use js_sys::{Array, Promise};
+use std::future::Future;
+use taskchampion::chrono::{DateTime, Utc};
+use taskchampion::Uuid;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    console, DomException, Event, IdbDatabase, IdbFactory, IdbObjectStoreParameters,
+    IdbOpenDbRequest, IdbRequest, IdbTransactionMode,
+};
+
+/// The version of the database schema, incremented when object stores are added or changed.
+const VERSION: u32 = 1;
+
+/// Tasks, keyed by UUID string, with their TaskMaps as JSON
+const TASKS: &str = "tasks";
+/// Operations, in order, each as JSON
+const OPERATIONS: &str = "operations";
+/// Individual values, keyed by the names below
+const META: &str = "meta";
+
+const BASE_VERSION: &str = "base_version";
+const LAST_SYNC: &str = "last_sync";
+const WORKING_SET: &str = "working_set";
+
+/// An open IndexedDB database containing a replica.
+pub(crate) struct Database {
+    db: IdbDatabase,
+    /// Writes are not awaited, so their errors are reported by this handler.
+    on_error: Closure<dyn FnMut(Event)>,
+}
+
+impl Database {
+    /// Open the database with the given name, creating it if necessary.
+    pub(crate) async fn open(name: &str) -> Result<Database> {
+        // `indexedDB` is available in both windows and workers
+        let factory: IdbFactory = js_sys::Reflect::get(&js_sys::global(), &"indexedDB".into())
+            .ok()
+            .and_then(|f| f.dyn_into().ok())
+            .ok_or_else(|| anyhow!("IndexedDB is not available"))?;
+        let request = factory.open_with_u32(name, VERSION).map_err(js_error)?;
+
+        let upgrade_request = request.clone();
+        let on_upgrade = Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+            if let Err(e) = create_stores(&upgrade_request) {
+                console::error_1(&format!("creating IndexedDB object stores: {:#}", e).into());
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade.as_ref().unchecked_ref()));
+        let result = complete(request.clone().into()).await;
+        request.set_onupgradeneeded(None);
+        drop(on_upgrade);
+
+        let db = result
+            .with_context(|| format!("opening IndexedDB database {:?}", name))?
+            .unchecked_into();
+        let on_error = Closure::<dyn FnMut(Event)>::new(|event: Event| {
+            console::error_2(&"writing to IndexedDB failed".into(), &event);
+        });
+        Ok(Database { db, on_error })
+    }
+
+    /// Read the entire contents of the database.
+    pub(crate) async fn load(&self) -> Result<Snapshot> {
+        let txn = self
+            .db
+            .transaction_with_str_sequence(&stores())
+            .map_err(js_error)?;
+        let tasks = txn.object_store(TASKS).map_err(js_error)?;
+        let meta = txn.object_store(META).map_err(js_error)?;
+
+        // issue all of the requests before waiting for any of them, so that they are all part of
+        // the same transaction
+        let task_keys = complete(tasks.get_all_keys().map_err(js_error)?);
+        let task_values = complete(tasks.get_all().map_err(js_error)?);
+        let operations = complete(
+            txn.object_store(OPERATIONS)
+                .map_err(js_error)?
+                .get_all()
+                .map_err(js_error)?,
+        );
+        let base_version = complete(meta.get(&BASE_VERSION.into()).map_err(js_error)?);
+        let last_sync = complete(meta.get(&LAST_SYNC.into()).map_err(js_error)?);
+        let working_set = complete(meta.get(&WORKING_SET.into()).map_err(js_error)?);
+
+        let task_keys: Array = task_keys.await?.unchecked_into();
+        let task_values: Array = task_values.await?.unchecked_into();
+        let mut snapshot = Snapshot::default();
+        for (key, value) in task_keys.iter().zip(task_values.iter()) {
+            let uuid = Uuid::parse_str(&string(key)?)?;
+            snapshot
+                .tasks
+                .push((uuid, serde_json::from_str(&string(value)?)?));
+        }
+        let operations: Array = operations.await?.unchecked_into();
+        for op in operations.iter() {
+            snapshot
+                .operations
+                .push(serde_json::from_str(&string(op)?)?);
+        }
+        if let Some(base_version) = optional_string(base_version.await?)? {
+            snapshot.base_version = Some(Uuid::parse_str(&base_version)?);
+        }
+        if let Some(last_sync) = optional_string(last_sync.await?)? {
+            snapshot.last_sync =
+                Some(DateTime::parse_from_rfc3339(&last_sync)?.with_timezone(&Utc));
+        }
+        if let Some(working_set) = optional_string(working_set.await?)? {
+            snapshot.working_set = Some(serde_json::from_str(&working_set)?);
+        }
+        Ok(snapshot)
+    }
+
+    /// Wait until all writes made so far are complete.
+    pub(crate) async fn flush(&self) -> Result<()> {
+        // a transaction does not start until all earlier read-write transactions with overlapping
+        // scope have finished
+        let txn = self
+            .db
+            .transaction_with_str_sequence(&stores())
+            .map_err(js_error)?;
+        let meta = txn.object_store(META).map_err(js_error)?;
+        complete(meta.count().map_err(js_error)?).await?;
+        Ok(())
+    }
+}
+
+impl Persist for Database {
+    fn persist(&self, update: Update) -> Result<()> {
+        let txn = self
+            .db
+            .transaction_with_str_sequence_and_mode(&stores(), IdbTransactionMode::Readwrite)
+            .map_err(js_error)?;
+        txn.set_onerror(Some(self.on_error.as_ref().unchecked_ref()));
+        txn.set_onabort(Some(self.on_error.as_ref().unchecked_ref()));
+
+        // the transaction commits on its own once these requests are complete
+        let tasks = txn.object_store(TASKS).map_err(js_error)?;
+        for (uuid, task) in update.tasks {
+            let key = JsValue::from(uuid.to_string());
+            match task {
+                Some(task) => tasks.put_with_key(&json(&task)?, &key),
+                None => tasks.delete(&key),
+            }
+            .map_err(js_error)?;
+        }
+
+        let operations = txn.object_store(OPERATIONS).map_err(js_error)?;
+        let append = match update.operations {
+            OperationsUpdate::Unchanged => vec![],
+            OperationsUpdate::Append(ops) => ops,
+            OperationsUpdate::Replace(ops) => {
+                operations.clear().map_err(js_error)?;
+                ops
+            }
+        };
+        for op in append {
+            operations.add(&json(&op)?).map_err(js_error)?;
+        }
+
+        let meta = txn.object_store(META).map_err(js_error)?;
+        if let Some(base_version) = update.base_version {
+            meta.put_with_key(&base_version.to_string().into(), &BASE_VERSION.into())
+                .map_err(js_error)?;
+        }
+        if let Some(last_sync) = update.last_sync {
+            meta.put_with_key(&last_sync.to_rfc3339().into(), &LAST_SYNC.into())
+                .map_err(js_error)?;
+        }
+        if let Some(working_set) = update.working_set {
+            meta.put_with_key(&json(&working_set)?, &WORKING_SET.into())
+                .map_err(js_error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Create the object stores in a new database, during an `upgradeneeded` event.
+fn create_stores(request: &IdbOpenDbRequest) -> Result<()> {
+    let db: IdbDatabase = request.result().map_err(js_error)?.unchecked_into();
+    db.create_object_store(TASKS).map_err(js_error)?;
+    let params = IdbObjectStoreParameters::new();
+    params.set_auto_increment(true);
+    db.create_object_store_with_optional_parameters(OPERATIONS, &params)
+        .map_err(js_error)?;
+    db.create_object_store(META).map_err(js_error)?;
+    Ok(())
+}
+
+/// The names of all object stores, for the scope of a transaction.
+fn stores() -> Array {
+    [TASKS, OPERATIONS, META]
+        .iter()
+        .map(|name| JsValue::from_str(name))
+        .collect()
+}
+
+/// Wait for a request to complete, returning its result.  The request's handlers are set
+/// immediately, so the returned future may be awaited later.
+fn complete(request: IdbRequest) -> impl Future<Output = Result<JsValue>> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    async move {
+        let res = JsFuture::from(promise).await;
+        request.set_onsuccess(None);
+        request.set_onerror(None);
+        match res {
+            Ok(_) => request.result().map_err(js_error),
+            Err(_) => Err(match request.error() {
+                Ok(Some(e)) => js_error(e.into()),
+                _ => anyhow!("IndexedDB request failed"),
+            }),
+        }
+    }
+}
+
+/// Serialize a value as a JSON string.
+fn json<T: serde::Serialize>(value: &T) -> Result<JsValue> {
+    Ok(serde_json::to_string(value)?.into())
+}
+
+/// Get a string stored in the database.
+fn string(value: JsValue) -> Result<String> {
+    value
+        .as_string()
+        .ok_or_else(|| anyhow!("unexpected value in IndexedDB: {:?}", value))
+}
+
+/// Get a string stored in the database, or None if there is no value.
+fn optional_string(value: JsValue) -> Result<Option<String>> {
+    if value.is_undefined() {
+        Ok(None)
+    } else {
+        string(value).map(Some)
+    }
+}
+
+/// Convert an exception from JavaScript into an error.
+fn js_error(value: JsValue) -> anyhow::Error {
+    if let Some(e) = value.dyn_ref::<DomException>() {
+        anyhow!("{}: {}", e.name(), e.message())
+    } else if let Some(e) = value.dyn_ref::<js_sys::Error>() {
+        anyhow!("{}", String::from(e.message()))
+    } else {
+        anyhow!("{:?}", value)
+    }
+}
@@ -0,0 +1,22 @@
+//! JavaScript bindings for TaskChampion, built with
+//! [wasm-bindgen](https://rustwasm.github.io/docs/wasm-bindgen/).
+//!
+//! The JavaScript API mirrors the Rust API of the `taskchampion` crate, with UUIDs represented as
+//! strings and timestamps as `Date` values.  Replicas can be kept in memory or stored in the
+//! browser's IndexedDB; see [`IndexedDbStorage`] for how the latter works.  Synchronization with a
+//! server is not yet supported.
+//!
+//! Build the package with [wasm-pack](https://rustwasm.github.io/wasm-pack/), e.g.,
+//! `wasm-pack build --target web` in this directory.
+
+mod idb;
+mod replica;
+mod status;
+mod storage;
+mod task;
+mod util;
+
+pub use replica::Replica;
+pub use status::Status;
+pub use storage::{Flusher, IndexedDbStorage};
+pub use task::Task;
@@ -0,0 +1,136 @@
+use crate::storage::{Flusher, IndexedDbStorage};
+use crate::util::{into_jserr, uuid_from_str};
+use crate::{Status, Task};
+use js_sys::{Array, Promise};
+use taskchampion::{Replica as TCReplica, StorageConfig};
+use wasm_bindgen::prelude::*;
+
+/// A replica of the task database.  See the Rust documentation for
+/// [`taskchampion::Replica`](https://docs.rs/taskchampion/latest/taskchampion/struct.Replica.html).
+#[wasm_bindgen]
+pub struct Replica {
+    pub(crate) replica: TCReplica,
+    /// Set for replicas stored in IndexedDB
+    flusher: Option<Flusher>,
+}
+
+#[wasm_bindgen]
+impl Replica {
+    /// Create a new replica that is stored in memory and discarded when it is no longer used.
+    #[wasm_bindgen(js_name = inMemory)]
+    pub fn in_memory() -> Result<Replica, JsValue> {
+        let storage = StorageConfig::InMemory.into_storage().map_err(into_jserr)?;
+        Ok(Replica {
+            replica: TCReplica::new(storage),
+            flusher: None,
+        })
+    }
+
+    /// Open the replica stored in the IndexedDB database with the given name, creating it if it
+    /// does not exist.  Changes are written to the database in the background; use `flush` to
+    /// wait for them to complete.
+    #[wasm_bindgen(js_name = openIndexedDb)]
+    pub async fn open_indexed_db(name: String) -> Result<Replica, JsValue> {
+        let storage = IndexedDbStorage::open(&name).await.map_err(into_jserr)?;
+        let flusher = storage.flusher();
+        Ok(Replica {
+            replica: TCReplica::new(Box::new(storage)),
+            flusher: Some(flusher),
+        })
+    }
+
+    /// Create a new task with the given status and description.
+    #[wasm_bindgen(js_name = newTask)]
+    pub fn new_task(&mut self, status: Status, description: String) -> Result<Task, JsValue> {
+        let task = self
+            .replica
+            .new_task(status.into_tc()?, description)
+            .map_err(into_jserr)?;
+        Ok(Task(task))
+    }
+
+    /// Create a new task with the given UUID, or get the existing task if one exists.  This is
+    /// useful for importing tasks from another source.
+    #[wasm_bindgen(js_name = importTaskWithUuid)]
+    pub fn import_task_with_uuid(&mut self, uuid: &str) -> Result<Task, JsValue> {
+        let task = self
+            .replica
+            .import_task_with_uuid(uuid_from_str(uuid)?)
+            .map_err(into_jserr)?;
+        Ok(Task(task))
+    }
+
+    /// Get all tasks, as an array in no particular order.
+    #[wasm_bindgen(js_name = allTasks)]
+    pub fn all_tasks(&mut self) -> Result<Array, JsValue> {
+        Ok(self
+            .replica
+            .all_tasks()
+            .map_err(into_jserr)?
+            .into_values()
+            .map(|task| JsValue::from(Task(task)))
+            .collect())
+    }
+
+    /// Get the UUIDs of all tasks.
+    #[wasm_bindgen(js_name = allTaskUuids)]
+    pub fn all_task_uuids(&mut self) -> Result<Vec<String>, JsValue> {
+        Ok(self
+            .replica
+            .all_task_uuids()
+            .map_err(into_jserr)?
+            .into_iter()
+            .map(|uuid| uuid.to_string())
+            .collect())
+    }
+
+    /// Get the task with the given UUID, or undefined if it does not exist.
+    #[wasm_bindgen(js_name = getTask)]
+    pub fn get_task(&mut self, uuid: &str) -> Result<Option<Task>, JsValue> {
+        Ok(self
+            .replica
+            .get_task(uuid_from_str(uuid)?)
+            .map_err(into_jserr)?
+            .map(Task))
+    }
+
+    /// Rebuild the working set, so that it contains exactly the pending tasks.  If `renumber` is
+    /// true, existing tasks may be moved to new indices.
+    #[wasm_bindgen(js_name = rebuildWorkingSet)]
+    pub fn rebuild_working_set(&mut self, renumber: bool) -> Result<(), JsValue> {
+        self.replica
+            .rebuild_working_set(renumber)
+            .map_err(into_jserr)
+    }
+
+    /// Add an undo point, if one has not already been added by this replica.  If `force` is
+    /// true, an undo point is always added.
+    #[wasm_bindgen(js_name = addUndoPoint)]
+    pub fn add_undo_point(&mut self, force: bool) -> Result<(), JsValue> {
+        self.replica.add_undo_point(force).map_err(into_jserr)
+    }
+
+    /// Undo local operations until the most recent undo point, returning false if there are no
+    /// local operations to undo.
+    pub fn undo(&mut self) -> Result<bool, JsValue> {
+        self.replica.undo().map_err(into_jserr)
+    }
+
+    /// Get the number of local, un-synchronized operations, not including undo points.
+    #[wasm_bindgen(js_name = numLocalOperations)]
+    pub fn num_local_operations(&mut self) -> Result<usize, JsValue> {
+        self.replica.num_local_operations().map_err(into_jserr)
+    }
+
+    /// Return a promise that resolves when all changes made so far have been written to
+    /// IndexedDB.  For an in-memory replica, it resolves immediately.
+    pub fn flush(&self) -> Promise {
+        let flusher = self.flusher.clone();
+        wasm_bindgen_futures::future_to_promise(async move {
+            if let Some(flusher) = flusher {
+                flusher.flush().await.map_err(into_jserr)?;
+            }
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+}
@@ -0,0 +1,37 @@
+use taskchampion::Status as TCStatus;
+use wasm_bindgen::prelude::*;
+
+/// The status of a task.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Pending,
+    Completed,
+    Deleted,
+    /// A status in the task database that was not recognized.  Tasks with this status should be
+    /// ignored, and it cannot be given to `Task.setStatus`.
+    Unknown,
+}
+
+impl From<TCStatus> for Status {
+    fn from(status: TCStatus) -> Status {
+        match status {
+            TCStatus::Pending => Status::Pending,
+            TCStatus::Completed => Status::Completed,
+            TCStatus::Deleted => Status::Deleted,
+            TCStatus::Unknown(_) => Status::Unknown,
+        }
+    }
+}
+
+impl Status {
+    /// Convert this status into the Rust type, failing for Unknown.
+    pub(crate) fn into_tc(self) -> Result<TCStatus, JsValue> {
+        match self {
+            Status::Pending => Ok(TCStatus::Pending),
+            Status::Completed => Ok(TCStatus::Completed),
+            Status::Deleted => Ok(TCStatus::Deleted),
+            Status::Unknown => Err(js_sys::Error::new("cannot use the Unknown status").into()),
+        }
+    }
+}
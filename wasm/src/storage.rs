@@ -0,0 +1,466 @@
+use crate::idb::Database;
+use std::collections::HashSet;
+use std::rc::Rc;
+use taskchampion::chrono::{DateTime, Utc};
+use taskchampion::storage::{InMemoryStorage, ReplicaOp, Storage, StorageTxn, TaskMap, VersionId};
+use taskchampion::{Error, Result, Uuid};
+
+/// IndexedDbStorage stores a replica's data in the browser's IndexedDB.
+///
+/// IndexedDB is asynchronous, while the [`Storage`] trait is not.  So the data is read into memory
+/// when the storage is opened, and transactions operate on that in-memory copy.  When a
+/// transaction is committed, its changes are written to IndexedDB in the background.  IndexedDB
+/// applies those writes in the order they were made, but a commit returns before they are
+/// complete; use [`flush`](IndexedDbStorage::flush) to wait for them.
+///
+/// A database should only be opened by one IndexedDbStorage at a time, as each has its own copy
+/// of the data and will not see changes made by the others.
+pub struct IndexedDbStorage {
+    memory: InMemoryStorage,
+    db: Rc<Database>,
+}
+
+impl IndexedDbStorage {
+    /// Open the IndexedDB database with the given name, creating it if necessary, and read its
+    /// contents.
+    pub async fn open(name: &str) -> Result<IndexedDbStorage> {
+        let db = Database::open(name).await.map_err(Error::Storage)?;
+        let snapshot = db.load().await.map_err(Error::Storage)?;
+        let mut memory = InMemoryStorage::new();
+        restore(&mut memory, snapshot)?;
+        Ok(IndexedDbStorage {
+            memory,
+            db: Rc::new(db),
+        })
+    }
+
+    /// Get a handle that can wait for this storage's writes to complete, even after the storage
+    /// itself has been given to a [`Replica`](taskchampion::Replica).
+    pub fn flusher(&self) -> Flusher {
+        Flusher(self.db.clone())
+    }
+}
+
+impl Storage for IndexedDbStorage {
+    fn txn<'a>(&'a mut self) -> Result<Box<dyn StorageTxn + 'a>> {
+        Ok(Box::new(Txn {
+            inner: self.memory.txn()?,
+            persist: &*self.db,
+            changes: Changes::default(),
+        }))
+    }
+}
+
+/// A handle for waiting until an [`IndexedDbStorage`] has finished writing.
+#[derive(Clone)]
+pub struct Flusher(Rc<Database>);
+
+impl Flusher {
+    /// Wait until all changes committed so far have been written to IndexedDB.
+    pub async fn flush(&self) -> Result<()> {
+        self.0.flush().await.map_err(Error::Storage)
+    }
+}
+
+/// Something to which the changes made by committed transactions can be written.
+pub(crate) trait Persist {
+    fn persist(&self, update: Update) -> anyhow::Result<()>;
+}
+
+/// The complete contents of a stored replica, as read when it is opened.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Snapshot {
+    pub(crate) tasks: Vec<(Uuid, TaskMap)>,
+    pub(crate) base_version: Option<VersionId>,
+    pub(crate) last_sync: Option<DateTime<Utc>>,
+    pub(crate) operations: Vec<ReplicaOp>,
+    pub(crate) working_set: Option<Vec<Option<Uuid>>>,
+}
+
+/// The data to write for a committed transaction.  Fields that the transaction did not change
+/// are None.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Update {
+    /// Tasks that were changed, with None for those that were deleted
+    pub(crate) tasks: Vec<(Uuid, Option<TaskMap>)>,
+    pub(crate) base_version: Option<VersionId>,
+    pub(crate) last_sync: Option<DateTime<Utc>>,
+    pub(crate) operations: OperationsUpdate,
+    pub(crate) working_set: Option<Vec<Option<Uuid>>>,
+}
+
+/// The change to the list of operations made by a transaction
+#[derive(Debug, PartialEq)]
+pub(crate) enum OperationsUpdate {
+    Unchanged,
+    /// These operations were added to the end of the list
+    Append(Vec<ReplicaOp>),
+    /// The list was replaced with these operations
+    Replace(Vec<ReplicaOp>),
+}
+
+impl Default for OperationsUpdate {
+    fn default() -> Self {
+        OperationsUpdate::Unchanged
+    }
+}
+
+/// What a transaction has changed so far
+struct Changes {
+    tasks: HashSet<Uuid>,
+    base_version: bool,
+    last_sync: bool,
+    /// The number of operations appended, or None if the list was replaced
+    operations: Option<usize>,
+    working_set: bool,
+}
+
+impl Default for Changes {
+    fn default() -> Self {
+        Changes {
+            tasks: HashSet::new(),
+            base_version: false,
+            last_sync: false,
+            operations: Some(0),
+            working_set: false,
+        }
+    }
+}
+
+impl Changes {
+    /// Read the changed data from the transaction that made the changes.
+    fn update(&self, txn: &mut dyn StorageTxn) -> Result<Update> {
+        let mut update = Update::default();
+        for uuid in &self.tasks {
+            update.tasks.push((*uuid, txn.get_task(*uuid)?));
+        }
+        if self.base_version {
+            update.base_version = Some(txn.base_version()?);
+        }
+        if self.last_sync {
+            update.last_sync = txn.last_sync()?;
+        }
+        update.operations = match self.operations {
+            Some(0) => OperationsUpdate::Unchanged,
+            Some(appended) => {
+                let mut operations = txn.operations()?;
+                OperationsUpdate::Append(operations.split_off(operations.len() - appended))
+            }
+            None => OperationsUpdate::Replace(txn.operations()?),
+        };
+        if self.working_set {
+            update.working_set = Some(txn.get_working_set()?);
+        }
+        Ok(update)
+    }
+}
+
+/// A transaction that keeps track of the changes made through it, and persists them when it is
+/// committed.
+struct Txn<'t, P: Persist> {
+    inner: Box<dyn StorageTxn + 't>,
+    persist: &'t P,
+    changes: Changes,
+}
+
+impl<'t, P: Persist> StorageTxn for Txn<'t, P> {
+    fn get_task(&mut self, uuid: Uuid) -> Result<Option<TaskMap>> {
+        self.inner.get_task(uuid)
+    }
+
+    fn create_task(&mut self, uuid: Uuid) -> Result<bool> {
+        self.changes.tasks.insert(uuid);
+        self.inner.create_task(uuid)
+    }
+
+    fn set_task(&mut self, uuid: Uuid, task: TaskMap) -> Result<()> {
+        self.changes.tasks.insert(uuid);
+        self.inner.set_task(uuid, task)
+    }
+
+    fn delete_task(&mut self, uuid: Uuid) -> Result<bool> {
+        self.changes.tasks.insert(uuid);
+        self.inner.delete_task(uuid)
+    }
+
+    fn all_tasks(&mut self) -> Result<Vec<(Uuid, TaskMap)>> {
+        self.inner.all_tasks()
+    }
+
+    fn all_task_uuids(&mut self) -> Result<Vec<Uuid>> {
+        self.inner.all_task_uuids()
+    }
+
+    fn base_version(&mut self) -> Result<VersionId> {
+        self.inner.base_version()
+    }
+
+    fn set_base_version(&mut self, version: VersionId) -> Result<()> {
+        self.changes.base_version = true;
+        self.inner.set_base_version(version)
+    }
+
+    fn last_sync(&mut self) -> Result<Option<DateTime<Utc>>> {
+        self.inner.last_sync()
+    }
+
+    fn set_last_sync(&mut self, time: DateTime<Utc>) -> Result<()> {
+        self.changes.last_sync = true;
+        self.inner.set_last_sync(time)
+    }
+
+    fn operations(&mut self) -> Result<Vec<ReplicaOp>> {
+        self.inner.operations()
+    }
+
+    fn num_operations(&mut self) -> Result<usize> {
+        self.inner.num_operations()
+    }
+
+    fn add_operation(&mut self, op: ReplicaOp) -> Result<()> {
+        self.changes.operations = self.changes.operations.map(|appended| appended + 1);
+        self.inner.add_operation(op)
+    }
+
+    fn set_operations(&mut self, ops: Vec<ReplicaOp>) -> Result<()> {
+        self.changes.operations = None;
+        self.inner.set_operations(ops)
+    }
+
+    fn get_working_set(&mut self) -> Result<Vec<Option<Uuid>>> {
+        self.inner.get_working_set()
+    }
+
+    fn add_to_working_set(&mut self, uuid: Uuid) -> Result<usize> {
+        self.changes.working_set = true;
+        self.inner.add_to_working_set(uuid)
+    }
+
+    fn set_working_set_item(&mut self, index: usize, uuid: Option<Uuid>) -> Result<()> {
+        self.changes.working_set = true;
+        self.inner.set_working_set_item(index, uuid)
+    }
+
+    fn clear_working_set(&mut self) -> Result<()> {
+        self.changes.working_set = true;
+        self.inner.clear_working_set()
+    }
+
+    fn commit(&mut self) -> Result<()> {
+        // write the changes before committing them in memory, so that the in-memory copy does
+        // not include changes that could not be written
+        let update = self.changes.update(self.inner.as_mut())?;
+        self.persist.persist(update).map_err(Error::Storage)?;
+        self.changes = Changes::default();
+        self.inner.commit()
+    }
+}
+
+/// Load a snapshot into an empty in-memory storage.
+fn restore(memory: &mut InMemoryStorage, snapshot: Snapshot) -> Result<()> {
+    let mut txn = memory.txn()?;
+    for (uuid, task) in snapshot.tasks {
+        txn.set_task(uuid, task)?;
+    }
+    if let Some(base_version) = snapshot.base_version {
+        txn.set_base_version(base_version)?;
+    }
+    if let Some(last_sync) = snapshot.last_sync {
+        txn.set_last_sync(last_sync)?;
+    }
+    txn.set_operations(snapshot.operations)?;
+    if let Some(working_set) = snapshot.working_set {
+        // element 0 is always None; add the remaining elements, including any gaps, in order
+        for (i, uuid) in working_set.into_iter().enumerate().skip(1) {
+            txn.add_to_working_set(uuid.unwrap_or_else(Uuid::nil))?;
+            if uuid.is_none() {
+                txn.set_working_set_item(i, None)?;
+            }
+        }
+    }
+    txn.commit()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use taskchampion::chrono::TimeZone;
+
+    /// A stand-in for IndexedDB, applying updates to a snapshot in the same way.
+    #[derive(Default)]
+    struct FakeDb {
+        snapshot: RefCell<Snapshot>,
+        updates: RefCell<usize>,
+    }
+
+    impl Persist for FakeDb {
+        fn persist(&self, update: Update) -> anyhow::Result<()> {
+            let mut snapshot = self.snapshot.borrow_mut();
+            for (uuid, task) in update.tasks {
+                snapshot.tasks.retain(|(u, _)| *u != uuid);
+                if let Some(task) = task {
+                    snapshot.tasks.push((uuid, task));
+                }
+            }
+            if update.base_version.is_some() {
+                snapshot.base_version = update.base_version;
+            }
+            if update.last_sync.is_some() {
+                snapshot.last_sync = update.last_sync;
+            }
+            match update.operations {
+                OperationsUpdate::Unchanged => {}
+                OperationsUpdate::Append(mut ops) => snapshot.operations.append(&mut ops),
+                OperationsUpdate::Replace(ops) => snapshot.operations = ops,
+            }
+            if update.working_set.is_some() {
+                snapshot.working_set = update.working_set;
+            }
+            *self.updates.borrow_mut() += 1;
+            Ok(())
+        }
+    }
+
+    /// A Persist implementation that always fails
+    struct BrokenDb;
+
+    impl Persist for BrokenDb {
+        fn persist(&self, _update: Update) -> anyhow::Result<()> {
+            anyhow::bail!("database is closed")
+        }
+    }
+
+    fn txn<'t, P: Persist>(memory: &'t mut InMemoryStorage, persist: &'t P) -> Txn<'t, P> {
+        Txn {
+            inner: memory.txn().unwrap(),
+            persist,
+            changes: Changes::default(),
+        }
+    }
+
+    fn taskmap(description: &str) -> TaskMap {
+        let mut task = TaskMap::new();
+        task.insert("description".into(), description.into());
+        task
+    }
+
+    #[test]
+    fn persisted_data_restores() -> Result<()> {
+        let db = FakeDb::default();
+        let mut memory = InMemoryStorage::new();
+        let (uuid1, uuid2, uuid3) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        {
+            let mut txn = txn(&mut memory, &db);
+            txn.create_task(uuid1)?;
+            txn.set_task(uuid1, taskmap("one"))?;
+            txn.set_task(uuid2, taskmap("two"))?;
+            txn.set_task(uuid3, taskmap("three"))?;
+            txn.add_operation(ReplicaOp::Create { uuid: uuid1 })?;
+            txn.add_to_working_set(uuid1)?;
+            txn.add_to_working_set(uuid2)?;
+            txn.add_to_working_set(uuid3)?;
+            txn.commit()?;
+        }
+
+        {
+            let mut txn = txn(&mut memory, &db);
+            txn.delete_task(uuid3)?;
+            txn.set_working_set_item(2, None)?;
+            txn.set_base_version(Uuid::new_v4())?;
+            txn.set_last_sync(Utc.ymd(2022, 3, 4).and_hms(5, 6, 7))?;
+            txn.add_operation(ReplicaOp::UndoPoint)?;
+            txn.add_operation(ReplicaOp::Create { uuid: uuid2 })?;
+            txn.commit()?;
+        }
+
+        let mut restored = InMemoryStorage::new();
+        restore(&mut restored, db.snapshot.into_inner())?;
+
+        let (mut txn, mut restored_txn) = (memory.txn()?, restored.txn()?);
+        let mut tasks = txn.all_tasks()?;
+        tasks.sort_by_key(|(uuid, _)| *uuid);
+        let mut restored_tasks = restored_txn.all_tasks()?;
+        restored_tasks.sort_by_key(|(uuid, _)| *uuid);
+        assert_eq!(restored_tasks, tasks);
+        assert_eq!(restored_txn.base_version()?, txn.base_version()?);
+        assert_eq!(restored_txn.last_sync()?, txn.last_sync()?);
+        assert_eq!(restored_txn.operations()?, txn.operations()?);
+        assert_eq!(restored_txn.get_working_set()?, txn.get_working_set()?);
+        assert_eq!(
+            restored_txn.get_working_set()?,
+            vec![None, Some(uuid1), None, Some(uuid3)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn only_changes_are_persisted() -> Result<()> {
+        let db = FakeDb::default();
+        let mut memory = InMemoryStorage::new();
+        let uuid = Uuid::new_v4();
+
+        {
+            let mut txn = txn(&mut memory, &db);
+            txn.set_task(uuid, taskmap("one"))?;
+            txn.add_operation(ReplicaOp::UndoPoint)?;
+            let update = txn.changes.update(txn.inner.as_mut())?;
+            assert_eq!(
+                update,
+                Update {
+                    tasks: vec![(uuid, Some(taskmap("one")))],
+                    operations: OperationsUpdate::Append(vec![ReplicaOp::UndoPoint]),
+                    ..Update::default()
+                }
+            );
+        }
+
+        {
+            let mut txn = txn(&mut memory, &db);
+            txn.add_operation(ReplicaOp::UndoPoint)?;
+            txn.set_operations(vec![])?;
+            txn.add_operation(ReplicaOp::Create { uuid })?;
+            let update = txn.changes.update(txn.inner.as_mut())?;
+            assert_eq!(
+                update,
+                Update {
+                    operations: OperationsUpdate::Replace(vec![ReplicaOp::Create { uuid }]),
+                    ..Update::default()
+                }
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn uncommitted_changes_are_not_persisted() -> Result<()> {
+        let db = FakeDb::default();
+        let mut memory = InMemoryStorage::new();
+
+        {
+            let mut txn = txn(&mut memory, &db);
+            txn.set_task(Uuid::new_v4(), taskmap("one"))?;
+            // dropped without committing
+        }
+
+        assert_eq!(*db.updates.borrow(), 0);
+        assert_eq!(*db.snapshot.borrow(), Snapshot::default());
+        Ok(())
+    }
+
+    #[test]
+    fn failed_persist_is_not_committed() -> Result<()> {
+        let mut memory = InMemoryStorage::new();
+
+        {
+            let mut txn = txn(&mut memory, &BrokenDb);
+            txn.set_task(Uuid::new_v4(), taskmap("one"))?;
+            assert!(txn.commit().is_err());
+        }
+
+        assert_eq!(memory.txn()?.all_tasks()?, vec![]);
+        Ok(())
+    }
+}
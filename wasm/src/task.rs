@@ -0,0 +1,198 @@
+use crate::util::{from_date, into_jserr, to_date, uuid_from_str};
+use crate::{Replica, Status};
+use js_sys::Date;
+use std::convert::TryFrom;
+use taskchampion::{Tag, Task as TCTask, TaskMut as TCTaskMut};
+use wasm_bindgen::prelude::*;
+
+/// Parse a tag, failing if it is invalid.
+fn tag_from_str(tag: &str) -> Result<Tag, JsValue> {
+    Tag::try_from(tag).map_err(|e| js_sys::Error::new(&e.to_string()).into())
+}
+
+/// A task.  The properties are a snapshot of the task when it was read from the replica, updated
+/// by modifications made through this object.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Task(pub(crate) TCTask);
+
+impl Task {
+    /// Apply a modification to the task in the given replica, keeping the updated task.
+    fn modify<F>(&mut self, replica: &mut Replica, f: F) -> Result<(), JsValue>
+    where
+        F: FnOnce(&mut TCTaskMut) -> taskchampion::Result<()>,
+    {
+        let mut task = self.0.clone().into_mut(&mut replica.replica);
+        let res = f(&mut task);
+        self.0 = task.into_immut();
+        res.map_err(into_jserr)
+    }
+}
+
+#[wasm_bindgen]
+impl Task {
+    #[wasm_bindgen(getter)]
+    pub fn uuid(&self) -> String {
+        self.0.get_uuid().to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn status(&self) -> Status {
+        self.0.get_status().into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn description(&self) -> String {
+        self.0.get_description().to_owned()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn priority(&self) -> String {
+        self.0.get_priority().to_owned()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn project(&self) -> Option<String> {
+        self.0.get_project().map(str::to_owned)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn entry(&self) -> Option<Date> {
+        to_date(self.0.get_entry())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn wait(&self) -> Option<Date> {
+        to_date(self.0.get_wait())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn due(&self) -> Option<Date> {
+        to_date(self.0.get_due())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn modified(&self) -> Option<Date> {
+        to_date(self.0.get_modified())
+    }
+
+    /// The time the task was started, if it is active.
+    #[wasm_bindgen(getter = startTime)]
+    pub fn start_time(&self) -> Option<Date> {
+        to_date(self.0.get_start())
+    }
+
+    /// The time the task was completed or deleted.
+    #[wasm_bindgen(getter = endTime)]
+    pub fn end_time(&self) -> Option<Date> {
+        to_date(self.0.get_end())
+    }
+
+    #[wasm_bindgen(js_name = isWaiting)]
+    pub fn is_waiting(&self) -> bool {
+        self.0.is_waiting()
+    }
+
+    #[wasm_bindgen(js_name = isActive)]
+    pub fn is_active(&self) -> bool {
+        self.0.is_active()
+    }
+
+    /// Check whether the task has the given tag, which may be a user tag or a synthetic tag
+    /// such as `PENDING`.
+    #[wasm_bindgen(js_name = hasTag)]
+    pub fn has_tag(&self, tag: &str) -> Result<bool, JsValue> {
+        Ok(self.0.has_tag(&tag_from_str(tag)?))
+    }
+
+    /// The task's tags, including synthetic tags.
+    #[wasm_bindgen(getter)]
+    pub fn tags(&self) -> Vec<String> {
+        self.0.get_tags().map(|t| t.to_string()).collect()
+    }
+
+    /// The UUIDs of the tasks on which this task depends.
+    #[wasm_bindgen(getter)]
+    pub fn dependencies(&self) -> Vec<String> {
+        self.0.get_dependencies().map(|u| u.to_string()).collect()
+    }
+
+    #[wasm_bindgen(js_name = setDescription)]
+    pub fn set_description(
+        &mut self,
+        replica: &mut Replica,
+        description: String,
+    ) -> Result<(), JsValue> {
+        self.modify(replica, |t| t.set_description(description))
+    }
+
+    #[wasm_bindgen(js_name = setStatus)]
+    pub fn set_status(&mut self, replica: &mut Replica, status: Status) -> Result<(), JsValue> {
+        let status = status.into_tc()?;
+        self.modify(replica, |t| t.set_status(status))
+    }
+
+    #[wasm_bindgen(js_name = setPriority)]
+    pub fn set_priority(&mut self, replica: &mut Replica, priority: String) -> Result<(), JsValue> {
+        self.modify(replica, |t| t.set_priority(priority))
+    }
+
+    #[wasm_bindgen(js_name = setProject)]
+    pub fn set_project(
+        &mut self,
+        replica: &mut Replica,
+        project: Option<String>,
+    ) -> Result<(), JsValue> {
+        self.modify(replica, |t| t.set_project(project))
+    }
+
+    #[wasm_bindgen(js_name = setWait)]
+    pub fn set_wait(&mut self, replica: &mut Replica, wait: Option<Date>) -> Result<(), JsValue> {
+        self.modify(replica, |t| t.set_wait(from_date(wait)))
+    }
+
+    #[wasm_bindgen(js_name = setDue)]
+    pub fn set_due(&mut self, replica: &mut Replica, due: Option<Date>) -> Result<(), JsValue> {
+        self.modify(replica, |t| t.set_due(from_date(due)))
+    }
+
+    pub fn start(&mut self, replica: &mut Replica) -> Result<(), JsValue> {
+        self.modify(replica, |t| t.start())
+    }
+
+    pub fn stop(&mut self, replica: &mut Replica) -> Result<(), JsValue> {
+        self.modify(replica, |t| t.stop())
+    }
+
+    pub fn done(&mut self, replica: &mut Replica) -> Result<(), JsValue> {
+        self.modify(replica, |t| t.done())
+    }
+
+    pub fn delete(&mut self, replica: &mut Replica) -> Result<(), JsValue> {
+        self.modify(replica, |t| t.delete())
+    }
+
+    #[wasm_bindgen(js_name = addTag)]
+    pub fn add_tag(&mut self, replica: &mut Replica, tag: &str) -> Result<(), JsValue> {
+        let tag = tag_from_str(tag)?;
+        self.modify(replica, |t| t.add_tag(&tag))
+    }
+
+    #[wasm_bindgen(js_name = removeTag)]
+    pub fn remove_tag(&mut self, replica: &mut Replica, tag: &str) -> Result<(), JsValue> {
+        let tag = tag_from_str(tag)?;
+        self.modify(replica, |t| t.remove_tag(&tag))
+    }
+
+    #[wasm_bindgen(js_name = addDependency)]
+    pub fn add_dependency(&mut self, replica: &mut Replica, dep: &str) -> Result<(), JsValue> {
+        let dep = uuid_from_str(dep)?;
+        self.modify(replica, |t| t.add_dependency(dep))
+    }
+
+    #[wasm_bindgen(js_name = removeDependency)]
+    pub fn remove_dependency(&mut self, replica: &mut Replica, dep: &str) -> Result<(), JsValue> {
+        let dep = uuid_from_str(dep)?;
+        self.modify(replica, |t| t.remove_dependency(dep))
+    }
+}
@@ -0,0 +1,25 @@
+use js_sys::Date;
+use taskchampion::chrono::{DateTime, TimeZone, Utc};
+use taskchampion::Uuid;
+use wasm_bindgen::JsValue;
+
+/// Convert an error from TaskChampion into a JavaScript `Error`, including the full chain of
+/// context in the message.
+pub(crate) fn into_jserr(err: taskchampion::Error) -> JsValue {
+    js_sys::Error::new(&format!("{:#}", err)).into()
+}
+
+/// Parse a UUID given as a string.
+pub(crate) fn uuid_from_str(uuid: &str) -> Result<Uuid, JsValue> {
+    Uuid::parse_str(uuid).map_err(|e| js_sys::Error::new(&format!("invalid UUID: {}", e)).into())
+}
+
+/// Convert a timestamp into a JavaScript `Date`.
+pub(crate) fn to_date(ts: Option<DateTime<Utc>>) -> Option<Date> {
+    ts.map(|ts| Date::new(&JsValue::from_f64(ts.timestamp_millis() as f64)))
+}
+
+/// Convert a JavaScript `Date` into a timestamp, truncated to whole seconds.
+pub(crate) fn from_date(date: Option<Date>) -> Option<DateTime<Utc>> {
+    date.map(|date| Utc.timestamp((date.get_time() / 1000.0).floor() as i64, 0))
+}